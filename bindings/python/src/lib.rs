@@ -0,0 +1,345 @@
+//! Python bindings for feroxbuster's scanning engine
+//!
+//! ```python
+//! import feroxbuster
+//!
+//! config = feroxbuster.Config("http://127.1", wordlist="/wordlists/common.txt", extensions=["php"])
+//! scan = feroxbuster.Scan(config)
+//!
+//! for result in scan:
+//!     print(result.status, result.url)
+//! ```
+use std::{
+    collections::HashMap,
+    sync::{
+        mpsc::{channel, Receiver, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use engine::{Configuration, ScanResult, Scanner};
+use futures::StreamExt;
+use pyo3::{exceptions::PyRuntimeError, exceptions::PyValueError, prelude::*};
+use tokio::runtime::Runtime;
+
+/// How long `Scan.__next__` waits on a result before checking for signals, i.e. ctrl+c
+const SIGNAL_CHECK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Convert any error into a python RuntimeError
+fn runtime_error<E: std::fmt::Display>(error: E) -> PyErr {
+    PyRuntimeError::new_err(error.to_string())
+}
+
+/// Configuration of a scan; every argument other than the target url is optional
+#[pyclass(module = "feroxbuster")]
+#[derive(Clone)]
+struct Config {
+    /// the validated configuration
+    inner: Configuration,
+}
+
+#[pymethods]
+impl Config {
+    #[new]
+    #[allow(clippy::too_many_arguments)]
+    #[args(
+        "*",
+        wordlist = "None",
+        threads = "None",
+        timeout = "None",
+        depth = "None",
+        status_codes = "None",
+        filter_status = "None",
+        filter_size = "None",
+        extensions = "None",
+        headers = "None",
+        proxy = "None",
+        user_agent = "None",
+        insecure = "false",
+        redirects = "false",
+        no_recursion = "false",
+        rate_limit = "None"
+    )]
+    fn new(
+        url: &str,
+        wordlist: Option<&str>,
+        threads: Option<usize>,
+        timeout: Option<u64>,
+        depth: Option<usize>,
+        status_codes: Option<Vec<u16>>,
+        filter_status: Option<Vec<u16>>,
+        filter_size: Option<Vec<u64>>,
+        extensions: Option<Vec<String>>,
+        headers: Option<HashMap<String, String>>,
+        proxy: Option<&str>,
+        user_agent: Option<&str>,
+        insecure: bool,
+        redirects: bool,
+        no_recursion: bool,
+        rate_limit: Option<usize>,
+    ) -> PyResult<Self> {
+        let mut builder = Configuration::builder()
+            .target_url(url)
+            .insecure(insecure)
+            .redirects(redirects)
+            .no_recursion(no_recursion);
+
+        if let Some(wordlist) = wordlist {
+            builder = builder.wordlist(wordlist);
+        }
+
+        if let Some(threads) = threads {
+            builder = builder.threads(threads);
+        }
+
+        if let Some(timeout) = timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(depth) = depth {
+            builder = builder.depth(depth);
+        }
+
+        if let Some(codes) = status_codes {
+            builder = builder.status_codes(&codes);
+        }
+
+        if let Some(codes) = filter_status {
+            builder = builder.filter_status(&codes);
+        }
+
+        if let Some(sizes) = filter_size {
+            builder = builder.filter_size(&sizes);
+        }
+
+        if let Some(extensions) = extensions {
+            let extensions: Vec<&str> = extensions.iter().map(String::as_str).collect();
+            builder = builder.extensions(&extensions);
+        }
+
+        if let Some(headers) = headers {
+            builder = builder.headers(headers);
+        }
+
+        if let Some(proxy) = proxy {
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(user_agent) = user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+
+        if let Some(limit) = rate_limit {
+            builder = builder.rate_limit(limit);
+        }
+
+        let inner = builder
+            .build()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+
+        Ok(Self { inner })
+    }
+
+    /// the target url
+    #[getter]
+    fn url(&self) -> &str {
+        &self.inner.target_url
+    }
+
+    /// path to the wordlist in use
+    #[getter]
+    fn wordlist(&self) -> &str {
+        &self.inner.wordlist
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "Config(url={:?}, wordlist={:?})",
+            self.inner.target_url, self.inner.wordlist
+        )
+    }
+}
+
+/// A single result of a scan; `kind` is either "response" or "finding"
+#[pyclass(module = "feroxbuster", name = "ScanResult")]
+struct PyScanResult {
+    /// "response" or "finding"
+    #[pyo3(get)]
+    kind: String,
+
+    /// url of the response (or the response in which the finding was discovered)
+    #[pyo3(get)]
+    url: String,
+
+    /// status code; None for findings
+    #[pyo3(get)]
+    status: Option<u16>,
+
+    /// content length; None for findings
+    #[pyo3(get)]
+    content_length: Option<u64>,
+
+    /// number of lines in the body; None for findings
+    #[pyo3(get)]
+    line_count: Option<usize>,
+
+    /// number of words in the body; None for findings
+    #[pyo3(get)]
+    word_count: Option<usize>,
+
+    /// the kind of finding, i.e. "form"; None for responses
+    #[pyo3(get)]
+    category: Option<String>,
+
+    /// short description of the finding; None for responses
+    #[pyo3(get)]
+    summary: Option<String>,
+
+    /// the full result as JSON, the same as what --json writes
+    #[pyo3(get)]
+    json: String,
+}
+
+impl From<ScanResult> for PyScanResult {
+    fn from(result: ScanResult) -> Self {
+        match result {
+            ScanResult::Response(response) => Self {
+                kind: "response".to_string(),
+                url: response.url().to_string(),
+                status: Some(response.status().as_u16()),
+                content_length: Some(response.content_length()),
+                line_count: Some(response.line_count()),
+                word_count: Some(response.word_count()),
+                category: None,
+                summary: None,
+                json: serde_json::to_string(&response).unwrap_or_default(),
+            },
+            ScanResult::Finding(finding) => Self {
+                kind: "finding".to_string(),
+                url: finding.url().to_string(),
+                status: None,
+                content_length: None,
+                line_count: None,
+                word_count: None,
+                category: Some(finding.category().to_string()),
+                summary: Some(finding.summary().to_string()),
+                json: serde_json::to_string(&finding).unwrap_or_default(),
+            },
+        }
+    }
+}
+
+#[pymethods]
+impl PyScanResult {
+    fn __repr__(&self) -> String {
+        match self.status {
+            Some(status) => format!("ScanResult(response, {}, {})", status, self.url),
+            None => format!(
+                "ScanResult(finding, {}, {})",
+                self.category.as_deref().unwrap_or_default(),
+                self.url
+            ),
+        }
+    }
+}
+
+/// A running scan; iterate over it to receive results as they're found
+///
+/// iteration blocks until the next result arrives and ends when the scan is finished or cancelled;
+/// a KeyboardInterrupt raised while waiting cancels the scan
+#[pyclass(module = "feroxbuster")]
+struct Scan {
+    /// runtime on which the scan runs; lives as long as the scan does
+    runtime: Arc<Runtime>,
+
+    /// engine running the scan, kept around for cancellation
+    scanner: Scanner,
+
+    /// results forwarded from the scan's stream
+    results: Arc<Mutex<Receiver<ScanResult>>>,
+}
+
+#[pymethods]
+impl Scan {
+    /// Start scanning the config's target in the background
+    #[new]
+    fn new(py: Python, config: Config) -> PyResult<Self> {
+        let runtime = Arc::new(Runtime::new().map_err(runtime_error)?);
+        let scanner = Scanner::new(config.inner);
+
+        let (tx, rx) = channel();
+
+        let engine = scanner.clone();
+        let scan_runtime = runtime.clone();
+
+        // connectivity checks happen before the scan starts, no need to hold the GIL for them
+        py.allow_threads(move || {
+            let mut stream = scan_runtime.block_on(engine.scan())?;
+
+            scan_runtime.spawn(async move {
+                while let Some(result) = stream.next().await {
+                    if tx.send(result).is_err() {
+                        // nothing's listening anymore
+                        break;
+                    }
+                }
+            });
+
+            Ok::<_, anyhow::Error>(())
+        })
+        .map_err(runtime_error)?;
+
+        Ok(Self {
+            runtime,
+            scanner,
+            results: Arc::new(Mutex::new(rx)),
+        })
+    }
+
+    /// Stop the scan; results that were already found can still be iterated over
+    fn cancel(&self, py: Python) -> PyResult<()> {
+        let runtime = self.runtime.clone();
+        let scanner = self.scanner.clone();
+
+        py.allow_threads(move || runtime.block_on(scanner.cancel()))
+            .map_err(runtime_error)
+    }
+
+    fn __iter__(slf: PyRef<Self>) -> PyRef<Self> {
+        slf
+    }
+
+    fn __next__(&self, py: Python) -> PyResult<Option<PyScanResult>> {
+        loop {
+            let results = self.results.clone();
+
+            // wait in short intervals with the GIL released, so signal handlers get to run
+            let received = py.allow_threads(move || match results.lock() {
+                Ok(receiver) => receiver.recv_timeout(SIGNAL_CHECK_INTERVAL),
+                Err(_) => Err(RecvTimeoutError::Disconnected),
+            });
+
+            match received {
+                Ok(result) => return Ok(Some(PyScanResult::from(result))),
+                Err(RecvTimeoutError::Disconnected) => return Ok(None),
+                Err(RecvTimeoutError::Timeout) => {
+                    if let Err(error) = py.check_signals() {
+                        self.cancel(py)?;
+                        return Err(error);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Python bindings for feroxbuster's scanning engine
+#[pymodule]
+fn feroxbuster(_py: Python, module: &PyModule) -> PyResult<()> {
+    module.add_class::<Config>()?;
+    module.add_class::<Scan>()?;
+    module.add_class::<PyScanResult>()?;
+    module.add("__version__", env!("CARGO_PKG_VERSION"))?;
+    Ok(())
+}
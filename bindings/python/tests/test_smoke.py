@@ -0,0 +1,53 @@
+"""Smoke tests for the python bindings; run with `python -m unittest discover -s tests`"""
+import functools
+import http.server
+import tempfile
+import threading
+import unittest
+from pathlib import Path
+
+import feroxbuster
+
+
+class SmokeTest(unittest.TestCase):
+    def setUp(self):
+        self.webroot = tempfile.TemporaryDirectory()
+        Path(self.webroot.name, "admin").write_text("hello from admin\n")
+
+        handler = functools.partial(
+            http.server.SimpleHTTPRequestHandler, directory=self.webroot.name
+        )
+        self.server = http.server.ThreadingHTTPServer(("127.0.0.1", 0), handler)
+        threading.Thread(target=self.server.serve_forever, daemon=True).start()
+
+        self.wordlist = Path(self.webroot.name, "words.txt")
+        self.wordlist.write_text("admin\nnot-here\n")
+
+    def tearDown(self):
+        self.server.shutdown()
+        self.server.server_close()
+        self.webroot.cleanup()
+
+    def test_module_has_a_version(self):
+        self.assertTrue(feroxbuster.__version__)
+
+    def test_scan_yields_found_responses(self):
+        url = "http://127.0.0.1:{}".format(self.server.server_address[1])
+        config = feroxbuster.Config(
+            url, wordlist=str(self.wordlist), threads=2, no_recursion=True
+        )
+
+        self.assertEqual(config.url, url)
+        self.assertEqual(config.wordlist, str(self.wordlist))
+
+        found = [
+            result.url
+            for result in feroxbuster.Scan(config)
+            if result.kind == "response" and result.status == 200
+        ]
+
+        self.assertIn(url + "/admin", found)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,218 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+use super::ResponseAnalyzer;
+use crate::{finding::FeroxFinding, response::FeroxResponse};
+
+/// maximum number of characters of a comment shown in the human readable summary
+const MAX_SUMMARY_LENGTH: usize = 120;
+
+lazy_static! {
+    /// Regex used to find html comments, i.e. `<!-- ... -->`
+    static ref HTML_COMMENT_REGEX: Regex =
+        Regex::new(r#"(?s)<!--(?P<comment>.*?)-->"#).expect("Could not compile regex");
+
+    /// Regex used to find inline script blocks within html, so their comments can be inspected
+    static ref SCRIPT_REGEX: Regex =
+        Regex::new(r#"(?is)<script\b[^>]*>(?P<script>.*?)</script\s*>"#)
+            .expect("Could not compile regex");
+
+    /// Regex used to find javascript comments, both `/* block */` and `// line` style
+    ///
+    /// line comments must not be preceded by a colon, quote, or slash, which keeps things like
+    /// `http://` and regex literals from being treated as the start of a comment
+    static ref JS_COMMENT_REGEX: Regex = Regex::new(
+        r#"(?s:/\*(?P<block>.*?)\*/)|(?m:(?:^|[^:"'\\/])//(?P<line>[^\r\n]*))"#
+    )
+    .expect("Could not compile regex");
+
+    /// (label, regex) pairs that determine whether or not a comment is worth reporting
+    static ref INTERESTING_PATTERNS: Vec<(&'static str, Regex)> = vec![
+        ("todo", Regex::new(r#"(?i)\b(?:todo|fixme|hack|xxx|bug)\b"#).expect("Could not compile regex")),
+        (
+            "credential",
+            Regex::new(r#"(?i)\b(?:pass(?:wd|word)?|pwd|secret|credentials?|api[_-]?key|token|auth)\b"#)
+                .expect("Could not compile regex"),
+        ),
+        (
+            "hostname",
+            Regex::new(r#"(?i)\b(?:localhost|[a-z0-9-]+(?:\.[a-z0-9-]+)*\.(?:internal|local|corp|lan|intranet|intra|priv))\b"#)
+                .expect("Could not compile regex"),
+        ),
+        (
+            "ip",
+            Regex::new(r#"\b(?:(?:25[0-5]|2[0-4]\d|1?\d?\d)\.){3}(?:25[0-5]|2[0-4]\d|1?\d?\d)\b"#)
+                .expect("Could not compile regex"),
+        ),
+    ];
+}
+
+/// Analyzer that reports interesting html/javascript comments; enabled with --extract-comments
+#[derive(Debug, Default)]
+pub(super) struct CommentAnalyzer;
+
+/// implementation of ResponseAnalyzer for CommentAnalyzer
+impl ResponseAnalyzer for CommentAnalyzer {
+    /// name of the analyzer, used for logging
+    fn name(&self) -> &'static str {
+        "comments"
+    }
+
+    /// pull all comments out of the response body and report the interesting ones
+    fn analyze(&self, response: &FeroxResponse) -> Vec<FeroxFinding> {
+        find_comments(response)
+    }
+}
+
+/// Pull all javascript comments out of the given source code
+fn js_comments(source: &str) -> Vec<String> {
+    JS_COMMENT_REGEX
+        .captures_iter(source)
+        .filter_map(|c| c.name("block").or_else(|| c.name("line")))
+        .map(|m| m.as_str().to_string())
+        .collect()
+}
+
+/// Return the labels of all interesting patterns that match the given comment
+fn interesting_labels(comment: &str) -> Vec<&'static str> {
+    INTERESTING_PATTERNS
+        .iter()
+        .filter(|(_, regex)| regex.is_match(comment))
+        .map(|(label, _)| *label)
+        .collect()
+}
+
+/// Parse all comments out of the given response and return a `FeroxFinding` for each one that
+/// matches at least one of the interesting patterns (todo, credential, hostname, ip)
+///
+/// javascript responses are searched for `//` and `/* */` comments; everything else is searched
+/// for `<!-- -->` comments as well as javascript comments within inline `<script>` blocks
+pub(super) fn find_comments(response: &FeroxResponse) -> Vec<FeroxFinding> {
+    log::trace!("enter: find_comments({})", response);
+
+    let body = response.text();
+
+    let comments = if response.is_javascript() {
+        js_comments(body)
+    } else {
+        let mut comments: Vec<String> = HTML_COMMENT_REGEX
+            .captures_iter(body)
+            .filter_map(|c| c.name("comment"))
+            .map(|m| m.as_str().to_string())
+            .collect();
+
+        for script in SCRIPT_REGEX.captures_iter(body) {
+            if let Some(source) = script.name("script") {
+                comments.extend(js_comments(source.as_str()));
+            }
+        }
+
+        comments
+    };
+
+    let mut findings = Vec::new();
+
+    for comment in comments {
+        // collapse whitespace so multi-line comments display on a single line
+        let comment = comment.split_whitespace().collect::<Vec<_>>().join(" ");
+
+        if comment.is_empty() {
+            continue;
+        }
+
+        let labels = interesting_labels(&comment);
+
+        if labels.is_empty() {
+            continue;
+        }
+
+        let shortened = if comment.chars().count() > MAX_SUMMARY_LENGTH {
+            format!(
+                "{}...",
+                comment.chars().take(MAX_SUMMARY_LENGTH).collect::<String>()
+            )
+        } else {
+            comment.clone()
+        };
+
+        let summary = format!("[{}] {}", labels.join(", "), shortened);
+
+        let details = json!({
+            "comment": comment,
+            "matches": labels,
+        });
+
+        findings.push(FeroxFinding::new(
+            "comment",
+            response.url().as_str(),
+            &summary,
+            details,
+        ));
+    }
+
+    log::trace!("exit: find_comments -> {:?}", findings);
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// html comments and inline script comments are found, boring comments are ignored
+    fn find_comments_reports_interesting_html_comments() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/index.html");
+        response.set_text(
+            r#"<html><!-- just a layout comment -->
+            <!-- TODO: remove the admin password before
+                 going live -->
+            <script>
+                var base = "http://localhost/api"; // talks to db01.corp
+            </script>
+            <!-- backend lives at 10.1.2.3 --></html>"#,
+        );
+
+        let findings = find_comments(&response);
+
+        assert_eq!(findings.len(), 3);
+        assert_eq!(findings[0].category(), "comment");
+        assert_eq!(
+            findings[0].details()["comment"],
+            "TODO: remove the admin password before going live"
+        );
+        assert_eq!(
+            findings[0].details()["matches"],
+            json!(["todo", "credential"])
+        );
+        assert_eq!(findings[1].details()["matches"], json!(["ip"]));
+        assert_eq!(findings[2].details()["comment"], "talks to db01.corp");
+        assert_eq!(findings[2].details()["matches"], json!(["hostname"]));
+    }
+
+    #[test]
+    /// javascript files are searched for line and block comments, urls aren't mistaken for them
+    fn find_comments_reports_interesting_js_comments() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/app.js");
+        response.set_text(
+            r#"const url = "https://example.com/path";
+            /* FIXME: hardcoded api_key below */
+            // nothing to see here
+            fetch(url); // dev server: localhost:8080"#,
+        );
+
+        let findings = find_comments(&response);
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(
+            findings[0].details()["matches"],
+            json!(["todo", "credential"])
+        );
+        assert_eq!(
+            findings[1].details()["comment"],
+            "dev server: localhost:8080"
+        );
+    }
+}
@@ -0,0 +1,161 @@
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+use super::ResponseAnalyzer;
+use crate::{finding::FeroxFinding, response::FeroxResponse};
+
+lazy_static! {
+    /// Regex used to find all html forms in a response body, captures the form's attributes and
+    /// inner html separately
+    static ref FORM_REGEX: Regex =
+        Regex::new(r#"(?is)<form\b(?P<attrs>[^>]*)>(?P<inner>.*?)</form\s*>"#)
+            .expect("Could not compile regex");
+
+    /// Regex used to pull the value of the action attribute from a form tag
+    static ref ACTION_REGEX: Regex =
+        Regex::new(r#"(?is)\baction\s*=\s*(?:"(?P<dq>[^"]*)"|'(?P<sq>[^']*)'|(?P<bare>[^\s>]+))"#)
+            .expect("Could not compile regex");
+
+    /// Regex used to pull the value of the method attribute from a form tag
+    static ref METHOD_REGEX: Regex =
+        Regex::new(r#"(?is)\bmethod\s*=\s*["']?(?P<method>[a-zA-Z]+)"#)
+            .expect("Could not compile regex");
+
+    /// Regex used to pull the names of all named inputs from a form's inner html
+    static ref INPUT_REGEX: Regex =
+        Regex::new(r#"(?is)<(?:input|select|textarea|button)\b[^>]*?\bname\s*=\s*(?:"(?P<dq>[^"]*)"|'(?P<sq>[^']*)'|(?P<bare>[^\s>]+))"#)
+            .expect("Could not compile regex");
+}
+
+/// given a set of captures from one of the quoted attribute regexes, return whichever of the
+/// double quoted, single quoted, or unquoted value matched
+fn quoted_value(captures: &regex::Captures) -> Option<String> {
+    captures
+        .name("dq")
+        .or_else(|| captures.name("sq"))
+        .or_else(|| captures.name("bare"))
+        .map(|m| m.as_str().trim().to_string())
+}
+
+/// Analyzer that reports html forms; enabled with --extract-forms
+#[derive(Debug, Default)]
+pub(super) struct FormAnalyzer;
+
+/// implementation of ResponseAnalyzer for FormAnalyzer
+impl ResponseAnalyzer for FormAnalyzer {
+    /// name of the analyzer, used for logging
+    fn name(&self) -> &'static str {
+        "forms"
+    }
+
+    /// parse all forms out of the response body
+    fn analyze(&self, response: &FeroxResponse) -> Vec<FeroxFinding> {
+        find_forms(response)
+    }
+}
+
+/// Parse all html forms out of the given response and return a `FeroxFinding` for each
+///
+/// Each finding contains the (absolute) action url, the method, and the names of all inputs
+/// found within the form
+pub(super) fn find_forms(response: &FeroxResponse) -> Vec<FeroxFinding> {
+    log::trace!("enter: find_forms({})", response);
+
+    let mut findings = Vec::new();
+
+    for form in FORM_REGEX.captures_iter(response.text()) {
+        let attrs = form.name("attrs").map_or("", |m| m.as_str());
+        let inner = form.name("inner").map_or("", |m| m.as_str());
+
+        // a missing/empty action means the form submits to the page it was found on
+        let action = match ACTION_REGEX.captures(attrs).and_then(|c| quoted_value(&c)) {
+            Some(action) if !action.is_empty() => match response.url().join(&action) {
+                Ok(joined) => joined.to_string(),
+                Err(_) => action,
+            },
+            _ => response.url().to_string(),
+        };
+
+        let method = METHOD_REGEX
+            .captures(attrs)
+            .and_then(|c| c.name("method"))
+            .map_or_else(|| "GET".to_string(), |m| m.as_str().to_uppercase());
+
+        let mut inputs: Vec<String> = Vec::new();
+
+        for input in INPUT_REGEX.captures_iter(inner) {
+            if let Some(name) = quoted_value(&input) {
+                if !name.is_empty() && !inputs.contains(&name) {
+                    inputs.push(name);
+                }
+            }
+        }
+
+        let summary = format!("{} {} [{}]", method, action, inputs.join(", "));
+
+        let details = json!({
+            "action": action,
+            "method": method,
+            "inputs": inputs,
+        });
+
+        findings.push(FeroxFinding::new(
+            "form",
+            response.url().as_str(),
+            &summary,
+            details,
+        ));
+    }
+
+    log::trace!("exit: find_forms -> {:?}", findings);
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// parse a page with two forms and ensure action, method, and inputs are all correct
+    fn find_forms_parses_action_method_and_inputs() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/account/login");
+        response.set_text(
+            r#"<html><body>
+            <FORM action="/auth" method='post'>
+                <input type="text" name="username">
+                <input type=password name=password>
+                <input type="hidden" name='csrf' value="abc">
+                <input type="submit" value="go">
+            </FORM>
+            <form><select name="lang"></select><textarea name="q"></textarea></form>
+            </body></html>"#,
+        );
+
+        let findings = find_forms(&response);
+
+        assert_eq!(findings.len(), 2);
+
+        let login = findings[0].details();
+        assert_eq!(login["action"], "http://localhost/auth");
+        assert_eq!(login["method"], "POST");
+        assert_eq!(login["inputs"], json!(["username", "password", "csrf"]));
+        assert_eq!(findings[0].category(), "form");
+        assert_eq!(findings[0].url(), "http://localhost/account/login");
+
+        let search = findings[1].details();
+        assert_eq!(search["action"], "http://localhost/account/login");
+        assert_eq!(search["method"], "GET");
+        assert_eq!(search["inputs"], json!(["lang", "q"]));
+    }
+
+    #[test]
+    /// a body without any forms should produce no findings
+    fn find_forms_returns_empty_when_no_forms_present() {
+        let mut response = FeroxResponse::default();
+        response.set_text("<html><body><input name='orphan'></body></html>");
+
+        assert!(find_forms(&response).is_empty());
+    }
+}
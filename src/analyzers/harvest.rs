@@ -0,0 +1,201 @@
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use serde_json::json;
+
+use super::ResponseAnalyzer;
+use crate::{finding::FeroxFinding, response::FeroxResponse};
+
+/// "domains" that are actually file extensions, i.e. `logo@2x.png`
+const IGNORED_EMAIL_SUFFIXES: [&str; 8] = [
+    ".png", ".jpg", ".jpeg", ".gif", ".svg", ".webp", ".css", ".js",
+];
+
+/// usernames that show up in markup/config all the time and aren't worth reporting
+const IGNORED_USERNAMES: [&str; 8] = [
+    "admin",
+    "administrator",
+    "root",
+    "user",
+    "username",
+    "guest",
+    "anonymous",
+    "null",
+];
+
+lazy_static! {
+    /// Regex used to find email addresses
+    static ref EMAIL_REGEX: Regex =
+        Regex::new(r#"(?i)\b[a-z0-9][a-z0-9._%+-]*@[a-z0-9-]+(?:\.[a-z0-9-]+)*\.[a-z]{2,24}\b"#)
+            .expect("Could not compile regex");
+
+    /// Regex used to find probable usernames, i.e. `"username": "jdoe"`, `user=jdoe`, or
+    /// `<meta name="author" content="jdoe">`
+    static ref USERNAME_REGEX: Regex = Regex::new(
+        r#"(?i)(?:\b(?:user(?:_?name)?|login|uid|author)["']?\s*[:=]\s*["']?|<meta\s+name=["']author["']\s+content=["'])(?P<user>[a-z][a-z0-9._-]{2,31})\b"#
+    )
+    .expect("Could not compile regex");
+}
+
+/// Analyzer that collects email addresses and probable usernames; enabled with --harvest
+///
+/// nothing is reported per-response, a single deduplicated summary is reported once the scan
+/// has finished (see `summary`)
+#[derive(Debug, Default)]
+pub(super) struct HarvestAnalyzer {
+    /// every email address seen during the scan
+    emails: Mutex<BTreeSet<String>>,
+
+    /// every probable username seen during the scan
+    usernames: Mutex<BTreeSet<String>>,
+}
+
+/// implementation of ResponseAnalyzer for HarvestAnalyzer
+impl ResponseAnalyzer for HarvestAnalyzer {
+    /// name of the analyzer, used for logging
+    fn name(&self) -> &'static str {
+        "harvest"
+    }
+
+    /// record any emails/usernames in the response; findings are deferred until the scan ends
+    fn analyze(&self, response: &FeroxResponse) -> Vec<FeroxFinding> {
+        let (emails, usernames) = harvest(response.text());
+
+        if let Ok(mut seen) = self.emails.lock() {
+            seen.extend(emails);
+        }
+
+        if let Ok(mut seen) = self.usernames.lock() {
+            seen.extend(usernames);
+        }
+
+        Vec::new()
+    }
+
+    /// a single finding, attributed to the target, containing every email address and username
+    /// harvested over the course of the scan; nothing when nothing was found
+    fn summary(&self, target_url: &str) -> Vec<FeroxFinding> {
+        let emails: Vec<String> = self
+            .emails
+            .lock()
+            .map(|seen| seen.iter().cloned().collect())
+            .unwrap_or_default();
+
+        let usernames: Vec<String> = self
+            .usernames
+            .lock()
+            .map(|seen| seen.iter().cloned().collect())
+            .unwrap_or_default();
+
+        if emails.is_empty() && usernames.is_empty() {
+            return Vec::new();
+        }
+
+        let summary = format!(
+            "{} emails [{}]; {} usernames [{}]",
+            emails.len(),
+            emails.join(", "),
+            usernames.len(),
+            usernames.join(", ")
+        );
+
+        vec![FeroxFinding::new(
+            "harvest",
+            target_url,
+            &summary,
+            json!({
+                "emails": emails,
+                "usernames": usernames,
+            }),
+        )]
+    }
+}
+
+/// Pull all email addresses and probable usernames out of the given body
+///
+/// the local part of each email address is also considered a probable username
+pub(super) fn harvest(body: &str) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut emails = BTreeSet::new();
+    let mut usernames = BTreeSet::new();
+
+    for found in EMAIL_REGEX.find_iter(body) {
+        let email = found.as_str().to_lowercase();
+
+        if IGNORED_EMAIL_SUFFIXES.iter().any(|s| email.ends_with(s)) {
+            continue;
+        }
+
+        if let Some((local, _)) = email.split_once('@') {
+            usernames.insert(local.to_string());
+        }
+
+        emails.insert(email);
+    }
+
+    for captures in USERNAME_REGEX.captures_iter(body) {
+        if let Some(user) = captures.name("user") {
+            let user = user.as_str().to_lowercase();
+
+            if !IGNORED_USERNAMES.contains(&user.as_str()) {
+                usernames.insert(user);
+            }
+        }
+    }
+
+    (emails, usernames)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// emails and usernames are found, lowercased, and deduplicated; image names are skipped
+    fn harvest_finds_emails_and_usernames() {
+        let body = r#"<meta name="author" content="MSmith">
+            Contact Jane.Doe@Example.com or jane.doe@example.com, logo: brand@2x.png
+            {"username": "bwayne", "login": "admin"}
+            <a href="/profile?user=ckent">profile</a>"#;
+
+        let (emails, usernames) = harvest(body);
+
+        assert_eq!(
+            emails.into_iter().collect::<Vec<_>>(),
+            vec!["jane.doe@example.com"]
+        );
+        assert_eq!(
+            usernames.into_iter().collect::<Vec<_>>(),
+            vec!["bwayne", "ckent", "jane.doe", "msmith"]
+        );
+    }
+
+    #[test]
+    /// harvested values from every response are rolled up into a single summary finding
+    fn summary_reports_everything_seen() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/about");
+        response.set_text("email ops@harvest.test for access");
+
+        let analyzer = HarvestAnalyzer::default();
+        assert!(analyzer.analyze(&response).is_empty());
+
+        let findings = analyzer.summary("http://localhost");
+        let finding = &findings[0];
+        assert_eq!(finding.category(), "harvest");
+        assert!(finding.details()["emails"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("ops@harvest.test")));
+        assert!(finding.details()["usernames"]
+            .as_array()
+            .unwrap()
+            .contains(&json!("ops")));
+
+        // the next scan's analyzer starts out empty
+        assert!(HarvestAnalyzer::default()
+            .summary("http://localhost")
+            .is_empty());
+    }
+}
@@ -0,0 +1,271 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Mutex;
+
+use reqwest::header::{
+    HeaderMap, HeaderName, CONTENT_SECURITY_POLICY, CONTENT_TYPE, STRICT_TRANSPORT_SECURITY,
+    X_CONTENT_TYPE_OPTIONS, X_FRAME_OPTIONS,
+};
+use serde_json::json;
+
+use super::ResponseAnalyzer;
+use crate::{finding::FeroxFinding, response::FeroxResponse};
+
+/// HSTS max-age values below this (180 days) are considered too short
+const MIN_HSTS_MAX_AGE: u64 = 15_552_000;
+
+/// Security header observations for a single host
+#[derive(Debug, Default, Clone, PartialEq)]
+struct HostHeaders {
+    /// number of responses that were examined
+    sampled: usize,
+
+    /// issue -> number of examined responses that had the issue
+    issues: BTreeMap<String, usize>,
+}
+
+/// Analyzer that checks for missing/misconfigured security headers; enabled with
+/// --check-headers
+///
+/// observations are summarized per host once the scan has finished (see `summary`)
+#[derive(Debug, Default)]
+pub(super) struct HeadersAnalyzer {
+    /// origin (scheme://host:port) -> security header observations
+    observed: Mutex<HashMap<String, HostHeaders>>,
+}
+
+/// implementation of ResponseAnalyzer for HeadersAnalyzer
+impl ResponseAnalyzer for HeadersAnalyzer {
+    /// name of the analyzer, used for logging
+    fn name(&self) -> &'static str {
+        "headers"
+    }
+
+    /// record the header issues of html responses; findings are deferred until the scan ends
+    fn analyze(&self, response: &FeroxResponse) -> Vec<FeroxFinding> {
+        if !is_primary(response) {
+            return Vec::new();
+        }
+
+        let origin = response.url().origin().ascii_serialization();
+        let is_https = response.url().scheme() == "https";
+        let issues = header_issues(response.headers(), is_https);
+
+        if let Ok(mut observed) = self.observed.lock() {
+            let host = observed.entry(origin).or_default();
+
+            host.sampled += 1;
+
+            for issue in issues {
+                *host.issues.entry(issue).or_insert(0) += 1;
+            }
+        }
+
+        Vec::new()
+    }
+
+    /// one finding per host that had at least one header issue
+    fn summary(&self, _target_url: &str) -> Vec<FeroxFinding> {
+        let observed = match self.observed.lock() {
+            Ok(observed) => observed.clone(),
+            Err(_) => return Vec::new(),
+        };
+
+        let mut origins: Vec<_> = observed.keys().cloned().collect();
+        origins.sort();
+
+        let mut findings = Vec::new();
+
+        for origin in origins {
+            let host = &observed[&origin];
+
+            if host.issues.is_empty() {
+                continue;
+            }
+
+            let summary = host
+                .issues
+                .iter()
+                .map(|(issue, count)| format!("{} ({}/{})", issue, count, host.sampled))
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            findings.push(FeroxFinding::new(
+                "headers",
+                &origin,
+                &summary,
+                json!({
+                    "sampled": host.sampled,
+                    "issues": host.issues,
+                }),
+            ));
+        }
+
+        findings
+    }
+}
+
+/// Only html pages are examined; security headers on images, scripts, etc aren't meaningful
+fn is_primary(response: &FeroxResponse) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_ascii_lowercase().contains("text/html"))
+        .unwrap_or(false)
+}
+
+/// return the value of the given header, lowercased, if present
+fn header_value(headers: &HeaderMap, name: &HeaderName) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_ascii_lowercase())
+}
+
+/// Check the given headers for missing/misconfigured security headers and return a short
+/// description of each problem found
+///
+/// HSTS is only checked for https responses, as browsers ignore it over plain http
+pub(super) fn header_issues(headers: &HeaderMap, is_https: bool) -> Vec<String> {
+    let mut issues = Vec::new();
+
+    let csp = header_value(headers, &CONTENT_SECURITY_POLICY);
+
+    match &csp {
+        None => issues.push("missing Content-Security-Policy".to_string()),
+        Some(policy) => {
+            for weakness in &["'unsafe-inline'", "'unsafe-eval'"] {
+                if policy.contains(weakness) {
+                    issues.push(format!("Content-Security-Policy allows {}", weakness));
+                }
+            }
+        }
+    }
+
+    if is_https {
+        match header_value(headers, &STRICT_TRANSPORT_SECURITY) {
+            None => issues.push("missing Strict-Transport-Security".to_string()),
+            Some(hsts) => {
+                let max_age = hsts
+                    .split(';')
+                    .filter_map(|directive| directive.trim().strip_prefix("max-age="))
+                    .find_map(|age| age.trim_matches('"').parse::<u64>().ok());
+
+                if max_age.unwrap_or(0) < MIN_HSTS_MAX_AGE {
+                    issues.push("Strict-Transport-Security max-age under 180 days".to_string());
+                }
+            }
+        }
+    }
+
+    // frame-ancestors supersedes X-Frame-Options
+    let has_frame_ancestors = matches!(&csp, Some(policy) if policy.contains("frame-ancestors"));
+
+    if !has_frame_ancestors {
+        match header_value(headers, &X_FRAME_OPTIONS) {
+            None => issues.push("missing X-Frame-Options".to_string()),
+            Some(value) if value != "deny" && value != "sameorigin" => {
+                issues.push(format!("X-Frame-Options has invalid value {}", value))
+            }
+            Some(_) => {}
+        }
+    }
+
+    match header_value(headers, &X_CONTENT_TYPE_OPTIONS) {
+        None => issues.push("missing X-Content-Type-Options".to_string()),
+        Some(value) if value != "nosniff" => issues.push(format!(
+            "X-Content-Type-Options has invalid value {}",
+            value
+        )),
+        Some(_) => {}
+    }
+
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// missing headers and weak values are all reported; hsts is only checked over https
+    fn header_issues_reports_missing_and_misconfigured_headers() {
+        let mut response = FeroxResponse::default();
+        response.add_header(
+            "content-security-policy",
+            "default-src 'self' 'unsafe-inline'",
+        );
+        response.add_header("strict-transport-security", "max-age=3600");
+        response.add_header("x-frame-options", "ALLOW-FROM https://example.com");
+
+        assert_eq!(
+            header_issues(response.headers(), true),
+            vec![
+                "Content-Security-Policy allows 'unsafe-inline'",
+                "Strict-Transport-Security max-age under 180 days",
+                "X-Frame-Options has invalid value allow-from https://example.com",
+                "missing X-Content-Type-Options",
+            ]
+        );
+
+        assert_eq!(
+            header_issues(&HeaderMap::new(), false),
+            vec![
+                "missing Content-Security-Policy",
+                "missing X-Frame-Options",
+                "missing X-Content-Type-Options",
+            ]
+        );
+    }
+
+    #[test]
+    /// well configured responses have no issues; frame-ancestors replaces X-Frame-Options
+    fn header_issues_accepts_good_configuration() {
+        let mut response = FeroxResponse::default();
+        response.add_header(
+            "content-security-policy",
+            "default-src 'self'; frame-ancestors 'none'",
+        );
+        response.add_header(
+            "strict-transport-security",
+            "max-age=31536000; includeSubDomains",
+        );
+        response.add_header("x-content-type-options", "nosniff");
+
+        assert!(header_issues(response.headers(), true).is_empty());
+    }
+
+    #[test]
+    /// only html responses are examined, issues are counted per host and scan
+    fn summary_counts_issues_per_host() {
+        let mut page = FeroxResponse::default();
+        page.set_url("http://headers-summary.test/index.html");
+        page.add_header("content-type", "text/html; charset=utf-8");
+        page.add_header("x-frame-options", "DENY");
+        page.add_header("x-content-type-options", "nosniff");
+
+        let mut script = FeroxResponse::default();
+        script.set_url("http://headers-summary.test/app.js");
+        script.add_header("content-type", "application/javascript");
+
+        let analyzer = HeadersAnalyzer::default();
+        analyzer.analyze(&page);
+        analyzer.analyze(&page);
+        analyzer.analyze(&script);
+
+        let findings = analyzer.summary("");
+        assert_eq!(findings.len(), 1);
+        let finding = &findings[0];
+
+        assert_eq!(finding.url(), "http://headers-summary.test");
+        assert_eq!(finding.category(), "headers");
+        assert_eq!(finding.details()["sampled"], 2);
+        assert_eq!(
+            finding.details()["issues"],
+            json!({"missing Content-Security-Policy": 2})
+        );
+
+        // the next scan's analyzer starts out empty
+        assert!(HeadersAnalyzer::default().summary("").is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! passive analysis of response bodies; anything interesting is reported as a `FeroxFinding`
+mod comments;
+mod forms;
+mod harvest;
+mod headers;
+mod secrets;
+mod tech;
+
+use std::fmt::Debug;
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::{
+    config::Configuration,
+    event_handlers::{Command, Handles},
+    finding::FeroxFinding,
+    response::FeroxResponse,
+};
+
+use self::{
+    comments::CommentAnalyzer, forms::FormAnalyzer, harvest::HarvestAnalyzer,
+    headers::HeadersAnalyzer, secrets::SecretsAnalyzer, tech::TechAnalyzer,
+};
+
+/// Trait implemented by each stage that inspects response bodies
+///
+/// Analyzers never make requests of their own, they only look at responses that have already
+/// passed filtering and return zero or more findings about them
+pub trait ResponseAnalyzer: Debug + Send + Sync {
+    /// short name of the analyzer, used for logging
+    fn name(&self) -> &'static str;
+
+    /// examine the given response and return any findings
+    fn analyze(&self, response: &FeroxResponse) -> Vec<FeroxFinding>;
+
+    /// findings about the scan as a whole, i.e. what was collected from every response analyzed;
+    /// `target_url` is the scan's target, for findings that aren't about any one url
+    fn summary(&self, _target_url: &str) -> Vec<FeroxFinding> {
+        Vec::new()
+    }
+}
+
+/// Build the collection of analyzers that were enabled by the user; done once, when the `Handles`
+/// that responses are analyzed with are created
+pub fn enabled_analyzers(config: &Configuration) -> Vec<Box<dyn ResponseAnalyzer>> {
+    let mut analyzers: Vec<Box<dyn ResponseAnalyzer>> = Vec::new();
+
+    if config.extract_forms {
+        analyzers.push(Box::new(FormAnalyzer));
+    }
+
+    if config.extract_comments {
+        analyzers.push(Box::new(CommentAnalyzer));
+    }
+
+    if config.fingerprint {
+        analyzers.push(Box::new(TechAnalyzer::default()));
+    }
+
+    if config.find_secrets {
+        analyzers.push(Box::new(SecretsAnalyzer::new(&config.secret_patterns)));
+    }
+
+    if config.harvest {
+        analyzers.push(Box::new(HarvestAnalyzer::default()));
+    }
+
+    if config.check_headers {
+        analyzers.push(Box::new(HeadersAnalyzer::default()));
+    }
+
+    analyzers
+}
+
+/// Run every enabled analyzer over the given response and send any resulting findings to the
+/// output handler
+pub fn analyze(response: &FeroxResponse, handles: Arc<Handles>) -> Result<()> {
+    log::trace!("enter: analyze({}, {:?})", response, handles);
+
+    for analyzer in handles.analyzers() {
+        let findings = analyzer.analyze(response);
+
+        log::debug!(
+            "{} analyzer produced {} finding(s) for {}",
+            analyzer.name(),
+            findings.len(),
+            response.url()
+        );
+
+        for finding in findings {
+            let finding = finding.with_exchange(response);
+
+            handles
+                .output
+                .send(Command::ReportFinding(Box::new(finding)))?;
+        }
+    }
+
+    log::trace!("exit: analyze");
+    Ok(())
+}
+
+/// Send the findings of analyzers that summarize the entire scan to the output handler; meant
+/// to be called once, after all scans have finished
+///
+/// - --harvest: the deduplicated emails/usernames
+/// - --check-headers: missing/misconfigured security headers per host
+///
+/// both are collected by the analyzers themselves, see `ResponseAnalyzer::summary`
+pub fn report_summaries(handles: Arc<Handles>) -> Result<()> {
+    log::trace!("enter: report_summaries({:?})", handles);
+
+    let mut findings = Vec::new();
+
+    for analyzer in handles.analyzers() {
+        findings.extend(analyzer.summary(&handles.config.target_url));
+    }
+
+    for finding in findings {
+        handles
+            .output
+            .send(Command::ReportFinding(Box::new(finding)))?;
+    }
+
+    log::trace!("exit: report_summaries");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// only analyzers that were turned on in the config should be returned
+    fn enabled_analyzers_respects_configuration() {
+        let mut config = Configuration::default();
+        assert!(enabled_analyzers(&config).is_empty());
+
+        config.extract_comments = true;
+        let analyzers = enabled_analyzers(&config);
+        assert_eq!(analyzers.len(), 1);
+        assert_eq!(analyzers[0].name(), "comments");
+
+        config.extract_forms = true;
+        let names: Vec<_> = enabled_analyzers(&config)
+            .iter()
+            .map(|a| a.name())
+            .collect();
+        assert_eq!(names, vec!["forms", "comments"]);
+
+        config.fingerprint = true;
+        assert_eq!(enabled_analyzers(&config).len(), 3);
+
+        config.find_secrets = true;
+        assert_eq!(enabled_analyzers(&config)[3].name(), "secrets");
+
+        config.harvest = true;
+        assert_eq!(enabled_analyzers(&config)[4].name(), "harvest");
+
+        config.check_headers = true;
+        assert_eq!(enabled_analyzers(&config)[5].name(), "headers");
+
+        // built once, along with the handles every response is analyzed with
+        let handles = Handles::for_testing(None, Some(Arc::new(config))).0;
+        assert_eq!(handles.analyzers().len(), 6);
+    }
+}
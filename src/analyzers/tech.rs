@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+use serde_json::json;
+
+use super::ResponseAnalyzer;
+use crate::{
+    finding::FeroxFinding,
+    fingerprint::{fingerprint, suggested_extensions},
+    response::FeroxResponse,
+};
+
+/// Analyzer that identifies the technologies used by a target; enabled with --fingerprint
+#[derive(Debug, Default)]
+pub(super) struct TechAnalyzer {
+    /// (host, technology name) pairs that have already been reported; each technology is only
+    /// reported once per target and scan
+    reported: Mutex<HashSet<(String, &'static str)>>,
+}
+
+/// implementation of ResponseAnalyzer for TechAnalyzer
+impl ResponseAnalyzer for TechAnalyzer {
+    /// name of the analyzer, used for logging
+    fn name(&self) -> &'static str {
+        "tech"
+    }
+
+    /// fingerprint the response and report any technologies not yet seen for its host
+    fn analyze(&self, response: &FeroxResponse) -> Vec<FeroxFinding> {
+        find_technologies(response, &self.reported)
+    }
+}
+
+/// Fingerprint the given response and return a `FeroxFinding` for each technology that isn't
+/// in `reported` yet for the response's host
+pub(super) fn find_technologies(
+    response: &FeroxResponse,
+    reported: &Mutex<HashSet<(String, &'static str)>>,
+) -> Vec<FeroxFinding> {
+    log::trace!("enter: find_technologies({})", response);
+
+    let mut findings = Vec::new();
+
+    let host = match response.url().host_str() {
+        Some(host) => host.to_string(),
+        None => return findings,
+    };
+
+    let mut reported = match reported.lock() {
+        Ok(guard) => guard,
+        Err(_) => return findings,
+    };
+
+    for tech in fingerprint(response) {
+        if !reported.insert((host.clone(), tech.name)) {
+            continue;
+        }
+
+        let summary = format!("{} ({})", tech.name, tech.category);
+
+        let details = json!({
+            "name": tech.name,
+            "category": tech.category.to_string(),
+            "extensions": suggested_extensions(&[tech]),
+        });
+
+        findings.push(FeroxFinding::new(
+            "tech",
+            response.url().as_str(),
+            &summary,
+            details,
+        ));
+    }
+
+    log::trace!("exit: find_technologies -> {:?}", findings);
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// technologies are reported the first time they're seen on a host, once per scan
+    fn find_technologies_reports_each_technology_once_per_host() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://tech-once.test/index.php");
+        response.add_header("server", "Apache/2.4.41 (Ubuntu)");
+        response.add_header("set-cookie", "PHPSESSID=deadbeef; path=/");
+
+        let reported = Mutex::new(HashSet::new());
+        let findings = find_technologies(&response, &reported);
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].category(), "tech");
+        assert_eq!(findings[0].details()["name"], "Apache");
+        assert_eq!(findings[1].details()["category"], "language");
+        assert_eq!(findings[1].details()["extensions"], json!(["php"]));
+
+        assert!(find_technologies(&response, &reported).is_empty());
+
+        response.set_url("http://tech-twice.test/index.php");
+        assert_eq!(find_technologies(&response, &reported).len(), 2);
+
+        // the next scan's analyzer reports them again
+        assert_eq!(TechAnalyzer::default().analyze(&response).len(), 2);
+    }
+}
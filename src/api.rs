@@ -0,0 +1,372 @@
+//! run scans from other programs; results are returned as an async stream instead of being
+//! printed
+//!
+//! ```no_run
+//! use feroxbuster::{api::{ScanResult, Scanner}, config::Configuration};
+//! use futures::StreamExt;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let config = Configuration::builder()
+//!     .target_url("http://localhost")
+//!     .wordlist("/wordlists/common.txt")
+//!     .build()?;
+//!
+//! let mut results = Scanner::new(config).scan().await?;
+//!
+//! while let Some(result) = results.next().await {
+//!     match result {
+//!         ScanResult::Response(response) => println!("{} {}", response.status(), response.url()),
+//!         ScanResult::Finding(finding) => println!("{}", finding.url()),
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! each scan keeps track of the responses it already reported, so a response is only reported
+//! once per scan; scanning the same target again reports its responses again
+use std::sync::{Arc, Mutex};
+
+use anyhow::{bail, Result};
+use futures::stream::{self, BoxStream, StreamExt};
+use tokio::sync::mpsc;
+
+use crate::{
+    config::Configuration,
+    event_handlers::{
+        Command::{ScanInitialUrls, UpdateWordlist},
+        Handles,
+    },
+    lifecycle, product_wordlists,
+    scan_manager::FeroxScans,
+    scanner,
+    statistics::Stats,
+    utils::{load_wordlist, merge_duplicate_targets},
+    FeroxFinding, FeroxResponse,
+};
+
+/// A single result produced by a scan
+#[derive(Debug, Clone)]
+pub enum ScanResult {
+    /// a response that made it through all filters
+    Response(Box<FeroxResponse>),
+
+    /// something found while analyzing responses, i.e. secrets, cors misconfigurations, etc
+    Finding(Box<FeroxFinding>),
+}
+
+/// Runs scans using a single [Configuration](../config/struct.Configuration.html)
+#[derive(Debug, Clone)]
+pub struct Scanner {
+    /// configuration shared by every scan started from this Scanner
+    config: Arc<Configuration>,
+
+    /// scans started from this Scanner that may still be running, used for cancellation
+    running: Arc<Mutex<Vec<Arc<FeroxScans>>>>,
+
+    /// statistics of the most recently started scan
+    stats: Arc<Mutex<Option<Arc<Stats>>>>,
+}
+
+impl Scanner {
+    /// Given a [Configuration](../config/struct.Configuration.html), create a new Scanner
+    pub fn new(config: Configuration) -> Self {
+        Self {
+            config: Arc::new(config),
+            running: Default::default(),
+            stats: Default::default(),
+        }
+    }
+
+    /// The configuration used by this Scanner
+    pub fn config(&self) -> &Configuration {
+        &self.config
+    }
+
+    /// Statistics (requests sent, errors, status codes seen, etc) of the most recently started
+    /// scan; they're updated while the scan runs
+    pub fn stats(&self) -> Option<Arc<Stats>> {
+        self.stats.lock().ok().and_then(|stats| stats.clone())
+    }
+
+    /// Scan the configuration's target url
+    pub async fn scan(&self) -> Result<BoxStream<'static, ScanResult>> {
+        self.scan_targets(vec![self.config.target_url.clone()])
+            .await
+    }
+
+    /// Scan each of the given targets
+    ///
+    /// the scan runs in the background; the returned stream ends once every scan, including
+    /// recursion into any directories found, has finished. errors that prevent the scan from
+    /// starting (no live targets, an empty wordlist, etc) are returned instead of a stream
+    pub async fn scan_targets(
+        &self,
+        targets: Vec<String>,
+    ) -> Result<BoxStream<'static, ScanResult>> {
+        self.run(targets, None).await
+    }
+
+    /// Scan each of the given targets using `words` instead of the configuration's wordlist
+    ///
+    /// the words are used as-is; they aren't deduplicated or extended with product wordlists
+    pub async fn scan_with_words(
+        &self,
+        targets: Vec<String>,
+        words: Arc<Vec<String>>,
+    ) -> Result<BoxStream<'static, ScanResult>> {
+        self.run(targets, Some(words)).await
+    }
+
+    /// Start the event handlers and kick off the scan, see
+    /// [scan_targets](#method.scan_targets); scans are started and shut down the same way as
+    /// they are from the command line
+    async fn run(
+        &self,
+        targets: Vec<String>,
+        words: Option<Arc<Vec<String>>>,
+    ) -> Result<BoxStream<'static, ScanResult>> {
+        log::trace!("enter: run({:?})", targets);
+
+        let config = self.config.clone();
+        let (tx_results, rx_results) = mpsc::unbounded_channel::<ScanResult>();
+
+        let (handles, tasks) = lifecycle::start(config, Some(tx_results)).await?;
+
+        if let Ok(mut running) = self.running.lock() {
+            // scans that finished cleaning up are only referenced from here
+            running.retain(|scans| Arc::strong_count(scans) > 1);
+            running.push(handles.ferox_scans()?);
+        }
+
+        if let Ok(mut stats) = self.stats.lock() {
+            *stats = Some(handles.stats.data.clone());
+        }
+
+        if let Err(e) = start(targets, words, handles.clone()).await {
+            lifecycle::shutdown(handles, tasks).await?;
+            return Err(e);
+        }
+
+        tokio::spawn(async move {
+            // the results stream ends when the output handler exits during shutdown
+            if let Err(e) = lifecycle::shutdown(handles, tasks).await {
+                log::error!("Could not clean up after scan: {}", e);
+            }
+        });
+
+        let results = stream::unfold(rx_results, |mut rx_results| async move {
+            rx_results.recv().await.map(|result| (result, rx_results))
+        });
+
+        log::trace!("exit: run");
+        Ok(results.boxed())
+    }
+
+    /// Stop every scan started from this Scanner; their result streams end shortly after
+    ///
+    /// results that were already sent can still be read from the streams
+    pub async fn cancel(&self) -> Result<()> {
+        log::trace!("enter: cancel");
+
+        let running = match self.running.lock() {
+            Ok(mut running) => std::mem::take(&mut *running),
+            Err(_) => bail!("Could not determine which scans are running"),
+        };
+
+        for scans in running {
+            for scan in scans.get_active_scans() {
+                scan.abort().await?;
+            }
+        }
+
+        log::trace!("exit: cancel");
+        Ok(())
+    }
+}
+
+/// Merge duplicate targets, get the rest ready to be scanned (see `lifecycle::prepare`), and
+/// kick off a scan against the live ones; the configuration's wordlist is read when no words
+/// are given
+async fn start(
+    targets: Vec<String>,
+    words: Option<Arc<Vec<String>>>,
+    handles: Arc<Handles>,
+) -> Result<()> {
+    let targets = merge_duplicate_targets(targets, handles.clone());
+
+    let live_targets = lifecycle::prepare(&targets, handles.clone()).await?;
+
+    let words = match words {
+        Some(words) => words,
+        None => {
+            let mut words = load_wordlist(&handles.config)?;
+
+            if words.is_empty() {
+                bail!("Did not find any words in {}", handles.config.wordlist);
+            }
+
+            if handles.config.fingerprint && !handles.config.no_product_wordlists {
+                words =
+                    product_wordlists::extend_wordlist(words, &live_targets, handles.clone()).await;
+            }
+
+            words
+        }
+    };
+
+    handles.send_scan_command(UpdateWordlist(words.clone()))?;
+
+    scanner::initialize(words.len(), handles.clone()).await?;
+
+    handles.send_scan_command(ScanInitialUrls(live_targets))?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use std::fs::write;
+    use tempfile::NamedTempFile;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// responses are streamed back instead of printed, and the stream ends with the scan
+    async fn scanner_streams_responses() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/LICENSE");
+            then.status(200).body("this is a test");
+        });
+
+        let wordlist = NamedTempFile::new().unwrap();
+        write(wordlist.path(), "LICENSE\nnope\n").unwrap();
+
+        let config = Configuration::builder()
+            .target_url(&srv.url("/"))
+            .wordlist(wordlist.path().to_str().unwrap())
+            .no_recursion(true)
+            .build()
+            .unwrap();
+
+        let results: Vec<ScanResult> = Scanner::new(config).scan().await.unwrap().collect().await;
+
+        let urls: Vec<String> = results
+            .iter()
+            .filter_map(|result| match result {
+                ScanResult::Response(response) => Some(response.url().to_string()),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(urls, vec![srv.url("/LICENSE")]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// statistics read once the results stream has ended are final, including updates that were
+    /// still waiting in a batch when the last scan finished
+    async fn scanner_stats_are_final_when_the_stream_ends() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET)
+                .path_matches(regex::Regex::new("^/word[0-9]+$").unwrap());
+            then.status(403);
+        });
+
+        let wordlist = NamedTempFile::new().unwrap();
+        let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        write(wordlist.path(), words.join("\n")).unwrap();
+
+        let config = Configuration::builder()
+            .target_url(&srv.url("/"))
+            .wordlist(wordlist.path().to_str().unwrap())
+            .no_recursion(true)
+            .build()
+            .unwrap();
+
+        let scanner = Scanner::new(config);
+        let results: Vec<ScanResult> = scanner.scan().await.unwrap().collect().await;
+
+        let stats = scanner.stats().unwrap();
+
+        assert_eq!(mock.hits(), 100);
+        assert_eq!(stats.status_403s(), 100);
+        assert_eq!(stats.resources_discovered(), results.len());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// a scan that stopped on a --stop-on-match match doesn't stop the scans after it
+    async fn stop_on_match_only_stops_its_own_scan() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/LICENSE");
+            then.status(200).body("this is a test");
+        });
+
+        let wordlist = NamedTempFile::new().unwrap();
+        write(wordlist.path(), "LICENSE\n").unwrap();
+
+        let mut config = Configuration::builder()
+            .target_url(&srv.url("/"))
+            .wordlist(wordlist.path().to_str().unwrap())
+            .no_recursion(true)
+            .build()
+            .unwrap();
+
+        config.stop_on_match = vec!["/LICENSE".to_string()];
+
+        let scanner = Scanner::new(config);
+
+        for _ in 0..2 {
+            let results: Vec<ScanResult> = scanner.scan().await.unwrap().collect().await;
+
+            let found = results.iter().any(|result| {
+                matches!(result, ScanResult::Response(response) if response.url().path() == "/LICENSE")
+            });
+
+            assert!(found);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// cancelling stops the scan and ends its stream
+    async fn scanner_cancel_ends_stream() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET);
+            then.status(404).delay(std::time::Duration::from_secs(2));
+        });
+
+        let wordlist = NamedTempFile::new().unwrap();
+        let words: Vec<String> = (0..100).map(|i| format!("word{}", i)).collect();
+        write(wordlist.path(), words.join("\n")).unwrap();
+
+        let config = Configuration::builder()
+            .target_url(&srv.url("/"))
+            .wordlist(wordlist.path().to_str().unwrap())
+            .threads(1)
+            .timeout(5)
+            .no_recursion(true)
+            .build()
+            .unwrap();
+
+        let scanner = Scanner::new(config);
+        let results = scanner.scan().await.unwrap();
+
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+        scanner.cancel().await.unwrap();
+
+        let finished = tokio::time::timeout(
+            std::time::Duration::from_secs(10),
+            results.collect::<Vec<_>>(),
+        )
+        .await;
+
+        assert!(finished.is_ok());
+    }
+}
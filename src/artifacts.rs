@@ -0,0 +1,199 @@
+//! look for version control and IDE artifacts that were accidentally deployed
+use std::sync::Arc;
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::Url;
+use serde_json::json;
+
+use crate::{event_handlers::Handles, finding::FeroxFinding, utils::logged_request};
+
+/// severity attached to every artifact finding
+const SEVERITY: &str = "high";
+
+/// A well-known artifact and the heuristic used to confirm that a response really contains it
+#[derive(Debug)]
+struct ArtifactCheck {
+    /// path of the artifact, relative to the directory being scanned
+    path: &'static str,
+
+    /// short description of what was exposed
+    description: &'static str,
+
+    /// regex that must match the response body for the artifact to be reported
+    validator: Regex,
+}
+
+lazy_static! {
+    /// every artifact that's requested from each scanned directory
+    static ref ARTIFACT_CHECKS: Vec<ArtifactCheck> = vec![
+        ArtifactCheck {
+            path: ".git/HEAD",
+            description: "git repository",
+            validator: Regex::new(r#"\A(?:ref: refs/[^\s]+|[0-9a-f]{40})\s*\z"#)
+                .expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".git/config",
+            description: "git repository config",
+            validator: Regex::new(r#"(?m)^\s*\[core\]"#).expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".svn/entries",
+            description: "subversion working copy",
+            validator: Regex::new(r#"\A(?:\d+\s*\n|<\?xml[^>]*>\s*<wc-entries)"#)
+                .expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".svn/wc.db",
+            description: "subversion working copy database",
+            validator: Regex::new(r#"\ASQLite format 3"#).expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".hg/requires",
+            description: "mercurial repository",
+            validator: Regex::new(r#"(?m)^(?:revlogv1|store|fncache|dotencode)\s*$"#)
+                .expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".DS_Store",
+            description: "macOS directory metadata",
+            validator: Regex::new(r#"\A(?s:.{0,8})Bud1"#).expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".idea/workspace.xml",
+            description: "JetBrains IDE workspace",
+            validator: Regex::new(r#"<project version="\d+">(?s:.*)<component name="#)
+                .expect("Could not compile regex"),
+        },
+        ArtifactCheck {
+            path: ".env",
+            description: "environment file",
+            validator: Regex::new(r#"(?m)\A(?:\s*(?:#[^\n]*)?\n)*\s*(?:export\s+)?[A-Za-z_][A-Za-z0-9_]*\s*="#)
+                .expect("Could not compile regex"),
+        },
+    ];
+}
+
+/// Request each known artifact from the given directory and return a finding for every one whose
+/// content confirms that it's really there (a 200 status code alone isn't enough)
+pub async fn check_artifacts(directory: &str, handles: Arc<Handles>) -> Result<Vec<FeroxFinding>> {
+    log::trace!("enter: check_artifacts({}, {:?})", directory, handles);
+
+    let mut base = Url::parse(directory)?;
+
+    if !base.path().ends_with('/') {
+        // artifacts live inside the directory, not alongside it
+        base.set_path(&format!("{}/", base.path()));
+    }
+
+    let mut findings = Vec::new();
+
+    for check in ARTIFACT_CHECKS.iter() {
+        let url = base.join(check.path)?;
+
+        let response = match logged_request(&url, handles.clone()).await {
+            Ok(response) => response,
+            Err(e) => {
+                log::warn!("Could not request {}: {}", url, e);
+                continue;
+            }
+        };
+
+        if !response.status().is_success() {
+            continue;
+        }
+
+        let body = response.text().await.unwrap_or_default();
+
+        if !check.validator.is_match(&body) {
+            log::debug!("{} didn't look like a {}", url, check.description);
+            continue;
+        }
+
+        findings.push(FeroxFinding::new(
+            "artifact",
+            url.as_str(),
+            &format!("[{}] exposed {}", SEVERITY, check.description),
+            json!({
+                "severity": SEVERITY,
+                "artifact": check.path,
+                "description": check.description,
+            }),
+        ));
+    }
+
+    log::trace!("exit: check_artifacts -> {:?}", findings);
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Configuration, scan_manager::FeroxScans};
+    use httpmock::{Method::GET, MockServer};
+
+    /// find the check for the given artifact path
+    fn check(path: &str) -> &'static ArtifactCheck {
+        ARTIFACT_CHECKS.iter().find(|c| c.path == path).unwrap()
+    }
+
+    #[test]
+    /// validators accept real artifact content and reject soft-404/catch-all pages
+    fn validators_distinguish_artifacts_from_html() {
+        let html = "<!DOCTYPE html><html><body>Not Found</body></html>";
+
+        for check in ARTIFACT_CHECKS.iter() {
+            assert!(!check.validator.is_match(html), "{}", check.path);
+        }
+
+        assert!(check(".git/HEAD")
+            .validator
+            .is_match("ref: refs/heads/main\n"));
+        assert!(check(".git/config")
+            .validator
+            .is_match("[core]\n\trepositoryformatversion = 0\n"));
+        assert!(check(".svn/entries").validator.is_match("12\n"));
+        assert!(check(".hg/requires")
+            .validator
+            .is_match("dotencode\nfncache\nrevlogv1\nstore\n"));
+        assert!(check(".DS_Store").validator.is_match("\0\0\0\u{1}Bud1\0\0"));
+        let workspace = r#"<?xml version="1.0"?>
+            <project version="4">
+              <component name="ChangeListManager">"#;
+        assert!(check(".idea/workspace.xml").validator.is_match(workspace));
+        assert!(check(".env")
+            .validator
+            .is_match("# local settings\nDB_PASSWORD=hunter2\n"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// artifacts are requested relative to the directory and only reported when validated
+    async fn check_artifacts_reports_validated_artifacts() {
+        let srv = MockServer::start();
+
+        let head = srv.mock(|when, then| {
+            when.method(GET).path("/app/.git/HEAD");
+            then.status(200).body("ref: refs/heads/master\n");
+        });
+
+        let env = srv.mock(|when, then| {
+            when.method(GET).path("/app/.env");
+            then.status(200).body("<html><body>welcome!</body></html>");
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let scans = Arc::new(FeroxScans::default());
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        let findings = check_artifacts(&srv.url("/app"), handles).await.unwrap();
+
+        assert_eq!(head.hits(), 1);
+        assert_eq!(env.hits(), 1);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].category(), "artifact");
+        assert_eq!(findings[0].url(), srv.url("/app/.git/HEAD"));
+        assert_eq!(findings[0].details()["severity"], "high");
+    }
+}
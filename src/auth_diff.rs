@@ -0,0 +1,297 @@
+//! request discovered endpoints again without credentials and report the ones whose responses
+//! change, i.e. the endpoints where access control is (or should be) enforced (--auth-diff)
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use reqwest::{Method, Url};
+use serde_json::json;
+
+use crate::{
+    config::is_sensitive,
+    event_handlers::Handles,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{send_anonymously, target_request},
+};
+
+/// smallest change in size (words, or bytes without a body) that's considered a difference; keeps
+/// timestamps, csrf tokens, and the like from being reported
+const MIN_SIZE_CHANGE: u64 = 5;
+
+/// How the response without credentials differs from the one with them
+#[derive(Debug, Clone, PartialEq)]
+enum Difference {
+    /// the status code changed, i.e. 200 -> 401, or 200 -> 302 to a login page
+    Status,
+
+    /// same status, but a meaningfully different body
+    Content,
+}
+
+/// size used to compare two responses: their word count when bodies were read, their length
+/// otherwise (--head-only)
+fn size(response: &FeroxResponse) -> u64 {
+    if response.text().is_empty() {
+        response.content_length()
+    } else {
+        response.word_count() as u64
+    }
+}
+
+/// Compare the response to a request with credentials to the one without them; `None` when they
+/// are effectively the same
+fn difference(authed: &FeroxResponse, anonymous: &FeroxResponse) -> Option<Difference> {
+    if authed.status() != anonymous.status() {
+        return Some(Difference::Status);
+    }
+
+    let (authed, anonymous) = (size(authed), size(anonymous));
+    let change = authed.max(anonymous) - authed.min(anonymous);
+
+    // more than a tenth of the larger response changed
+    if change >= MIN_SIZE_CHANGE && change * 10 > authed.max(anonymous) {
+        return Some(Difference::Content);
+    }
+
+    None
+}
+
+/// `url` without any of the --query parameters that carry credentials
+fn anonymous_url(url: &Url, queries: &[(String, String)]) -> Url {
+    let secret: HashSet<&str> = queries
+        .iter()
+        .map(|(name, _)| name.as_str())
+        .filter(|name| is_sensitive(name))
+        .collect();
+
+    if secret.is_empty() {
+        return url.clone();
+    }
+
+    let kept: Vec<(String, String)> = url
+        .query_pairs()
+        .filter(|(name, _)| !secret.contains(name.as_ref()))
+        .map(|(name, value)| (name.into_owned(), value.into_owned()))
+        .collect();
+
+    let mut anonymous = url.clone();
+
+    if kept.is_empty() {
+        anonymous.set_query(None);
+    } else {
+        anonymous.query_pairs_mut().clear().extend_pairs(kept);
+    }
+
+    anonymous
+}
+
+/// Request the url of the given response again, without the credentials from --headers and
+/// --query, and report it when the response changes meaningfully
+///
+/// endpoints that respond the same either way are public, and `Ok(None)` is returned for them
+pub async fn probe(
+    response: &FeroxResponse,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let client = handles
+        .config
+        .anonymous_client
+        .as_ref()
+        .ok_or_else(|| anyhow!("no client without credentials was built"))?;
+
+    let url = anonymous_url(response.url(), &handles.config.queries);
+
+    let method = if handles.config.head_only {
+        Method::HEAD
+    } else {
+        Method::GET
+    };
+
+    // without with_user_headers, which would add the [login] session back
+    let context = &handles.config.request_context;
+    let request = target_request(context, client, &method, &url);
+    let anonymous = send_anonymously(context, request, &url).await?;
+
+    let anonymous = FeroxResponse::from(
+        anonymous,
+        !handles.config.head_only,
+        handles.config.max_response_size,
+        handles.config.output_level,
+    )
+    .await;
+
+    let difference = match difference(response, &anonymous) {
+        Some(difference) => difference,
+        None => {
+            log::trace!("exit: probe -> None");
+            return Ok(None);
+        }
+    };
+
+    // still getting a successful response without credentials, just a different one, deserves
+    // a closer look than an endpoint that turns anonymous users away
+    let (severity, summary) = if anonymous.status().is_success() {
+        (
+            "low",
+            format!(
+                "[low] response changes without credentials ({} -> {}, {} -> {} {})",
+                response.status().as_u16(),
+                anonymous.status().as_u16(),
+                size(response),
+                size(&anonymous),
+                if anonymous.text().is_empty() {
+                    "bytes"
+                } else {
+                    "words"
+                }
+            ),
+        )
+    } else {
+        (
+            "info",
+            format!(
+                "[info] credentials required ({} without them)",
+                anonymous.status().as_u16()
+            ),
+        )
+    };
+
+    let finding = FeroxFinding::new(
+        "auth-diff",
+        response.url().as_str(),
+        &summary,
+        json!({
+            "severity": severity,
+            "difference": match difference {
+                Difference::Status => "status",
+                Difference::Content => "content",
+            },
+            "authenticated": {
+                "status": response.status().as_u16(),
+                "content_length": response.content_length(),
+                "word_count": response.word_count(),
+            },
+            "anonymous": {
+                "url": url.as_str(),
+                "status": anonymous.status().as_u16(),
+                "content_length": anonymous.content_length(),
+                "word_count": anonymous.word_count(),
+            },
+        }),
+    )
+    // the request without credentials is what shows access control is missing
+    .with_exchange(&anonymous);
+
+    log::trace!("exit: probe -> {:?}", finding);
+    Ok(Some(finding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::GET, MockServer};
+    use reqwest::StatusCode;
+    use std::collections::HashMap;
+
+    /// response to the given url with the given status and body
+    fn response(url: &str, status: u16, words: usize) -> FeroxResponse {
+        let mut response = FeroxResponse::default();
+        response.set_url(url);
+        response.set_status(StatusCode::from_u16(status).unwrap());
+        response.set_text(&vec!["word"; words].join(" "));
+        response
+    }
+
+    #[test]
+    /// status changes always count, small changes in size don't
+    fn difference_ignores_small_changes() {
+        let url = "http://localhost/admin";
+
+        assert_eq!(
+            difference(&response(url, 200, 100), &response(url, 401, 100)),
+            Some(Difference::Status)
+        );
+        assert_eq!(
+            difference(&response(url, 200, 100), &response(url, 200, 40)),
+            Some(Difference::Content)
+        );
+        assert_eq!(
+            difference(&response(url, 200, 100), &response(url, 200, 96)),
+            None
+        );
+        assert_eq!(
+            difference(&response(url, 200, 6), &response(url, 200, 3)),
+            None
+        );
+    }
+
+    #[test]
+    /// only query parameters that look like credentials are dropped
+    fn anonymous_url_drops_credential_queries() {
+        let url = Url::parse("http://localhost/admin?api_key=abc&page=2").unwrap();
+        let queries = vec![
+            ("api_key".to_string(), "abc".to_string()),
+            ("page".to_string(), "2".to_string()),
+        ];
+
+        assert_eq!(
+            anonymous_url(&url, &queries).as_str(),
+            "http://localhost/admin?page=2"
+        );
+        assert_eq!(anonymous_url(&url, &queries[1..]).as_str(), url.as_str());
+
+        let url = Url::parse("http://localhost/admin?token=abc").unwrap();
+        let queries = vec![("token".to_string(), "abc".to_string())];
+
+        assert_eq!(
+            anonymous_url(&url, &queries).as_str(),
+            "http://localhost/admin"
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// endpoints that turn away requests without credentials are reported, public ones aren't
+    async fn probe_reports_endpoints_that_need_credentials() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/admin");
+            then.status(401);
+        });
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/public");
+            then.status(200).body("welcome");
+        });
+
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer hunter2".to_string());
+
+        let mut config = Configuration {
+            auth_diff: true,
+            headers,
+            ..Default::default()
+        };
+        config.rebuild_clients().unwrap();
+
+        let handles = Arc::new(Handles::for_testing(None, Some(Arc::new(config))).0);
+
+        let mut admin = response(&srv.url("/admin"), 200, 0);
+        admin.set_text("top secret");
+
+        let finding = probe(&admin, handles.clone()).await.unwrap().unwrap();
+
+        assert_eq!(finding.category(), "auth-diff");
+        assert_eq!(finding.details()["severity"], "info");
+        assert_eq!(finding.details()["anonymous"]["status"], 401);
+
+        let mut public = response(&srv.url("/public"), 200, 0);
+        public.set_text("welcome");
+
+        assert!(probe(&public, handles).await.unwrap().is_none());
+    }
+}
@@ -1,7 +1,12 @@
 use super::entry::BannerEntry;
 use crate::{
+    client,
     config::Configuration,
     event_handlers::Handles,
+    fingerprint::{fingerprint_target, suggested_extensions},
+    numbers::NumberFormat,
+    presets::PRESETS_VERSION,
+    product_wordlists::product_wordlist,
     utils::{logged_request, status_colorizer},
     VERSION,
 };
@@ -59,12 +64,31 @@ pub struct Banner {
     /// represents Configuration.replay_proxy
     replay_proxy: BannerEntry,
 
+    /// where the proxy comes from, when it's not (only) --proxy; represents
+    /// Configuration.no_env_proxy and the proxy environment variables
+    proxy_source: Option<BannerEntry>,
+
+    /// represents Configuration.tor_control
+    tor_control: BannerEntry,
+
     /// represents Configuration.replay_codes
     replay_codes: BannerEntry,
 
     /// represents Configuration.headers
     headers: Vec<BannerEntry>,
 
+    /// represents Configuration.rotate_headers
+    rotate_headers: Vec<BannerEntry>,
+
+    /// represents Configuration.host_header
+    host_header: BannerEntry,
+
+    /// represents Configuration.login
+    login: BannerEntry,
+
+    /// represents Configuration.csrf
+    csrf: BannerEntry,
+
     /// represents Configuration.filter_size
     filter_size: Vec<BannerEntry>,
 
@@ -80,12 +104,24 @@ pub struct Banner {
     /// represents Configuration.filter_regex
     filter_regex: Vec<BannerEntry>,
 
+    /// represents Configuration.cluster_threshold
+    cluster_threshold: BannerEntry,
+
     /// represents Configuration.extract_links
     extract_links: BannerEntry,
 
+    /// represents Configuration.extract_forms
+    extract_forms: BannerEntry,
+
+    /// represents Configuration.extract_comments
+    extract_comments: BannerEntry,
+
     /// represents Configuration.json
     json: BannerEntry,
 
+    /// represents Configuration.error_records
+    error_records: BannerEntry,
+
     /// represents Configuration.output
     output: BannerEntry,
 
@@ -95,6 +131,97 @@ pub struct Banner {
     /// represents Configuration.extensions
     extensions: BannerEntry,
 
+    /// represents Configuration.seed_from
+    seed_from: BannerEntry,
+
+    /// represents Configuration.seed_file
+    seed_file: BannerEntry,
+
+    /// represents Configuration.buckets
+    buckets: BannerEntry,
+
+    /// represents Configuration.presets
+    presets: BannerEntry,
+
+    /// represents Configuration.crawl_wordlist
+    crawl_wordlist: BannerEntry,
+
+    /// represents Configuration.fingerprint
+    fingerprint: BannerEntry,
+
+    /// represents Configuration.parse_listings
+    parse_listings: BannerEntry,
+
+    /// represents Configuration.discover_openapi
+    discover_openapi: BannerEntry,
+
+    /// represents Configuration.graphql_probe
+    graphql_probe: BannerEntry,
+
+    /// represents Configuration.source_maps
+    source_maps: BannerEntry,
+
+    /// represents Configuration.find_secrets
+    find_secrets: BannerEntry,
+
+    /// represents Configuration.harvest
+    harvest: BannerEntry,
+
+    /// represents Configuration.mine_params
+    mine_params: BannerEntry,
+
+    /// represents Configuration.check_artifacts
+    check_artifacts: BannerEntry,
+
+    /// represents Configuration.check_headers
+    check_headers: BannerEntry,
+
+    /// represents Configuration.cors_probe
+    cors_probe: BannerEntry,
+
+    /// represents Configuration.ws_probe
+    ws_probe: BannerEntry,
+
+    /// represents Configuration.auth_diff
+    auth_diff: BannerEntry,
+
+    /// represents Configuration.check_methods
+    check_methods: BannerEntry,
+
+    /// represents Configuration.permute and Configuration.permutation_rules
+    permute: BannerEntry,
+
+    /// represents Configuration.iis_shortnames
+    iis_shortnames: BannerEntry,
+
+    /// represents Configuration.redact_secrets
+    redact_secrets: BannerEntry,
+
+    /// represents Configuration.no_product_wordlists
+    no_product_wordlists: BannerEntry,
+
+    /// represents Configuration.track_changes
+    track_changes: BannerEntry,
+
+    /// represents Configuration.save_config
+    save_config: BannerEntry,
+
+    /// represents Configuration.secret_patterns
+    secret_patterns: Vec<BannerEntry>,
+
+    /// represents Configuration.skip_listed_dirs
+    skip_listed_dirs: BannerEntry,
+
+    /// technologies identified on each target, populated by `fingerprint_targets`
+    technologies: Vec<BannerEntry>,
+
+    /// extensions relevant to the identified technologies, populated by `fingerprint_targets`
+    suggested_extensions: Option<BannerEntry>,
+
+    /// products whose bundled words are appended to the wordlist, populated by
+    /// `fingerprint_targets`
+    product_wordlists: Option<BannerEntry>,
+
     /// represents Configuration.insecure
     insecure: BannerEntry,
 
@@ -125,18 +252,135 @@ pub struct Banner {
     /// represents Configuration.rate_limit
     rate_limit: BannerEntry,
 
+    /// represents Configuration.request_limit
+    request_limit: BannerEntry,
+
+    /// represents Configuration.max_429s and Configuration.cooldown
+    cooldown: BannerEntry,
+
     /// represents Configuration.parallel
     parallel: BannerEntry,
 
+    /// represents Configuration.coordinate
+    coordinate: BannerEntry,
+
+    /// represents Configuration.shard_size
+    shard_size: BannerEntry,
+
+    /// represents Configuration.log_format
+    log_format: BannerEntry,
+
+    /// represents Configuration.thousands_separator
+    thousands_separator: BannerEntry,
+
+    /// represents Configuration.byte_units
+    byte_units: BannerEntry,
+
     /// represents Configuration.auto_tune
     auto_tune: BannerEntry,
 
     /// represents Configuration.auto_bail
     auto_bail: BannerEntry,
 
+    /// represents Configuration.adaptive_threads
+    adaptive_threads: BannerEntry,
+
+    /// represents Configuration.seen_urls
+    seen_urls: BannerEntry,
+
+    /// represents Configuration.wordlist_cache
+    wordlist_cache: BannerEntry,
+
+    /// represents Configuration.dns_ttl
+    dns_ttl: BannerEntry,
+
+    /// represents Configuration.max_response_size
+    max_response_size: BannerEntry,
+
+    /// represents Configuration.head_only
+    head_only: BannerEntry,
+
+    /// represents Configuration.response_time
+    response_time: BannerEntry,
+
+    /// represents Configuration.streams_per_connection
+    streams_per_connection: BannerEntry,
+
+    /// represents Configuration.memory_limit
+    memory_limit: BannerEntry,
+
+    /// represents Configuration.adaptive_timeout
+    adaptive_timeout: BannerEntry,
+
+    /// represents Configuration.throttle_bytes
+    throttle_bytes: BannerEntry,
+
+    /// represents Configuration.async_workers
+    async_workers: BannerEntry,
+
+    /// represents Configuration.blocking_threads
+    blocking_threads: BannerEntry,
+
+    /// represents Configuration.trace_output
+    trace_output: BannerEntry,
+
+    /// represents Configuration.refresh_rate
+    refresh_rate: BannerEntry,
+
+    /// represents Configuration.checkpoint_interval
+    checkpoint_interval: BannerEntry,
+
+    /// represents Configuration.checkpoint_requests
+    checkpoint_requests: BannerEntry,
+
+    /// represents Configuration.burp_output
+    burp_output: BannerEntry,
+
+    /// represents Configuration.zap_url
+    zap_url: BannerEntry,
+
+    /// represents Configuration.zap_active_scan
+    zap_active_scan: BannerEntry,
+
+    /// represents Configuration.event_stream
+    event_stream: BannerEntry,
+
+    /// represents Configuration.scan_id
+    scan_id: BannerEntry,
+
+    /// represents Configuration.scan_id_header
+    scan_id_header: BannerEntry,
+
+    /// represents Configuration.defectdojo_output
+    defectdojo_output: BannerEntry,
+
+    /// represents Configuration.evidence_dir and Configuration.evidence_severity
+    evidence: BannerEntry,
+
+    /// represents Configuration.output_format
+    output_format: BannerEntry,
+
+    /// represents Configuration.bucket_by_tech
+    bucket_by_tech: BannerEntry,
+
     /// represents Configuration.url_denylist
     url_denylist: Vec<BannerEntry>,
 
+    /// represents Configuration.deny_url_regex
+    deny_url_regex: Vec<BannerEntry>,
+
+    /// represents Configuration.scope
+    scope: Vec<BannerEntry>,
+
+    /// represents Configuration.expect_found
+    expect_found: Vec<BannerEntry>,
+
+    /// represents Configuration.expect_absent
+    expect_absent: Vec<BannerEntry>,
+
+    /// represents Configuration.stop_on_match
+    stop_on_match: Vec<BannerEntry>,
+
     /// current version of feroxbuster
     pub(super) version: String,
 
@@ -148,17 +392,25 @@ pub struct Banner {
 impl Banner {
     /// Create a new Banner from a Configuration and live targets
     pub fn new(tgts: &[String], config: &Configuration) -> Self {
+        let numbers = NumberFormat::from_config(config);
         let mut targets = Vec::new();
         let mut url_denylist = Vec::new();
+        let mut deny_url_regex = Vec::new();
+        let mut scope = Vec::new();
+        let mut expect_found = Vec::new();
+        let mut expect_absent = Vec::new();
+        let mut stop_on_match = Vec::new();
         let mut code_filters = Vec::new();
         let mut replay_codes = Vec::new();
         let mut headers = Vec::new();
+        let mut rotate_headers = Vec::new();
         let mut filter_size = Vec::new();
         let mut filter_similar = Vec::new();
         let mut filter_word_count = Vec::new();
         let mut filter_line_count = Vec::new();
         let mut filter_regex = Vec::new();
         let mut queries = Vec::new();
+        let mut secret_patterns = Vec::new();
 
         for target in tgts {
             targets.push(BannerEntry::new("🎯", "Target Url", target));
@@ -168,6 +420,26 @@ impl Banner {
             url_denylist.push(BannerEntry::new("🚫", "Don't Scan", denied_url));
         }
 
+        for pattern in &config.deny_url_regex {
+            deny_url_regex.push(BannerEntry::new("🚫", "Deny Url Regex", pattern));
+        }
+
+        for rule in &config.scope {
+            scope.push(BannerEntry::new("🔭", "Scope", rule));
+        }
+
+        for path in &config.expect_found {
+            expect_found.push(BannerEntry::new("✅", "Expect Found", path));
+        }
+
+        for path in &config.expect_absent {
+            expect_absent.push(BannerEntry::new("⛔", "Expect Absent", path));
+        }
+
+        for criterion in &config.stop_on_match {
+            stop_on_match.push(BannerEntry::new("🛑", "Stop On Match", criterion));
+        }
+
         let mut codes = vec![];
         for code in &config.status_codes {
             codes.push(status_colorizer(&code.to_string()))
@@ -201,8 +473,34 @@ impl Banner {
             ));
         }
 
+        for spec in &config.rotate_headers {
+            rotate_headers.push(BannerEntry::new("🔄", "Rotating Header", spec));
+        }
+
+        let login = match &config.login {
+            Some(login) => BannerEntry::new(
+                "🔐",
+                "Login",
+                &format!("{} {}", login.method.to_uppercase(), login.url),
+            ),
+            None => BannerEntry::default(),
+        };
+
+        let csrf = match &config.csrf {
+            Some(csrf) => BannerEntry::new(
+                "🎫",
+                "CSRF Token",
+                &format!("{} from {}", csrf.name, csrf.url),
+            ),
+            None => BannerEntry::default(),
+        };
+
         for filter in &config.filter_size {
-            filter_size.push(BannerEntry::new("💢", "Size Filter", &filter.to_string()));
+            filter_size.push(BannerEntry::new(
+                "💢",
+                "Size Filter",
+                &numbers.bytes(*filter),
+            ));
         }
 
         for filter in &config.filter_similar {
@@ -229,6 +527,10 @@ impl Banner {
             filter_regex.push(BannerEntry::new("💢", "Regex Filter", filter));
         }
 
+        for pattern in &config.secret_patterns {
+            secret_patterns.push(BannerEntry::new("🔑", "Secret Pattern", pattern));
+        }
+
         for query in &config.queries {
             queries.push(BannerEntry::new(
                 "🤔",
@@ -264,37 +566,237 @@ impl Banner {
         let scan_limit = BannerEntry::new(
             "🦥",
             "Concurrent Scan Limit",
-            &config.scan_limit.to_string(),
+            &numbers.count(config.scan_limit as u64),
         );
 
         let replay_proxy = BannerEntry::new("🎥", "Replay Proxy", &config.replay_proxy);
+        let proxy_source = client::proxy_source(&config.proxy, config.no_env_proxy)
+            .map(|source| BannerEntry::new("🧭", "Proxy Source", &source));
         let auto_tune = BannerEntry::new("🎶", "Auto Tune", &config.auto_tune.to_string());
         let auto_bail = BannerEntry::new("🪣", "Auto Bail", &config.auto_bail.to_string());
+        let adaptive_threads = BannerEntry::new(
+            "🎚",
+            "Adaptive Threads",
+            &config.adaptive_threads.to_string(),
+        );
+        let seen_urls = BannerEntry::new("🌸", "Seen Urls", &config.seen_urls);
+        let wordlist_cache =
+            BannerEntry::new("🗃", "Wordlist Cache", &config.wordlist_cache.to_string());
+        let dns_ttl = BannerEntry::new("🧭", "DNS TTL (secs)", &config.dns_ttl.to_string());
+        let max_response_size = BannerEntry::new(
+            "📦",
+            "Max Response Size",
+            &numbers.bytes(config.max_response_size),
+        );
+        let head_only = BannerEntry::new(
+            "🪶",
+            "Head Only",
+            "true (bodies skipped; no word or line counts)",
+        );
+        let response_time =
+            BannerEntry::new("⏱", "Response Time", &config.response_time.to_string());
+        let streams_per_connection = BannerEntry::new(
+            "🔀",
+            "Streams Per Connection",
+            &numbers.count(config.streams_per_connection as u64),
+        );
+        let memory_limit =
+            BannerEntry::new("🧠", "Memory Limit", &numbers.bytes(config.memory_limit));
+        let adaptive_timeout = BannerEntry::new(
+            "📐",
+            "Adaptive Timeout",
+            &format!("{}x response time", config.adaptive_timeout),
+        );
+        let throttle_bytes = BannerEntry::new(
+            "🐢",
+            "Throttle Bytes",
+            &format!("{}/s", numbers.bytes(config.throttle_bytes)),
+        );
+        let async_workers = BannerEntry::new(
+            "🧵",
+            "Async Workers",
+            &numbers.count(config.async_workers as u64),
+        );
+        let blocking_threads = BannerEntry::new(
+            "🧱",
+            "Blocking Threads",
+            &numbers.count(config.blocking_threads as u64),
+        );
+        let trace_output = BannerEntry::new("🔭", "Trace Output", &config.trace_output);
+        let refresh_rate =
+            BannerEntry::new("🎞", "Refresh Rate (ms)", &config.refresh_rate.to_string());
+        let checkpoint_interval =
+            BannerEntry::new("⏲", "Checkpoint Interval", &config.checkpoint_interval);
+        let checkpoint_requests = BannerEntry::new(
+            "💾",
+            "Checkpoint Requests",
+            &numbers.count(config.checkpoint_requests),
+        );
         let cfg = BannerEntry::new("💉", "Config File", &config.config);
         let proxy = BannerEntry::new("💎", "Proxy", &config.proxy);
-        let threads = BannerEntry::new("🚀", "Threads", &config.threads.to_string());
+        let tor_control = BannerEntry::new("🧅", "Tor Control", &config.tor_control);
+        let threads = BannerEntry::new("🚀", "Threads", &numbers.count(config.threads as u64));
         let wordlist = BannerEntry::new("📖", "Wordlist", &config.wordlist);
         let timeout = BannerEntry::new("💥", "Timeout (secs)", &config.timeout.to_string());
         let user_agent = BannerEntry::new("🦡", "User-Agent", &config.user_agent);
         let extract_links =
             BannerEntry::new("🔎", "Extract Links", &config.extract_links.to_string());
+        let extract_forms =
+            BannerEntry::new("📝", "Extract Forms", &config.extract_forms.to_string());
+        let extract_comments = BannerEntry::new(
+            "💬",
+            "Extract Comments",
+            &config.extract_comments.to_string(),
+        );
         let json = BannerEntry::new("🧔", "JSON Output", &config.json.to_string());
+        let error_records =
+            BannerEntry::new("🧯", "Error Records", &config.error_records.to_string());
         let output = BannerEntry::new("💾", "Output File", &config.output);
         let debug_log = BannerEntry::new("🪲", "Debugging Log", &config.debug_log);
+        let burp_output = BannerEntry::new("🔶", "Burp Output", &config.burp_output);
+        let zap_url = BannerEntry::new("⚡", "ZAP Url", &config.zap_url);
+        let zap_active_scan =
+            BannerEntry::new("⚡", "ZAP Active Scan", &config.zap_active_scan.to_string());
+        let event_stream = BannerEntry::new("📡", "Event Stream", &config.event_stream);
+        let scan_id = BannerEntry::new("🪪", "Scan ID", &config.scan_id);
+        let scan_id_header = BannerEntry::new("🪪", "Scan ID Header", &config.scan_id_header);
+        let host_header = BannerEntry::new("📍", "Host Header", &config.host_header);
+        let defectdojo_output =
+            BannerEntry::new("🥋", "DefectDojo Output", &config.defectdojo_output);
+        let evidence = BannerEntry::new(
+            "🧾",
+            "Evidence",
+            &format!(
+                "{} ({} and up)",
+                config.evidence_dir, config.evidence_severity
+            ),
+        );
+        let output_format = BannerEntry::new("📝", "Output Format", &config.output_format);
+        let bucket_by_tech =
+            BannerEntry::new("🪣", "Bucket By Tech", &config.bucket_by_tech.to_string());
+        let track_changes = BannerEntry::new("🔄", "Track Changes", &config.track_changes);
+        let save_config = BannerEntry::new("💾", "Save Config", &config.save_config);
         let extensions = BannerEntry::new(
             "💲",
             "Extensions",
             &format!("[{}]", config.extensions.join(", ")),
         );
+        let fingerprint =
+            BannerEntry::new("🔬", "Fingerprint Targets", &config.fingerprint.to_string());
+        let parse_listings = BannerEntry::new(
+            "📂",
+            "Parse Dir Listings",
+            &config.parse_listings.to_string(),
+        );
+        let discover_openapi = BannerEntry::new(
+            "📜",
+            "Discover API Specs",
+            &config.discover_openapi.to_string(),
+        );
+        let graphql_probe =
+            BannerEntry::new("🕸", "GraphQL Probe", &config.graphql_probe.to_string());
+        let source_maps = BannerEntry::new("🗺", "Source Maps", &config.source_maps.to_string());
+        let find_secrets = BannerEntry::new("🔑", "Find Secrets", &config.find_secrets.to_string());
+        let harvest = BannerEntry::new("🌾", "Harvest Emails/Users", &config.harvest.to_string());
+        let mine_params = BannerEntry::new("⛏", "Mine Parameters", &config.mine_params.to_string());
+        let check_artifacts =
+            BannerEntry::new("🗃", "Check Artifacts", &config.check_artifacts.to_string());
+        let check_headers =
+            BannerEntry::new("🛡", "Check Headers", &config.check_headers.to_string());
+        let cors_probe = BannerEntry::new("🌐", "CORS Probe", &config.cors_probe.to_string());
+        let ws_probe = BannerEntry::new("🔌", "WebSocket Probe", &config.ws_probe.to_string());
+        let auth_diff = BannerEntry::new("🔑", "Auth Diff", &config.auth_diff.to_string());
+        let check_methods =
+            BannerEntry::new("🚦", "Check Methods", &config.check_methods.to_string());
+        let permute = BannerEntry::new(
+            "🧬",
+            "Permute Names",
+            &if config.permutation_rules.is_empty() {
+                String::from("built-in rules")
+            } else {
+                format!("[{}]", config.permutation_rules.join(", "))
+            },
+        );
+        let iis_shortnames =
+            BannerEntry::new("🔡", "IIS Short Names", &config.iis_shortnames.to_string());
+        let redact_secrets =
+            BannerEntry::new("🙈", "Redact Secrets", &config.redact_secrets.to_string());
+        let no_product_wordlists = BannerEntry::new(
+            "📚",
+            "No Product Wordlists",
+            &config.no_product_wordlists.to_string(),
+        );
+        let skip_listed_dirs = BannerEntry::new(
+            "⏩",
+            "Skip Listed Dirs",
+            &config.skip_listed_dirs.to_string(),
+        );
+        let seed_from = BannerEntry::new(
+            "🕰",
+            "Seed URLs From",
+            &format!("[{}]", config.seed_from.join(", ")),
+        );
+        let seed_file = BannerEntry::new("🌱", "Seed URLs File", &config.seed_file);
+        let presets = BannerEntry::new(
+            "📦",
+            "Presets",
+            &format!("[{}] v{}", config.presets.join(", "), PRESETS_VERSION),
+        );
+        let crawl_wordlist = BannerEntry::new(
+            "🕸",
+            "Crawl Wordlist",
+            &format!("depth {}", config.crawl_wordlist),
+        );
+        let buckets = BannerEntry::new(
+            "🪣",
+            "Cloud Buckets For",
+            &format!("[{}]", config.buckets.join(", ")),
+        );
         let insecure = BannerEntry::new("🔓", "Insecure", &config.insecure.to_string());
         let redirects = BannerEntry::new("📍", "Follow Redirects", &config.redirects.to_string());
         let dont_filter =
             BannerEntry::new("🤪", "Filter Wildcards", &(!config.dont_filter).to_string());
         let add_slash = BannerEntry::new("🪓", "Add Slash", &config.add_slash.to_string());
         let time_limit = BannerEntry::new("🕖", "Time Limit", &config.time_limit);
-        let parallel = BannerEntry::new("🛤", "Parallel Scans", &config.parallel.to_string());
-        let rate_limit =
-            BannerEntry::new("🚧", "Requests per Second", &config.rate_limit.to_string());
+        let parallel = BannerEntry::new(
+            "🛤",
+            "Parallel Scans",
+            &numbers.count(config.parallel as u64),
+        );
+        let coordinate = BannerEntry::new("🛰", "Coordinating Workers On", &config.coordinate);
+        let shard_size =
+            BannerEntry::new("🧱", "Shard Size", &numbers.count(config.shard_size as u64));
+        let log_format = BannerEntry::new("🧾", "Log Format", &config.log_format);
+        let thousands_separator = BannerEntry::new(
+            "🔢",
+            "Thousands Separator",
+            &format!("{:?}", config.thousands_separator),
+        );
+        let byte_units = BannerEntry::new("📏", "Byte Units", &config.byte_units);
+        let rate_limit = BannerEntry::new(
+            "🚧",
+            "Requests per Second",
+            &numbers.count(config.rate_limit as u64),
+        );
+        let request_limit = BannerEntry::new(
+            "🚧",
+            "Requests per Host",
+            &numbers.count(config.request_limit as u64),
+        );
+        let cooldown = BannerEntry::new(
+            "🧊",
+            "429 Cool-down",
+            &format!(
+                "{}s after {} in a row",
+                config.cooldown_duration().as_secs(),
+                config.cooldown_after()
+            ),
+        );
+        let cluster_threshold = BannerEntry::new(
+            "🧩",
+            "Response Cluster Limit",
+            &numbers.count(config.cluster_threshold as u64),
+        );
 
         Self {
             targets,
@@ -306,22 +808,94 @@ impl Banner {
             user_agent,
             auto_bail,
             auto_tune,
+            adaptive_threads,
+            seen_urls,
+            wordlist_cache,
+            dns_ttl,
+            max_response_size,
+            head_only,
+            response_time,
+            streams_per_connection,
+            memory_limit,
+            adaptive_timeout,
+            throttle_bytes,
+            async_workers,
+            blocking_threads,
+            trace_output,
+            refresh_rate,
+            checkpoint_interval,
+            checkpoint_requests,
+            burp_output,
+            zap_url,
+            zap_active_scan,
+            event_stream,
+            scan_id,
+            scan_id_header,
+            defectdojo_output,
+            evidence,
+            output_format,
+            bucket_by_tech,
             proxy,
             replay_codes,
             replay_proxy,
+            proxy_source,
+            tor_control,
             headers,
+            rotate_headers,
+            host_header,
+            login,
+            csrf,
             filter_size,
             filter_similar,
             filter_word_count,
             filter_line_count,
             filter_regex,
+            cluster_threshold,
             extract_links,
+            extract_forms,
+            extract_comments,
             parallel,
+            coordinate,
+            shard_size,
+            log_format,
+            thousands_separator,
+            byte_units,
             json,
+            error_records,
             queries,
             output,
             debug_log,
             extensions,
+            seed_from,
+            seed_file,
+            buckets,
+            presets,
+            crawl_wordlist,
+            fingerprint,
+            parse_listings,
+            discover_openapi,
+            graphql_probe,
+            source_maps,
+            find_secrets,
+            harvest,
+            mine_params,
+            check_artifacts,
+            check_headers,
+            cors_probe,
+            ws_probe,
+            auth_diff,
+            check_methods,
+            permute,
+            iis_shortnames,
+            redact_secrets,
+            no_product_wordlists,
+            track_changes,
+            save_config,
+            secret_patterns,
+            skip_listed_dirs,
+            technologies: Vec::new(),
+            suggested_extensions: None,
+            product_wordlists: None,
             insecure,
             dont_filter,
             redirects,
@@ -329,9 +903,16 @@ impl Banner {
             add_slash,
             no_recursion,
             rate_limit,
+            request_limit,
+            cooldown,
             scan_limit,
             time_limit,
             url_denylist,
+            deny_url_regex,
+            scope,
+            expect_found,
+            expect_absent,
+            stop_on_match,
             config: cfg,
             version: VERSION.to_string(),
             update_status: UpdateStatus::Unknown,
@@ -410,6 +991,85 @@ by Ben "epi" Risher {}                 ver: {}"#,
         Ok(())
     }
 
+    /// Requests each target and fingerprints the response, storing the identified technologies,
+    /// as well as any extensions they suggest, for display
+    ///
+    /// targets that can't be reached are skipped; connectivity is tested separately
+    pub async fn fingerprint_targets(
+        &mut self,
+        targets: &[String],
+        handles: Arc<Handles>,
+    ) -> Result<()> {
+        log::trace!("enter: fingerprint_targets({:?}, {:?})", targets, handles);
+
+        let mut identified = Vec::new();
+
+        for target in targets {
+            let technologies = match fingerprint_target(target, handles.clone()).await {
+                Ok(technologies) => technologies,
+                Err(e) => {
+                    log::warn!("Could not fingerprint {}: {}", target, e);
+                    continue;
+                }
+            };
+
+            if technologies.is_empty() {
+                continue;
+            }
+
+            let names: Vec<_> = technologies
+                .iter()
+                .map(|tech| format!("{} ({})", tech.name, tech.category))
+                .collect();
+
+            self.technologies.push(BannerEntry::new(
+                "🧬",
+                "Technologies",
+                &format!("{} => {}", target, names.join(", ")),
+            ));
+
+            identified.extend(technologies);
+        }
+
+        let extensions = suggested_extensions(&identified);
+
+        if !extensions.is_empty() {
+            self.suggested_extensions = Some(BannerEntry::new(
+                "💡",
+                "Suggested Extensions",
+                &format!(
+                    "[{}] (ex: -x {})",
+                    extensions.join(", "),
+                    extensions.join(" -x ")
+                ),
+            ));
+        }
+
+        if !handles.config.no_product_wordlists {
+            let mut products: Vec<String> = Vec::new();
+
+            for tech in &identified {
+                let words = product_wordlist(tech.name).len();
+                let product = format!("{} (+{} words)", tech.name, words);
+
+                if words > 0 && !products.contains(&product) {
+                    products.push(product);
+                }
+            }
+
+            if !products.is_empty() {
+                self.product_wordlists = Some(BannerEntry::new(
+                    "📚",
+                    "Product Wordlists",
+                    &products.join(", "),
+                ));
+            }
+        }
+
+        log::trace!("exit: fingerprint_targets");
+        Ok(())
+    }
+
     /// display the banner on Write writer
     pub fn print_to<W>(&self, mut writer: W, config: Arc<Configuration>) -> Result<()>
     where
@@ -426,8 +1086,40 @@ by Ben "epi" Risher {}                 ver: {}"#,
             writeln!(&mut writer, "{}", denied_url)?;
         }
 
+        for pattern in &self.deny_url_regex {
+            writeln!(&mut writer, "{}", pattern)?;
+        }
+
+        for rule in &self.scope {
+            writeln!(&mut writer, "{}", rule)?;
+        }
+
+        for path in &self.expect_found {
+            writeln!(&mut writer, "{}", path)?;
+        }
+
+        for path in &self.expect_absent {
+            writeln!(&mut writer, "{}", path)?;
+        }
+
+        for criterion in &self.stop_on_match {
+            writeln!(&mut writer, "{}", criterion)?;
+        }
+
         writeln!(&mut writer, "{}", self.threads)?;
-        writeln!(&mut writer, "{}", self.wordlist)?;
+
+        if !config.uses_presets_only() {
+            writeln!(&mut writer, "{}", self.wordlist)?;
+        }
+
+        if !config.presets.is_empty() {
+            writeln!(&mut writer, "{}", self.presets)?;
+        }
+
+        if config.crawl_wordlist > 0 {
+            writeln!(&mut writer, "{}", self.crawl_wordlist)?;
+        }
+
         writeln!(&mut writer, "{}", self.status_codes)?;
 
         if !config.filter_status.is_empty() {
@@ -448,6 +1140,14 @@ by Ben "epi" Risher {}                 ver: {}"#,
             writeln!(&mut writer, "{}", self.proxy)?;
         }
 
+        if let Some(source) = &self.proxy_source {
+            writeln!(&mut writer, "{}", source)?;
+        }
+
+        if !config.tor_control.is_empty() {
+            writeln!(&mut writer, "{}", self.tor_control)?;
+        }
+
         if !config.replay_proxy.is_empty() {
             // i include replay codes logic here because in config.rs, replay codes are set to the
             // value in status codes, meaning it's never empty
@@ -459,6 +1159,22 @@ by Ben "epi" Risher {}                 ver: {}"#,
             writeln!(&mut writer, "{}", header)?;
         }
 
+        for header in &self.rotate_headers {
+            writeln!(&mut writer, "{}", header)?;
+        }
+
+        if !config.host_header.is_empty() {
+            writeln!(&mut writer, "{}", self.host_header)?;
+        }
+
+        if config.login.is_some() {
+            writeln!(&mut writer, "{}", self.login)?;
+        }
+
+        if config.csrf.is_some() {
+            writeln!(&mut writer, "{}", self.csrf)?;
+        }
+
         for filter in &self.filter_size {
             writeln!(&mut writer, "{}", filter)?;
         }
@@ -479,14 +1195,30 @@ by Ben "epi" Risher {}                 ver: {}"#,
             writeln!(&mut writer, "{}", filter)?;
         }
 
+        if config.cluster_threshold > 0 {
+            writeln!(&mut writer, "{}", self.cluster_threshold)?;
+        }
+
         if config.extract_links {
             writeln!(&mut writer, "{}", self.extract_links)?;
         }
 
+        if config.extract_forms {
+            writeln!(&mut writer, "{}", self.extract_forms)?;
+        }
+
+        if config.extract_comments {
+            writeln!(&mut writer, "{}", self.extract_comments)?;
+        }
+
         if config.json {
             writeln!(&mut writer, "{}", self.json)?;
         }
 
+        if config.error_records {
+            writeln!(&mut writer, "{}", self.error_records)?;
+        }
+
         for query in &self.queries {
             writeln!(&mut writer, "{}", query)?;
         }
@@ -499,10 +1231,125 @@ by Ben "epi" Risher {}                 ver: {}"#,
             writeln!(&mut writer, "{}", self.debug_log)?;
         }
 
+        if !config.track_changes.is_empty() {
+            writeln!(&mut writer, "{}", self.track_changes)?;
+        }
+
+        if !config.save_config.is_empty() {
+            writeln!(&mut writer, "{}", self.save_config)?;
+        }
+
         if !config.extensions.is_empty() {
             writeln!(&mut writer, "{}", self.extensions)?;
         }
 
+        if !config.seed_from.is_empty() {
+            writeln!(&mut writer, "{}", self.seed_from)?;
+        }
+
+        if !config.seed_file.is_empty() {
+            writeln!(&mut writer, "{}", self.seed_file)?;
+        }
+
+        if !config.buckets.is_empty() {
+            writeln!(&mut writer, "{}", self.buckets)?;
+        }
+
+        if config.parse_listings {
+            writeln!(&mut writer, "{}", self.parse_listings)?;
+        }
+
+        if config.skip_listed_dirs {
+            writeln!(&mut writer, "{}", self.skip_listed_dirs)?;
+        }
+
+        if config.discover_openapi {
+            writeln!(&mut writer, "{}", self.discover_openapi)?;
+        }
+
+        if config.graphql_probe {
+            writeln!(&mut writer, "{}", self.graphql_probe)?;
+        }
+
+        if config.source_maps {
+            writeln!(&mut writer, "{}", self.source_maps)?;
+        }
+
+        if config.find_secrets {
+            writeln!(&mut writer, "{}", self.find_secrets)?;
+        }
+
+        if config.harvest {
+            writeln!(&mut writer, "{}", self.harvest)?;
+        }
+
+        if config.mine_params {
+            writeln!(&mut writer, "{}", self.mine_params)?;
+        }
+
+        if config.check_artifacts {
+            writeln!(&mut writer, "{}", self.check_artifacts)?;
+        }
+
+        if config.check_headers {
+            writeln!(&mut writer, "{}", self.check_headers)?;
+        }
+
+        if config.cors_probe {
+            writeln!(&mut writer, "{}", self.cors_probe)?;
+        }
+
+        if config.ws_probe {
+            writeln!(&mut writer, "{}", self.ws_probe)?;
+        }
+
+        if config.auth_diff {
+            writeln!(&mut writer, "{}", self.auth_diff)?;
+        }
+
+        if config.check_methods {
+            writeln!(&mut writer, "{}", self.check_methods)?;
+        }
+
+        if config.permute {
+            writeln!(&mut writer, "{}", self.permute)?;
+        }
+
+        if config.iis_shortnames {
+            writeln!(&mut writer, "{}", self.iis_shortnames)?;
+        }
+
+        if config.redact_secrets {
+            writeln!(&mut writer, "{}", self.redact_secrets)?;
+        }
+
+        if config.no_product_wordlists {
+            writeln!(&mut writer, "{}", self.no_product_wordlists)?;
+        }
+
+        for pattern in &self.secret_patterns {
+            writeln!(&mut writer, "{}", pattern)?;
+        }
+
+        if config.fingerprint {
+            writeln!(&mut writer, "{}", self.fingerprint)?;
+
+            for technology in &self.technologies {
+                writeln!(&mut writer, "{}", technology)?;
+            }
+
+            if config.extensions.is_empty() {
+                // only worth suggesting extensions when the user didn't pick their own
+                if let Some(suggestion) = &self.suggested_extensions {
+                    writeln!(&mut writer, "{}", suggestion)?;
+                }
+            }
+
+            if let Some(products) = &self.product_wordlists {
+                writeln!(&mut writer, "{}", products)?;
+            }
+        }
+
         if config.insecure {
             writeln!(&mut writer, "{}", self.insecure)?;
         }
@@ -513,6 +1360,87 @@ by Ben "epi" Risher {}                 ver: {}"#,
         if config.auto_tune {
             writeln!(&mut writer, "{}", self.auto_tune)?;
         }
+        if config.adaptive_threads {
+            writeln!(&mut writer, "{}", self.adaptive_threads)?;
+        }
+        if config.seen_urls != "exact" {
+            writeln!(&mut writer, "{}", self.seen_urls)?;
+        }
+        if config.wordlist_cache {
+            writeln!(&mut writer, "{}", self.wordlist_cache)?;
+        }
+        if config.dns_ttl > 0 {
+            writeln!(&mut writer, "{}", self.dns_ttl)?;
+        }
+        if config.max_response_size > 0 {
+            writeln!(&mut writer, "{}", self.max_response_size)?;
+        }
+        if config.head_only {
+            writeln!(&mut writer, "{}", self.head_only)?;
+        }
+        if config.response_time {
+            writeln!(&mut writer, "{}", self.response_time)?;
+        }
+        if config.streams_per_connection > 0 {
+            writeln!(&mut writer, "{}", self.streams_per_connection)?;
+        }
+        if config.memory_limit > 0 {
+            writeln!(&mut writer, "{}", self.memory_limit)?;
+        }
+        if config.adaptive_timeout > 0 {
+            writeln!(&mut writer, "{}", self.adaptive_timeout)?;
+        }
+        if config.throttle_bytes > 0 {
+            writeln!(&mut writer, "{}", self.throttle_bytes)?;
+        }
+        if config.async_workers > 0 {
+            writeln!(&mut writer, "{}", self.async_workers)?;
+        }
+        if config.blocking_threads > 0 {
+            writeln!(&mut writer, "{}", self.blocking_threads)?;
+        }
+        if !config.trace_output.is_empty() {
+            writeln!(&mut writer, "{}", self.trace_output)?;
+        }
+        if config.refresh_rate > 0 {
+            writeln!(&mut writer, "{}", self.refresh_rate)?;
+        }
+        if !config.checkpoint_interval.is_empty() {
+            writeln!(&mut writer, "{}", self.checkpoint_interval)?;
+        }
+        if config.checkpoint_requests > 0 {
+            writeln!(&mut writer, "{}", self.checkpoint_requests)?;
+        }
+        if !config.burp_output.is_empty() {
+            writeln!(&mut writer, "{}", self.burp_output)?;
+        }
+        if !config.zap_url.is_empty() {
+            writeln!(&mut writer, "{}", self.zap_url)?;
+        }
+        if config.zap_active_scan {
+            writeln!(&mut writer, "{}", self.zap_active_scan)?;
+        }
+        if !config.event_stream.is_empty() {
+            writeln!(&mut writer, "{}", self.event_stream)?;
+        }
+        if !config.scan_id.is_empty() {
+            writeln!(&mut writer, "{}", self.scan_id)?;
+        }
+        if !config.scan_id_header.is_empty() {
+            writeln!(&mut writer, "{}", self.scan_id_header)?;
+        }
+        if !config.defectdojo_output.is_empty() {
+            writeln!(&mut writer, "{}", self.defectdojo_output)?;
+        }
+        if !config.evidence_dir.is_empty() {
+            writeln!(&mut writer, "{}", self.evidence)?;
+        }
+        if config.output_format != "text" {
+            writeln!(&mut writer, "{}", self.output_format)?;
+        }
+        if config.bucket_by_tech {
+            writeln!(&mut writer, "{}", self.bucket_by_tech)?;
+        }
 
         if config.redirects {
             writeln!(&mut writer, "{}", self.redirects)?;
@@ -540,10 +1468,35 @@ by Ben "epi" Risher {}                 ver: {}"#,
             writeln!(&mut writer, "{}", self.parallel)?;
         }
 
+        if !config.coordinate.is_empty() {
+            writeln!(&mut writer, "{}", self.coordinate)?;
+            writeln!(&mut writer, "{}", self.shard_size)?;
+        }
+
+        if config.log_format != "text" {
+            writeln!(&mut writer, "{}", self.log_format)?;
+        }
+
+        if !config.thousands_separator.is_empty() {
+            writeln!(&mut writer, "{}", self.thousands_separator)?;
+        }
+
+        if config.byte_units != "bytes" {
+            writeln!(&mut writer, "{}", self.byte_units)?;
+        }
+
         if config.rate_limit > 0 {
             writeln!(&mut writer, "{}", self.rate_limit)?;
         }
 
+        if config.request_limit > 0 {
+            writeln!(&mut writer, "{}", self.request_limit)?;
+        }
+
+        if config.cooldown_after() > 0 {
+            writeln!(&mut writer, "{}", self.cooldown)?;
+        }
+
         if !config.time_limit.is_empty() {
             writeln!(&mut writer, "{}", self.time_limit)?;
         }
@@ -0,0 +1,294 @@
+//! measure feroxbuster's own performance (`feroxbuster bench`)
+//!
+//! a small http server is started on the loopback interface, and a scan with fixed settings is
+//! run against it; the server answers instantly, so the numbers reflect feroxbuster rather than a
+//! target or the network. the same arguments produce the same scan on every release, which makes
+//! the results comparable between releases on the same machine
+use std::{
+    convert::Infallible,
+    env, fs,
+    net::SocketAddr,
+    process,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result};
+use clap::ArgMatches;
+use futures::StreamExt;
+use hyper::{
+    service::{make_service_fn, service_fn},
+    Body, Request, Response, Server, StatusCode,
+};
+
+use crate::{
+    api::{ScanResult, Scanner},
+    config::Configuration,
+    numbers::NumberFormat,
+    parser, VERSION,
+};
+
+/// number of words scanned when `--requests` isn't given
+pub const DEFAULT_REQUESTS: usize = 50_000;
+
+/// number of concurrent requests when `--threads` isn't given; same as a normal scan's default
+pub const DEFAULT_THREADS: usize = 50;
+
+/// one in this many words exists on the benchmark server
+const FOUND_EVERY: usize = 100;
+
+/// the `index`th word of the benchmark's wordlist
+fn word(index: usize) -> String {
+    format!("bench{:07}", index)
+}
+
+/// whether the benchmark server has something at the given path
+fn exists(path: &str) -> bool {
+    path.trim_start_matches('/')
+        .strip_prefix("bench")
+        .and_then(|index| index.parse::<usize>().ok())
+        .map_or(false, |index| index % FOUND_EVERY == 0)
+}
+
+/// answer a request to the benchmark server; a small page for words that exist, a 404 otherwise
+async fn respond(request: Request<Body>) -> Result<Response<Body>, Infallible> {
+    let (status, body) = if exists(request.uri().path()) {
+        (StatusCode::OK, "<html><body>found</body></html>\n")
+    } else {
+        (
+            StatusCode::NOT_FOUND,
+            "<html><body>not found</body></html>\n",
+        )
+    };
+
+    let mut response = Response::new(Body::from(body));
+    *response.status_mut() = status;
+
+    Ok(response)
+}
+
+/// start the benchmark server on a free port of the loopback interface, returning its address
+fn start_server() -> Result<SocketAddr> {
+    let make_service = make_service_fn(|_| async { Ok::<_, Infallible>(service_fn(respond)) });
+
+    let server = Server::try_bind(&([127, 0, 0, 1], 0).into())?.serve(make_service);
+    let address = server.local_addr();
+
+    tokio::spawn(async move {
+        if let Err(e) = server.await {
+            log::error!("benchmark server stopped: {}", e);
+        }
+    });
+
+    Ok(address)
+}
+
+/// processor time used and peak memory of the whole process, including the benchmark server
+#[derive(Debug, Default, Clone, Copy)]
+struct Usage {
+    /// user + system processor time
+    cpu: Duration,
+
+    /// peak resident set size, in bytes
+    max_rss: u64,
+}
+
+impl Usage {
+    /// usage so far; `None` where the platform doesn't report it
+    #[cfg(unix)]
+    fn current() -> Option<Self> {
+        // safety: getrusage only writes to the struct it's given
+        let usage = unsafe {
+            let mut usage: libc::rusage = std::mem::zeroed();
+
+            if libc::getrusage(libc::RUSAGE_SELF, &mut usage) != 0 {
+                return None;
+            }
+
+            usage
+        };
+
+        let time = |time: libc::timeval| {
+            Duration::from_secs(time.tv_sec as u64) + Duration::from_micros(time.tv_usec as u64)
+        };
+
+        // ru_maxrss is in bytes on macOS, kilobytes everywhere else
+        let scale = if cfg!(target_os = "macos") { 1 } else { 1024 };
+
+        Some(Self {
+            cpu: time(usage.ru_utime) + time(usage.ru_stime),
+            max_rss: usage.ru_maxrss as u64 * scale,
+        })
+    }
+
+    /// usage so far; `None` where the platform doesn't report it
+    #[cfg(not(unix))]
+    fn current() -> Option<Self> {
+        None
+    }
+}
+
+/// Results of a benchmark run
+#[derive(Debug)]
+struct Report {
+    /// number of words in the benchmark's wordlist
+    words: usize,
+
+    /// concurrent requests
+    threads: usize,
+
+    /// responses reported by the scan
+    found: usize,
+
+    /// wall clock time of the scan
+    elapsed: Duration,
+
+    /// processor time and memory used during the scan, when available
+    usage: Option<Usage>,
+}
+
+/// run the benchmark scan and gather its results; `Scanner` statistics are left for the caller
+async fn bench(words: usize, threads: usize) -> Result<(Report, Scanner)> {
+    let address = start_server()?;
+
+    // the wordlist is written to disk so that loading it is part of the measurement, like it is
+    // for a normal scan
+    let wordlist = env::temp_dir().join(format!("feroxbuster-bench-{}.txt", process::id()));
+    let contents: String = (0..words).map(|index| word(index) + "\n").collect();
+    fs::write(&wordlist, contents)
+        .with_context(|| format!("Could not write {}", wordlist.display()))?;
+
+    let config = Configuration::builder()
+        .target_url(&format!("http://{}/", address))
+        .wordlist(&wordlist.to_string_lossy())
+        .threads(threads)
+        .no_recursion(true)
+        .build();
+
+    let config = match config {
+        Ok(config) => config,
+        Err(e) => {
+            fs::remove_file(&wordlist).unwrap_or_default();
+            return Err(e);
+        }
+    };
+
+    let scanner = Scanner::new(config);
+
+    let before = Usage::current();
+    let start = Instant::now();
+
+    let results = scanner.scan().await;
+    fs::remove_file(&wordlist).unwrap_or_default();
+
+    let found = results?
+        .filter(|result| futures::future::ready(matches!(result, ScanResult::Response(_))))
+        .count()
+        .await;
+
+    let elapsed = start.elapsed();
+
+    let usage = match (before, Usage::current()) {
+        (Some(before), Some(after)) => Some(Usage {
+            cpu: after.cpu.saturating_sub(before.cpu),
+            max_rss: after.max_rss,
+        }),
+        _ => None,
+    };
+
+    let report = Report {
+        words,
+        threads,
+        found,
+        elapsed,
+        usage,
+    };
+
+    Ok((report, scanner))
+}
+
+/// Entry point for `feroxbuster bench`; runs the benchmark and prints its results to stdout
+pub fn run(subcommand: &ArgMatches) -> Result<()> {
+    let words = match subcommand.value_of("requests") {
+        Some(requests) => parser::parse_count(requests).map_err(anyhow::Error::msg)? as usize,
+        None => DEFAULT_REQUESTS,
+    };
+
+    let threads = match subcommand.value_of("threads") {
+        Some(threads) => threads.parse::<usize>()?.max(1),
+        None => DEFAULT_THREADS,
+    };
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    eprintln!(
+        "benchmarking feroxbuster {}: {} words, {} threads",
+        VERSION, words, threads
+    );
+
+    let (report, scanner) = runtime.block_on(bench(words, threads))?;
+
+    let numbers = NumberFormat::new(",", "iec", "");
+    let seconds = report.elapsed.as_secs_f64();
+    let stats = scanner.stats().unwrap_or_default();
+
+    println!("feroxbuster {} benchmark", VERSION);
+    println!(
+        "Scan: {} words | {} threads | {} found | {:.2}s",
+        numbers.count(report.words as u64),
+        report.threads,
+        numbers.count(report.found as u64),
+        seconds
+    );
+
+    let per_second = if seconds > 0.0 {
+        stats.requests() as f64 / seconds
+    } else {
+        0.0
+    };
+
+    println!("Throughput: {} req/s", numbers.count(per_second as u64));
+
+    match report.usage {
+        Some(usage) => println!(
+            "CPU: {:.2}s ({:.0}% of one core) | Peak memory: {}",
+            usage.cpu.as_secs_f64(),
+            usage.cpu.as_secs_f64() / seconds.max(f64::EPSILON) * 100.0,
+            numbers.bytes(usage.max_rss)
+        ),
+        None => println!("CPU: unavailable | Peak memory: unavailable"),
+    }
+
+    print!("{}", stats.summary(&numbers));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// one in every FOUND_EVERY words exists on the benchmark server, nothing else does
+    fn benchmark_server_paths() {
+        assert!(exists(&format!("/{}", word(0))));
+        assert!(exists(&format!("/{}", word(FOUND_EVERY))));
+        assert!(!exists(&format!("/{}", word(1))));
+        assert!(!exists("/index.html"));
+        assert!(!exists("/benchmark"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// a small benchmark scans every word and reports each one that exists
+    async fn bench_scans_every_word() {
+        let (report, scanner) = bench(1_000, 10).await.unwrap();
+
+        assert_eq!(report.words, 1_000);
+        assert_eq!(report.found, 1_000 / FOUND_EVERY);
+
+        let stats = scanner.stats().unwrap();
+        assert!(stats.requests() >= 1_000);
+        assert_eq!(stats.errors(), 0);
+    }
+}
@@ -0,0 +1,472 @@
+//! look for cloud storage buckets named after a company (--buckets), reporting the ones that
+//! exist and, above all, the ones that anyone can list
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use futures::{stream, StreamExt};
+use reqwest::{Client, Response, StatusCode, Url};
+use serde_json::json;
+
+use crate::{
+    event_handlers::{
+        Command::{self, AddError, AddStatus},
+        Handles,
+    },
+    finding::FeroxFinding,
+    scanner::build_a_bucket,
+    send_command,
+    statistics::StatError,
+    utils::load_wordlist,
+};
+
+/// separators used to join a company name and a word into a bucket name
+const SEPARATORS: [&str; 3] = ["-", ".", ""];
+
+/// Cloud storage services whose buckets are checked
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum Provider {
+    /// Amazon S3
+    Aws,
+
+    /// Google Cloud Storage
+    Gcp,
+
+    /// Azure Blob Storage; buckets are containers within the company's storage account
+    Azure,
+}
+
+/// implementation of Provider
+impl Provider {
+    /// name used in findings
+    fn as_str(&self) -> &'static str {
+        match self {
+            Provider::Aws => "aws",
+            Provider::Gcp => "gcp",
+            Provider::Azure => "azure",
+        }
+    }
+
+    /// whether the given bucket name is valid for this provider; azure containers can't have dots
+    /// or consecutive hyphens
+    fn allows(&self, name: &str) -> bool {
+        match self {
+            Provider::Aws | Provider::Gcp => true,
+            Provider::Azure => !name.contains('.') && !name.contains("--"),
+        }
+    }
+
+    /// url that lists the contents of the given bucket, when listing is allowed
+    ///
+    /// virtual-hosted s3 urls don't work with dotted bucket names over https (the certificate only
+    /// covers one level of subdomain), so those are requested path-style
+    fn url(&self, name: &str, account: &str) -> String {
+        match self {
+            Provider::Aws if name.contains('.') => format!("https://s3.amazonaws.com/{}/", name),
+            Provider::Aws => format!("https://{}.s3.amazonaws.com/", name),
+            Provider::Gcp => format!("https://storage.googleapis.com/{}/", name),
+            Provider::Azure => format!(
+                "https://{}.blob.core.windows.net/{}?restype=container&comp=list",
+                account, name
+            ),
+        }
+    }
+
+    /// root of the given azure storage account, used to check whether it exists at all
+    fn account_url(account: &str) -> String {
+        format!("https://{}.blob.core.windows.net/", account)
+    }
+
+    /// marker that only appears in the xml of a successful bucket listing
+    fn listing_marker(&self) -> &'static str {
+        match self {
+            Provider::Aws | Provider::Gcp => "<ListBucketResult",
+            Provider::Azure => "<EnumerationResults",
+        }
+    }
+}
+
+/// What a bucket's response says about it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(crate) enum BucketState {
+    /// the bucket exists, but its contents can't be listed anonymously
+    Private,
+
+    /// anyone can list the bucket's contents
+    Listable,
+}
+
+/// Decide whether a bucket exists, and whether it can be listed, from the response to a listing
+/// request; `None` means it doesn't exist (or can't be told apart from a missing bucket)
+pub(crate) fn classify(provider: Provider, status: StatusCode, body: &str) -> Option<BucketState> {
+    if status.is_success() && body.contains(provider.listing_marker()) {
+        return Some(BucketState::Listable);
+    }
+
+    match (provider, status) {
+        // s3 redirects requests for buckets that live in another region
+        (Provider::Aws, StatusCode::MOVED_PERMANENTLY) => Some(BucketState::Private),
+        (_, StatusCode::FORBIDDEN) | (Provider::Gcp, StatusCode::UNAUTHORIZED) => {
+            Some(BucketState::Private)
+        }
+        // the container exists, but the account doesn't allow anonymous access at all
+        (Provider::Azure, StatusCode::CONFLICT) => Some(BucketState::Private),
+        _ => None,
+    }
+}
+
+/// Lowercase the company name and drop anything that can't be part of a bucket name
+fn normalize(company: &str) -> String {
+    company
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '-' || *c == '.')
+        .collect()
+}
+
+/// whether the given name follows the rules shared by s3, gcs, and azure containers: 3 to 63
+/// lowercase letters, digits, hyphens, and dots, starting and ending with a letter or digit
+fn is_valid_name(name: &str) -> bool {
+    (3..=63).contains(&name.len())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '.')
+        && name.starts_with(|c: char| c.is_ascii_alphanumeric())
+        && name.ends_with(|c: char| c.is_ascii_alphanumeric())
+        && !name.contains("..")
+}
+
+/// Bucket names to try for the given company: the name itself, followed by the name joined to
+/// each word, on either side, with each of `SEPARATORS`
+///
+/// ex: ellingson + backup -> ellingson, ellingson-backup, backup-ellingson, ellingson.backup, ...
+pub(crate) fn candidates(company: &str, words: &[String]) -> Vec<String> {
+    let company = normalize(company);
+    let mut names = vec![company.clone()];
+
+    for word in words {
+        let word = normalize(word);
+
+        if word.is_empty() {
+            continue;
+        }
+
+        for separator in SEPARATORS.iter() {
+            names.push(format!("{}{}{}", company, separator, word));
+            names.push(format!("{}{}{}", word, separator, company));
+        }
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    names.retain(|name| is_valid_name(name) && seen.insert(name.clone()));
+    names
+}
+
+/// storage account name for the given company; 3 to 24 lowercase letters and digits
+fn azure_account(company: &str) -> Option<String> {
+    let account: String = normalize(company)
+        .chars()
+        .filter(char::is_ascii_alphanumeric)
+        .take(24)
+        .collect();
+
+    if account.len() < 3 {
+        None
+    } else {
+        Some(account)
+    }
+}
+
+/// Request the given url from a cloud provider, counting it in the scan's statistics
+///
+/// `client` is the configuration's `third_party_client`; the providers are sent none of the -H
+/// headers, session, or scan id meant for the target
+async fn request(client: &Client, url: &Url, handles: &Handles) -> Result<Response> {
+    match client.get(url.clone()).send().await {
+        Ok(response) => {
+            send_command!(handles.stats, AddStatus(response.status()));
+            Ok(response)
+        }
+        Err(e) => {
+            send_command!(handles.stats, AddError(StatError::from_request_error(&e)));
+            bail!(e)
+        }
+    }
+}
+
+/// Request the listing url of a single bucket and return a finding when the bucket exists
+pub(crate) async fn check_bucket(
+    provider: Provider,
+    name: &str,
+    url: &str,
+    client: &Client,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: check_bucket({:?}, {})", provider, url);
+
+    let response = request(client, &Url::parse(url)?, &handles).await?;
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+
+    let finding = classify(provider, status, &body).map(|state| {
+        let (severity, summary) = match state {
+            BucketState::Listable => ("high", "listable"),
+            BucketState::Private => ("info", "exists"),
+        };
+
+        FeroxFinding::new(
+            "bucket",
+            url,
+            &format!(
+                "[{}] {} bucket {} {}",
+                severity,
+                provider.as_str(),
+                name,
+                summary
+            ),
+            json!({
+                "severity": severity,
+                "provider": provider.as_str(),
+                "bucket": name,
+                "listable": state == BucketState::Listable,
+                "status": status.as_u16(),
+            }),
+        )
+    });
+
+    log::trace!("exit: check_bucket -> {:?}", finding);
+    Ok(finding)
+}
+
+/// Check every candidate bucket for every company given to --buckets against every provider,
+/// reporting the ones that exist as findings
+///
+/// requests honor --threads, --rate-limit, and --proxy, and are counted like any other request,
+/// but carry none of the -H headers
+pub async fn scan_buckets(handles: Arc<Handles>) -> Result<()> {
+    log::trace!("enter: scan_buckets({:?})", handles);
+
+    let words = load_wordlist(&handles.config)?;
+    let client = handles.config.third_party_client()?;
+
+    let limiter = if handles.config.rate_limit > 0 {
+        Some(Arc::new(build_a_bucket(handles.config.rate_limit)?))
+    } else {
+        None
+    };
+
+    let mut checks = Vec::new();
+
+    for company in &handles.config.buckets {
+        let names = candidates(company, &words);
+
+        log::info!("checking {} bucket names for {}", names.len(), company);
+
+        // containers are only worth checking when the storage account resolves; otherwise
+        // every one of them would be a dns error
+        let account = match azure_account(company) {
+            Some(account) => {
+                let url = Url::parse(&Provider::account_url(&account))?;
+
+                match request(&client, &url, &handles).await {
+                    Ok(_) => Some(account),
+                    Err(_) => {
+                        log::info!("no azure storage account named {}", account);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
+        for name in names {
+            for provider in [Provider::Aws, Provider::Gcp, Provider::Azure].iter() {
+                let account = match (provider, &account) {
+                    (Provider::Azure, None) => continue,
+                    (Provider::Azure, Some(account)) => account.as_str(),
+                    _ => "",
+                };
+
+                if provider.allows(&name) {
+                    checks.push((*provider, provider.url(&name, account), name.clone()));
+                }
+            }
+        }
+    }
+
+    let mut results = stream::iter(checks)
+        .map(|(provider, url, name)| {
+            let handles = handles.clone();
+            let limiter = limiter.clone();
+            let client = client.clone();
+
+            async move {
+                if let Some(limiter) = limiter {
+                    limiter.acquire_one().await?;
+                }
+
+                check_bucket(provider, &name, &url, &client, handles).await
+            }
+        })
+        .buffer_unordered(handles.config.threads);
+
+    while let Some(result) = results.next().await {
+        match result {
+            Ok(Some(finding)) => {
+                handles
+                    .output
+                    .send(Command::ReportFinding(Box::new(finding)))?;
+            }
+            Ok(None) => {}
+            Err(e) => log::debug!("Could not check bucket: {}", e),
+        }
+    }
+
+    log::trace!("exit: scan_buckets");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Configuration, scan_manager::FeroxScans};
+    use httpmock::{Method::GET, MockServer};
+
+    #[test]
+    /// the company name is tried alone and joined to each word on either side
+    fn candidates_permute_company_and_words() {
+        let words = vec!["backup".to_string(), "Dev".to_string(), "x".to_string()];
+        let names = candidates("Ellingson", &words);
+
+        assert_eq!(names[0], "ellingson");
+        assert!(names.contains(&"ellingson-backup".to_string()));
+        assert!(names.contains(&"backup-ellingson".to_string()));
+        assert!(names.contains(&"ellingson.dev".to_string()));
+        assert!(names.contains(&"devellingson".to_string()));
+        assert!(names.contains(&"ellingsonx".to_string()));
+        assert_eq!(names.len(), 1 + 3 * 6);
+    }
+
+    #[test]
+    /// names that no provider would accept are dropped
+    fn candidates_skip_invalid_names() {
+        let words = vec!["-".to_string(), "a".repeat(60)];
+        let names = candidates("ab", &words);
+
+        assert!(names.iter().all(|name| is_valid_name(name)));
+        assert!(!names.contains(&"ab".to_string()));
+        assert!(!names.contains(&"ab--".to_string()));
+    }
+
+    #[test]
+    /// dotted names are requested path-style from s3, and never from azure
+    fn provider_urls() {
+        assert_eq!(
+            Provider::Aws.url("ellingson-dev", ""),
+            "https://ellingson-dev.s3.amazonaws.com/"
+        );
+        assert_eq!(
+            Provider::Aws.url("ellingson.dev", ""),
+            "https://s3.amazonaws.com/ellingson.dev/"
+        );
+        assert_eq!(
+            Provider::Gcp.url("ellingson-dev", ""),
+            "https://storage.googleapis.com/ellingson-dev/"
+        );
+        assert_eq!(
+            Provider::Azure.url("dev", "ellingson"),
+            "https://ellingson.blob.core.windows.net/dev?restype=container&comp=list"
+        );
+        assert!(!Provider::Azure.allows("ellingson.dev"));
+        assert_eq!(
+            azure_account("Ellingson Mineral Company").unwrap(),
+            "ellingsonmineralcompany"
+        );
+    }
+
+    #[test]
+    /// listings, access denied, and missing buckets are told apart
+    fn classify_reads_status_and_body() {
+        let listing = r#"<?xml version="1.0"?><ListBucketResult><Name>x</Name></ListBucketResult>"#;
+
+        assert_eq!(
+            classify(Provider::Aws, StatusCode::OK, listing),
+            Some(BucketState::Listable)
+        );
+        assert_eq!(
+            classify(Provider::Gcp, StatusCode::UNAUTHORIZED, ""),
+            Some(BucketState::Private)
+        );
+        assert_eq!(
+            classify(Provider::Aws, StatusCode::MOVED_PERMANENTLY, ""),
+            Some(BucketState::Private)
+        );
+        assert_eq!(classify(Provider::Aws, StatusCode::NOT_FOUND, ""), None);
+        assert_eq!(classify(Provider::Azure, StatusCode::OK, listing), None);
+        assert_eq!(
+            classify(Provider::Azure, StatusCode::OK, "<EnumerationResults>"),
+            Some(BucketState::Listable)
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// existing buckets are reported with their severity, missing ones aren't reported at all;
+    /// the -H headers meant for the target are never sent to the provider
+    async fn check_bucket_reports_existing_buckets() {
+        let srv = MockServer::start();
+
+        let leaked = srv.mock(|when, then| {
+            when.method(GET).header_exists("authorization");
+            then.status(200)
+                .body("<ListBucketResult><Contents><Key>leaked.txt</Key></Contents>");
+        });
+
+        let listable = srv.mock(|when, then| {
+            when.method(GET).path("/ellingson-backup/");
+            then.status(200)
+                .body("<ListBucketResult><Contents><Key>garbage.txt</Key></Contents>");
+        });
+
+        let missing = srv.mock(|when, then| {
+            when.method(GET).path("/ellingson-nope/");
+            then.status(404)
+                .body("<Error><Code>NoSuchBucket</Code></Error>");
+        });
+
+        let mut config = Configuration::new().unwrap();
+        config
+            .headers
+            .insert("Authorization".to_string(), "Bearer s3cr3t".to_string());
+
+        let client = config.third_party_client().unwrap();
+        let config = Arc::new(config);
+        let scans = Arc::new(FeroxScans::default());
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        let url = srv.url("/ellingson-backup/");
+        let finding = check_bucket(
+            Provider::Gcp,
+            "ellingson-backup",
+            &url,
+            &client,
+            handles.clone(),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(finding.category(), "bucket");
+        assert_eq!(finding.url(), url);
+        assert_eq!(finding.details()["severity"], "high");
+        assert_eq!(finding.details()["listable"], true);
+
+        let url = srv.url("/ellingson-nope/");
+        let finding = check_bucket(Provider::Gcp, "ellingson-nope", &url, &client, handles)
+            .await
+            .unwrap();
+
+        assert!(finding.is_none());
+        assert_eq!(listable.hits(), 1);
+        assert_eq!(missing.hits(), 1);
+        assert_eq!(leaked.hits(), 0);
+    }
+}
@@ -0,0 +1,536 @@
+//! write reported responses as Burp Suite site map items (--burp-output)
+//!
+//! while --burp-output or --evidence-dir is used, each request is recorded as it's sent (see
+//! `record`) and travels with its response, so that what's written is the request that actually
+//! went out, per-request headers included
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+    sync::Arc,
+};
+
+use anyhow::{Context, Result};
+use reqwest::{
+    header::{CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE, TRANSFER_ENCODING},
+    Request, RequestBuilder,
+};
+
+use crate::{
+    config::{is_sensitive, Configuration},
+    response::FeroxResponse,
+    utils::{civil_date, fmt_err, unix_timestamp},
+};
+
+/// everything that comes before the first item
+const HEADER: &str = "<?xml version=\"1.0\"?>\n<items burpVersion=\"feroxbuster\" exportTime=\"";
+
+/// closes the document; rewritten after every item so that the file is always valid xml
+const FOOTER: &str = "</items>\n";
+
+/// abbreviated day names, starting with thursday (the unix epoch)
+const DAYS: [&str; 7] = ["Thu", "Fri", "Sat", "Sun", "Mon", "Tue", "Wed"];
+
+/// abbreviated month names
+const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Writes each response it's given to a file in the xml format used by Burp Suite's
+/// "Save items", with the request and response base64 encoded
+///
+/// the closing `</items>` tag is written after every item and then overwritten by the next one,
+/// so a scan that's killed midway still leaves an importable file behind
+#[derive(Debug)]
+pub struct BurpWriter {
+    /// the --burp-output file
+    file: BufWriter<File>,
+
+    /// user-agent sent with every request
+    user_agent: String,
+
+    /// additional headers sent with every request (-H|--headers), sorted by name
+    headers: Vec<(String, String)>,
+}
+
+/// implementation of BurpWriter
+impl BurpWriter {
+    /// create (or truncate) the given file and write the start of the document to it
+    pub fn create(filename: &str, config: &Configuration) -> Result<Self> {
+        let file = File::create(filename)
+            .with_context(|| fmt_err(&format!("Could not create {}", filename)))?;
+
+        let mut headers: Vec<_> = config
+            .headers
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+        headers.sort();
+
+        let mut writer = Self {
+            file: BufWriter::new(file),
+            user_agent: config.user_agent.clone(),
+            headers,
+        };
+
+        writeln!(writer.file, "{}{}\">", HEADER, burp_time(unix_timestamp()))?;
+        writer.close()?;
+
+        Ok(writer)
+    }
+
+    /// append the given response as an `<item>`
+    pub fn write(&mut self, response: &FeroxResponse) -> Result<()> {
+        let item = self.item(response, unix_timestamp());
+
+        // overwrite the footer written after the previous item
+        self.file.seek(SeekFrom::End(-(FOOTER.len() as i64)))?;
+        self.file.write_all(item.as_bytes())?;
+        self.close()
+    }
+
+    /// write the closing tag and flush everything to disk
+    fn close(&mut self) -> Result<()> {
+        self.file.write_all(FOOTER.as_bytes())?;
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// a single `<item>` describing the given response
+    fn item(&self, response: &FeroxResponse, timestamp: u64) -> String {
+        let url = response.url();
+        let request = raw_request(response, &self.user_agent, &self.headers);
+        let raw_response = raw_response(response);
+
+        let extension = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .and_then(|last| last.rfind('.').map(|index| &last[index + 1..]))
+            .filter(|extension| !extension.is_empty())
+            .unwrap_or("null");
+
+        let port = url.port_or_known_default().unwrap_or_default();
+
+        format!(
+            "  <item>\n    <time>{}</time>\n    <url>{}</url>\n    <host ip=\"\">{}</host>\n    <port>{}</port>\n    <protocol>{}</protocol>\n    <method>{}</method>\n    <path>{}</path>\n    <extension>{}</extension>\n    <request base64=\"true\">{}</request>\n    <status>{}</status>\n    <responselength>{}</responselength>\n    <mimetype>{}</mimetype>\n    <response base64=\"true\">{}</response>\n    <comment></comment>\n  </item>\n",
+            burp_time(timestamp),
+            cdata(url.as_str()),
+            escape(url.host_str().unwrap_or_default()),
+            port,
+            escape(url.scheme()),
+            cdata(method(response)),
+            cdata(&target(response)),
+            escape(extension),
+            cdata(&base64::encode(&request)),
+            response.status().as_u16(),
+            raw_response.len(),
+            mime_type(response),
+            cdata(&base64::encode(&raw_response)),
+        )
+    }
+}
+
+/// A request as it was sent, rendered the way it looked on the wire; attached to the response it
+/// got, and kept by the `FeroxResponse` made from it
+#[derive(Debug, Clone)]
+pub(crate) struct SentRequest(pub(crate) Arc<String>);
+
+/// The headers that clients add to every request on their own, and so never show up on the
+/// requests themselves
+///
+/// each scan's `RequestContext` has its own, built from the scan's configuration
+#[derive(Debug)]
+pub struct Recorder {
+    /// user-agent sent with every request
+    user_agent: String,
+
+    /// additional headers sent with every request (-H|--headers), sorted by name
+    headers: Vec<(String, String)>,
+}
+
+impl Recorder {
+    /// The given request as it'll look on the wire; headers set on the request itself replace
+    /// the client's headers of the same name
+    fn render(&self, request: &Request, credentials: bool) -> String {
+        let url = request.url();
+
+        let host = match url.port() {
+            Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+            None => url.host_str().unwrap_or_default().to_string(),
+        };
+
+        let target = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+
+        let mut headers = vec![
+            ("User-Agent".to_string(), self.user_agent.clone()),
+            ("Accept".to_string(), "*/*".to_string()),
+        ];
+
+        headers.extend(
+            self.headers
+                .iter()
+                .filter(|(name, _)| credentials || !is_sensitive(name))
+                .cloned(),
+        );
+
+        headers.retain(|(name, _)| !request.headers().contains_key(name.as_str()));
+
+        headers.extend(request.headers().iter().map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        }));
+
+        let body = request
+            .body()
+            .and_then(|body| body.as_bytes())
+            .map(|body| String::from_utf8_lossy(body).into_owned())
+            .unwrap_or_default();
+
+        if !body.is_empty() && !request.headers().contains_key(CONTENT_LENGTH) {
+            headers.push(("Content-Length".to_string(), body.len().to_string()));
+        }
+
+        let mut raw = format!(
+            "{} {} HTTP/1.1\r\nHost: {}\r\n",
+            request.method(),
+            target,
+            host
+        );
+
+        for (name, value) in headers {
+            raw.push_str(&format!("{}: {}\r\n", name, value));
+        }
+
+        raw.push_str("\r\n");
+        raw.push_str(&body);
+
+        raw
+    }
+}
+
+/// Recorder for the requests sent with the given configuration; `None` unless --burp-output or
+/// --evidence-dir is used
+pub fn recorder(config: &Configuration) -> Option<Recorder> {
+    if config.burp_output.is_empty() && config.evidence_dir.is_empty() {
+        return None;
+    }
+
+    let mut headers: Vec<_> = config
+        .headers
+        .iter()
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+    headers.sort();
+
+    Some(Recorder {
+        user_agent: config.user_agent.clone(),
+        headers,
+    })
+}
+
+/// The given request as it'll look on the wire, or `None` when requests aren't being recorded
+/// (`recorder` is `None`)
+///
+/// `credentials` is false for requests made with the client that leaves them out (--auth-diff)
+pub(crate) fn record(
+    recorder: Option<&Recorder>,
+    request: &RequestBuilder,
+    credentials: bool,
+) -> Option<SentRequest> {
+    let recorder = recorder?;
+    let request = request.try_clone()?.build().ok()?;
+
+    Some(SentRequest(Arc::new(
+        recorder.render(&request, credentials),
+    )))
+}
+
+/// the request that was sent for the given response, as it would've looked on the wire; the
+/// recorded request when there is one, otherwise rebuilt with the given user-agent and
+/// additional headers
+pub(crate) fn raw_request(
+    response: &FeroxResponse,
+    user_agent: &str,
+    headers: &[(String, String)],
+) -> String {
+    if let Some(sent) = response.sent_request() {
+        return sent.to_string();
+    }
+
+    let url = response.url();
+
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+
+    let mut request = format!(
+        "{} {} HTTP/1.1\r\nHost: {}\r\nUser-Agent: {}\r\nAccept: */*\r\n",
+        method(response),
+        target(response),
+        host,
+        user_agent
+    );
+
+    for (name, value) in headers {
+        request.push_str(&format!("{}: {}\r\n", name, value));
+    }
+
+    request.push_str("\r\n");
+    request
+}
+
+/// the given response, as it would've looked on the wire
+///
+/// bodies have already been decompressed, so content-encoding and transfer-encoding are left
+/// out and content-length matches the body that's included
+pub(crate) fn raw_response(response: &FeroxResponse) -> String {
+    let status = response.status();
+
+    let mut raw = format!(
+        "HTTP/1.1 {} {}\r\n",
+        status.as_str(),
+        status.canonical_reason().unwrap_or_default()
+    );
+
+    for (name, value) in response.headers() {
+        if name == CONTENT_ENCODING || name == TRANSFER_ENCODING || name == CONTENT_LENGTH {
+            continue;
+        }
+
+        raw.push_str(&format!(
+            "{}: {}\r\n",
+            name,
+            String::from_utf8_lossy(value.as_bytes())
+        ));
+    }
+
+    raw.push_str(&format!(
+        "Content-Length: {}\r\n\r\n{}",
+        response.text().len(),
+        response.text()
+    ));
+    raw
+}
+
+/// method of the request that was sent for the given response
+pub(crate) fn method(response: &FeroxResponse) -> &'static str {
+    if response.headers_only() {
+        // --head-only skipped the body
+        "HEAD"
+    } else {
+        "GET"
+    }
+}
+
+/// path and query of the given response's url
+fn target(response: &FeroxResponse) -> String {
+    let url = response.url();
+
+    match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    }
+}
+
+/// format a unix timestamp the way Burp does, i.e. `Mon Oct 16 10:00:00 UTC 2026`
+fn burp_time(timestamp: u64) -> String {
+    let days = timestamp / 86400;
+    let seconds = timestamp % 86400;
+    let (year, month, day) = civil_date(timestamp);
+
+    format!(
+        "{} {} {:02} {:02}:{:02}:{:02} UTC {}",
+        DAYS[(days % 7) as usize],
+        MONTHS[(month - 1) as usize],
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        year
+    )
+}
+
+/// the broad category Burp shows in its MIME type column, based on the content-type header
+fn mime_type(response: &FeroxResponse) -> &'static str {
+    let content_type = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_lowercase();
+
+    if content_type.is_empty() {
+        ""
+    } else if content_type.contains("html") {
+        "HTML"
+    } else if content_type.contains("json") {
+        "JSON"
+    } else if content_type.contains("javascript") || content_type.contains("ecmascript") {
+        "script"
+    } else if content_type.contains("css") {
+        "CSS"
+    } else if content_type.contains("xml") {
+        "XML"
+    } else if content_type.starts_with("image/") {
+        "image"
+    } else if content_type.starts_with("text/") {
+        "text"
+    } else {
+        "app"
+    }
+}
+
+/// wrap the given value in a CDATA section, splitting any `]]>` it contains across two sections
+fn cdata(value: &str) -> String {
+    format!("<![CDATA[{}]]>", value.replace("]]>", "]]]]><![CDATA[>"))
+}
+
+/// escape the characters that can't appear as-is in xml text
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{collections::HashMap, fs};
+
+    #[test]
+    /// timestamps are formatted the way burp formats them
+    fn burp_time_formats_like_burp() {
+        assert_eq!(burp_time(0), "Thu Jan 01 00:00:00 UTC 1970");
+        assert_eq!(burp_time(951_782_400), "Tue Feb 29 00:00:00 UTC 2000");
+        assert_eq!(burp_time(1_792_145_045), "Fri Oct 16 10:04:05 UTC 2026");
+    }
+
+    #[test]
+    /// cdata sections can't be closed early by the value they wrap
+    fn cdata_splits_closing_sequence() {
+        assert_eq!(cdata("a]]>b"), "<![CDATA[a]]]]><![CDATA[>b]]>");
+        assert_eq!(escape("<a & \"b\">"), "&lt;a &amp; &quot;b&quot;&gt;");
+    }
+
+    #[test]
+    /// every response written is a complete item with its request and response base64 encoded,
+    /// and the file is a complete document after each one
+    fn burp_writer_writes_complete_items() {
+        let directory = tempfile::tempdir().unwrap();
+        let filename = directory.path().join("burp.xml");
+        let filename = filename.to_str().unwrap();
+
+        let mut headers = HashMap::new();
+        headers.insert(String::from("Cookie"), String::from("session=1"));
+
+        let config = Configuration {
+            user_agent: String::from("feroxbuster/test"),
+            headers,
+            ..Default::default()
+        };
+
+        let mut writer = BurpWriter::create(filename, &config).unwrap();
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost:8080/admin/login.php?next=1");
+        response.set_text("<html>login</html>");
+        writer.write(&response).unwrap();
+
+        let contents = fs::read_to_string(filename).unwrap();
+        assert!(contents.starts_with("<?xml version=\"1.0\"?>\n<items burpVersion="));
+        assert!(contents.ends_with("</item>\n</items>\n"));
+        assert_eq!(contents.matches("</items>").count(), 1);
+        assert!(contents
+            .contains("<url><![CDATA[http://localhost:8080/admin/login.php?next=1]]></url>"));
+        assert!(contents.contains("<host ip=\"\">localhost</host>"));
+        assert!(contents.contains("<port>8080</port>"));
+        assert!(contents.contains("<path><![CDATA[/admin/login.php?next=1]]></path>"));
+        assert!(contents.contains("<extension>php</extension>"));
+
+        let request = base64::encode(
+            "GET /admin/login.php?next=1 HTTP/1.1\r\nHost: localhost:8080\r\nUser-Agent: feroxbuster/test\r\nAccept: */*\r\nCookie: session=1\r\n\r\n",
+        );
+        assert!(contents.contains(&format!("<request base64=\"true\"><![CDATA[{}]]>", request)));
+
+        let raw_response =
+            base64::encode("HTTP/1.1 200 OK\r\nContent-Length: 18\r\n\r\n<html>login</html>");
+        assert!(contents.contains(&format!(
+            "<response base64=\"true\"><![CDATA[{}]]>",
+            raw_response
+        )));
+
+        response.set_url("http://localhost:8080/admin/");
+        writer.write(&response).unwrap();
+
+        let contents = fs::read_to_string(filename).unwrap();
+        assert_eq!(contents.matches("<item>").count(), 2);
+        assert_eq!(contents.matches("</items>").count(), 1);
+        assert!(contents.contains("<extension>null</extension>"));
+
+        // a recorded request is written as it was sent
+        response.set_sent_request(
+            "GET /admin/ HTTP/1.1\r\nHost: localhost:8080\r\nX-Scan-Id: 1\r\n\r\n",
+        );
+        writer.write(&response).unwrap();
+
+        let request = base64::encode(response.sent_request().unwrap());
+        let contents = fs::read_to_string(filename).unwrap();
+        assert!(contents.contains(&format!("<request base64=\"true\"><![CDATA[{}]]>", request)));
+    }
+
+    #[test]
+    /// recorded requests carry the client's headers, unless the request replaces them, along
+    /// with everything set on the request itself
+    fn recorder_renders_requests_as_sent() {
+        let recorder = Recorder {
+            user_agent: String::from("feroxbuster/test"),
+            headers: vec![
+                (String::from("Authorization"), String::from("Bearer s3cr3t")),
+                (String::from("X-Team"), String::from("red")),
+            ],
+        };
+
+        let request = reqwest::Client::new()
+            .post("http://api.localhost:8080/login?next=1")
+            .header("X-Team", "blue")
+            .body("user=admin")
+            .build()
+            .unwrap();
+
+        let raw = recorder.render(&request, true);
+        assert!(raw.starts_with(
+            "POST /login?next=1 HTTP/1.1\r\nHost: api.localhost:8080\r\nUser-Agent: feroxbuster/test\r\nAccept: */*\r\nAuthorization: Bearer s3cr3t\r\n"
+        ));
+        assert!(raw.contains("x-team: blue\r\n"));
+        assert!(!raw.contains("X-Team: red"));
+        assert!(raw.ends_with("Content-Length: 10\r\n\r\nuser=admin"));
+
+        let raw = recorder.render(&request, false);
+        assert!(!raw.contains("Authorization"));
+    }
+
+    #[test]
+    /// requests are only recorded for configurations that write them somewhere, so one scan's
+    /// --burp-output doesn't turn on recording for another scan in the same process
+    fn recorder_follows_configuration() {
+        let request = reqwest::Client::new().get("http://localhost/");
+
+        let config = Configuration::default();
+        assert!(recorder(&config).is_none());
+        assert!(record(recorder(&config).as_ref(), &request, true).is_none());
+
+        let config = Configuration {
+            burp_output: String::from("/tmp/burp.xml"),
+            ..Default::default()
+        };
+        let sent = record(recorder(&config).as_ref(), &request, true).unwrap();
+        assert!(sent.0.starts_with("GET / HTTP/1.1\r\n"));
+    }
+}
@@ -0,0 +1,137 @@
+use reqwest::Url;
+use std::net::IpAddr;
+use std::sync::RwLock;
+
+/// ip address in `url`'s host, if it is one
+pub fn ip_host(url: &Url) -> Option<IpAddr> {
+    // ipv6 hosts are bracketed, i.e. http://[::1]:8080/
+    url.host_str()?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Host name that requests to an ip address target are sent to instead (--host-header)
+///
+/// a request to the ip goes out with the name in its url, so the Host header and tls SNI carry the
+/// name, and the name resolves to the ip; responses get the ip back in their url, so everything
+/// built from them (extracted links, recursion, output) stays on the ip
+#[derive(Debug, Default)]
+pub struct HostPins {
+    /// host name, and the ip address it's pinned to
+    pin: RwLock<Option<(String, IpAddr)>>,
+}
+
+impl HostPins {
+    /// send requests to the ip address in `target`'s url to `name` instead; a no-op when `name`
+    /// is empty or the target isn't an ip address
+    pub fn set(&self, name: &str, target: &str) {
+        let ip = Url::parse(target).ok().and_then(|url| ip_host(&url));
+
+        let pin = match ip {
+            Some(ip) if !name.is_empty() => Some((name.to_ascii_lowercase(), ip)),
+            _ => None,
+        };
+
+        if let Ok(mut current) = self.pin.write() {
+            *current = pin;
+        }
+    }
+
+    /// the pinned ip address of the given host name, if it's the one that's pinned
+    pub(super) fn resolve(&self, name: &str) -> Option<IpAddr> {
+        let pin = self.pin.read().ok()?;
+        let (pinned, ip) = pin.as_ref()?;
+
+        if pinned.eq_ignore_ascii_case(name) {
+            Some(*ip)
+        } else {
+            None
+        }
+    }
+
+    /// `url` with the pinned name in place of the pinned ip address; `None` when the url isn't
+    /// on that ip
+    pub fn pinned(&self, url: &Url) -> Option<Url> {
+        let pin = self.pin.read().ok()?;
+        let (name, ip) = pin.as_ref()?;
+
+        if ip_host(url)? != *ip {
+            return None;
+        }
+
+        let mut pinned = url.clone();
+        pinned.set_host(Some(name)).ok()?;
+        Some(pinned)
+    }
+
+    /// `url` with the pinned ip address in place of the pinned name; `None` when the url isn't
+    /// on that name
+    pub fn unpinned(&self, url: &Url) -> Option<Url> {
+        let pin = self.pin.read().ok()?;
+        let (name, ip) = pin.as_ref()?;
+
+        if !url.host_str()?.eq_ignore_ascii_case(name) {
+            return None;
+        }
+
+        let mut unpinned = url.clone();
+        unpinned.set_ip_host(*ip).ok()?;
+        Some(unpinned)
+    }
+
+    /// attach the response's url, with the pinned ip address in place of the pinned name, to the
+    /// response; see `UnpinnedUrl`
+    pub fn unpin(&self, response: &mut reqwest::Response) {
+        if let Some(url) = self.unpinned(response.url()) {
+            response.extensions_mut().insert(UnpinnedUrl(url));
+        }
+    }
+}
+
+/// Url of a response to a request sent to the pinned name, with the ip address that was
+/// requested back in place of the name (--host-header)
+#[derive(Debug, Clone)]
+pub struct UnpinnedUrl(pub Url);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// urls on the pinned ip are sent to the pinned name and back, anything else is left alone
+    fn host_pins_swap_ip_and_name() {
+        let pins = HostPins::default();
+        pins.set("Example.com", "https://10.0.0.5:8443/");
+
+        let url = Url::parse("https://10.0.0.5:8443/admin?q=1").unwrap();
+        let pinned = pins.pinned(&url).unwrap();
+
+        assert_eq!(pinned.as_str(), "https://example.com:8443/admin?q=1");
+        assert_eq!(pins.unpinned(&pinned), Some(url));
+        assert_eq!(
+            pins.resolve("EXAMPLE.com"),
+            Some("10.0.0.5".parse().unwrap())
+        );
+
+        let other = Url::parse("https://10.0.0.6/").unwrap();
+        assert_eq!(pins.pinned(&other), None);
+        assert_eq!(pins.unpinned(&other), None);
+        assert_eq!(pins.resolve("example.org"), None);
+
+        pins.set("example.com", "http://[::1]:8080/");
+        let pinned = pins
+            .pinned(&Url::parse("http://[::1]:8080/").unwrap())
+            .unwrap();
+        assert_eq!(pinned.as_str(), "http://example.com:8080/");
+        assert_eq!(
+            pins.unpinned(&pinned).unwrap().as_str(),
+            "http://[::1]:8080/"
+        );
+
+        // only ip address targets are pinned
+        pins.set("example.com", "http://localhost/");
+        assert_eq!(pins.resolve("example.com"), None);
+    }
+}
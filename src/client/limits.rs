@@ -0,0 +1,301 @@
+use leaky_bucket::LeakyBucket;
+use reqwest::Url;
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use super::host_key;
+
+/// Number of requests sent to each host, and the most any one host may be sent (--request-limit)
+#[derive(Debug, Default)]
+pub struct RequestLimits {
+    /// most requests sent to a single host; 0 when there's no limit
+    limit: AtomicUsize,
+
+    /// `host:port` -> requests sent to it so far
+    sent: Mutex<HashMap<String, usize>>,
+
+    /// hosts whose limit was reported as reached already
+    reported: Mutex<HashSet<String>>,
+}
+
+impl RequestLimits {
+    /// cap the requests sent to each host at `limit`; 0 removes the cap
+    pub fn set_limit(&self, limit: usize) {
+        self.limit.store(limit, Ordering::Relaxed);
+    }
+
+    /// the most requests sent to a single host; 0 when there's no limit
+    pub fn limit(&self) -> usize {
+        self.limit.load(Ordering::Relaxed)
+    }
+
+    /// count a request to `url`'s host; false, without counting it, when the host's requests are
+    /// used up already
+    pub fn acquire(&self, url: &Url) -> bool {
+        let limit = self.limit();
+
+        if limit == 0 {
+            return true;
+        }
+
+        let key = match host_key(url) {
+            Some(key) => key,
+            None => return true,
+        };
+
+        let mut sent = match self.sent.lock() {
+            Ok(sent) => sent,
+            Err(_) => return true,
+        };
+
+        let count = sent.entry(key).or_insert(0);
+
+        if *count >= limit {
+            return false;
+        }
+
+        *count += 1;
+        true
+    }
+
+    /// whether `url`'s host has been sent as many requests as it may be
+    pub fn reached(&self, url: &Url) -> bool {
+        let limit = self.limit();
+
+        if limit == 0 {
+            return false;
+        }
+
+        host_key(url)
+            .and_then(|key| self.sent.lock().ok()?.get(&key).copied())
+            .map_or(false, |count| count >= limit)
+    }
+
+    /// whether `url`'s host reached its limit, and that hasn't been reported yet; true only once
+    /// per host
+    pub fn newly_reached(&self, url: &Url) -> bool {
+        if !self.reached(url) {
+            return false;
+        }
+
+        match (host_key(url), self.reported.lock()) {
+            (Some(key), Ok(mut reported)) => reported.insert(key),
+            _ => false,
+        }
+    }
+}
+
+/// Cap on aggregate download throughput, shared by every response body that's read
+///
+/// bodies are read a chunk at a time, and each chunk waits for its share of the allowance; data
+/// that isn't read yet stays in the socket's buffers, so tcp flow control slows the server down
+/// rather than the link filling up
+#[derive(Default)]
+pub struct Bandwidth {
+    /// bytes per second; 0 when downloads aren't capped
+    limit: AtomicU64,
+
+    /// bucket of bytes that may be downloaded, created on first use since it needs a runtime
+    bucket: Mutex<Option<Arc<LeakyBucket>>>,
+}
+
+impl Bandwidth {
+    /// cap downloads at the given number of bytes per second (--throttle-bytes); 0 removes the cap
+    pub fn set_limit(&self, bytes_per_second: u64) {
+        self.limit.store(bytes_per_second, Ordering::Relaxed);
+
+        if let Ok(mut bucket) = self.bucket.lock() {
+            *bucket = None;
+        }
+    }
+
+    /// whether downloads are capped
+    pub fn is_limited(&self) -> bool {
+        self.limit.load(Ordering::Relaxed) > 0
+    }
+
+    /// bucket for the current limit, if there is one
+    fn bucket(&self) -> Option<Arc<LeakyBucket>> {
+        let limit = usize::try_from(self.limit.load(Ordering::Relaxed)).unwrap_or(usize::MAX);
+
+        if limit == 0 {
+            return None;
+        }
+
+        let mut bucket = self.bucket.lock().ok()?;
+
+        if bucket.is_none() {
+            // a tenth of the limit every 0.1s keeps downloads smooth; tiny limits are refilled
+            // once a second instead, so each refill is at least a couple of bytes
+            let (refill, interval) = if limit >= 20 {
+                (limit / 10, 100)
+            } else {
+                (limit, 1000)
+            };
+
+            *bucket = LeakyBucket::builder()
+                .refill_interval(Duration::from_millis(interval))
+                .refill_amount(refill)
+                .tokens(refill)
+                .max(limit)
+                .build()
+                .map_err(|e| log::warn!("Could not create --throttle-bytes limiter: {}", e))
+                .ok()
+                .map(Arc::new);
+        }
+
+        bucket.clone()
+    }
+
+    /// wait until another `bytes` may be downloaded; returns right away when there's no cap
+    pub async fn consume(&self, bytes: usize) {
+        let bucket = match self.bucket() {
+            Some(bucket) => bucket,
+            None => return,
+        };
+
+        let mut remaining = bytes;
+
+        // a chunk can be bigger than a second's allowance, in which case it's paid for in parts
+        while remaining > 0 {
+            let amount = remaining.min(bucket.max());
+
+            if let Err(e) = bucket.acquire(amount).await {
+                log::warn!("--throttle-bytes limiter failed: {}", e);
+                return;
+            }
+
+            remaining -= amount;
+        }
+    }
+}
+
+/// Requests per second shared by every scan of a recipe, handed out one request at a time by the
+/// limiter `feroxbuster recipe` runs for them
+///
+/// unlike --rate-limit, which applies to each directory on its own, every request the scan sends
+/// counts against the recipe's limit, no matter how many directories are being scanned
+#[derive(Debug, Default)]
+pub struct SharedRateLimit {
+    /// address of the recipe's limiter; empty when there's no shared limit
+    address: String,
+
+    /// connection to the limiter, opened on first use since it needs a runtime
+    connection: tokio::sync::Mutex<Option<TcpStream>>,
+
+    /// set once the limiter couldn't be reached, after which requests are no longer held up
+    unreachable: AtomicBool,
+}
+
+impl SharedRateLimit {
+    /// limit shared through the limiter at the given address; an empty address doesn't limit
+    pub fn new(address: &str) -> Self {
+        Self {
+            address: address.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// wait until the recipe's limiter allows another request; returns right away when there's
+    /// no shared limit
+    pub async fn acquire(&self) {
+        if self.address.is_empty() || self.unreachable.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // waiting on the lock queues the scan's requests; the limiter answers them in turn
+        let mut connection = self.connection.lock().await;
+
+        if connection.is_none() {
+            match TcpStream::connect(&self.address).await {
+                Ok(stream) => *connection = Some(stream),
+                Err(e) => {
+                    self.give_up(&e.to_string());
+                    return;
+                }
+            }
+        }
+
+        if let Some(stream) = connection.as_mut() {
+            let mut permit = [0_u8; 1];
+
+            let answered = match stream.write_all(b"?").await {
+                Ok(_) => stream.read_exact(&mut permit).await.map(|_| ()),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = answered {
+                *connection = None;
+                self.give_up(&e.to_string());
+            }
+        }
+    }
+
+    /// stop asking the limiter for permits, i.e. once the recipe that runs it is gone
+    fn give_up(&self, reason: &str) {
+        if !self.unreachable.swap(true, Ordering::Relaxed) {
+            log::warn!(
+                "Could not reach the recipe's rate limiter at {}, requests are no longer limited by it: {}",
+                self.address,
+                reason
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// downloads wait for their share of the allowance, and aren't held up without a limit
+    async fn bandwidth_holds_back_downloads_over_the_limit() {
+        let bandwidth = Bandwidth::default();
+
+        let start = Instant::now();
+        bandwidth.consume(1_000_000).await;
+        assert!(!bandwidth.is_limited());
+        assert!(start.elapsed() < Duration::from_millis(100));
+
+        // 10,000 bytes up front, then 10,000 more every 0.1s
+        bandwidth.set_limit(100_000);
+        assert!(bandwidth.is_limited());
+
+        let start = Instant::now();
+        bandwidth.consume(30_000).await;
+        assert!(start.elapsed() >= Duration::from_millis(150));
+    }
+
+    #[test]
+    /// each host gets its own allowance of requests, and is reported once it's used up
+    fn request_limits_cap_each_host() {
+        let limits = RequestLimits::default();
+        let one = Url::parse("http://one.test/admin").unwrap();
+        let two = Url::parse("http://two.test/").unwrap();
+
+        assert!(limits.acquire(&one));
+        assert!(!limits.reached(&one));
+
+        limits.set_limit(2);
+
+        assert!(limits.acquire(&one));
+        assert!(limits.acquire(&one));
+        assert!(!limits.acquire(&one));
+        assert!(limits.reached(&one));
+
+        assert!(limits.acquire(&two));
+        assert!(!limits.reached(&two));
+        assert!(!limits.newly_reached(&two));
+
+        assert!(limits.newly_reached(&one));
+        assert!(!limits.newly_reached(&one));
+    }
+}
@@ -0,0 +1,289 @@
+//! builds the http clients used for scanning, and the per-host state shared by their requests
+use anyhow::{Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{redirect::Policy, Client, Proxy, Url};
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::{
+    burp::{self, Recorder},
+    config::Configuration,
+    placeholders::TemplatedHeaders,
+    scan_id,
+    scope::Scope,
+    utils::fmt_err,
+};
+
+pub use self::host_pins::{ip_host, HostPins, UnpinnedUrl};
+pub use self::limits::{Bandwidth, RequestLimits, SharedRateLimit};
+pub use self::proxy::proxy_source;
+pub use self::rate_limits::{ObservedRateLimit, RateLimits, DEFAULT_COOLDOWN};
+pub use self::redirects::{take_redirect_chain, RedirectHop};
+pub use self::rotating::{parse_rotate_header, RotatingHeaders};
+pub use self::timeouts::{adaptive_timeout, HostTimeouts};
+pub use self::transport::{DnsCache, TransportSnapshot, TransportStats};
+
+use self::redirects::recording_policy;
+use self::transport::CountingResolver;
+
+mod host_pins;
+mod limits;
+mod proxy;
+mod rate_limits;
+mod redirects;
+mod rotating;
+mod timeouts;
+mod transport;
+
+/// round-robin position used by `next_client`
+static NEXT_CLIENT: AtomicUsize = AtomicUsize::new(0);
+
+lazy_static! {
+    /// connection level counters for every client created by `initialize`
+    pub static ref TRANSPORT: TransportStats = TransportStats::default();
+
+    /// dns answers shared by every client created by `initialize`
+    pub static ref DNS_CACHE: DnsCache = DnsCache::default();
+
+    /// request timeouts learned for each host (--adaptive-timeout)
+    pub static ref HOST_TIMEOUTS: HostTimeouts = HostTimeouts::default();
+
+    /// cap on how fast response bodies are downloaded (--throttle-bytes)
+    pub static ref BANDWIDTH: Bandwidth = Bandwidth::default();
+
+    /// headers whose value changes with every request (--rotate-header)
+    pub static ref ROTATING_HEADERS: RotatingHeaders = RotatingHeaders::default();
+
+    /// rate limits announced by each host, and the pauses they called for
+    pub static ref RATE_LIMITS: RateLimits = RateLimits::default();
+}
+
+/// What a single scan's requests are sent with beyond the client's own settings: the
+/// --host-header pin, --scope and --deny-url-regex (for redirects), templated -H headers, the
+/// --request-limit counts, the --scan-id-header, and the recorder for --burp-output
+///
+/// every [Configuration](../config/struct.Configuration.html) has its own, shared with the
+/// clients built from it, so scans running side by side in one process (i.e. `feroxbuster serve`)
+/// don't overwrite each other's
+#[derive(Debug, Default)]
+pub struct RequestContext {
+    /// host name sent in place of an ip address target (--host-header)
+    pub host_pins: HostPins,
+
+    /// hosts beyond the target that may be scanned (--scope)
+    pub scope: Scope,
+
+    /// -H headers with {{placeholders}} in their value
+    pub templated_headers: TemplatedHeaders,
+
+    /// requests sent to each host, capped by --request-limit
+    pub request_limits: RequestLimits,
+
+    /// header carrying the scan id (--scan-id-header)
+    pub scan_id_header: Option<(HeaderName, HeaderValue)>,
+
+    /// renders requests as they're sent, for --burp-output and --evidence-dir
+    pub recorder: Option<Recorder>,
+
+    /// requests per second shared with the other scans of a recipe
+    pub shared_rate_limit: SharedRateLimit,
+
+    /// redirects to a url matching any of these aren't followed (--deny-url-regex)
+    deny: Vec<Regex>,
+
+    /// ignore the proxy in HTTP_PROXY, HTTPS_PROXY, and ALL_PROXY (--no-env-proxy)
+    no_env_proxy: bool,
+}
+
+impl RequestContext {
+    /// Context for requests sent using the given configuration
+    pub fn new(config: &Configuration) -> Result<Self> {
+        let deny: Vec<Regex> = config
+            .deny_url_regex
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern)
+                    .with_context(|| fmt_err(&format!("Invalid deny url regex {:?}", pattern)))
+            })
+            .collect::<Result<_>>()?;
+
+        let context = Self {
+            scan_id_header: scan_id::header(config),
+            recorder: burp::recorder(config),
+            shared_rate_limit: SharedRateLimit::new(&config.recipe_rate_limit),
+            deny,
+            no_env_proxy: config.no_env_proxy,
+            ..Default::default()
+        };
+
+        context
+            .host_pins
+            .set(&config.host_header, &config.target_url);
+        context.scope.load(&config.scope);
+        context.templated_headers.load(&config.headers);
+        context.request_limits.set_limit(config.request_limit);
+
+        Ok(context)
+    }
+}
+
+/// `host:port` of the given url, the key hosts are tracked by (timeouts, request limits, rate limits)
+fn host_key(url: &Url) -> Option<String> {
+    Some(format!(
+        "{}:{}",
+        url.host_str()?,
+        url.port_or_known_default()?
+    ))
+}
+
+/// Number of clients needed to keep about `streams` requests in flight per http/2 connection
+/// when `threads` requests are in flight overall (--streams-per-connection)
+///
+/// each client has its own connection pool, and an http/2 pool multiplexes everything for a host
+/// over a single connection, so the number of clients is the number of connections per host.
+/// `streams` of 0 means a single client
+pub fn connections_needed(threads: usize, streams: usize) -> usize {
+    if streams == 0 {
+        return 1;
+    }
+
+    ((threads + streams - 1) / streams).max(1)
+}
+
+/// Pick the client for the next request, taking turns between `first` and `rest`
+pub fn next_client<'a>(first: &'a Client, rest: &'a [Client]) -> &'a Client {
+    if rest.is_empty() {
+        return first;
+    }
+
+    match NEXT_CLIENT.fetch_add(1, Ordering::Relaxed) % (rest.len() + 1) {
+        0 => first,
+        index => &rest[index - 1],
+    }
+}
+
+/// Create and return an instance of [reqwest::Client](https://docs.rs/reqwest/latest/reqwest/struct.Client.html)
+/// that sends requests within the given `RequestContext`
+pub fn initialize(
+    timeout: u64,
+    user_agent: &str,
+    redirects: bool,
+    insecure: bool,
+    headers: &HashMap<String, String>,
+    proxy: Option<&str>,
+    context: &Arc<RequestContext>,
+) -> Result<Client> {
+    let policy = if redirects {
+        recording_policy(context.clone())
+    } else {
+        Policy::none()
+    };
+
+    let header_map: HeaderMap = headers.try_into()?;
+
+    let client = Client::builder()
+        .timeout(Duration::new(timeout, 0))
+        .user_agent(user_agent)
+        .danger_accept_invalid_certs(insecure)
+        .default_headers(header_map)
+        .dns_resolver(Arc::new(CountingResolver {
+            stats: &*TRANSPORT,
+            cache: &*DNS_CACHE,
+            context: context.clone(),
+        }))
+        .redirect(policy);
+
+    if let Some(some_proxy) = proxy {
+        if !some_proxy.is_empty() {
+            // it's not an empty string; set the proxy
+            let proxy_obj = Proxy::all(some_proxy)?;
+            return Ok(client.proxy(proxy_obj).build()?);
+        }
+    }
+
+    if context.no_env_proxy {
+        // --no-env-proxy; otherwise, reqwest picks up HTTP_PROXY and friends on its own
+        return Ok(client.no_proxy().build()?);
+    }
+
+    Ok(client.build()?)
+}
+
+/// Create a client for requests that go to third parties rather than the target, i.e. the
+/// Wayback Machine, the --tor exit check, or cloud storage providers (--buckets)
+///
+/// none of the -H headers are set, and since requests made with it aren't sent through
+/// `make_request`, neither are the session, scan id, or any other per-request header; only the
+/// proxy (--proxy, or --tor) carries over, along with --no-env-proxy
+pub fn third_party(
+    timeout: u64,
+    user_agent: &str,
+    insecure: bool,
+    proxy: Option<&str>,
+    no_env_proxy: bool,
+) -> Result<Client> {
+    let client = Client::builder()
+        .timeout(Duration::new(timeout, 0))
+        .user_agent(user_agent)
+        .danger_accept_invalid_certs(insecure)
+        .redirect(Policy::limited(10));
+
+    if let Some(some_proxy) = proxy {
+        if !some_proxy.is_empty() {
+            return Ok(client.proxy(Proxy::all(some_proxy)?).build()?);
+        }
+    }
+
+    if no_env_proxy {
+        // --no-env-proxy
+        return Ok(client.no_proxy().build()?);
+    }
+
+    Ok(client.build()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// enough connections are opened to keep each one at or under the requested number of streams
+    fn connections_needed_rounds_up() {
+        assert_eq!(connections_needed(50, 0), 1);
+        assert_eq!(connections_needed(50, 100), 1);
+        assert_eq!(connections_needed(200, 100), 2);
+        assert_eq!(connections_needed(201, 100), 3);
+        assert_eq!(connections_needed(0, 10), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    /// create client with a bad proxy, expect panic
+    fn client_with_bad_proxy() {
+        let headers = HashMap::new();
+        initialize(
+            0,
+            "stuff",
+            true,
+            false,
+            &headers,
+            Some("not a valid proxy"),
+            &Arc::default(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    /// create client with a proxy, expect no error
+    fn client_with_good_proxy() {
+        let headers = HashMap::new();
+        let proxy = "http://127.0.0.1:8080";
+        let context = Arc::default();
+        initialize(0, "stuff", true, true, &headers, Some(proxy), &context).unwrap();
+    }
+}
@@ -0,0 +1,474 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Mutex, RwLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use super::host_key;
+
+/// longest a host is left alone when it asks for a pause; keeps a bogus Retry-After or reset time
+/// from stalling the scan indefinitely
+const MAX_RATE_LIMIT_PAUSE: Duration = Duration::from_secs(10 * 60);
+
+/// length of a cool-down window when --max-429s is given without --cooldown
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// What a host announced about its rate limit, and the pauses it called for
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ObservedRateLimit {
+    /// `host:port` the limit applies to
+    pub host: String,
+
+    /// requests allowed per window (X-RateLimit-Limit), when the host said
+    pub limit: Option<u64>,
+
+    /// fewest requests the host said were left in a window (X-RateLimit-Remaining)
+    pub lowest_remaining: Option<u64>,
+
+    /// number of times requests to the host were paused
+    pub pauses: usize,
+
+    /// total time requests to the host were paused, in milliseconds
+    pub paused_millis: u64,
+
+    /// number of 429 responses the host sent
+    #[serde(default)]
+    pub too_many_requests: usize,
+
+    /// number of pauses that were --cooldown windows, after --max-429s consecutive 429s
+    #[serde(default)]
+    pub cooldowns: usize,
+}
+
+/// Rate limits announced by each host through Retry-After and X-RateLimit-* (or RateLimit-*)
+/// headers
+///
+/// a 429 or 503 with a Retry-After, or a window with no requests remaining, pauses every request
+/// to the host until it's ready again, rather than sending more requests it will turn away
+///
+/// with --max-429s (or --cooldown), a host that keeps answering 429 is paused for a cool-down
+/// window, whether it said how long to wait or not
+#[derive(Debug, Default)]
+pub struct RateLimits {
+    /// `host:port` -> when requests to it may resume
+    paused_until: RwLock<HashMap<String, Instant>>,
+
+    /// `host:port` -> what it announced
+    observed: RwLock<HashMap<String, ObservedRateLimit>>,
+
+    /// consecutive 429s that start a cool-down; 0 when there's no cool-down policy
+    max_429s: AtomicUsize,
+
+    /// length of a cool-down window, in milliseconds
+    cooldown_millis: AtomicU64,
+
+    /// `host:port` -> 429s it answered with in a row
+    consecutive_429s: Mutex<HashMap<String, usize>>,
+}
+
+/// value of the first of the given headers that's a whole number
+fn header_number(headers: &HeaderMap, names: &[&str]) -> Option<u64> {
+    names.iter().find_map(|name| {
+        headers
+            .get(*name)?
+            .to_str()
+            .ok()?
+            .trim()
+            .parse::<f64>()
+            .ok()
+            .filter(|number| *number >= 0.0)
+            .map(|number| number.ceil() as u64)
+    })
+}
+
+/// Time of an http date (`Sun, 06 Nov 1994 08:49:37 GMT`, rfc 7231 section 7.1.1.1)
+///
+/// the date comes from the target, so fields out of their range (and years past 9999) are
+/// rejected rather than trusted to fit in the arithmetic below
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    const MONTHS: [&str; 12] = [
+        "jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec",
+    ];
+
+    let parts: Vec<&str> = value.split_whitespace().collect();
+
+    if parts.len() != 6 || parts[5] != "GMT" {
+        return None;
+    }
+
+    let day: u64 = parts[1].parse().ok()?;
+    let month = MONTHS
+        .iter()
+        .position(|month| month.eq_ignore_ascii_case(parts[2]))? as u64
+        + 1;
+    let year: u64 = parts[3].parse().ok()?;
+
+    let time: Vec<u64> = parts[4]
+        .split(':')
+        .map(|part| part.parse().ok())
+        .collect::<Option<_>>()?;
+
+    if time.len() != 3 || !(1970..=9999).contains(&year) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    // a second of 60 is a leap second
+    if time[0] > 23 || time[1] > 59 || time[2] > 60 {
+        return None;
+    }
+
+    // days since the epoch, counted from march so leap days fall at the end of the year
+    let (year, month) = if month <= 2 {
+        (year - 1, month + 9)
+    } else {
+        (year, month - 3)
+    };
+    let era = year / 400;
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * month + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    let days = (era * 146_097 + day_of_era).checked_sub(719_468)?;
+
+    let seconds = days
+        .checked_mul(86_400)?
+        .checked_add(time[0] * 3_600 + time[1] * 60 + time[2])?;
+
+    UNIX_EPOCH.checked_add(Duration::from_secs(seconds))
+}
+
+/// how long a Retry-After value (seconds, or an http date) asks clients to wait
+fn retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+    let value = value.trim();
+
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+
+    let date = parse_http_date(value)?;
+    Some(date.duration_since(now).unwrap_or_default())
+}
+
+/// how long until a rate limit window resets; X-RateLimit-Reset is the time of the reset in
+/// seconds since the epoch for some hosts (github, twitter), and the seconds left for others
+fn reset_after(reset: u64, now: SystemTime) -> Duration {
+    // no window lasts anywhere near 30 years, anything that big is a timestamp
+    if reset > 1_000_000_000 {
+        let now = now
+            .duration_since(UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        Duration::from_secs(reset.saturating_sub(now))
+    } else {
+        Duration::from_secs(reset)
+    }
+}
+
+impl RateLimits {
+    /// pause a host for `cooldown` once it answers with `max_429s` 429s in a row; 0 turns the
+    /// policy off
+    pub fn set_cooldown(&self, max_429s: usize, cooldown: Duration) {
+        self.max_429s.store(max_429s, Ordering::Relaxed);
+        self.cooldown_millis
+            .store(cooldown.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// count a response from the host at `key`; returns the cool-down to pause it for when it
+    /// just sent its --max-429s'th 429 in a row
+    fn cooldown(&self, key: &str, too_many_requests: bool) -> Option<Duration> {
+        let max_429s = self.max_429s.load(Ordering::Relaxed);
+
+        if max_429s == 0 {
+            return None;
+        }
+
+        let mut consecutive = self.consecutive_429s.lock().ok()?;
+
+        if !too_many_requests {
+            consecutive.remove(key);
+            return None;
+        }
+
+        let count = consecutive.entry(key.to_string()).or_insert(0);
+        *count += 1;
+
+        if *count < max_429s {
+            return None;
+        }
+
+        // the count starts over once the window's over
+        *count = 0;
+
+        Some(Duration::from_millis(
+            self.cooldown_millis.load(Ordering::Relaxed),
+        ))
+    }
+
+    /// learn from the status and headers of a response to `url`, pausing requests to its host
+    /// when it's asked for a break; returns the length of the pause, if any
+    pub fn learn(&self, url: &Url, status: StatusCode, headers: &HeaderMap) -> Option<Duration> {
+        let key = host_key(url)?;
+        let now = SystemTime::now();
+        let too_many_requests = status == StatusCode::TOO_MANY_REQUESTS;
+        let cooldown = self.cooldown(&key, too_many_requests);
+
+        let limit = header_number(headers, &["x-ratelimit-limit", "ratelimit-limit"]);
+        let remaining = header_number(headers, &["x-ratelimit-remaining", "ratelimit-remaining"]);
+        let reset = header_number(headers, &["x-ratelimit-reset", "ratelimit-reset"]);
+        let retry = headers
+            .get(RETRY_AFTER)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| retry_after(value, now));
+
+        let pause = if matches!(
+            status,
+            StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+        ) && retry.is_some()
+        {
+            retry
+        } else if remaining == Some(0) {
+            reset.map(|reset| reset_after(reset, now)).or(retry)
+        } else {
+            None
+        };
+
+        // a cool-down is at least as long as whatever the host asked for
+        let pause = match (pause, cooldown) {
+            (Some(pause), Some(cooldown)) => Some(pause.max(cooldown)),
+            (pause, cooldown) => pause.or(cooldown),
+        };
+
+        if limit.is_none() && remaining.is_none() && pause.is_none() && !too_many_requests {
+            return None;
+        }
+
+        let pause = pause.map(|pause| pause.min(MAX_RATE_LIMIT_PAUSE));
+        let paused = pause.and_then(|pause| self.pause(&key, pause));
+
+        if let Ok(mut observed) = self.observed.write() {
+            let observed = observed
+                .entry(key.clone())
+                .or_insert_with(|| ObservedRateLimit {
+                    host: key,
+                    ..Default::default()
+                });
+
+            if limit.is_some() {
+                observed.limit = limit;
+            }
+
+            if let Some(remaining) = remaining {
+                observed.lowest_remaining = Some(
+                    observed
+                        .lowest_remaining
+                        .map_or(remaining, |lowest| lowest.min(remaining)),
+                );
+            }
+
+            if let Some((extended, new)) = paused {
+                observed.paused_millis += extended.as_millis() as u64;
+                observed.pauses += new as usize;
+            }
+
+            observed.too_many_requests += too_many_requests as usize;
+            observed.cooldowns += cooldown.is_some() as usize;
+        }
+
+        pause
+    }
+
+    /// pause requests to the host until `pause` from now, unless it's already paused for longer;
+    /// returns how much longer the pause got, and whether the host wasn't paused before
+    fn pause(&self, key: &str, pause: Duration) -> Option<(Duration, bool)> {
+        let now = Instant::now();
+        let until = now + pause;
+
+        let mut paused_until = self.paused_until.write().ok()?;
+        let current = paused_until
+            .get(key)
+            .copied()
+            .filter(|current| *current > now);
+
+        match current {
+            Some(current) if current >= until => None,
+            Some(current) => {
+                paused_until.insert(key.to_string(), until);
+                Some((until - current, false))
+            }
+            None => {
+                log::warn!(
+                    "{} asked for a break, pausing requests to it for {:?}",
+                    key,
+                    pause
+                );
+                paused_until.insert(key.to_string(), until);
+                Some((pause, true))
+            }
+        }
+    }
+
+    /// wait until requests to `url`'s host may resume; returns right away when it isn't paused
+    pub async fn wait(&self, url: &Url) {
+        let until = host_key(url).and_then(|key| self.paused_until.read().ok()?.get(&key).copied());
+
+        if let Some(until) = until {
+            let now = Instant::now();
+
+            if until > now {
+                tokio::time::sleep(until - now).await;
+            }
+        }
+    }
+
+    /// what each host announced so far, ordered by host
+    pub fn snapshot(&self) -> Vec<ObservedRateLimit> {
+        let mut observed: Vec<ObservedRateLimit> = self
+            .observed
+            .read()
+            .map(|observed| observed.values().cloned().collect())
+            .unwrap_or_default();
+
+        observed.sort_by(|a, b| a.host.cmp(&b.host));
+        observed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    /// retry-after is read as seconds or as an http date, and reset times as timestamps or
+    /// seconds left
+    fn rate_limit_headers_are_parsed() {
+        let now = UNIX_EPOCH + Duration::from_secs(784_111_700);
+
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+        );
+        assert_eq!(
+            parse_http_date("Tue, 29 Feb 2000 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(951_782_400))
+        );
+        assert_eq!(parse_http_date("tomorrow"), None);
+
+        // fields out of range, and years that would overflow, are rejected instead of panicking
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 300000000000 08:49:37 GMT"),
+            None
+        );
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 18446744073709551615 08:49:37 GMT"),
+            None
+        );
+        assert_eq!(parse_http_date("Sun, 06 Nov 10000 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 99 Nov 1994 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 24:49:37 GMT"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:60:37 GMT"), None);
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:18446744073709551615 GMT"),
+            None
+        );
+        assert_eq!(
+            retry_after("Sun, 06 Nov 300000000000 08:49:37 GMT", now),
+            None
+        );
+
+        assert_eq!(retry_after("120", now), Some(Duration::from_secs(120)));
+        assert_eq!(
+            retry_after("Sun, 06 Nov 1994 08:49:37 GMT", now),
+            Some(Duration::from_secs(77))
+        );
+        assert_eq!(retry_after("soon", now), None);
+
+        assert_eq!(reset_after(30, now), Duration::from_secs(30));
+        assert_eq!(reset_after(784_111_760, now), Duration::from_secs(60));
+    }
+
+    #[test]
+    /// 429s with a retry-after and exhausted windows pause the host, limits are recorded, and
+    /// overlapping pauses only count once
+    fn rate_limits_pause_hosts_that_ask_for_it() {
+        let limits = RateLimits::default();
+        let url = Url::parse("http://localhost/admin").unwrap();
+
+        let mut headers = HeaderMap::new();
+        headers.insert("x-ratelimit-limit", HeaderValue::from_static("100"));
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("3"));
+
+        assert_eq!(limits.learn(&url, StatusCode::OK, &headers), None);
+
+        headers.insert("x-ratelimit-remaining", HeaderValue::from_static("0"));
+        headers.insert("x-ratelimit-reset", HeaderValue::from_static("2"));
+
+        assert_eq!(
+            limits.learn(&url, StatusCode::OK, &headers),
+            Some(Duration::from_secs(2))
+        );
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("1"));
+
+        assert_eq!(limits.learn(&url, StatusCode::OK, &headers), None);
+        assert_eq!(
+            limits.learn(&url, StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(1))
+        );
+
+        let observed = limits.snapshot();
+
+        assert_eq!(observed.len(), 1);
+        assert_eq!(observed[0].host, "localhost:80");
+        assert_eq!(observed[0].limit, Some(100));
+        assert_eq!(observed[0].lowest_remaining, Some(0));
+        assert_eq!(observed[0].pauses, 1);
+        assert!(observed[0].paused_millis >= 1_900);
+    }
+
+    #[test]
+    /// --max-429s 429s in a row start a cool-down at least as long as what the host asked for;
+    /// anything else in between starts the count over
+    fn rate_limits_cool_down_after_consecutive_429s() {
+        let limits = RateLimits::default();
+        limits.set_cooldown(2, Duration::from_secs(5));
+
+        let url = Url::parse("http://localhost/admin").unwrap();
+        let headers = HeaderMap::new();
+
+        assert_eq!(
+            limits.learn(&url, StatusCode::TOO_MANY_REQUESTS, &headers),
+            None
+        );
+        assert_eq!(limits.learn(&url, StatusCode::OK, &headers), None);
+        assert_eq!(
+            limits.learn(&url, StatusCode::TOO_MANY_REQUESTS, &headers),
+            None
+        );
+        assert_eq!(
+            limits.learn(&url, StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(5))
+        );
+
+        let mut retry = HeaderMap::new();
+        retry.insert(RETRY_AFTER, HeaderValue::from_static("8"));
+
+        assert_eq!(
+            limits.learn(&url, StatusCode::TOO_MANY_REQUESTS, &retry),
+            Some(Duration::from_secs(8))
+        );
+        assert_eq!(
+            limits.learn(&url, StatusCode::TOO_MANY_REQUESTS, &headers),
+            Some(Duration::from_secs(5))
+        );
+
+        let observed = limits.snapshot();
+
+        assert_eq!(observed[0].too_many_requests, 5);
+        assert_eq!(observed[0].cooldowns, 2);
+        assert_eq!(observed[0].pauses, 1);
+        assert!(observed[0].paused_millis >= 7_900);
+    }
+}
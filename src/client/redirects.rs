@@ -0,0 +1,178 @@
+use lazy_static::lazy_static;
+use reqwest::{redirect::Policy, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use super::RequestContext;
+
+/// maximum number of redirects followed for a single request
+const MAX_REDIRECTS: usize = 10;
+
+lazy_static! {
+    /// original request url -> redirects followed while requesting it
+    static ref REDIRECT_CHAINS: Mutex<HashMap<String, Vec<RedirectHop>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// A single redirect that was followed on the way to a final response
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RedirectHop {
+    /// status code of the redirect response
+    pub status: u16,
+
+    /// url that returned the redirect
+    pub url: String,
+}
+
+/// Remove and return the redirects that were followed while requesting `original`
+///
+/// empty when redirects aren't followed or the url didn't redirect
+pub fn take_redirect_chain(original: &Url) -> Vec<RedirectHop> {
+    REDIRECT_CHAINS
+        .lock()
+        .ok()
+        .and_then(|mut chains| chains.remove(original.as_str()))
+        .unwrap_or_default()
+}
+
+/// Redirect policy that follows up to MAX_REDIRECTS redirects, recording each hop along the way
+///
+/// redirects to a url matching any of the context's --deny-url-regex patterns, or outside of its
+/// --scope, aren't followed; the redirect response itself is returned instead
+pub(super) fn recording_policy(context: Arc<RequestContext>) -> Policy {
+    Policy::custom(move |attempt| {
+        let previous = attempt.previous();
+        let pins = &context.host_pins;
+
+        if let (Some(original), Some(hop)) = (previous.first(), previous.last()) {
+            // --host-header; chains are recorded against the ip that was requested
+            let original = pins.unpinned(original).unwrap_or_else(|| original.clone());
+            let hop = pins.unpinned(hop).unwrap_or_else(|| hop.clone());
+
+            if let Ok(mut chains) = REDIRECT_CHAINS.lock() {
+                let chain = chains.entry(original.to_string()).or_default();
+
+                if previous.len() == 1 {
+                    // first redirect for this request, discard anything left by an earlier one
+                    chain.clear();
+                }
+
+                chain.push(RedirectHop {
+                    status: attempt.status().as_u16(),
+                    url: hop.to_string(),
+                });
+            }
+        }
+
+        // --scope; judged on unpinned urls, same as the chains above
+        let out_of_scope = previous.first().map_or(false, |original| {
+            let original = pins.unpinned(original).unwrap_or_else(|| original.clone());
+            let next = pins
+                .unpinned(attempt.url())
+                .unwrap_or_else(|| attempt.url().clone());
+
+            !context.scope.follows_redirect(&next, &original)
+        });
+
+        if context
+            .deny
+            .iter()
+            .any(|pattern| pattern.is_match(attempt.url().as_str()))
+        {
+            log::debug!(
+                "not following redirect to {} (--deny-url-regex)",
+                attempt.url()
+            );
+            attempt.stop()
+        } else if out_of_scope {
+            log::debug!("not following redirect to {} (--scope)", attempt.url());
+            attempt.stop()
+        } else if previous.len() > MAX_REDIRECTS {
+            attempt.error("too many redirects")
+        } else {
+            attempt.follow()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{client::initialize, config::Configuration};
+    use httpmock::{Method::GET, MockServer};
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// each redirect followed is recorded, in order, against the original url
+    async fn client_records_redirect_chain() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/old");
+            then.status(301).header("Location", &srv.url("/older"));
+        });
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/older");
+            then.status(302).header("Location", &srv.url("/new"));
+        });
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/new");
+            then.status(200).body("moved");
+        });
+
+        let context = Arc::default();
+        let client = initialize(5, "stuff", true, false, &HashMap::new(), None, &context).unwrap();
+        let url = Url::parse(&srv.url("/old")).unwrap();
+
+        let response = client.get(url.clone()).send().await.unwrap();
+        assert_eq!(response.url().as_str(), srv.url("/new"));
+
+        assert_eq!(
+            take_redirect_chain(&url),
+            vec![
+                RedirectHop {
+                    status: 301,
+                    url: srv.url("/old"),
+                },
+                RedirectHop {
+                    status: 302,
+                    url: srv.url("/older"),
+                },
+            ]
+        );
+
+        // the chain is consumed when taken
+        assert!(take_redirect_chain(&url).is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// redirects to a url matching --deny-url-regex aren't followed
+    async fn client_doesnt_follow_redirects_to_denied_urls() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/account");
+            then.status(302).header("Location", &srv.url("/logout"));
+        });
+
+        let logout = srv.mock(|when, then| {
+            when.method(GET).path("/logout");
+            then.status(200).body("bye");
+        });
+
+        let config = Configuration {
+            deny_url_regex: vec![String::from("logout")],
+            ..Default::default()
+        };
+        let context = Arc::new(RequestContext::new(&config).unwrap());
+        let client = initialize(5, "stuff", true, false, &HashMap::new(), None, &context).unwrap();
+        let url = Url::parse(&srv.url("/account")).unwrap();
+
+        let response = client.get(url).send().await.unwrap();
+
+        assert_eq!(response.status().as_u16(), 302);
+        assert_eq!(logout.hits(), 0);
+    }
+}
@@ -0,0 +1,166 @@
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::header::{HeaderName, HeaderValue};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::RwLock;
+
+/// Split a --rotate-header value (`NAME:@FILE`) into the header's name and the file its values
+/// are read from
+pub fn parse_rotate_header(spec: &str) -> Option<(&str, &str)> {
+    let (name, path) = spec.split_once(':')?;
+
+    let name = name.trim();
+    let path = path.trim().strip_prefix('@')?;
+
+    if name.is_empty() || path.is_empty() {
+        return None;
+    }
+
+    Some((name, path))
+}
+
+/// Headers whose value is swapped for the next one from a file on every request
+///
+/// each header takes turns through its values independently, starting over at the top of the
+/// file once every value has been sent
+#[derive(Debug, Default)]
+pub struct RotatingHeaders {
+    /// headers in the order they were given
+    headers: RwLock<Vec<RotatingHeader>>,
+}
+
+/// A single header of `RotatingHeaders`
+#[derive(Debug)]
+struct RotatingHeader {
+    /// name of the header
+    name: HeaderName,
+
+    /// every value from the header's file
+    values: Vec<HeaderValue>,
+
+    /// position of the value sent with the next request
+    next: AtomicUsize,
+}
+
+impl RotatingHeaders {
+    /// read the values of each `NAME:@FILE` spec, one per line, replacing any headers loaded
+    /// before; blank lines are skipped
+    pub fn load(&self, specs: &[String]) -> Result<()> {
+        let mut headers = Vec::new();
+
+        for spec in specs {
+            let (name, path) = parse_rotate_header(spec)
+                .ok_or_else(|| anyhow!("Invalid rotate header {}, expected NAME:@FILE", spec))?;
+
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .with_context(|| format!("Invalid rotate header name {}", name))?;
+
+            let contents = std::fs::read_to_string(path)
+                .with_context(|| format!("Could not read rotate header file {}", path))?;
+
+            let mut values = Vec::new();
+
+            for line in contents
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+            {
+                values.push(
+                    HeaderValue::from_str(line).with_context(|| {
+                        format!("Invalid {} value {:?} in {}", name, line, path)
+                    })?,
+                );
+            }
+
+            if values.is_empty() {
+                bail!("Rotate header file {} has no values for {}", path, name);
+            }
+
+            headers.push(RotatingHeader {
+                name,
+                values,
+                next: AtomicUsize::new(0),
+            });
+        }
+
+        if let Ok(mut current) = self.headers.write() {
+            *current = headers;
+        }
+
+        Ok(())
+    }
+
+    /// the value each rotating header gets on the next request; empty without --rotate-header
+    pub fn next_values(&self) -> Vec<(HeaderName, HeaderValue)> {
+        let headers = match self.headers.read() {
+            Ok(headers) => headers,
+            Err(_) => return Vec::new(),
+        };
+
+        headers
+            .iter()
+            .map(|header| {
+                let position = header.next.fetch_add(1, Ordering::Relaxed) % header.values.len();
+                (header.name.clone(), header.values[position].clone())
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    /// only NAME:@FILE is accepted by --rotate-header
+    fn parse_rotate_header_needs_name_and_file() {
+        assert_eq!(
+            parse_rotate_header("X-Forwarded-For:@ips.txt"),
+            Some(("X-Forwarded-For", "ips.txt"))
+        );
+        assert_eq!(
+            parse_rotate_header("X-Real-IP: @/tmp/a:b.txt"),
+            Some(("X-Real-IP", "/tmp/a:b.txt"))
+        );
+        assert_eq!(parse_rotate_header("X-Forwarded-For:ips.txt"), None);
+        assert_eq!(parse_rotate_header("X-Forwarded-For:@"), None);
+        assert_eq!(parse_rotate_header(":@ips.txt"), None);
+        assert_eq!(parse_rotate_header("X-Forwarded-For"), None);
+    }
+
+    #[test]
+    /// each header takes turns through its file's values, starting over at the end
+    fn rotating_headers_take_turns_through_values() {
+        let mut ips = NamedTempFile::new().unwrap();
+        writeln!(ips, "10.0.0.1\n\n10.0.0.2\n10.0.0.3").unwrap();
+
+        let mut hosts = NamedTempFile::new().unwrap();
+        writeln!(hosts, "internal").unwrap();
+
+        let rotating = RotatingHeaders::default();
+        assert!(rotating.next_values().is_empty());
+
+        rotating
+            .load(&[
+                format!("X-Forwarded-For:@{}", ips.path().display()),
+                format!("X-Forwarded-Host:@{}", hosts.path().display()),
+            ])
+            .unwrap();
+
+        let sent: Vec<_> = (0..4)
+            .map(|_| {
+                let values = rotating.next_values();
+                assert_eq!(values[1].1, "internal");
+                values[0].1.to_str().unwrap().to_string()
+            })
+            .collect();
+
+        assert_eq!(sent, ["10.0.0.1", "10.0.0.2", "10.0.0.3", "10.0.0.1"]);
+
+        let empty = NamedTempFile::new().unwrap();
+        assert!(rotating
+            .load(&[format!("X-Forwarded-For:@{}", empty.path().display())])
+            .is_err());
+    }
+}
@@ -0,0 +1,85 @@
+use reqwest::Url;
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use super::host_key;
+
+/// shortest timeout --adaptive-timeout gives a host; keeps a little jitter on a very fast host
+/// from turning into timeout errors
+const MIN_ADAPTIVE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Request timeouts for each host, based on how quickly the host answered the heuristic requests
+/// made before scanning it; only populated when --adaptive-timeout is used
+#[derive(Debug, Default)]
+pub struct HostTimeouts {
+    /// `host:port` -> timeout for requests to it
+    timeouts: RwLock<HashMap<String, Duration>>,
+}
+
+/// timeout for a host that took `elapsed` to respond: `factor` times as long, but no shorter
+/// than MIN_ADAPTIVE_TIMEOUT and no longer than `max`
+pub fn adaptive_timeout(elapsed: Duration, factor: u32, max: Duration) -> Duration {
+    elapsed
+        .saturating_mul(factor)
+        .max(MIN_ADAPTIVE_TIMEOUT)
+        .min(max)
+}
+
+impl HostTimeouts {
+    /// learn from a response to `url` that took `elapsed`; the slowest response seen from a host
+    /// decides its timeout, which is returned
+    pub fn learn(
+        &self,
+        url: &Url,
+        elapsed: Duration,
+        factor: u32,
+        max: Duration,
+    ) -> Option<Duration> {
+        let key = host_key(url)?;
+        let timeout = adaptive_timeout(elapsed, factor, max);
+
+        let mut timeouts = self.timeouts.write().ok()?;
+        let learned = timeouts.entry(key).or_insert(timeout);
+        *learned = (*learned).max(timeout);
+
+        Some(*learned)
+    }
+
+    /// timeout learned for requests to `url`'s host, if there is one
+    pub fn get(&self, url: &Url) -> Option<Duration> {
+        let key = host_key(url)?;
+        self.timeouts.read().ok()?.get(&key).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// learned timeouts are a multiple of the slowest response seen from a host, within bounds
+    fn host_timeouts_learn_from_slowest_response() {
+        let max = Duration::from_secs(30);
+        let timeouts = HostTimeouts::default();
+        let url = Url::parse("http://localhost/admin").unwrap();
+        let other = Url::parse("http://localhost:8080/").unwrap();
+
+        assert_eq!(timeouts.get(&url), None);
+
+        timeouts.learn(&url, Duration::from_millis(400), 10, max);
+        timeouts.learn(&url, Duration::from_millis(200), 10, max);
+        assert_eq!(timeouts.get(&url), Some(Duration::from_secs(4)));
+        assert_eq!(
+            timeouts.get(&Url::parse("http://localhost/other").unwrap()),
+            Some(Duration::from_secs(4))
+        );
+        assert_eq!(timeouts.get(&other), None);
+
+        // fast hosts get at least the minimum, slow ones at most --timeout
+        timeouts.learn(&other, Duration::from_millis(5), 10, max);
+        assert_eq!(timeouts.get(&other), Some(MIN_ADAPTIVE_TIMEOUT));
+        timeouts.learn(&other, Duration::from_secs(20), 10, max);
+        assert_eq!(timeouts.get(&other), Some(max));
+    }
+}
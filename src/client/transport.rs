@@ -0,0 +1,352 @@
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::error::Error;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+use trust_dns_resolver::{system_conf::read_system_conf, TokioAsyncResolver};
+
+use crate::{atomic_increment, atomic_load};
+
+use super::{RequestContext, TRANSPORT};
+
+/// longest --dns-ttl answers are kept for; anything longer already outlives any scan, and a
+/// huge ttl can't be added to the current time
+const MAX_DNS_TTL: u64 = 365 * 24 * 60 * 60;
+
+/// Counters describing how requests made it onto the wire
+///
+/// the connection pool resolves a host each time it opens a new connection to it, so calls to
+/// the resolver double as a count of new connections; requests to hosts given as ip addresses
+/// never hit the resolver, and are left out of the counts entirely
+#[derive(Debug, Default)]
+pub struct TransportStats {
+    /// requests sent to a host given by name
+    named_requests: AtomicUsize,
+
+    /// connections opened, one per call to the resolver
+    new_connections: AtomicUsize,
+
+    /// dns lookups that weren't answered from `DnsCache`
+    dns_lookups: AtomicUsize,
+
+    /// tls handshakes performed, one per new connection to a host requested over https
+    tls_handshakes: AtomicUsize,
+
+    /// hosts that have been requested over https
+    tls_hosts: RwLock<HashSet<String>>,
+}
+
+/// Point in time copy of `TransportStats`
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TransportSnapshot {
+    /// dns lookups performed
+    pub dns_lookups: usize,
+
+    /// connections opened
+    pub new_connections: usize,
+
+    /// requests sent over an already open connection
+    pub reused_connections: usize,
+
+    /// tls handshakes performed
+    pub tls_handshakes: usize,
+}
+
+impl TransportStats {
+    /// record a request that's about to be sent
+    pub fn record_request(&self, url: &Url) {
+        let host = match url.domain() {
+            Some(host) => host,
+            None => return, // ip addresses aren't resolved, so their connections can't be seen
+        };
+
+        atomic_increment!(self.named_requests);
+
+        if url.scheme() != "https" {
+            return;
+        }
+
+        let known = self
+            .tls_hosts
+            .read()
+            .map(|hosts| hosts.contains(host))
+            .unwrap_or(true);
+
+        if !known {
+            if let Ok(mut hosts) = self.tls_hosts.write() {
+                hosts.insert(host.to_string());
+            }
+        }
+    }
+
+    /// record a new connection to the given host
+    fn record_connection(&self, host: &str) {
+        atomic_increment!(self.new_connections);
+
+        let tls = self
+            .tls_hosts
+            .read()
+            .map(|hosts| hosts.contains(host))
+            .unwrap_or(false);
+
+        if tls {
+            atomic_increment!(self.tls_handshakes);
+        }
+    }
+
+    /// current value of each counter
+    pub fn snapshot(&self) -> TransportSnapshot {
+        let connections = atomic_load!(self.new_connections);
+
+        TransportSnapshot {
+            dns_lookups: atomic_load!(self.dns_lookups),
+            new_connections: connections,
+            reused_connections: atomic_load!(self.named_requests).saturating_sub(connections),
+            tls_handshakes: atomic_load!(self.tls_handshakes),
+        }
+    }
+}
+
+/// Cache of dns answers, so that opening another connection to a host (a new vhost, or after the
+/// target closed the previous one) doesn't wait on another lookup
+///
+/// answers are kept for as long as their TTL allows, unless --dns-ttl says otherwise. lookups
+/// go to the nameservers in the system's configuration; anything they can't answer (i.e. names
+/// only known to other sources configured in nsswitch.conf) is handed to the system's resolver
+#[derive(Default)]
+pub struct DnsCache {
+    /// host -> its addresses, and when they expire
+    entries: Mutex<HashMap<String, (Vec<IpAddr>, Instant)>>,
+
+    /// number of seconds answers are kept, regardless of their TTL; 0 respects each TTL
+    ttl: AtomicU64,
+
+    /// resolver created from the system's configuration on first use; `None` inside the
+    /// `Option` when that configuration couldn't be used
+    resolver: Mutex<Option<Option<TokioAsyncResolver>>>,
+}
+
+impl DnsCache {
+    /// keep answers for the given number of seconds, regardless of their TTL (--dns-ttl); 0
+    /// respects each answer's TTL
+    pub fn set_ttl(&self, seconds: u64) {
+        self.ttl.store(seconds, Ordering::Relaxed);
+    }
+
+    /// addresses of the given host, if they're cached and haven't expired
+    fn cached(&self, host: &str) -> Option<Vec<IpAddr>> {
+        let entries = self.entries.lock().ok()?;
+        let (addrs, expires) = entries.get(host)?;
+
+        if *expires > Instant::now() {
+            Some(addrs.clone())
+        } else {
+            None
+        }
+    }
+
+    /// resolver that queries the nameservers from the system's configuration
+    fn resolver(&self) -> Option<TokioAsyncResolver> {
+        let mut resolver = self.resolver.lock().ok()?;
+
+        resolver
+            .get_or_insert_with(|| {
+                let (config, options) = read_system_conf().ok()?;
+
+                TokioAsyncResolver::tokio(config, options)
+                    .map_err(|e| log::warn!("Could not create dns resolver: {}", e))
+                    .ok()
+            })
+            .clone()
+    }
+
+    /// resolve the given host ahead of time, so that its answer is already cached when the first
+    /// connection to it is opened; returns whether the host resolved to anything
+    pub async fn prime(&self, host: &str) -> bool {
+        self.lookup(host, &TRANSPORT)
+            .await
+            .map(|addrs| !addrs.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// addresses of the given host, from the cache when possible; lookups that miss the cache
+    /// are counted in `stats`
+    async fn lookup(
+        &self,
+        host: &str,
+        stats: &TransportStats,
+    ) -> Result<Vec<IpAddr>, Box<dyn Error + Send + Sync>> {
+        if let Some(addrs) = self.cached(host) {
+            return Ok(addrs);
+        }
+
+        atomic_increment!(stats.dns_lookups);
+
+        let answer = match self.resolver() {
+            Some(resolver) => resolver
+                .lookup_ip(host)
+                .await
+                .map(|lookup| (lookup.iter().collect::<Vec<_>>(), lookup.valid_until()))
+                .map_err(|e| log::debug!("dns lookup of {} failed: {}", host, e))
+                .ok(),
+            None => None,
+        };
+
+        let (addrs, expires): (Vec<IpAddr>, Instant) = match answer {
+            Some(answer) => answer,
+            None => {
+                // the system's resolver doesn't say how long its answers are good for, so they're
+                // only cached when --dns-ttl is used
+                let addrs = tokio::net::lookup_host((host, 0)).await?;
+                (addrs.map(|addr| addr.ip()).collect(), Instant::now())
+            }
+        };
+
+        let ttl = self.ttl.load(Ordering::Relaxed);
+        let expires = if ttl > 0 {
+            Instant::now()
+                .checked_add(Duration::from_secs(ttl.min(MAX_DNS_TTL)))
+                .unwrap_or(expires)
+        } else {
+            expires
+        };
+
+        if !addrs.is_empty() {
+            if let Ok(mut entries) = self.entries.lock() {
+                entries.insert(host.to_string(), (addrs.clone(), expires));
+            }
+        }
+
+        Ok(addrs)
+    }
+}
+
+/// Resolver that answers from `DnsCache`, counting each call as a new connection
+pub(super) struct CountingResolver {
+    /// where connections and lookups are counted
+    pub(super) stats: &'static TransportStats,
+
+    /// where answers are cached
+    pub(super) cache: &'static DnsCache,
+
+    /// scan the client belongs to, for its --host-header pin
+    pub(super) context: Arc<RequestContext>,
+}
+
+impl Resolve for CountingResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        self.stats.record_connection(name.as_str());
+
+        let stats = self.stats;
+        let cache = self.cache;
+        let context = self.context.clone();
+        let host = name.as_str().to_string();
+
+        Box::pin(async move {
+            let addrs = match context.host_pins.resolve(&host) {
+                // --host-header; the name is only ever sent to the target's ip
+                Some(ip) => vec![ip],
+                None => cache.lookup(&host, stats).await?,
+            };
+            let addrs = addrs.into_iter().map(|ip| SocketAddr::new(ip, 0));
+
+            Ok(Box::new(addrs) as Addrs)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use reqwest::Client;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// new connections are seen through lookups, and pooled connections are reused
+    async fn transport_stats_count_lookups_and_reuse() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/stuff");
+            then.status(200).body("things");
+        });
+
+        let stats: &'static TransportStats = Box::leak(Box::new(TransportStats::default()));
+        let cache: &'static DnsCache = Box::leak(Box::new(DnsCache::default()));
+        let client = Client::builder()
+            .dns_resolver(Arc::new(CountingResolver {
+                stats,
+                cache,
+                context: Arc::default(),
+            }))
+            .build()
+            .unwrap();
+
+        let url = Url::parse(&format!("http://localhost:{}/stuff", srv.port())).unwrap();
+
+        for _ in 0..3 {
+            stats.record_request(&url);
+            let response = client.get(url.clone()).send().await.unwrap();
+            response.text().await.unwrap(); // finish the body so the connection goes back to the pool
+        }
+
+        let ip_url = Url::parse(&srv.url("/stuff")).unwrap();
+        stats.record_request(&ip_url); // ip addresses aren't counted
+
+        let https_url = Url::parse("https://localhost/").unwrap();
+        stats.record_request(&https_url);
+        stats.record_connection("localhost");
+
+        assert_eq!(
+            stats.snapshot(),
+            TransportSnapshot {
+                dns_lookups: 1,
+                new_connections: 2,
+                reused_connections: 2,
+                tls_handshakes: 1,
+            }
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// answers are served from the cache until they expire, and --dns-ttl overrides expiration
+    async fn dns_cache_reuses_answers_until_they_expire() {
+        let stats = TransportStats::default();
+        let cache = DnsCache::default();
+
+        cache.set_ttl(300);
+
+        let first = cache.lookup("localhost", &stats).await.unwrap();
+        let second = cache.lookup("localhost", &stats).await.unwrap();
+
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+        assert_eq!(stats.snapshot().dns_lookups, 1);
+
+        // expire the cached answer; the next lookup goes back out
+        if let Ok(mut entries) = cache.entries.lock() {
+            entries.get_mut("localhost").unwrap().1 = Instant::now();
+        }
+
+        cache.lookup("localhost", &stats).await.unwrap();
+        assert_eq!(stats.snapshot().dns_lookups, 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a --dns-ttl too big to add to the current time is capped instead of panicking
+    async fn dns_cache_caps_huge_ttl() {
+        let stats = TransportStats::default();
+        let cache = DnsCache::default();
+
+        cache.set_ttl(u64::MAX);
+
+        cache.lookup("localhost", &stats).await.unwrap();
+        cache.lookup("localhost", &stats).await.unwrap();
+
+        assert_eq!(stats.snapshot().dns_lookups, 1);
+    }
+}
@@ -0,0 +1,353 @@
+//! typed construction of a [Configuration](struct.Configuration.html) for use as a library
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use super::{
+    container::Configuration,
+    utils::{determine_output_level, determine_requester_policy, OutputLevel},
+};
+
+/// Builds a [Configuration](struct.Configuration.html) without reading the command line, config
+/// files, or environment variables
+///
+/// Anything that isn't set keeps the same default used by the command line tool, except for
+/// output: embedded scans are silent and don't save a state file unless asked to.
+///
+/// ```no_run
+/// use feroxbuster::config::ConfigurationBuilder;
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let config = ConfigurationBuilder::new()
+///     .target_url("http://localhost")
+///     .wordlist("/wordlists/common.txt")
+///     .threads(20)
+///     .extensions(&["php", "html"])
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ConfigurationBuilder {
+    /// configuration being built
+    config: Configuration,
+
+    /// whether or not replay_codes was set explicitly; mirrors the command line where replay
+    /// codes default to whatever status codes were given
+    replay_codes_set: bool,
+}
+
+impl Default for ConfigurationBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigurationBuilder {
+    /// Start from the default configuration
+    pub fn new() -> Self {
+        let config = Configuration {
+            silent: true,
+            output_level: OutputLevel::Silent,
+            save_state: false,
+            ..Default::default()
+        };
+
+        Self {
+            config,
+            replay_codes_set: false,
+        }
+    }
+
+    /// The target URL
+    pub fn target_url(mut self, url: &str) -> Self {
+        self.config.target_url = url.to_string();
+        self
+    }
+
+    /// Path to the wordlist, or the name of an installed wordlist
+    pub fn wordlist(mut self, wordlist: &str) -> Self {
+        self.config.wordlist = wordlist.to_string();
+        self
+    }
+
+    /// Number of concurrent threads
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.config.threads = threads;
+        self
+    }
+
+    /// Number of seconds before a request times out
+    pub fn timeout(mut self, seconds: u64) -> Self {
+        self.config.timeout = seconds;
+        self
+    }
+
+    /// Maximum recursion depth, a depth of 0 is infinite recursion
+    pub fn depth(mut self, depth: usize) -> Self {
+        self.config.depth = depth;
+        self
+    }
+
+    /// Do not scan recursively
+    pub fn no_recursion(mut self, no_recursion: bool) -> Self {
+        self.config.no_recursion = no_recursion;
+        self
+    }
+
+    /// Status codes to include
+    pub fn status_codes(mut self, codes: &[u16]) -> Self {
+        self.config.status_codes = codes.to_vec();
+        self
+    }
+
+    /// Status codes to send through the replay proxy; defaults to the status codes
+    pub fn replay_codes(mut self, codes: &[u16]) -> Self {
+        self.config.replay_codes = codes.to_vec();
+        self.replay_codes_set = true;
+        self
+    }
+
+    /// Status codes to filter out
+    pub fn filter_status(mut self, codes: &[u16]) -> Self {
+        self.config.filter_status = codes.to_vec();
+        self
+    }
+
+    /// Response sizes to filter out
+    pub fn filter_size(mut self, sizes: &[u64]) -> Self {
+        self.config.filter_size = sizes.to_vec();
+        self
+    }
+
+    /// Word counts to filter out
+    pub fn filter_words(mut self, counts: &[usize]) -> Self {
+        self.config.filter_word_count = counts.to_vec();
+        self
+    }
+
+    /// Line counts to filter out
+    pub fn filter_lines(mut self, counts: &[usize]) -> Self {
+        self.config.filter_line_count = counts.to_vec();
+        self
+    }
+
+    /// Regular expressions matched against response bodies/headers to filter out
+    pub fn filter_regex(mut self, patterns: &[&str]) -> Self {
+        self.config.filter_regex = patterns.iter().map(|p| p.to_string()).collect();
+        self
+    }
+
+    /// File extension(s) to search for
+    pub fn extensions(mut self, extensions: &[&str]) -> Self {
+        self.config.extensions = extensions.iter().map(|e| e.to_string()).collect();
+        self
+    }
+
+    /// Add a header to each request
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.config
+            .headers
+            .insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// Replace all headers sent with each request
+    pub fn headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.config.headers = headers;
+        self
+    }
+
+    /// Add a query parameter to each request
+    pub fn query(mut self, name: &str, value: &str) -> Self {
+        self.config
+            .queries
+            .push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Proxy to use for requests
+    pub fn proxy(mut self, proxy: &str) -> Self {
+        self.config.proxy = proxy.to_string();
+        self
+    }
+
+    /// Proxy through which only the responses with a replay code are sent again
+    pub fn replay_proxy(mut self, proxy: &str) -> Self {
+        self.config.replay_proxy = proxy.to_string();
+        self
+    }
+
+    /// User-Agent sent with each request
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.config.user_agent = user_agent.to_string();
+        self
+    }
+
+    /// Follow redirects
+    pub fn redirects(mut self, redirects: bool) -> Self {
+        self.config.redirects = redirects;
+        self
+    }
+
+    /// Disable TLS certificate validation
+    pub fn insecure(mut self, insecure: bool) -> Self {
+        self.config.insecure = insecure;
+        self
+    }
+
+    /// Append / to each request
+    pub fn add_slash(mut self, add_slash: bool) -> Self {
+        self.config.add_slash = add_slash;
+        self
+    }
+
+    /// Extract links from response bodies and make new requests based on them
+    pub fn extract_links(mut self, extract_links: bool) -> Self {
+        self.config.extract_links = extract_links;
+        self
+    }
+
+    /// Don't auto-filter wildcard responses
+    pub fn dont_filter(mut self, dont_filter: bool) -> Self {
+        self.config.dont_filter = dont_filter;
+        self
+    }
+
+    /// Limit the number of concurrent scans, 0 is no limit
+    pub fn scan_limit(mut self, limit: usize) -> Self {
+        self.config.scan_limit = limit;
+        self
+    }
+
+    /// Limit the number of requests per second per directory, 0 is no limit
+    pub fn rate_limit(mut self, limit: usize) -> Self {
+        self.config.rate_limit = limit;
+        self
+    }
+
+    /// Automatically lower the scan rate when an excessive amount of errors are encountered
+    pub fn auto_tune(mut self, auto_tune: bool) -> Self {
+        self.config.auto_tune = auto_tune;
+        self
+    }
+
+    /// Automatically stop scanning when an excessive amount of errors are encountered
+    pub fn auto_bail(mut self, auto_bail: bool) -> Self {
+        self.config.auto_bail = auto_bail;
+        self
+    }
+
+    /// Stop scanning after the given amount of time, i.e. 10m or 1h30m
+    pub fn time_limit(mut self, limit: &str) -> Self {
+        self.config.time_limit = limit.to_string();
+        self
+    }
+
+    /// Also write results to the given file
+    pub fn output(mut self, path: &str) -> Self {
+        self.config.output = path.to_string();
+        self
+    }
+
+    /// Write a state file when the scan is cancelled or times out
+    pub fn save_state(mut self, save_state: bool) -> Self {
+        self.config.save_state = save_state;
+        self
+    }
+
+    /// Validate the configuration and build its http clients
+    ///
+    /// every problem found is included in the returned error, one per line
+    pub fn build(self) -> Result<Configuration> {
+        let mut config = self.config;
+
+        if !self.replay_codes_set {
+            config.replay_codes = config.status_codes.clone();
+        }
+
+        config.output_level = determine_output_level(config.quiet, config.silent);
+        config.requester_policy = determine_requester_policy(config.auto_tune, config.auto_bail);
+
+        Configuration::resolve_wordlist(&mut config);
+
+        let mut problems = config.validate();
+
+        if config.target_url.is_empty() {
+            problems.push("No target url was given".to_string());
+        }
+
+        if config.threads == 0 {
+            problems.push("The number of threads must be above 0".to_string());
+        }
+
+        if !problems.is_empty() {
+            bail!("Invalid configuration:\n{}", problems.join("\n"));
+        }
+
+        Configuration::try_rebuild_clients(&mut config)?;
+
+        Ok(config)
+    }
+}
+
+impl Configuration {
+    /// Start building a [Configuration](struct.Configuration.html) in code, see
+    /// [ConfigurationBuilder](struct.ConfigurationBuilder.html)
+    pub fn builder() -> ConfigurationBuilder {
+        ConfigurationBuilder::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    /// setters end up in the built configuration, replay codes follow the status codes
+    fn build_applies_settings() {
+        let wordlist = NamedTempFile::new().unwrap();
+
+        let config = Configuration::builder()
+            .target_url("http://localhost")
+            .wordlist(wordlist.path().to_str().unwrap())
+            .threads(5)
+            .status_codes(&[200, 403])
+            .extensions(&["php"])
+            .header("X-Api-Key", "abc")
+            .query("token", "123")
+            .build()
+            .unwrap();
+
+        assert_eq!(config.target_url, "http://localhost");
+        assert_eq!(config.threads, 5);
+        assert_eq!(config.status_codes, vec![200, 403]);
+        assert_eq!(config.replay_codes, vec![200, 403]);
+        assert_eq!(config.extensions, vec!["php"]);
+        assert_eq!(config.headers.get("X-Api-Key").unwrap(), "abc");
+        assert_eq!(
+            config.queries,
+            vec![("token".to_string(), "123".to_string())]
+        );
+        assert_eq!(config.output_level, OutputLevel::Silent);
+        assert!(!config.save_state);
+    }
+
+    #[test]
+    /// every problem is reported instead of exiting the process
+    fn build_reports_all_problems() {
+        let error = Configuration::builder()
+            .wordlist("/definitely/not/a/wordlist")
+            .threads(0)
+            .status_codes(&[42])
+            .build()
+            .unwrap_err()
+            .to_string();
+
+        assert!(error.contains("Wordlist /definitely/not/a/wordlist not found"));
+        assert!(error.contains("No target url was given"));
+        assert!(error.contains("The number of threads must be above 0"));
+        assert!(error.contains("Invalid status code 42 in status_codes"));
+    }
+}
@@ -1,24 +1,86 @@
 use super::utils::{
-    depth, report_and_exit, save_state, serialized_type, status_codes, threads, timeout,
-    user_agent, wordlist, OutputLevel, RequesterPolicy,
+    byte_units, depth, evidence_severity, log_format, output_format, save_state, seen_urls,
+    serialized_type, shard_size, status_codes, threads, timeout, user_agent, wordlist, OutputLevel,
+    RequesterPolicy,
 };
+use super::wizard;
 use crate::config::determine_output_level;
 use crate::config::utils::determine_requester_policy;
+use crate::scan_manager::FINDING_PREFIX;
 use crate::{
-    client, parser, scan_manager::resume_scan, traits::FeroxSerialize, utils::fmt_err,
-    DEFAULT_CONFIG_NAME,
+    client, csrf::Csrf, event_stream, evidence, login::Login, parser, permutations, placeholders,
+    presets, scan_id, scan_manager::resume_scan, scope, tor, trace, traits::FeroxSerialize,
+    utils::fmt_err, wordlist_map::WordlistMapping, wordlists, DEFAULT_CONFIG_NAME,
 };
-use anyhow::{anyhow, Context, Result};
+use anyhow::{anyhow, bail, Context, Result};
 use clap::{value_t, ArgMatches};
-use reqwest::{Client, StatusCode};
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Client, Proxy, StatusCode, Url,
+};
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 use std::{
     collections::HashMap,
-    env::{current_dir, current_exe},
-    fs::read_to_string,
+    env::{self, current_dir, current_exe},
+    fs::{read_to_string, write},
+    io::{stdin, stdout},
+    iter::once,
+    net::SocketAddr,
     path::PathBuf,
+    sync::Arc,
+    time::Duration,
 };
 
+/// What the command line asked for, as returned by [`Configuration::from_command_line`]; main
+/// carries it out and exits with the appropriate code
+#[derive(Debug)]
+pub enum Invocation {
+    /// scan using the resolved configuration
+    Scan(Box<Configuration>),
+
+    /// feroxbuster [OPTIONS] recipe FILE; each of the recipe's scans builds upon the resolved
+    /// configuration
+    Recipe(Box<Configuration>, ArgMatches<'static>),
+
+    /// feroxbuster [OPTIONS] watch --targets FILE --interval DURATION; like a recipe, each scan
+    /// builds upon the resolved configuration
+    Watch(Box<Configuration>, ArgMatches<'static>),
+
+    /// feroxbuster serve [--listen ADDRESS]
+    Serve(ArgMatches<'static>),
+
+    /// feroxbuster worker --connect ADDRESS
+    Worker(ArgMatches<'static>),
+
+    /// feroxbuster bench [--requests COUNT] [--threads THREADS]
+    Bench(ArgMatches<'static>),
+
+    /// feroxbuster [OPTIONS] wordlists install|list|remove; holds all of the matches, since the
+    /// top-level options are used as well
+    Wordlists(ArgMatches<'static>),
+
+    /// feroxbuster [OPTIONS] config check; holds every problem found with the configuration
+    ConfigCheck(Vec<String>),
+
+    /// --help, --version, or a usage error, as reported by clap
+    Usage(clap::Error),
+
+    /// --wizard was left without launching a scan
+    Nothing,
+}
+
+/// prefix of the environment variables that can be used to set configuration values
+const ENV_PREFIX: &str = "FEROX_";
+
+/// placeholder written in place of sensitive values when saving a configuration with
+/// --redact-secrets
+const REDACTED: &str = "REDACTED";
+
+/// fields that describe how the current run was started, rather than what it scans; they're left
+/// out of saved configurations
+const UNSAVED_FIELDS: [&str; 4] = ["type", "save_config", "resumed", "resume_from"];
+
 /// macro helper to abstract away repetitive configuration updates
 macro_rules! update_config_if_present {
     ($c:expr, $m:ident, $v:expr, $t:ty) => {
@@ -31,7 +93,7 @@ macro_rules! update_config_if_present {
             }) => {
                 // Do nothing if argument not found
             }
-            Err(e) => e.exit(), // Exit with error on parse error
+            Err(e) => return Err(e.into()), // bubble up parse errors
         }
     };
 }
@@ -50,6 +112,7 @@ macro_rules! update_if_not_default {
 /// This struct is the combination of the following:
 /// - default configuration values
 /// - plus overrides read from a configuration file
+/// - plus overrides read from `FEROX_*` environment variables
 /// - plus command-line options
 ///
 /// In that order.
@@ -77,6 +140,11 @@ pub struct Configuration {
     #[serde(default)]
     pub replay_proxy: String,
 
+    /// Ignore the proxy in HTTP_PROXY, HTTPS_PROXY, and ALL_PROXY, which is otherwise used (for
+    /// hosts not in NO_PROXY) when there's no --proxy
+    #[serde(default)]
+    pub no_env_proxy: bool,
+
     /// The target URL
     #[serde(default)]
     pub target_url: String,
@@ -101,6 +169,21 @@ pub struct Configuration {
     #[serde(skip)]
     pub replay_client: Option<Client>,
 
+    /// Clients that share scan requests with `client` (--streams-per-connection), each with its
+    /// own connection pool
+    #[serde(skip)]
+    pub stream_clients: Vec<Client>,
+
+    /// Client without the credentials found in `headers`, used to request endpoints a second
+    /// time (--auth-diff)
+    #[serde(skip)]
+    pub anonymous_client: Option<Client>,
+
+    /// --host-header pin, --scope, --request-limit counts, etc shared by the clients above, see
+    /// [RequestContext](../client/struct.RequestContext.html)
+    #[serde(skip)]
+    pub request_context: Arc<client::RequestContext>,
+
     /// Number of concurrent threads (default: 50)
     #[serde(default = "threads")]
     pub threads: usize,
@@ -141,6 +224,10 @@ pub struct Configuration {
     #[serde(default)]
     pub json: bool,
 
+    /// Write failed requests, by category, to the output file and event stream as error records
+    #[serde(default)]
+    pub error_records: bool,
+
     /// Output file to write results to (default: stdout)
     #[serde(default)]
     pub output: String,
@@ -170,6 +257,11 @@ pub struct Configuration {
     #[serde(default)]
     pub headers: HashMap<String, String>,
 
+    /// Host name sent (in the Host header and tls SNI) with requests to a target given as an ip
+    /// address; the target's ip is still the one connected to
+    #[serde(default)]
+    pub host_header: String,
+
     /// URL query parameters
     #[serde(default)]
     pub queries: Vec<(String, String)>,
@@ -182,6 +274,113 @@ pub struct Configuration {
     #[serde(default)]
     pub extract_links: bool,
 
+    /// Parse html forms found in responses and report them as findings
+    #[serde(default)]
+    pub extract_forms: bool,
+
+    /// Report html/javascript comments that match interesting patterns as findings
+    #[serde(default)]
+    pub extract_comments: bool,
+
+    /// Sources of known urls used to seed a scan before brute forcing begins (i.e. `wayback`)
+    #[serde(default)]
+    pub seed_from: Vec<String>,
+
+    /// Identify the technologies used by each target and report them as findings
+    #[serde(default)]
+    pub fingerprint: bool,
+
+    /// Parse auto-generated directory listings, adding their contents to results/recursion
+    #[serde(default)]
+    pub parse_listings: bool,
+
+    /// Don't use the wordlist against directories whose contents came from a directory listing
+    #[serde(default)]
+    pub skip_listed_dirs: bool,
+
+    /// Probe for OpenAPI/Swagger specs and request every documented path
+    #[serde(default)]
+    pub discover_openapi: bool,
+
+    /// Send an introspection query to likely GraphQL endpoints
+    #[serde(default)]
+    pub graphql_probe: bool,
+
+    /// Request the source map of each javascript file and report the original sources it lists
+    #[serde(default)]
+    pub source_maps: bool,
+
+    /// Search response bodies for secrets (api keys, private keys, JWTs, etc)
+    #[serde(default)]
+    pub find_secrets: bool,
+
+    /// Collect email addresses and probable usernames and report them once the scan ends
+    #[serde(default)]
+    pub harvest: bool,
+
+    /// Send batches of common parameter names to discovered endpoints to find the ones they accept
+    #[serde(default)]
+    pub mine_params: bool,
+
+    /// Request well-known VCS/IDE artifacts (.git/HEAD, .env, etc) from every scanned directory
+    #[serde(default)]
+    pub check_artifacts: bool,
+
+    /// Summarize missing/misconfigured security headers for each host once the scan ends
+    #[serde(default)]
+    pub check_headers: bool,
+
+    /// send an Origin probe to each discovered endpoint and report permissive CORS policies
+    #[serde(default)]
+    pub cors_probe: bool,
+
+    /// attempt a websocket handshake with each discovered endpoint and report the ones that accept
+    #[serde(default)]
+    pub ws_probe: bool,
+
+    /// request each discovered endpoint again without credentials and report the ones whose
+    /// responses change
+    #[serde(default)]
+    pub auth_diff: bool,
+
+    /// send an OPTIONS request to each discovered directory and report the methods it allows
+    #[serde(default)]
+    pub check_methods: bool,
+
+    /// request variations of the names of discovered resources (admin -> admin2, admin_old)
+    #[serde(default)]
+    pub permute: bool,
+
+    /// rules used by `permute`, where `{word}` is the discovered name and `{year}` each of the
+    /// last few years; the built-in rules are used when empty
+    #[serde(default)]
+    pub permutation_rules: Vec<String>,
+
+    /// enumerate the 8.3 short names in each directory of IIS targets, and request the words of
+    /// the wordlist that match them
+    #[serde(default)]
+    pub iis_shortnames: bool,
+
+    /// redact sensitive values (auth headers, tokens, proxy passwords) from the config written by --save-config
+    #[serde(default)]
+    pub redact_secrets: bool,
+
+    /// don't append bundled product wordlists for technologies identified by --fingerprint
+    #[serde(default)]
+    pub no_product_wordlists: bool,
+
+    /// File used to compare the content of discovered urls with that of previous runs
+    #[serde(default)]
+    pub track_changes: String,
+
+    /// File to which the fully resolved configuration is written, as TOML
+    #[serde(default)]
+    pub save_config: String,
+
+    /// Additional secret detection rules, in the form `NAME=REGEX`
+    #[serde(default)]
+    pub secret_patterns: Vec<String>,
+
     /// Append / to each request
     #[serde(default)]
     pub add_slash: bool,
@@ -206,6 +405,20 @@ pub struct Configuration {
     #[serde(default)]
     pub rate_limit: usize,
 
+    /// Most requests sent to any single host over the whole run; a limit of 0 means no limit
+    #[serde(default)]
+    pub request_limit: usize,
+
+    /// Number of 429s in a row after which a host is paused for `cooldown`; 0 means hosts are
+    /// only paused when they say for how long (Retry-After)
+    #[serde(default)]
+    pub max_429s: usize,
+
+    /// How long a host is paused after `max_429s` 429s in a row (ex: 30s, 2m); empty means 30
+    /// seconds
+    #[serde(default)]
+    pub cooldown: String,
+
     /// Filter out messages of a particular size
     #[serde(default)]
     pub filter_size: Vec<u64>,
@@ -252,6 +465,252 @@ pub struct Configuration {
     /// URLs that should never be scanned/recursed into
     #[serde(default)]
     pub url_denylist: Vec<String>,
+
+    /// Regular expressions matched against every url right before it's requested; matching urls
+    /// are never requested
+    #[serde(default)]
+    pub deny_url_regex: Vec<String>,
+
+    /// Hosts (example.com), domains (*.example.com), and ip ranges (10.0.0.0/24) that links found
+    /// in responses and redirects may lead to, beyond the host they were found on; rules starting
+    /// with a ! are never in scope
+    #[serde(default)]
+    pub scope: Vec<String>,
+
+    /// Number of responses sharing a status code, word count, and line count that are shown
+    /// before the rest are automatically muted; a threshold of 0 disables clustering
+    #[serde(default)]
+    pub cluster_threshold: usize,
+
+    /// Address on which to accept worker connections; the scan is sharded across the workers
+    /// instead of being run locally
+    #[serde(default)]
+    pub coordinate: String,
+
+    /// Secret workers must prove they know before being sent the configuration; never written
+    /// to config or state files, nor sent to workers
+    #[serde(skip)]
+    pub coordinate_secret: String,
+
+    /// Number of words sent to a worker at a time when coordinating a distributed scan
+    #[serde(default = "shard_size")]
+    pub shard_size: usize,
+
+    /// Format of log messages, either `text` or `json` (one JSON object per line)
+    #[serde(default = "log_format")]
+    pub log_format: String,
+
+    /// Placed between each group of three digits in displayed counts; `locale` derives it from
+    /// the environment's locale
+    #[serde(default)]
+    pub thousands_separator: String,
+
+    /// Units used when displaying sizes, one of `bytes`, `si` (kB, MB), or `iec` (KiB, MiB)
+    #[serde(default = "byte_units")]
+    pub byte_units: String,
+
+    /// Paths that must be discovered by the scan, checked once the scan completes
+    #[serde(default)]
+    pub expect_found: Vec<String>,
+
+    /// Paths that must not be discovered by the scan, checked once the scan completes
+    #[serde(default)]
+    pub expect_absent: Vec<String>,
+
+    /// Paths (or `finding:CATEGORY`s) that end the scan as soon as a result matches one of them
+    #[serde(default)]
+    pub stop_on_match: Vec<String>,
+
+    /// Grow and shrink each scan's concurrency based on the target's responses, using `threads`
+    /// as the maximum
+    #[serde(default)]
+    pub adaptive_threads: bool,
+
+    /// How urls that were already seen are tracked, one of `exact` or `bloom` (a bloom filter,
+    /// which uses far less memory, but occasionally skips a url that wasn't seen)
+    #[serde(default = "seen_urls")]
+    pub seen_urls: String,
+
+    /// Compile the wordlist into a binary cache on first use and load it from there afterwards
+    #[serde(default)]
+    pub wordlist_cache: bool,
+
+    /// Number of seconds dns answers are cached for, regardless of their TTL; `0` respects each
+    /// answer's TTL
+    #[serde(default)]
+    pub dns_ttl: u64,
+
+    /// Stop reading a response's body after this many bytes; `0` reads bodies in full
+    #[serde(default)]
+    pub max_response_size: u64,
+
+    /// Send HEAD requests instead of GET, skipping response bodies entirely
+    #[serde(default)]
+    pub head_only: bool,
+
+    /// Record how long each request took to be answered, shown with each result and written to
+    /// json output
+    #[serde(default)]
+    pub response_time: bool,
+
+    /// Number of requests to keep in flight over each http/2 connection; `0` multiplexes
+    /// everything for a host over a single connection
+    #[serde(default)]
+    pub streams_per_connection: usize,
+
+    /// Pause new requests while resident memory is over this many bytes; `0` means no limit
+    #[serde(default)]
+    pub memory_limit: u64,
+
+    /// Time out requests to each host after this many times its response time during the
+    /// heuristic checks, capped by `timeout`; `0` uses `timeout` for every host
+    #[serde(default)]
+    pub adaptive_timeout: u32,
+
+    /// Cap on how many bytes of response bodies are downloaded per second, across all requests;
+    /// `0` means no cap
+    #[serde(default)]
+    pub throttle_bytes: u64,
+
+    /// Number of os threads that run the async runtime; `0` uses one per cpu core
+    #[serde(default)]
+    pub async_workers: usize,
+
+    /// Maximum number of os threads the async runtime keeps for blocking work; `0` uses the
+    /// runtime's default
+    #[serde(default)]
+    pub blocking_threads: usize,
+
+    /// Jaeger agent (`jaeger://HOST:PORT`) or OTLP collector (`http://HOST:PORT`) that spans are
+    /// exported to; only available with the `trace` feature
+    #[serde(default)]
+    pub trace_output: String,
+
+    /// Milliseconds between progress bar redraws; `0` uses indicatif's default (~15 per second)
+    #[serde(default)]
+    pub refresh_rate: u64,
+
+    /// How often the scan's state is written to disk while scanning, expressed the same way as
+    /// `time_limit`; empty means no checkpoints on a timer
+    #[serde(default)]
+    pub checkpoint_interval: String,
+
+    /// Number of requests between writes of the scan's state to disk; `0` means no checkpoints
+    /// based on request count
+    #[serde(default)]
+    pub checkpoint_requests: u64,
+
+    /// File to which reported responses are written as Burp Suite items, along with the requests
+    /// that produced them
+    #[serde(default)]
+    pub burp_output: String,
+
+    /// Base url of a running OWASP ZAP instance's api, to which every result is sent
+    #[serde(default)]
+    pub zap_url: String,
+
+    /// API key used when talking to `zap_url`
+    #[serde(default)]
+    pub zap_api_key: String,
+
+    /// Whether ZAP starts an active scan against every result it's sent
+    #[serde(default)]
+    pub zap_active_scan: bool,
+
+    /// Where every event of the scan is written as NDJSON while it happens: `unix:PATH`,
+    /// `tcp:HOST:PORT`, or the path of a file/FIFO
+    #[serde(default)]
+    pub event_stream: String,
+
+    /// Identifier of this run, stamped on every json record written; generated when not given
+    #[serde(default)]
+    pub scan_id: String,
+
+    /// Header in which `scan_id` is sent with every request
+    #[serde(default)]
+    pub scan_id_header: String,
+
+    /// File to which results and findings are written in DefectDojo's generic findings import
+    /// format
+    #[serde(default)]
+    pub defectdojo_output: String,
+
+    /// Directory in which the raw request and response behind each finding of at least
+    /// `evidence_severity` are saved
+    #[serde(default)]
+    pub evidence_dir: String,
+
+    /// Least severe finding whose evidence is saved to `evidence_dir`; one of info, low, medium,
+    /// high, or critical
+    #[serde(default = "evidence_severity")]
+    pub evidence_severity: String,
+
+    /// Format of results written to `output`, either `text` or `urls-with-meta`
+    /// (`url [status] [content-type]` lines, as read by nuclei and httpx)
+    #[serde(default = "output_format")]
+    pub output_format: String,
+
+    /// Whether urls written to `output` are also grouped into one file per technology
+    /// identified on the target they were found on
+    #[serde(default)]
+    pub bucket_by_tech: bool,
+
+    /// File of known urls (one per line, i.e. gau/katana/waybackurls output) used to seed a scan
+    /// before brute forcing begins
+    #[serde(default)]
+    pub seed_file: String,
+
+    /// Whether requests are sent through a local Tor daemon's SOCKS proxy
+    #[serde(default)]
+    pub tor: bool,
+
+    /// Address of the Tor daemon's control port, used to ask for a new identity when the exit
+    /// node looks blocked
+    #[serde(default)]
+    pub tor_control: String,
+
+    /// Password used to authenticate with `tor_control`; the authentication cookie is tried
+    /// when empty
+    #[serde(default)]
+    pub tor_control_password: String,
+
+    /// Company names whose cloud storage buckets are looked for instead of scanning a target
+    #[serde(default)]
+    pub buckets: Vec<String>,
+
+    /// Names of bundled wordlists whose words are scanned along with (or, without a wordlist,
+    /// instead of) the wordlist's
+    #[serde(default)]
+    pub presets: Vec<String>,
+
+    /// Number of links followed while crawling each target for words to add to the wordlist;
+    /// 0 means targets aren't crawled
+    #[serde(default)]
+    pub crawl_wordlist: usize,
+
+    /// Headers (`NAME:@FILE`) whose value is the next line of the file on every request
+    #[serde(default)]
+    pub rotate_headers: Vec<String>,
+
+    /// Request that logs in before scanning, and again whenever the session expires; only read
+    /// from a config file's `[login]` table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub login: Option<Login>,
+
+    /// Page a csrf token is read from, and the field it's sent back in, for requests with a form
+    /// body; only read from a config file's `[csrf]` table
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub csrf: Option<Csrf>,
+
+    /// Wordlists used instead of `wordlist` for the directories whose path matches a pattern;
+    /// only read from a config file's `[[wordlist_map]]` tables
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub wordlist_map: Vec<WordlistMapping>,
+
+    /// Address of the rate limiter shared by the scans of a recipe; set by `feroxbuster recipe`
+    /// in the config file handed to each of its scans
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub recipe_rate_limit: String,
 }
 
 impl Default for Configuration {
@@ -259,8 +718,17 @@ impl Default for Configuration {
     fn default() -> Self {
         let timeout = timeout();
         let user_agent = user_agent();
-        let client = client::initialize(timeout, &user_agent, false, false, &HashMap::new(), None)
-            .expect("Could not build client");
+        let request_context = Arc::default();
+        let client = client::initialize(
+            timeout,
+            &user_agent,
+            false,
+            false,
+            &HashMap::new(),
+            None,
+            &request_context,
+        )
+        .expect("Could not build client");
         let replay_client = None;
         let status_codes = status_codes();
         let replay_codes = status_codes.clone();
@@ -276,6 +744,9 @@ impl Default for Configuration {
             replay_codes,
             status_codes,
             replay_client,
+            stream_clients: Vec::new(),
+            anonymous_client: None,
+            request_context,
             requester_policy,
             dont_filter: false,
             auto_bail: false,
@@ -286,15 +757,96 @@ impl Default for Configuration {
             resumed: false,
             stdin: false,
             json: false,
+            error_records: false,
             verbosity: 0,
             scan_limit: 0,
             parallel: 0,
             rate_limit: 0,
+            request_limit: 0,
+            max_429s: 0,
+            cooldown: String::new(),
+            cluster_threshold: 0,
+            coordinate: String::new(),
+            coordinate_secret: String::new(),
+            shard_size: shard_size(),
+            log_format: log_format(),
+            thousands_separator: String::new(),
+            byte_units: byte_units(),
+            expect_found: Vec::new(),
+            expect_absent: Vec::new(),
+            stop_on_match: Vec::new(),
+            adaptive_threads: false,
+            seen_urls: seen_urls(),
+            wordlist_cache: false,
+            dns_ttl: 0,
+            max_response_size: 0,
+            head_only: false,
+            response_time: false,
+            streams_per_connection: 0,
+            memory_limit: 0,
+            adaptive_timeout: 0,
+            throttle_bytes: 0,
+            async_workers: 0,
+            blocking_threads: 0,
+            trace_output: String::new(),
+            refresh_rate: 0,
+            checkpoint_interval: String::new(),
+            checkpoint_requests: 0,
+            burp_output: String::new(),
+            zap_url: String::new(),
+            zap_api_key: String::new(),
+            zap_active_scan: false,
+            event_stream: String::new(),
+            scan_id: String::new(),
+            scan_id_header: String::new(),
+            defectdojo_output: String::new(),
+            evidence_dir: String::new(),
+            evidence_severity: evidence_severity(),
+            output_format: output_format(),
+            bucket_by_tech: false,
+            seed_file: String::new(),
+            tor: false,
+            tor_control: String::new(),
+            tor_control_password: String::new(),
+            buckets: Vec::new(),
+            presets: Vec::new(),
+            crawl_wordlist: 0,
+            rotate_headers: Vec::new(),
+            login: None,
+            csrf: None,
+            wordlist_map: Vec::new(),
+            recipe_rate_limit: String::new(),
             add_slash: false,
             insecure: false,
             redirects: false,
             no_recursion: false,
             extract_links: false,
+            extract_forms: false,
+            extract_comments: false,
+            seed_from: Vec::new(),
+            fingerprint: false,
+            parse_listings: false,
+            skip_listed_dirs: false,
+            discover_openapi: false,
+            graphql_probe: false,
+            source_maps: false,
+            find_secrets: false,
+            harvest: false,
+            mine_params: false,
+            check_artifacts: false,
+            check_headers: false,
+            cors_probe: false,
+            ws_probe: false,
+            auth_diff: false,
+            check_methods: false,
+            permute: false,
+            permutation_rules: Vec::new(),
+            iis_shortnames: false,
+            redact_secrets: false,
+            no_product_wordlists: false,
+            track_changes: String::new(),
+            save_config: String::new(),
+            secret_patterns: Vec::new(),
             save_state: true,
             proxy: String::new(),
             config: String::new(),
@@ -304,16 +856,20 @@ impl Default for Configuration {
             time_limit: String::new(),
             resume_from: String::new(),
             replay_proxy: String::new(),
+            no_env_proxy: false,
             queries: Vec::new(),
             extensions: Vec::new(),
             filter_size: Vec::new(),
             filter_regex: Vec::new(),
             url_denylist: Vec::new(),
+            deny_url_regex: Vec::new(),
+            scope: Vec::new(),
             filter_line_count: Vec::new(),
             filter_word_count: Vec::new(),
             filter_status: Vec::new(),
             filter_similar: Vec::new(),
             headers: HashMap::new(),
+            host_header: String::new(),
             depth: depth(),
             threads: threads(),
             wordlist: wordlist(),
@@ -347,24 +903,109 @@ impl Configuration {
     /// - **insecure**: `false` (don't be insecure, i.e. don't allow invalid certs)
     /// - **extensions**: `None`
     /// - **url_denylist**: `None`
+    /// - **deny_url_regex**: `None`
+    /// - **scope**: `None` (links and redirects stay on the host they were found on)
     /// - **filter_size**: `None`
     /// - **filter_similar**: `None`
     /// - **filter_regex**: `None`
     /// - **filter_word_count**: `None`
     /// - **filter_line_count**: `None`
     /// - **headers**: `None`
+    /// - **host_header**: `None` (requests carry the host in the target url)
     /// - **queries**: `None`
     /// - **no_recursion**: `false` (recursively scan enumerated sub-directories)
     /// - **add_slash**: `false`
     /// - **stdin**: `false`
     /// - **json**: `false`
+    /// - **error_records**: `false`
     /// - **dont_filter**: `false` (auto filter wildcard responses)
     /// - **depth**: `4` (maximum recursion depth)
     /// - **scan_limit**: `0` (no limit on concurrent scans imposed)
     /// - **parallel**: `0` (no limit on parallel scans imposed)
     /// - **rate_limit**: `0` (no limit on requests per second imposed)
+    /// - **request_limit**: `0` (no limit on total requests per host imposed)
+    /// - **max_429s**: `0` (hosts aren't cooled down after repeated 429s)
+    /// - **cooldown**: `None` (a cool-down lasts 30 seconds)
     /// - **time_limit**: `None` (no limit on length of scan imposed)
+    /// - **cluster_threshold**: `0` (no automatic muting of response clusters)
+    /// - **coordinate**: `None` (the scan runs locally instead of on workers)
+    /// - **coordinate_secret**: `None` (only loopback addresses may be coordinated on)
+    /// - **shard_size**: `1000` (words sent to a worker at a time)
+    /// - **log_format**: `text` (colored, human readable log messages)
+    /// - **thousands_separator**: `None` (counts aren't grouped)
+    /// - **byte_units**: `bytes` (sizes are shown as a plain number of bytes)
+    /// - **expect_found**: `None`
+    /// - **expect_absent**: `None`
+    /// - **stop_on_match**: `None` (scans run through the whole wordlist)
+    /// - **adaptive_threads**: `false`
+    /// - **seen_urls**: `exact` (every seen url is kept in memory)
+    /// - **wordlist_cache**: `false` (the wordlist is parsed on every run)
+    /// - **dns_ttl**: `0` (dns answers are cached for as long as their TTL allows)
+    /// - **max_response_size**: `0` (response bodies are read in full)
+    /// - **head_only**: `false` (GET requests; bodies are downloaded)
+    /// - **response_time**: `false` (response times aren't recorded per result)
+    /// - **streams_per_connection**: `0` (http/2 requests share one connection per host)
+    /// - **memory_limit**: `0` (no limit on resident memory)
+    /// - **adaptive_timeout**: `0` (every host gets the same timeout)
+    /// - **throttle_bytes**: `0` (downloads aren't capped)
+    /// - **async_workers**: `0` (one runtime worker per cpu core)
+    /// - **blocking_threads**: `0` (tokio's default blocking pool size)
+    /// - **trace_output**: `None` (spans aren't exported)
+    /// - **refresh_rate**: `0` (progress bars are redrawn about 15 times a second)
+    /// - **checkpoint_interval**: `None` (state is only saved on ctrl+c)
+    /// - **checkpoint_requests**: `0` (state is only saved on ctrl+c)
+    /// - **burp_output**: `None` (no burp items are written)
+    /// - **zap_url**: `None` (results aren't sent to ZAP)
+    /// - **zap_api_key**: `None`
+    /// - **zap_active_scan**: `false` (ZAP only adds results to its sites tree)
+    /// - **event_stream**: `None` (events aren't streamed anywhere)
+    /// - **scan_id**: `None` (a random uuid is generated for each run)
+    /// - **scan_id_header**: `None` (the scan id isn't sent with requests)
+    /// - **defectdojo_output**: `None` (no DefectDojo import file is written)
+    /// - **evidence_dir**: `None` (no evidence is saved)
+    /// - **evidence_severity**: `medium`
+    /// - **output_format**: `text` (same as what's printed to the terminal, or json with --json)
+    /// - **bucket_by_tech**: `false` (urls aren't grouped by technology)
+    /// - **seed_file**: `None` (no urls from other tools are requested)
+    /// - **tor**: `false` (requests don't go through Tor)
+    /// - **tor_control**: `None` (the exit node is never changed)
+    /// - **tor_control_password**: `None` (cookie authentication, if any)
+    /// - **buckets**: `None` (targets are scanned, not cloud buckets)
+    /// - **presets**: `None` (only the wordlist's words are scanned)
+    /// - **crawl_wordlist**: `0` (targets aren't crawled for words)
+    /// - **rotate_headers**: `None` (every request sends the same headers)
+    /// - **login**: `None` (no session is established)
+    /// - **csrf**: `None` (form bodies are sent without a csrf token)
+    /// - **wordlist_map**: `None` (every directory is scanned with `wordlist`)
+    /// - **recipe_rate_limit**: `None` (requests aren't limited by a recipe)
+    /// - **extract_forms**: `false` (don't report html forms)
+    /// - **extract_comments**: `false` (don't report html/javascript comments)
+    /// - **seed_from**: `None` (no historical urls are requested)
+    /// - **fingerprint**: `false` (don't identify target technologies)
+    /// - **parse_listings**: `false` (directory listings are treated like any other response)
+    /// - **skip_listed_dirs**: `false` (always brute force directories)
+    /// - **discover_openapi**: `false` (don't look for api specs)
+    /// - **graphql_probe**: `false` (no introspection queries are sent)
+    /// - **source_maps**: `false` (source maps aren't requested)
+    /// - **find_secrets**: `false` (bodies aren't searched for secrets)
+    /// - **harvest**: `false` (emails/usernames aren't collected)
+    /// - **mine_params**: `false` (endpoints aren't mined for parameters)
+    /// - **check_artifacts**: `false` (artifacts are only found via the wordlist)
+    /// - **check_headers**: `false` (security headers aren't checked)
+    /// - **cors_probe**: `false` (CORS policies aren't probed)
+    /// - **ws_probe**: `false` (websocket upgrades aren't attempted)
+    /// - **auth_diff**: `false` (endpoints are only requested with credentials)
+    /// - **check_methods**: `false` (allowed methods aren't enumerated)
+    /// - **permute**: `false` (names of discovered resources aren't permuted)
+    /// - **permutation_rules**: `None` (the built-in rules are used)
+    /// - **iis_shortnames**: `false` (short names aren't enumerated)
+    /// - **redact_secrets**: `false` (saved configs contain every value as-is)
+    /// - **no_product_wordlists**: `false` (product wordlists are appended)
+    /// - **track_changes**: `None` (content isn't compared between runs)
+    /// - **save_config**: `None` (the resolved configuration isn't saved)
+    /// - **secret_patterns**: `None` (only the built-in secret rules are used)
     /// - **replay_proxy**: `None` (no limit on concurrent scans imposed)
+    /// - **no_env_proxy**: `false` (HTTP_PROXY, HTTPS_PROXY, and ALL_PROXY are used)
     /// - **replay_codes**: [`DEFAULT_RESPONSE_CODES`](constant.DEFAULT_RESPONSE_CODES.html)
     ///
     /// After which, any values defined in a
@@ -378,12 +1019,19 @@ impl Configuration {
     /// - The user's current working directory
     ///
     /// If more than one valid configuration file is found, each one overwrites the values found previously.
+    /// When `--config FILE` is used, only the given file is read and the locations above are skipped.
     ///
-    /// Finally, any options/arguments given on the commandline will override both built-in and
-    /// config-file specified values.
+    /// Next, any `FEROX_*` environment variables (i.e. `FEROX_THREADS`) override the config-file
+    /// specified values; see `parse_env_vars` for the expected format.
+    ///
+    /// Finally, any options/arguments given on the commandline will override built-in,
+    /// config-file, and environment specified values.
     ///
     /// The resulting [Configuration](struct.Configuration.html) is a singleton with a `static`
     /// lifetime.
+    ///
+    /// Only a plain scan has a Configuration of its own; see `from_command_line` for everything
+    /// else the command line can ask for.
     pub fn new() -> Result<Self> {
         // when compiling for test, we want to eliminate the runtime dependency of the parser
         if cfg!(test) {
@@ -394,25 +1042,101 @@ impl Configuration {
             return Ok(test_config);
         }
 
-        let args = parser::initialize().get_matches();
+        match Self::from_command_line()? {
+            Invocation::Scan(config) => Ok(*config),
+            _ => bail!("Expected a scan, but the command line asked for something else"),
+        }
+    }
+
+    /// Parse the command line and determine what it asks for; scans (including those of a
+    /// recipe or watch) come with their resolved configuration, as described in `new`
+    ///
+    /// nothing is run and the process isn't exited here, that's left to the caller
+    pub fn from_command_line() -> Result<Invocation> {
+        let mut args = match parser::initialize().get_matches_safe() {
+            Ok(args) => args,
+            Err(e) => return Ok(Invocation::Usage(e)),
+        };
+
+        if args.is_present("wizard") {
+            // everything else given on the command line is kept and the wizard's answers are
+            // added to it
+            let existing: Vec<String> = env::args()
+                .skip(1)
+                .filter(|arg| arg != "--wizard")
+                .collect();
+
+            let input = stdin();
+            let outcome = wizard::run(input.lock(), stdout(), &existing)?;
+
+            if !outcome.launch {
+                return Ok(Invocation::Nothing);
+            }
+
+            let wizard_args = once(String::from("feroxbuster")).chain(outcome.args);
+            args = match parser::initialize().get_matches_from_safe(wizard_args) {
+                Ok(args) => args,
+                Err(e) => return Ok(Invocation::Usage(e)),
+            };
+        }
+
+        // subcommands that don't scan with the resolved configuration
+        if let Some(serve_args) = args.subcommand_matches("serve") {
+            return Ok(Invocation::Serve(serve_args.clone()));
+        }
+
+        if let Some(worker_args) = args.subcommand_matches("worker") {
+            return Ok(Invocation::Worker(worker_args.clone()));
+        }
+
+        if let Some(bench_args) = args.subcommand_matches("bench") {
+            return Ok(Invocation::Bench(bench_args.clone()));
+        }
+
+        if args.subcommand_matches("wordlists").is_some() {
+            return Ok(Invocation::Wordlists(args));
+        }
+
+        if args
+            .subcommand_matches("config")
+            .and_then(|config| config.subcommand_matches("check"))
+            .is_some()
+        {
+            // validate everything, instead of stopping at the first problem
+            return Ok(Invocation::ConfigCheck(Self::check(&args)));
+        }
 
         // Get the default configuration, this is what will apply if nothing
         // else is specified.
         let mut config = Configuration::default();
 
-        // read in all config files
-        Self::parse_config_files(&mut config)?;
+        // read in all config files, or only the one given by --config
+        if let Some(config_file) = args.value_of("config") {
+            let path = PathBuf::from(config_file);
+
+            if !path.exists() {
+                bail!(fmt_err(&format!("Config file {} not found", config_file)));
+            }
+
+            Self::parse_and_merge_config(path, &mut config)?;
+        } else {
+            Self::parse_config_files(&mut config)?;
+        }
+
+        // environment variables sit between the config files and the command line
+        let env_config = Self::parse_env_vars(env::vars())?;
+        Self::merge_config(&mut config, env_config);
 
         // read in the user provided options, this produces a separate instance of Configuration
         // in order to allow for potentially merging into a --resume-from Configuration
-        let cli_config = Self::parse_cli_args(&args);
+        let cli_config = Self::parse_cli_args(&args)?;
 
         // --resume-from used, need to first read the Configuration from disk, and then
         // merge the cli_config into the resumed config
         if let Some(filename) = args.value_of("resume_from") {
             // when resuming a scan, instead of normal configuration loading, we just
             // load the config from disk by calling resume_scan
-            let mut previous_config = resume_scan(filename);
+            let mut previous_config = resume_scan(filename)?;
 
             // if any other arguments were passed on the command line, the theory is that the
             // user meant to modify the previously cancelled/saved scan in some way that we
@@ -430,19 +1154,30 @@ impl Configuration {
             previous_config.stdin = false;
 
             // clients aren't serialized, have to remake them from the previous config
-            Self::try_rebuild_clients(&mut previous_config);
+            Self::try_rebuild_clients(&mut previous_config)?;
 
-            return Ok(previous_config);
+            return Ok(Invocation::Scan(Box::new(previous_config)));
         }
 
         // if we've gotten to this point in the code, --resume-from was not used, so we need to
         // merge the cli options into the config file options and return the result
         Self::merge_config(&mut config, cli_config);
 
+        if let Some(recipe_args) = args.subcommand_matches("recipe") {
+            // everything gathered so far is the base that each of the recipe's scans builds upon
+            return Ok(Invocation::Recipe(Box::new(config), recipe_args.clone()));
+        }
+
+        if let Some(watch_args) = args.subcommand_matches("watch") {
+            return Ok(Invocation::Watch(Box::new(config), watch_args.clone()));
+        }
+
+        Self::resolve_wordlist(&mut config);
+
         // rebuild clients is the last step in either code branch
-        Self::try_rebuild_clients(&mut config);
+        Self::try_rebuild_clients(&mut config)?;
 
-        Ok(config)
+        Ok(Invocation::Scan(Box::new(config)))
     }
 
     /// Parse all possible versions of the ferox-config.toml file, adhering to the order of
@@ -452,92 +1187,649 @@ impl Configuration {
         // therein to overwrite our default values. Deserialized defaults are specified
         // in the Configuration struct so that we don't change anything that isn't
         // actually specified in the config file
-        //
-        // search for a config using the following order of precedence
-        //   - /etc/feroxbuster/
-        //   - CONFIG_DIR/ferxobuster/
-        //   - same directory as feroxbuster executable
-        //   - current directory
-
-        // merge a config found at /etc/feroxbuster/ferox-config.toml
-        let config_file = PathBuf::new()
+        for config_file in Self::config_file_locations()? {
+            Self::parse_and_merge_config(config_file, &mut config)?;
+        }
+
+        Ok(())
+    }
+
+    /// All locations searched for a ferox-config.toml file, in order of precedence (lowest first)
+    ///
+    ///   - /etc/feroxbuster/
+    ///   - CONFIG_DIR/ferxobuster/
+    ///   - same directory as feroxbuster executable
+    ///   - current directory
+    fn config_file_locations() -> Result<Vec<PathBuf>> {
+        // a config found at /etc/feroxbuster/ferox-config.toml
+        let etc_file = PathBuf::new()
             .join("/etc/feroxbuster")
             .join(DEFAULT_CONFIG_NAME);
-        Self::parse_and_merge_config(config_file, &mut config)?;
 
-        // merge a config found at ~/.config/feroxbuster/ferox-config.toml
+        // a config found at ~/.config/feroxbuster/ferox-config.toml
         // config_dir() resolves to one of the following
         //   - linux: $XDG_CONFIG_HOME or $HOME/.config
         //   - macOS: $HOME/Library/Application Support
         //   - windows: {FOLDERID_RoamingAppData}
         let config_dir = dirs::config_dir().ok_or_else(|| anyhow!("Couldn't load config"))?;
-        let config_file = config_dir.join("feroxbuster").join(DEFAULT_CONFIG_NAME);
-        Self::parse_and_merge_config(config_file, &mut config)?;
+        let config_dir_file = config_dir.join("feroxbuster").join(DEFAULT_CONFIG_NAME);
 
-        // merge a config found in same the directory as feroxbuster executable
+        // a config found in same the directory as feroxbuster executable
         let exe_path = current_exe()?;
         let bin_dir = exe_path
             .parent()
             .ok_or_else(|| anyhow!("Couldn't load config"))?;
-        let config_file = bin_dir.join(DEFAULT_CONFIG_NAME);
-        Self::parse_and_merge_config(config_file, &mut config)?;
+        let bin_dir_file = bin_dir.join(DEFAULT_CONFIG_NAME);
 
-        // merge a config found in the user's current working directory
+        // a config found in the user's current working directory
         let cwd = current_dir()?;
-        let config_file = cwd.join(DEFAULT_CONFIG_NAME);
-        Self::parse_and_merge_config(config_file, &mut config)?;
+        let cwd_file = cwd.join(DEFAULT_CONFIG_NAME);
 
-        Ok(())
+        Ok(vec![etc_file, config_dir_file, bin_dir_file, cwd_file])
     }
 
-    /// Given a set of ArgMatches read from the CLI, update and return the default Configuration
-    /// settings
-    fn parse_cli_args(args: &ArgMatches) -> Self {
-        let mut config = Configuration::default();
+    /// Given a set of environment variables, update and return the default Configuration settings
+    ///
+    /// every serialized field can be set using its uppercased name, prefixed with `FEROX_`, i.e.
+    /// `FEROX_THREADS=20` or `FEROX_PROXY=http://127.0.0.1:8080`. values are interpreted based on
+    /// the type of the field they're setting:
+    /// - booleans accept `true`/`false`, `1`/`0`, `yes`/`no`, and `on`/`off`
+    /// - lists are comma separated, i.e. `FEROX_STATUS_CODES=200,301`; queries are given as
+    ///   `name=value` pairs
+    /// - headers are comma separated `Name: value` pairs, i.e. `FEROX_HEADERS="Api: abc, Stuff: things"`
+    /// - lists and headers may also be given as JSON, i.e. `FEROX_HEADERS='{"Api": "a,b"}'`
+    ///
+    /// variables without the prefix, or that don't name a known field, are ignored
+    pub(super) fn parse_env_vars<I>(vars: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let fields = match serde_json::to_value(Configuration::default())? {
+            Value::Object(fields) => fields,
+            _ => bail!("Could not determine configuration fields"),
+        };
+
+        let mut settings = Map::new();
+
+        for (name, raw) in vars {
+            let key = match name.strip_prefix(ENV_PREFIX) {
+                Some(key) => key.to_ascii_lowercase(),
+                None => continue,
+            };
 
-        update_config_if_present!(&mut config.threads, args, "threads", usize);
-        update_config_if_present!(&mut config.depth, args, "depth", usize);
-        update_config_if_present!(&mut config.scan_limit, args, "scan_limit", usize);
-        update_config_if_present!(&mut config.parallel, args, "parallel", usize);
-        update_config_if_present!(&mut config.rate_limit, args, "rate_limit", usize);
-        update_config_if_present!(&mut config.wordlist, args, "wordlist", String);
-        update_config_if_present!(&mut config.output, args, "output", String);
-        update_config_if_present!(&mut config.debug_log, args, "debug_log", String);
-        update_config_if_present!(&mut config.time_limit, args, "time_limit", String);
-        update_config_if_present!(&mut config.resume_from, args, "resume_from", String);
+            let default = match fields.get(&key) {
+                Some(default) if key != "type" => default,
+                _ => {
+                    log::debug!("{} doesn't correspond to a configuration field", name);
+                    continue;
+                }
+            };
 
-        if let Some(arg) = args.values_of("status_codes") {
-            config.status_codes = arg
-                .map(|code| {
-                    StatusCode::from_bytes(code.as_bytes())
-                        .unwrap_or_else(|e| report_and_exit(&e.to_string()))
-                        .as_u16()
+            // the first interpretation of the raw value that's valid for the field wins
+            let value = env_candidates(&raw, default)
+                .into_iter()
+                .find(|candidate| {
+                    serde_json::from_value::<Configuration>(json!({ key.as_str(): candidate }))
+                        .is_ok()
                 })
-                .collect();
-        }
+                .ok_or_else(|| {
+                    anyhow!("Could not parse {}={} as a configuration value", name, raw)
+                })?;
 
-        if let Some(arg) = args.values_of("replay_codes") {
-            // replay codes passed in by the user
-            config.replay_codes = arg
-                .map(|code| {
-                    StatusCode::from_bytes(code.as_bytes())
-                        .unwrap_or_else(|e| report_and_exit(&e.to_string()))
-                        .as_u16()
-                })
-                .collect();
-        } else {
-            // not passed in by the user, use whatever value is held in status_codes
-            config.replay_codes = config.status_codes.clone();
+            settings.insert(key, value);
         }
 
-        if let Some(arg) = args.values_of("filter_status") {
-            config.filter_status = arg
-                .map(|code| {
+        let config = serde_json::from_value(Value::Object(settings))?;
+
+        Ok(config)
+    }
+
+    /// Resolve the configuration the same way `new` does, but collect every problem found along
+    /// the way instead of stopping at the first one
+    pub(super) fn check(args: &ArgMatches) -> Vec<String> {
+        let mut problems = Vec::new();
+        let mut config = Configuration::default();
+
+        let config_files = match args.value_of("config") {
+            Some(config_file) => {
+                if !PathBuf::from(config_file).exists() {
+                    problems.push(format!("Config file {} not found", config_file));
+                }
+                vec![PathBuf::from(config_file)]
+            }
+            None => Self::config_file_locations().unwrap_or_else(|e| {
+                problems.push(plain_error(&e));
+                Vec::new()
+            }),
+        };
+
+        for config_file in config_files {
+            if let Err(e) = Self::parse_and_merge_config(config_file, &mut config) {
+                problems.push(plain_error(&e));
+            }
+        }
+
+        match Self::parse_env_vars(env::vars()) {
+            Ok(env_config) => Self::merge_config(&mut config, env_config),
+            Err(e) => problems.push(plain_error(&e)),
+        }
+
+        // parse_cli_args stops at the first invalid status code, so they're checked beforehand
+        // in order to report all of them
+        let invalid_codes = invalid_cli_status_codes(args);
+
+        if invalid_codes.is_empty() {
+            match Self::parse_cli_args(args) {
+                Ok(cli_config) => Self::merge_config(&mut config, cli_config),
+                Err(e) => problems.push(plain_error(&e)),
+            }
+        } else {
+            problems.extend(invalid_codes);
+            problems.push("The remaining command line options weren't checked".to_string());
+        }
+
+        Self::resolve_wordlist(&mut config);
+
+        problems.extend(config.validate());
+        problems
+    }
+
+    /// Point `wordlist` at an installed wordlist (`feroxbuster wordlists install NAME`) when it
+    /// isn't a file on disk, but names one
+    pub(super) fn resolve_wordlist(config: &mut Self) {
+        if let Some(path) = wordlists::resolve(&config.wordlist) {
+            log::debug!("using installed wordlist {}", path.display());
+            config.wordlist = path.to_string_lossy().to_string();
+        }
+    }
+
+    /// Check the resolved values that aren't verified while they're being read in, returning a
+    /// description of each problem found
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !self.uses_presets_only() && !PathBuf::from(&self.wordlist).is_file() {
+            problems.push(format!("Wordlist {} not found", self.wordlist));
+        }
+
+        for name in &self.presets {
+            if presets::preset(name).is_none() {
+                problems.push(format!(
+                    "Unknown preset {}, expected one of {}",
+                    name,
+                    presets::PRESET_NAMES.join(", ")
+                ));
+            }
+        }
+
+        if !self.target_url.is_empty() {
+            if let Err(e) = Url::parse(&self.target_url) {
+                problems.push(format!("Invalid target url {}: {}", self.target_url, e));
+            }
+        }
+
+        for (name, proxy) in [("proxy", &self.proxy), ("replay_proxy", &self.replay_proxy)].iter() {
+            if !proxy.is_empty() {
+                if let Err(e) = Proxy::all(proxy.as_str()) {
+                    problems.push(format!("Invalid {} {}: {}", name, proxy, e));
+                }
+            }
+        }
+
+        for (name, value) in &self.headers {
+            if let Err(e) = HeaderName::from_bytes(name.as_bytes()) {
+                problems.push(format!("Invalid header name {:?}: {}", name, e));
+            }
+
+            if let Err(e) = HeaderValue::from_str(value) {
+                problems.push(format!("Invalid value for header {}: {}", name, e));
+            }
+
+            for placeholder in placeholders::unknown_placeholders(value) {
+                problems.push(format!(
+                    "Unknown placeholder {} in header {}; expected one of {{{{{}}}}}",
+                    placeholder,
+                    name,
+                    placeholders::PLACEHOLDERS.join("}}, {{")
+                ));
+            }
+        }
+
+        for (name, value) in &self.queries {
+            if placeholders::is_template(name) || placeholders::is_template(value) {
+                // sent as-is, which is never what was meant
+                problems.push(format!(
+                    "Placeholders are only expanded in header values, not in query {}",
+                    name
+                ));
+            }
+        }
+
+        if let Err(e) = HeaderValue::from_str(&self.user_agent) {
+            problems.push(format!("Invalid user agent {:?}: {}", self.user_agent, e));
+        }
+
+        let codes = [
+            ("status_codes", &self.status_codes),
+            ("replay_codes", &self.replay_codes),
+            ("filter_status", &self.filter_status),
+        ];
+
+        for (name, codes) in codes.iter() {
+            for code in codes.iter() {
+                if StatusCode::from_u16(*code).is_err() {
+                    problems.push(format!("Invalid status code {} in {}", code, name));
+                }
+            }
+        }
+
+        for pattern in &self.deny_url_regex {
+            if let Err(e) = regex::Regex::new(pattern) {
+                problems.push(format!("Invalid deny url regex {:?}: {}", pattern, e));
+            }
+        }
+
+        for rule in &self.scope {
+            if let Err(e) = scope::valid_rule(rule) {
+                problems.push(e);
+            }
+        }
+
+        if let Err(e) = evidence::valid_severity(&self.evidence_severity) {
+            problems.push(e);
+        }
+
+        if self.log_format != "text" && self.log_format != "json" {
+            problems.push(format!(
+                "Invalid log format {}, expected text or json",
+                self.log_format
+            ));
+        }
+
+        if self.thousands_separator != "locale" && self.thousands_separator.chars().count() > 1 {
+            problems.push(format!(
+                "Invalid thousands separator {:?}, expected a single character or locale",
+                self.thousands_separator
+            ));
+        }
+
+        if !["bytes", "si", "iec"].contains(&self.byte_units.as_str()) {
+            problems.push(format!(
+                "Invalid byte units {}, expected bytes, si, or iec",
+                self.byte_units
+            ));
+        }
+
+        if !["text", "urls-with-meta"].contains(&self.output_format.as_str()) {
+            problems.push(format!(
+                "Invalid output format {}, expected text or urls-with-meta",
+                self.output_format
+            ));
+        }
+
+        if !["exact", "bloom"].contains(&self.seen_urls.as_str()) {
+            problems.push(format!(
+                "Invalid seen urls mode {}, expected exact or bloom",
+                self.seen_urls
+            ));
+        }
+
+        if self.refresh_rate > 1000 {
+            problems.push(format!(
+                "Invalid refresh rate {}, expected 1 to 1000 milliseconds",
+                self.refresh_rate
+            ));
+        }
+
+        if !self.cooldown.is_empty() {
+            match parser::parse_duration(&self.cooldown) {
+                Ok(0) => problems.push("Invalid cooldown 0, expected at least 1s".to_string()),
+                Ok(_) => {}
+                Err(e) => problems.push(format!("Invalid cooldown: {}", e)),
+            }
+        }
+
+        if !self.checkpoint_interval.is_empty() {
+            match parser::parse_duration(&self.checkpoint_interval) {
+                Ok(0) => {
+                    problems.push("Invalid checkpoint interval 0, expected at least 1s".to_string())
+                }
+                Ok(_) => {}
+                Err(e) => problems.push(format!("Invalid checkpoint interval: {}", e)),
+            }
+        }
+
+        if !self.trace_output.is_empty() {
+            if let Err(e) = trace::parse_endpoint(&self.trace_output) {
+                problems.push(e);
+            }
+
+            if cfg!(not(feature = "trace")) {
+                problems.push(
+                    "trace_output needs a feroxbuster built with `--features trace`".to_string(),
+                );
+            }
+        }
+
+        if !self.zap_url.is_empty() {
+            match Url::parse(&self.zap_url) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => {}
+                _ => problems.push(format!(
+                    "Invalid ZAP url {}, expected something like http://127.0.0.1:8080",
+                    self.zap_url
+                )),
+            }
+        }
+
+        if !self.event_stream.is_empty() {
+            if let Err(e) = event_stream::valid_destination(&self.event_stream) {
+                problems.push(e);
+            }
+        }
+
+        if !self.host_header.is_empty() {
+            let is_host_name = !self
+                .host_header
+                .contains(|c: char| matches!(c, '/' | ':' | '@' | '[' | ']') || c.is_whitespace())
+                && Url::parse(&format!("http://{}/", self.host_header)).is_ok();
+
+            if !is_host_name {
+                problems.push(format!(
+                    "Invalid host_header {:?}, expected a host name (ex: example.com)",
+                    self.host_header
+                ));
+            }
+
+            if self.stdin {
+                problems.push(
+                    "host_header can't be used with --stdin, it applies to a single target"
+                        .to_string(),
+                );
+            } else if let Ok(url) = Url::parse(&self.target_url) {
+                if client::ip_host(&url).is_none() {
+                    problems.push(format!(
+                        "host_header needs a target given as an ip address (ex: http://10.0.0.5), received {}",
+                        self.target_url
+                    ));
+                }
+            }
+        }
+
+        if !self.scan_id_header.is_empty() {
+            if let Err(e) = HeaderName::from_bytes(self.scan_id_header.as_bytes()) {
+                problems.push(format!(
+                    "Invalid scan_id_header {:?}: {}",
+                    self.scan_id_header, e
+                ));
+            }
+
+            if let Err(e) = HeaderValue::from_str(&self.scan_id) {
+                problems.push(format!(
+                    "scan_id {:?} can't be sent in a header: {}",
+                    self.scan_id, e
+                ));
+            }
+        }
+
+        if self.tor && !self.proxy.is_empty() && self.proxy != tor::TOR_PROXY {
+            // same as the conflict between --tor and --proxy, for values from a config file
+            problems.push(format!(
+                "tor can't be used with proxy {}, requests would skip Tor",
+                self.proxy
+            ));
+        }
+
+        if !self.tor_control.is_empty() {
+            if let Err(e) = self.tor_control.parse::<SocketAddr>() {
+                problems.push(format!(
+                    "Invalid Tor control address {}: {}",
+                    self.tor_control, e
+                ));
+            }
+        }
+
+        if self.auth_diff
+            && !self.headers.keys().any(|name| is_sensitive(name))
+            && !self.queries.iter().any(|(name, _)| is_sensitive(name))
+        {
+            problems.push(
+                "auth_diff needs credentials to leave out, i.e. an Authorization or Cookie header"
+                    .to_string(),
+            );
+        }
+
+        for criterion in &self.stop_on_match {
+            if criterion.strip_prefix(FINDING_PREFIX) == Some("") {
+                problems.push(format!(
+                    "stop_on_match {} needs the category of the findings to stop on",
+                    criterion
+                ));
+            }
+        }
+
+        for rule in &self.permutation_rules {
+            if !permutations::valid_rule(rule) {
+                problems.push(format!(
+                    "Permutation rule {} needs a {{word}} to replace",
+                    rule
+                ));
+            }
+        }
+
+        if let Some(login) = &self.login {
+            problems.extend(login.problems());
+        }
+
+        for mapping in &self.wordlist_map {
+            problems.extend(mapping.problems());
+        }
+
+        if let Some(csrf) = &self.csrf {
+            problems.extend(csrf.problems());
+
+            if !self.mine_params {
+                problems.push(
+                    "csrf tokens are only sent with the form bodies of --mine-params".to_string(),
+                );
+            }
+        }
+
+        for spec in &self.rotate_headers {
+            match client::parse_rotate_header(spec) {
+                Some((_, path)) if !PathBuf::from(path).is_file() => {
+                    problems.push(format!("Rotate header file {} not found", path));
+                }
+                None => {
+                    problems.push(format!(
+                        "Invalid rotate header {}, expected NAME:@FILE",
+                        spec
+                    ));
+                }
+                _ => {}
+            }
+        }
+
+        if self.head_only {
+            // same as the conflicts on --head-only, for values that came from a config file
+            let body_options = [
+                ("extract_links", self.extract_links),
+                ("filter_regex", !self.filter_regex.is_empty()),
+                ("filter_word_count", !self.filter_word_count.is_empty()),
+                ("filter_line_count", !self.filter_line_count.is_empty()),
+                ("filter_similar", !self.filter_similar.is_empty()),
+            ];
+
+            for (name, _) in body_options.iter().filter(|(_, used)| *used) {
+                problems.push(format!(
+                    "{} needs response bodies, which head_only skips",
+                    name
+                ));
+            }
+        }
+
+        if !self.coordinate.is_empty() {
+            if let Err(e) = self.coordinate.parse::<SocketAddr>() {
+                problems.push(format!(
+                    "Invalid coordinate address {}: {}",
+                    self.coordinate, e
+                ));
+            }
+
+            if self.shard_size == 0 {
+                problems.push("The shard size must be above 0".to_string());
+            }
+        }
+
+        problems
+    }
+
+    /// Given a set of ArgMatches read from the CLI, update and return the default Configuration
+    /// settings
+    pub(super) fn parse_cli_args(args: &ArgMatches) -> Result<Self> {
+        let mut config = Configuration::default();
+
+        update_config_if_present!(&mut config.threads, args, "threads", usize);
+        update_config_if_present!(&mut config.async_workers, args, "async_workers", usize);
+        update_config_if_present!(
+            &mut config.blocking_threads,
+            args,
+            "blocking_threads",
+            usize
+        );
+        update_config_if_present!(&mut config.depth, args, "depth", usize);
+        update_config_if_present!(&mut config.scan_limit, args, "scan_limit", usize);
+        update_config_if_present!(&mut config.crawl_wordlist, args, "crawl_wordlist", usize);
+        update_config_if_present!(&mut config.parallel, args, "parallel", usize);
+
+        if let Some(arg) = args.value_of("rate_limit") {
+            config.rate_limit = parser::parse_count(arg).map_err(|e| anyhow!(e))? as usize;
+        }
+
+        if let Some(arg) = args.value_of("request_limit") {
+            config.request_limit = parser::parse_count(arg).map_err(|e| anyhow!(e))? as usize;
+        }
+
+        if let Some(arg) = args.value_of("max_429s") {
+            config.max_429s = parser::parse_count(arg).map_err(|e| anyhow!(e))? as usize;
+        }
+
+        update_config_if_present!(&mut config.cooldown, args, "cooldown", String);
+
+        if let Some(arg) = args.value_of("cluster_threshold") {
+            config.cluster_threshold = parser::parse_count(arg).map_err(|e| anyhow!(e))? as usize;
+        }
+
+        if let Some(arg) = args.value_of("shard_size") {
+            config.shard_size = parser::parse_count(arg).map_err(|e| anyhow!(e))? as usize;
+        }
+
+        update_config_if_present!(&mut config.coordinate, args, "coordinate", String);
+        update_config_if_present!(
+            &mut config.coordinate_secret,
+            args,
+            "coordinate_secret",
+            String
+        );
+
+        if let Some(arg) = args.value_of("log_format") {
+            config.log_format = arg.to_lowercase();
+        }
+
+        update_config_if_present!(
+            &mut config.thousands_separator,
+            args,
+            "thousands_separator",
+            String
+        );
+
+        if let Some(arg) = args.value_of("byte_units") {
+            config.byte_units = arg.to_lowercase();
+        }
+
+        if let Some(arg) = args.value_of("output_format") {
+            config.output_format = arg.to_lowercase();
+        }
+
+        if let Some(arg) = args.value_of("seen_urls") {
+            config.seen_urls = arg.to_lowercase();
+        }
+        update_config_if_present!(&mut config.wordlist, args, "wordlist", String);
+        update_config_if_present!(&mut config.output, args, "output", String);
+        update_config_if_present!(&mut config.debug_log, args, "debug_log", String);
+        update_config_if_present!(&mut config.burp_output, args, "burp_output", String);
+        update_config_if_present!(&mut config.zap_url, args, "zap_url", String);
+        update_config_if_present!(&mut config.zap_api_key, args, "zap_api_key", String);
+        update_config_if_present!(&mut config.event_stream, args, "event_stream", String);
+        update_config_if_present!(&mut config.scan_id, args, "scan_id", String);
+        update_config_if_present!(&mut config.scan_id_header, args, "scan_id_header", String);
+        update_config_if_present!(
+            &mut config.defectdojo_output,
+            args,
+            "defectdojo_output",
+            String
+        );
+        update_config_if_present!(&mut config.evidence_dir, args, "evidence_dir", String);
+
+        if let Some(arg) = args.value_of("evidence_severity") {
+            config.evidence_severity = arg.to_lowercase();
+        }
+        update_config_if_present!(&mut config.seed_file, args, "seed_file", String);
+        update_config_if_present!(&mut config.tor_control, args, "tor_control", String);
+        update_config_if_present!(
+            &mut config.tor_control_password,
+            args,
+            "tor_control_password",
+            String
+        );
+        update_config_if_present!(&mut config.trace_output, args, "trace_output", String);
+        update_config_if_present!(&mut config.refresh_rate, args, "refresh_rate", u64);
+        update_config_if_present!(&mut config.track_changes, args, "track_changes", String);
+        update_config_if_present!(&mut config.save_config, args, "save_config", String);
+        update_config_if_present!(&mut config.time_limit, args, "time_limit", String);
+        update_config_if_present!(
+            &mut config.checkpoint_interval,
+            args,
+            "checkpoint_interval",
+            String
+        );
+
+        if let Some(arg) = args.value_of("checkpoint_requests") {
+            config.checkpoint_requests = parser::parse_count(arg).map_err(|e| anyhow!(e))?;
+        }
+        update_config_if_present!(&mut config.resume_from, args, "resume_from", String);
+
+        if let Some(arg) = args.values_of("status_codes") {
+            config.status_codes = arg
+                .map(|code| {
                     StatusCode::from_bytes(code.as_bytes())
-                        .unwrap_or_else(|e| report_and_exit(&e.to_string()))
-                        .as_u16()
+                        .map(|status| status.as_u16())
+                        .with_context(|| format!("Invalid status code {}", code))
                 })
-                .collect();
+                .collect::<Result<_>>()?;
+        }
+
+        if let Some(arg) = args.values_of("replay_codes") {
+            // replay codes passed in by the user
+            config.replay_codes = arg
+                .map(|code| {
+                    StatusCode::from_bytes(code.as_bytes())
+                        .map(|status| status.as_u16())
+                        .with_context(|| format!("Invalid status code {}", code))
+                })
+                .collect::<Result<_>>()?;
+        } else {
+            // not passed in by the user, use whatever value is held in status_codes
+            config.replay_codes = config.status_codes.clone();
+        }
+
+        if let Some(arg) = args.values_of("filter_status") {
+            config.filter_status = arg
+                .map(|code| {
+                    StatusCode::from_bytes(code.as_bytes())
+                        .map(|status| status.as_u16())
+                        .with_context(|| format!("Invalid status code {}", code))
+                })
+                .collect::<Result<_>>()?;
         }
 
         if let Some(arg) = args.values_of("extensions") {
@@ -548,6 +1840,51 @@ impl Configuration {
             config.url_denylist = arg.map(|val| val.to_string()).collect();
         }
 
+        if let Some(arg) = args.values_of("deny_url_regex") {
+            config.deny_url_regex = arg.map(|val| val.to_string()).collect();
+        }
+
+        if let Some(arg) = args.values_of("scope") {
+            config.scope = arg.map(|val| val.to_string()).collect();
+        }
+
+        if let Some(arg) = args.values_of("expect_found") {
+            config.expect_found = arg.map(|val| val.to_string()).collect();
+        }
+
+        if let Some(arg) = args.values_of("expect_absent") {
+            config.expect_absent = arg.map(|val| val.to_string()).collect();
+        }
+
+        if let Some(arg) = args.values_of("stop_on_match") {
+            config.stop_on_match = arg.map(|val| val.to_string()).collect();
+        }
+
+        if let Some(arg) = args.values_of("rotate_headers") {
+            config.rotate_headers = arg.map(String::from).collect();
+        }
+
+        if let Some(arg) = args.values_of("permutation_rules") {
+            config.permutation_rules = arg.map(String::from).collect();
+        }
+
+        if let Some(arg) = args.values_of("presets") {
+            config.presets = arg.map(|val| val.to_lowercase()).collect();
+        }
+
+        if let Some(arg) = args.values_of("buckets") {
+            config.buckets = arg.map(String::from).collect();
+        }
+
+        if let Some(arg) = args.values_of("seed_from") {
+            config.seed_from = arg.map(|val| val.to_lowercase()).collect();
+        }
+
+        if let Some(arg) = args.values_of("secret_patterns") {
+            config.find_secrets = true;
+            config.secret_patterns = arg.map(|val| val.to_string()).collect();
+        }
+
         if let Some(arg) = args.values_of("filter_regex") {
             config.filter_regex = arg.map(|val| val.to_string()).collect();
         }
@@ -558,29 +1895,20 @@ impl Configuration {
 
         if let Some(arg) = args.values_of("filter_size") {
             config.filter_size = arg
-                .map(|size| {
-                    size.parse::<u64>()
-                        .unwrap_or_else(|e| report_and_exit(&e.to_string()))
-                })
-                .collect();
+                .map(|size| parser::parse_size(size).map_err(|e| anyhow!(e)))
+                .collect::<Result<_>>()?;
         }
 
         if let Some(arg) = args.values_of("filter_words") {
             config.filter_word_count = arg
-                .map(|size| {
-                    size.parse::<usize>()
-                        .unwrap_or_else(|e| report_and_exit(&e.to_string()))
-                })
-                .collect();
+                .map(|size| Ok(size.parse::<usize>()?))
+                .collect::<Result<_>>()?;
         }
 
         if let Some(arg) = args.values_of("filter_lines") {
             config.filter_line_count = arg
-                .map(|size| {
-                    size.parse::<usize>()
-                        .unwrap_or_else(|e| report_and_exit(&e.to_string()))
-                })
-                .collect();
+                .map(|size| Ok(size.parse::<usize>()?))
+                .collect::<Result<_>>()?;
         }
 
         if args.is_present("silent") {
@@ -607,6 +1935,34 @@ impl Configuration {
             config.requester_policy = RequesterPolicy::AutoBail;
         }
 
+        if args.is_present("adaptive_threads") {
+            config.adaptive_threads = true;
+        }
+
+        if args.is_present("wordlist_cache") {
+            config.wordlist_cache = true;
+        }
+
+        if args.is_present("zap_active_scan") {
+            config.zap_active_scan = true;
+        }
+
+        if args.is_present("tor") {
+            config.tor = true;
+        }
+
+        if args.is_present("bucket_by_tech") {
+            config.bucket_by_tech = true;
+        }
+
+        if args.is_present("head_only") {
+            config.head_only = true;
+        }
+
+        if args.is_present("response_time") {
+            config.response_time = true;
+        }
+
         if args.is_present("dont_filter") {
             config.dont_filter = true;
         }
@@ -629,10 +1985,99 @@ impl Configuration {
             config.extract_links = true;
         }
 
+        if args.is_present("extract_forms") {
+            config.extract_forms = true;
+        }
+
+        if args.is_present("extract_comments") {
+            config.extract_comments = true;
+        }
+
+        if args.is_present("fingerprint") {
+            config.fingerprint = true;
+        }
+
+        if args.is_present("parse_listings") {
+            config.parse_listings = true;
+        }
+
+        if args.is_present("discover_openapi") {
+            config.discover_openapi = true;
+        }
+
+        if args.is_present("graphql_probe") {
+            config.graphql_probe = true;
+        }
+
+        if args.is_present("source_maps") {
+            config.source_maps = true;
+        }
+
+        if args.is_present("find_secrets") {
+            config.find_secrets = true;
+        }
+
+        if args.is_present("harvest") {
+            config.harvest = true;
+        }
+
+        if args.is_present("mine_params") {
+            config.mine_params = true;
+        }
+
+        if args.is_present("check_artifacts") {
+            config.check_artifacts = true;
+        }
+
+        if args.is_present("check_headers") {
+            config.check_headers = true;
+        }
+
+        if args.is_present("cors_probe") {
+            config.cors_probe = true;
+        }
+
+        if args.is_present("ws_probe") {
+            config.ws_probe = true;
+        }
+
+        if args.is_present("auth_diff") {
+            config.auth_diff = true;
+        }
+
+        if args.is_present("check_methods") {
+            config.check_methods = true;
+        }
+
+        if args.is_present("permute") {
+            config.permute = true;
+        }
+
+        if args.is_present("iis_shortnames") {
+            config.iis_shortnames = true;
+        }
+
+        if args.is_present("redact_secrets") {
+            config.redact_secrets = true;
+        }
+
+        if args.is_present("no_product_wordlists") {
+            config.no_product_wordlists = true;
+        }
+
+        if args.is_present("skip_listed_dirs") {
+            config.parse_listings = true;
+            config.skip_listed_dirs = true;
+        }
+
         if args.is_present("json") {
             config.json = true;
         }
 
+        if args.is_present("error_records") {
+            config.error_records = true;
+        }
+
         if args.is_present("stdin") {
             config.stdin = true;
         } else if let Some(url) = args.value_of("url") {
@@ -644,8 +2089,45 @@ impl Configuration {
         ////
         update_config_if_present!(&mut config.proxy, args, "proxy", String);
         update_config_if_present!(&mut config.replay_proxy, args, "replay_proxy", String);
+        if args.is_present("no_env_proxy") {
+            config.no_env_proxy = true;
+        }
         update_config_if_present!(&mut config.user_agent, args, "user_agent", String);
-        update_config_if_present!(&mut config.timeout, args, "timeout", u64);
+        if let Some(arg) = args.value_of("timeout") {
+            // plain numbers are seconds, anything else is a duration, i.e. 1m
+            config.timeout = arg
+                .parse::<u64>()
+                .or_else(|_| parser::parse_duration(arg))
+                .map_err(|e| anyhow!(e))?;
+        }
+
+        if let Some(arg) = args.value_of("dns_ttl") {
+            // same format as --timeout
+            config.dns_ttl = arg
+                .parse::<u64>()
+                .or_else(|_| parser::parse_duration(arg))
+                .map_err(|e| anyhow!(e))?;
+        }
+
+        if let Some(arg) = args.value_of("streams_per_connection") {
+            config.streams_per_connection = arg.parse::<usize>()?;
+        }
+
+        if let Some(arg) = args.value_of("max_response_size") {
+            config.max_response_size = parser::parse_size(arg).map_err(|e| anyhow!(e))?;
+        }
+
+        if let Some(arg) = args.value_of("memory_limit") {
+            config.memory_limit = parser::parse_size(arg).map_err(|e| anyhow!(e))?;
+        }
+
+        if let Some(arg) = args.value_of("adaptive_timeout") {
+            config.adaptive_timeout = arg.parse::<u32>()?;
+        }
+
+        if let Some(arg) = args.value_of("throttle_bytes") {
+            config.throttle_bytes = parser::parse_size(arg).map_err(|e| anyhow!(e))?;
+        }
 
         if args.is_present("redirects") {
             config.redirects = true;
@@ -657,18 +2139,13 @@ impl Configuration {
 
         if let Some(headers) = args.values_of("headers") {
             for val in headers {
-                let mut split_val = val.split(':');
-
-                // explicitly take first split value as header's name
-                let name = split_val.next().unwrap().trim();
-
-                // all other items in the iterator returned by split, when combined with the
-                // original split deliminator (:), make up the header's final value
-                let value = split_val.collect::<Vec<&str>>().join(":");
-                config.headers.insert(name.to_string(), value.to_string());
+                let (name, value) = parser::parse_header(val).map_err(|e| anyhow!(e))?;
+                add_header(&mut config.headers, name, value);
             }
         }
 
+        update_config_if_present!(&mut config.host_header, args, "host_header", String);
+
         if let Some(queries) = args.values_of("queries") {
             for val in queries {
                 // same basic logic used as reading in the headers HashMap above
@@ -682,58 +2159,174 @@ impl Configuration {
             }
         }
 
-        config
+        Ok(config)
     }
 
-    /// this function determines if we've gotten a Client configuration change from
-    /// either the config file or command line arguments; if we have, we need to rebuild
-    /// the client and store it in the config struct
-    fn try_rebuild_clients(configuration: &mut Configuration) {
-        if !configuration.proxy.is_empty()
-            || configuration.timeout != timeout()
-            || configuration.user_agent != user_agent()
-            || configuration.redirects
-            || configuration.insecure
-            || !configuration.headers.is_empty()
-            || configuration.resumed
-        {
-            if configuration.proxy.is_empty() {
-                configuration.client = client::initialize(
-                    configuration.timeout,
-                    &configuration.user_agent,
-                    configuration.redirects,
-                    configuration.insecure,
-                    &configuration.headers,
-                    None,
+    /// this function finishes a configuration gathered from the config file and command line
+    /// arguments: the process-wide settings (dns cache, bandwidth cap, rotating headers, ...) are
+    /// applied, and the clients are rebuilt along with their `RequestContext`, which every
+    /// configuration needs its own of
+    pub(super) fn try_rebuild_clients(configuration: &mut Configuration) -> Result<()> {
+        // the dns cache, bandwidth cap, and rotating headers are shared by every client
+        client::DNS_CACHE.set_ttl(configuration.dns_ttl);
+
+        if configuration.scan_id.is_empty() {
+            // a resumed scan keeps the id it was saved with
+            configuration.scan_id = scan_id::generate();
+        }
+
+        client::BANDWIDTH.set_limit(configuration.throttle_bytes);
+        client::RATE_LIMITS.set_cooldown(
+            configuration.cooldown_after(),
+            configuration.cooldown_duration(),
+        );
+        client::ROTATING_HEADERS
+            .load(&configuration.rotate_headers)
+            .with_context(|| fmt_err("Could not load --rotate-header values"))?;
+
+        if configuration.tor && configuration.proxy.is_empty() {
+            // --tor is shorthand for --proxy pointed at the local Tor daemon
+            configuration.proxy = tor::TOR_PROXY.to_string();
+        }
+
+        configuration.rebuild_clients()
+    }
+
+    /// Whether --preset was used without a --wordlist, in which case only the presets' words
+    /// are scanned
+    pub fn uses_presets_only(&self) -> bool {
+        !self.presets.is_empty() && self.wordlist == wordlist()
+    }
+
+    /// Number of 429s in a row that start a cool-down; --cooldown on its own cools a host down
+    /// after every 429, 0 means there are no cool-downs
+    pub fn cooldown_after(&self) -> usize {
+        if self.max_429s == 0 && !self.cooldown.is_empty() {
+            1
+        } else {
+            self.max_429s
+        }
+    }
+
+    /// Length of a cool-down; 30 seconds unless --cooldown says otherwise
+    pub fn cooldown_duration(&self) -> Duration {
+        parser::parse_duration(&self.cooldown)
+            .ok()
+            .filter(|seconds| *seconds > 0)
+            .map(Duration::from_secs)
+            .unwrap_or(client::DEFAULT_COOLDOWN)
+    }
+
+    /// Client for the next scan request; requests take turns between `client` and
+    /// `stream_clients`, spreading them across that many http/2 connections per host
+    pub fn scan_client(&self) -> &Client {
+        client::next_client(&self.client, &self.stream_clients)
+    }
+
+    /// Client for requests that go to third parties rather than the target; see
+    /// `client::third_party`
+    pub fn third_party_client(&self) -> Result<Client> {
+        let proxy = if self.proxy.is_empty() {
+            None
+        } else {
+            Some(self.proxy.as_str())
+        };
+
+        client::third_party(
+            self.timeout,
+            &self.user_agent,
+            self.insecure,
+            proxy,
+            self.no_env_proxy,
+        )
+        .with_context(|| "Could not build third party client")
+    }
+
+    /// Build `client` (and `replay_client`, when a replay proxy is set, `stream_clients`, when
+    /// --streams-per-connection is used, and `anonymous_client`, when --auth-diff is used) from the
+    /// current settings, along with a new `request_context` for them to share
+    ///
+    /// clients aren't serialized, so any configuration that was deserialized needs this before
+    /// it can be used to scan
+    pub fn rebuild_clients(&mut self) -> Result<()> {
+        self.request_context = Arc::new(client::RequestContext::new(self)?);
+
+        let proxy = if self.proxy.is_empty() {
+            None
+        } else {
+            Some(self.proxy.as_str())
+        };
+
+        self.client = client::initialize(
+            self.timeout,
+            &self.user_agent,
+            self.redirects,
+            self.insecure,
+            &self.headers,
+            proxy,
+            &self.request_context,
+        )
+        .with_context(|| "Could not rebuild client")?;
+
+        self.replay_client = if self.replay_proxy.is_empty() {
+            None
+        } else {
+            Some(
+                client::initialize(
+                    self.timeout,
+                    &self.user_agent,
+                    self.redirects,
+                    self.insecure,
+                    &self.headers,
+                    Some(&self.replay_proxy),
+                    &self.request_context,
                 )
-                .expect("Could not rebuild client")
-            } else {
-                configuration.client = client::initialize(
-                    configuration.timeout,
-                    &configuration.user_agent,
-                    configuration.redirects,
-                    configuration.insecure,
-                    &configuration.headers,
-                    Some(&configuration.proxy),
+                .with_context(|| "Could not rebuild client")?,
+            )
+        };
+
+        let connections = client::connections_needed(self.threads, self.streams_per_connection);
+
+        self.stream_clients = (1..connections)
+            .map(|_| {
+                client::initialize(
+                    self.timeout,
+                    &self.user_agent,
+                    self.redirects,
+                    self.insecure,
+                    &self.headers,
+                    proxy,
+                    &self.request_context,
                 )
-                .expect("Could not rebuild client")
-            }
-        }
+                .with_context(|| "Could not rebuild client")
+            })
+            .collect::<Result<_>>()?;
+
+        self.anonymous_client = if self.auth_diff {
+            let headers: HashMap<String, String> = self
+                .headers
+                .iter()
+                .filter(|(name, _)| !is_sensitive(name))
+                .map(|(name, value)| (name.clone(), value.clone()))
+                .collect();
 
-        if !configuration.replay_proxy.is_empty() {
-            // only set replay_client when replay_proxy is set
-            configuration.replay_client = Some(
+            Some(
                 client::initialize(
-                    configuration.timeout,
-                    &configuration.user_agent,
-                    configuration.redirects,
-                    configuration.insecure,
-                    &configuration.headers,
-                    Some(&configuration.replay_proxy),
+                    self.timeout,
+                    &self.user_agent,
+                    self.redirects,
+                    self.insecure,
+                    &headers,
+                    proxy,
+                    &self.request_context,
                 )
-                .expect("Could not rebuild client"),
-            );
-        }
+                .with_context(|| "Could not rebuild client")?,
+            )
+        } else {
+            None
+        };
+
+        Ok(())
     }
 
     /// Given a configuration file's location and an instance of `Configuration`, read in
@@ -754,11 +2347,13 @@ impl Configuration {
     }
 
     /// Given two Configurations, overwrite `settings` with the fields found in `settings_to_merge`
-    fn merge_config(conf: &mut Self, new: Self) {
+    pub(super) fn merge_config(conf: &mut Self, new: Self) {
         // does not include the following Configuration fields, as they don't make sense here
         //  - kind
         //  - client
         //  - replay_client
+        //  - stream_clients
+        //  - anonymous_client
         //  - resumed
         //  - config
         update_if_not_default!(&mut conf.target_url, new.target_url, "");
@@ -769,6 +2364,46 @@ impl Configuration {
         update_if_not_default!(&mut conf.quiet, new.quiet, false);
         update_if_not_default!(&mut conf.auto_bail, new.auto_bail, false);
         update_if_not_default!(&mut conf.auto_tune, new.auto_tune, false);
+        update_if_not_default!(&mut conf.adaptive_threads, new.adaptive_threads, false);
+        update_if_not_default!(&mut conf.wordlist_cache, new.wordlist_cache, false);
+        update_if_not_default!(&mut conf.dns_ttl, new.dns_ttl, 0);
+        update_if_not_default!(&mut conf.max_response_size, new.max_response_size, 0);
+        update_if_not_default!(&mut conf.head_only, new.head_only, false);
+        update_if_not_default!(&mut conf.response_time, new.response_time, false);
+        update_if_not_default!(
+            &mut conf.streams_per_connection,
+            new.streams_per_connection,
+            0
+        );
+        update_if_not_default!(&mut conf.memory_limit, new.memory_limit, 0);
+        update_if_not_default!(&mut conf.adaptive_timeout, new.adaptive_timeout, 0);
+        update_if_not_default!(&mut conf.throttle_bytes, new.throttle_bytes, 0);
+        update_if_not_default!(&mut conf.async_workers, new.async_workers, 0);
+        update_if_not_default!(&mut conf.blocking_threads, new.blocking_threads, 0);
+        update_if_not_default!(&mut conf.trace_output, new.trace_output, "");
+        update_if_not_default!(&mut conf.refresh_rate, new.refresh_rate, 0);
+        update_if_not_default!(&mut conf.checkpoint_interval, new.checkpoint_interval, "");
+        update_if_not_default!(&mut conf.checkpoint_requests, new.checkpoint_requests, 0);
+        update_if_not_default!(&mut conf.burp_output, new.burp_output, "");
+        update_if_not_default!(&mut conf.zap_url, new.zap_url, "");
+        update_if_not_default!(&mut conf.zap_api_key, new.zap_api_key, "");
+        update_if_not_default!(&mut conf.zap_active_scan, new.zap_active_scan, false);
+        update_if_not_default!(&mut conf.event_stream, new.event_stream, "");
+        update_if_not_default!(&mut conf.scan_id, new.scan_id, "");
+        update_if_not_default!(&mut conf.scan_id_header, new.scan_id_header, "");
+        update_if_not_default!(&mut conf.defectdojo_output, new.defectdojo_output, "");
+        update_if_not_default!(&mut conf.evidence_dir, new.evidence_dir, "");
+        update_if_not_default!(
+            &mut conf.evidence_severity,
+            new.evidence_severity,
+            evidence_severity()
+        );
+        update_if_not_default!(&mut conf.output_format, new.output_format, output_format());
+        update_if_not_default!(&mut conf.bucket_by_tech, new.bucket_by_tech, false);
+        update_if_not_default!(&mut conf.seed_file, new.seed_file, "");
+        update_if_not_default!(&mut conf.tor, new.tor, false);
+        update_if_not_default!(&mut conf.tor_control, new.tor_control, "");
+        update_if_not_default!(&mut conf.tor_control_password, new.tor_control_password, "");
         // use updated quiet/silent values to determine output level; same for requester policy
         conf.output_level = determine_output_level(conf.quiet, conf.silent);
         conf.requester_policy = determine_requester_policy(conf.auto_tune, conf.auto_bail);
@@ -776,13 +2411,87 @@ impl Configuration {
         update_if_not_default!(&mut conf.redirects, new.redirects, false);
         update_if_not_default!(&mut conf.insecure, new.insecure, false);
         update_if_not_default!(&mut conf.extract_links, new.extract_links, false);
+        update_if_not_default!(&mut conf.extract_forms, new.extract_forms, false);
+        update_if_not_default!(&mut conf.extract_comments, new.extract_comments, false);
+        update_if_not_default!(&mut conf.fingerprint, new.fingerprint, false);
+        update_if_not_default!(&mut conf.parse_listings, new.parse_listings, false);
+        update_if_not_default!(&mut conf.skip_listed_dirs, new.skip_listed_dirs, false);
+        update_if_not_default!(&mut conf.discover_openapi, new.discover_openapi, false);
+        update_if_not_default!(&mut conf.graphql_probe, new.graphql_probe, false);
+        update_if_not_default!(&mut conf.source_maps, new.source_maps, false);
+        update_if_not_default!(&mut conf.find_secrets, new.find_secrets, false);
+        update_if_not_default!(&mut conf.harvest, new.harvest, false);
+        update_if_not_default!(&mut conf.mine_params, new.mine_params, false);
+        update_if_not_default!(&mut conf.check_artifacts, new.check_artifacts, false);
+        update_if_not_default!(&mut conf.check_headers, new.check_headers, false);
+        update_if_not_default!(&mut conf.cors_probe, new.cors_probe, false);
+        update_if_not_default!(&mut conf.ws_probe, new.ws_probe, false);
+        update_if_not_default!(&mut conf.auth_diff, new.auth_diff, false);
+        update_if_not_default!(&mut conf.check_methods, new.check_methods, false);
+        update_if_not_default!(&mut conf.permute, new.permute, false);
+        update_if_not_default!(&mut conf.iis_shortnames, new.iis_shortnames, false);
+        update_if_not_default!(&mut conf.redact_secrets, new.redact_secrets, false);
+        update_if_not_default!(
+            &mut conf.no_product_wordlists,
+            new.no_product_wordlists,
+            false
+        );
         update_if_not_default!(&mut conf.extensions, new.extensions, Vec::<String>::new());
         update_if_not_default!(
             &mut conf.url_denylist,
             new.url_denylist,
             Vec::<String>::new()
         );
+        update_if_not_default!(
+            &mut conf.deny_url_regex,
+            new.deny_url_regex,
+            Vec::<String>::new()
+        );
+        update_if_not_default!(&mut conf.scope, new.scope, Vec::<String>::new());
+        update_if_not_default!(&mut conf.seed_from, new.seed_from, Vec::<String>::new());
+        update_if_not_default!(&mut conf.buckets, new.buckets, Vec::<String>::new());
+        update_if_not_default!(&mut conf.presets, new.presets, Vec::<String>::new());
+        update_if_not_default!(
+            &mut conf.permutation_rules,
+            new.permutation_rules,
+            Vec::<String>::new()
+        );
+        update_if_not_default!(&mut conf.crawl_wordlist, new.crawl_wordlist, 0);
+        update_if_not_default!(
+            &mut conf.rotate_headers,
+            new.rotate_headers,
+            Vec::<String>::new()
+        );
+        update_if_not_default!(&mut conf.login, new.login, None);
+        update_if_not_default!(&mut conf.csrf, new.csrf, None);
+        update_if_not_default!(
+            &mut conf.wordlist_map,
+            new.wordlist_map,
+            Vec::<WordlistMapping>::new()
+        );
+        update_if_not_default!(&mut conf.recipe_rate_limit, new.recipe_rate_limit, "");
+        update_if_not_default!(
+            &mut conf.expect_found,
+            new.expect_found,
+            Vec::<String>::new()
+        );
+        update_if_not_default!(
+            &mut conf.expect_absent,
+            new.expect_absent,
+            Vec::<String>::new()
+        );
+        update_if_not_default!(
+            &mut conf.stop_on_match,
+            new.stop_on_match,
+            Vec::<String>::new()
+        );
+        update_if_not_default!(
+            &mut conf.secret_patterns,
+            new.secret_patterns,
+            Vec::<String>::new()
+        );
         update_if_not_default!(&mut conf.headers, new.headers, HashMap::new());
+        update_if_not_default!(&mut conf.host_header, new.host_header, "");
         update_if_not_default!(&mut conf.queries, new.queries, Vec::new());
         update_if_not_default!(&mut conf.no_recursion, new.no_recursion, false);
         update_if_not_default!(&mut conf.add_slash, new.add_slash, false);
@@ -817,15 +2526,34 @@ impl Configuration {
         update_if_not_default!(&mut conf.scan_limit, new.scan_limit, 0);
         update_if_not_default!(&mut conf.parallel, new.parallel, 0);
         update_if_not_default!(&mut conf.rate_limit, new.rate_limit, 0);
+        update_if_not_default!(&mut conf.request_limit, new.request_limit, 0);
+        update_if_not_default!(&mut conf.max_429s, new.max_429s, 0);
+        update_if_not_default!(&mut conf.cooldown, new.cooldown, "");
+        update_if_not_default!(&mut conf.cluster_threshold, new.cluster_threshold, 0);
+        update_if_not_default!(&mut conf.coordinate, new.coordinate, "");
+        update_if_not_default!(&mut conf.coordinate_secret, new.coordinate_secret, "");
         update_if_not_default!(&mut conf.replay_proxy, new.replay_proxy, "");
+        update_if_not_default!(&mut conf.no_env_proxy, new.no_env_proxy, false);
         update_if_not_default!(&mut conf.debug_log, new.debug_log, "");
+        update_if_not_default!(&mut conf.track_changes, new.track_changes, "");
+        update_if_not_default!(&mut conf.save_config, new.save_config, "");
         update_if_not_default!(&mut conf.resume_from, new.resume_from, "");
         update_if_not_default!(&mut conf.json, new.json, false);
+        update_if_not_default!(&mut conf.error_records, new.error_records, false);
 
         update_if_not_default!(&mut conf.timeout, new.timeout, timeout());
         update_if_not_default!(&mut conf.user_agent, new.user_agent, user_agent());
         update_if_not_default!(&mut conf.threads, new.threads, threads());
         update_if_not_default!(&mut conf.depth, new.depth, depth());
+        update_if_not_default!(&mut conf.shard_size, new.shard_size, shard_size());
+        update_if_not_default!(&mut conf.log_format, new.log_format, log_format());
+        update_if_not_default!(
+            &mut conf.thousands_separator,
+            new.thousands_separator,
+            String::new()
+        );
+        update_if_not_default!(&mut conf.byte_units, new.byte_units, byte_units());
+        update_if_not_default!(&mut conf.seen_urls, new.seen_urls, seen_urls());
         update_if_not_default!(&mut conf.wordlist, new.wordlist, wordlist());
         update_if_not_default!(&mut conf.status_codes, new.status_codes, status_codes());
         // status_codes() is the default for replay_codes, if they're not provided
@@ -837,9 +2565,243 @@ impl Configuration {
     ///
     /// uses serde to deserialize the toml into a `Configuration` struct
     pub(super) fn parse_config(config_file: PathBuf) -> Result<Self> {
-        let content = read_to_string(config_file)?;
-        let config: Self = toml::from_str(content.as_str())?;
-        Ok(config)
+        let content = read_to_string(&config_file)
+            .with_context(|| fmt_err(&format!("Could not read {}", config_file.display())))?;
+
+        toml::from_str(content.as_str()).map_err(|e| {
+            // point at the offending line, when toml knows where it is
+            let location = e
+                .line_col()
+                .and_then(|(line, col)| {
+                    content.lines().nth(line).map(|text| {
+                        format!(
+                            "\n  --> {}:{}:{}\n   | {}\n   | {:>width$}",
+                            config_file.display(),
+                            line + 1,
+                            col + 1,
+                            text,
+                            "^",
+                            width = col + 1
+                        )
+                    })
+                })
+                .unwrap_or_default();
+
+            anyhow!(fmt_err(&format!(
+                "Could not parse {}: {}{}",
+                config_file.display(),
+                e,
+                location
+            )))
+        })
+    }
+
+    /// Serialize the resolved configuration as TOML that can be handed back to --config to
+    /// reproduce the run
+    ///
+    /// sensitive header/query values and proxy passwords are masked when `redact_secrets` is set
+    pub fn as_toml(&self) -> Result<String> {
+        let mut value = serde_json::to_value(self)?;
+
+        if let Value::Object(fields) = &mut value {
+            for field in UNSAVED_FIELDS.iter() {
+                fields.remove(*field);
+            }
+
+            if self.redact_secrets {
+                redact(fields);
+            }
+        }
+
+        // going through toml::Value ensures plain values are written before tables
+        let value = toml::Value::try_from(value)?;
+
+        Ok(toml::to_string(&value)?)
+    }
+
+    /// Write the resolved configuration to `save_config` as TOML
+    pub fn save(&self) -> Result<()> {
+        log::trace!("enter: save({})", self.save_config);
+
+        let content = self.as_toml()?;
+
+        write(&self.save_config, content)
+            .with_context(|| fmt_err(&format!("Could not write {}", self.save_config)))?;
+
+        log::trace!("exit: save");
+        Ok(())
+    }
+}
+
+/// Status codes passed on the command line that can't be parsed, described as problems
+fn invalid_cli_status_codes(args: &ArgMatches) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    for name in ["status_codes", "replay_codes", "filter_status"].iter() {
+        if let Some(codes) = args.values_of(name) {
+            for code in codes {
+                if StatusCode::from_bytes(code.as_bytes()).is_err() {
+                    problems.push(format!("Invalid status code {} in {}", code, name));
+                }
+            }
+        }
+    }
+
+    problems
+}
+
+/// An error's message, without the colored `ERROR: ` prefix added by `fmt_err`
+fn plain_error(error: &anyhow::Error) -> String {
+    let message = console::strip_ansi_codes(&error.to_string()).to_string();
+
+    message
+        .strip_prefix("ERROR: ")
+        .unwrap_or(&message)
+        .to_string()
+}
+
+/// Add a header, combining its value with that of a header given before under the same name
+/// (names are case-insensitive); cookies are joined with `; `, everything else with `, `
+fn add_header(headers: &mut HashMap<String, String>, name: String, value: String) {
+    let existing = headers
+        .iter_mut()
+        .find(|(existing, _)| existing.eq_ignore_ascii_case(&name));
+
+    match existing {
+        Some((_, current)) if current.is_empty() => *current = value,
+        Some((_, current)) if !value.is_empty() => {
+            let separator = if name.eq_ignore_ascii_case("cookie") {
+                "; "
+            } else {
+                ", "
+            };
+            current.push_str(separator);
+            current.push_str(&value);
+        }
+        Some(_) => {}
+        None => {
+            headers.insert(name, value);
+        }
+    }
+}
+
+/// Whether or not a header/query parameter with the given name is likely to carry a secret
+pub(crate) fn is_sensitive(name: &str) -> bool {
+    let name = name.to_ascii_lowercase();
+
+    [
+        "auth", "cookie", "token", "key", "secret", "pass", "session",
+    ]
+    .iter()
+    .any(|marker| name.contains(marker))
+}
+
+/// Replace the values of sensitive headers and query parameters, as well as any proxy passwords,
+/// the tor control password, the zap api key, and the login body, with `REDACTED`
+fn redact(fields: &mut Map<String, Value>) {
+    if let Some(Value::Object(headers)) = fields.get_mut("headers") {
+        for (name, value) in headers.iter_mut() {
+            if is_sensitive(name) {
+                *value = json!(REDACTED);
+            }
+        }
+    }
+
+    if let Some(Value::Array(queries)) = fields.get_mut("queries") {
+        for query in queries.iter_mut() {
+            if let Value::Array(pair) = query {
+                if matches!(pair.first(), Some(Value::String(name)) if is_sensitive(name)) {
+                    pair[1] = json!(REDACTED);
+                }
+            }
+        }
+    }
+
+    for field in ["proxy", "replay_proxy"].iter() {
+        if let Some(Value::String(proxy)) = fields.get_mut(*field) {
+            if let Ok(mut url) = Url::parse(proxy) {
+                if url.password().is_some() && url.set_password(Some(REDACTED)).is_ok() {
+                    *proxy = url.to_string();
+                }
+            }
+        }
+    }
+
+    for field in ["tor_control_password", "zap_api_key"].iter() {
+        if matches!(fields.get(*field), Some(Value::String(secret)) if !secret.is_empty()) {
+            fields.insert(field.to_string(), json!(REDACTED));
+        }
+    }
+
+    if let Some(Value::Object(login)) = fields.get_mut("login") {
+        // the body is where a password ends up when it isn't read from the environment
+        if matches!(login.get("body"), Some(Value::String(body)) if !body.is_empty()) {
+            login.insert("body".to_string(), json!(REDACTED));
+        }
+    }
+}
+
+/// Possible interpretations of the raw value of an environment variable, based on the type of
+/// the field's default value; in order of preference
+fn env_candidates(raw: &str, default: &Value) -> Vec<Value> {
+    let raw = raw.trim();
+
+    let items: Vec<&str> = raw
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .collect();
+
+    match default {
+        Value::Bool(_) => match raw.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" | "on" => vec![Value::Bool(true)],
+            "false" | "0" | "no" | "off" => vec![Value::Bool(false)],
+            _ => Vec::new(),
+        },
+        Value::Number(_) => serde_json::from_str(raw).into_iter().collect(),
+        Value::Array(_) => {
+            let mut candidates: Vec<Value> = serde_json::from_str(raw).into_iter().collect();
+
+            let numbers: Vec<Value> = items
+                .iter()
+                .filter_map(|item| serde_json::from_str::<serde_json::Number>(item).ok())
+                .map(Value::Number)
+                .collect();
+
+            if numbers.len() == items.len() {
+                candidates.push(Value::Array(numbers));
+            }
+
+            candidates.push(json!(items));
+
+            let pairs: Vec<Value> = items
+                .iter()
+                .filter_map(|item| item.split_once('='))
+                .map(|(name, value)| json!([name.trim(), value.trim()]))
+                .collect();
+
+            if pairs.len() == items.len() {
+                candidates.push(Value::Array(pairs));
+            }
+
+            candidates
+        }
+        Value::Object(_) => {
+            let mut candidates: Vec<Value> = serde_json::from_str(raw).into_iter().collect();
+
+            let headers: Map<String, Value> = items
+                .iter()
+                .filter_map(|item| item.split_once(':'))
+                .map(|(name, value)| (name.trim().to_string(), json!(value.trim())))
+                .collect();
+
+            if headers.len() == items.len() {
+                candidates.push(Value::Object(headers));
+            }
+
+            candidates
+        }
+        _ => vec![Value::String(raw.to_string())],
     }
 }
 
@@ -1,9 +1,17 @@
 //! all logic related to instantiating a running configuration
 
+mod builder;
 mod container;
+pub mod recipe;
 mod utils;
+pub mod watch;
+mod wizard;
 #[cfg(test)]
 mod tests;
 
-pub use self::container::Configuration;
-pub use self::utils::{determine_output_level, OutputLevel, RequesterPolicy};
+pub use self::builder::ConfigurationBuilder;
+pub use self::container::{Configuration, Invocation};
+pub(crate) use self::container::is_sensitive;
+pub use self::utils::{
+    determine_output_level, determine_requester_policy, report_check, OutputLevel, RequesterPolicy,
+};
@@ -0,0 +1,697 @@
+//! recipe files describe several scans that are run, one feroxbuster process per scan, by
+//! `feroxbuster recipe FILE`
+//!
+//! ```toml
+//! parallel = 2        # number of scans running at once (default: 1, i.e. one after the other)
+//! rate_limit = 200    # requests per second, shared by every scan in the recipe (default: 0,
+//!                     # i.e. no limit)
+//!
+//! [defaults]          # applied to every scan; same keys as ferox-config.toml
+//! threads = 20
+//!
+//! [[scan]]            # one table per scan; same keys as ferox-config.toml
+//! target_url = "https://one.example.com"
+//! filter_status = [404]
+//!
+//! [[scan]]
+//! target_url = "https://two.example.com"
+//! wordlist = "/wordlists/api.txt"
+//! ```
+//!
+//! instead of independent scans, a recipe can describe a pipeline: stages that run one after the
+//! other, each one scanning the directories found by the stage before it
+//!
+//! ```toml
+//! [[stage]]           # one table per stage; same keys as ferox-config.toml, plus a name
+//! name = "directories"
+//! target_url = "https://one.example.com"
+//! wordlist = "/wordlists/small.txt"
+//! add_slash = true
+//!
+//! [[stage]]
+//! name = "files"
+//! wordlist = "/wordlists/big.txt"
+//! extensions = ["php", "bak"]
+//! no_recursion = true
+//! ```
+use std::{
+    collections::HashSet,
+    env::{current_exe, temp_dir},
+    fs::{read_to_string, remove_file, write, OpenOptions},
+    io::{Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex},
+    thread::{self, sleep},
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use clap::ArgMatches;
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::Configuration;
+use crate::{exit_codes::ExitCode, response::FeroxResponse, utils::fmt_err, SLEEP_DURATION};
+
+/// default number of scans that run at the same time
+fn parallel() -> usize {
+    1
+}
+
+/// Representation of a recipe file
+#[derive(Debug, Deserialize)]
+pub(super) struct Recipe {
+    /// number of scans running at the same time
+    #[serde(default = "parallel")]
+    parallel: usize,
+
+    /// requests per second, shared by every scan (or stage) of the recipe; see `share_rate_limit`
+    ///
+    /// a scan with its own rate_limit keeps it as well, which applies to each of its directories
+    /// on its own, like --rate-limit
+    #[serde(default)]
+    rate_limit: usize,
+
+    /// settings applied to every scan, overridden by the scan's own settings
+    #[serde(default)]
+    defaults: Option<Configuration>,
+
+    /// the scans to run, in order
+    #[serde(default, rename = "scan")]
+    scans: Vec<Configuration>,
+
+    /// stages of a pipeline, in order; can't be combined with `scans`
+    #[serde(default, rename = "stage")]
+    stages: Vec<Stage>,
+}
+
+/// One stage of a pipeline
+#[derive(Debug, Deserialize)]
+struct Stage {
+    /// name of the stage, added to each of its results; `stage N` when left out
+    #[serde(default)]
+    name: String,
+
+    /// settings of the stage; same keys as ferox-config.toml
+    #[serde(flatten)]
+    config: Configuration,
+}
+
+impl Recipe {
+    /// Parse the given recipe; files ending in .yml or .yaml are read as YAML, everything else
+    /// as TOML
+    pub(super) fn parse(path: &Path) -> Result<Self> {
+        let content = read_to_string(path)
+            .with_context(|| fmt_err(&format!("Could not read {}", path.display())))?;
+
+        let is_yaml = matches!(
+            path.extension().and_then(|ext| ext.to_str()),
+            Some("yml") | Some("yaml")
+        );
+
+        let recipe: Self = if is_yaml {
+            serde_yaml::from_str(&content)
+                .with_context(|| fmt_err(&format!("Could not parse {} as YAML", path.display())))?
+        } else {
+            toml::from_str(&content)
+                .with_context(|| fmt_err(&format!("Could not parse {} as TOML", path.display())))?
+        };
+
+        if recipe.scans.is_empty() && recipe.stages.is_empty() {
+            bail!(fmt_err(&format!(
+                "{} doesn't define any scans",
+                path.display()
+            )));
+        }
+
+        if !recipe.scans.is_empty() && !recipe.stages.is_empty() {
+            bail!(fmt_err(&format!(
+                "{} defines both scans and stages, expected one or the other",
+                path.display()
+            )));
+        }
+
+        if recipe.parallel == 0 {
+            bail!(fmt_err("A recipe's parallel setting must be above 0"));
+        }
+
+        for (index, scan) in recipe.scans.iter().enumerate() {
+            if scan.target_url.is_empty() {
+                bail!(fmt_err(&format!(
+                    "Scan #{} in {} has no target_url",
+                    index + 1,
+                    path.display()
+                )));
+            }
+        }
+
+        Ok(recipe)
+    }
+
+    /// number of scans that actually run at the same time
+    fn concurrency(&self) -> usize {
+        self.parallel.min(self.scans.len())
+    }
+
+    /// `base`, then the recipe's defaults, then the given scan's (or stage's) own settings
+    fn layered(&self, base: &Configuration, scan: Configuration) -> Configuration {
+        let mut config = base.clone();
+
+        if let Some(defaults) = &self.defaults {
+            Configuration::merge_config(&mut config, defaults.clone());
+        }
+
+        Configuration::merge_config(&mut config, scan);
+
+        // the child reads its settings from a file
+        config.redact_secrets = false;
+        config.save_config = String::new();
+
+        config
+    }
+
+    /// Resolve the configuration of each scan: `base` (config files, environment, and options
+    /// given before `recipe`), then the recipe's defaults, then the scan's own settings
+    pub(super) fn configurations(mut self, base: &Configuration) -> Vec<Configuration> {
+        let mut configurations = Vec::with_capacity(self.scans.len());
+
+        let scans = std::mem::take(&mut self.scans);
+
+        for scan in scans {
+            let mut config = self.layered(base, scan);
+
+            // each child only ever scans the one target
+            config.stdin = false;
+
+            configurations.push(config);
+        }
+
+        configurations
+    }
+
+    /// Resolve the name and configuration of each stage, layered the same way as scans
+    fn stage_configurations(mut self, base: &Configuration) -> Vec<(String, Configuration)> {
+        let stages = std::mem::take(&mut self.stages);
+        let mut configurations = Vec::with_capacity(stages.len());
+
+        for (index, stage) in stages.into_iter().enumerate() {
+            let config = self.layered(base, stage.config);
+
+            let name = if stage.name.is_empty() {
+                format!("stage {}", index + 1)
+            } else {
+                stage.name
+            };
+
+            configurations.push((name, config));
+        }
+
+        configurations
+    }
+}
+
+/// Start the limiter that hands out the recipe's rate_limit to its scans, and return its address,
+/// which is given to each scan as its `recipe_rate_limit`; no limiter is started for a rate_limit
+/// of 0, and the address is empty
+///
+/// scans ask for a permit before every request, over a connection to the limiter; permits are
+/// spaced evenly across all of the connections, so the recipe as a whole sends at most
+/// `rate_limit` requests per second, however many scans and directories are running at once
+fn share_rate_limit(rate_limit: usize) -> Result<String> {
+    if rate_limit == 0 {
+        return Ok(String::new());
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .with_context(|| fmt_err("Could not start the recipe's rate limiter"))?;
+    let address = listener.local_addr()?.to_string();
+
+    let interval = Duration::from_secs_f64(1.0 / rate_limit as f64);
+    let next_permit = Arc::new(Mutex::new(Instant::now()));
+
+    // the limiter lives as long as the recipe does
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let next_permit = next_permit.clone();
+            thread::spawn(move || hand_out_permits(stream, &next_permit, interval));
+        }
+    });
+
+    Ok(address)
+}
+
+/// Answer each byte a scan sends with a byte of its own, once the next permit is due; permits
+/// that weren't asked for don't pile up, so a scan that was idle doesn't get to burst
+fn hand_out_permits(mut stream: TcpStream, next_permit: &Mutex<Instant>, interval: Duration) {
+    let mut request = [0_u8; 1];
+
+    while stream.read_exact(&mut request).is_ok() {
+        let due = match next_permit.lock() {
+            Ok(mut next_permit) => {
+                let due = (*next_permit).max(Instant::now());
+                *next_permit = due + interval;
+                due
+            }
+            Err(_) => return,
+        };
+
+        sleep(due.saturating_duration_since(Instant::now()));
+
+        if stream.write_all(&request).is_err() {
+            // the scan is done
+            return;
+        }
+    }
+}
+
+/// A scan started from a recipe, along with the config file handed to it
+struct RunningScan {
+    /// the feroxbuster child process
+    child: Child,
+
+    /// resolved configuration of the scan, removed once the child exits
+    config_file: PathBuf,
+}
+
+/// Write the given configuration to a temporary file handed to a child process
+pub(super) fn write_config(config: &Configuration) -> Result<PathBuf> {
+    let config_file = temp_dir().join(format!("feroxbuster-recipe-{}.toml", uuid::Uuid::new_v4()));
+
+    write(&config_file, config.as_toml()?)
+        .with_context(|| fmt_err(&format!("Could not write {}", config_file.display())))?;
+
+    Ok(config_file)
+}
+
+/// Start a feroxbuster child process for the given configuration
+fn spawn(config: &Configuration, silent: bool) -> Result<RunningScan> {
+    let config_file = write_config(config)?;
+
+    let mut command = Command::new(current_exe()?);
+    command
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--url")
+        .arg(&config.target_url);
+
+    if silent {
+        // several scans writing bars to the same terminal is a mess; --parallel does the same
+        command.arg("--silent");
+    }
+
+    log::debug!("recipe exec: {:?}", command);
+
+    let child = command
+        .spawn()
+        .with_context(|| fmt_err(&format!("Could not start a scan of {}", config.target_url)))?;
+
+    Ok(RunningScan { child, config_file })
+}
+
+/// Run a single stage of a pipeline against the given targets, fed to the child over stdin, and
+/// wait for it to finish
+fn run_stage(config: &Configuration, targets: &[String]) -> Result<ExitCode> {
+    let mut config = config.clone();
+    config.target_url = String::new();
+    config.stdin = true;
+
+    let config_file = write_config(&config)?;
+
+    let mut command = Command::new(current_exe()?);
+    command
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--stdin")
+        .stdin(Stdio::piped());
+
+    log::debug!("recipe exec: {:?}", command);
+
+    let mut child = command
+        .spawn()
+        .with_context(|| fmt_err("Could not start a pipeline stage"))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // dropping stdin once every target is written lets the child know there are no more
+        stdin.write_all(format!("{}\n", targets.join("\n")).as_bytes())?;
+    }
+
+    let status = child.wait()?;
+    remove_file(&config_file).unwrap_or_default();
+
+    // a signal-killed child counts as interrupted
+    let code = status
+        .code()
+        .unwrap_or_else(|| ExitCode::Interrupted.code());
+
+    Ok(ExitCode::from_code(code))
+}
+
+/// Directories found in a stage's results (`--json` lines), each with a trailing slash and only
+/// listed once; these are the targets of the next stage
+fn found_directories(results: &[Value]) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut directories = Vec::new();
+
+    for result in results {
+        if result.get("type").and_then(Value::as_str) != Some("response") {
+            continue;
+        }
+
+        let response: FeroxResponse = match serde_json::from_value(result.clone()) {
+            Ok(response) => response,
+            Err(_) => continue,
+        };
+
+        if !response.is_directory() || response.wildcard() {
+            continue;
+        }
+
+        let mut directory = response.url().to_string();
+
+        if !directory.ends_with('/') {
+            directory.push('/');
+        }
+
+        if seen.insert(directory.clone()) {
+            directories.push(directory);
+        }
+    }
+
+    directories
+}
+
+/// Run each stage of a pipeline in turn, scanning the directories found by the stage before it;
+/// every stage's results are added to `base.output`, with the name of the stage that found them
+fn run_pipeline(base: &Configuration, recipe: Recipe) -> Result<ExitCode> {
+    log::trace!("enter: run_pipeline({:?})", recipe);
+
+    let rate_limit = share_rate_limit(recipe.rate_limit)?;
+    let stages = recipe.stage_configurations(base);
+
+    // the first stage scans the target given to it, or to the recipe (--url, or the defaults)
+    if stages[0].1.target_url.is_empty() {
+        bail!(fmt_err("The first stage of a pipeline needs a target_url"));
+    }
+
+    let mut targets = vec![stages[0].1.target_url.clone()];
+    let mut codes = Vec::new();
+
+    for (name, mut config) in stages {
+        config.recipe_rate_limit = rate_limit.clone();
+
+        if targets.is_empty() {
+            eprintln!(
+                "No directories were found, skipping {} and the stages after it",
+                name
+            );
+            break;
+        }
+
+        // results are read back from a file of their own, whatever the stage was told to do
+        let results_file =
+            temp_dir().join(format!("feroxbuster-stage-{}.json", uuid::Uuid::new_v4()));
+        config.output = results_file.to_string_lossy().to_string();
+        config.json = true;
+
+        let code = run_stage(&config, &targets)?;
+        codes.push(code);
+
+        let results: Vec<Value> = read_to_string(&results_file)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        remove_file(&results_file).unwrap_or_default();
+
+        if !base.output.is_empty() {
+            let mut output = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&base.output)
+                .with_context(|| fmt_err(&format!("Could not open {}", base.output)))?;
+
+            for mut result in results.iter().cloned() {
+                if let Value::Object(fields) = &mut result {
+                    fields.insert("stage".to_string(), Value::String(name.clone()));
+                }
+
+                writeln!(output, "{}", result)?;
+            }
+        }
+
+        if code == ExitCode::Interrupted {
+            break;
+        }
+
+        targets = found_directories(&results);
+    }
+
+    let exit_code = ExitCode::combine(&codes);
+
+    log::trace!("exit: run_pipeline -> {:?}", exit_code);
+    Ok(exit_code)
+}
+
+/// feroxbuster [OPTIONS] recipe FILE; run every scan (or pipeline stage) in the recipe and
+/// combine their exit codes
+pub fn run(base: &Configuration, subcommand: &ArgMatches) -> Result<ExitCode> {
+    log::trace!("enter: run({:?})", subcommand);
+
+    // unwrap is fine, clap ensures FILE is present
+    let path = PathBuf::from(subcommand.value_of("file").unwrap());
+
+    let recipe = Recipe::parse(&path)?;
+
+    if !recipe.stages.is_empty() {
+        return run_pipeline(base, recipe);
+    }
+    let concurrency = recipe.concurrency();
+    let rate_limit = share_rate_limit(recipe.rate_limit)?;
+    let mut pending = recipe.configurations(base).into_iter();
+
+    let mut running: Vec<RunningScan> = Vec::new();
+    let mut codes = Vec::new();
+
+    loop {
+        while running.len() < concurrency {
+            match pending.next() {
+                Some(mut config) => {
+                    config.recipe_rate_limit = rate_limit.clone();
+                    running.push(spawn(&config, concurrency > 1)?);
+                }
+                None => break,
+            }
+        }
+
+        if running.is_empty() {
+            break;
+        }
+
+        let mut index = 0;
+
+        while index < running.len() {
+            if let Some(status) = running[index].child.try_wait()? {
+                let finished = running.remove(index);
+                remove_file(&finished.config_file).unwrap_or_default();
+
+                // a signal-killed child counts as interrupted
+                let code = status
+                    .code()
+                    .unwrap_or_else(|| ExitCode::Interrupted.code());
+                codes.push(ExitCode::from_code(code));
+            } else {
+                index += 1;
+            }
+        }
+
+        sleep(Duration::from_millis(SLEEP_DURATION / 5));
+    }
+
+    let exit_code = ExitCode::combine(&codes);
+
+    log::trace!("exit: run -> {:?}", exit_code);
+    Ok(exit_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::SharedRateLimit;
+    use tempfile::TempDir;
+
+    /// write the given recipe to a file with the given name and parse it
+    fn parse(name: &str, content: &str) -> Result<Recipe> {
+        let tmp_dir = TempDir::new().unwrap();
+        let file = tmp_dir.path().join(name);
+        write(&file, content).unwrap();
+        Recipe::parse(&file)
+    }
+
+    #[test]
+    /// toml recipes layer the base configuration, defaults, and each scan's own settings
+    fn recipe_configurations_are_layered() {
+        let recipe = parse(
+            "recipe.toml",
+            r#"
+            parallel = 2
+            rate_limit = 100
+
+            [defaults]
+            threads = 20
+            filter_status = [404]
+
+            [[scan]]
+            target_url = "http://one.test"
+
+            [[scan]]
+            target_url = "http://two.test"
+            threads = 5
+            rate_limit = 10
+            "#,
+        )
+        .unwrap();
+
+        let base = Configuration {
+            timeout: 3,
+            redact_secrets: true,
+            ..Default::default()
+        };
+
+        let configs = recipe.configurations(&base);
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].target_url, "http://one.test");
+        assert_eq!(configs[0].threads, 20);
+        assert_eq!(configs[0].timeout, 3);
+        assert_eq!(configs[0].filter_status, vec![404]);
+        assert_eq!(configs[0].rate_limit, 0);
+        assert!(!configs[0].redact_secrets);
+        assert_eq!(configs[1].target_url, "http://two.test");
+        assert_eq!(configs[1].threads, 5);
+        assert_eq!(configs[1].rate_limit, 10);
+    }
+
+    #[test]
+    /// yaml recipes are recognized by their extension
+    fn recipe_parses_yaml() {
+        let recipe = parse(
+            "recipe.yml",
+            "scan:\n  - target_url: http://one.test\n    extensions: [php]\n",
+        )
+        .unwrap();
+
+        assert_eq!(recipe.parallel, 1);
+
+        let configs = recipe.configurations(&Configuration::default());
+        assert_eq!(configs[0].extensions, vec!["php"]);
+        assert_eq!(configs[0].rate_limit, 0);
+    }
+
+    #[test]
+    /// recipes without scans, without targets, or with a parallel of 0 are rejected
+    fn recipe_rejects_invalid_files() {
+        assert!(parse("recipe.toml", "parallel = 2").is_err());
+        assert!(parse("recipe.toml", "[[scan]]\nthreads = 2").is_err());
+        assert!(parse(
+            "recipe.toml",
+            "parallel = 0\n[[scan]]\ntarget_url = \"http://one.test\""
+        )
+        .is_err());
+        assert!(parse(
+            "recipe.toml",
+            "[[scan]]\ntarget_url = \"http://one.test\"\n[[stage]]\nthreads = 2"
+        )
+        .is_err());
+    }
+
+    #[test]
+    /// stages are named, and layered like scans
+    fn recipe_stage_configurations_are_layered() {
+        let recipe = parse(
+            "recipe.toml",
+            r#"
+            rate_limit = 100
+
+            [defaults]
+            threads = 20
+
+            [[stage]]
+            name = "directories"
+            target_url = "http://one.test"
+            add_slash = true
+
+            [[stage]]
+            extensions = ["php"]
+            no_recursion = true
+            "#,
+        )
+        .unwrap();
+
+        let stages = recipe.stage_configurations(&Configuration::default());
+
+        assert_eq!(stages.len(), 2);
+        assert_eq!(stages[0].0, "directories");
+        assert_eq!(stages[0].1.target_url, "http://one.test");
+        assert!(stages[0].1.add_slash);
+        assert_eq!(stages[0].1.threads, 20);
+        assert_eq!(stages[0].1.rate_limit, 0);
+        assert_eq!(stages[1].0, "stage 2");
+        assert_eq!(stages[1].1.extensions, vec!["php"]);
+        assert!(stages[1].1.no_recursion);
+        assert!(!stages[1].1.add_slash);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// the recipe's rate_limit is shared: two scans asking for permits at once get 10 requests
+    /// per second between them, not each
+    async fn recipe_rate_limit_is_shared_by_its_scans() {
+        let address = share_rate_limit(10).unwrap();
+
+        let scans: Vec<_> = (0..2)
+            .map(|_| {
+                let limit = Arc::new(SharedRateLimit::new(&address));
+
+                tokio::spawn(async move {
+                    for _ in 0..5 {
+                        limit.acquire().await;
+                    }
+                })
+            })
+            .collect();
+
+        let start = Instant::now();
+
+        for scan in scans {
+            scan.await.unwrap();
+        }
+
+        // 10 permits, the first of which is handed out right away
+        assert!(start.elapsed() >= Duration::from_millis(850));
+        assert!(share_rate_limit(0).unwrap().is_empty());
+    }
+
+    #[test]
+    /// the next stage scans each directory found once; files, wildcards, and other output
+    /// aren't targets
+    fn found_directories_are_the_next_targets() {
+        let results: Vec<Value> = [
+            r#"{"type":"response","url":"http://one.test/admin","path":"/admin","wildcard":false,"status":301,"content_length":0,"line_count":0,"word_count":0,"headers":{"location":"/admin/"}}"#,
+            r#"{"type":"response","url":"http://one.test/api/","path":"/api/","wildcard":false,"status":200,"content_length":0,"line_count":0,"word_count":0,"headers":{}}"#,
+            r#"{"type":"response","url":"http://one.test/api/","path":"/api/","wildcard":false,"status":403,"content_length":0,"line_count":0,"word_count":0,"headers":{}}"#,
+            r#"{"type":"response","url":"http://one.test/index.php","path":"/index.php","wildcard":false,"status":200,"content_length":0,"line_count":0,"word_count":0,"headers":{}}"#,
+            r#"{"type":"response","url":"http://one.test/junk/","path":"/junk/","wildcard":true,"status":200,"content_length":0,"line_count":0,"word_count":0,"headers":{}}"#,
+            r#"{"type":"statistics","requests":5}"#,
+        ]
+        .iter()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+        assert_eq!(
+            found_directories(&results),
+            vec!["http://one.test/admin/", "http://one.test/api/"]
+        );
+    }
+}
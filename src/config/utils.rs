@@ -2,22 +2,29 @@ use crate::{
     utils::{module_colorizer, status_colorizer},
     DEFAULT_STATUS_CODES, DEFAULT_WORDLIST, VERSION,
 };
-#[cfg(not(test))]
-use std::process::exit;
+use anyhow::{bail, Result};
+
+/// Print the outcome of `feroxbuster config check`; an error is returned when any problems were
+/// found, which results in a non-zero exit code
+pub fn report_check(problems: &[String]) -> Result<()> {
+    if problems.is_empty() {
+        println!("No problems found, the configuration is valid");
+        return Ok(());
+    }
 
-/// simple helper to clean up some code reuse below; panics under test / exits in prod
-pub(super) fn report_and_exit(err: &str) -> ! {
     eprintln!(
-        "{} {}: {}",
+        "{} {}: found {} problem(s)",
         status_colorizer("ERROR"),
-        module_colorizer("Configuration::new"),
-        err
+        module_colorizer("config check"),
+        problems.len()
     );
 
-    #[cfg(test)]
-    panic!();
-    #[cfg(not(test))]
-    exit(1);
+    for problem in problems {
+        // multi-line problems (i.e. config parse errors) stay indented under their bullet
+        eprintln!("  - {}", problem.replace('\n', "\n    "));
+    }
+
+    bail!("The configuration has {} problem(s)", problems.len())
 }
 
 // functions timeout, threads, status_codes, user_agent, wordlist, save_state, and depth are used to provide
@@ -67,6 +74,36 @@ pub(super) fn depth() -> usize {
     4
 }
 
+/// default format of log messages
+pub(super) fn log_format() -> String {
+    String::from("text")
+}
+
+/// default severity of the least severe finding whose evidence is saved
+pub(super) fn evidence_severity() -> String {
+    String::from("medium")
+}
+
+/// default format of results written to --output
+pub(super) fn output_format() -> String {
+    String::from("text")
+}
+
+/// default units used when displaying sizes
+pub(super) fn byte_units() -> String {
+    String::from("bytes")
+}
+
+/// default way of tracking urls that were already seen
+pub(super) fn seen_urls() -> String {
+    String::from("exact")
+}
+
+/// default number of words per shard of a distributed scan
+pub(super) fn shard_size() -> usize {
+    1000
+}
+
 /// enum representing the three possible states for informational output (not logging verbosity)
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum OutputLevel {
@@ -174,9 +211,9 @@ mod tests {
     }
 
     #[test]
-    #[should_panic]
-    /// report_and_exit should panic/exit when called
-    fn report_and_exit_panics_under_test() {
-        report_and_exit("test");
+    /// report_check only errors when problems were found
+    fn report_check_errors_on_problems() {
+        assert!(report_check(&[]).is_ok());
+        assert!(report_check(&["Wordlist /nope not found".to_string()]).is_err());
     }
 }
@@ -0,0 +1,382 @@
+//! `feroxbuster [OPTIONS] watch --targets FILE --interval 24h` scans every target again on a
+//! schedule, one feroxbuster process per target, and reports only what's new or changed since the
+//! target's previous scan
+//!
+//! each target keeps two baselines in the state directory: the content of the responses it
+//! returned (the same file --track-changes uses), and the findings reported for it
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::{create_dir_all, read_to_string, remove_file, write},
+    path::{Path, PathBuf},
+    process::Command,
+    sync::Arc,
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ArgMatches;
+use reqwest::{header::CONTENT_TYPE, Client, Url};
+use serde_json::{json, Value};
+
+use super::{recipe::write_config, Configuration};
+use crate::{
+    client, exit_codes::ExitCode, finding::FeroxFinding, parser::parse_duration,
+    traits::FeroxSerialize, utils::fmt_err,
+};
+
+/// Directory in which baselines are kept when --state-dir isn't given
+fn default_state_dir() -> Result<PathBuf> {
+    let data_dir = dirs::data_dir().ok_or_else(|| anyhow!("Couldn't determine data directory"))?;
+    Ok(data_dir.join("feroxbuster").join("watch"))
+}
+
+/// Read the targets to watch, one per line; blank lines and lines starting with # are skipped
+fn read_targets(path: &Path) -> Result<Vec<String>> {
+    let content = read_to_string(path)
+        .with_context(|| fmt_err(&format!("Could not read {}", path.display())))?;
+
+    let targets: Vec<String> = content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(String::from)
+        .collect();
+
+    for target in &targets {
+        if let Err(e) = Url::parse(target) {
+            bail!(fmt_err(&format!("Invalid target {}: {}", target, e)));
+        }
+    }
+
+    if targets.is_empty() {
+        bail!(fmt_err(&format!(
+            "{} doesn't list any targets",
+            path.display()
+        )));
+    }
+
+    Ok(targets)
+}
+
+/// Name under which the baselines of the given target are stored; anything that isn't safe in a
+/// file name becomes an underscore (https://one.test:8443/app -> https___one.test_8443_app)
+fn baseline_name(target: &str) -> String {
+    target
+        .trim_end_matches('/')
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Key a finding is remembered by between scans
+fn finding_key(finding: &FeroxFinding) -> String {
+    format!(
+        "{} {} {}",
+        finding.category(),
+        finding.url(),
+        finding.summary()
+    )
+}
+
+/// Findings in a scan's results (`--json` lines) that weren't reported by the previous scan,
+/// along with every finding's key, which is the baseline for the next scan
+///
+/// `change` findings come from comparing content against the previous scan already (the
+/// --track-changes baseline), so they're always new. without a previous scan, nothing is
+fn new_findings(
+    results: &[Value],
+    previous: Option<&BTreeSet<String>>,
+) -> (Vec<FeroxFinding>, BTreeSet<String>) {
+    let mut new = Vec::new();
+    let mut seen = BTreeSet::new();
+
+    for result in results {
+        if result.get("type").and_then(Value::as_str) != Some("finding") {
+            continue;
+        }
+
+        let finding: FeroxFinding = match serde_json::from_value(result.clone()) {
+            Ok(finding) => finding,
+            Err(_) => continue,
+        };
+
+        if finding.category() == "change" {
+            new.push(finding);
+            continue;
+        }
+
+        let key = finding_key(&finding);
+
+        if let Some(previous) = previous {
+            if !previous.contains(&key) && !seen.contains(&key) {
+                new.push(finding);
+            }
+        }
+
+        seen.insert(key);
+    }
+
+    (new, seen)
+}
+
+/// Scan the given target once, comparing it against its baselines in `state_dir` and updating
+/// them, and return the scan's exit code along with whatever is new or changed
+fn scan(
+    base: &Configuration,
+    target: &str,
+    state_dir: &Path,
+) -> Result<(ExitCode, Vec<FeroxFinding>)> {
+    log::trace!("enter: scan({}, {})", target, state_dir.display());
+
+    let name = baseline_name(target);
+    let findings_file = state_dir.join(format!("{}.findings.json", name));
+    let results_file = state_dir.join(format!("{}.results.json", name));
+
+    let mut config = base.clone();
+    config.target_url = target.to_string();
+    config.track_changes = state_dir
+        .join(format!("{}.json", name))
+        .to_string_lossy()
+        .to_string();
+    config.output = results_file.to_string_lossy().to_string();
+    config.json = true;
+    config.stdin = false;
+    config.redact_secrets = false;
+    config.save_config = String::new();
+
+    remove_file(&results_file).unwrap_or_default();
+
+    let config_file = write_config(&config)?;
+
+    let mut command = Command::new(std::env::current_exe()?);
+    command
+        .arg("--config")
+        .arg(&config_file)
+        .arg("--url")
+        .arg(target)
+        .arg("--silent");
+
+    log::debug!("watch exec: {:?}", command);
+
+    let status = command
+        .status()
+        .with_context(|| fmt_err(&format!("Could not start a scan of {}", target)));
+
+    remove_file(&config_file).unwrap_or_default();
+
+    // a signal-killed child counts as interrupted
+    let code = ExitCode::from_code(
+        status?
+            .code()
+            .unwrap_or_else(|| ExitCode::Interrupted.code()),
+    );
+
+    let results: Vec<Value> = read_to_string(&results_file)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect();
+
+    remove_file(&results_file).unwrap_or_default();
+
+    if code == ExitCode::Interrupted {
+        // a partial scan would make everything it didn't get to look new next time
+        log::trace!("exit: scan -> {:?}", code);
+        return Ok((code, Vec::new()));
+    }
+
+    let previous: Option<BTreeSet<String>> = match read_to_string(&findings_file) {
+        Ok(contents) => Some(serde_json::from_str(&contents).with_context(|| {
+            fmt_err(&format!(
+                "Could not parse {} as a findings baseline",
+                findings_file.display()
+            ))
+        })?),
+        Err(_) => {
+            log::info!("no baseline for {} yet; saving this scan as one", target);
+            None
+        }
+    };
+
+    let (new, seen) = new_findings(&results, previous.as_ref());
+
+    write(&findings_file, serde_json::to_string_pretty(&seen)?).with_context(|| {
+        fmt_err(&format!(
+            "Could not write a findings baseline to {}",
+            findings_file.display()
+        ))
+    })?;
+
+    log::trace!("exit: scan -> ({:?}, [{} new...])", code, new.len());
+    Ok((code, new))
+}
+
+/// POST whatever is new or changed about the given target to the --webhook, as json
+async fn notify(client: &Client, webhook: &Url, target: &str, new: &[FeroxFinding]) -> Result<()> {
+    let response = client
+        .post(webhook.clone())
+        .header(CONTENT_TYPE, "application/json")
+        .body(json!({ "target": target, "findings": new }).to_string())
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        bail!("{} answered with {}", webhook, response.status());
+    }
+
+    Ok(())
+}
+
+/// feroxbuster [OPTIONS] watch --targets FILE --interval DURATION; scan every target, wait for
+/// the interval to pass, and do it again until interrupted, reporting what's new or changed
+/// after each scan
+pub fn run(base: &Configuration, subcommand: &ArgMatches) -> Result<ExitCode> {
+    log::trace!("enter: run({:?})", subcommand);
+
+    // unwraps are fine, clap ensures both are present and that the interval is valid
+    let targets = read_targets(Path::new(subcommand.value_of("targets").unwrap()))?;
+    let interval = parse_duration(subcommand.value_of("interval").unwrap())
+        .map_err(|e| anyhow!(fmt_err(&e)))?;
+    let interval = Duration::from_secs(interval);
+
+    let state_dir = match subcommand.value_of("state_dir") {
+        Some(dir) => PathBuf::from(dir),
+        None => default_state_dir()?,
+    };
+
+    create_dir_all(&state_dir)
+        .with_context(|| fmt_err(&format!("Could not create {}", state_dir.display())))?;
+
+    let webhook = match subcommand.value_of("webhook") {
+        Some(webhook) => Some(
+            Url::parse(webhook)
+                .with_context(|| fmt_err(&format!("Invalid webhook {}", webhook)))?,
+        ),
+        None => None,
+    };
+
+    let proxy = Some(base.proxy.as_str()).filter(|proxy| !proxy.is_empty());
+    let client = client::initialize(
+        base.timeout,
+        &base.user_agent,
+        false,
+        base.insecure,
+        &HashMap::new(),
+        proxy,
+        &Arc::default(),
+    )?;
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    loop {
+        let started = Instant::now();
+
+        for target in &targets {
+            let (code, new) = scan(base, target, &state_dir)?;
+
+            if code == ExitCode::Interrupted {
+                log::trace!("exit: run -> {:?}", code);
+                return Ok(code);
+            }
+
+            if new.is_empty() {
+                continue;
+            }
+
+            println!("{}: {} new or changed", target, new.len());
+
+            for finding in &new {
+                print!("{}", finding.as_str());
+            }
+
+            if let Some(webhook) = &webhook {
+                if let Err(e) = runtime.block_on(notify(&client, webhook, target, &new)) {
+                    eprintln!("Could not notify {} about {}: {}", webhook, target, e);
+                }
+            }
+        }
+
+        // the interval is between the starts of two rounds, however long a round takes
+        sleep(interval.saturating_sub(started.elapsed()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// finding result line of the given category, url, and summary
+    fn finding(category: &str, url: &str, summary: &str) -> Value {
+        serde_json::to_value(FeroxFinding::new(category, url, summary, json!({}))).unwrap()
+    }
+
+    #[test]
+    /// targets are read one per line, skipping blanks and comments; bad urls are rejected
+    fn read_targets_skips_blanks_and_comments() {
+        let tmp_dir = TempDir::new().unwrap();
+        let file = tmp_dir.path().join("targets.txt");
+
+        write(
+            &file,
+            "# staging\nhttp://one.test\n\n  http://two.test/app  \n",
+        )
+        .unwrap();
+        assert_eq!(
+            read_targets(&file).unwrap(),
+            vec!["http://one.test", "http://two.test/app"]
+        );
+
+        write(&file, "# nothing\n\n").unwrap();
+        assert!(read_targets(&file).is_err());
+
+        write(&file, "one.test\n").unwrap();
+        assert!(read_targets(&file).is_err());
+    }
+
+    #[test]
+    /// baseline names are safe to use as file names
+    fn baseline_name_replaces_unsafe_characters() {
+        assert_eq!(
+            baseline_name("https://one.test:8443/app/"),
+            "https___one.test_8443_app"
+        );
+        assert_eq!(baseline_name("http://two.test"), "http___two.test");
+    }
+
+    #[test]
+    /// only findings missing from the baseline are new, except changes, which always are; the
+    /// first scan only makes a baseline
+    fn new_findings_compares_against_baseline() {
+        let results = vec![
+            finding("secret", "http://one.test/app.js", "aws key"),
+            finding("secret", "http://one.test/app.js", "aws key"),
+            finding("form", "http://one.test/login", "login form"),
+            finding("change", "http://one.test/admin", "appeared"),
+            json!({"type": "statistics", "requests": 5}),
+        ];
+
+        let (new, seen) = new_findings(&results, None);
+        assert_eq!(new.len(), 1);
+        assert_eq!(new[0].category(), "change");
+        assert_eq!(seen.len(), 2);
+
+        let mut previous = BTreeSet::new();
+        previous.insert(String::from("form http://one.test/login login form"));
+
+        let (new, seen) = new_findings(&results, Some(&previous));
+        let categories: Vec<_> = new.iter().map(|finding| finding.category()).collect();
+
+        assert_eq!(categories, vec!["secret", "change"]);
+        assert_eq!(seen.len(), 2);
+    }
+}
@@ -0,0 +1,358 @@
+//! interactive prompts that build a scan's command line, for those that don't know the flags (yet)
+use std::{
+    io::{BufRead, Write},
+    path::Path,
+};
+
+use anyhow::{bail, Result};
+use reqwest::{StatusCode, Url};
+
+use super::utils::{depth, status_codes, threads, wordlist};
+use crate::parser::parse_size;
+
+/// What the wizard came up with
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct WizardOutcome {
+    /// command line arguments for the scan, without the program's name
+    pub(super) args: Vec<String>,
+
+    /// whether or not the scan should be started right away
+    pub(super) launch: bool,
+}
+
+/// Reads answers from `input` and writes questions to `output`
+struct Prompter<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Prompter<R, W> {
+    /// Ask a question and return the trimmed answer; `default` is returned for an empty answer
+    fn ask(&mut self, question: &str, default: &str) -> Result<String> {
+        if default.is_empty() {
+            write!(self.output, "{}: ", question)?;
+        } else {
+            write!(self.output, "{} [{}]: ", question, default)?;
+        }
+
+        self.output.flush()?;
+
+        let mut answer = String::new();
+
+        if self.input.read_line(&mut answer)? == 0 {
+            bail!("Input closed before the wizard was finished");
+        }
+
+        match answer.trim() {
+            "" => Ok(default.to_string()),
+            answer => Ok(answer.to_string()),
+        }
+    }
+
+    /// Ask the same question until `validate` accepts the answer, explaining what was wrong with
+    /// each rejected answer
+    fn ask_until<F>(&mut self, question: &str, default: &str, validate: F) -> Result<String>
+    where
+        F: Fn(&str) -> Result<(), String>,
+    {
+        loop {
+            let answer = self.ask(question, default)?;
+
+            match validate(&answer) {
+                Ok(_) => return Ok(answer),
+                Err(reason) => writeln!(self.output, "  {}", reason)?,
+            }
+        }
+    }
+
+    /// Ask a yes/no question
+    fn confirm(&mut self, question: &str, default: bool) -> Result<bool> {
+        let hint = if default { "Y/n" } else { "y/N" };
+        let question = format!("{} ({})", question, hint);
+
+        loop {
+            match self.ask(&question, "")?.to_ascii_lowercase().as_str() {
+                "" => return Ok(default),
+                "y" | "yes" => return Ok(true),
+                "n" | "no" => return Ok(false),
+                _ => writeln!(self.output, "  please answer y or n")?,
+            }
+        }
+    }
+}
+
+/// Split a comma/space separated answer into its values
+fn split_values(answer: &str) -> Vec<&str> {
+    answer
+        .split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+/// Quote an argument so it can be pasted into a shell as-is
+fn shell_quote(arg: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "-_./:=,@%+".contains(c);
+
+    if !arg.is_empty() && arg.chars().all(safe) {
+        arg.to_string()
+    } else {
+        format!("'{}'", arg.replace('\'', r"'\''"))
+    }
+}
+
+/// Interactively ask for the target, wordlist, authentication, and filters of a scan, then show
+/// the equivalent command line and ask whether or not to start the scan
+///
+/// `existing` holds any other arguments given alongside --wizard; they're kept as-is
+pub(super) fn run<R: BufRead, W: Write>(
+    input: R,
+    output: W,
+    existing: &[String],
+) -> Result<WizardOutcome> {
+    let mut prompter = Prompter { input, output };
+    let mut args = existing.to_vec();
+
+    writeln!(
+        prompter.output,
+        "feroxbuster setup wizard; press enter to accept the [default] shown\n"
+    )?;
+
+    let target =
+        prompter.ask_until(
+            "Target url (ex: https://example.com)",
+            "",
+            |answer| match Url::parse(answer) {
+                Ok(url) if url.scheme() == "http" || url.scheme() == "https" => Ok(()),
+                Ok(_) => Err("the url must start with http:// or https://".to_string()),
+                Err(e) => Err(format!("{} isn't a valid url: {}", answer, e)),
+            },
+        )?;
+    args.extend(vec!["--url".to_string(), target]);
+
+    let words = prompter.ask_until("Wordlist", &wordlist(), |answer| {
+        if Path::new(answer).is_file() {
+            Ok(())
+        } else {
+            Err(format!("{} doesn't exist", answer))
+        }
+    })?;
+
+    if words != wordlist() {
+        args.extend(vec!["--wordlist".to_string(), words]);
+    }
+
+    let extensions = prompter.ask("Extensions to append to each word (ex: php,html)", "")?;
+
+    if !extensions.is_empty() {
+        args.extend(vec![
+            "--extensions".to_string(),
+            split_values(&extensions).join(","),
+        ]);
+    }
+
+    let auth = prompter.ask_until(
+        "Authentication: (n)one, (b)asic, bearer (t)oken, (c)ookie, or custom (h)eader",
+        "n",
+        |answer| match answer {
+            "n" | "b" | "t" | "c" | "h" => Ok(()),
+            _ => Err("please answer n, b, t, c, or h".to_string()),
+        },
+    )?;
+
+    let header = match auth.as_str() {
+        "b" => {
+            let username = prompter.ask("Username", "")?;
+            let password = prompter.ask("Password", "")?;
+            let credentials = base64::encode(format!("{}:{}", username, password));
+            Some(format!("Authorization: Basic {}", credentials))
+        }
+        "t" => {
+            let token = prompter.ask_until("Token", "", |answer| {
+                if answer.is_empty() {
+                    Err("the token can't be empty".to_string())
+                } else {
+                    Ok(())
+                }
+            })?;
+            Some(format!("Authorization: Bearer {}", token))
+        }
+        "c" => Some(format!(
+            "Cookie: {}",
+            prompter.ask("Cookie (ex: session=abc123)", "")?
+        )),
+        "h" => Some(
+            prompter.ask_until("Header (ex: X-Api-Key: abc123)", "", |answer| match answer
+                .split_once(':')
+            {
+                Some((name, _)) if !name.trim().is_empty() => Ok(()),
+                _ => Err("headers look like Name: value".to_string()),
+            })?,
+        ),
+        _ => None,
+    };
+
+    if let Some(header) = header {
+        args.extend(vec!["--headers".to_string(), header]);
+    }
+
+    let default_codes = status_codes()
+        .iter()
+        .map(|code| code.to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    let codes = prompter.ask_until("Status codes to report", &default_codes, |answer| {
+        for code in split_values(answer) {
+            if StatusCode::from_bytes(code.as_bytes()).is_err() {
+                return Err(format!("{} isn't a valid status code", code));
+            }
+        }
+        Ok(())
+    })?;
+
+    let codes = split_values(&codes).join(",");
+
+    if codes != default_codes {
+        args.extend(vec!["--status-codes".to_string(), codes]);
+    }
+
+    let sizes = prompter.ask_until(
+        "Response sizes to filter out (ex: 4242, 10kb)",
+        "",
+        |answer| {
+            for size in split_values(answer) {
+                parse_size(size)?;
+            }
+            Ok(())
+        },
+    )?;
+
+    if !sizes.is_empty() {
+        args.extend(vec![
+            "--filter-size".to_string(),
+            split_values(&sizes).join(","),
+        ]);
+    }
+
+    let default_threads = threads().to_string();
+
+    let thread_count =
+        prompter.ask_until("Number of threads", &default_threads, |answer| match answer
+            .parse::<usize>()
+        {
+            Ok(count) if count > 0 => Ok(()),
+            _ => Err("the number of threads must be a whole number above 0".to_string()),
+        })?;
+
+    if thread_count != default_threads {
+        args.extend(vec!["--threads".to_string(), thread_count]);
+    }
+
+    let default_depth = depth().to_string();
+
+    let recursion_depth = prompter.ask_until(
+        "Maximum recursion depth (0 is infinite)",
+        &default_depth,
+        |answer| match answer.parse::<usize>() {
+            Ok(_) => Ok(()),
+            Err(_) => Err("the depth must be a whole number".to_string()),
+        },
+    )?;
+
+    if recursion_depth != default_depth {
+        args.extend(vec!["--depth".to_string(), recursion_depth]);
+    }
+
+    let command = args
+        .iter()
+        .map(|arg| shell_quote(arg))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    writeln!(
+        prompter.output,
+        "\nThe equivalent command line is\n\n    feroxbuster {}\n",
+        command
+    )?;
+
+    let launch = prompter.confirm("Start the scan now?", true)?;
+
+    Ok(WizardOutcome { args, launch })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    /// arguments that can't be quoted as-is are wrapped in single quotes
+    fn shell_quote_only_quotes_when_needed() {
+        assert_eq!(shell_quote("https://example.com"), "https://example.com");
+        assert_eq!(shell_quote("Cookie: a=b"), "'Cookie: a=b'");
+        assert_eq!(shell_quote("it's"), r"'it'\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    /// answers are turned into arguments; defaults and invalid answers don't produce any
+    fn run_builds_arguments_from_answers() {
+        let wordlist_file = NamedTempFile::new().unwrap();
+        let words = wordlist_file.path().to_str().unwrap();
+
+        let answers = [
+            "ftp://example.com",   // rejected scheme
+            "https://example.com", // target
+            words,                 // wordlist
+            "php, html",           // extensions
+            "t",                   // auth
+            "abc123",              // token
+            "200,999x",            // rejected status codes
+            "200 301",             // status codes
+            "",                    // filter sizes
+            "",                    // threads
+            "2",                   // depth
+            "n",                   // launch
+        ];
+
+        let input = Cursor::new(answers.join("\n") + "\n");
+        let mut output = Vec::new();
+        let existing = vec!["--insecure".to_string()];
+
+        let outcome = run(input, &mut output, &existing).unwrap();
+
+        assert_eq!(
+            outcome.args,
+            vec![
+                "--insecure",
+                "--url",
+                "https://example.com",
+                "--wordlist",
+                words,
+                "--extensions",
+                "php,html",
+                "--headers",
+                "Authorization: Bearer abc123",
+                "--status-codes",
+                "200,301",
+                "--depth",
+                "2"
+            ]
+        );
+        assert!(!outcome.launch);
+
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("the url must start with http:// or https://"));
+        assert!(output.contains("999x isn't a valid status code"));
+        assert!(output.contains("'Authorization: Bearer abc123'"));
+    }
+
+    #[test]
+    /// running out of input is an error instead of an endless loop
+    fn run_errors_when_input_ends() {
+        let input = Cursor::new("https://example.com\n");
+
+        assert!(run(input, Vec::new(), &[]).is_err());
+    }
+}
@@ -0,0 +1,250 @@
+//! probe discovered endpoints for overly permissive cross-origin resource sharing policies
+use std::sync::Arc;
+
+use anyhow::Result;
+use reqwest::header::{
+    HeaderMap, ACCESS_CONTROL_ALLOW_CREDENTIALS, ACCESS_CONTROL_ALLOW_ORIGIN, ORIGIN,
+};
+use reqwest::{Method, Url};
+use serde_json::json;
+
+use crate::{
+    event_handlers::Handles,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{send_to_target, target_request, with_user_headers},
+};
+
+/// an origin that no application should trust
+const UNTRUSTED_ORIGIN: &str = "https://ferox-cors-probe.example";
+
+/// A permissive CORS policy, along with how bad it is
+#[derive(Debug, Clone, PartialEq)]
+struct CorsIssue {
+    /// high, medium, or low
+    severity: &'static str,
+
+    /// short description of the problem
+    description: &'static str,
+
+    /// whether or not the policy allows credentialed requests
+    credentials: bool,
+}
+
+/// Origins sent to the given url, in order of how much they'd reveal about the policy
+///
+/// - an arbitrary, untrusted origin
+/// - the `null` origin (sandboxed iframes, `file://` pages, etc)
+/// - an attacker-controlled domain that starts with the target's host (broken prefix matching)
+fn probe_origins(url: &Url) -> Vec<String> {
+    let mut origins = vec![UNTRUSTED_ORIGIN.to_string(), "null".to_string()];
+
+    if let Some(host) = url.host_str() {
+        origins.push(format!(
+            "{}://{}.ferox-cors-probe.example",
+            url.scheme(),
+            host
+        ));
+    }
+
+    origins
+}
+
+/// Determine whether or not the CORS headers returned in response to a request sent with the given
+/// `origin` are overly permissive; `None` when they aren't
+fn classify(origin: &str, headers: &HeaderMap) -> Option<CorsIssue> {
+    let allowed_origin = headers
+        .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)?;
+
+    let credentials = headers
+        .get(ACCESS_CONTROL_ALLOW_CREDENTIALS)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().eq_ignore_ascii_case("true"))
+        .unwrap_or(false);
+
+    let issue = |severity, description| {
+        Some(CorsIssue {
+            severity,
+            description,
+            credentials,
+        })
+    };
+
+    match (allowed_origin, credentials) {
+        ("*", true) => issue("low", "wildcard origin allowed with credentials"),
+        ("*", false) => None,
+        (allowed, true) if allowed == origin => {
+            issue("high", "untrusted origin reflected with credentials")
+        }
+        (allowed, false) if allowed == origin => issue("medium", "untrusted origin reflected"),
+        _ => None,
+    }
+}
+
+/// Send requests with untrusted `Origin` headers to the url of the given response and report the
+/// first overly permissive policy found
+///
+/// `Ok(None)` is returned when the endpoint was already probed or its policy looks sane
+pub async fn probe(
+    response: &FeroxResponse,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let url = response.url().clone();
+
+    if handles.denied_by_regex(&url) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    if !handles.first_probe("cors", url.as_str()) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    let context = &handles.config.request_context;
+
+    for origin in probe_origins(&url) {
+        let request = target_request(context, &handles.config.client, &Method::GET, &url);
+        let request = with_user_headers(context, &handles.session, request, &url, None)
+            .header(ORIGIN, &origin);
+        let probe_response = send_to_target(context, request, &url).await?;
+        let probe_response =
+            FeroxResponse::from(probe_response, false, 0, handles.config.output_level).await;
+
+        if let Some(issue) = classify(&origin, probe_response.headers()) {
+            let finding = FeroxFinding::new(
+                "cors",
+                url.as_str(),
+                &format!(
+                    "[{}] {} (Origin: {})",
+                    issue.severity, issue.description, origin
+                ),
+                json!({
+                    "severity": issue.severity,
+                    "issue": issue.description,
+                    "origin": origin,
+                    "allow_credentials": issue.credentials,
+                }),
+            )
+            .with_exchange(&probe_response);
+
+            log::trace!("exit: probe -> {:?}", finding);
+            return Ok(Some(finding));
+        }
+    }
+
+    log::trace!("exit: probe -> None");
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::GET, MockServer};
+    use reqwest::header::HeaderValue;
+
+    /// helper to build the CORS response headers
+    fn cors_headers(origin: &str, credentials: Option<&'static str>) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCESS_CONTROL_ALLOW_ORIGIN,
+            HeaderValue::from_str(origin).unwrap(),
+        );
+
+        if let Some(credentials) = credentials {
+            headers.insert(
+                ACCESS_CONTROL_ALLOW_CREDENTIALS,
+                HeaderValue::from_static(credentials),
+            );
+        }
+
+        headers
+    }
+
+    #[test]
+    /// reflected origins and wildcard + credentials are flagged, sane policies aren't
+    fn classify_flags_permissive_policies() {
+        let origin = UNTRUSTED_ORIGIN;
+
+        let reflected = classify(origin, &cors_headers(origin, Some("true"))).unwrap();
+        assert_eq!(reflected.severity, "high");
+
+        let reflected = classify(origin, &cors_headers(origin, None)).unwrap();
+        assert_eq!(reflected.severity, "medium");
+
+        let wildcard = classify(origin, &cors_headers("*", Some("true"))).unwrap();
+        assert_eq!(wildcard.severity, "low");
+
+        assert!(classify(origin, &cors_headers("*", None)).is_none());
+        assert!(classify(
+            origin,
+            &cors_headers("https://trusted.example", Some("true"))
+        )
+        .is_none());
+        assert!(classify(origin, &HeaderMap::new()).is_none());
+    }
+
+    #[test]
+    /// the prefix-matching origin is built from the target's host
+    fn probe_origins_includes_prefixed_host() {
+        let url = Url::parse("https://app.example.com/api").unwrap();
+
+        assert_eq!(
+            probe_origins(&url),
+            vec![
+                UNTRUSTED_ORIGIN,
+                "null",
+                "https://app.example.com.ferox-cors-probe.example"
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a null origin reflected with credentials is reported once per endpoint and scan
+    async fn probe_reports_reflected_null_origin_once() {
+        let srv = MockServer::start();
+
+        let null = srv.mock(|when, then| {
+            when.method(GET).path("/api/me").header("origin", "null");
+            then.status(200)
+                .header("access-control-allow-origin", "null")
+                .header("access-control-allow-credentials", "true");
+        });
+
+        let other = srv.mock(|when, then| {
+            when.method(GET)
+                .path("/api/me")
+                .header("origin", UNTRUSTED_ORIGIN);
+            then.status(200);
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&srv.url("/api/me"));
+
+        let finding = probe(&response, handles.clone()).await.unwrap().unwrap();
+
+        assert_eq!(finding.category(), "cors");
+        assert_eq!(finding.details()["severity"], "high");
+        assert_eq!(finding.details()["origin"], "null");
+        assert_eq!(other.hits(), 1);
+        assert_eq!(null.hits(), 1);
+
+        assert!(probe(&response, handles).await.unwrap().is_none());
+        assert_eq!(null.hits(), 1);
+
+        // the next scan probes the endpoint again
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        assert!(probe(&response, handles).await.unwrap().is_some());
+        assert_eq!(null.hits(), 2);
+    }
+}
@@ -0,0 +1,295 @@
+//! crawl each target before brute forcing it and add the words found along the way (page
+//! content, path segments, and parameter names) to the wordlist (--crawl-wordlist)
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{Method, Url};
+
+use crate::{event_handlers::Handles, utils::make_request};
+
+/// most pages requested while crawling a single target, however deep the crawl goes
+const MAX_CRAWL_PAGES: usize = 500;
+
+lazy_static! {
+    /// href/src/action attributes; the links followed while crawling
+    static ref LINK: Regex =
+        Regex::new(r#"(?i)\b(?:href|src|action)\s*=\s*["']([^"'#\s]+)"#).unwrap();
+
+    /// name attributes of form fields
+    static ref FIELD_NAME: Regex =
+        Regex::new(r#"(?i)<(?:input|select|textarea)\b[^>]*\bname\s*=\s*["']([^"']+)["']"#)
+            .unwrap();
+
+    /// scripts, styles, and comments, whose content isn't prose
+    static ref NOT_PROSE: Regex =
+        Regex::new(r"(?is)<script\b.*?</script>|<style\b.*?</style>|<!--.*?-->").unwrap();
+
+    /// any other tag
+    static ref TAG: Regex = Regex::new(r"(?s)<[^>]*>").unwrap();
+
+    /// candidate words: 3 to 30 letters, digits, dashes, and underscores, starting with a letter
+    static ref WORD: Regex = Regex::new(r"\b[A-Za-z][A-Za-z0-9_-]{2,29}\b").unwrap();
+}
+
+/// Words found in a single page
+#[derive(Debug, Default)]
+struct PageWords {
+    /// words, path segments, and parameter names, in the order they were found
+    words: Vec<String>,
+
+    /// links to other pages on the same host
+    links: Vec<Url>,
+}
+
+/// Pull words, path segments, parameter names, and same-host links out of the page at `url`
+fn page_words(url: &Url, page: &str) -> PageWords {
+    let mut found = PageWords::default();
+
+    let prose = NOT_PROSE.replace_all(page, " ");
+    let prose = TAG.replace_all(&prose, " ");
+
+    found
+        .words
+        .extend(WORD.find_iter(&prose).map(|word| word.as_str().to_string()));
+
+    for captures in FIELD_NAME.captures_iter(page) {
+        found.words.push(captures[1].to_string());
+    }
+
+    for captures in LINK.captures_iter(page) {
+        let link = match url.join(&captures[1]) {
+            Ok(link) if link.host_str() == url.host_str() && link.scheme().starts_with("http") => {
+                link
+            }
+            _ => continue,
+        };
+
+        for segment in link.path_segments().into_iter().flatten() {
+            if !segment.is_empty() {
+                found.words.push(segment.to_string());
+            }
+        }
+
+        for (name, _) in link.query_pairs() {
+            if !name.is_empty() {
+                found.words.push(name.into_owned());
+            }
+        }
+
+        found.links.push(link);
+    }
+
+    found
+}
+
+/// Crawl `target`, following same-host links up to `depth` times, and return the words found
+async fn crawl(target: &str, depth: usize, handles: Arc<Handles>) -> Vec<String> {
+    log::trace!("enter: crawl({}, {}, {:?})", target, depth, handles);
+
+    let mut words = Vec::new();
+
+    let start = match Url::parse(target) {
+        Ok(start) => start,
+        Err(e) => {
+            log::warn!("Could not crawl {}: {}", target, e);
+            return words;
+        }
+    };
+
+    let mut visited = HashSet::new();
+    let mut queue = VecDeque::new();
+
+    visited.insert(start.to_string());
+    queue.push_back((start, 0));
+
+    while let Some((url, hops)) = queue.pop_front() {
+        if visited.len() > MAX_CRAWL_PAGES {
+            log::info!(
+                "Stopped crawling {} after {} pages",
+                target,
+                MAX_CRAWL_PAGES
+            );
+            break;
+        }
+
+        if handles.denied_by_regex(&url) {
+            continue;
+        }
+
+        let response = match make_request(
+            &handles.config.client,
+            &Method::GET,
+            &url,
+            None,
+            &handles.config,
+            &handles.session,
+            handles.stats.batched(),
+        )
+        .await
+        {
+            Ok(response) => response,
+            Err(e) => {
+                log::warn!("Could not crawl {}: {}", url, e);
+                continue;
+            }
+        };
+
+        let page = match response.text().await {
+            Ok(page) => page,
+            Err(_) => continue,
+        };
+
+        let found = page_words(&url, &page);
+        words.extend(found.words);
+
+        if hops >= depth {
+            continue;
+        }
+
+        for link in found.links {
+            if visited.insert(link.to_string()) {
+                queue.push_back((link, hops + 1));
+            }
+        }
+    }
+
+    log::trace!("exit: crawl -> [{} words...]", words.len());
+    words
+}
+
+/// Crawl each target and append the words found that aren't in `words` already; `words` is
+/// returned untouched when nothing new was found
+pub async fn extend_wordlist(
+    words: Arc<Vec<String>>,
+    targets: &[String],
+    handles: Arc<Handles>,
+) -> Arc<Vec<String>> {
+    log::trace!(
+        "enter: extend_wordlist(wordlist[{} words...], {:?}, {:?})",
+        words.len(),
+        targets,
+        handles
+    );
+
+    let depth = handles.config.crawl_wordlist;
+    let mut seen: HashSet<String> = words.iter().cloned().collect();
+    let mut additions = Vec::new();
+
+    for target in targets {
+        for word in crawl(target, depth, handles.clone()).await {
+            if seen.insert(word.clone()) {
+                additions.push(word);
+            }
+        }
+    }
+
+    if additions.is_empty() {
+        log::trace!(
+            "exit: extend_wordlist -> wordlist[{} words...]",
+            words.len()
+        );
+        return words;
+    }
+
+    log::info!("crawling added {} words to the wordlist", additions.len());
+
+    let mut combined = Vec::with_capacity(words.len() + additions.len());
+    combined.extend(words.iter().cloned());
+    combined.extend(additions);
+
+    log::trace!(
+        "exit: extend_wordlist -> wordlist[{} words...]",
+        combined.len()
+    );
+    Arc::new(combined)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::GET, MockServer};
+
+    #[test]
+    /// prose, path segments, query and form field names are collected; scripts and links to
+    /// other hosts aren't
+    fn page_words_collects_words_segments_and_parameters() {
+        let url = Url::parse("http://localhost/blog/").unwrap();
+        let page = r#"
+            <html><head><script>var ignored = 1;</script></head>
+            <body>
+              <h1>Quarterly Reports</h1>
+              <a href="archive/2019.html?year=2019#top">old</a>
+              <a href="https://elsewhere.test/partners">friends</a>
+              <form action="/search"><input type="text" name="query_term"></form>
+            </body></html>
+        "#;
+
+        let found = page_words(&url, page);
+
+        for word in &[
+            "Quarterly",
+            "Reports",
+            "archive",
+            "2019.html",
+            "year",
+            "search",
+            "query_term",
+        ] {
+            assert!(found.words.contains(&word.to_string()), "{}", word);
+        }
+
+        assert!(!found.words.contains(&"ignored".to_string()));
+        assert!(!found.words.contains(&"partners".to_string()));
+        assert_eq!(
+            found.links,
+            vec![
+                Url::parse("http://localhost/blog/archive/2019.html?year=2019").unwrap(),
+                Url::parse("http://localhost/search").unwrap(),
+            ]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// links are followed up to the given depth, and only new words are appended
+    async fn extend_wordlist_adds_crawled_words() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(200)
+                .body(r#"<p>welcome aboard</p><a href="/staff/directory">staff</a>"#);
+        });
+
+        let staff = srv.mock(|when, then| {
+            when.method(GET).path("/staff/directory");
+            then.status(200)
+                .body(r#"<p>payroll</p><a href="/deeper">deeper</a>"#);
+        });
+
+        let deeper = srv.mock(|when, then| {
+            when.method(GET).path("/deeper");
+            then.status(200).body("<p>unreachable</p>");
+        });
+
+        let config = Configuration {
+            crawl_wordlist: 1,
+            ..Default::default()
+        };
+        let handles = Arc::new(Handles::for_testing(None, Some(Arc::new(config))).0);
+
+        let words = Arc::new(vec![String::from("welcome")]);
+        let words = extend_wordlist(words, &[srv.url("/")], handles).await;
+
+        assert_eq!(words[0], "welcome");
+        assert!(words.contains(&String::from("aboard")));
+        assert!(words.contains(&String::from("staff")));
+        assert!(words.contains(&String::from("payroll")));
+        assert!(!words.contains(&String::from("unreachable")));
+        assert_eq!(words.iter().filter(|word| *word == "welcome").count(), 1);
+        assert_eq!(staff.hits(), 1);
+        assert_eq!(deeper.hits(), 0);
+    }
+}
@@ -0,0 +1,325 @@
+//! read a csrf token from a page and send it with the requests that have a body, fetching a new
+//! one whenever the server starts rejecting it (`[csrf]` in the config file)
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, Result};
+use regex::Regex;
+use reqwest::{
+    header::{HeaderName, COOKIE},
+    Method, RequestBuilder, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    event_handlers::Handles,
+    login::{session_cookies, Session},
+    utils::make_request,
+};
+
+/// minimum time between two fetches of a new token; keeps an endpoint that always answers with a
+/// 403 from turning into a token fetch per request
+const REFRESH_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Where a csrf token is read from, and how it's sent back
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Csrf {
+    /// page the token is read from
+    pub url: String,
+
+    /// form field the token is sent in; without a `regex`, also the name of the `<input>` or
+    /// `<meta>` tag whose value is the token
+    pub name: String,
+
+    /// regex whose first capture group, found in the page, is the token
+    #[serde(default)]
+    pub regex: String,
+
+    /// header the token is sent in as well, i.e. X-CSRF-Token
+    #[serde(default)]
+    pub header: String,
+}
+
+impl Csrf {
+    /// Problems with the values of this csrf definition, worded like `Configuration::validate`'s
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Err(e) = Url::parse(&self.url) {
+            problems.push(format!("Invalid csrf url {}: {}", self.url, e));
+        }
+
+        if self.name.is_empty() {
+            problems.push("csrf needs the name of the field its token is sent in".to_string());
+        }
+
+        if let Err(e) = Regex::new(&self.regex) {
+            problems.push(format!("Invalid csrf regex {:?}: {}", self.regex, e));
+        }
+
+        if !self.header.is_empty() && HeaderName::from_bytes(self.header.as_bytes()).is_err() {
+            problems.push(format!("Invalid csrf header {}", self.header));
+        }
+
+        problems
+    }
+
+    /// Find the token in the given page
+    fn extract(&self, page: &str) -> Option<String> {
+        if !self.regex.is_empty() {
+            return Regex::new(&self.regex)
+                .ok()?
+                .captures(page)?
+                .get(1)
+                .map(|token| token.as_str().to_string());
+        }
+
+        // <input type="hidden" name="NAME" value="TOKEN"> or <meta name="NAME" content="TOKEN">
+        let tag = format!(
+            r#"(?i)<(?:input|meta)\b[^>]*\bname\s*=\s*["']{}["'][^>]*>"#,
+            regex::escape(&self.name)
+        );
+
+        let tag = Regex::new(&tag).ok()?.find(page)?.as_str();
+
+        Regex::new(r#"(?i)\b(?:value|content)\s*=\s*["']([^"']*)["']"#)
+            .ok()?
+            .captures(tag)
+            .map(|captures| captures[1].to_string())
+    }
+}
+
+/// A token read from the csrf page, along with the cookies it's tied to
+#[derive(Debug, Clone, PartialEq)]
+pub struct CsrfToken {
+    /// the token itself
+    pub value: String,
+
+    /// `name=value` of each cookie set by the page; frameworks that double-submit the token
+    /// (django, laravel) only accept it alongside its cookie
+    cookies: Vec<String>,
+
+    /// when the token was fetched
+    fetched: Instant,
+}
+
+impl CsrfToken {
+    /// Add the token's header and cookies to the given request; the token's form field is up to
+    /// the caller, since it's part of the body
+    pub fn add_to(
+        &self,
+        csrf: &Csrf,
+        session: &Session,
+        mut request: RequestBuilder,
+        headers: &HashMap<String, String>,
+    ) -> RequestBuilder {
+        if !csrf.header.is_empty() {
+            request = request.header(csrf.header.as_str(), self.value.as_str());
+        }
+
+        if self.cookies.is_empty() {
+            return request;
+        }
+
+        // the cookies already being sent (a [login] session's, or -H's) go along with the token's
+        let mut cookies: Vec<String> = session
+            .headers()
+            .into_iter()
+            .find(|(name, _)| *name == COOKIE)
+            .and_then(|(_, value)| value.to_str().ok().map(String::from))
+            .or_else(|| {
+                headers
+                    .iter()
+                    .find(|(name, _)| name.eq_ignore_ascii_case("cookie"))
+                    .map(|(_, value)| value.trim().to_string())
+            })
+            .into_iter()
+            .collect();
+
+        cookies.extend(self.cookies.iter().cloned());
+
+        request.header(COOKIE, cookies.join("; "))
+    }
+}
+
+/// Whether the given status is how servers usually reject a missing or expired csrf token (403:
+/// django, spring, express; 419: laravel; 422: rails)
+pub fn rejected(status: StatusCode) -> bool {
+    matches!(status.as_u16(), 403 | 419 | 422)
+}
+
+/// Request the csrf page and read a new token from it
+async fn fetch(csrf: &Csrf, handles: &Arc<Handles>) -> Result<CsrfToken> {
+    log::trace!("enter: fetch({})", csrf.url);
+
+    let url = Url::parse(&csrf.url)?;
+
+    let response = make_request(
+        &handles.config.client,
+        &Method::GET,
+        &url,
+        None,
+        &handles.config,
+        &handles.session,
+        handles.stats.batched(),
+    )
+    .await?;
+
+    let cookies = session_cookies(response.headers(), &[]);
+    let page = response.text().await?;
+
+    let value = csrf.extract(&page).ok_or_else(|| {
+        anyhow!(
+            "No csrf token named {} was found at {}",
+            csrf.name,
+            csrf.url
+        )
+    })?;
+
+    let token = CsrfToken {
+        value,
+        cookies,
+        fetched: Instant::now(),
+    };
+
+    log::trace!("exit: fetch -> {:?}", token);
+    Ok(token)
+}
+
+/// Token to send with the next request that has a body, fetching one first if needed; `None`
+/// without a `[csrf]` in the configuration
+pub async fn current(handles: &Arc<Handles>) -> Result<Option<CsrfToken>> {
+    let csrf = match &handles.config.csrf {
+        Some(csrf) => csrf,
+        None => return Ok(None),
+    };
+
+    let mut token = handles.csrf_token.lock().await;
+
+    if token.is_none() {
+        *token = Some(fetch(csrf, handles).await?);
+    }
+
+    Ok(token.clone())
+}
+
+/// Fetch a new token after `stale` was rejected, returning the token to try again with
+///
+/// when another request already replaced `stale`, its replacement is returned without fetching;
+/// otherwise new tokens are fetched at most once every `REFRESH_INTERVAL`
+pub async fn refresh(stale: &CsrfToken, handles: &Arc<Handles>) -> Option<CsrfToken> {
+    let csrf = handles.config.csrf.as_ref()?;
+    let mut token = handles.csrf_token.lock().await;
+
+    if token.as_ref() != Some(stale) {
+        return token.clone();
+    }
+
+    if stale.fetched.elapsed() < REFRESH_INTERVAL {
+        return None;
+    }
+
+    match fetch(csrf, handles).await {
+        Ok(fresh) => {
+            log::debug!("csrf token was rejected, using a new one");
+            *token = Some(fresh);
+            token.clone()
+        }
+        Err(e) => {
+            log::warn!("csrf token was rejected, could not get a new one: {}", e);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::GET, MockServer};
+
+    /// csrf definition read from the `csrf_token` field
+    fn test_csrf(regex: &str) -> Csrf {
+        Csrf {
+            url: String::from("http://localhost/form"),
+            name: String::from("csrf_token"),
+            regex: regex.to_string(),
+            header: String::new(),
+        }
+    }
+
+    #[test]
+    /// tokens are found in hidden inputs and meta tags by name, or with a regex
+    fn extract_finds_token() {
+        let csrf = test_csrf("");
+
+        let page = r#"<form><input type="hidden" name="csrf_token" value="abc123"></form>"#;
+        assert_eq!(csrf.extract(page), Some(String::from("abc123")));
+
+        let page = r#"<head><meta content='def456' name='csrf_token'></head>"#;
+        assert_eq!(csrf.extract(page), Some(String::from("def456")));
+
+        let page = r#"<input name="other_token" value="nope">"#;
+        assert_eq!(csrf.extract(page), None);
+
+        let csrf = test_csrf(r#"window\.csrf = "([^"]+)""#);
+        let page = r#"<script>window.csrf = "ghi789";</script>"#;
+        assert_eq!(csrf.extract(page), Some(String::from("ghi789")));
+    }
+
+    #[test]
+    /// the statuses used by common frameworks are treated as rejections
+    fn rejected_matches_framework_statuses() {
+        assert!(rejected(StatusCode::FORBIDDEN));
+        assert!(rejected(StatusCode::from_u16(419).unwrap()));
+        assert!(rejected(StatusCode::UNPROCESSABLE_ENTITY));
+        assert!(!rejected(StatusCode::OK));
+        assert!(!rejected(StatusCode::METHOD_NOT_ALLOWED));
+    }
+
+    #[test]
+    /// bad urls, names, patterns, and headers are reported
+    fn problems_reports_bad_values() {
+        let mut csrf = test_csrf("");
+        assert!(csrf.problems().is_empty());
+
+        csrf.url = String::from("nope");
+        csrf.name = String::new();
+        csrf.regex = String::from("(");
+        csrf.header = String::from("X CSRF");
+
+        assert_eq!(csrf.problems().len(), 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a token is fetched once per scan, and isn't carried over into the next scan
+    async fn current_fetches_once_per_scan() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET).path("/form");
+            then.status(200)
+                .body(r#"<input type="hidden" name="csrf_token" value="abc123">"#);
+        });
+
+        let mut config = Configuration::new().unwrap();
+        config.csrf = Some(Csrf {
+            url: srv.url("/form"),
+            ..test_csrf("")
+        });
+        let config = Arc::new(config);
+
+        let first = Arc::new(Handles::for_testing(None, Some(config.clone())).0);
+        let token = current(&first).await.unwrap().unwrap();
+        assert_eq!(token.value, "abc123");
+        assert_eq!(current(&first).await.unwrap(), Some(token));
+        assert_eq!(mock.hits(), 1);
+
+        let second = Arc::new(Handles::for_testing(None, Some(config)).0);
+        assert!(current(&second).await.unwrap().is_some());
+        assert_eq!(mock.hits(), 2);
+    }
+}
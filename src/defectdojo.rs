@@ -0,0 +1,301 @@
+//! write results in DefectDojo's generic findings import format (--defectdojo-output)
+use std::{
+    fs::File,
+    io::{BufWriter, Seek, SeekFrom, Write},
+};
+
+use anyhow::{Context, Result};
+use reqwest::{header::LOCATION, Url};
+use serde_json::{json, Value};
+
+use crate::{
+    burp::method,
+    config::Configuration,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{civil_date, fmt_err, unix_timestamp},
+    VERSION,
+};
+
+/// closes the findings array and the document; rewritten after every finding so that the file is
+/// always valid json
+const FOOTER: &str = "\n]}\n";
+
+/// longest title written, DefectDojo rejects anything over 511 characters
+const MAX_TITLE: usize = 255;
+
+/// Writes each result it's given to a file that DefectDojo imports with its "Generic Findings
+/// Import" scan type
+///
+/// responses become informational findings (one per discovered resource), while findings from the
+/// analyzers keep the severity they were reported with; the closing `]}` is written after every
+/// finding and then overwritten by the next one, so a scan that's killed midway still leaves an
+/// importable file behind
+#[derive(Debug)]
+pub struct DefectDojoWriter {
+    /// the --defectdojo-output file
+    file: BufWriter<File>,
+
+    /// number of findings written so far
+    written: usize,
+
+    /// description of the scan that's appended to every finding
+    scan: String,
+}
+
+/// implementation of DefectDojoWriter
+impl DefectDojoWriter {
+    /// create (or truncate) the given file and write the start of the document to it
+    pub fn create(filename: &str, config: &Configuration) -> Result<Self> {
+        let file = File::create(filename)
+            .with_context(|| fmt_err(&format!("Could not create {}", filename)))?;
+
+        let mut writer = Self {
+            file: BufWriter::new(file),
+            written: 0,
+            scan: scan_description(config, unix_timestamp()),
+        };
+
+        let header = json!({
+            "name": "feroxbuster",
+            "type": "feroxbuster Scan",
+            "version": VERSION,
+            "description": writer.scan,
+        })
+        .to_string();
+
+        // everything but the closing brace of the header, followed by the start of the findings
+        write!(
+            writer.file,
+            "{},\"findings\":[",
+            &header[..header.len() - 1]
+        )?;
+        writer.close()?;
+
+        Ok(writer)
+    }
+
+    /// append the given response as an informational finding
+    pub fn write_response(&mut self, response: &FeroxResponse) -> Result<()> {
+        let value = self.response_finding(response, unix_timestamp());
+        self.append(&value)
+    }
+
+    /// append the given finding, keeping the severity it was reported with
+    pub fn write_finding(&mut self, finding: &FeroxFinding) -> Result<()> {
+        let value = self.finding(finding, unix_timestamp());
+        self.append(&value)
+    }
+
+    /// add the given value to the findings array
+    fn append(&mut self, value: &Value) -> Result<()> {
+        let separator = if self.written == 0 { "\n" } else { ",\n" };
+
+        // overwrite the footer written after the previous finding
+        self.file.seek(SeekFrom::End(-(FOOTER.len() as i64)))?;
+        write!(self.file, "{}{}", separator, value)?;
+        self.written += 1;
+        self.close()
+    }
+
+    /// write the end of the document and flush everything to disk
+    fn close(&mut self) -> Result<()> {
+        self.file.write_all(FOOTER.as_bytes())?;
+        self.file.flush()?;
+        Ok(())
+    }
+
+    /// a discovered resource, as a DefectDojo finding
+    fn response_finding(&self, response: &FeroxResponse, timestamp: u64) -> Value {
+        let url = response.url();
+
+        let mut description = format!(
+            "{} {} responded with {} ({} bytes, {} words, {} lines)",
+            method(response),
+            url,
+            response.status().as_u16(),
+            response.content_length(),
+            response.word_count(),
+            response.line_count()
+        );
+
+        if let Some(location) = response
+            .headers()
+            .get(LOCATION)
+            .and_then(|value| value.to_str().ok())
+        {
+            description.push_str(&format!(", redirecting to {}", location));
+        }
+
+        json!({
+            "title": truncate(&format!("Discovered {} ({})", url.path(), response.status().as_u16())),
+            "severity": "Info",
+            "description": format!("{}\n\n{}", description, self.scan),
+            "date": date(timestamp),
+            "unique_id_from_tool": url.as_str(),
+            "vuln_id_from_tool": "discovered-resource",
+            "static_finding": false,
+            "dynamic_finding": true,
+            "endpoints": endpoints(url.as_str()),
+        })
+    }
+
+    /// a finding reported by one of the analyzers, as a DefectDojo finding
+    fn finding(&self, finding: &FeroxFinding, timestamp: u64) -> Value {
+        json!({
+            "title": truncate(&format!("{} finding: {}", finding.category(), finding.summary())),
+            "severity": severity(finding),
+            "description": format!(
+                "{} at {}\n\n{}\n\n{}",
+                finding.summary(),
+                finding.url(),
+                serde_json::to_string_pretty(finding.details()).unwrap_or_default(),
+                self.scan
+            ),
+            "date": date(timestamp),
+            "unique_id_from_tool": format!("{} {} {}", finding.category(), finding.url(), finding.summary()),
+            "vuln_id_from_tool": finding.category(),
+            "static_finding": false,
+            "dynamic_finding": true,
+            "endpoints": endpoints(finding.url()),
+        })
+    }
+}
+
+/// the options that shape a scan's results, along with when it started
+fn scan_description(config: &Configuration, timestamp: u64) -> String {
+    let (year, month, day) = civil_date(timestamp);
+    let seconds = timestamp % 86400;
+
+    let status_codes: Vec<_> = config
+        .status_codes
+        .iter()
+        .map(|code| code.to_string())
+        .collect();
+
+    format!(
+        "feroxbuster {} scan of {} started {}-{:02}-{:02}T{:02}:{:02}:{:02}Z (wordlist: {}, threads: {}, depth: {}, status codes: [{}])",
+        VERSION,
+        if config.target_url.is_empty() {
+            "targets read from stdin"
+        } else {
+            config.target_url.as_str()
+        },
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60,
+        config.wordlist,
+        config.threads,
+        config.depth,
+        status_codes.join(", ")
+    )
+}
+
+/// DefectDojo's severity for the given finding
+fn severity(finding: &FeroxFinding) -> &'static str {
+    match finding.severity() {
+        "critical" => "Critical",
+        "high" => "High",
+        "medium" => "Medium",
+        "low" => "Low",
+        _ => "Info",
+    }
+}
+
+/// the given url as a list of DefectDojo endpoints; DefectDojo's paths don't start with a slash
+fn endpoints(url: &str) -> Value {
+    match Url::parse(url) {
+        Ok(url) => json!([{
+            "protocol": url.scheme(),
+            "host": url.host_str().unwrap_or_default(),
+            "port": url.port_or_known_default(),
+            "path": url.path().trim_start_matches('/'),
+            "query": url.query().unwrap_or_default(),
+        }]),
+        Err(_) => json!([]),
+    }
+}
+
+/// the given unix timestamp as the YYYY-MM-DD date DefectDojo expects
+fn date(timestamp: u64) -> String {
+    let (year, month, day) = civil_date(timestamp);
+    format!("{}-{:02}-{:02}", year, month, day)
+}
+
+/// cut the given title down to MAX_TITLE characters
+fn truncate(title: &str) -> String {
+    title.chars().take(MAX_TITLE).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    /// findings are mapped to DefectDojo's severities
+    fn severity_maps_reported_severities() {
+        let high = FeroxFinding::new("cors", "http://localhost/", "", json!({"severity": "high"}));
+        let secret = FeroxFinding::new("secret", "http://localhost/", "", json!({}));
+        let form = FeroxFinding::new("form", "http://localhost/", "", json!({}));
+
+        assert_eq!(severity(&high), "High");
+        assert_eq!(severity(&secret), "High");
+        assert_eq!(severity(&form), "Info");
+        assert_eq!(date(1_792_145_045), "2026-10-16");
+    }
+
+    #[test]
+    /// every result written is a complete finding, and the file is a complete document after each
+    fn defectdojo_writer_writes_complete_findings() {
+        let directory = tempfile::tempdir().unwrap();
+        let filename = directory.path().join("dojo.json");
+        let filename = filename.to_str().unwrap();
+
+        let config = Configuration {
+            target_url: String::from("http://localhost:8080"),
+            ..Default::default()
+        };
+
+        let mut writer = DefectDojoWriter::create(filename, &config).unwrap();
+
+        let contents = fs::read_to_string(filename).unwrap();
+        let document: Value = serde_json::from_str(&contents).unwrap();
+        assert_eq!(document["name"], "feroxbuster");
+        assert_eq!(document["findings"], json!([]));
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost:8080/admin/login.php?next=1");
+        response.set_text("<html>login</html>");
+        writer.write_response(&response).unwrap();
+
+        let finding = FeroxFinding::new(
+            "artifact",
+            "http://localhost:8080/.git/config",
+            "[high] exposed git config",
+            json!({"severity": "high"}),
+        );
+        writer.write_finding(&finding).unwrap();
+
+        let contents = fs::read_to_string(filename).unwrap();
+        let document: Value = serde_json::from_str(&contents).unwrap();
+        let findings = document["findings"].as_array().unwrap();
+
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0]["title"], "Discovered /admin/login.php (200)");
+        assert_eq!(findings[0]["severity"], "Info");
+        assert_eq!(findings[0]["endpoints"][0]["host"], "localhost");
+        assert_eq!(findings[0]["endpoints"][0]["port"], 8080);
+        assert_eq!(findings[0]["endpoints"][0]["path"], "admin/login.php");
+        assert_eq!(findings[0]["endpoints"][0]["query"], "next=1");
+        assert!(findings[0]["description"]
+            .as_str()
+            .unwrap()
+            .contains("scan of http://localhost:8080 started"));
+        assert_eq!(findings[1]["severity"], "High");
+        assert_eq!(findings[1]["vuln_id_from_tool"], "artifact");
+    }
+}
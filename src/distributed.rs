@@ -0,0 +1,1087 @@
+//! distribute a scan across multiple machines
+//!
+//! a coordinator (`--coordinate ADDRESS`) splits each directory's wordlist into shards and hands
+//! them out to any number of workers (`feroxbuster worker --connect ADDRESS`). Workers scan their
+//! shard without recursing and send every result back; the coordinator reports the results,
+//! queues any directories that were found, and merges each worker's statistics into its own.
+//! Shards held by a worker that disconnects or fails are put back on the queue for another worker.
+//!
+//! coordinator and workers talk over TCP, one JSON [Message](enum.Message.html) per line. Each
+//! connection starts with a handshake in which both ends prove they know the shared secret
+//! (--coordinate-secret and `worker --secret`), so the coordinator's configuration is only sent
+//! to its own workers, and only its own workers can report results. The traffic itself isn't
+//! encrypted.
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ArgMatches;
+use futures::{SinkExt, StreamExt};
+use openssl::{hash::MessageDigest, memcmp, pkey::PKey, sha::sha256, sign::Signer};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::{
+    net::{TcpListener, TcpStream},
+    sync::Notify,
+};
+use tokio_util::codec::{Framed, LinesCodec};
+use uuid::Uuid;
+
+use crate::{
+    api::{ScanResult, Scanner},
+    config::{determine_requester_policy, Configuration, OutputLevel},
+    event_handlers::{Command, Handles},
+    statistics::Stats,
+    utils::{load_wordlist, should_deny_url},
+    FeroxFinding, FeroxResponse,
+};
+
+/// Number of times a shard is handed out before it's given up on
+const MAX_ATTEMPTS: usize = 3;
+
+/// A single line sent between the coordinator and a worker
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Message {
+    /// both ways: first message on a connection; the other end must answer with a `Proof`
+    Challenge {
+        /// random value the proof is computed over
+        nonce: String,
+    },
+
+    /// both ways: answer to the other end's `Challenge`, see `authenticate`
+    Proof {
+        /// hmac of the challenge's nonce, keyed by the shared secret
+        proof: String,
+    },
+
+    /// worker -> coordinator: the worker is ready for its next shard
+    Ready,
+
+    /// coordinator -> worker: settings used for every shard; sent once, right after the handshake
+    Configuration {
+        /// the coordinator's configuration
+        configuration: Box<Configuration>,
+    },
+
+    /// coordinator -> worker: scan `url` using only `words`
+    Shard {
+        /// identifies the shard in later messages
+        id: usize,
+
+        /// directory to scan
+        url: String,
+
+        /// part of the wordlist to use
+        words: Vec<String>,
+    },
+
+    /// worker -> coordinator: a response that made it through the worker's filters
+    Response {
+        /// shard during which the response was found
+        shard: usize,
+
+        /// the response itself
+        response: Box<FeroxResponse>,
+    },
+
+    /// worker -> coordinator: something found while analyzing responses
+    Finding {
+        /// shard during which the finding was made
+        shard: usize,
+
+        /// the finding itself
+        finding: Box<FeroxFinding>,
+    },
+
+    /// worker -> coordinator: the shard is done; `statistics` is null when it failed
+    Finished {
+        /// shard that's done
+        shard: usize,
+
+        /// the worker's statistics for this shard
+        statistics: Option<Value>,
+    },
+
+    /// coordinator -> worker: there's nothing left to scan
+    Done,
+}
+
+/// A range of the wordlist to be requested against a single directory
+#[derive(Debug, Clone, PartialEq)]
+struct Shard {
+    /// unique id
+    id: usize,
+
+    /// directory to scan
+    url: String,
+
+    /// number of directories between this one and the initial target
+    level: usize,
+
+    /// index of the first word
+    start: usize,
+
+    /// index one past the last word
+    end: usize,
+
+    /// number of times the shard was handed out
+    attempts: usize,
+}
+
+/// Shards waiting to be scanned and those currently assigned to a worker
+#[derive(Debug, Default)]
+struct Queue {
+    /// shards waiting for a worker
+    pending: VecDeque<Shard>,
+
+    /// shards assigned to a worker, by id
+    in_flight: HashMap<usize, Shard>,
+
+    /// every directory that was queued, so each one is only scanned once
+    directories: HashSet<String>,
+
+    /// id of the next shard created
+    next_id: usize,
+}
+
+/// Hands out shards to workers and collects their results
+#[derive(Debug)]
+struct Coordinator {
+    /// work left to do
+    queue: Mutex<Queue>,
+
+    /// woken whenever the queue changes
+    changed: Notify,
+
+    /// the full wordlist; shards are ranges of it
+    words: Arc<Vec<String>>,
+
+    /// number of words per shard
+    shard_size: usize,
+
+    /// handles to the coordinator's event handlers
+    handles: Arc<Handles>,
+
+    /// secret workers must prove they know (--coordinate-secret)
+    secret: String,
+}
+
+impl Coordinator {
+    /// create a new Coordinator with an empty queue
+    fn new(words: Arc<Vec<String>>, handles: Arc<Handles>) -> Self {
+        Self {
+            queue: Mutex::new(Queue::default()),
+            changed: Notify::new(),
+            words,
+            shard_size: handles.config.shard_size.max(1),
+            secret: handles.config.coordinate_secret.clone(),
+            handles,
+        }
+    }
+
+    /// split the wordlist into shards for the given directory, unless it was already queued
+    fn add_directory(&self, url: &str, level: usize) {
+        let url = if url.ends_with('/') {
+            url.to_string()
+        } else {
+            format!("{}/", url)
+        };
+
+        if let Ok(mut queue) = self.queue.lock() {
+            if !queue.directories.insert(url.clone()) {
+                return;
+            }
+
+            for start in (0..self.words.len()).step_by(self.shard_size) {
+                let shard = Shard {
+                    id: queue.next_id,
+                    url: url.clone(),
+                    level,
+                    start,
+                    end: (start + self.shard_size).min(self.words.len()),
+                    attempts: 0,
+                };
+
+                queue.next_id += 1;
+                queue.pending.push_back(shard);
+            }
+        }
+
+        self.changed.notify_waiters();
+    }
+
+    /// take the next pending shard and mark it as in flight
+    fn take_shard(&self) -> Option<Shard> {
+        let mut queue = self.queue.lock().ok()?;
+        let mut shard = queue.pending.pop_front()?;
+
+        shard.attempts += 1;
+        queue.in_flight.insert(shard.id, shard.clone());
+
+        Some(shard)
+    }
+
+    /// whether every shard was scanned (or given up on)
+    fn is_finished(&self) -> bool {
+        self.queue
+            .lock()
+            .map(|queue| queue.pending.is_empty() && queue.in_flight.is_empty())
+            .unwrap_or(true)
+    }
+
+    /// wait for a shard to become available; None once there's nothing left to scan
+    async fn next_shard(&self) -> Option<Shard> {
+        loop {
+            // created before checking the queue so that a change in between isn't missed
+            let changed = self.changed.notified();
+
+            if let Some(shard) = self.take_shard() {
+                return Some(shard);
+            }
+
+            if self.is_finished() {
+                return None;
+            }
+
+            changed.await;
+        }
+    }
+
+    /// wait until there's nothing left to scan
+    async fn finished(&self) {
+        loop {
+            let changed = self.changed.notified();
+
+            if self.is_finished() {
+                return;
+            }
+
+            changed.await;
+        }
+    }
+
+    /// level of the given in-flight shard
+    fn level_of(&self, id: usize) -> Option<usize> {
+        let queue = self.queue.lock().ok()?;
+        queue.in_flight.get(&id).map(|shard| shard.level)
+    }
+
+    /// mark a shard as done and add the worker's statistics to the coordinator's
+    fn complete(&self, id: usize, mut statistics: Value) -> Result<()> {
+        if let Ok(mut queue) = self.queue.lock() {
+            queue.in_flight.remove(&id);
+        }
+
+        if let Some(discovered) = statistics.get_mut("resources_discovered") {
+            // the coordinator counts these itself as the responses are reported
+            *discovered = Value::from(0);
+        }
+
+        let worker_stats = serde_json::from_value::<Stats>(statistics)?;
+        self.handles.stats.data.merge(&worker_stats);
+
+        self.changed.notify_waiters();
+        Ok(())
+    }
+
+    /// put an in-flight shard back on the queue, unless it already failed too many times
+    fn requeue(&self, id: usize) {
+        if let Ok(mut queue) = self.queue.lock() {
+            if let Some(shard) = queue.in_flight.remove(&id) {
+                if shard.attempts >= MAX_ATTEMPTS {
+                    log::warn!(
+                        "Giving up on words {}-{} of {} after {} attempts",
+                        shard.start,
+                        shard.end,
+                        shard.url,
+                        shard.attempts
+                    );
+                } else {
+                    log::info!(
+                        "Re-queuing words {}-{} of {}",
+                        shard.start,
+                        shard.end,
+                        shard.url
+                    );
+                    queue.pending.push_front(shard);
+                }
+            }
+        }
+
+        self.changed.notify_waiters();
+    }
+
+    /// build the message that assigns a shard to a worker
+    fn shard_message(&self, shard: &Shard) -> Message {
+        Message::Shard {
+            id: shard.id,
+            url: shard.url.clone(),
+            words: self.words[shard.start..shard.end].to_vec(),
+        }
+    }
+
+    /// queue any directory worth recursing into, then report the response
+    fn report(&self, shard: usize, response: Box<FeroxResponse>) -> Result<()> {
+        if response.is_directory() {
+            if let Some(level) = self.level_of(shard) {
+                let depth = self.handles.config.depth;
+                let within_depth = depth == 0 || level + 1 < depth;
+
+                if !self.handles.config.no_recursion
+                    && within_depth
+                    && !should_deny_url(response.url(), self.handles.clone())?
+                {
+                    self.add_directory(response.url().as_str(), level + 1);
+                }
+            }
+        }
+
+        self.handles.output.send(Command::Report(response))?;
+        Ok(())
+    }
+
+    /// talk to a single worker until there's nothing left to scan; whatever shard the worker
+    /// held when it disconnected or failed is re-queued
+    ///
+    /// nothing is sent to the worker, and nothing it says is believed, until it has proven that
+    /// it knows the shared secret
+    async fn handle_worker(self: Arc<Self>, stream: TcpStream) -> Result<()> {
+        let mut connection = Framed::new(stream, LinesCodec::new());
+
+        authenticate(&mut connection, &self.secret, COORDINATOR, WORKER)
+            .await
+            .context("Worker failed the handshake")?;
+
+        send(
+            &mut connection,
+            &Message::Configuration {
+                configuration: Box::new((*self.handles.config).clone()),
+            },
+        )
+        .await?;
+
+        let mut assigned = None;
+
+        let result = self.serve_worker(&mut connection, &mut assigned).await;
+
+        if let Some(id) = assigned {
+            self.requeue(id);
+        }
+
+        result
+    }
+
+    /// answer a worker's messages; `assigned` tracks the shard the worker is scanning
+    async fn serve_worker(
+        &self,
+        connection: &mut Framed<TcpStream, LinesCodec>,
+        assigned: &mut Option<usize>,
+    ) -> Result<()> {
+        loop {
+            match receive(connection).await? {
+                Message::Response { shard, .. }
+                | Message::Finding { shard, .. }
+                | Message::Finished { shard, .. }
+                    if *assigned != Some(shard) =>
+                {
+                    bail!(
+                        "Worker sent results for shard {}, which it wasn't assigned",
+                        shard
+                    )
+                }
+                Message::Ready => match self.next_shard().await {
+                    Some(shard) => {
+                        *assigned = Some(shard.id);
+                        send(connection, &self.shard_message(&shard)).await?;
+                    }
+                    None => {
+                        send(connection, &Message::Done).await?;
+                        return Ok(());
+                    }
+                },
+                Message::Response { shard, response } => self.report(shard, response)?,
+                Message::Finding { finding, .. } => {
+                    self.handles.output.send(Command::ReportFinding(finding))?
+                }
+                Message::Finished { shard, statistics } => {
+                    *assigned = None;
+
+                    match statistics {
+                        Some(statistics) => self.complete(shard, statistics)?,
+                        None => self.requeue(shard),
+                    }
+                }
+                other => bail!("Unexpected message from worker: {:?}", other),
+            }
+        }
+    }
+}
+
+/// Role of the coordinator in the handshake, see `authenticate`
+const COORDINATOR: &str = "coordinator";
+
+/// Role of a worker in the handshake, see `authenticate`
+const WORKER: &str = "worker";
+
+/// hmac of the role and nonce, keyed by the shared secret
+///
+/// the key is a hash of the secret, which keeps it a fixed length even when there's no secret
+fn prove(secret: &str, role: &str, nonce: &str) -> Result<String> {
+    let key = PKey::hmac(&sha256(secret.as_bytes()))?;
+    let mut signer = Signer::new(MessageDigest::sha256(), &key)?;
+
+    signer.update(role.as_bytes())?;
+    signer.update(b":")?;
+    signer.update(nonce.as_bytes())?;
+
+    Ok(base64::encode(signer.sign_to_vec()?))
+}
+
+/// whether `proof` is the proof of `role` for `nonce`; compared in constant time, so a proof
+/// can't be guessed a byte at a time
+fn verify(secret: &str, role: &str, nonce: &str, proof: &str) -> Result<bool> {
+    let expected = prove(secret, role, nonce)?;
+
+    Ok(expected.len() == proof.len() && memcmp::eq(expected.as_bytes(), proof.as_bytes()))
+}
+
+/// Make sure the other end of the connection knows the shared secret, and prove that we do too
+///
+/// each end sends a random nonce and answers the other's with an hmac of its role (`ours`) and
+/// that nonce, keyed by the secret; the secret itself never crosses the network, and proofs
+/// can't be replayed or reflected back, since each is bound to a fresh nonce and to a role
+async fn authenticate(
+    connection: &mut Framed<TcpStream, LinesCodec>,
+    secret: &str,
+    ours: &str,
+    theirs: &str,
+) -> Result<()> {
+    let nonce = Uuid::new_v4().to_simple().to_string();
+
+    send(
+        connection,
+        &Message::Challenge {
+            nonce: nonce.clone(),
+        },
+    )
+    .await?;
+
+    let challenge = match receive(connection).await? {
+        Message::Challenge { nonce } => nonce,
+        other => bail!("Expected a challenge from the {}, got {:?}", theirs, other),
+    };
+
+    send(
+        connection,
+        &Message::Proof {
+            proof: prove(secret, ours, &challenge)?,
+        },
+    )
+    .await?;
+
+    match receive(connection).await? {
+        Message::Proof { proof } if verify(secret, theirs, &nonce, &proof)? => Ok(()),
+        Message::Proof { .. } => bail!(
+            "The {} doesn't know the shared secret (--coordinate-secret/--secret, or FEROX_COORDINATE_SECRET)",
+            theirs
+        ),
+        other => bail!("Expected a proof from the {}, got {:?}", theirs, other),
+    }
+}
+
+/// Refuse to coordinate beyond the local machine without a secret; anyone that can reach the
+/// coordinator would be sent its configuration (headers, credentials, etc) and could report
+/// results of their own
+fn check_exposure(address: &str, secret: &str) -> Result<()> {
+    let address: SocketAddr = address
+        .parse()
+        .map_err(|e| anyhow!("Invalid --coordinate address {}: {}", address, e))?;
+
+    if !address.ip().is_loopback() && secret.is_empty() {
+        bail!(
+            "Refusing to coordinate on {} without --coordinate-secret (or FEROX_COORDINATE_SECRET); only loopback addresses may be used without one",
+            address
+        );
+    }
+
+    Ok(())
+}
+
+/// write a single message to the connection
+async fn send(connection: &mut Framed<TcpStream, LinesCodec>, message: &Message) -> Result<()> {
+    connection.send(serde_json::to_string(message)?).await?;
+    Ok(())
+}
+
+/// read a single message from the connection
+async fn receive(connection: &mut Framed<TcpStream, LinesCodec>) -> Result<Message> {
+    let line = connection
+        .next()
+        .await
+        .ok_or_else(|| anyhow!("Connection closed"))??;
+
+    Ok(serde_json::from_str(&line)?)
+}
+
+/// Scan the given targets by handing out shards to workers that connect to
+/// `--coordinate ADDRESS`; returns once every shard (including those of any directories found
+/// along the way) was scanned
+pub async fn coordinate(targets: Vec<String>, handles: Arc<Handles>) -> Result<()> {
+    log::trace!("enter: coordinate({:?}, {:?})", targets, handles);
+
+    check_exposure(
+        &handles.config.coordinate,
+        &handles.config.coordinate_secret,
+    )?;
+
+    let words = load_wordlist(&handles.config)?;
+
+    if words.is_empty() {
+        bail!("Did not find any words in {}", handles.config.wordlist);
+    }
+
+    let coordinator = Arc::new(Coordinator::new(words, handles.clone()));
+
+    for target in &targets {
+        coordinator.add_directory(target, 0);
+    }
+
+    let listener = TcpListener::bind(&handles.config.coordinate)
+        .await
+        .with_context(|| format!("Could not listen on {}", handles.config.coordinate))?;
+
+    let secret_arg = if handles.config.coordinate_secret.is_empty() {
+        ""
+    } else {
+        " --secret SECRET"
+    };
+
+    log::warn!(
+        "Waiting for workers on {}; start them with: feroxbuster worker --connect {}{}",
+        listener.local_addr()?,
+        listener.local_addr()?,
+        secret_arg
+    );
+
+    let mut workers = Vec::new();
+
+    loop {
+        tokio::select! {
+            accepted = listener.accept() => {
+                let (stream, peer) = accepted?;
+                log::info!("Worker connected from {}", peer);
+
+                let coordinator = coordinator.clone();
+
+                workers.push(tokio::spawn(async move {
+                    if let Err(e) = coordinator.handle_worker(stream).await {
+                        log::warn!("Lost worker {}: {}", peer, e);
+                    }
+                }));
+            }
+            _ = coordinator.finished() => break,
+        }
+    }
+
+    // connected workers are told there's nothing left to do
+    for worker in workers {
+        worker.await?;
+    }
+
+    log::trace!("exit: coordinate");
+    Ok(())
+}
+
+/// Settings a worker takes from the coordinator's configuration: the ones that shape the requests
+/// it sends and the results it reports
+///
+/// everything else is left at its default; that covers output files, state, and event streams,
+/// which stay with the coordinator, and anything else that refers to the coordinator's filesystem
+const WORKER_SETTINGS: &[&str] = &[
+    "target_url",
+    "status_codes",
+    "filter_status",
+    "threads",
+    "timeout",
+    "auto_bail",
+    "auto_tune",
+    "user_agent",
+    "redirects",
+    "insecure",
+    "extensions",
+    "headers",
+    "host_header",
+    "queries",
+    "proxy",
+    "no_env_proxy",
+    "tor",
+    "tor_control",
+    "tor_control_password",
+    "extract_links",
+    "extract_forms",
+    "extract_comments",
+    "fingerprint",
+    "parse_listings",
+    "skip_listed_dirs",
+    "discover_openapi",
+    "graphql_probe",
+    "source_maps",
+    "find_secrets",
+    "secret_patterns",
+    "harvest",
+    "mine_params",
+    "check_artifacts",
+    "check_headers",
+    "cors_probe",
+    "ws_probe",
+    "auth_diff",
+    "check_methods",
+    "permute",
+    "permutation_rules",
+    "iis_shortnames",
+    "add_slash",
+    "rate_limit",
+    "request_limit",
+    "max_429s",
+    "cooldown",
+    "filter_size",
+    "filter_line_count",
+    "filter_word_count",
+    "filter_regex",
+    "filter_similar",
+    "dont_filter",
+    "url_denylist",
+    "deny_url_regex",
+    "scope",
+    "cluster_threshold",
+    "adaptive_threads",
+    "adaptive_timeout",
+    "seen_urls",
+    "dns_ttl",
+    "max_response_size",
+    "head_only",
+    "response_time",
+    "streams_per_connection",
+    "throttle_bytes",
+    "scan_id",
+    "scan_id_header",
+    "login",
+    "csrf",
+];
+
+/// Prepare a configuration received from the coordinator for use by a worker
+///
+/// only the `WORKER_SETTINGS` are used; results are only sent back to the coordinator, and
+/// recursion is left up to the coordinator
+fn worker_configuration(configuration: Configuration) -> Result<Configuration> {
+    let mut settings = serde_json::to_value(&configuration)?;
+
+    if let Value::Object(settings) = &mut settings {
+        settings.retain(|name, _| WORKER_SETTINGS.contains(&name.as_str()));
+    }
+
+    let mut config = Configuration {
+        silent: true,
+        quiet: false,
+        output_level: OutputLevel::Silent,
+        no_recursion: true,
+        ..serde_json::from_value(settings)?
+    };
+
+    config.requester_policy = determine_requester_policy(config.auto_tune, config.auto_bail);
+    config.rebuild_clients()?;
+
+    Ok(config)
+}
+
+/// Connect to a coordinator and scan the shards it hands out until it says there's nothing left
+///
+/// `secret` is the one shared with the coordinator (worker --secret)
+pub async fn work(address: &str, secret: &str) -> Result<()> {
+    log::trace!("enter: work({})", address);
+
+    let stream = TcpStream::connect(address)
+        .await
+        .with_context(|| format!("Could not connect to coordinator at {}", address))?;
+
+    let mut connection = Framed::new(stream, LinesCodec::new());
+
+    authenticate(&mut connection, secret, WORKER, COORDINATOR)
+        .await
+        .with_context(|| format!("Could not authenticate with the coordinator at {}", address))?;
+
+    let config = match receive(&mut connection).await? {
+        Message::Configuration { configuration } => worker_configuration(*configuration)?,
+        other => bail!(
+            "Expected a configuration from the coordinator, got {:?}",
+            other
+        ),
+    };
+
+    let scanner = Scanner::new(config);
+
+    loop {
+        send(&mut connection, &Message::Ready).await?;
+
+        let (id, url, words) = match receive(&mut connection).await? {
+            Message::Shard { id, url, words } => (id, url, words),
+            Message::Done => break,
+            other => bail!("Unexpected message from the coordinator: {:?}", other),
+        };
+
+        log::info!("Scanning {} with {} words", url, words.len());
+
+        let statistics = match scanner.scan_with_words(vec![url], Arc::new(words)).await {
+            Ok(mut results) => {
+                while let Some(result) = results.next().await {
+                    let message = match result {
+                        ScanResult::Response(response) => Message::Response {
+                            shard: id,
+                            response,
+                        },
+                        ScanResult::Finding(finding) => Message::Finding { shard: id, finding },
+                    };
+
+                    send(&mut connection, &message).await?;
+                }
+
+                scanner
+                    .stats()
+                    .and_then(|stats| serde_json::to_value(&*stats).ok())
+            }
+            Err(e) => {
+                // the coordinator gives the shard to another worker
+                log::warn!("Could not scan shard {}: {}", id, e);
+                None
+            }
+        };
+
+        send(
+            &mut connection,
+            &Message::Finished {
+                shard: id,
+                statistics,
+            },
+        )
+        .await?;
+    }
+
+    log::trace!("exit: work");
+    Ok(())
+}
+
+/// Run the `feroxbuster worker` subcommand
+pub fn run(subcommand: &ArgMatches) -> Result<()> {
+    // --connect is required, clap won't let us get here without it
+    let address = subcommand.value_of("connect").unwrap_or_default();
+    let secret = subcommand.value_of("secret").unwrap_or_default();
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(work(address, secret))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{atomic_load, scan_manager::FeroxScans};
+    use std::sync::atomic::Ordering;
+
+    /// coordinator over a 5 word list using shards of 2 words
+    fn coordinator() -> Coordinator {
+        let config = Configuration {
+            shard_size: 2,
+            ..Default::default()
+        };
+
+        let handles = Handles::for_testing(
+            Some(Arc::new(FeroxScans::default())),
+            Some(Arc::new(config)),
+        )
+        .0;
+
+        let words: Vec<String> = (0..5).map(|i| format!("word{}", i)).collect();
+        Coordinator::new(Arc::new(words), Arc::new(handles))
+    }
+
+    #[test]
+    /// each directory is split into ranges of the wordlist and only queued once
+    fn coordinator_shards_each_directory_once() {
+        let coordinator = coordinator();
+
+        coordinator.add_directory("http://localhost", 0);
+        coordinator.add_directory("http://localhost/", 0);
+
+        let queue = coordinator.queue.lock().unwrap();
+        let ranges: Vec<(usize, usize)> = queue.pending.iter().map(|s| (s.start, s.end)).collect();
+
+        assert_eq!(ranges, vec![(0, 2), (2, 4), (4, 5)]);
+        assert!(queue.pending.iter().all(|s| s.url == "http://localhost/"));
+    }
+
+    #[test]
+    /// a failed shard goes back to the front of the queue until it runs out of attempts
+    fn coordinator_requeues_failed_shards() {
+        let coordinator = coordinator();
+        coordinator.add_directory("http://localhost/", 0);
+
+        for attempt in 1..=MAX_ATTEMPTS {
+            let shard = coordinator.take_shard().unwrap();
+            assert_eq!(shard.id, 0);
+            assert_eq!(shard.attempts, attempt);
+            coordinator.requeue(shard.id);
+        }
+
+        // given up on; the next shard in line is handed out instead
+        assert_eq!(coordinator.take_shard().unwrap().id, 1);
+        assert!(!coordinator.is_finished());
+    }
+
+    #[test]
+    /// completed shards leave the queue and their statistics are merged
+    fn coordinator_completes_shards() {
+        let coordinator = coordinator();
+        coordinator.add_directory("http://localhost/", 0);
+
+        while let Some(shard) = coordinator.take_shard() {
+            let statistics = serde_json::json!({"requests": 2, "resources_discovered": 1});
+            coordinator.complete(shard.id, statistics).unwrap();
+        }
+
+        assert!(coordinator.is_finished());
+        assert_eq!(atomic_load!(coordinator.handles.stats.data.requests), 6);
+        assert_eq!(coordinator.handles.stats.data.resources_discovered(), 0);
+    }
+
+    /// run the handshake between a coordinator and a worker with the given secrets, returning
+    /// each end's outcome
+    async fn handshake(coordinator_secret: &str, worker_secret: &str) -> (Result<()>, Result<()>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+        let worker_secret = worker_secret.to_string();
+
+        let worker = tokio::spawn(async move {
+            let stream = TcpStream::connect(address).await.unwrap();
+            let mut connection = Framed::new(stream, LinesCodec::new());
+            authenticate(&mut connection, &worker_secret, WORKER, COORDINATOR).await
+        });
+
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut connection = Framed::new(stream, LinesCodec::new());
+        let coordinator =
+            authenticate(&mut connection, coordinator_secret, COORDINATOR, WORKER).await;
+
+        (coordinator, worker.await.unwrap())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// both ends must know the same secret to get past the handshake
+    async fn handshake_requires_the_shared_secret() {
+        let (coordinator, worker) = handshake("s3cr3t", "s3cr3t").await;
+        assert!(coordinator.is_ok());
+        assert!(worker.is_ok());
+
+        let (coordinator, worker) = handshake("s3cr3t", "guess").await;
+        assert!(coordinator.is_err());
+        assert!(worker.is_err());
+
+        let (coordinator, _) = handshake("s3cr3t", "").await;
+        assert!(coordinator.is_err());
+    }
+
+    #[test]
+    /// proofs are bound to a role, so one end's proof can't be reflected back as the other's
+    fn proofs_are_bound_to_role_and_nonce() {
+        let proof = prove("s3cr3t", WORKER, "nonce").unwrap();
+
+        assert!(verify("s3cr3t", WORKER, "nonce", &proof).unwrap());
+        assert!(!verify("s3cr3t", COORDINATOR, "nonce", &proof).unwrap());
+        assert!(!verify("s3cr3t", WORKER, "other", &proof).unwrap());
+        assert!(!verify("guess", WORKER, "nonce", &proof).unwrap());
+    }
+
+    #[test]
+    /// only loopback addresses can be coordinated on without a secret
+    fn check_exposure_requires_secret_beyond_loopback() {
+        assert!(check_exposure("127.0.0.1:9000", "").is_ok());
+        assert!(check_exposure("0.0.0.0:9000", "").is_err());
+        assert!(check_exposure("0.0.0.0:9000", "s3cr3t").is_ok());
+    }
+
+    #[test]
+    /// workers keep the settings that shape the scan, and none of the coordinator's files
+    fn worker_configuration_only_keeps_scan_settings() {
+        let config = Configuration {
+            threads: 7,
+            filter_status: vec![404],
+            extensions: vec!["php".to_string()],
+            output: "/coordinator/results.txt".to_string(),
+            burp_output: "/coordinator/burp.xml".to_string(),
+            defectdojo_output: "/coordinator/dojo.json".to_string(),
+            evidence_dir: "/coordinator/evidence".to_string(),
+            event_stream: "/coordinator/events.jsonl".to_string(),
+            trace_output: "/coordinator/trace.json".to_string(),
+            checkpoint_interval: "5m".to_string(),
+            zap_url: "http://127.0.0.1:8080".to_string(),
+            seed_file: "/coordinator/urls.txt".to_string(),
+            ..Default::default()
+        };
+
+        let worker = worker_configuration(config).unwrap();
+
+        assert_eq!(worker.threads, 7);
+        assert_eq!(worker.filter_status, vec![404]);
+        assert_eq!(worker.extensions, vec!["php"]);
+        assert!(worker.no_recursion);
+        assert!(worker.output.is_empty());
+        assert!(worker.burp_output.is_empty());
+        assert!(worker.defectdojo_output.is_empty());
+        assert!(worker.evidence_dir.is_empty());
+        assert!(worker.event_stream.is_empty());
+        assert!(worker.trace_output.is_empty());
+        assert!(worker.checkpoint_interval.is_empty());
+        assert!(worker.zap_url.is_empty());
+        assert!(worker.seed_file.is_empty());
+    }
+
+    #[test]
+    /// every setting is either sent on to workers or deliberately left with the coordinator; a new
+    /// setting (i.e. another output file) fails this until it's added to one list or the other
+    fn worker_settings_cover_every_setting() {
+        let left_behind = [
+            "type",
+            "wordlist",
+            "config",
+            "replay_proxy",
+            "replay_codes",
+            "verbosity",
+            "silent",
+            "quiet",
+            "json",
+            "error_records",
+            "output",
+            "debug_log",
+            "no_recursion",
+            "seed_from",
+            "redact_secrets",
+            "no_product_wordlists",
+            "track_changes",
+            "save_config",
+            "stdin",
+            "depth",
+            "scan_limit",
+            "parallel",
+            "resumed",
+            "resume_from",
+            "save_state",
+            "time_limit",
+            "coordinate",
+            "shard_size",
+            "log_format",
+            "thousands_separator",
+            "byte_units",
+            "expect_found",
+            "expect_absent",
+            "stop_on_match",
+            "wordlist_cache",
+            "memory_limit",
+            "async_workers",
+            "blocking_threads",
+            "trace_output",
+            "refresh_rate",
+            "checkpoint_interval",
+            "checkpoint_requests",
+            "burp_output",
+            "zap_url",
+            "zap_api_key",
+            "zap_active_scan",
+            "event_stream",
+            "defectdojo_output",
+            "evidence_dir",
+            "evidence_severity",
+            "output_format",
+            "bucket_by_tech",
+            "seed_file",
+            "buckets",
+            "presets",
+            "crawl_wordlist",
+            "rotate_headers",
+            "wordlist_map",
+            "recipe_rate_limit",
+        ];
+
+        // settings that are only written out when they're set
+        let config: Configuration = toml::from_str(
+            r#"
+            recipe_rate_limit = "127.0.0.1:9000"
+
+            [login]
+            url = "http://localhost/login"
+
+            [csrf]
+            url = "http://localhost/form"
+            name = "token"
+
+            [[wordlist_map]]
+            path = "/api/**"
+            wordlist = "/wordlists/api.txt"
+            "#,
+        )
+        .unwrap();
+
+        let settings = match serde_json::to_value(&config).unwrap() {
+            Value::Object(settings) => settings,
+            other => panic!("unexpected configuration {:?}", other),
+        };
+
+        for name in settings.keys() {
+            let kept = WORKER_SETTINGS.contains(&name.as_str());
+            let dropped = left_behind.contains(&name.as_str());
+
+            assert!(
+                kept != dropped,
+                "{} must be either a worker setting or left behind, not {}",
+                name,
+                if kept { "both" } else { "neither" }
+            );
+        }
+    }
+
+    #[test]
+    /// messages are tagged with their type and survive a round trip
+    fn message_round_trip() {
+        let message = Message::Shard {
+            id: 3,
+            url: "http://localhost/".to_string(),
+            words: vec!["admin".to_string()],
+        };
+
+        let line = serde_json::to_string(&message).unwrap();
+        assert!(line.starts_with(r#"{"type":"shard""#));
+
+        match serde_json::from_str(&line).unwrap() {
+            Message::Shard { id, url, words } => {
+                assert_eq!(id, 3);
+                assert_eq!(url, "http://localhost/");
+                assert_eq!(words, vec!["admin"]);
+            }
+            other => panic!("unexpected message {:?}", other),
+        }
+
+        let finished: Message =
+            serde_json::from_str(r#"{"type":"finished","shard":1,"statistics":null}"#).unwrap();
+        assert!(matches!(
+            finished,
+            Message::Finished {
+                shard: 1,
+                statistics: None
+            }
+        ));
+    }
+}
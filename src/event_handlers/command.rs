@@ -1,11 +1,12 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 
 use reqwest::StatusCode;
 use tokio::sync::oneshot::Sender;
 
+use crate::finding::FeroxFinding;
 use crate::response::FeroxResponse;
 use crate::{
-    statistics::{StatError, StatField},
+    statistics::{ErrorRecord, StatError, StatField},
     traits::FeroxFilter,
 };
 
@@ -21,6 +22,15 @@ pub enum Command {
     /// Add one to the proper field(s) based on the given `StatusCode`
     AddStatus(StatusCode),
 
+    /// Record how long a request took to receive a response with the given `StatusCode`
+    AddLatency(StatusCode, Duration),
+
+    /// Apply a batch of buffered per-request updates, in the order they were recorded
+    AddBatch(Vec<Command>),
+
+    /// Add to the number of requests sent while scanning the given directory
+    AddDirectoryRequests(String, usize),
+
     /// Create the progress bar (`BarType::Total`) that is updated from the stats thread
     CreateBar,
 
@@ -45,6 +55,12 @@ pub enum Command {
     /// Send a `FeroxResponse` to the output handler for reporting
     Report(Box<FeroxResponse>),
 
+    /// Send a `FeroxFinding` to the output handler for reporting
+    ReportFinding(Box<FeroxFinding>),
+
+    /// Send an `ErrorRecord` (a failed request) to the output handler for reporting
+    ReportError(Box<ErrorRecord>),
+
     /// Send a group of urls to be scanned (only used for the urls passed in explicitly by the user)
     ScanInitialUrls(Vec<String>),
 
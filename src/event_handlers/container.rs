@@ -1,14 +1,23 @@
 use super::*;
+use crate::analyzers::{enabled_analyzers, ResponseAnalyzer};
 use crate::config::Configuration;
+use crate::csrf::CsrfToken;
 use crate::event_handlers::scans::ScanHandle;
-use crate::scan_manager::FeroxScans;
+use crate::event_stream::EventStream;
+use crate::login::Session;
+use crate::scan_manager::{ContentHashes, FeroxScans};
 use crate::Joiner;
 #[cfg(test)]
 use crate::{filters::FeroxFilters, statistics::Stats, Command};
 use anyhow::{bail, Result};
-use std::sync::{Arc, RwLock};
+use regex::Regex;
+use reqwest::Url;
+use std::collections::HashSet;
+use std::future::Future;
+use std::sync::{Arc, Mutex, RwLock};
 #[cfg(test)]
 use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::task::JoinHandle;
 
 #[derive(Debug)]
 /// Simple container for multiple JoinHandles
@@ -54,8 +63,33 @@ pub struct Handles {
     /// Handle for Configuration
     pub config: Arc<Configuration>,
 
+    /// the scan's `[login]` session; empty when there's no `[login]`
+    pub session: Arc<Session>,
+
+    /// the scan's --event-stream; drops every event when there's no --event-stream
+    pub events: Arc<EventStream>,
+
+    /// content hash of every response reported during the scan (--track-changes)
+    pub content_hashes: ContentHashes,
+
+    /// `[csrf]` token currently sent with requests; held while a new one is fetched, so that
+    /// requests that find it rejected at the same time only fetch one
+    pub csrf_token: tokio::sync::Mutex<Option<CsrfToken>>,
+
     /// Handle for recursion
     pub scans: RwLock<Option<ScanHandle>>,
+
+    /// compiled --deny-url-regex patterns
+    deny_url_regexes: Vec<Regex>,
+
+    /// analyzers enabled by the configuration, built once and shared by every response
+    analyzers: Vec<Box<dyn ResponseAnalyzer>>,
+
+    /// tasks that run alongside the scan until it's shut down, i.e. the `[login]` token refresh
+    background: Mutex<Vec<JoinHandle<()>>>,
+
+    /// (kind of probe, what was probed) pairs already handled during this scan, see `first_probe`
+    probed: Mutex<HashSet<(&'static str, String)>>,
 }
 
 /// implementation of Handles
@@ -65,14 +99,34 @@ impl Handles {
         stats: StatsHandle,
         filters: FiltersHandle,
         output: TermOutHandle,
+        session: Arc<Session>,
+        events: Arc<EventStream>,
         config: Arc<Configuration>,
     ) -> Self {
+        // patterns are checked when the configuration is built, anything that still doesn't
+        // compile is skipped
+        let deny_url_regexes = config
+            .deny_url_regex
+            .iter()
+            .filter_map(|pattern| Regex::new(pattern).ok())
+            .collect();
+
+        let analyzers = enabled_analyzers(&config);
+
         Self {
             stats,
             filters,
             output,
             config,
+            session,
+            events,
+            content_hashes: ContentHashes::default(),
+            csrf_token: tokio::sync::Mutex::new(None),
             scans: RwLock::new(None),
+            deny_url_regexes,
+            analyzers,
+            background: Mutex::new(Vec::new()),
+            probed: Mutex::new(HashSet::new()),
         }
     }
 
@@ -84,16 +138,18 @@ impl Handles {
     ) -> (Self, UnboundedReceiver<Command>) {
         let configuration = config.unwrap_or_else(|| Arc::new(Configuration::new().unwrap()));
         let (tx, rx) = mpsc::unbounded_channel::<Command>();
-        let terminal_handle = TermOutHandle::new(tx.clone(), tx.clone());
-        let stats_handle = StatsHandle::new(
-            Arc::new(Stats::new(
-                configuration.extensions.len(),
-                configuration.json,
-            )),
-            tx.clone(),
-        );
+        let terminal_handle =
+            TermOutHandle::new(Arc::default(), Arc::default(), tx.clone(), tx.clone());
+        let stats_handle = StatsHandle::new(Arc::new(Stats::new(configuration.json)), tx.clone());
         let filters_handle = FiltersHandle::new(Arc::new(FeroxFilters::default()), tx.clone());
-        let handles = Self::new(stats_handle, filters_handle, terminal_handle, configuration);
+        let handles = Self::new(
+            stats_handle,
+            filters_handle,
+            terminal_handle,
+            Arc::default(),
+            Arc::default(),
+            configuration,
+        );
         if let Some(sh) = scanned_urls {
             let scan_handle = ScanHandle::new(sh, tx);
             handles.set_scan_handle(scan_handle);
@@ -122,6 +178,58 @@ impl Handles {
         bail!("Could not get underlying CommandSender object")
     }
 
+    /// Run the given task alongside the scan; it's aborted once the scan shuts down, see
+    /// `abort_background_tasks`
+    pub fn spawn_background<F>(&self, task: F)
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let task = tokio::spawn(task);
+
+        if let Ok(mut background) = self.background.lock() {
+            background.push(task);
+        }
+    }
+
+    /// Abort every task started with `spawn_background`
+    pub fn abort_background_tasks(&self) {
+        if let Ok(mut background) = self.background.lock() {
+            for task in background.drain(..) {
+                task.abort();
+            }
+        }
+    }
+
+    /// The analyzers every response that passes filtering is run through
+    pub fn analyzers(&self) -> &[Box<dyn ResponseAnalyzer>] {
+        &self.analyzers
+    }
+
+    /// Whether this is the first time during the scan that the given kind of probe (cors, graphql,
+    /// ...) is run against `key`; each probe is only ever run once per endpoint, and the next
+    /// scan starts over
+    pub fn first_probe(&self, kind: &'static str, key: &str) -> bool {
+        match self.probed.lock() {
+            Ok(mut probed) => probed.insert((kind, key.to_string())),
+            Err(_) => true,
+        }
+    }
+
+    /// Whether the given url matches any of the --deny-url-regex patterns, meaning it must never
+    /// be requested
+    pub fn denied_by_regex(&self, url: &Url) -> bool {
+        let denied = self
+            .deny_url_regexes
+            .iter()
+            .any(|pattern| pattern.is_match(url.as_str()));
+
+        if denied {
+            log::debug!("{} matches a --deny-url-regex pattern", url);
+        }
+
+        denied
+    }
+
     /// Helper to easily get the (locked) underlying FeroxScans object
     pub fn ferox_scans(&self) -> Result<Arc<FeroxScans>> {
         if let Ok(guard) = self.scans.read().as_ref() {
@@ -1,15 +1,14 @@
 use super::*;
 use crate::{
+    exit_codes::ExitCode,
+    numbers::NumberFormat,
     progress::PROGRESS_PRINTER,
-    scan_manager::{FeroxState, PAUSE_SCAN},
-    scanner::RESPONSES,
+    scan_manager::{save_state, state_filename, PAUSE_SCAN},
     statistics::StatError,
-    utils::slugify_filename,
-    utils::{open_file, write_to},
     SLEEP_DURATION,
 };
 use anyhow::Result;
-use console::style;
+use console::{style, user_attended};
 use crossterm::event::{self, Event, KeyCode};
 use std::{
     sync::{
@@ -17,8 +16,14 @@ use std::{
         Arc,
     },
     thread::sleep,
-    time::Duration,
+    time::{Duration, Instant},
 };
+#[cfg(not(target_os = "windows"))]
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::oneshot;
+
+/// how long to wait for buffered results to be written to disk before exiting on ctrl+c
+const FLUSH_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Atomic boolean flag, used to determine whether or not the terminal input handler should exit
 pub static SCAN_COMPLETE: AtomicBool = AtomicBool::new(false);
@@ -27,6 +32,9 @@ pub static SCAN_COMPLETE: AtomicBool = AtomicBool::new(false);
 pub struct TermInputHandler {
     /// handles to other handlers
     handles: Arc<Handles>,
+
+    /// when the handler was started, used as the start of the scan in statistics snapshots
+    started: Instant,
 }
 
 /// implementation of event handler for terminal input
@@ -34,10 +42,14 @@ pub struct TermInputHandler {
 /// kicks off the following handlers related to terminal input:
 ///     ctrl+c handler that saves scan state to disk
 ///     enter handler that listens for enter during scans to drop into interactive scan cancel menu
+///     s key and SIGUSR2 handlers that print a snapshot of the scan's statistics
 impl TermInputHandler {
     /// Create new event handler
     pub fn new(handles: Arc<Handles>) -> Self {
-        Self { handles }
+        Self {
+            handles,
+            started: Instant::now(),
+        }
     }
 
     /// Initialize the sigint and enter handlers that are responsible for handling initial user
@@ -51,9 +63,17 @@ impl TermInputHandler {
         log::trace!("exit: initialize");
     }
 
-    /// wrapper around sigint_handler and enter_handler
+    /// wrapper around sigint_handler, enter_handler, and snapshot_signal_handler
     fn start(&self) {
-        tokio::task::spawn_blocking(Self::enter_handler);
+        let handles = self.handles.clone();
+        let started = self.started;
+        tokio::task::spawn_blocking(move || Self::enter_handler(handles, started));
+
+        #[cfg(not(target_os = "windows"))]
+        tokio::spawn(Self::snapshot_signal_handler(
+            self.handles.clone(),
+            self.started,
+        ));
 
         if self.handles.config.save_state {
             // start the ctrl+c handler
@@ -77,13 +97,7 @@ impl TermInputHandler {
     pub fn sigint_handler(handles: Arc<Handles>) -> Result<()> {
         log::trace!("enter: sigint_handler({:?})", handles);
 
-        let filename = if !handles.config.target_url.is_empty() {
-            // target url populated
-            slugify_filename(&handles.config.target_url, "ferox", "state")
-        } else {
-            // stdin used
-            slugify_filename("stdin", "ferox", "state")
-        };
+        let filename = state_filename(&handles.config);
 
         let warning = format!(
             "🚨 Caught {} 🚨 saving scan state to {} ...",
@@ -93,24 +107,103 @@ impl TermInputHandler {
 
         PROGRESS_PRINTER.println(warning);
 
-        let state = FeroxState::new(
-            handles.ferox_scans()?,
-            handles.config.clone(),
-            &RESPONSES,
-            handles.stats.data.clone(),
-        );
-
-        let state_file = open_file(&filename);
+        save_state(&handles, &filename)?;
 
-        let mut buffered_file = state_file?;
-        write_to(&state, &mut buffered_file, true)?;
+        Self::flush_output(&handles);
 
         log::trace!("exit: sigint_handler (end of program)");
-        std::process::exit(1);
+        std::process::exit(ExitCode::Interrupted.code());
+    }
+
+    /// Ask the file output handler to write out any results it has buffered, waiting up to
+    /// `FLUSH_TIMEOUT` for it to finish; used right before the process exits
+    fn flush_output(handles: &Handles) {
+        let (tx, rx) = oneshot::channel::<bool>();
+
+        if handles.output.tx_file.send(Command::Sync(tx)).is_err() {
+            // no --output file, nothing to flush
+            return;
+        }
+
+        // this may be called from within the runtime, so the wait happens on a separate thread
+        let (done_tx, done_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || done_tx.send(futures::executor::block_on(rx)));
+
+        if done_rx.recv_timeout(FLUSH_TIMEOUT).is_err() {
+            log::warn!("Timed out writing buffered results to disk");
+        }
+    }
+
+    /// Prints a statistics snapshot each time SIGUSR2 is received, until the scan completes
+    #[cfg(not(target_os = "windows"))]
+    async fn snapshot_signal_handler(handles: Arc<Handles>, started: Instant) {
+        let mut signals = match signal(SignalKind::user_defined2()) {
+            Ok(signals) => signals,
+            Err(e) => {
+                log::warn!(
+                    "Could not set SIGUSR2 handler; snapshots only available with s: {}",
+                    e
+                );
+                return;
+            }
+        };
+
+        while signals.recv().await.is_some() {
+            if SCAN_COMPLETE.load(Ordering::Relaxed) {
+                break;
+            }
+            Self::print_snapshot(&handles, started);
+        }
+    }
+
+    /// One line summary of where the scan is at: elapsed time, requests per second, requests
+    /// made vs expected, errors, and running/queued directory scans
+    fn snapshot(handles: &Handles, elapsed: Duration) -> String {
+        let numbers = NumberFormat::from_config(&handles.config);
+        let stats = &handles.stats.data;
+
+        let seconds = elapsed.as_secs();
+        let requests = stats.requests() as u64;
+        let per_second = requests.checked_div(seconds).unwrap_or(requests);
+
+        let (running, queued) = handles
+            .ferox_scans()
+            .map(|scans| {
+                let active = scans.get_active_scans();
+                let running = active.iter().filter(|scan| scan.is_running()).count();
+                (running, active.len() - running)
+            })
+            .unwrap_or((0, 0));
+
+        format!(
+            "📊 {} elapsed {:02}:{:02}:{:02} | {} req/s | {}/{} requests | {} errors | {} running, {} queued scans",
+            style("Snapshot").cyan(),
+            seconds / 3600,
+            seconds / 60 % 60,
+            seconds % 60,
+            numbers.count(per_second),
+            numbers.count(requests),
+            numbers.count(stats.total_expected() as u64),
+            numbers.count(stats.errors() as u64),
+            numbers.count(running as u64),
+            numbers.count(queued as u64),
+        )
+    }
+
+    /// Print a statistics snapshot above the progress bars; headless runs have no bars to print
+    /// above, so the snapshot goes straight to stderr, keeping stdout free for results
+    fn print_snapshot(handles: &Handles, started: Instant) {
+        let snapshot = Self::snapshot(handles, started.elapsed());
+
+        if user_attended() {
+            PROGRESS_PRINTER.println(snapshot);
+        } else {
+            eprintln!("{}", snapshot);
+        }
     }
 
     /// Handles specific key events triggered by the user over stdin
-    fn enter_handler() {
+    fn enter_handler(handles: Arc<Handles>, started: Instant) {
         // todo eventually move away from atomics, the blocking recv is the problem
         log::trace!("enter: start_enter_handler");
 
@@ -129,6 +222,14 @@ impl TermInputHandler {
                         // if the user presses Enter, set PAUSE_SCAN to true. The interactive menu
                         // will be triggered and will handle setting PAUSE_SCAN to false
                         PAUSE_SCAN.store(true, Ordering::Release);
+                    } else if key_pressed == Event::Key(KeyCode::Char('s').into()) {
+                        Self::print_snapshot(&handles, started);
+
+                        // a line-buffered terminal only delivers the s along with the Enter that
+                        // followed it; that Enter is consumed so it doesn't also pause the scan
+                        if event::poll(Duration::from_millis(0)).unwrap_or(false) {
+                            let _ = event::read();
+                        }
                     }
                 }
             } else {
@@ -142,3 +243,37 @@ impl TermInputHandler {
         log::trace!("exit: start_enter_handler");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scan_manager::{FeroxScans, ScanOrder, ScanStatus};
+
+    #[test]
+    /// snapshots show elapsed time, request rate, progress, errors, and running/queued scans
+    fn snapshot_reports_progress_and_scans() {
+        let scans = Arc::new(FeroxScans::default());
+
+        let (_, running) = scans.add_directory_scan("http://localhost/", ScanOrder::Initial);
+        running.set_status(ScanStatus::Running).unwrap();
+        scans.add_directory_scan("http://localhost/api/", ScanOrder::Latest);
+        let (_, done) = scans.add_directory_scan("http://localhost/js/", ScanOrder::Latest);
+        done.finish().unwrap();
+
+        let (handles, _rx) = Handles::for_testing(Some(scans), None);
+
+        for _ in 0..250 {
+            handles.stats.data.add_request();
+        }
+        handles.stats.data.add_error(StatError::Timeout);
+
+        let snapshot = TermInputHandler::snapshot(&handles, Duration::from_secs(3725));
+        let snapshot = console::strip_ansi_codes(&snapshot);
+
+        assert!(snapshot.contains("elapsed 01:02:05"));
+        assert!(snapshot.contains("0 req/s"));
+        assert!(snapshot.contains("251/0 requests"));
+        assert!(snapshot.contains("1 errors"));
+        assert!(snapshot.contains("1 running, 1 queued scans"));
+    }
+}
@@ -11,6 +11,6 @@ pub use self::command::Command;
 pub use self::container::{Handles, Tasks};
 pub use self::filters::{FiltersHandle, FiltersHandler};
 pub use self::inputs::{TermInputHandler, SCAN_COMPLETE};
-pub use self::outputs::{TermOutHandle, TermOutHandler};
+pub use self::outputs::{TermOutHandle, TermOutHandler, FINDINGS_REPORTED};
 pub use self::scans::{ScanHandle, ScanHandler};
-pub use self::statistics::{StatsHandle, StatsHandler};
+pub use self::statistics::{StatsHandle, StatsHandler, StatsSender};
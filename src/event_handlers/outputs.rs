@@ -1,24 +1,196 @@
 use super::Command::AddToUsizeField;
 use super::*;
 
-use anyhow::{Context, Result};
-use tokio::sync::{mpsc, oneshot};
+use anyhow::{anyhow, Context, Result};
+use console::style;
+use reqwest::{Method, Url};
+use tokio::{
+    sync::{
+        mpsc::{self, UnboundedSender},
+        oneshot,
+    },
+    time::{interval, Duration},
+};
 
 use crate::{
-    config::Configuration,
+    api::ScanResult,
+    burp::BurpWriter,
+    config::{Configuration, OutputLevel},
+    defectdojo::DefectDojoWriter,
+    event_stream::EventStream,
+    evidence,
+    fingerprint::fingerprint,
+    login::Session,
     progress::PROGRESS_PRINTER,
-    scanner::RESPONSES,
+    response::FeroxResponse,
+    scan_manager::{stop_criterion, FeroxResponses},
     send_command, skip_fail,
     statistics::StatField::ResourcesDiscovered,
     traits::FeroxSerialize,
     utils::{ferox_print, fmt_err, make_request, open_file, write_to},
-    CommandReceiver, CommandSender, Joiner,
+    zap, CommandReceiver, CommandSender, Joiner,
+};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs::{self, File},
+    io::{BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
-use std::sync::Arc;
+
+/// Atomic boolean flag, set once a response (other than a wildcard) or a finding has been
+/// reported; used to pick the process' exit code
+pub static FINDINGS_REPORTED: AtomicBool = AtomicBool::new(false);
+
+/// Stop the scan (set `stopped`) when a result at `url` matches one of --stop-on-match's
+/// criteria; `category` is the category of a finding, `None` for a response
+fn stop_on_match(config: &Configuration, stopped: &AtomicBool, url: &str, category: Option<&str>) {
+    if config.stop_on_match.is_empty() || stopped.load(Ordering::Relaxed) {
+        return;
+    }
+
+    let criterion = match Url::parse(url)
+        .ok()
+        .and_then(|url| stop_criterion(&config.stop_on_match, &url, category))
+    {
+        Some(criterion) => criterion,
+        None => return,
+    };
+
+    stopped.store(true, Ordering::Relaxed);
+
+    if matches!(
+        config.output_level,
+        OutputLevel::Default | OutputLevel::Quiet
+    ) {
+        ferox_print(
+            &format!(
+                "{} {} matched --stop-on-match {}, stopping the scan\n",
+                style("STOP").red(),
+                url,
+                criterion
+            ),
+            &PROGRESS_PRINTER,
+        );
+    }
+}
+
+/// how often results buffered by the file output handler are written to disk
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// number of buffered bytes at which the file output handler writes to disk without waiting for
+/// the next `FLUSH_INTERVAL`
+const FLUSH_THRESHOLD: usize = 64 * 1024;
+
+/// --output-format that writes one `url [status] [content-type]` line per result
+const URLS_WITH_META: &str = "urls-with-meta";
+
+/// Urls written to --output, grouped by the technologies identified on the target they were found
+/// on (--bucket-by-tech); each group is written to its own file once the scan is over
+#[derive(Debug, Default)]
+struct TechBuckets {
+    /// origin (scheme, host, and port) -> names of the technologies identified on it
+    technologies: HashMap<String, BTreeSet<&'static str>>,
+
+    /// origin -> urls found on it, in the order they were reported
+    urls: HashMap<String, Vec<String>>,
+}
+
+/// implementation of TechBuckets
+impl TechBuckets {
+    /// fingerprint the given response and add its url to its origin's bucket
+    fn add(&mut self, response: &FeroxResponse) {
+        let origin = response.url().origin().ascii_serialization();
+
+        let technologies = self.technologies.entry(origin.clone()).or_default();
+
+        for tech in fingerprint(response) {
+            technologies.insert(tech.name);
+        }
+
+        self.urls
+            .entry(origin)
+            .or_default()
+            .push(response.url().to_string());
+    }
+
+    /// urls of every origin on which each technology was identified, by technology name
+    fn buckets(&self) -> BTreeMap<&'static str, Vec<&str>> {
+        let mut buckets: BTreeMap<&'static str, Vec<&str>> = BTreeMap::new();
+
+        for (origin, technologies) in &self.technologies {
+            let urls = self.urls.get(origin).map(Vec::as_slice).unwrap_or_default();
+
+            for tech in technologies {
+                buckets
+                    .entry(*tech)
+                    .or_default()
+                    .extend(urls.iter().map(String::as_str));
+            }
+        }
+
+        buckets
+    }
+
+    /// write each bucket next to the given --output file, one url per line
+    fn write(&self, output: &str) -> Result<()> {
+        for (tech, urls) in self.buckets() {
+            let filename = bucket_filename(output, tech);
+
+            let mut contents = urls.join("\n");
+            contents.push('\n');
+
+            fs::write(&filename, contents)
+                .with_context(|| fmt_err(&format!("Could not write {}", filename.display())))?;
+
+            log::info!(
+                "Wrote {} {} urls to {}",
+                urls.len(),
+                tech,
+                filename.display()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+/// name of the file holding the urls of the given technology, i.e. `results.php.txt` for
+/// `results.txt` and PHP
+fn bucket_filename(output: &str, tech: &str) -> PathBuf {
+    let slug: String = tech
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+
+    let path = Path::new(output);
+
+    match (path.file_stem(), path.extension()) {
+        (Some(stem), Some(extension)) => path.with_file_name(format!(
+            "{}.{}.{}",
+            stem.to_string_lossy(),
+            slug,
+            extension.to_string_lossy()
+        )),
+        _ => PathBuf::from(format!("{}.{}", output, slug)),
+    }
+}
 
 #[derive(Debug)]
 /// Container for terminal output transmitter
 pub struct TermOutHandle {
+    /// responses reported so far, used to report each one only once and saved with the scan's
+    /// state
+    pub data: Arc<FeroxResponses>,
+
+    /// set once a result matches --stop-on-match; requests that haven't been made yet are
+    /// skipped and no new directories are scanned
+    pub stopped: Arc<AtomicBool>,
+
     /// Transmitter that sends to the TermOutHandler handler
     pub tx: CommandSender,
 
@@ -28,9 +200,25 @@ pub struct TermOutHandle {
 
 /// implementation of OutputHandle
 impl TermOutHandle {
-    /// Given a CommandSender, create a new OutputHandle
-    pub fn new(tx: CommandSender, tx_file: CommandSender) -> Self {
-        Self { tx, tx_file }
+    /// Given the reported responses, the --stop-on-match flag, and a CommandSender, create a new
+    /// OutputHandle
+    pub fn new(
+        data: Arc<FeroxResponses>,
+        stopped: Arc<AtomicBool>,
+        tx: CommandSender,
+        tx_file: CommandSender,
+    ) -> Self {
+        Self {
+            data,
+            stopped,
+            tx,
+            tx_file,
+        }
+    }
+
+    /// Whether a result matched --stop-on-match
+    pub fn is_stopped(&self) -> bool {
+        self.stopped.load(Ordering::Relaxed)
     }
 
     /// Send the given Command over `tx`
@@ -77,37 +265,114 @@ impl FileOutHandler {
 
     /// Spawn a single consumer task (sc side of mpsc)
     ///
-    /// The consumer simply receives responses from the terminal handler and writes them to disk
+    /// The consumer receives responses from the terminal handler and buffers them in memory;
+    /// the buffer is written to disk on a blocking thread every `FLUSH_INTERVAL`, whenever it
+    /// grows past `FLUSH_THRESHOLD`, when synced, and before exiting. A slow disk only slows down
+    /// this task, never the scanners
     async fn start(&mut self, tx_stats: CommandSender) -> Result<()> {
         log::trace!("enter: start_file_handler({:?})", tx_stats);
 
-        let mut file = open_file(&self.config.output)?;
+        let file = Arc::new(Mutex::new(open_file(&self.config.output)?));
+        let mut pending = Vec::with_capacity(FLUSH_THRESHOLD);
+        let mut ticker = interval(FLUSH_INTERVAL);
+        let urls_with_meta = self.config.output_format == URLS_WITH_META;
+
+        let mut buckets = if self.config.bucket_by_tech {
+            Some(TechBuckets::default())
+        } else {
+            None
+        };
 
         log::info!("Writing scan results to {}", self.config.output);
 
-        while let Some(command) = self.receiver.recv().await {
+        loop {
+            let command = tokio::select! {
+                command = self.receiver.recv() => command,
+                _ = ticker.tick() => {
+                    skip_fail!(Self::flush(&file, &mut pending).await);
+                    continue;
+                }
+            };
+
             match command {
-                Command::Report(response) => {
-                    skip_fail!(write_to(&*response, &mut file, self.config.json));
+                Some(Command::Report(response)) => {
+                    if let Some(buckets) = buckets.as_mut().filter(|_| !response.wildcard()) {
+                        buckets.add(&response);
+                    }
+
+                    if !urls_with_meta {
+                        skip_fail!(write_to(&*response, &mut pending, self.config.json));
+                    } else if !response.wildcard() {
+                        // wildcards would only send other tools after the same junk
+                        pending.extend_from_slice(response.as_url_with_meta().as_bytes());
+                    }
                 }
-                Command::Exit => {
-                    break;
+                Some(Command::ReportFinding(finding)) => {
+                    if !urls_with_meta {
+                        skip_fail!(write_to(&*finding, &mut pending, self.config.json));
+                    }
                 }
-                Command::Sync(sender) => {
+                Some(Command::ReportError(record)) => {
+                    if !urls_with_meta {
+                        skip_fail!(write_to(&*record, &mut pending, self.config.json));
+                    }
+                }
+                Some(Command::Sync(sender)) => {
+                    skip_fail!(Self::flush(&file, &mut pending).await);
                     skip_fail!(sender.send(true));
                 }
+                Some(Command::Exit) | None => break,
                 _ => {} // no more needed
             }
+
+            if pending.len() >= FLUSH_THRESHOLD {
+                skip_fail!(Self::flush(&file, &mut pending).await);
+            }
+        }
+
+        if let Err(e) = Self::flush(&file, &mut pending).await {
+            log::error!("Could not write results to {}: {}", self.config.output, e);
         }
 
         // close the file before we tell statistics to save current data to the same file
         drop(file);
 
-        send_command!(tx_stats, Command::Save);
+        if let Some(buckets) = buckets {
+            if let Err(e) = buckets.write(&self.config.output) {
+                log::error!("{}", e);
+            }
+        }
+
+        if !urls_with_meta {
+            // other tools read urls-with-meta files line by line, so they're left without stats
+            send_command!(tx_stats, Command::Save);
+        }
 
         log::trace!("exit: start_file_handler");
         Ok(())
     }
+
+    /// write everything buffered so far to disk, without blocking the async runtime
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(bytes = pending.len())))]
+    async fn flush(file: &Arc<Mutex<BufWriter<File>>>, pending: &mut Vec<u8>) -> Result<()> {
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let contents = std::mem::replace(pending, Vec::with_capacity(FLUSH_THRESHOLD));
+        let file = file.clone();
+
+        tokio::task::spawn_blocking(move || -> Result<()> {
+            let mut file = file
+                .lock()
+                .map_err(|_| anyhow!("output file lock was poisoned"))?;
+
+            file.write_all(&contents)?;
+            file.flush()?;
+            Ok(())
+        })
+        .await?
+    }
 }
 
 #[derive(Debug)]
@@ -122,32 +387,57 @@ pub struct TermOutHandler {
     /// optional file handler task
     file_task: Option<Joiner>,
 
+    /// when scanning as a library, results are sent here instead of being printed
+    tx_results: Option<UnboundedSender<ScanResult>>,
+
+    /// responses reported so far, shared with the `TermOutHandle`
+    responses: Arc<FeroxResponses>,
+
+    /// --stop-on-match flag, shared with the `TermOutHandle`
+    stopped: Arc<AtomicBool>,
+
+    /// the scan's `[login]` session, sent with requests replayed through --replay-proxy
+    session: Arc<Session>,
+
+    /// the scan's --event-stream
+    events: Arc<EventStream>,
+
     /// pointer to "global" configuration struct
     config: Arc<Configuration>,
 }
 
 /// implementation of TermOutHandler
 impl TermOutHandler {
-    /// Given a terminal receiver along with a file transmitter and filename, create
-    /// an OutputHandler
-    fn new(
-        receiver: CommandReceiver,
-        tx_file: CommandSender,
-        file_task: Option<Joiner>,
+    /// Creates all required output handlers (terminal, file) and updates the given Handles/Tasks
+    pub fn initialize(
         config: Arc<Configuration>,
-    ) -> Self {
-        Self {
-            receiver,
-            tx_file,
-            file_task,
-            config,
-        }
+        tx_stats: CommandSender,
+        session: Arc<Session>,
+        events: Arc<EventStream>,
+    ) -> (Joiner, TermOutHandle) {
+        Self::spawn(config, tx_stats, session, events, None)
     }
 
-    /// Creates all required output handlers (terminal, file) and updates the given Handles/Tasks
-    pub fn initialize(
+    /// Same as `initialize`, except that results are sent to `tx_results` instead of being
+    /// printed to the terminal
+    pub fn initialize_with_results(
+        config: Arc<Configuration>,
+        tx_stats: CommandSender,
+        session: Arc<Session>,
+        events: Arc<EventStream>,
+        tx_results: UnboundedSender<ScanResult>,
+    ) -> (Joiner, TermOutHandle) {
+        Self::spawn(config, tx_stats, session, events, Some(tx_results))
+    }
+
+    /// Creates all required output handlers (terminal, file), returning the terminal handler's
+    /// task and handle
+    fn spawn(
         config: Arc<Configuration>,
         tx_stats: CommandSender,
+        session: Arc<Session>,
+        events: Arc<EventStream>,
+        tx_results: Option<UnboundedSender<ScanResult>>,
     ) -> (Joiner, TermOutHandle) {
         log::trace!("enter: initialize({:?}, {:?})", config, tx_stats);
 
@@ -167,10 +457,23 @@ impl TermOutHandler {
             None
         };
 
-        let mut term_handler = Self::new(rx_term, tx_file.clone(), file_task, config);
+        let responses = Arc::new(FeroxResponses::default());
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let mut term_handler = Self {
+            receiver: rx_term,
+            tx_file: tx_file.clone(),
+            file_task,
+            tx_results,
+            responses: responses.clone(),
+            stopped: stopped.clone(),
+            session,
+            events,
+            config,
+        };
         let term_task = tokio::spawn(async move { term_handler.start(tx_stats).await });
 
-        let event_handle = TermOutHandle::new(tx_term, tx_file);
+        let event_handle = TermOutHandle::new(responses, stopped, tx_term, tx_file);
 
         log::trace!("exit: initialize -> ({:?}, {:?})", term_task, event_handle);
 
@@ -183,17 +486,64 @@ impl TermOutHandler {
     async fn start(&mut self, tx_stats: CommandSender) -> Result<()> {
         log::trace!("enter: start({:?})", tx_stats);
 
+        let mut burp = if self.config.burp_output.is_empty() {
+            None
+        } else {
+            match BurpWriter::create(&self.config.burp_output, &self.config) {
+                Ok(writer) => Some(writer),
+                Err(e) => {
+                    log::error!("{}", e);
+                    None
+                }
+            }
+        };
+
+        let mut dojo = if self.config.defectdojo_output.is_empty() {
+            None
+        } else {
+            match DefectDojoWriter::create(&self.config.defectdojo_output, &self.config) {
+                Ok(writer) => Some(writer),
+                Err(e) => {
+                    log::error!("{}", e);
+                    None
+                }
+            }
+        };
+
+        let mut zap = if self.config.zap_url.is_empty() {
+            None
+        } else {
+            match zap::start(&self.config) {
+                Ok(zap) => Some(zap),
+                Err(e) => {
+                    log::error!("{}", e);
+                    None
+                }
+            }
+        };
+
         while let Some(command) = self.receiver.recv().await {
             match command {
                 Command::Report(mut resp) => {
                     let contains_sentry =
                         self.config.status_codes.contains(&resp.status().as_u16());
-                    let unknown_sentry = !RESPONSES.contains(&resp); // !contains == unknown
+                    let unknown_sentry = !self.responses.contains(&resp); // !contains == unknown
                     let should_process_response = contains_sentry && unknown_sentry;
 
                     if should_process_response {
-                        // print to stdout
-                        ferox_print(&resp.as_str(), &PROGRESS_PRINTER);
+                        if !resp.wildcard() {
+                            FINDINGS_REPORTED.store(true, Ordering::Relaxed);
+                        }
+
+                        if let Some(tx_results) = &self.tx_results {
+                            // embedded scan; a closed stream just means nobody's listening anymore
+                            tx_results
+                                .send(ScanResult::Response(resp.clone()))
+                                .unwrap_or_default();
+                        } else {
+                            // print to stdout
+                            ferox_print(&resp.as_str(), &PROGRESS_PRINTER);
+                        }
 
                         send_command!(tx_stats, AddToUsizeField(ResourcesDiscovered, 1));
 
@@ -205,6 +555,15 @@ impl TermOutHandler {
                                     fmt_err(&format!("Could not send {} to file handler", resp))
                                 })?;
                         }
+
+                        if self.events.enabled() {
+                            let data = serde_json::to_value(&*resp).unwrap_or_default();
+                            self.events.emit("response", data);
+                        }
+
+                        if !resp.wildcard() {
+                            stop_on_match(&self.config, &self.stopped, resp.url().as_str(), None);
+                        }
                     }
                     log::trace!("report complete: {}", resp.url());
 
@@ -213,24 +572,131 @@ impl TermOutHandler {
                         // should be replayed; not using logged_request due to replay proxy client
                         make_request(
                             self.config.replay_client.as_ref().unwrap(),
+                            &Method::GET,
                             resp.url(),
-                            self.config.output_level,
+                            None,
+                            &self.config,
+                            &self.session,
                             tx_stats.clone(),
                         )
                         .await
                         .with_context(|| "Could not replay request through replay proxy")?;
                     }
 
+                    if let Some((tx_zap, _)) = zap.as_ref() {
+                        if should_process_response && !resp.wildcard() {
+                            tx_zap.send(resp.url().to_string()).unwrap_or_default();
+                        }
+                    }
+
+                    if let Some(writer) = burp.as_mut().filter(|_| should_process_response) {
+                        // --burp-output needs the body, so this has to happen before it's dropped
+                        if let Err(e) = writer.write(&resp) {
+                            log::warn!("Could not write {} to Burp output: {}", resp.url(), e);
+                        }
+                    }
+
+                    if let Some(writer) = dojo
+                        .as_mut()
+                        .filter(|_| should_process_response && !resp.wildcard())
+                    {
+                        if let Err(e) = writer.write_response(&resp) {
+                            log::warn!(
+                                "Could not write {} to DefectDojo output: {}",
+                                resp.url(),
+                                e
+                            );
+                        }
+                    }
+
                     if should_process_response {
-                        // add response to RESPONSES for serialization in case of ctrl+c
-                        // placed all by its lonesome like this so that RESPONSES can take ownership
+                        // add response to responses for serialization in case of ctrl+c
+                        // placed all by its lonesome like this so that responses can take ownership
                         // of the FeroxResponse
 
                         // before ownership is transferred, there's no real reason to keep the body anymore
                         // so we can free that piece of data, reducing memory usage
                         resp.drop_text();
 
-                        RESPONSES.insert(*resp);
+                        self.responses.insert(*resp);
+                    }
+                }
+                Command::ReportFinding(mut finding) => {
+                    FINDINGS_REPORTED.store(true, Ordering::Relaxed);
+
+                    // saved first, so every output points to the evidence
+                    if let Err(e) = evidence::save(&mut finding, &self.config) {
+                        log::warn!(
+                            "Could not save evidence of {} finding: {}",
+                            finding.url(),
+                            e
+                        );
+                    }
+
+                    if let Some((tx_zap, _)) = zap.as_ref() {
+                        tx_zap.send(finding.url().to_string()).unwrap_or_default();
+                    }
+
+                    if let Some(writer) = dojo.as_mut() {
+                        if let Err(e) = writer.write_finding(&finding) {
+                            log::warn!(
+                                "Could not write {} finding to DefectDojo output: {}",
+                                finding.url(),
+                                e
+                            );
+                        }
+                    }
+
+                    if let Some(tx_results) = &self.tx_results {
+                        tx_results
+                            .send(ScanResult::Finding(finding.clone()))
+                            .unwrap_or_default();
+                    } else if !matches!(self.config.output_level, OutputLevel::Silent) {
+                        // --silent is reserved for urls only, findings are omitted
+                        ferox_print(&finding.as_str(), &PROGRESS_PRINTER);
+                    }
+
+                    if self.file_task.is_some() {
+                        // -o used, need to send the finding to be written out to disk
+                        self.tx_file
+                            .send(Command::ReportFinding(finding.clone()))
+                            .with_context(|| {
+                                fmt_err(&format!(
+                                    "Could not send {} finding to file handler",
+                                    finding.url()
+                                ))
+                            })?;
+                    }
+
+                    if self.events.enabled() {
+                        let data = serde_json::to_value(&*finding).unwrap_or_default();
+                        self.events.emit("finding", data);
+                    }
+
+                    stop_on_match(
+                        &self.config,
+                        &self.stopped,
+                        finding.url(),
+                        Some(finding.category()),
+                    );
+                }
+                Command::ReportError(record) => {
+                    // failed requests are only logged in the terminal, records go to files/streams
+                    if self.events.enabled() {
+                        let data = serde_json::to_value(&*record).unwrap_or_default();
+                        self.events.emit("request_error", data);
+                    }
+
+                    if self.file_task.is_some() {
+                        self.tx_file
+                            .send(Command::ReportError(record.clone()))
+                            .with_context(|| {
+                                fmt_err(&format!(
+                                    "Could not send {} error for {} to file handler",
+                                    record.category(),
+                                    record.url()
+                                ))
+                            })?;
                     }
                 }
                 Command::Sync(sender) => {
@@ -245,6 +711,13 @@ impl TermOutHandler {
                 _ => {} // no more commands needed
             }
         }
+
+        if let Some((tx_zap, zap_task)) = zap.take() {
+            // let ZAP catch up on whatever it hasn't been sent yet
+            drop(tx_zap);
+            zap_task.await?;
+        }
+
         log::trace!("exit: start");
         Ok(())
     }
@@ -253,6 +726,10 @@ impl TermOutHandler {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{
+        response::FeroxResponse,
+        statistics::{ErrorRecord, StatError},
+    };
 
     #[test]
     /// try to hit struct field coverage of FileOutHandler
@@ -266,6 +743,138 @@ mod tests {
         println!("{:?}", foh);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// buffered results are on disk once the file handler answers a sync, and anything reported
+    /// afterwards is written before the handler exits
+    async fn file_out_handler_flushes_on_sync_and_exit() {
+        let outfile = tempfile::NamedTempFile::new().unwrap();
+        let config = Arc::new(Configuration {
+            output: outfile.path().to_str().unwrap().to_string(),
+            ..Default::default()
+        });
+
+        let (tx, rx) = mpsc::unbounded_channel::<Command>();
+        let (tx_stats, _rx_stats) = mpsc::unbounded_channel::<Command>();
+        let mut handler = FileOutHandler::new(rx, config);
+        let task = tokio::spawn(async move { handler.start(tx_stats).await });
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/first");
+        tx.send(Command::Report(Box::new(response))).unwrap();
+
+        let (tx_sync, rx_sync) = oneshot::channel::<bool>();
+        tx.send(Command::Sync(tx_sync)).unwrap();
+        rx_sync.await.unwrap();
+
+        let contents = std::fs::read_to_string(outfile.path()).unwrap();
+        assert!(contents.contains("http://localhost/first"));
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/second");
+        tx.send(Command::Report(Box::new(response))).unwrap();
+        tx.send(Command::Exit).unwrap();
+        task.await.unwrap().unwrap();
+
+        let contents = std::fs::read_to_string(outfile.path()).unwrap();
+        assert!(contents.contains("http://localhost/second"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// error records are written to the output file as json, next to the results
+    async fn file_out_handler_writes_error_records() {
+        let outfile = tempfile::NamedTempFile::new().unwrap();
+        let config = Arc::new(Configuration {
+            output: outfile.path().to_str().unwrap().to_string(),
+            json: true,
+            ..Default::default()
+        });
+
+        let (tx, rx) = mpsc::unbounded_channel::<Command>();
+        let (tx_stats, _rx_stats) = mpsc::unbounded_channel::<Command>();
+        let mut handler = FileOutHandler::new(rx, config);
+        let task = tokio::spawn(async move { handler.start(tx_stats).await });
+
+        let record = ErrorRecord::new(
+            StatError::Dns,
+            &Method::GET,
+            &Url::parse("http://nope.localhost/").unwrap(),
+            "dns error",
+        );
+        tx.send(Command::ReportError(Box::new(record))).unwrap();
+        tx.send(Command::Exit).unwrap();
+        task.await.unwrap().unwrap();
+
+        let contents = std::fs::read_to_string(outfile.path()).unwrap();
+        assert!(contents.contains(
+            r#"{"type":"error","category":"dns","method":"GET","url":"http://nope.localhost/","#
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// urls-with-meta writes a line per non-wildcard response, nothing else, and buckets urls by
+    /// the technologies identified on their origin
+    async fn file_out_handler_writes_urls_with_meta_and_buckets() {
+        let directory = tempfile::tempdir().unwrap();
+        let output = directory.path().join("results.txt");
+
+        let config = Arc::new(Configuration {
+            output: output.to_str().unwrap().to_string(),
+            output_format: String::from(URLS_WITH_META),
+            bucket_by_tech: true,
+            ..Default::default()
+        });
+
+        let (tx, rx) = mpsc::unbounded_channel::<Command>();
+        let (tx_stats, mut rx_stats) = mpsc::unbounded_channel::<Command>();
+        let mut handler = FileOutHandler::new(rx, config);
+        let task = tokio::spawn(async move { handler.start(tx_stats).await });
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/index.php");
+        response.add_header("x-powered-by", "PHP/8.1.2");
+        response.add_header("content-type", "text/html; charset=UTF-8");
+        tx.send(Command::Report(Box::new(response))).unwrap();
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/images/");
+        tx.send(Command::Report(Box::new(response))).unwrap();
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/wildcard");
+        response.set_wildcard(true);
+        tx.send(Command::Report(Box::new(response))).unwrap();
+
+        tx.send(Command::Exit).unwrap();
+        task.await.unwrap().unwrap();
+
+        let contents = std::fs::read_to_string(&output).unwrap();
+        assert_eq!(
+            contents,
+            "http://localhost/index.php [200] [text/html]\nhttp://localhost/images/ [200] []\n"
+        );
+
+        let php = std::fs::read_to_string(directory.path().join("results.php.txt")).unwrap();
+        assert_eq!(
+            php,
+            "http://localhost/index.php\nhttp://localhost/images/\n"
+        );
+
+        assert!(rx_stats.try_recv().is_err());
+    }
+
+    #[test]
+    /// bucket files sit next to the output file, named after the technology
+    fn bucket_filename_inserts_technology_before_extension() {
+        assert_eq!(
+            bucket_filename("/tmp/results.txt", "ASP.NET"),
+            PathBuf::from("/tmp/results.asp-net.txt")
+        );
+        assert_eq!(
+            bucket_filename("results", "PHP"),
+            PathBuf::from("results.php")
+        );
+    }
+
     #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
     /// try to hit struct field coverage of TermOutHandler
     async fn struct_fields_of_term_out_handler() {
@@ -278,6 +887,11 @@ mod tests {
             file_task: None,
             receiver: rx,
             tx_file,
+            tx_results: None,
+            responses: Arc::default(),
+            stopped: Arc::default(),
+            session: Arc::default(),
+            events: Arc::default(),
         };
 
         println!("{:?}", toh);
@@ -1,4 +1,4 @@
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 
 use anyhow::{bail, Result};
 use tokio::sync::{mpsc, Semaphore};
@@ -9,8 +9,8 @@ use crate::{
     scanner::FeroxScanner,
     statistics::StatField::TotalScans,
     url::FeroxUrl,
-    utils::should_deny_url,
-    CommandReceiver, CommandSender, FeroxChannel, Joiner, SLEEP_DURATION,
+    utils::{get_unique_words_from_wordlist, should_deny_url},
+    wordlist_map, CommandReceiver, CommandSender, FeroxChannel, Joiner, SLEEP_DURATION,
 };
 
 use super::command::Command::AddToUsizeField;
@@ -57,6 +57,10 @@ pub struct ScanHandler {
     /// wordlist (re)used for each scan
     wordlist: std::sync::Mutex<Option<Arc<Vec<String>>>>,
 
+    /// wordlists of `[[wordlist_map]]` entries, by path on disk, read the first time a directory
+    /// matches them
+    mapped_wordlists: HashMap<String, Arc<Vec<String>>>,
+
     /// group of scans that need to be joined
     tasks: Vec<Arc<FeroxScan>>,
 
@@ -102,6 +106,7 @@ impl ScanHandler {
             depths: Vec::new(),
             limiter: Arc::new(limiter),
             wordlist: std::sync::Mutex::new(None),
+            mapped_wordlists: HashMap::new(),
         }
     }
 
@@ -119,7 +124,11 @@ impl ScanHandler {
     pub fn initialize(handles: Arc<Handles>) -> (Joiner, ScanHandle) {
         log::trace!("enter: initialize");
 
-        let data = Arc::new(FeroxScans::new(handles.config.output_level));
+        let data = Arc::new(FeroxScans::new(
+            handles.config.output_level,
+            handles.output.data.clone(),
+            handles.events.clone(),
+        ));
         let (tx, rx): FeroxChannel<Command> = mpsc::unbounded_channel();
 
         let max_depth = handles.config.depth;
@@ -186,10 +195,46 @@ impl ScanHandler {
         bail!("Could not get underlying wordlist")
     }
 
+    /// Wordlist for the directory at `url`: the one mapped to its path by `[[wordlist_map]]`, or
+    /// the underlying wordlist when there's none (or it can't be read)
+    fn wordlist_for(&mut self, url: &str) -> Result<Arc<Vec<String>>> {
+        let mapping = match wordlist_map::mapping_for(&self.handles.config.wordlist_map, url) {
+            Some(mapping) => mapping,
+            None => return self.get_wordlist(),
+        };
+
+        let path = mapping.resolved_wordlist();
+
+        if let Some(words) = self.mapped_wordlists.get(&path) {
+            return Ok(words.clone());
+        }
+
+        match get_unique_words_from_wordlist(&path) {
+            Ok(words) if !words.is_empty() => {
+                log::info!("scanning {} and below with {}", mapping.path, path);
+                self.mapped_wordlists.insert(path, words.clone());
+                Ok(words)
+            }
+            Ok(_) => {
+                log::warn!(
+                    "Did not find any words in {}, using the wordlist instead",
+                    path
+                );
+                self.get_wordlist()
+            }
+            Err(e) => {
+                log::warn!("{}, using the wordlist instead", e);
+                self.get_wordlist()
+            }
+        }
+    }
+
     /// wrapper around scanning a url to stay DRY
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(targets = targets.len())))]
     async fn ordered_scan_url(&mut self, targets: Vec<String>, order: ScanOrder) -> Result<()> {
         log::trace!("enter: ordered_scan_url({:?}, {:?})", targets, order);
-        let should_test_deny = !self.handles.config.url_denylist.is_empty();
+        let should_test_deny = !self.handles.config.url_denylist.is_empty()
+            || !self.handles.config.deny_url_regex.is_empty();
 
         for target in targets {
             if self.data.contains(&target) && matches!(order, ScanOrder::Latest) {
@@ -213,7 +258,7 @@ impl ScanHandler {
                 continue;
             }
 
-            let list = self.get_wordlist()?;
+            let list = self.wordlist_for(&target)?;
 
             log::info!("scan handler received {} - beginning scan", target);
 
@@ -250,6 +295,7 @@ impl ScanHandler {
         Ok(())
     }
 
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(url = %response.url())))]
     async fn try_recursion(&mut self, response: Box<FeroxResponse>) -> Result<()> {
         log::trace!("enter: try_recursion({:?})", response,);
 
@@ -258,6 +304,18 @@ impl ScanHandler {
             return Ok(());
         }
 
+        if self.handles.output.is_stopped() {
+            // --stop-on-match already found what it was looking for
+            return Ok(());
+        }
+
+        let limits = &self.handles.config.request_context.request_limits;
+
+        if limits.reached(response.url()) {
+            // --request-limit: the host can't be sent any more requests
+            return Ok(());
+        }
+
         let mut base_depth = 1_usize;
 
         for (base_url, base_url_depth) in &self.depths {
@@ -1,6 +1,7 @@
 use super::*;
 use crate::{
     config::Configuration,
+    numbers::NumberFormat,
     progress::{add_bar, BarType},
     statistics::{StatField, Stats},
     CommandSender, FeroxChannel, Joiner,
@@ -8,11 +9,103 @@ use crate::{
 use anyhow::Result;
 use console::style;
 use indicatif::ProgressBar;
-use std::{sync::Arc, time::Instant};
-use tokio::sync::{
-    mpsc::{self, UnboundedReceiver},
-    oneshot,
+use std::{
+    mem,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
+use tokio::{
+    sync::{
+        mpsc::{self, error::SendError, UnboundedReceiver},
+        oneshot,
+    },
+    time,
+};
+
+/// number of buffers that batched updates are spread across, so that the scanners' tasks don't
+/// all wait on the same lock to add an update
+const STATS_BATCHES: usize = 8;
+
+/// number of per-request updates buffered by a `StatsSender` before they're sent to the handler
+/// as a single `Command::AddBatch`
+const STATS_BATCH_SIZE: usize = 64;
+
+/// how often the handler collects updates that are still sitting in a buffer, so that the
+/// progress bar keeps moving when requests trickle in slower than `STATS_BATCH_SIZE` at a time
+const STATS_BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// per-request updates waiting to be handed to the statistics handler
+type StatsBatch = Arc<Mutex<Vec<Command>>>;
+
+/// take everything out of the given batch, leaving it empty
+fn take_batch(batch: &StatsBatch) -> Vec<Command> {
+    match batch.lock() {
+        Ok(mut updates) => mem::take(&mut *updates),
+        Err(_) => Vec::new(),
+    }
+}
+
+#[derive(Debug, Clone)]
+/// transmitter for the updates sent for every request (status codes, errors, response times)
+///
+/// when created by `StatsHandle::batched`, those updates are buffered and sent to the handler
+/// `STATS_BATCH_SIZE` at a time, instead of costing a trip through the channel each; anything
+/// else is sent right away. one created from a plain `CommandSender` sends everything right away
+pub struct StatsSender {
+    /// transmitter used for commands and full batches
+    tx: CommandSender,
+
+    /// buffer shared with the handler, which also empties it on a timer and before `Sync`,
+    /// `Save`, and `Exit`
+    batch: Option<StatsBatch>,
+}
+
+/// implementation of StatsSender
+impl StatsSender {
+    /// send the given `Command`, or add it to the buffer if it's a per-request update
+    pub fn send(&self, command: Command) -> Result<(), SendError<Command>> {
+        let batch = match &self.batch {
+            Some(batch) if Self::is_batched(&command) => batch,
+            _ => return self.tx.send(command),
+        };
+
+        let full = match batch.lock() {
+            Ok(mut updates) => {
+                updates.push(command);
+
+                if updates.len() >= STATS_BATCH_SIZE {
+                    mem::take(&mut *updates)
+                } else {
+                    return Ok(());
+                }
+            }
+            Err(_) => return self.tx.send(command),
+        };
+
+        self.tx.send(Command::AddBatch(full))
+    }
+
+    /// whether the given `Command` is one of the per-request updates that get buffered
+    fn is_batched(command: &Command) -> bool {
+        matches!(
+            command,
+            Command::AddError(_)
+                | Command::AddStatus(_)
+                | Command::AddLatency(..)
+                | Command::AddRequest
+        )
+    }
+}
+
+/// a `StatsSender` that doesn't buffer anything
+impl From<CommandSender> for StatsSender {
+    fn from(tx: CommandSender) -> Self {
+        Self { tx, batch: None }
+    }
+}
 
 #[derive(Debug)]
 /// Container for statistics transmitter and Stats object
@@ -22,13 +115,42 @@ pub struct StatsHandle {
 
     /// transmitter used to update `data`
     pub tx: CommandSender,
+
+    /// buffers handed out by `batched`
+    batches: Vec<StatsBatch>,
+
+    /// round-robin position used to pick the next buffer
+    next_batch: AtomicUsize,
 }
 
 /// implementation of StatsHandle
 impl StatsHandle {
     /// Given an Arc-wrapped Stats and CommandSender, create a new StatsHandle
     pub fn new(data: Arc<Stats>, tx: CommandSender) -> Self {
-        Self { data, tx }
+        Self {
+            data,
+            tx,
+            batches: Vec::new(),
+            next_batch: AtomicUsize::new(0),
+        }
+    }
+
+    /// transmitter for high-volume updates, such as the ones sent for every request; per-request
+    /// updates are buffered and sent in batches
+    ///
+    /// buffered updates reach `data` within `STATS_BATCH_INTERVAL`, and are always accounted for
+    /// by the time a `Sync`, `Save`, or `Exit` is acted upon
+    pub fn batched(&self) -> StatsSender {
+        if self.batches.is_empty() {
+            return StatsSender::from(self.tx.clone());
+        }
+
+        let index = self.next_batch.fetch_add(1, Ordering::Relaxed) % self.batches.len();
+
+        StatsSender {
+            tx: self.tx.clone(),
+            batch: Some(self.batches[index].clone()),
+        }
     }
 
     /// Send the given Command over `tx`
@@ -55,24 +177,82 @@ pub struct StatsHandler {
     /// Receiver half of mpsc from which `StatCommand`s are processed
     receiver: UnboundedReceiver<Command>,
 
+    /// buffers filled by the `StatsSender`s handed out by `StatsHandle::batched`
+    batches: Vec<StatsBatch>,
+
     /// data class that stores all statistics updates
     stats: Arc<Stats>,
+
+    /// formatting of the counts shown on the overall scan's progress bar
+    numbers: NumberFormat,
 }
 
 /// implementation of event handler for statistics
 impl StatsHandler {
     /// create new event handler
-    fn new(stats: Arc<Stats>, rx_stats: UnboundedReceiver<Command>) -> Self {
+    fn new(
+        stats: Arc<Stats>,
+        numbers: NumberFormat,
+        rx_stats: UnboundedReceiver<Command>,
+        batches: Vec<StatsBatch>,
+    ) -> Self {
         // will be updated later via StatCommand; delay is for banner to print first
         let bar = ProgressBar::hidden();
 
         Self {
             bar,
             stats,
+            numbers,
+            batches,
             receiver: rx_stats,
         }
     }
 
+    /// process every update still sitting in a buffer
+    ///
+    /// called on a timer, and before acting on commands that expect all prior updates to be
+    /// accounted for
+    fn drain_batches(&mut self) {
+        for index in 0..self.batches.len() {
+            for command in take_batch(&self.batches[index]) {
+                self.handle_update(command);
+            }
+        }
+    }
+
+    /// apply a single high-volume update
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all))]
+    fn handle_update(&mut self, command: Command) {
+        match command {
+            Command::AddError(err) => {
+                self.stats.add_error(err);
+                self.increment_bar();
+            }
+            Command::AddStatus(status) => {
+                self.stats.add_status_code(status);
+                self.increment_bar();
+            }
+            Command::AddLatency(status, elapsed) => self.stats.add_latency(status, elapsed),
+            Command::AddBatch(updates) => {
+                for update in updates {
+                    self.handle_update(update);
+                }
+            }
+            Command::AddRequest => {
+                self.stats.add_request();
+                self.increment_bar();
+            }
+            Command::AddToUsizeField(field, value) => {
+                self.stats.update_usize_field(field, value);
+
+                if matches!(field, StatField::TotalScans) {
+                    self.set_bar_length();
+                }
+            }
+            _ => log::warn!("unexpected statistics update: {:?}", command),
+        }
+    }
+
     /// Start a single consumer task (sc side of mpsc)
     ///
     /// The consumer simply receives `StatCommands` and updates the given `Stats` object as appropriate
@@ -80,43 +260,55 @@ impl StatsHandler {
         log::trace!("enter: start({:?})", self);
 
         let start = Instant::now();
+        let mut flush = time::interval(STATS_BATCH_INTERVAL);
 
-        while let Some(command) = self.receiver.recv().await {
-            match command as Command {
-                Command::AddError(err) => {
-                    self.stats.add_error(err);
-                    self.increment_bar();
+        loop {
+            // the tick only wakes the loop up; buffers are drained once `recv` is out of scope
+            let received = tokio::select! {
+                command = self.receiver.recv() => Some(command),
+                _ = flush.tick() => None,
+            };
+
+            let command = match received {
+                Some(Some(command)) => command,
+                Some(None) => break,
+                None => {
+                    self.drain_batches();
+                    continue;
                 }
-                Command::AddStatus(status) => {
-                    self.stats.add_status_code(status);
+            };
+
+            if matches!(command, Command::Save | Command::Sync(_) | Command::Exit) {
+                self.drain_batches();
+            }
 
-                    self.increment_bar();
+            match command {
+                Command::AddError(_)
+                | Command::AddStatus(_)
+                | Command::AddLatency(..)
+                | Command::AddBatch(_)
+                | Command::AddRequest
+                | Command::AddToUsizeField(..) => {
+                    self.handle_update(command);
                 }
-                Command::AddRequest => {
-                    self.stats.add_request();
-                    self.increment_bar();
+                Command::AddDirectoryRequests(directory, value) => {
+                    self.stats.add_directory_requests(&directory, value);
                 }
                 Command::Save => {
                     self.stats
                         .save(start.elapsed().as_secs_f64(), output_file)?;
                 }
-                Command::AddToUsizeField(field, value) => {
-                    self.stats.update_usize_field(field, value);
-
-                    if matches!(field, StatField::TotalScans) {
-                        self.bar.set_length(self.stats.total_expected() as u64);
-                    }
-                }
                 Command::SubtractFromUsizeField(field, value) => {
                     self.stats.subtract_from_usize_field(field, value);
 
                     if matches!(field, StatField::TotalExpected) {
-                        self.bar.set_length(self.stats.total_expected() as u64);
+                        self.set_bar_length();
                     }
                 }
                 Command::AddToF64Field(field, value) => self.stats.update_f64_field(field, value),
                 Command::CreateBar => {
                     self.bar = add_bar("", self.stats.total_expected() as u64, BarType::Total);
+                    self.update_bar_counts();
                 }
                 Command::LoadStats(filename) => {
                     self.stats.merge_from(&filename)?;
@@ -141,13 +333,31 @@ impl StatsHandler {
         let msg = format!(
             "{}:{:<7} {}:{:<7}",
             style("found").green(),
-            self.stats.resources_discovered(),
+            self.numbers.count(self.stats.resources_discovered() as u64),
             style("errors").red(),
-            self.stats.errors(),
+            self.numbers.count(self.stats.errors() as u64),
         );
 
         self.bar.set_message(&msg);
         self.bar.inc(1);
+        self.update_bar_counts();
+    }
+
+    /// Wrapper around changing the length of the overall scan's progress bar
+    fn set_bar_length(&self) {
+        self.bar.set_length(self.stats.total_expected() as u64);
+        self.update_bar_counts();
+    }
+
+    /// show the overall scan's position/length, formatted per --thousands-separator
+    fn update_bar_counts(&self) {
+        let counts = format!(
+            "{:>7}/{:7}",
+            self.numbers.count(self.bar.position()),
+            self.numbers.count(self.bar.length())
+        );
+
+        self.bar.set_prefix(&counts);
     }
 
     /// Initialize new `Stats` object and the sc side of an mpsc channel that is responsible for
@@ -155,14 +365,17 @@ impl StatsHandler {
     pub fn initialize(config: Arc<Configuration>) -> (Joiner, StatsHandle) {
         log::trace!("enter: initialize");
 
-        let data = Arc::new(Stats::new(config.extensions.len(), config.json));
+        let data = Arc::new(Stats::new(config.json));
         let (tx, rx): FeroxChannel<Command> = mpsc::unbounded_channel();
+        let batches: Vec<StatsBatch> = (0..STATS_BATCHES).map(|_| StatsBatch::default()).collect();
 
-        let mut handler = StatsHandler::new(data.clone(), rx);
+        let numbers = NumberFormat::from_config(&config);
+        let mut handler = StatsHandler::new(data.clone(), numbers, rx, batches.clone());
 
         let task = tokio::spawn(async move { handler.start(&config.output).await });
 
-        let event_handle = StatsHandle::new(data, tx);
+        let mut event_handle = StatsHandle::new(data, tx);
+        event_handle.batches = batches;
 
         log::trace!("exit: initialize -> ({:?}, {:?})", task, event_handle);
 
@@ -0,0 +1,413 @@
+//! emit what happens during a scan (results, findings, errors, directory scans starting and
+//! stopping, and periodic statistics) as NDJSON while it happens, to a unix socket, a tcp
+//! listener, or a file/FIFO (--event-stream)
+//!
+//! each scan opens its own stream (see `EventStream`), so scans running side by side in one
+//! process (i.e. `feroxbuster serve`) write to their own destination, stamped with their own id
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncWrite, AsyncWriteExt},
+    net::TcpStream,
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+    time,
+};
+
+use crate::{config::Configuration, event_handlers::Handles, utils::unix_timestamp};
+
+/// time between two `statistics` events
+const STATISTICS_INTERVAL: Duration = Duration::from_secs(5);
+
+lazy_static! {
+    /// (scan id, events) of every stream that's open; log messages aren't tied to any one scan,
+    /// so warnings and errors go to all of them (see `broadcast`)
+    static ref OPEN: Mutex<Vec<(String, UnboundedSender<String>)>> = Mutex::new(Vec::new());
+}
+
+/// Where events are written, as given to --event-stream
+#[derive(Debug, PartialEq)]
+enum Destination {
+    /// `unix:PATH`; a unix socket something is already listening on
+    Unix(String),
+
+    /// `tcp:HOST:PORT`; a tcp address something is already listening on
+    Tcp(String),
+
+    /// anything else; a file (or FIFO) that events are appended to
+    File(String),
+}
+
+impl Destination {
+    /// Parse an --event-stream value
+    fn parse(value: &str) -> Result<Self, String> {
+        if let Some(path) = value.strip_prefix("unix:") {
+            if !cfg!(unix) {
+                return Err(format!(
+                    "Event stream {} is a unix socket, which isn't supported on this platform",
+                    value
+                ));
+            }
+
+            if path.is_empty() {
+                return Err(format!("Event stream {} needs a path to a socket", value));
+            }
+
+            return Ok(Destination::Unix(path.to_string()));
+        }
+
+        if let Some(address) = value.strip_prefix("tcp:") {
+            let has_port = match address.rsplit_once(':') {
+                Some((host, port)) => !host.is_empty() && port.parse::<u16>().is_ok(),
+                None => false,
+            };
+
+            if !has_port {
+                return Err(format!(
+                    "Event stream {} needs a host and port (ex: tcp:127.0.0.1:9000)",
+                    value
+                ));
+            }
+
+            return Ok(Destination::Tcp(address.to_string()));
+        }
+
+        if value.is_empty() {
+            return Err(String::from("Event stream needs a destination"));
+        }
+
+        Ok(Destination::File(value.to_string()))
+    }
+
+    /// Open a connection to (or the file at) the destination
+    async fn connect(&self) -> Result<Box<dyn AsyncWrite + Send + Unpin>> {
+        match self {
+            #[cfg(unix)]
+            Destination::Unix(path) => Ok(Box::new(tokio::net::UnixStream::connect(path).await?)),
+            #[cfg(not(unix))]
+            Destination::Unix(_) => {
+                anyhow::bail!("unix sockets aren't supported on this platform")
+            }
+            Destination::Tcp(address) => Ok(Box::new(TcpStream::connect(address).await?)),
+            Destination::File(path) => Ok(Box::new(
+                tokio::fs::OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .await?,
+            )),
+        }
+    }
+}
+
+/// Validate an --event-stream value; `unix:PATH`, `tcp:HOST:PORT`, or the path of a file/FIFO
+pub fn valid_destination(value: &str) -> Result<(), String> {
+    Destination::parse(value).map(|_| ())
+}
+
+/// Single line of NDJSON for the given event, stamped with the id of the scan it's from
+fn event_line(scan_id: &str, event: &str, data: Value) -> String {
+    let mut line = json!({
+        "type": "event",
+        "event": event,
+        "time": unix_timestamp(),
+        "data": data,
+    });
+
+    if !scan_id.is_empty() {
+        line["scan_id"] = Value::String(scan_id.to_string());
+    }
+
+    format!("{}\n", line)
+}
+
+/// A single scan's --event-stream; the default is a stream that was never opened, which drops
+/// every event
+#[derive(Debug, Default)]
+pub struct EventStream {
+    /// events waiting to be written out; `None` without --event-stream, or once the stream is
+    /// closed
+    events: Mutex<Option<UnboundedSender<String>>>,
+
+    /// task writing events out, waited on when the stream is finished
+    writer: Mutex<Option<JoinHandle<()>>>,
+
+    /// id of the scan the stream belongs to, stamped on every event
+    scan_id: String,
+}
+
+impl EventStream {
+    /// Start writing the scan's events to its --event-stream destination; without one, the
+    /// stream that's returned drops every event
+    ///
+    /// a destination that can't be reached is warned about, and events are dropped from then on
+    pub fn open(config: &Configuration) -> Arc<Self> {
+        let stream = Self {
+            scan_id: config.scan_id.clone(),
+            ..Default::default()
+        };
+
+        if config.event_stream.is_empty() {
+            return Arc::new(stream);
+        }
+
+        let destination = match Destination::parse(&config.event_stream) {
+            Ok(destination) => destination,
+            Err(e) => {
+                log::error!("{}", e);
+                return Arc::new(stream);
+            }
+        };
+
+        let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+
+        if let Ok(mut open) = OPEN.lock() {
+            open.push((stream.scan_id.clone(), tx.clone()));
+        }
+
+        if let Ok(mut events) = stream.events.lock() {
+            *events = Some(tx);
+        }
+
+        let name = config.event_stream.clone();
+
+        // when the destination goes away, the receiver is dropped along with the task, which
+        // closes the stream for everything still holding on to it
+        let task = tokio::spawn(async move {
+            let mut writer = match destination.connect().await {
+                Ok(writer) => writer,
+                Err(e) => {
+                    log::warn!("Could not open event stream {}: {}", name, e);
+                    return;
+                }
+            };
+
+            while let Some(line) = rx.recv().await {
+                if let Err(e) = writer.write_all(line.as_bytes()).await {
+                    log::warn!("Could not write to event stream {}: {}", name, e);
+                    return;
+                }
+            }
+
+            writer.flush().await.unwrap_or_default();
+        });
+
+        if let Ok(mut writer) = stream.writer.lock() {
+            *writer = Some(task);
+        }
+
+        Arc::new(stream)
+    }
+
+    /// Whether events are being written anywhere; callers check this before building an
+    /// expensive event
+    pub fn enabled(&self) -> bool {
+        self.events
+            .lock()
+            .map(|events| matches!(events.as_ref(), Some(tx) if !tx.is_closed()))
+            .unwrap_or(false)
+    }
+
+    /// Emit the given event; a no-op without --event-stream
+    pub fn emit(&self, event: &str, data: Value) {
+        if let Ok(events) = self.events.lock() {
+            if let Some(tx) = events.as_ref() {
+                tx.send(event_line(&self.scan_id, event, data))
+                    .unwrap_or_default();
+            }
+        }
+    }
+
+    /// Stop accepting events and wait for the ones already emitted to be written out
+    pub async fn finish(&self) {
+        let tx = self.events.lock().ok().and_then(|mut events| events.take());
+
+        if let (Some(tx), Ok(mut open)) = (tx, OPEN.lock()) {
+            open.retain(|(_, other)| !other.same_channel(&tx));
+        }
+
+        let task = self.writer.lock().ok().and_then(|mut writer| writer.take());
+
+        if let Some(task) = task {
+            task.await.unwrap_or_default();
+        }
+    }
+}
+
+/// Emit the given event on every open stream, each stamped with its own scan's id; used for log
+/// messages, which aren't tied to any one scan
+pub fn broadcast(event: &str, data: Value) {
+    if let Ok(open) = OPEN.lock() {
+        for (scan_id, tx) in open.iter() {
+            tx.send(event_line(scan_id, event, data.clone()))
+                .unwrap_or_default();
+        }
+    }
+}
+
+/// Emit a `statistics` event every `STATISTICS_INTERVAL`, for as long as the scan's stream is
+/// open
+pub async fn tick_statistics(handles: Arc<Handles>) {
+    let mut ticker = time::interval(STATISTICS_INTERVAL);
+
+    // the first tick completes right away, before anything's been counted
+    ticker.tick().await;
+
+    loop {
+        ticker.tick().await;
+
+        if !handles.events.enabled() {
+            break;
+        }
+
+        handles.events.emit(
+            "statistics",
+            serde_json::to_value(&*handles.stats.data).unwrap_or_default(),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{io::AsyncReadExt, net::TcpListener};
+
+    #[test]
+    /// unix sockets, tcp addresses, and files are told apart by their prefix
+    fn destination_parses_prefixes() {
+        assert_eq!(
+            Destination::parse("tcp:127.0.0.1:9000"),
+            Ok(Destination::Tcp(String::from("127.0.0.1:9000")))
+        );
+        assert_eq!(
+            Destination::parse("/tmp/ferox.fifo"),
+            Ok(Destination::File(String::from("/tmp/ferox.fifo")))
+        );
+
+        if cfg!(unix) {
+            assert_eq!(
+                Destination::parse("unix:/tmp/ferox.sock"),
+                Ok(Destination::Unix(String::from("/tmp/ferox.sock")))
+            );
+        }
+
+        assert!(Destination::parse("unix:").is_err());
+        assert!(Destination::parse("tcp:127.0.0.1").is_err());
+        assert!(Destination::parse("tcp::9000").is_err());
+        assert!(Destination::parse("").is_err());
+    }
+
+    #[test]
+    /// events are single lines of json that carry their name, time, data, and scan id
+    fn event_line_is_ndjson() {
+        let line = event_line("abc", "scan_start", json!({"url": "http://localhost/"}));
+
+        assert!(line.ends_with('\n'));
+        assert_eq!(line.matches('\n').count(), 1);
+
+        let parsed: Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(parsed["type"], "event");
+        assert_eq!(parsed["event"], "scan_start");
+        assert_eq!(parsed["data"]["url"], "http://localhost/");
+        assert_eq!(parsed["scan_id"], "abc");
+        assert!(parsed["time"].as_u64().unwrap() > 0);
+
+        let line = event_line("", "scan_start", json!({}));
+        assert!(serde_json::from_str::<Value>(&line).unwrap()["scan_id"].is_null());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// events emitted before finish are written to a tcp listener, none after
+    async fn events_are_written_to_tcp() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        let config = Configuration {
+            event_stream: format!("tcp:{}", address),
+            ..Default::default()
+        };
+
+        let events = EventStream::open(&config);
+        assert!(events.enabled());
+
+        // other tests broadcast log messages of their own while this one runs
+        let marker = json!({"test": "events_are_written_to_tcp"});
+
+        events.emit("finding", marker.clone());
+        events.emit("scan_stop", marker.clone());
+
+        let (mut stream, _) = listener.accept().await.unwrap();
+
+        events.finish().await;
+        assert!(!events.enabled());
+        events.emit("ignored", marker.clone());
+
+        let mut received = String::new();
+        stream.read_to_string(&mut received).await.unwrap();
+
+        let events: Vec<Value> = received
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .filter(|event| event["data"] == marker)
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[0]["event"], "finding");
+        assert_eq!(events[1]["event"], "scan_stop");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// scans open their own streams; each one's events go to its own destination, stamped with
+    /// its own scan id
+    async fn streams_are_per_scan() {
+        let first_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let second_listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+
+        let first = EventStream::open(&Configuration {
+            event_stream: format!("tcp:{}", first_listener.local_addr().unwrap()),
+            scan_id: String::from("first"),
+            ..Default::default()
+        });
+        let second = EventStream::open(&Configuration {
+            event_stream: format!("tcp:{}", second_listener.local_addr().unwrap()),
+            scan_id: String::from("second"),
+            ..Default::default()
+        });
+
+        let marker = json!({"test": "streams_are_per_scan"});
+
+        first.emit("finding", marker.clone());
+        second.emit("scan_stop", marker.clone());
+
+        let (mut first_stream, _) = first_listener.accept().await.unwrap();
+        let (mut second_stream, _) = second_listener.accept().await.unwrap();
+
+        first.finish().await;
+        assert!(second.enabled());
+        second.finish().await;
+
+        for (stream, event, scan_id) in vec![
+            (&mut first_stream, "finding", "first"),
+            (&mut second_stream, "scan_stop", "second"),
+        ] {
+            let mut received = String::new();
+            stream.read_to_string(&mut received).await.unwrap();
+
+            let events: Vec<Value> = received
+                .lines()
+                .map(|line| serde_json::from_str::<Value>(line).unwrap())
+                .filter(|event| event["data"] == marker)
+                .collect();
+
+            assert_eq!(events.len(), 1);
+            assert_eq!(events[0]["event"], event);
+            assert_eq!(events[0]["scan_id"], scan_id);
+        }
+    }
+}
@@ -0,0 +1,215 @@
+//! keep the raw request and response behind each finding that's severe enough
+//! (--evidence-dir, --evidence-severity), so the evidence for a report doesn't need the url to be
+//! requested again later
+//!
+//! a finding carries the exchange it was made from: the response an analyzer looked at, or the
+//! request a probe sent of its own (see `burp::record`); a finding's evidence is written next to
+//! the others in the evidence directory as `<name>.request` and `<name>.response`
+use std::{
+    fs::{create_dir_all, write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+
+use crate::{
+    burp::raw_response,
+    config::Configuration,
+    finding::{FeroxFinding, SEVERITIES},
+    response::FeroxResponse,
+    utils::fmt_err,
+};
+
+/// The raw request and response behind a finding
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exchange {
+    /// the request, as it looked on the wire
+    request: String,
+
+    /// the response, as it would've looked on the wire
+    response: String,
+}
+
+impl Exchange {
+    /// Exchange of the given response, when the request that was sent for it was recorded
+    pub fn of(response: &FeroxResponse) -> Option<Self> {
+        let request = response.sent_request()?;
+
+        Some(Self {
+            request: request.to_string(),
+            response: raw_response(response),
+        })
+    }
+}
+
+/// Position of the given severity in `SEVERITIES`; unknown severities rank with `info`
+fn rank(severity: &str) -> usize {
+    SEVERITIES
+        .iter()
+        .position(|known| known.eq_ignore_ascii_case(severity))
+        .unwrap_or(0)
+}
+
+/// Validate an --evidence-severity value
+pub fn valid_severity(severity: &str) -> Result<(), String> {
+    if SEVERITIES
+        .iter()
+        .any(|known| known.eq_ignore_ascii_case(severity))
+    {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid evidence severity {}, expected one of {}",
+            severity,
+            SEVERITIES.join(", ")
+        ))
+    }
+}
+
+/// Name the evidence of the given finding is saved under; anything that isn't safe in a file name
+/// becomes an underscore (secret, https://one.test/app.js -> secret_https___one.test_app.js)
+fn evidence_name(finding: &FeroxFinding) -> String {
+    format!("{}_{}", finding.category(), finding.url())
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Write the given exchange to `dir` under `name`; returns the paths of the saved request and
+/// response
+fn write_exchange(dir: &Path, name: &str, exchange: &Exchange) -> Result<(PathBuf, PathBuf)> {
+    create_dir_all(dir).with_context(|| fmt_err(&format!("Could not create {}", dir.display())))?;
+
+    let request = dir.join(format!("{}.request", name));
+    let response = dir.join(format!("{}.response", name));
+
+    write(&request, &exchange.request)
+        .with_context(|| fmt_err(&format!("Could not write {}", request.display())))?;
+    write(&response, &exchange.response)
+        .with_context(|| fmt_err(&format!("Could not write {}", response.display())))?;
+
+    Ok((request, response))
+}
+
+/// Save the raw request and response behind the given finding to the --evidence-dir, when it's
+/// at least as severe as --evidence-severity, and record where in the finding's details
+/// (`evidence`); findings that don't carry an exchange (i.e. the ones summarizing the whole scan)
+/// are left as they are
+pub fn save(finding: &mut FeroxFinding, config: &Configuration) -> Result<()> {
+    if config.evidence_dir.is_empty() || rank(finding.severity()) < rank(&config.evidence_severity)
+    {
+        return Ok(());
+    }
+
+    let exchange = match finding.exchange() {
+        Some(exchange) => exchange,
+        None => {
+            log::debug!("no exchange behind {}, no evidence saved", finding.url());
+            return Ok(());
+        }
+    };
+
+    let (request, response) = write_exchange(
+        Path::new(&config.evidence_dir),
+        &evidence_name(finding),
+        exchange,
+    )?;
+
+    if let Value::Object(details) = &mut finding.details {
+        details.insert(
+            "evidence".to_string(),
+            json!({
+                "request": request.to_string_lossy(),
+                "response": response.to_string_lossy(),
+            }),
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    /// severities are ranked from info to critical, and unrated secrets rank as high
+    fn findings_are_ranked_by_severity() {
+        let secret = FeroxFinding::new("secret", "http://localhost/", "", json!({}));
+        let form = FeroxFinding::new("form", "http://localhost/", "", json!({}));
+        let cors = FeroxFinding::new("cors", "http://localhost/", "", json!({"severity": "low"}));
+
+        assert!(rank(secret.severity()) > rank("medium"));
+        assert!(rank(form.severity()) < rank("low"));
+        assert_eq!(rank(cors.severity()), rank("LOW"));
+
+        assert!(valid_severity("High").is_ok());
+        assert!(valid_severity("severe").is_err());
+    }
+
+    #[test]
+    /// findings that are severe enough get their own exchange saved, and point to it
+    fn save_writes_evidence_of_severe_findings() {
+        let tmp_dir = TempDir::new().unwrap();
+        let config = Configuration {
+            evidence_dir: tmp_dir.path().to_string_lossy().to_string(),
+            evidence_severity: "high".to_string(),
+            ..Default::default()
+        };
+
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/app.js");
+        response.set_text("const key = 'AKIA...';");
+        response.set_sent_request(
+            "GET /app.js HTTP/1.1\r\nHost: localhost\r\nX-Scan-Id: ferox-1\r\n\r\n",
+        );
+
+        // another exchange for the same url, i.e. a probe's, doesn't take its place
+        let mut probe = FeroxResponse::default();
+        probe.set_url("http://localhost/app.js");
+        probe.set_sent_request("OPTIONS /app.js HTTP/1.1\r\nHost: localhost\r\n\r\n");
+        let probed = FeroxFinding::new("cors", "http://localhost/app.js", "", json!({}))
+            .with_exchange(&probe);
+
+        let mut form = FeroxFinding::new("form", "http://localhost/app.js", "", json!({}))
+            .with_exchange(&response);
+        save(&mut form, &config).unwrap();
+        assert!(form.details().get("evidence").is_none());
+
+        let mut unrecorded = FeroxFinding::new("secret", "http://localhost/", "", json!({}));
+        save(&mut unrecorded, &config).unwrap();
+        assert!(unrecorded.details().get("evidence").is_none());
+
+        let mut secret = FeroxFinding::new("secret", "http://localhost/app.js", "", json!({}))
+            .with_exchange(&response);
+        save(&mut secret, &config).unwrap();
+
+        let request = tmp_dir
+            .path()
+            .join("secret_http___localhost_app.js.request");
+        assert_eq!(
+            secret.details()["evidence"]["request"],
+            request.to_string_lossy().to_string()
+        );
+
+        let request = std::fs::read_to_string(request).unwrap();
+        let response =
+            std::fs::read_to_string(secret.details()["evidence"]["response"].as_str().unwrap())
+                .unwrap();
+
+        assert_eq!(
+            request,
+            "GET /app.js HTTP/1.1\r\nHost: localhost\r\nX-Scan-Id: ferox-1\r\n\r\n"
+        );
+        assert!(response.ends_with("\r\n\r\nconst key = 'AKIA...';"));
+        assert!(probed.exchange().unwrap().request.starts_with("OPTIONS "));
+    }
+}
@@ -0,0 +1,182 @@
+//! Process exit codes used by feroxbuster, allowing scripts and CI pipelines to tell the
+//! outcome of a scan apart without having to parse its output
+//!
+//! | code | meaning                                  |
+//! |------|------------------------------------------|
+//! | 0    | scan completed, nothing was found        |
+//! | 1    | scan completed, at least one finding     |
+//! | 2    | configuration/usage error                |
+//! | 3    | none of the targets could be reached     |
+//! | 4    | scan interrupted (ctrl+c / --time-limit) |
+//! | 5    | --expect-found/--expect-absent violated  |
+use std::fmt;
+
+use anyhow::Error;
+
+/// Outcome of a feroxbuster process, expressed as the code passed to `std::process::exit`
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ExitCode {
+    /// the scan completed and nothing was reported
+    NoFindings = 0,
+
+    /// the scan completed and at least one response or finding was reported
+    Findings = 1,
+
+    /// bad command line arguments, config file, wordlist, etc...
+    ConfigError = 2,
+
+    /// none of the given targets could be reached
+    Unreachable = 3,
+
+    /// the scan was cut short by ctrl+c or --time-limit
+    Interrupted = 4,
+
+    /// the scan completed, but a path given to --expect-found wasn't discovered, or a path given
+    /// to --expect-absent was
+    ExpectationsFailed = 5,
+}
+
+impl ExitCode {
+    /// integer value handed to the operating system
+    pub fn code(self) -> i32 {
+        self as i32
+    }
+
+    /// determine the exit code associated with the given error; errors that weren't explicitly
+    /// tagged with an [`ExitError`] are treated as configuration errors
+    pub fn from_error(error: &Error) -> Self {
+        error
+            .chain()
+            .find_map(|cause| cause.downcast_ref::<ExitError>())
+            .map(|exit_error| exit_error.code)
+            .unwrap_or(ExitCode::ConfigError)
+    }
+
+    /// translate a child process' exit code back into an `ExitCode`; anything unrecognized is
+    /// treated as a configuration error
+    pub fn from_code(code: i32) -> Self {
+        match code {
+            0 => ExitCode::NoFindings,
+            1 => ExitCode::Findings,
+            3 => ExitCode::Unreachable,
+            4 => ExitCode::Interrupted,
+            5 => ExitCode::ExpectationsFailed,
+            _ => ExitCode::ConfigError,
+        }
+    }
+
+    /// combine the exit codes of several scans (i.e. --parallel) into one; the first failure wins,
+    /// otherwise findings in any scan means findings overall
+    pub fn combine(codes: &[ExitCode]) -> Self {
+        codes
+            .iter()
+            .find(|code| !matches!(code, ExitCode::NoFindings | ExitCode::Findings))
+            .or_else(|| codes.iter().find(|code| **code == ExitCode::Findings))
+            .copied()
+            .unwrap_or(ExitCode::NoFindings)
+    }
+
+    /// the exit code for a scan that completed normally, based on whether anything was found
+    pub fn from_findings(found_something: bool) -> Self {
+        if found_something {
+            ExitCode::Findings
+        } else {
+            ExitCode::NoFindings
+        }
+    }
+}
+
+/// Error that carries the [`ExitCode`] the process should exit with
+#[derive(Debug)]
+pub struct ExitError {
+    /// code to exit with
+    code: ExitCode,
+
+    /// human readable description of what went wrong
+    message: String,
+}
+
+impl ExitError {
+    /// create a new `ExitError`
+    pub fn new(code: ExitCode, message: &str) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ExitError {
+    /// the error's message, as given to [`ExitError::new`]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for ExitError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::{anyhow, Context};
+
+    #[test]
+    /// each variant maps to its documented integer value
+    fn exit_codes_have_expected_values() {
+        assert_eq!(ExitCode::NoFindings.code(), 0);
+        assert_eq!(ExitCode::Findings.code(), 1);
+        assert_eq!(ExitCode::ConfigError.code(), 2);
+        assert_eq!(ExitCode::Unreachable.code(), 3);
+        assert_eq!(ExitCode::Interrupted.code(), 4);
+        assert_eq!(ExitCode::ExpectationsFailed.code(), 5);
+    }
+
+    #[test]
+    /// untagged errors are config errors, tagged errors keep their code, even behind context
+    fn exit_code_from_error_finds_tagged_errors() {
+        assert_eq!(
+            ExitCode::from_error(&anyhow!("bad wordlist")),
+            ExitCode::ConfigError
+        );
+
+        let unreachable: Result<(), Error> =
+            Err(ExitError::new(ExitCode::Unreachable, "no live targets").into());
+        let error = unreachable.context("Failed while scanning").unwrap_err();
+
+        assert_eq!(ExitCode::from_error(&error), ExitCode::Unreachable);
+        assert_eq!(
+            format!("{}", error.root_cause()),
+            "no live targets".to_string()
+        );
+    }
+
+    #[test]
+    /// child exit codes round trip, failures take precedence over findings when combined
+    fn exit_codes_combine_child_results() {
+        for code in 0..6 {
+            assert_eq!(ExitCode::from_code(code).code(), code);
+        }
+        assert_eq!(ExitCode::from_code(101), ExitCode::ConfigError);
+
+        assert_eq!(ExitCode::combine(&[]), ExitCode::NoFindings);
+        assert_eq!(
+            ExitCode::combine(&[ExitCode::NoFindings, ExitCode::Findings]),
+            ExitCode::Findings
+        );
+        assert_eq!(
+            ExitCode::combine(&[
+                ExitCode::Findings,
+                ExitCode::Unreachable,
+                ExitCode::Interrupted
+            ]),
+            ExitCode::Unreachable
+        );
+    }
+
+    #[test]
+    /// a completed scan exits with 1 only when something was found
+    fn exit_code_from_findings() {
+        assert_eq!(ExitCode::from_findings(true), ExitCode::Findings);
+        assert_eq!(ExitCode::from_findings(false), ExitCode::NoFindings);
+    }
+}
@@ -1,18 +1,59 @@
 use super::*;
 use crate::event_handlers::Handles;
 use anyhow::{bail, Result};
+use lazy_static::lazy_static;
 
 /// Regular expression used in [LinkFinder](https://github.com/GerbenJavado/LinkFinder)
 ///
 /// Incorporates change from this [Pull Request](https://github.com/GerbenJavado/LinkFinder/pull/66/files)
 pub(super) const LINKFINDER_REGEX: &str = r#"(?:"|')(((?:[a-zA-Z]{1,10}://|//)[^"'/]{1,}\.[a-zA-Z]{2,}[^"']{0,})|((?:/|\.\./|\./)[^"'><,;| *()(%%$^/\\\[\]][^"'><,;|()]{1,})|([a-zA-Z0-9_\-/]{1,}/[a-zA-Z0-9_\-/]{1,}\.(?:[a-zA-Z]{1,4}|action)(?:[\?|#][^"|']{0,}|))|([a-zA-Z0-9_\-/]{1,}/[a-zA-Z0-9_\-/]{3,}(?:[\?|#][^"|']{0,}|))|([a-zA-Z0-9_\-.]{1,}\.(?:php|asp|aspx|jsp|json|action|html|js|txt|xml)(?:[\?|#][^"|']{0,}|)))(?:"|')"#;
 
+/// Regular expression used to find endpoints in javascript that LINKFINDER_REGEX misses
+///
+/// covers paths built with template literals (`/api/users/${id}`) and string arguments passed to
+/// common request helpers (fetch, axios, XMLHttpRequest.open, `url:` properties, etc)
+pub(super) const JS_ENDPOINT_REGEX: &str = r#"(?:`(?P<template>(?:/|\./|\.\./)[^`\s]+)`)|(?:(?:fetch|axios(?:\.[a-zA-Z]+)?|\.open|url\s*:|path\s*:|endpoint\s*:)\s*\(?\s*(?:["'][a-zA-Z]+["']\s*,\s*)?["'`](?P<call>[^"'`\s]+)["'`])"#;
+
 /// Regular expression to pull url paths from robots.txt
 ///
 /// ref: https://developers.google.com/search/reference/robots_txt
 pub(super) const ROBOTS_TXT_REGEX: &str =
     r#"(?m)^ *(Allow|Disallow): *(?P<url_path>[a-zA-Z0-9._/?#@!&'()+,;%=-]+?)$"#; // multi-line (?m)
 
+/// Regular expression used to determine whether or not a response is an auto-generated directory
+/// listing (apache, nginx, python's http.server, iis, etc)
+pub(super) const DIRECTORY_LISTING_REGEX: &str = r#"(?i)(?:<title>\s*(?:index of|directory listing for)\s+/|<h1>\s*index of\s+/|\[to parent directory\])"#;
+
+/// Regular expression to pull the target of every anchor tag out of a directory listing
+pub(super) const HREF_REGEX: &str = r#"(?i)<a\s[^>]*?href\s*=\s*["']?(?P<href>[^"'\s>]+)"#;
+
+lazy_static! {
+    /// `LINKFINDER_REGEX`, compiled once and shared by every `Extractor`
+    static ref LINKS: Regex = Regex::new(LINKFINDER_REGEX).expect("Could not compile regex");
+
+    /// `JS_ENDPOINT_REGEX`, compiled once and shared by every `Extractor`
+    static ref JS_ENDPOINTS: Regex =
+        Regex::new(JS_ENDPOINT_REGEX).expect("Could not compile regex");
+
+    /// `ROBOTS_TXT_REGEX`, compiled once and shared by every `Extractor`
+    static ref ROBOTS_TXT: Regex = Regex::new(ROBOTS_TXT_REGEX).expect("Could not compile regex");
+
+    /// `DIRECTORY_LISTING_REGEX`, compiled once and shared by every `Extractor`
+    static ref DIRECTORY_LISTING: Regex =
+        Regex::new(DIRECTORY_LISTING_REGEX).expect("Could not compile regex");
+
+    /// `HREF_REGEX`, compiled once and shared by every `Extractor`
+    static ref HREF: Regex = Regex::new(HREF_REGEX).expect("Could not compile regex");
+}
+
+/// archive.org's CDX API, used to look up historical urls for a given host
+///
+/// ref: https://github.com/internetarchive/wayback/tree/master/wayback-cdx-server
+pub(super) const WAYBACK_CDX_URL: &str = "https://web.archive.org/cdx/search/cdx";
+
+/// maximum number of (unique) urls requested from the CDX API per target
+pub(super) const WAYBACK_CDX_LIMIT: usize = 5000;
+
 /// Which type of extraction should be performed
 #[derive(Debug, Copy, Clone)]
 pub enum ExtractionTarget {
@@ -21,6 +62,18 @@ pub enum ExtractionTarget {
 
     /// Examine robots.txt (specifically) and extract links
     RobotsTxt,
+
+    /// Query the Wayback Machine for historical urls on the target's host
+    Wayback,
+
+    /// Read urls gathered by other recon tools (gau, katana, waybackurls, etc...) from --seed-file
+    SeedFile,
+
+    /// Examine a directory (specifically) for an auto-generated listing of its contents
+    DirectoryListing,
+
+    /// Probe well-known locations for OpenAPI/Swagger specs and extract documented paths
+    OpenApi,
 }
 
 /// responsible for building an `Extractor`
@@ -85,8 +138,12 @@ impl<'a> ExtractorBuilder<'a> {
         }
 
         Ok(Extractor {
-            links_regex: Regex::new(LINKFINDER_REGEX).unwrap(),
-            robots_regex: Regex::new(ROBOTS_TXT_REGEX).unwrap(),
+            // cloning a compiled regex is cheap, compiling one for every response isn't
+            links_regex: LINKS.clone(),
+            js_regex: JS_ENDPOINTS.clone(),
+            robots_regex: ROBOTS_TXT.clone(),
+            listing_regex: DIRECTORY_LISTING.clone(),
+            href_regex: HREF.clone(),
             response: if self.response.is_some() {
                 Some(self.response.unwrap())
             } else {
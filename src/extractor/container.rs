@@ -1,3 +1,5 @@
+use super::builder::{WAYBACK_CDX_LIMIT, WAYBACK_CDX_URL};
+use super::openapi::{self, OPENAPI_SPEC_PATHS};
 use super::*;
 use crate::utils::should_deny_url;
 use crate::{
@@ -16,8 +18,9 @@ use crate::{
     utils::{logged_request, make_request},
 };
 use anyhow::{bail, Context, Result};
-use reqwest::{StatusCode, Url};
-use std::collections::HashSet;
+use reqwest::{Client, Method, StatusCode, Url};
+use serde_json::Value;
+use std::{collections::HashSet, time::Instant};
 use tokio::sync::oneshot;
 
 /// Whether an active scan is recursive or not
@@ -36,9 +39,18 @@ pub struct Extractor<'a> {
     /// `LINKFINDER_REGEX` as a regex::Regex type
     pub(super) links_regex: Regex,
 
+    /// `JS_ENDPOINT_REGEX` as a regex::Regex type
+    pub(super) js_regex: Regex,
+
     /// `ROBOTS_TXT_REGEX` as a regex::Regex type
     pub(super) robots_regex: Regex,
 
+    /// `DIRECTORY_LISTING_REGEX` as a regex::Regex type
+    pub(super) listing_regex: Regex,
+
+    /// `HREF_REGEX` as a regex::Regex type
+    pub(super) href_regex: Regex,
+
     /// Response from which to extract links
     pub(super) response: Option<&'a FeroxResponse>,
 
@@ -60,6 +72,10 @@ impl<'a> Extractor<'a> {
         match self.target {
             ExtractionTarget::ResponseBody => Ok(self.extract_from_body().await?),
             ExtractionTarget::RobotsTxt => Ok(self.extract_from_robots().await?),
+            ExtractionTarget::Wayback => Ok(self.extract_from_wayback().await?),
+            ExtractionTarget::SeedFile => Ok(self.extract_from_seed_file().await?),
+            ExtractionTarget::DirectoryListing => Ok(self.extract_from_listing().await?),
+            ExtractionTarget::OpenApi => Ok(self.extract_from_openapi().await?),
         }
     }
 
@@ -75,12 +91,17 @@ impl<'a> Extractor<'a> {
 
         let scanned_urls = self.handles.ferox_scans()?;
 
+        // links pulled out of javascript are tagged as such when reported
+        let from_js = self.response.map_or(false, |r| r.is_javascript());
+
         for link in links {
             let mut resp = match self.request_link(&link).await {
                 Ok(resp) => resp,
                 Err(_) => continue,
             };
 
+            resp.set_extracted_from_js(from_js);
+
             // filter if necessary
             if self
                 .handles
@@ -149,39 +170,38 @@ impl<'a> Extractor<'a> {
 
         let body = self.response.unwrap().text();
 
+        // every link the regexes below can find is quoted; a body without any quotes (minified
+        // json, plain text, etc) can be skipped after a single vectorized pass over it
+        if memchr::memchr3(b'"', b'\'', b'`', body.as_bytes()).is_none() {
+            log::trace!("exit: get_links -> {:?}", links);
+            return Ok(links);
+        }
+
         for capture in self.links_regex.captures_iter(body) {
             // remove single & double quotes from both ends of the capture
             // capture[0] is the entire match, additional capture groups start at [1]
             let link = capture[0].trim_matches(|c| c == '\'' || c == '"');
 
-            match Url::parse(link) {
-                Ok(absolute) => {
-                    if absolute.domain() != self.response.unwrap().url().domain()
-                        || absolute.host() != self.response.unwrap().url().host()
-                    {
-                        // domains/ips are not the same, don't scan things that aren't part of the original
-                        // target url
-                        continue;
-                    }
+            self.add_extracted_link(link, &mut links);
+        }
 
-                    if self.add_all_sub_paths(absolute.path(), &mut links).is_err() {
-                        log::warn!("could not add sub-paths from {} to {:?}", absolute, links);
-                    }
-                }
-                Err(e) => {
-                    // this is the expected error that happens when we try to parse a url fragment
-                    //     ex: Url::parse("/login") -> Err("relative URL without a base")
-                    // while this is technically an error, these are good results for us
-                    if e.to_string().contains("relative URL without a base") {
-                        if self.add_all_sub_paths(link, &mut links).is_err() {
-                            log::warn!("could not add sub-paths from {} to {:?}", link, links);
-                        }
-                    } else {
-                        // unexpected error has occurred
-                        log::warn!("Could not parse given url: {}", e);
-                        self.handles.stats.send(AddError(Other)).unwrap_or_default();
-                    }
+        if self.response.unwrap().is_javascript() {
+            // javascript gets an additional pass to catch endpoints that are built dynamically
+            for capture in self.js_regex.captures_iter(body) {
+                let raw = match capture.name("template").or_else(|| capture.name("call")) {
+                    Some(matched) => matched.as_str(),
+                    None => continue,
+                };
+
+                // template literals are only useful up to the first substitution, i.e.
+                // /api/users/${id}/profile -> /api/users/
+                let link = raw.split("${").next().unwrap_or_default();
+
+                if link.is_empty() || link == "/" {
+                    continue;
                 }
+
+                self.add_extracted_link(link, &mut links);
             }
         }
 
@@ -192,6 +212,54 @@ impl<'a> Extractor<'a> {
         Ok(links)
     }
 
+    /// Given a link extracted from a response body, add it (and all of its sub-paths) to `links`
+    ///
+    /// absolute urls are only added when they share the same domain/host as the response they
+    /// were found in, or their host is in --scope
+    fn add_extracted_link(&self, link: &str, links: &mut HashSet<String>) {
+        match Url::parse(link) {
+            Ok(absolute) => {
+                let found_on = self.response.unwrap().url();
+
+                let scope = &self.handles.config.request_context.scope;
+
+                if !scope.allows(&absolute, found_on) {
+                    // domains/ips are not the same, don't scan things that aren't part of the original
+                    // target url (or --scope)
+                    return;
+                }
+
+                if absolute.host() != found_on.host() {
+                    // --scope let in another host; its sub-paths are joined to its own root
+                    for sub_path in self.get_sub_paths_from_path(absolute.path()) {
+                        if let Ok(url) = absolute.join(&format!("/{}", sub_path)) {
+                            links.insert(url.to_string());
+                        }
+                    }
+                    return;
+                }
+
+                if self.add_all_sub_paths(absolute.path(), links).is_err() {
+                    log::warn!("could not add sub-paths from {} to {:?}", absolute, links);
+                }
+            }
+            Err(e) => {
+                // this is the expected error that happens when we try to parse a url fragment
+                //     ex: Url::parse("/login") -> Err("relative URL without a base")
+                // while this is technically an error, these are good results for us
+                if e.to_string().contains("relative URL without a base") {
+                    if self.add_all_sub_paths(link, links).is_err() {
+                        log::warn!("could not add sub-paths from {} to {:?}", link, links);
+                    }
+                } else {
+                    // unexpected error has occurred
+                    log::warn!("Could not parse given url: {}", e);
+                    self.handles.stats.send(AddError(Other)).unwrap_or_default();
+                }
+            }
+        }
+    }
+
     /// take a url fragment like homepage/assets/img/icons/handshake.svg and
     /// incrementally add
     ///     - homepage/assets/img/icons/
@@ -266,7 +334,11 @@ impl<'a> Extractor<'a> {
 
         let old_url = match self.target {
             ExtractionTarget::ResponseBody => self.response.unwrap().url().clone(),
-            ExtractionTarget::RobotsTxt => match Url::parse(&self.url) {
+            ExtractionTarget::RobotsTxt
+            | ExtractionTarget::Wayback
+            | ExtractionTarget::SeedFile
+            | ExtractionTarget::DirectoryListing
+            | ExtractionTarget::OpenApi => match Url::parse(&self.url) {
                 Ok(u) => u,
                 Err(e) => {
                     bail!("Could not parse {}: {}", self.url, e);
@@ -310,22 +382,36 @@ impl<'a> Extractor<'a> {
             bail!("previously seen url");
         }
 
-        if !self.handles.config.url_denylist.is_empty()
+        if (!self.handles.config.url_denylist.is_empty()
+            || !self.handles.config.deny_url_regex.is_empty())
             && should_deny_url(&new_url, self.handles.clone())?
         {
             // can't allow a denied url to be requested
             bail!(
-                "prevented request to {} due to {:?}",
+                "prevented request to {} due to {:?} {:?}",
                 url,
-                self.handles.config.url_denylist
+                self.handles.config.url_denylist,
+                self.handles.config.deny_url_regex
             );
         }
 
         // make the request and store the response
+        let timer = Instant::now();
         let new_response = logged_request(&new_url, self.handles.clone()).await?;
+        let response_time = timer.elapsed();
+
+        let mut new_ferox_response = FeroxResponse::from(
+            new_response,
+            true,
+            self.handles.config.max_response_size,
+            self.handles.config.output_level,
+        )
+        .await;
+        new_ferox_response.set_redirect_chain(client::take_redirect_chain(&new_url));
 
-        let new_ferox_response =
-            FeroxResponse::from(new_response, true, self.handles.config.output_level).await;
+        if self.handles.config.response_time {
+            new_ferox_response.set_response_time(response_time);
+        }
 
         log::trace!("exit: request_link -> {:?}", new_ferox_response);
 
@@ -374,9 +460,44 @@ impl<'a> Extractor<'a> {
         log::trace!("enter: get_robots_file");
 
         // more often than not, domain/robots.txt will redirect to www.domain/robots.txt or something
-        // similar; to account for that, create a client that will follow redirects, regardless of
-        // what the user specified for the scanning client. Other than redirects, it will respect
-        // all other user specified settings
+        // similar; to account for that, use a client that will follow redirects
+        let client = self.redirect_following_client()?;
+
+        let mut url = Url::parse(&self.url)?;
+        url.set_path("/robots.txt"); // overwrite existing path with /robots.txt
+
+        if self.handles.denied_by_regex(&url) {
+            bail!("prevented request to {} due to --deny-url-regex", url);
+        }
+
+        // purposefully not using logged_request here due to using the special client
+        let response = make_request(
+            &client,
+            &Method::GET,
+            &url,
+            None,
+            &self.handles.config,
+            &self.handles.session,
+            self.handles.stats.tx.clone(),
+        )
+        .await?;
+
+        let mut ferox_response = FeroxResponse::from(
+            response,
+            true,
+            self.handles.config.max_response_size,
+            self.handles.config.output_level,
+        )
+        .await;
+        ferox_response.set_redirect_chain(client::take_redirect_chain(&url));
+
+        log::trace!("exit: get_robots_file -> {}", ferox_response);
+        Ok(ferox_response)
+    }
+
+    /// create a client that will follow redirects, regardless of what the user specified for the
+    /// scanning client. Other than redirects, it will respect all other user specified settings
+    fn redirect_following_client(&self) -> Result<Client> {
         let follow_redirects = true;
 
         let proxy = if self.handles.config.proxy.is_empty() {
@@ -385,44 +506,313 @@ impl<'a> Extractor<'a> {
             Some(self.handles.config.proxy.as_str())
         };
 
-        let client = client::initialize(
+        client::initialize(
             self.handles.config.timeout,
             &self.handles.config.user_agent,
             follow_redirects,
             self.handles.config.insecure,
             &self.handles.config.headers,
             proxy,
+            &self.handles.config.request_context,
+        )
+    }
+
+    /// Entry point to seed a scan with historical urls from the Wayback Machine
+    ///
+    /// every url archive.org knows about on the target's host is normalized down to its path, and
+    /// each path (along with its sub-paths) is returned to be requested
+    pub(super) async fn extract_from_wayback(&self) -> Result<HashSet<String>> {
+        log::trace!("enter: extract_from_wayback");
+
+        let mut links: HashSet<String> = HashSet::new();
+
+        let target = Url::parse(&self.url)?;
+
+        let response = self.request_wayback_cdx(&target).await?;
+
+        for path in self.normalize_seed_urls(response.text(), &target) {
+            if self.add_all_sub_paths(&path, &mut links).is_err() {
+                log::warn!("could not add sub-paths from {} to {:?}", path, links);
+            }
+        }
+
+        self.update_stats(links.len())?;
+
+        log::trace!("exit: extract_from_wayback -> {:?}", links);
+        Ok(links)
+    }
+
+    /// Entry point to seed a scan with urls gathered by other recon tools (--seed-file)
+    ///
+    /// the file holds one url per line (gau, katana, waybackurls, etc... output); every url that
+    /// shares a host with the target is normalized down to its path, and each path (along with its
+    /// sub-paths) other than the target itself is returned to be requested
+    pub(super) async fn extract_from_seed_file(&self) -> Result<HashSet<String>> {
+        log::trace!("enter: extract_from_seed_file");
+
+        let mut links: HashSet<String> = HashSet::new();
+
+        let target = Url::parse(&self.url)?;
+
+        let contents = tokio::fs::read_to_string(&self.handles.config.seed_file)
+            .await
+            .with_context(|| format!("Could not read {}", self.handles.config.seed_file))?;
+
+        for path in self.normalize_seed_urls(&contents, &target) {
+            for sub_path in self.get_sub_paths_from_path(&path) {
+                // seeded paths are absolute, they're not relative to the target's directory
+                if self
+                    .add_link_to_set_of_links(&format!("/{}", sub_path), &mut links)
+                    .is_err()
+                {
+                    log::warn!("could not add {} to {:?}", sub_path, links);
+                }
+            }
+        }
+
+        // the target is already being scanned, no need to request it again
+        let normalized_target = FeroxUrl::from_string(&self.url, self.handles.clone()).normalize();
+
+        links.retain(|link| {
+            FeroxUrl::from_string(link, self.handles.clone()).normalize() != normalized_target
+        });
+
+        self.update_stats(links.len())?;
+
+        log::trace!("exit: extract_from_seed_file -> {:?}", links);
+        Ok(links)
+    }
+
+    /// query archive.org's CDX API for all urls it has archived on the given url's host
+    ///
+    /// example:
+    ///     http://localhost/api -> CDX query for localhost/*
+    pub(super) async fn request_wayback_cdx(&self, target: &Url) -> Result<FeroxResponse> {
+        log::trace!("enter: request_wayback_cdx({})", target);
+
+        let host = target
+            .host_str()
+            .with_context(|| format!("Could not determine host of {}", target))?;
+
+        let limit = WAYBACK_CDX_LIMIT.to_string();
+
+        let url = Url::parse_with_params(
+            WAYBACK_CDX_URL,
+            &[
+                ("url", format!("{}/*", host).as_str()),
+                ("output", "text"),
+                ("fl", "original"),
+                ("collapse", "urlkey"),
+                ("limit", limit.as_str()),
+            ],
         )?;
 
-        let mut url = Url::parse(&self.url)?;
-        url.set_path("/robots.txt"); // overwrite existing path with /robots.txt
+        // purposefully not using logged_request or make_request here; the request isn't made to
+        // the target, so it's sent without the -H headers, session, and scan id
+        let response = self
+            .handles
+            .config
+            .third_party_client()?
+            .get(url)
+            .send()
+            .await?;
+
+        let ferox_response = FeroxResponse::from(
+            response,
+            true,
+            self.handles.config.max_response_size,
+            self.handles.config.output_level,
+        )
+        .await;
 
-        // purposefully not using logged_request here due to using the special client
-        let response = make_request(
-            &client,
-            &url,
+        log::trace!("exit: request_wayback_cdx -> {}", ferox_response);
+        Ok(ferox_response)
+    }
+
+    /// given the body of a CDX response or the contents of a --seed-file (one url per line),
+    /// return the unique, sorted paths of all urls that share a host with `target`
+    ///
+    /// query strings and fragments are dropped, as are urls that point to the root of the site
+    pub(super) fn normalize_seed_urls(&self, body: &str, target: &Url) -> Vec<String> {
+        log::trace!("enter: normalize_seed_urls({}, {})", body.len(), target);
+
+        let mut paths = HashSet::new();
+
+        for line in body.lines() {
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            // archived urls are frequently missing a scheme, i.e. example.com:80/admin
+            let parsed = match Url::parse(line) {
+                Ok(url) if url.has_host() => url,
+                _ => match Url::parse(&format!("http://{}", line)) {
+                    Ok(url) => url,
+                    Err(_) => continue,
+                },
+            };
+
+            let same_host = parsed.host_str().map(str::to_ascii_lowercase)
+                == target.host_str().map(str::to_ascii_lowercase);
+
+            if !same_host || parsed.path() == "/" || parsed.path().is_empty() {
+                continue;
+            }
+
+            paths.insert(parsed.path().to_string());
+        }
+
+        let mut paths: Vec<String> = paths.into_iter().collect();
+        paths.sort();
+
+        log::trace!("exit: normalize_seed_urls -> {:?}", paths);
+        paths
+    }
+
+    /// Entry point to perform link extraction from an auto-generated directory listing
+    ///
+    /// the directory is requested and, if the response looks like an index page, every file and
+    /// subdirectory listed beneath it is returned; an empty set is returned otherwise
+    ///
+    /// given the url:
+    ///     http://localhost/stuff
+    /// this function requests:
+    ///     http://localhost/stuff/
+    pub(super) async fn extract_from_listing(&self) -> Result<HashSet<String>> {
+        log::trace!("enter: extract_from_listing");
+
+        let mut links: HashSet<String> = HashSet::new();
+
+        let mut directory = Url::parse(&self.url)?;
+
+        if !directory.path().ends_with('/') {
+            directory.set_path(&format!("{}/", directory.path()));
+        }
+
+        let response = logged_request(&directory, self.handles.clone()).await?;
+
+        let mut ferox_response = FeroxResponse::from(
+            response,
+            true,
+            self.handles.config.max_response_size,
             self.handles.config.output_level,
-            self.handles.stats.tx.clone(),
         )
-        .await?;
+        .await;
+        ferox_response.set_redirect_chain(client::take_redirect_chain(&directory));
 
-        let ferox_response =
-            FeroxResponse::from(response, true, self.handles.config.output_level).await;
+        if !ferox_response.status().is_success()
+            || !self.listing_regex.is_match(ferox_response.text())
+        {
+            log::trace!("exit: extract_from_listing -> {:?}", links);
+            return Ok(links);
+        }
 
-        log::trace!("exit: get_robots_file -> {}", ferox_response);
-        Ok(ferox_response)
+        for capture in self.href_regex.captures_iter(ferox_response.text()) {
+            let href = match capture.name("href") {
+                Some(href) => href.as_str(),
+                None => continue,
+            };
+
+            if href.starts_with('?') || href.starts_with('#') {
+                // column sorting links (?C=N;O=D) and anchors
+                continue;
+            }
+
+            let mut listed = match directory.join(href) {
+                Ok(url) => url,
+                Err(_) => continue,
+            };
+
+            // only direct contents of the listed directory are wanted; this discards links to
+            // parent directories (../) as well as other sites
+            if listed.origin() != directory.origin()
+                || !listed.path().starts_with(directory.path())
+                || listed.path() == directory.path()
+            {
+                continue;
+            }
+
+            listed.set_query(None);
+            listed.set_fragment(None);
+
+            links.insert(listed.to_string());
+        }
+
+        self.update_stats(links.len())?;
+
+        log::trace!("exit: extract_from_listing -> {:?}", links);
+        Ok(links)
+    }
+
+    /// Entry point to perform link extraction from OpenAPI/Swagger specifications
+    ///
+    /// each of the well-known spec locations is requested from the root of the target; every
+    /// documented path found is reported as a finding (with its methods and parameters) and the
+    /// spec itself, along with the requestable portion of each path, is returned
+    pub(super) async fn extract_from_openapi(&self) -> Result<HashSet<String>> {
+        log::trace!("enter: extract_from_openapi");
+
+        let mut links: HashSet<String> = HashSet::new();
+
+        let target = Url::parse(&self.url)?;
+
+        for spec_path in OPENAPI_SPEC_PATHS.iter() {
+            let mut spec_url = target.clone();
+            spec_url.set_path(spec_path);
+            spec_url.set_query(None);
+
+            let response = match logged_request(&spec_url, self.handles.clone()).await {
+                Ok(response) if response.status().is_success() => response,
+                _ => continue,
+            };
+
+            let spec: Value = match serde_json::from_str(&response.text().await?) {
+                Ok(spec) => spec,
+                Err(_) => continue, // not json, can't be a spec
+            };
+
+            let endpoints = openapi::parse_spec(&spec);
+
+            if endpoints.is_empty() {
+                continue;
+            }
+
+            links.insert(spec_url.to_string());
+
+            for endpoint in endpoints {
+                self.handles.output.send(Command::ReportFinding(Box::new(
+                    endpoint.as_finding(spec_url.as_str()),
+                )))?;
+
+                let requestable = endpoint.requestable_path();
+
+                if requestable.is_empty() || requestable == "/" {
+                    continue;
+                }
+
+                if let Ok(endpoint_url) = spec_url.join(requestable) {
+                    links.insert(endpoint_url.to_string());
+                }
+            }
+        }
+
+        self.update_stats(links.len())?;
+
+        log::trace!("exit: extract_from_openapi -> {:?}", links);
+        Ok(links)
     }
 
     /// update total number of links extracted and expected responses
     fn update_stats(&self, num_links: usize) -> Result<()> {
-        let multiplier = self.handles.config.extensions.len().max(1);
-
         self.handles
             .stats
             .send(AddToUsizeField(LinksExtracted, num_links))?;
+        // each link is requested as it is, extensions aren't added to it
         self.handles
             .stats
-            .send(AddToUsizeField(TotalExpected, num_links * multiplier))?;
+            .send(AddToUsizeField(TotalExpected, num_links))?;
 
         Ok(())
     }
@@ -1,6 +1,8 @@
-//! extract links from html source and robots.txt
+//! extract links from html source, robots.txt, directory listings, OpenAPI specs, the Wayback
+//! Machine, and url lists produced by other tools
 mod builder;
 mod container;
+mod openapi;
 #[cfg(test)]
 mod tests;
 
@@ -0,0 +1,225 @@
+use reqwest::Url;
+use serde_json::{json, Value};
+
+use crate::finding::FeroxFinding;
+
+/// Well-known locations of OpenAPI/Swagger specifications, relative to the root of a target
+pub(super) const OPENAPI_SPEC_PATHS: [&str; 7] = [
+    "/swagger.json",
+    "/openapi.json",
+    "/v2/api-docs",
+    "/v3/api-docs",
+    "/api-docs",
+    "/swagger/v1/swagger.json",
+    "/api/swagger.json",
+];
+
+/// http methods that may appear as keys of an OpenAPI path item
+const HTTP_METHODS: [&str; 8] = [
+    "get", "put", "post", "delete", "options", "head", "patch", "trace",
+];
+
+/// A single endpoint documented in an OpenAPI/Swagger specification
+#[derive(Debug, Default, Clone, PartialEq)]
+pub(super) struct ApiEndpoint {
+    /// documented path, prefixed with the spec's base path, i.e. `/api/users/{id}`
+    pub(super) path: String,
+
+    /// uppercased http methods documented for the path
+    pub(super) methods: Vec<String>,
+
+    /// names of all documented parameters (path, query, header, body, etc)
+    pub(super) parameters: Vec<String>,
+}
+
+/// ApiEndpoint implementation
+impl ApiEndpoint {
+    /// the portion of the path that can be requested without knowing any parameter values
+    ///
+    /// ex: `/api/users/{id}/profile` -> `/api/users/`
+    pub(super) fn requestable_path(&self) -> &str {
+        self.path.split('{').next().unwrap_or_default()
+    }
+
+    /// convert the endpoint into a `FeroxFinding`, found in the spec at `spec_url`
+    pub(super) fn as_finding(&self, spec_url: &str) -> FeroxFinding {
+        let summary = format!(
+            "{} {} [{}]",
+            self.methods.join(","),
+            self.path,
+            self.parameters.join(", ")
+        );
+
+        FeroxFinding::new(
+            "openapi",
+            spec_url,
+            &summary,
+            json!({
+                "path": self.path,
+                "methods": self.methods,
+                "parameters": self.parameters,
+            }),
+        )
+    }
+}
+
+/// determine the path prefix that all documented paths are relative to
+///
+/// swagger 2.0 uses `basePath`; openapi 3.x uses the first entry in `servers`, which may be an
+/// absolute url or a path
+fn base_path(spec: &Value) -> String {
+    let base = if let Some(base) = spec["basePath"].as_str() {
+        base.to_string()
+    } else if let Some(server) = spec["servers"][0]["url"].as_str() {
+        match Url::parse(server) {
+            Ok(url) => url.path().to_string(),
+            Err(_) => server.to_string(),
+        }
+    } else {
+        String::new()
+    };
+
+    base.trim_end_matches('/').to_string()
+}
+
+/// add the names of all parameters in the given list to `names`, ignoring duplicates
+fn add_parameter_names(parameters: &Value, names: &mut Vec<String>) {
+    if let Some(parameters) = parameters.as_array() {
+        for name in parameters.iter().filter_map(|p| p["name"].as_str()) {
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_string());
+            }
+        }
+    }
+}
+
+/// add the names of all top-level request body properties (openapi 3.x) to `names`, ignoring
+/// duplicates
+fn add_request_body_names(operation: &Value, names: &mut Vec<String>) {
+    if let Some(content) = operation["requestBody"]["content"].as_object() {
+        for media_type in content.values() {
+            if let Some(properties) = media_type["schema"]["properties"].as_object() {
+                for name in properties.keys() {
+                    if !names.iter().any(|n| n == name) {
+                        names.push(name.to_string());
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Parse every documented path out of the given OpenAPI/Swagger specification
+///
+/// returns an empty vector when `spec` doesn't look like a specification
+pub(super) fn parse_spec(spec: &Value) -> Vec<ApiEndpoint> {
+    log::trace!("enter: parse_spec");
+
+    let mut endpoints = Vec::new();
+
+    if spec["swagger"].is_null() && spec["openapi"].is_null() {
+        log::trace!("exit: parse_spec -> {:?}", endpoints);
+        return endpoints;
+    }
+
+    let base = base_path(spec);
+
+    if let Some(paths) = spec["paths"].as_object() {
+        for (path, item) in paths {
+            let mut endpoint = ApiEndpoint {
+                path: format!("{}/{}", base, path.trim_start_matches('/')),
+                ..Default::default()
+            };
+
+            // parameters defined on the path item apply to every operation beneath it
+            add_parameter_names(&item["parameters"], &mut endpoint.parameters);
+
+            for method in HTTP_METHODS.iter() {
+                let operation = &item[*method];
+
+                if operation.is_null() {
+                    continue;
+                }
+
+                endpoint.methods.push(method.to_uppercase());
+                add_parameter_names(&operation["parameters"], &mut endpoint.parameters);
+                add_request_body_names(operation, &mut endpoint.parameters);
+            }
+
+            endpoints.push(endpoint);
+        }
+    }
+
+    log::trace!("exit: parse_spec -> {:?}", endpoints);
+    endpoints
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// swagger 2.0 specs use basePath and may define parameters on the path item
+    fn parse_spec_handles_swagger_2() {
+        let spec = json!({
+            "swagger": "2.0",
+            "basePath": "/api/",
+            "paths": {
+                "/users/{id}": {
+                    "parameters": [{"name": "id", "in": "path"}],
+                    "get": {"parameters": [{"name": "fields", "in": "query"}]},
+                    "delete": {}
+                }
+            }
+        });
+
+        let endpoints = parse_spec(&spec);
+
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].path, "/api/users/{id}");
+        assert_eq!(endpoints[0].methods, vec!["GET", "DELETE"]);
+        assert_eq!(endpoints[0].parameters, vec!["id", "fields"]);
+        assert_eq!(endpoints[0].requestable_path(), "/api/users/");
+    }
+
+    #[test]
+    /// openapi 3.x specs use servers and may document request bodies
+    fn parse_spec_handles_openapi_3() {
+        let spec = json!({
+            "openapi": "3.0.1",
+            "servers": [{"url": "https://example.com/v1"}],
+            "paths": {
+                "/login": {
+                    "post": {
+                        "requestBody": {
+                            "content": {
+                                "application/json": {
+                                    "schema": {"properties": {"username": {}, "password": {}}}
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        });
+
+        let endpoints = parse_spec(&spec);
+
+        assert_eq!(endpoints.len(), 1);
+        assert_eq!(endpoints[0].path, "/v1/login");
+        assert_eq!(endpoints[0].methods, vec!["POST"]);
+        assert_eq!(endpoints[0].parameters, vec!["password", "username"]);
+        assert_eq!(endpoints[0].requestable_path(), "/v1/login");
+
+        let finding = endpoints[0].as_finding("https://example.com/openapi.json");
+        assert_eq!(finding.category(), "openapi");
+        assert_eq!(finding.details()["methods"], json!(["POST"]));
+    }
+
+    #[test]
+    /// json that isn't a specification produces no endpoints
+    fn parse_spec_ignores_other_json() {
+        let spec = json!({"paths": {"/not/a/spec": {"get": {}}}});
+        assert!(parse_spec(&spec).is_empty());
+    }
+}
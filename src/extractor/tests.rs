@@ -1,6 +1,9 @@
-use super::builder::{LINKFINDER_REGEX, ROBOTS_TXT_REGEX};
+use super::builder::{
+    DIRECTORY_LISTING_REGEX, HREF_REGEX, JS_ENDPOINT_REGEX, LINKFINDER_REGEX, ROBOTS_TXT_REGEX,
+};
 use super::*;
 use crate::config::{Configuration, OutputLevel};
+use crate::login::Session;
 use crate::scan_manager::ScanOrder;
 use crate::{
     event_handlers::Handles, scan_manager::FeroxScans, utils::make_request, Command, FeroxChannel,
@@ -8,7 +11,7 @@ use crate::{
 use anyhow::Result;
 use httpmock::{Method::GET, MockServer};
 use lazy_static::lazy_static;
-use reqwest::{Client, StatusCode, Url};
+use reqwest::{Client, Method, StatusCode, Url};
 use std::collections::HashSet;
 use tokio::sync::mpsc;
 
@@ -41,6 +44,18 @@ fn setup_extractor(target: ExtractionTarget, scanned_urls: Arc<FeroxScans>) -> E
         ExtractionTarget::RobotsTxt => builder
             .url("http://localhost")
             .target(ExtractionTarget::RobotsTxt),
+        ExtractionTarget::Wayback => builder
+            .url("http://localhost")
+            .target(ExtractionTarget::Wayback),
+        ExtractionTarget::SeedFile => builder
+            .url("http://localhost")
+            .target(ExtractionTarget::SeedFile),
+        ExtractionTarget::DirectoryListing => builder
+            .url("http://localhost")
+            .target(ExtractionTarget::DirectoryListing),
+        ExtractionTarget::OpenApi => builder
+            .url("http://localhost")
+            .target(ExtractionTarget::OpenApi),
     };
 
     let config = Arc::new(Configuration::new().unwrap());
@@ -183,6 +198,39 @@ fn extractor_add_link_to_set_of_links_happy_path() {
     assert!(b_links.contains("http://localhost/shmadmin"));
 }
 
+#[test]
+/// cdx results are reduced to unique paths on the target's host; other hosts, root urls, query
+/// strings, and fragments are all discarded
+fn extractor_normalize_seed_urls_keeps_unique_paths_on_target_host() {
+    let wayback = setup_extractor(ExtractionTarget::Wayback, Arc::new(FeroxScans::default()));
+    let target = Url::parse("http://localhost/").unwrap();
+
+    let body = "http://localhost/\n\
+                https://localhost/admin/login.php?next=/\n\
+                localhost:80/admin/login.php#top\n\
+                http://LOCALHOST/api/v1/users\n\
+                http://sub.localhost/not-mine\n\
+                https://example.com/also-not-mine\n\
+                \n";
+
+    let paths = wayback.normalize_seed_urls(body, &target);
+
+    assert_eq!(paths, vec!["/admin/login.php", "/api/v1/users"]);
+}
+
+#[test]
+/// wayback extraction resolves paths against the target url, just like robots.txt
+fn extractor_wayback_add_link_to_set_of_links() {
+    let wayback = setup_extractor(ExtractionTarget::Wayback, Arc::new(FeroxScans::default()));
+    let mut links = HashSet::<String>::new();
+
+    wayback
+        .add_link_to_set_of_links("admin/", &mut links)
+        .unwrap();
+
+    assert!(links.contains("http://localhost/admin/"));
+}
+
 #[test]
 /// test that an invalid path fragment doesn't add anything to the set of links
 fn extractor_add_link_to_set_of_links_with_non_base_url() {
@@ -218,17 +266,28 @@ async fn extractor_get_links_with_absolute_url_that_differs_from_target_domain()
     let client = Client::new();
     let url = Url::parse(&srv.url("/some-path")).unwrap();
 
-    let response = make_request(&client, &url, OutputLevel::Default, tx_stats.clone())
-        .await
-        .unwrap();
+    let response = make_request(
+        &client,
+        &Method::GET,
+        &url,
+        None,
+        &Configuration::default(),
+        &Session::default(),
+        tx_stats.clone(),
+    )
+    .await
+    .unwrap();
     let (handles, _rx) = Handles::for_testing(None, None);
 
     let handles = Arc::new(handles);
-    let ferox_response = FeroxResponse::from(response, true, OutputLevel::Default).await;
+    let ferox_response = FeroxResponse::from(response, true, 0, OutputLevel::Default).await;
 
     let extractor = Extractor {
         links_regex: Regex::new(LINKFINDER_REGEX).unwrap(),
+        js_regex: Regex::new(JS_ENDPOINT_REGEX).unwrap(),
         robots_regex: Regex::new(ROBOTS_TXT_REGEX).unwrap(),
+        listing_regex: Regex::new(DIRECTORY_LISTING_REGEX).unwrap(),
+        href_regex: Regex::new(HREF_REGEX).unwrap(),
         response: Some(&ferox_response),
         url: String::new(),
         target: ExtractionTarget::ResponseBody,
@@ -242,6 +301,64 @@ async fn extractor_get_links_with_absolute_url_that_differs_from_target_domain()
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// use make_request to generate a javascript Response, and use the Response to test get_links;
+/// expect endpoints built via template literals and request helpers to be extracted
+async fn extractor_get_links_from_javascript_endpoints() -> Result<()> {
+    let (tx_stats, _): FeroxChannel<Command> = mpsc::unbounded_channel();
+
+    let srv = MockServer::start();
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/app.js");
+        then.status(200)
+            .header("Content-Type", "application/javascript")
+            .body("const u = `/api/users/${id}/profile`; axios.post(`/api/login`); xhr.open(\"GET\", \"/api/v2/items\");");
+    });
+
+    let client = Client::new();
+    let url = Url::parse(&srv.url("/app.js")).unwrap();
+
+    let response = make_request(
+        &client,
+        &Method::GET,
+        &url,
+        None,
+        &Configuration::default(),
+        &Session::default(),
+        tx_stats.clone(),
+    )
+    .await
+    .unwrap();
+    let (handles, _rx) = Handles::for_testing(None, None);
+
+    let handles = Arc::new(handles);
+    let ferox_response = FeroxResponse::from(response, true, 0, OutputLevel::Default).await;
+
+    assert!(ferox_response.is_javascript());
+
+    let extractor = Extractor {
+        links_regex: Regex::new(LINKFINDER_REGEX).unwrap(),
+        js_regex: Regex::new(JS_ENDPOINT_REGEX).unwrap(),
+        robots_regex: Regex::new(ROBOTS_TXT_REGEX).unwrap(),
+        listing_regex: Regex::new(DIRECTORY_LISTING_REGEX).unwrap(),
+        href_regex: Regex::new(HREF_REGEX).unwrap(),
+        response: Some(&ferox_response),
+        url: String::new(),
+        target: ExtractionTarget::ResponseBody,
+        handles: handles.clone(),
+    };
+
+    let links = extractor.extract_from_body().await?;
+
+    assert!(links.contains(&srv.url("/api/users")));
+    assert!(links.contains(&srv.url("/api/login")));
+    assert!(links.contains(&srv.url("/api/v2/items")));
+    assert!(!links.iter().any(|link| link.contains("${")));
+    assert_eq!(mock.hits(), 1);
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 /// test that /robots.txt is correctly requested given a base url (happy path)
 async fn request_robots_txt_without_proxy() -> Result<()> {
@@ -256,7 +373,10 @@ async fn request_robots_txt_without_proxy() -> Result<()> {
 
     let extractor = Extractor {
         links_regex: Regex::new(LINKFINDER_REGEX).unwrap(),
+        js_regex: Regex::new(JS_ENDPOINT_REGEX).unwrap(),
         robots_regex: Regex::new(ROBOTS_TXT_REGEX).unwrap(),
+        listing_regex: Regex::new(DIRECTORY_LISTING_REGEX).unwrap(),
+        href_regex: Regex::new(HREF_REGEX).unwrap(),
         response: None,
         url: srv.url("/api/users/stuff/things"),
         target: ExtractionTarget::RobotsTxt,
@@ -352,3 +472,158 @@ async fn request_link_bails_on_seen_url() -> Result<()> {
     assert_eq!(mock.hits(), 0); // function exits before requests can happen
     Ok(())
 }
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// an apache style index page should have all of its files and subdirectories extracted, while
+/// sorting links, parent directories, and other sites are ignored
+async fn extractor_extract_from_listing_returns_directory_contents() -> Result<()> {
+    let srv = MockServer::start();
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/files/");
+        then.status(200).body(
+            r#"<html><head><title>Index of /files</title></head><body><h1>Index of /files</h1>
+            <a href="?C=N;O=D">Name</a> <a href="?C=M;O=A">Last modified</a>
+            <a href="/">Parent Directory</a>
+            <a href="report.pdf">report.pdf</a>
+            <a href='backups/'>backups/</a>
+            <a href="http://some-other-site.com/files/">elsewhere</a>
+            </body></html>"#,
+        );
+    });
+
+    let config = Arc::new(Configuration::new().unwrap());
+    let (handles, _rx) = Handles::for_testing(None, Some(config));
+    let handles = Arc::new(handles);
+
+    let extractor = ExtractorBuilder::default()
+        .url(&srv.url("/files"))
+        .target(ExtractionTarget::DirectoryListing)
+        .handles(handles)
+        .build()?;
+
+    let links = extractor.extract().await?;
+
+    assert_eq!(mock.hits(), 1);
+    assert_eq!(links.len(), 2);
+    assert!(links.contains(&srv.url("/files/report.pdf")));
+    assert!(links.contains(&srv.url("/files/backups/")));
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// urls from a seed file are limited to the target's host, broken down into their sub-paths, and
+/// never include the target itself
+async fn extractor_extract_from_seed_file_returns_in_scope_paths() -> Result<()> {
+    let directory = tempfile::tempdir()?;
+    let seed_file = directory.path().join("urls.txt");
+
+    std::fs::write(
+        &seed_file,
+        "http://localhost/app\n\
+         http://localhost/app/admin/login.php?next=/\n\
+         https://example.com/elsewhere\n\
+         not a url at all\n",
+    )?;
+
+    let config = Arc::new(Configuration {
+        seed_file: seed_file.to_str().unwrap().to_string(),
+        ..Default::default()
+    });
+    let (handles, _rx) = Handles::for_testing(None, Some(config));
+
+    let extractor = ExtractorBuilder::default()
+        .url("http://localhost/app/")
+        .target(ExtractionTarget::SeedFile)
+        .handles(Arc::new(handles))
+        .build()?;
+
+    let links = extractor.extract().await?;
+
+    let expected: HashSet<String> = [
+        "http://localhost/app/admin/",
+        "http://localhost/app/admin/login.php",
+    ]
+    .iter()
+    .map(|link| link.to_string())
+    .collect();
+
+    assert_eq!(links, expected);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// a normal page isn't a directory listing, expect nothing to be extracted from it
+async fn extractor_extract_from_listing_ignores_normal_pages() -> Result<()> {
+    let srv = MockServer::start();
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/blog/");
+        then.status(200).body(
+            r#"<html><head><title>My Blog</title></head><a href="post-1.html">post</a></html>"#,
+        );
+    });
+
+    let config = Arc::new(Configuration::new().unwrap());
+    let (handles, _rx) = Handles::for_testing(None, Some(config));
+    let handles = Arc::new(handles);
+
+    let extractor = ExtractorBuilder::default()
+        .url(&srv.url("/blog/"))
+        .target(ExtractionTarget::DirectoryListing)
+        .handles(handles)
+        .build()?;
+
+    let links = extractor.extract().await?;
+
+    assert_eq!(mock.hits(), 1);
+    assert!(links.is_empty());
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// a swagger spec served from a well-known location should have its documented paths reported
+/// as findings and returned as links
+async fn extractor_extract_from_openapi_returns_documented_paths() -> Result<()> {
+    let srv = MockServer::start();
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/swagger.json");
+        then.status(200).body(
+            r#"{"swagger":"2.0","basePath":"/api","paths":{"/users/{id}":{"get":{"parameters":[{"name":"id"}]}},"/health":{"get":{}}}}"#,
+        );
+    });
+
+    let config = Arc::new(Configuration::new().unwrap());
+    let (handles, mut rx) = Handles::for_testing(None, Some(config));
+    let handles = Arc::new(handles);
+
+    let extractor = ExtractorBuilder::default()
+        .url(&srv.url("/some/dir/"))
+        .target(ExtractionTarget::OpenApi)
+        .handles(handles)
+        .build()?;
+
+    let links = extractor.extract().await?;
+
+    assert_eq!(mock.hits(), 1);
+    assert_eq!(links.len(), 3);
+    assert!(links.contains(&srv.url("/swagger.json")));
+    assert!(links.contains(&srv.url("/api/health")));
+    assert!(links.contains(&srv.url("/api/users/")));
+
+    let mut findings = Vec::new();
+
+    while let Ok(command) = rx.try_recv() {
+        if let Command::ReportFinding(finding) = command {
+            findings.push(finding);
+        }
+    }
+
+    assert_eq!(findings.len(), 2);
+    assert!(findings.iter().all(|f| f.category() == "openapi"));
+    assert!(findings
+        .iter()
+        .any(|f| f.details()["path"] == "/api/users/{id}"));
+    Ok(())
+}
@@ -0,0 +1,164 @@
+use super::*;
+use crate::{
+    config::OutputLevel,
+    numbers::NumberFormat,
+    progress::PROGRESS_PRINTER,
+    utils::{ferox_print, status_colorizer},
+};
+use console::style;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// (status code, word count, line count) tuple used to group similar responses together
+pub type ClusterKey = (u16, usize, usize);
+
+/// Bookkeeping for a single cluster of responses
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Cluster {
+    /// url of the first response seen that belongs to this cluster
+    pub representative: String,
+
+    /// total number of responses seen that belong to this cluster
+    pub count: usize,
+}
+
+/// Simple implementor of FeroxFilter; used to automatically mute groups of responses that share
+/// the same status code, word count, and line count once the group has been seen more than
+/// `threshold` times; specified using --cluster-threshold
+///
+/// templated error pages (custom 404s that return 200, generic 'access denied' pages, etc) tend to
+/// produce the same status/word/line triplet over and over; once a triplet is seen often enough,
+/// one representative is kept and the rest are muted
+#[derive(Debug, Default)]
+pub struct ClusterFilter {
+    /// number of responses allowed through per cluster before the cluster is muted
+    pub threshold: usize,
+
+    /// status codes that will be shown to the user; responses with any other status are never
+    /// counted, since they'll never be reported anyway
+    pub status_codes: Vec<u16>,
+
+    /// whether or not the user passed --silent|--quiet on the command line
+    pub output_level: OutputLevel,
+
+    /// formatting of the counts shown in the summary (i.e. --thousands-separator)
+    pub numbers: NumberFormat,
+
+    /// all clusters seen so far
+    clusters: Mutex<HashMap<ClusterKey, Cluster>>,
+}
+
+/// implementation of ClusterFilter
+impl ClusterFilter {
+    /// create a new ClusterFilter
+    pub fn new(threshold: usize, status_codes: &[u16], output_level: OutputLevel) -> Self {
+        Self {
+            threshold,
+            output_level,
+            status_codes: status_codes.to_vec(),
+            ..Default::default()
+        }
+    }
+
+    /// return all clusters that have exceeded the threshold, along with their keys
+    pub fn muted(&self) -> Vec<(ClusterKey, Cluster)> {
+        let mut muted = vec![];
+
+        if let Ok(guard) = self.clusters.lock() {
+            for (key, cluster) in guard.iter() {
+                if cluster.count > self.threshold {
+                    muted.push((*key, cluster.clone()));
+                }
+            }
+        }
+
+        muted.sort_by(|a, b| b.1.count.cmp(&a.1.count));
+        muted
+    }
+
+    /// display the final tally of all muted clusters to the user
+    pub fn print_summary(&self) {
+        if !matches!(self.output_level, OutputLevel::Default | OutputLevel::Quiet) {
+            return;
+        }
+
+        for ((status, words, lines), cluster) in self.muted() {
+            let msg = format!(
+                "{} {:>9} {:>9} {:>9} Muted {} responses similar to {} (status: {}, words: {}, lines: {})\n",
+                status_colorizer("CLU"),
+                "-",
+                "-",
+                "-",
+                style(self.numbers.count((cluster.count - self.threshold) as u64)).cyan(),
+                cluster.representative,
+                status,
+                words,
+                lines
+            );
+            ferox_print(&msg, &PROGRESS_PRINTER);
+        }
+    }
+}
+
+/// implementation of FeroxFilter for ClusterFilter
+impl FeroxFilter for ClusterFilter {
+    /// Add the response to its cluster, filtering it if the cluster has grown beyond `threshold`
+    fn should_filter_response(&self, response: &FeroxResponse) -> bool {
+        log::trace!("enter: should_filter_response({:?} {})", self, response);
+
+        let status = response.status().as_u16();
+
+        if self.threshold == 0 || !self.status_codes.contains(&status) {
+            log::trace!("exit: should_filter_response -> false");
+            return false;
+        }
+
+        let key = (status, response.word_count(), response.line_count());
+
+        let count = if let Ok(mut guard) = self.clusters.lock() {
+            let cluster = guard.entry(key).or_insert_with(|| Cluster {
+                representative: response.url().to_string(),
+                count: 0,
+            });
+            cluster.count += 1;
+
+            if cluster.count == self.threshold + 1
+                && matches!(self.output_level, OutputLevel::Default | OutputLevel::Quiet)
+            {
+                let msg = format!(
+                    "{} {:>9} {:>9} {:>9} Response cluster is noisy; {} responses similar to {} (seen more than {} times)\n",
+                    status_colorizer("CLU"),
+                    "-",
+                    "-",
+                    "-",
+                    style("auto-muting").yellow(),
+                    cluster.representative,
+                    style(self.threshold).cyan(),
+                );
+                ferox_print(&msg, &PROGRESS_PRINTER);
+            }
+
+            cluster.count
+        } else {
+            0
+        };
+
+        let result = count > self.threshold;
+
+        log::trace!("exit: should_filter_response -> {}", result);
+
+        result
+    }
+
+    /// Compare one ClusterFilter to another
+    fn box_eq(&self, other: &dyn Any) -> bool {
+        other
+            .downcast_ref::<Self>()
+            .map_or(false, |a| self.threshold == a.threshold)
+    }
+
+    /// Return self as Any for dynamic dispatch purposes
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
@@ -1,4 +1,4 @@
-use std::sync::Mutex;
+use std::sync::{Mutex, RwLock};
 
 use anyhow::Result;
 
@@ -8,13 +8,16 @@ use crate::{
     CommandSender,
 };
 
-use super::{FeroxFilter, WildcardFilter};
+use super::{BodyPatterns, ClusterFilter, FeroxFilter, RegexFilter, WildcardFilter};
 
 /// Container around a collection of `FeroxFilters`s
 #[derive(Debug, Default)]
 pub struct FeroxFilters {
     /// collection of `FeroxFilters`
     pub filters: Mutex<Vec<Box<dyn FeroxFilter>>>,
+
+    /// every `RegexFilter` in `filters`, combined so that bodies are only searched once
+    body_patterns: RwLock<BodyPatterns>,
 }
 
 /// implementation of FeroxFilter collection
@@ -26,20 +29,58 @@ impl FeroxFilters {
                 return Ok(());
             }
 
-            guard.push(filter)
+            let is_regex = filter.as_any().downcast_ref::<RegexFilter>().is_some();
+
+            guard.push(filter);
+
+            if is_regex {
+                let regex_filters: Vec<_> = guard
+                    .iter()
+                    .filter_map(|filter| filter.as_any().downcast_ref::<RegexFilter>())
+                    .collect();
+
+                if let Ok(mut patterns) = self.body_patterns.write() {
+                    *patterns = BodyPatterns::new(&regex_filters);
+                }
+            }
         }
         Ok(())
     }
 
     /// Simple helper to stay DRY; determines whether or not a given `FeroxResponse` should be reported
     /// to the user or not.
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(url = %response.url())))]
     pub fn should_filter_response(
         &self,
         response: &FeroxResponse,
         tx_stats: CommandSender,
     ) -> bool {
         if let Ok(filters) = self.filters.lock() {
+            let mut cluster_filter = None;
+            let mut searched_body = false;
+
             for filter in filters.iter() {
+                if filter.as_any().downcast_ref::<ClusterFilter>().is_some() {
+                    // clusters should only ever count responses that made it past every other
+                    // filter, so the cluster filter is saved for last
+                    cluster_filter = Some(filter);
+                    continue;
+                }
+
+                if filter.as_any().downcast_ref::<RegexFilter>().is_some() {
+                    // all regex filters are checked at once, in place of the first one
+                    if !searched_body {
+                        searched_body = true;
+
+                        if let Ok(patterns) = self.body_patterns.read() {
+                            if patterns.is_match(response.text()) {
+                                return true;
+                            }
+                        }
+                    }
+                    continue;
+                }
+
                 // wildcard.should_filter goes here
                 if filter.should_filter_response(response) {
                     if filter.as_any().downcast_ref::<WildcardFilter>().is_some() {
@@ -50,7 +91,22 @@ impl FeroxFilters {
                     return true;
                 }
             }
+
+            if let Some(filter) = cluster_filter {
+                return filter.should_filter_response(response);
+            }
         }
         false
     }
+
+    /// display a summary of any response clusters that were muted during the scan
+    pub fn print_cluster_summary(&self) {
+        if let Ok(filters) = self.filters.lock() {
+            for filter in filters.iter() {
+                if let Some(cluster_filter) = filter.as_any().downcast_ref::<ClusterFilter>() {
+                    cluster_filter.print_summary();
+                }
+            }
+        }
+    }
 }
@@ -1,8 +1,10 @@
 use super::{
-    LinesFilter, RegexFilter, SimilarityFilter, SizeFilter, StatusCodeFilter, WordsFilter,
+    ClusterFilter, LinesFilter, RegexFilter, SimilarityFilter, SizeFilter, StatusCodeFilter,
+    WordsFilter,
 };
 use crate::{
     event_handlers::Handles,
+    numbers::NumberFormat,
     response::FeroxResponse,
     skip_fail,
     utils::{fmt_err, logged_request},
@@ -75,7 +77,13 @@ pub async fn initialize(handles: Arc<Handles>) -> Result<()> {
         let resp = skip_fail!(logged_request(&url, handles.clone()).await);
 
         // if successful, create a filter based on the response's body
-        let fr = FeroxResponse::from(resp, true, handles.config.output_level).await;
+        let fr = FeroxResponse::from(
+            resp,
+            true,
+            handles.config.max_response_size,
+            handles.config.output_level,
+        )
+        .await;
 
         // hash the response body and store the resulting hash in the filter object
         let hash = FuzzyHash::new(&fr.text()).to_string();
@@ -89,6 +97,18 @@ pub async fn initialize(handles: Arc<Handles>) -> Result<()> {
         skip_fail!(handles.filters.send(AddFilter(boxed_filter)));
     }
 
+    // add the response clustering filter to filters handler's FeroxFilters (--cluster-threshold)
+    if handles.config.cluster_threshold > 0 {
+        let mut filter = ClusterFilter::new(
+            handles.config.cluster_threshold,
+            &handles.config.status_codes,
+            handles.config.output_level,
+        );
+        filter.numbers = NumberFormat::from_config(&handles.config);
+        let boxed_filter = Box::new(filter);
+        handles.filters.send(AddFilter(boxed_filter))?;
+    }
+
     handles.filters.sync().await?;
     Ok(())
 }
@@ -5,10 +5,11 @@ use std::fmt::Debug;
 use crate::response::FeroxResponse;
 use crate::traits::{FeroxFilter, FeroxSerialize};
 
+pub use self::cluster::{Cluster, ClusterFilter, ClusterKey};
 pub use self::container::FeroxFilters;
 pub use self::init::initialize;
 pub use self::lines::LinesFilter;
-pub use self::regex::RegexFilter;
+pub use self::regex::{BodyPatterns, RegexFilter};
 pub use self::similarity::SimilarityFilter;
 pub use self::size::SizeFilter;
 pub use self::status_code::StatusCodeFilter;
@@ -22,6 +23,7 @@ mod lines;
 mod size;
 mod regex;
 mod similarity;
+mod cluster;
 mod container;
 #[cfg(test)]
 mod tests;
@@ -1,5 +1,6 @@
 use super::*;
-use ::regex::Regex;
+use ::regex::{Regex, RegexSet};
+use aho_corasick::AhoCorasick;
 
 /// Simple implementor of FeroxFilter; used to filter out responses based on a given regular
 /// expression; specified using -X|--filter-regex
@@ -44,3 +45,68 @@ impl PartialEq for RegexFilter {
         self.raw_string == other.raw_string
     }
 }
+
+/// every `RegexFilter`'s expression combined, so that a response body is searched once, no
+/// matter how many of them are configured
+///
+/// expressions without any regex syntax (plain keywords) are matched with a single aho-corasick
+/// automaton, which searches with SIMD instructions where the cpu supports them; the rest are
+/// compiled into a single `RegexSet`
+#[derive(Debug, Default)]
+pub struct BodyPatterns {
+    /// automaton built from the expressions that are plain keywords
+    keywords: Option<AhoCorasick>,
+
+    /// every other expression
+    expressions: Option<RegexSet>,
+
+    /// expressions that couldn't be combined into a `RegexSet` (size limits), matched one by one
+    fallback: Vec<Regex>,
+}
+
+/// implementation of BodyPatterns
+impl BodyPatterns {
+    /// combine the given filters' expressions
+    pub fn new(filters: &[&RegexFilter]) -> Self {
+        let (keywords, expressions): (Vec<_>, Vec<_>) = filters
+            .iter()
+            .partition(|filter| ::regex::escape(&filter.raw_string) == filter.raw_string);
+
+        let mut patterns = Self::default();
+
+        if !keywords.is_empty() {
+            let keywords = keywords.iter().map(|filter| &filter.raw_string);
+            patterns.keywords = Some(AhoCorasick::new(keywords));
+        }
+
+        if !expressions.is_empty() {
+            match RegexSet::new(expressions.iter().map(|filter| &filter.raw_string)) {
+                Ok(set) => patterns.expressions = Some(set),
+                Err(e) => {
+                    log::warn!(
+                        "Could not combine regex filters, matching separately: {}",
+                        e
+                    );
+                    patterns.fallback = expressions
+                        .iter()
+                        .map(|filter| filter.compiled.clone())
+                        .collect();
+                }
+            }
+        }
+
+        patterns
+    }
+
+    /// whether any of the combined expressions match the given response body
+    pub fn is_match(&self, body: &str) -> bool {
+        self.keywords
+            .as_ref()
+            .map_or(false, |keywords| keywords.is_match(body))
+            || self
+                .expressions
+                .as_ref()
+                .map_or(false, |expressions| expressions.is_match(body))
+            || self.fallback.iter().any(|regex| regex.is_match(body))
+    }
+}
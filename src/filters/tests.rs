@@ -1,4 +1,5 @@
 use super::*;
+use crate::config::OutputLevel;
 use ::fuzzyhash::FuzzyHash;
 use ::regex::Regex;
 
@@ -163,6 +164,59 @@ fn regexfilter_should_filter_when_regex_matches_on_response_body() {
     assert!(filter.should_filter_response(&resp));
 }
 
+#[test]
+/// keywords and regular expressions are matched together, whichever kind the match comes from
+fn body_patterns_match_keywords_and_expressions() {
+    let filters: Vec<_> = ["Access Denied", r"session \d+ expired", "maintenance"]
+        .iter()
+        .map(|raw| RegexFilter {
+            raw_string: raw.to_string(),
+            compiled: Regex::new(raw).unwrap(),
+        })
+        .collect();
+    let filters: Vec<_> = filters.iter().collect();
+
+    let patterns = BodyPatterns::new(&filters);
+
+    assert!(patterns.is_match("<h1>Access Denied</h1>"));
+    assert!(patterns.is_match("your session 1234 expired, log in again"));
+    assert!(patterns.is_match("down for maintenance"));
+    assert!(!patterns.is_match("access denied"));
+    assert!(!patterns.is_match("your session expired"));
+    assert!(!BodyPatterns::default().is_match("anything at all"));
+}
+
+#[test]
+/// FeroxFilters should filter a response matched by any one of several regex filters, and only
+/// those responses
+fn ferox_filters_combine_regex_filters() {
+    let filters = FeroxFilters::default();
+    let (tx, _rx) = tokio::sync::mpsc::unbounded_channel();
+
+    for raw in ["Access Denied", r"error code: \d{3}"].iter() {
+        let filter = RegexFilter {
+            raw_string: raw.to_string(),
+            compiled: Regex::new(raw).unwrap(),
+        };
+        filters.push(Box::new(filter)).unwrap();
+    }
+    filters
+        .push(Box::new(StatusCodeFilter { filter_code: 500 }))
+        .unwrap();
+
+    let mut resp = FeroxResponse::default();
+    resp.set_url("http://localhost/stuff");
+
+    resp.set_text("oops, error code: 418");
+    assert!(filters.should_filter_response(&resp, tx.clone()));
+
+    resp.set_text("<title>Access Denied</title>");
+    assert!(filters.should_filter_response(&resp, tx.clone()));
+
+    resp.set_text("welcome home");
+    assert!(!filters.should_filter_response(&resp, tx));
+}
+
 #[test]
 /// a few simple tests for similarity filter
 fn similarity_filter_is_accurate() {
@@ -213,3 +267,62 @@ fn similarity_filter_as_any() {
         filter
     );
 }
+
+#[test]
+/// ClusterFilter should allow `threshold` responses through per cluster and mute the rest
+fn cluster_filter_mutes_clusters_over_threshold() {
+    let filter = ClusterFilter::new(2, &[200], OutputLevel::Silent);
+
+    let mut resp = FeroxResponse::default();
+    resp.set_url("http://localhost/stuff");
+    resp.set_text("im a templated error page");
+
+    assert!(!filter.should_filter_response(&resp));
+    assert!(!filter.should_filter_response(&resp));
+    assert!(filter.should_filter_response(&resp));
+
+    // different word/line counts belong to a different cluster
+    let mut other = FeroxResponse::default();
+    other.set_url("http://localhost/things");
+    other.set_text("im a real page\nwith more than one line");
+
+    assert!(!filter.should_filter_response(&other));
+
+    let muted = filter.muted();
+    assert_eq!(muted.len(), 1);
+    assert_eq!(muted[0].0, (200, 5, 1));
+    assert_eq!(muted[0].1.count, 3);
+    assert_eq!(muted[0].1.representative, "http://localhost/stuff");
+}
+
+#[test]
+/// ClusterFilter should ignore responses whose status code won't be reported anyway
+fn cluster_filter_ignores_unreported_status_codes() {
+    let filter = ClusterFilter::new(1, &[403], OutputLevel::Silent);
+
+    let mut resp = FeroxResponse::default();
+    resp.set_text("stuff");
+
+    for _ in 0..3 {
+        assert!(!filter.should_filter_response(&resp));
+    }
+
+    assert!(filter.muted().is_empty());
+}
+
+#[test]
+/// just a simple test to increase code coverage by hitting as_any and the inner value
+fn cluster_filter_as_any() {
+    let filter = ClusterFilter::new(5, &[200], OutputLevel::Default);
+    let filter2 = ClusterFilter::new(5, &[200], OutputLevel::Default);
+
+    assert!(filter.box_eq(filter2.as_any()));
+    assert_eq!(
+        filter
+            .as_any()
+            .downcast_ref::<ClusterFilter>()
+            .unwrap()
+            .threshold,
+        5
+    );
+}
@@ -0,0 +1,180 @@
+use anyhow::Context;
+use console::style;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::evidence::Exchange;
+use crate::response::FeroxResponse;
+use crate::traits::FeroxSerialize;
+use crate::utils::fmt_err;
+
+/// severities a finding can be rated with, from least to most severe
+pub const SEVERITIES: [&str; 5] = ["info", "low", "medium", "high", "critical"];
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// Representation of a single piece of structured data discovered while analyzing a response
+/// (html forms, comments, etc...); can be represented as a human readable string or JSON
+pub struct FeroxFinding {
+    #[serde(rename = "type")]
+    /// Name of this type of struct, used for serialization, i.e. `{"type":"finding"}`
+    pub(crate) kind: String,
+
+    /// The kind of finding, i.e. `form`
+    pub(crate) category: String,
+
+    /// The url of the response in which the finding was discovered
+    pub(crate) url: String,
+
+    /// Short, human readable description of the finding
+    pub(crate) summary: String,
+
+    /// Finding-specific structured data
+    pub(crate) details: Value,
+
+    /// The raw request and response the finding was made from, when requests are recorded
+    /// (--evidence-dir); never serialized
+    #[serde(skip)]
+    pub(crate) exchange: Option<Exchange>,
+}
+
+/// Implementation of FeroxFinding
+impl FeroxFinding {
+    /// Create a new FeroxFinding of the given category
+    pub fn new(category: &str, url: &str, summary: &str, details: Value) -> Self {
+        Self {
+            kind: "finding".to_string(),
+            category: category.to_string(),
+            url: url.to_string(),
+            summary: summary.to_string(),
+            details,
+            exchange: None,
+        }
+    }
+
+    /// The same finding, made from the given response; its exchange is what --evidence-dir saves
+    pub fn with_exchange(mut self, response: &FeroxResponse) -> Self {
+        self.exchange = Exchange::of(response);
+        self
+    }
+
+    /// getter for exchange
+    pub fn exchange(&self) -> Option<&Exchange> {
+        self.exchange.as_ref()
+    }
+
+    /// getter for category
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// getter for url
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// getter for summary
+    pub fn summary(&self) -> &str {
+        &self.summary
+    }
+
+    /// getter for details
+    pub fn details(&self) -> &Value {
+        &self.details
+    }
+
+    /// how severe the finding is, one of `SEVERITIES`; the analyzers that rate what they find
+    /// report a lowercase severity in the finding's details, and unrated secrets count as high
+    pub fn severity(&self) -> &str {
+        match self.details["severity"].as_str() {
+            Some(severity) if SEVERITIES.contains(&severity) => severity,
+            Some(_) => "info",
+            None if self.category == "secret" => "high",
+            None => "info",
+        }
+    }
+}
+
+/// Implementation of FeroxSerialize for FeroxFinding
+impl FeroxSerialize for FeroxFinding {
+    /// Create a string representation of the finding
+    ///
+    /// ex: FND      form https://localhost/login => POST https://localhost/auth [user, pass]
+    fn as_str(&self) -> String {
+        format!(
+            "{} {:>9} {} => {}\n",
+            style("FND").magenta(),
+            self.category,
+            self.url,
+            self.summary
+        )
+    }
+
+    /// Create an NDJSON representation of the finding
+    ///
+    /// (expanded for clarity)
+    /// ex:
+    /// {
+    ///   "type": "finding",
+    ///   "category": "form",
+    ///   "url": "https://localhost/login",
+    ///   "summary": "POST https://localhost/auth [user, pass]",
+    ///   "details": {
+    ///     "action": "https://localhost/auth",
+    ///     "method": "POST",
+    ///     "inputs": ["user", "pass"]
+    ///   }
+    /// }\n
+    fn as_json(&self) -> anyhow::Result<String> {
+        let mut json = serde_json::to_string(&self).with_context(|| {
+            fmt_err(&format!(
+                "Could not convert {} finding for {} to JSON",
+                self.category, self.url
+            ))
+        })?;
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    /// test as_str method of FeroxFinding
+    fn ferox_finding_as_str_returns_string_with_newline() {
+        let finding = FeroxFinding::new(
+            "form",
+            "http://localhost/login",
+            "POST http://localhost/auth [user]",
+            json!({}),
+        );
+        let finding_str = console::strip_ansi_codes(&finding.as_str()).to_string();
+
+        assert!(finding_str.starts_with("FND"));
+        assert!(finding_str.contains("form"));
+        assert!(finding_str.contains("http://localhost/login"));
+        assert!(finding_str.contains("POST http://localhost/auth [user]"));
+        assert!(finding_str.ends_with('\n'));
+    }
+
+    #[test]
+    /// test as_json method of FeroxFinding
+    fn ferox_finding_as_json_returns_json_representation_with_newline() {
+        let finding = FeroxFinding::new(
+            "form",
+            "http://localhost/login",
+            "POST http://localhost/auth [user]",
+            json!({"method": "POST", "inputs": ["user"]}),
+        );
+
+        let finding_str = finding.as_json().unwrap();
+        assert!(finding_str.ends_with('\n'));
+
+        let deserialized: FeroxFinding = serde_json::from_str(&finding_str).unwrap();
+        assert_eq!(deserialized, finding);
+        assert_eq!(deserialized.kind, "finding");
+        assert_eq!(deserialized.details()["method"], "POST");
+    }
+}
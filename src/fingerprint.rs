@@ -0,0 +1,553 @@
+//! identify the technologies (server, language, framework, cms) used by a target, based on
+//! Wappalyzer-style header/cookie/body rules
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{header::SET_COOKIE, Url};
+use serde_json::json;
+
+use crate::{
+    event_handlers::Handles, finding::FeroxFinding, response::FeroxResponse, url::FeroxUrl,
+    utils::logged_request,
+};
+
+/// number of characters per line of base64 output, mirrors python's `base64.encodebytes`
+const BASE64_LINE_LENGTH: usize = 76;
+
+/// The broad category a `Technology` falls into
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TechCategory {
+    /// web servers, i.e. nginx
+    Server,
+
+    /// server-side languages, i.e. PHP
+    Language,
+
+    /// web frameworks, i.e. Django
+    Framework,
+
+    /// content management systems, i.e. WordPress
+    Cms,
+}
+
+/// Implement Display for TechCategory
+impl fmt::Display for TechCategory {
+    /// formatter for Display
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            TechCategory::Server => "server",
+            TechCategory::Language => "language",
+            TechCategory::Framework => "framework",
+            TechCategory::Cms => "cms",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A single identifiable technology
+#[derive(Debug, PartialEq)]
+pub struct Technology {
+    /// name of the technology
+    pub name: &'static str,
+
+    /// what kind of technology this is
+    pub category: TechCategory,
+
+    /// file extensions commonly served by the technology
+    pub extensions: &'static [&'static str],
+}
+
+/// Set of rules that identify a `Technology`; any single matching rule is enough
+struct Rule {
+    /// technology identified by this rule
+    tech: Technology,
+
+    /// (header name, regex) pairs matched against response header values
+    headers: Vec<(&'static str, Regex)>,
+
+    /// regexes matched against each Set-Cookie header
+    cookies: Vec<Regex>,
+
+    /// regexes matched against the response body
+    body: Vec<Regex>,
+}
+
+/// implementation of Rule
+impl Rule {
+    /// create a new Rule from uncompiled patterns
+    fn new(
+        tech: Technology,
+        headers: &[(&'static str, &str)],
+        cookies: &[&str],
+        body: &[&str],
+    ) -> Self {
+        let compile = |pattern: &str| Regex::new(pattern).expect("Could not compile regex");
+
+        Self {
+            tech,
+            headers: headers.iter().map(|(h, p)| (*h, compile(p))).collect(),
+            cookies: cookies.iter().map(|p| compile(p)).collect(),
+            body: body.iter().map(|p| compile(p)).collect(),
+        }
+    }
+
+    /// determine whether or not the given response matches this rule
+    fn matches(&self, response: &FeroxResponse) -> bool {
+        let headers = response.headers();
+
+        let header_match = self.headers.iter().any(|(name, regex)| {
+            headers
+                .get_all(*name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .any(|value| regex.is_match(value))
+        });
+
+        let cookie_match = || {
+            headers
+                .get_all(SET_COOKIE)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .any(|value| self.cookies.iter().any(|regex| regex.is_match(value)))
+        };
+
+        let body_match = || {
+            self.body
+                .iter()
+                .any(|regex| regex.is_match(response.text()))
+        };
+
+        header_match || cookie_match() || body_match()
+    }
+}
+
+lazy_static! {
+    /// all known fingerprinting rules
+    static ref RULES: Vec<Rule> = vec![
+        Rule::new(
+            Technology { name: "nginx", category: TechCategory::Server, extensions: &[] },
+            &[("server", r"(?i)^nginx")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "Apache", category: TechCategory::Server, extensions: &[] },
+            &[("server", r"(?i)^apache")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "IIS", category: TechCategory::Server, extensions: &["asp", "aspx"] },
+            &[("server", r"(?i)microsoft-iis")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "LiteSpeed", category: TechCategory::Server, extensions: &[] },
+            &[("server", r"(?i)^litespeed")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "Caddy", category: TechCategory::Server, extensions: &[] },
+            &[("server", r"(?i)^caddy")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "Werkzeug", category: TechCategory::Server, extensions: &["py"] },
+            &[("server", r"(?i)werkzeug")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "PHP", category: TechCategory::Language, extensions: &["php"] },
+            &[("x-powered-by", r"(?i)php")],
+            &[r"(?i)^PHPSESSID="],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "ASP.NET", category: TechCategory::Framework, extensions: &["aspx", "ashx", "asmx"] },
+            &[("x-powered-by", r"(?i)asp\.net"), ("x-aspnet-version", r".+"), ("x-aspnetmvc-version", r".+")],
+            &[r"(?i)^ASP\.NET_SessionId="],
+            &[r#"(?i)name="__VIEWSTATE""#],
+        ),
+        Rule::new(
+            Technology { name: "Java", category: TechCategory::Language, extensions: &["jsp", "do", "action"] },
+            &[("x-powered-by", r"(?i)(servlet|jsp)")],
+            &[r"(?i)^JSESSIONID="],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "ColdFusion", category: TechCategory::Language, extensions: &["cfm", "cfc"] },
+            &[],
+            &[r"(?i)^(CFID|CFTOKEN)="],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "Express", category: TechCategory::Framework, extensions: &[] },
+            &[("x-powered-by", r"(?i)^express")],
+            &[],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "Django", category: TechCategory::Framework, extensions: &[] },
+            &[],
+            &[r"(?i)^(csrftoken|django_language)="],
+            &[r#"name=["']csrfmiddlewaretoken["']"#],
+        ),
+        Rule::new(
+            Technology { name: "Ruby on Rails", category: TechCategory::Framework, extensions: &[] },
+            &[("x-powered-by", r"(?i)phusion passenger")],
+            &[r"(?i)^_[a-z0-9_]+_session="],
+            &[r#"<meta name=["']csrf-param["'] content=["']authenticity_token["']"#],
+        ),
+        Rule::new(
+            Technology { name: "Laravel", category: TechCategory::Framework, extensions: &["php"] },
+            &[],
+            &[r"(?i)^laravel_session="],
+            &[],
+        ),
+        Rule::new(
+            Technology { name: "WordPress", category: TechCategory::Cms, extensions: &["php"] },
+            &[("link", r"(?i)api\.w\.org")],
+            &[r"(?i)^wordpress_"],
+            &[r"/wp-(?:content|includes)/", r#"(?i)<meta name=["']generator["'] content=["']WordPress"#],
+        ),
+        Rule::new(
+            Technology { name: "Drupal", category: TechCategory::Cms, extensions: &["php"] },
+            &[("x-generator", r"(?i)drupal"), ("x-drupal-cache", r".+")],
+            &[],
+            &[r"Drupal\.settings", r"/sites/(?:all|default)/(?:themes|modules)/"],
+        ),
+        Rule::new(
+            Technology { name: "Tomcat", category: TechCategory::Server, extensions: &["jsp"] },
+            &[("server", r"(?i)apache-coyote")],
+            &[],
+            &[r"(?i)<title>Apache Tomcat", r"Apache Tomcat/\d"],
+        ),
+        Rule::new(
+            Technology { name: "SharePoint", category: TechCategory::Cms, extensions: &["aspx", "asmx"] },
+            &[("microsoftsharepointteamservices", r".+"), ("sprequestguid", r".+"), ("sharepointhealthscore", r".+")],
+            &[],
+            &[r"/_layouts/1[56]/"],
+        ),
+        Rule::new(
+            Technology { name: "Joomla", category: TechCategory::Cms, extensions: &["php"] },
+            &[],
+            &[],
+            &[r"/media/jui/", r#"(?i)<meta name=["']generator["'] content=["']Joomla"#],
+        ),
+    ];
+
+    /// target -> technologies identified on it; each target is only fingerprinted once
+    static ref FINGERPRINTED: Mutex<HashMap<String, Vec<&'static Technology>>> =
+        Mutex::new(HashMap::new());
+}
+
+/// Return every known technology that matches the given response
+pub fn fingerprint(response: &FeroxResponse) -> Vec<&'static Technology> {
+    log::trace!("enter: fingerprint({})", response);
+
+    let technologies: Vec<_> = RULES
+        .iter()
+        .filter(|rule| rule.matches(response))
+        .map(|rule| &rule.tech)
+        .collect();
+
+    log::trace!("exit: fingerprint -> {:?}", technologies);
+    technologies
+}
+
+/// Return the unique extensions associated with the given technologies, in the order they
+/// were first seen
+pub fn suggested_extensions(technologies: &[&Technology]) -> Vec<String> {
+    let mut extensions: Vec<String> = Vec::new();
+
+    for extension in technologies.iter().flat_map(|tech| tech.extensions.iter()) {
+        if !extensions.iter().any(|e| e == extension) {
+            extensions.push(extension.to_string());
+        }
+    }
+
+    extensions
+}
+
+/// Request the given target and return the technologies identified from the response
+pub async fn fingerprint_target(
+    target: &str,
+    handles: Arc<Handles>,
+) -> Result<Vec<&'static Technology>> {
+    log::trace!("enter: fingerprint_target({}, {:?})", target, handles);
+
+    if let Some(technologies) = FINGERPRINTED
+        .lock()
+        .ok()
+        .and_then(|fingerprinted| fingerprinted.get(target).cloned())
+    {
+        log::trace!("exit: fingerprint_target -> {:?}", technologies);
+        return Ok(technologies);
+    }
+
+    let url = FeroxUrl::from_string(target, handles.clone()).format("", None)?;
+
+    let response = logged_request(&url, handles.clone()).await?;
+
+    let ferox_response = FeroxResponse::from(
+        response,
+        true,
+        handles.config.max_response_size,
+        handles.config.output_level,
+    )
+    .await;
+
+    let technologies = fingerprint(&ferox_response);
+
+    if let Ok(mut fingerprinted) = FINGERPRINTED.lock() {
+        fingerprinted.insert(target.to_string(), technologies.clone());
+    }
+
+    log::trace!("exit: fingerprint_target -> {:?}", technologies);
+    Ok(technologies)
+}
+
+/// 32-bit x86 variant of MurmurHash3, equivalent to python's `mmh3.hash` (as an unsigned value)
+pub(crate) fn murmur3_32(data: &[u8], seed: u32) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+
+    let mut hash = seed;
+
+    let chunks = data.chunks_exact(4);
+    let tail = chunks.remainder();
+
+    for chunk in chunks {
+        let k = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        hash = hash
+            .rotate_left(13)
+            .wrapping_mul(5)
+            .wrapping_add(0xe654_6b64);
+    }
+
+    if !tail.is_empty() {
+        let mut k = 0_u32;
+
+        for (i, byte) in tail.iter().enumerate() {
+            k ^= (*byte as u32) << (8 * i);
+        }
+
+        hash ^= k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+    }
+
+    // finalization mix
+    hash ^= data.len() as u32;
+    hash ^= hash >> 16;
+    hash = hash.wrapping_mul(0x85eb_ca6b);
+    hash ^= hash >> 13;
+    hash = hash.wrapping_mul(0xc2b2_ae35);
+    hash ^= hash >> 16;
+
+    hash
+}
+
+/// base64 encode the given bytes the same way python's `base64.encodebytes` does, i.e. a
+/// newline after every 76 characters of output, as well as one at the very end
+fn encode_base64_lines(data: &[u8]) -> String {
+    let encoded = base64::encode(data);
+
+    let mut wrapped = String::with_capacity(encoded.len() + encoded.len() / BASE64_LINE_LENGTH + 1);
+
+    for (i, character) in encoded.chars().enumerate() {
+        if i > 0 && i % BASE64_LINE_LENGTH == 0 {
+            wrapped.push('\n');
+        }
+        wrapped.push(character);
+    }
+
+    wrapped.push('\n');
+    wrapped
+}
+
+/// Compute the favicon hash used by Shodan's `http.favicon.hash` filter
+///
+/// equivalent to `mmh3.hash(base64.encodebytes(favicon))` in python
+pub fn favicon_hash(favicon: &[u8]) -> i32 {
+    murmur3_32(encode_base64_lines(favicon).as_bytes(), 0) as i32
+}
+
+/// Request /favicon.ico from the root of the given target and return a finding containing its
+/// Shodan-compatible hash
+///
+/// `Ok(None)` is returned when the target doesn't serve a favicon
+pub async fn favicon_finding(target: &str, handles: Arc<Handles>) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: favicon_finding({}, {:?})", target, handles);
+
+    let mut url = Url::parse(target)?;
+    url.set_path("/favicon.ico"); // overwrite existing path with /favicon.ico
+    url.set_query(None);
+
+    let response = logged_request(&url, handles.clone()).await?;
+
+    if !response.status().is_success() {
+        log::trace!("exit: favicon_finding -> None");
+        return Ok(None);
+    }
+
+    let favicon = response.bytes().await?;
+
+    if favicon.is_empty() {
+        log::trace!("exit: favicon_finding -> None");
+        return Ok(None);
+    }
+
+    let hash = favicon_hash(&favicon);
+
+    let finding = FeroxFinding::new(
+        "favicon",
+        url.as_str(),
+        &format!("mmh3: {} (shodan: http.favicon.hash:{})", hash, hash),
+        json!({
+            "mmh3": hash,
+            "size": favicon.len(),
+        }),
+    );
+
+    log::trace!("exit: favicon_finding -> {:?}", finding);
+    Ok(Some(finding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Configuration, scan_manager::FeroxScans};
+    use httpmock::{Method::GET, MockServer};
+
+    /// helper to reduce a list of technologies to their names
+    fn names(technologies: &[&Technology]) -> Vec<&'static str> {
+        technologies.iter().map(|tech| tech.name).collect()
+    }
+
+    #[test]
+    /// headers and cookies are both used to identify technologies
+    fn fingerprint_identifies_technologies_from_headers_and_cookies() {
+        let mut response = FeroxResponse::default();
+        response.add_header("server", "nginx/1.18.0 (Ubuntu)");
+        response.add_header("x-powered-by", "PHP/7.4.3");
+        response.add_header("set-cookie", "laravel_session=abc123; path=/; httponly");
+
+        let technologies = fingerprint(&response);
+
+        assert_eq!(names(&technologies), vec!["nginx", "PHP", "Laravel"]);
+        assert_eq!(technologies[0].category, TechCategory::Server);
+        assert_eq!(technologies[2].category, TechCategory::Framework);
+    }
+
+    #[test]
+    /// response bodies are used to identify technologies
+    fn fingerprint_identifies_technologies_from_body() {
+        let mut response = FeroxResponse::default();
+        response.set_text(
+            r#"<html><head><meta name="generator" content="WordPress 5.7.2" />
+            <link rel="stylesheet" href="/wp-content/themes/twentytwentyone/style.css">"#,
+        );
+
+        let technologies = fingerprint(&response);
+
+        assert_eq!(names(&technologies), vec!["WordPress"]);
+        assert_eq!(technologies[0].category.to_string(), "cms");
+    }
+
+    #[test]
+    /// a response without any identifying information produces no technologies
+    fn fingerprint_returns_empty_when_nothing_matches() {
+        let mut response = FeroxResponse::default();
+        response.set_text("<html><body>hello</body></html>");
+
+        assert!(fingerprint(&response).is_empty());
+    }
+
+    #[test]
+    /// extensions are deduplicated across technologies
+    fn suggested_extensions_are_unique() {
+        let mut response = FeroxResponse::default();
+        response.add_header("x-powered-by", "PHP/8.0");
+        response.add_header("x-generator", "Drupal 9 (https://www.drupal.org)");
+
+        let technologies = fingerprint(&response);
+
+        assert_eq!(names(&technologies), vec!["PHP", "Drupal"]);
+        assert_eq!(suggested_extensions(&technologies), vec!["php"]);
+    }
+
+    #[test]
+    /// murmur3 matches the values produced by python's mmh3 library
+    fn murmur3_32_matches_known_values() {
+        assert_eq!(murmur3_32(b"", 0), 0);
+        assert_eq!(murmur3_32(b"foo", 0) as i32, -156_908_512);
+        assert_eq!(murmur3_32(b"hello", 0) as i32, 613_153_351);
+    }
+
+    #[test]
+    /// base64 output is wrapped at 76 characters and ends in a newline, just like python's
+    fn encode_base64_lines_matches_python_encodebytes() {
+        let data: Vec<u8> = (0..100).collect();
+
+        assert_eq!(
+            encode_base64_lines(&data),
+            "AAECAwQFBgcICQoLDA0ODxAREhMUFRYXGBkaGxwdHh8gISIjJCUmJygpKissLS4vMDEyMzQ1Njc4\n\
+             OTo7PD0+P0BBQkNERUZHSElKS0xNTk9QUVJTVFVWV1hZWltcXV5fYGFiYw==\n"
+        );
+        assert_eq!(encode_base64_lines(b"foo"), "Zm9v\n");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// the favicon is requested from the root of the target and its hash is reported
+    async fn favicon_finding_reports_hash_of_favicon() {
+        let srv = MockServer::start();
+        let favicon: Vec<u8> = (0..=255).collect();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET).path("/favicon.ico");
+            then.status(200).body(&favicon);
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let scans = Arc::new(FeroxScans::default());
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        let finding = favicon_finding(&srv.url("/some/dir/"), handles)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(mock.hits(), 1);
+        assert_eq!(finding.category(), "favicon");
+        assert_eq!(finding.url(), srv.url("/favicon.ico"));
+        assert_eq!(finding.details()["mmh3"], favicon_hash(&favicon));
+        assert_eq!(finding.details()["size"], 256);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// targets without a favicon produce no finding
+    async fn favicon_finding_returns_none_when_missing() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET).path("/favicon.ico");
+            then.status(404);
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let finding = favicon_finding(&srv.url("/"), handles).await.unwrap();
+
+        assert_eq!(mock.hits(), 1);
+        assert!(finding.is_none());
+    }
+}
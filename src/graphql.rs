@@ -0,0 +1,213 @@
+//! detect likely GraphQL endpoints and probe them with an introspection query
+use std::sync::Arc;
+
+use anyhow::Result;
+use reqwest::{header::CONTENT_TYPE, Method};
+use serde_json::{json, Value};
+
+use crate::{
+    event_handlers::Handles,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{send_to_target, target_request, with_user_headers},
+};
+
+/// final path segments that commonly host a GraphQL endpoint
+const GRAPHQL_SEGMENTS: [&str; 6] = [
+    "graphql",
+    "graphiql",
+    "graphql.php",
+    "gql",
+    "playground",
+    "altair",
+];
+
+/// response body snippets produced by common GraphQL servers when no query is given
+const GRAPHQL_BODY_MARKERS: [&str; 4] = [
+    "Must provide query string",
+    "GET query missing",
+    "\"errors\":[{\"message\":\"Syntax Error",
+    "graphiql.min.js",
+];
+
+/// introspection query that asks for the root operation types and the names of all types
+const INTROSPECTION_QUERY: &str =
+    "query IntrospectionQuery { __schema { queryType { name } mutationType { name } subscriptionType { name } types { name kind } } }";
+
+/// Determine whether or not the given response looks like it came from a GraphQL endpoint
+pub fn is_graphql_candidate(response: &FeroxResponse) -> bool {
+    let last_segment = response
+        .url()
+        .path_segments()
+        .and_then(|mut segments| segments.rev().find(|s| !s.is_empty()))
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if GRAPHQL_SEGMENTS.contains(&last_segment.as_str()) {
+        return true;
+    }
+
+    GRAPHQL_BODY_MARKERS
+        .iter()
+        .any(|marker| response.text().contains(marker))
+}
+
+/// Interpret the body of an introspection response, returning `None` when the body didn't come
+/// from a GraphQL server
+///
+/// when introspection is enabled, the user-defined type names are returned (built-in types that
+/// start with `__` are skipped)
+pub(crate) fn parse_introspection(body: &str) -> Option<(bool, Vec<String>)> {
+    let json: Value = serde_json::from_str(body).ok()?;
+
+    let schema = &json["data"]["__schema"];
+
+    if schema.is_object() {
+        let types = schema["types"]
+            .as_array()
+            .map(|types| {
+                types
+                    .iter()
+                    .filter_map(|t| t["name"].as_str())
+                    .filter(|name| !name.starts_with("__"))
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        return Some((true, types));
+    }
+
+    if json["errors"].is_array() {
+        // a graphql server that refused the query; introspection is disabled
+        return Some((false, Vec::new()));
+    }
+
+    None
+}
+
+/// Send an introspection query to the url of the given response and report whether or not
+/// introspection is enabled, along with the top-level types it exposes
+///
+/// `Ok(None)` is returned when the endpoint was already probed or isn't a GraphQL server
+pub async fn probe(
+    response: &FeroxResponse,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let url = response.url().clone();
+
+    if handles.denied_by_regex(&url) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    if !handles.first_probe("graphql", url.as_str()) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    let context = &handles.config.request_context;
+    let request = target_request(context, &handles.config.client, &Method::POST, &url);
+    let request = with_user_headers(context, &handles.session, request, &url, None)
+        .header(CONTENT_TYPE, "application/json")
+        .body(json!({ "query": INTROSPECTION_QUERY }).to_string());
+    let introspection = send_to_target(context, request, &url).await?;
+    let introspection =
+        FeroxResponse::from(introspection, true, 0, handles.config.output_level).await;
+
+    let finding = parse_introspection(introspection.text()).map(|(enabled, types)| {
+        let summary = if enabled {
+            format!(
+                "introspection enabled; {} types [{}]",
+                types.len(),
+                types.join(", ")
+            )
+        } else {
+            "introspection disabled".to_string()
+        };
+
+        FeroxFinding::new(
+            "graphql",
+            url.as_str(),
+            &summary,
+            json!({
+                "introspection": enabled,
+                "types": types,
+            }),
+        )
+        .with_exchange(&introspection)
+    });
+
+    log::trace!("exit: probe -> {:?}", finding);
+    Ok(finding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::POST, MockServer};
+
+    #[test]
+    /// endpoints are recognized by their path or by the body they return
+    fn is_graphql_candidate_checks_path_and_body() {
+        let mut response = FeroxResponse::default();
+
+        response.set_url("http://localhost/api/graphql");
+        assert!(is_graphql_candidate(&response));
+
+        response.set_url("http://localhost/api/GraphQL/");
+        assert!(is_graphql_candidate(&response));
+
+        response.set_url("http://localhost/api/query");
+        assert!(!is_graphql_candidate(&response));
+
+        response.set_text(r#"{"errors":[{"message":"Must provide query string."}]}"#);
+        assert!(is_graphql_candidate(&response));
+    }
+
+    #[test]
+    /// introspection responses are parsed into enabled/disabled + type names
+    fn parse_introspection_handles_enabled_disabled_and_other() {
+        let enabled = r#"{"data":{"__schema":{"queryType":{"name":"Query"},"types":[{"name":"Query","kind":"OBJECT"},{"name":"User","kind":"OBJECT"},{"name":"__Type","kind":"OBJECT"}]}}}"#;
+        assert_eq!(
+            parse_introspection(enabled),
+            Some((true, vec!["Query".to_string(), "User".to_string()]))
+        );
+
+        let disabled = r#"{"errors":[{"message":"GraphQL introspection is not allowed"}]}"#;
+        assert_eq!(parse_introspection(disabled), Some((false, vec![])));
+
+        assert_eq!(parse_introspection("<html>not graphql</html>"), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// an introspection query is sent to the endpoint once, the result is reported as a finding
+    async fn probe_reports_introspection_once() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(POST).path("/graphql").body_contains("__schema");
+            then.status(200).body(
+                r#"{"data":{"__schema":{"types":[{"name":"Query","kind":"OBJECT"},{"name":"Mutation","kind":"OBJECT"}]}}}"#,
+            );
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&srv.url("/graphql"));
+
+        let finding = probe(&response, handles.clone()).await.unwrap().unwrap();
+
+        assert_eq!(finding.category(), "graphql");
+        assert_eq!(finding.details()["introspection"], true);
+        assert_eq!(finding.details()["types"], json!(["Query", "Mutation"]));
+
+        assert!(probe(&response, handles).await.unwrap().is_none());
+        assert_eq!(mock.hits(), 1);
+    }
+}
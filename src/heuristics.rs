@@ -1,10 +1,17 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{bail, Result};
 use console::style;
+use futures::{stream, StreamExt};
+use reqwest::Url;
 use uuid::Uuid;
 
 use crate::{
+    client::{DNS_CACHE, HOST_TIMEOUTS},
     config::OutputLevel,
     event_handlers::{Command, Handles},
     filters::WildcardFilter,
@@ -66,6 +73,27 @@ impl HeuristicTests {
         unique_id
     }
 
+    /// learn how long requests to `url`'s host should be given, based on how long this heuristic
+    /// request took (--adaptive-timeout)
+    fn learn_timeout(&self, url: &Url, elapsed: Duration) {
+        let factor = self.handles.config.adaptive_timeout;
+
+        if factor == 0 {
+            return;
+        }
+
+        let max = Duration::from_secs(self.handles.config.timeout);
+
+        if let Some(timeout) = HOST_TIMEOUTS.learn(url, elapsed, factor, max) {
+            log::info!(
+                "{} took {}ms to respond; its requests now time out after {}ms",
+                url.host_str().unwrap_or_default(),
+                elapsed.as_millis(),
+                timeout.as_millis()
+            );
+        }
+    }
+
     /// wrapper for sending a filter to the filters event handler
     fn send_filter(&self, filter: WildcardFilter) -> Result<()> {
         self.handles
@@ -158,7 +186,9 @@ impl HeuristicTests {
         let unique_str = self.unique_string(length);
         let nonexistent_url = target.format(&unique_str, None)?;
 
+        let start = Instant::now();
         let response = logged_request(&nonexistent_url.to_owned(), self.handles.clone()).await?;
+        self.learn_timeout(&nonexistent_url, start.elapsed());
 
         if self
             .handles
@@ -167,8 +197,13 @@ impl HeuristicTests {
             .contains(&response.status().as_u16())
         {
             // found a wildcard response
-            let mut ferox_response =
-                FeroxResponse::from(response, true, self.handles.config.output_level).await;
+            let mut ferox_response = FeroxResponse::from(
+                response,
+                true,
+                self.handles.config.max_response_size,
+                self.handles.config.output_level,
+            )
+            .await;
             ferox_response.set_wildcard(true);
 
             if self
@@ -196,31 +231,98 @@ impl HeuristicTests {
         bail!("uninteresting status code")
     }
 
-    /// Simply tries to connect to all given sites before starting to scan
+    /// hosts of the given requests that don't resolve; every host is looked up concurrently, and
+    /// the answers are cached for the requests that follow
     ///
-    /// In the event that no sites can be reached, the program will exit.
+    /// when a proxy is used, it resolves names instead, so nothing is looked up here
+    async fn unresolved_hosts(&self, requests: &[(&String, Url)]) -> HashSet<String> {
+        if !self.handles.config.proxy.is_empty() {
+            return HashSet::new();
+        }
+
+        let hosts: HashSet<&str> = requests
+            .iter()
+            .filter_map(|(_, request)| request.domain())
+            .collect();
+
+        stream::iter(hosts)
+            .map(|host| async move { (host, DNS_CACHE.prime(host).await) })
+            .buffer_unordered(self.handles.config.threads.max(1))
+            .filter_map(|(host, resolved)| async move {
+                if resolved {
+                    None
+                } else {
+                    log::warn!("Could not resolve {}", host);
+                    Some(host.to_string())
+                }
+            })
+            .collect()
+            .await
+    }
+
+    /// Tries to connect to all given sites before starting to scan
     ///
-    /// Any urls that are found to be alive are returned to the caller.
+    /// all hosts are resolved up front, then the sites are probed concurrently (up to --threads
+    /// at a time), so dead hosts in a long list of targets are weeded out together instead of
+    /// one timeout at a time. In the event that no sites can be reached, the program will exit.
+    ///
+    /// Any urls that are found to be alive are returned to the caller, fastest to respond first.
     pub async fn connectivity(&self, target_urls: &[String]) -> Result<Vec<String>> {
         log::trace!("enter: connectivity_test({:?})", target_urls);
 
-        let mut good_urls = vec![];
+        let print = matches!(
+            self.handles.config.output_level,
+            OutputLevel::Default | OutputLevel::Quiet
+        );
+
+        let mut requests = vec![];
 
         for target_url in target_urls {
             let url = FeroxUrl::from_string(target_url, self.handles.clone());
-            let request = skip_fail!(url.format("", None));
+            requests.push((target_url, skip_fail!(url.format("", None))));
+        }
+
+        let unresolved = self.unresolved_hosts(&requests).await;
+
+        let (unresolved_targets, requests): (Vec<_>, Vec<_>) =
+            requests.into_iter().partition(|(_, request)| {
+                request
+                    .domain()
+                    .map_or(false, |host| unresolved.contains(host))
+            });
+
+        for (target_url, _) in &unresolved_targets {
+            if print {
+                ferox_print(
+                    &format!("Could not connect to {}, skipping...", target_url),
+                    &PROGRESS_PRINTER,
+                );
+            }
+        }
 
-            let result = logged_request(&request, self.handles.clone()).await;
+        let probes: Vec<_> = stream::iter(requests)
+            .map(|(target_url, request)| async move {
+                let start = Instant::now();
+                let result = logged_request(&request, self.handles.clone()).await;
+                (target_url, request, start.elapsed(), result)
+            })
+            .buffer_unordered(self.handles.config.threads.max(1))
+            .collect()
+            .await;
 
+        let mut good_urls = vec![];
+        let mut unresponsive = 0;
+
+        for (target_url, request, elapsed, result) in probes {
             match result {
                 Ok(_) => {
-                    good_urls.push(target_url.to_owned());
+                    self.learn_timeout(&request, elapsed);
+                    good_urls.push((elapsed, target_url.to_owned()));
                 }
                 Err(e) => {
-                    if matches!(
-                        self.handles.config.output_level,
-                        OutputLevel::Default | OutputLevel::Quiet
-                    ) {
+                    unresponsive += 1;
+
+                    if print {
                         if e.to_string().contains(":SSL") {
                             ferox_print(
                                 &format!("Could not connect to {} due to SSL errors (run with -k to ignore), skipping...", target_url),
@@ -242,6 +344,25 @@ impl HeuristicTests {
             bail!("Could not connect to any target provided");
         }
 
+        let skipped = unresolved_targets.len() + unresponsive;
+
+        if skipped > 0 && print {
+            ferox_print(
+                &format!(
+                    "Skipped {} of {} targets: {} didn't resolve, {} didn't respond",
+                    skipped,
+                    skipped + good_urls.len(),
+                    unresolved_targets.len(),
+                    unresponsive
+                ),
+                &PROGRESS_PRINTER,
+            );
+        }
+
+        // fastest first; the sort is stable, so targets that were equally quick keep their order
+        good_urls.sort_by_key(|(elapsed, _)| *elapsed);
+        let good_urls: Vec<String> = good_urls.into_iter().map(|(_, url)| url).collect();
+
         log::trace!("exit: connectivity_test -> {:?}", good_urls);
         Ok(good_urls)
     }
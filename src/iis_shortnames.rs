@@ -0,0 +1,450 @@
+//! enumerate the 8.3 short names of files and directories on IIS targets via tilde probing
+//! (`/a*~1*/.aspx`), and request the words of the wordlist that match them (--iis-shortnames)
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::Result;
+use reqwest::{StatusCode, Url};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    event_handlers::Handles,
+    extractor::{ExtractionTarget::ResponseBody, ExtractorBuilder},
+    finding::FeroxFinding,
+    fingerprint::fingerprint_target,
+    utils::logged_request,
+};
+
+/// characters a short name is made of (case doesn't matter to IIS)
+const CHARACTERS: &str = "abcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// short names are at most 6 characters, followed by ~N
+const NAME_LENGTH: usize = 6;
+
+/// short extensions are at most 3 characters
+const EXTENSION_LENGTH: usize = 3;
+
+/// highest ~N looked for; anything past ~4 has a hashed name on recent versions of windows
+const MAX_INDEX: usize = 4;
+
+/// most probes sent per directory, however many short names turn up
+const MAX_PROBES: usize = 1500;
+
+/// most full names requested per short name; a one or two letter short name matches a good
+/// chunk of any wordlist
+const MAX_MATCHES: usize = 100;
+
+/// extensions a truncated, three letter extension is most likely short for, i.e. asp -> aspx
+const LONG_EXTENSIONS: [&str; 10] = [
+    "aspx", "ashx", "asmx", "html", "config", "json", "jpeg", "java", "conf", "backup",
+];
+
+/// A short name found in a directory, i.e. ADMINI~1.ASP
+#[derive(Debug, Clone, PartialEq)]
+struct ShortName {
+    /// up to 6 leading characters of the long name, lowercased
+    name: String,
+
+    /// the N of ~N
+    index: usize,
+
+    /// up to 3 leading characters of the long extension, lowercased; empty for directories and
+    /// files without an extension
+    extension: String,
+}
+
+/// Implement Display for ShortName, the way windows shows it
+impl fmt::Display for ShortName {
+    /// formatter for Display
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}~{}", self.name.to_uppercase(), self.index)?;
+
+        if !self.extension.is_empty() {
+            write!(f, ".{}", self.extension.to_uppercase())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ShortName {
+    /// Full names from `words` this short name could be short for, with the likely extensions
+    /// added to words that don't have one
+    fn matches(&self, words: &[String], extensions: &[String]) -> Vec<String> {
+        let mut long_extensions = vec![self.extension.clone()];
+
+        long_extensions.extend(
+            extensions
+                .iter()
+                .map(String::as_str)
+                .chain(LONG_EXTENSIONS.iter().copied())
+                .filter(|extension| extension.to_lowercase().starts_with(&self.extension))
+                .map(|extension| extension.to_lowercase()),
+        );
+
+        let mut matched = Vec::new();
+
+        for word in words {
+            if matched.len() >= MAX_MATCHES {
+                break;
+            }
+
+            let lowered = word.to_lowercase();
+
+            let (stem, extension) = match lowered.rfind('.') {
+                Some(dot) if dot > 0 => (&lowered[..dot], Some(&lowered[dot + 1..])),
+                _ => (lowered.as_str(), None),
+            };
+
+            // windows leaves out spaces, dots, and the like when it makes up a short name
+            let squeezed: String = stem.chars().filter(|c| CHARACTERS.contains(*c)).collect();
+
+            if !squeezed.starts_with(&self.name) {
+                continue;
+            }
+
+            match extension {
+                Some(extension)
+                    if !self.extension.is_empty() && extension.starts_with(&self.extension) =>
+                {
+                    matched.push(word.to_string());
+                }
+                Some(_) => {}
+                None if self.extension.is_empty() => matched.push(word.to_string()),
+                None => {
+                    for extension in &long_extensions {
+                        if !matched.contains(&format!("{}.{}", word, extension)) {
+                            matched.push(format!("{}.{}", word, extension));
+                        }
+                    }
+                }
+            }
+        }
+
+        matched
+    }
+}
+
+/// Sends tilde probes to a single directory, comparing each response to one for a name that
+/// doesn't exist
+struct Prober {
+    /// directory being probed, with a trailing slash
+    directory: Url,
+
+    /// status of a probe for a name that doesn't exist
+    baseline: StatusCode,
+
+    /// probes sent so far
+    probes: usize,
+
+    /// handles to handlers and config
+    handles: Arc<Handles>,
+}
+
+impl Prober {
+    /// Status of the probe for the given pattern, i.e. `admin*~1*`
+    async fn status(directory: &Url, pattern: &str, handles: Arc<Handles>) -> Result<StatusCode> {
+        let mut url = directory.clone();
+        url.set_path(&format!("{}{}/.aspx", directory.path(), pattern));
+
+        let response = logged_request(&url, handles).await?;
+        Ok(response.status())
+    }
+
+    /// Whether some short name matches the given pattern; always false once `MAX_PROBES` have
+    /// been sent
+    async fn hit(&mut self, pattern: &str) -> bool {
+        if self.probes >= MAX_PROBES {
+            return false;
+        }
+
+        self.probes += 1;
+
+        match Self::status(&self.directory, pattern, self.handles.clone()).await {
+            Ok(status) => status != self.baseline,
+            Err(e) => {
+                log::warn!("Could not probe {}{}: {}", self.directory, pattern, e);
+                false
+            }
+        }
+    }
+
+    /// Complete short names (without ~N), pieced together a character at a time
+    async fn names(&mut self) -> Vec<String> {
+        let mut names = Vec::new();
+        let mut prefixes = vec![String::new()];
+
+        while let Some(prefix) = prefixes.pop() {
+            for character in CHARACTERS.chars() {
+                let name = format!("{}{}", prefix, character);
+
+                if !self.hit(&format!("{}*~1*", name)).await {
+                    continue;
+                }
+
+                if name.len() == NAME_LENGTH || self.hit(&format!("{}~1*", name)).await {
+                    names.push(name.clone());
+                }
+
+                if name.len() < NAME_LENGTH {
+                    prefixes.push(name);
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Extensions of the short name `name~index`; a single empty extension when it has none
+    async fn extensions(&mut self, name: &str, index: usize) -> Vec<String> {
+        let mut extensions = Vec::new();
+        let mut prefixes = vec![String::new()];
+
+        while let Some(prefix) = prefixes.pop() {
+            for character in CHARACTERS.chars() {
+                let extension = format!("{}{}", prefix, character);
+
+                if !self
+                    .hit(&format!("{}~{}.{}*", name, index, extension))
+                    .await
+                {
+                    continue;
+                }
+
+                if extension.len() == EXTENSION_LENGTH
+                    || self.hit(&format!("{}~{}.{}", name, index, extension)).await
+                {
+                    extensions.push(extension.clone());
+                }
+
+                if extension.len() < EXTENSION_LENGTH {
+                    prefixes.push(extension);
+                }
+            }
+        }
+
+        if extensions.is_empty() {
+            extensions.push(String::new());
+        }
+
+        extensions
+    }
+
+    /// Every short name in the directory
+    async fn short_names(&mut self) -> Vec<ShortName> {
+        let mut short_names = Vec::new();
+
+        for name in self.names().await {
+            for index in 1..=MAX_INDEX {
+                if index > 1 && !self.hit(&format!("{}~{}*", name, index)).await {
+                    break;
+                }
+
+                for extension in self.extensions(&name, index).await {
+                    short_names.push(ShortName {
+                        name: name.clone(),
+                        index,
+                        extension,
+                    });
+                }
+            }
+        }
+
+        short_names
+    }
+}
+
+/// Enumerate the short names in the given directory when its server is IIS, request the words
+/// of the wordlist that match them, and report the short names found
+///
+/// `Ok(None)` is returned when the server isn't IIS, or doesn't give its short names away
+pub async fn enumerate(
+    directory: &str,
+    words: Arc<Vec<String>>,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: enumerate({}, {:?})", directory, handles);
+
+    let technologies = fingerprint_target(directory, handles.clone()).await?;
+
+    if !technologies.iter().any(|tech| tech.name == "IIS") {
+        log::trace!("exit: enumerate -> None");
+        return Ok(None);
+    }
+
+    let mut url = Url::parse(directory)?;
+    url.set_query(None);
+
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+
+    // a name no directory has in it; six random characters
+    let nonsense = &Uuid::new_v4().to_simple().to_string()[..NAME_LENGTH];
+    let baseline = Prober::status(&url, &format!("{}*~1*", nonsense), handles.clone()).await?;
+
+    let mut prober = Prober {
+        directory: url.clone(),
+        baseline,
+        probes: 1,
+        handles: handles.clone(),
+    };
+
+    if !prober.hit("*~1*").await {
+        log::trace!("exit: enumerate -> None");
+        return Ok(None);
+    }
+
+    let short_names = prober.short_names().await;
+
+    if short_names.is_empty() {
+        log::trace!("exit: enumerate -> None");
+        return Ok(None);
+    }
+
+    if prober.probes >= MAX_PROBES {
+        log::warn!(
+            "Stopped looking for short names in {} after {} requests",
+            url,
+            MAX_PROBES
+        );
+    }
+
+    let mut links = HashSet::new();
+
+    for short_name in &short_names {
+        for word in short_name.matches(&words, &handles.config.extensions) {
+            if let Ok(link) = url.join(&word) {
+                links.insert(link.to_string());
+            }
+        }
+    }
+
+    let candidates = links.len();
+
+    if !links.is_empty() {
+        let extractor = ExtractorBuilder::default()
+            .url(url.as_str())
+            .handles(handles.clone())
+            .target(ResponseBody)
+            .build()?;
+
+        extractor.request_links(links).await?;
+    }
+
+    let names: Vec<String> = short_names.iter().map(ToString::to_string).collect();
+
+    let finding = FeroxFinding::new(
+        "iis-shortname",
+        url.as_str(),
+        &format!("[medium] short names disclosed: {}", names.join(", ")),
+        json!({
+            "severity": "medium",
+            "short_names": names,
+            "probes": prober.probes,
+            "candidates": candidates,
+        }),
+    );
+
+    log::trace!("exit: enumerate -> {:?}", finding);
+    Ok(Some(finding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Configuration, scan_manager::FeroxScans};
+    use httpmock::{Method::GET, MockServer};
+    use regex::Regex;
+
+    /// short name with the given parts
+    fn short_name(name: &str, index: usize, extension: &str) -> ShortName {
+        ShortName {
+            name: name.to_string(),
+            index,
+            extension: extension.to_string(),
+        }
+    }
+
+    #[test]
+    /// short names are shown uppercased, with their extension when they have one
+    fn short_name_displays_like_windows() {
+        assert_eq!(short_name("admini", 1, "asp").to_string(), "ADMINI~1.ASP");
+        assert_eq!(short_name("backup", 2, "").to_string(), "BACKUP~2");
+    }
+
+    #[test]
+    /// words whose squeezed stem starts with the name match, and get likely extensions when
+    /// they don't have their own
+    fn matches_finds_words_and_extensions() {
+        let words: Vec<String> = vec![
+            "administration",
+            "admin",
+            "Admin Panel.aspx",
+            "administrator.php",
+            "about",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+        let matched = short_name("admini", 1, "asp").matches(&words, &[]);
+
+        assert_eq!(matched, vec!["administration.asp", "administration.aspx"]);
+
+        let matched = short_name("adminp", 1, "asp").matches(&words, &[]);
+        assert_eq!(matched, vec!["Admin Panel.aspx"]);
+
+        let matched = short_name("admin", 1, "").matches(&words, &[]);
+        assert_eq!(matched, vec!["administration", "admin"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// ADMINI~1.ASP is pieced together from the probes it answers differently, and the matching
+    /// word is requested
+    async fn enumerate_reconstructs_short_names() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(200).header("Server", "Microsoft-IIS/10.0");
+        });
+
+        // real servers answer with 404/400; anything that differs from the baseline works
+        srv.mock(|when, then| {
+            when.method(GET).path_matches(
+                Regex::new(r"^/(\*~1\*|a(d(m(i(n(i)?)?)?)?)?\*~1\*|admini~1\.a(s(p)?)?\*)/\.aspx$")
+                    .unwrap(),
+            );
+            then.status(400);
+        });
+
+        let found = srv.mock(|when, then| {
+            when.method(GET).path("/administration.aspx");
+            then.status(200).body("welcome back");
+        });
+
+        let config = Configuration {
+            no_recursion: true,
+            ..Default::default()
+        };
+
+        let (handles, _rx) = Handles::for_testing(
+            Some(Arc::new(FeroxScans::default())),
+            Some(Arc::new(config)),
+        );
+
+        let words = Arc::new(vec![String::from("about"), String::from("administration")]);
+
+        let finding = enumerate(&srv.url("/"), words, Arc::new(handles))
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(finding.category(), "iis-shortname");
+        assert_eq!(finding.details()["short_names"], json!(["ADMINI~1.ASP"]));
+        assert_eq!(found.hits(), 1);
+    }
+}
@@ -0,0 +1,233 @@
+//! read the expiry of a jwt sent in the Authorization header, warn when the scan is likely to
+//! outlive it, and log in again (`[login]`) shortly before it expires
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use console::style;
+use indicatif::HumanDuration;
+use reqwest::header::AUTHORIZATION;
+use serde_json::Value;
+use tokio::time::sleep;
+
+use crate::{
+    config::{Configuration, OutputLevel},
+    login::{self, Session},
+    parser::parse_duration,
+    progress::PROGRESS_PRINTER,
+    utils::ferox_print,
+};
+
+/// how long before a token expires that a new one is requested
+const REFRESH_MARGIN: Duration = Duration::from_secs(60);
+
+/// without --time-limit, tokens that expire sooner than this are warned about
+const EXPIRY_WARNING: Duration = Duration::from_secs(60 * 60);
+
+/// wait before trying again when logging in for a new token failed
+const RETRY_INTERVAL: Duration = Duration::from_secs(30);
+
+/// When the jwt in the given Authorization header value expires; `None` when the value isn't a
+/// jwt, or the jwt has no `exp` claim (or one too far out to be represented)
+pub fn expiry(value: &str) -> Option<SystemTime> {
+    let token = value.trim();
+    let token = token
+        .split_once(' ')
+        .map_or(token, |(_, credentials)| credentials.trim());
+
+    let mut parts = token.split('.');
+    let (_, payload, _) = (parts.next()?, parts.next()?, parts.next()?);
+
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let payload =
+        base64::decode_config(payload.trim_end_matches('='), base64::URL_SAFE_NO_PAD).ok()?;
+    let claims: Value = serde_json::from_slice(&payload).ok()?;
+
+    let exp = claims.get("exp")?.as_f64()?;
+
+    if exp < 0.0 {
+        return None;
+    }
+
+    UNIX_EPOCH.checked_add(Duration::from_secs(exp as u64))
+}
+
+/// Authorization header value currently sent with requests: the `[login]` session's, or the one
+/// given with --headers
+fn authorization(config: &Configuration, session: &Session) -> Option<String> {
+    session
+        .headers()
+        .into_iter()
+        .find(|(name, _)| *name == AUTHORIZATION)
+        .and_then(|(_, value)| value.to_str().ok().map(String::from))
+        .or_else(|| {
+            config
+                .headers
+                .iter()
+                .find(|(name, _)| name.eq_ignore_ascii_case("authorization"))
+                .map(|(_, value)| value.to_string())
+        })
+}
+
+/// Why the scan is likely to outlive a token that expires at `expires`; `None` when it isn't
+fn expiry_problem(expires: SystemTime, time_limit: &str) -> Option<String> {
+    let remaining = match expires.duration_since(SystemTime::now()) {
+        Ok(remaining) => remaining,
+        Err(_) => return Some(String::from("has already expired")),
+    };
+
+    match parse_duration(time_limit) {
+        Ok(limit) if Duration::from_secs(limit) > remaining => Some(format!(
+            "expires in {}, before the --time-limit of {}",
+            HumanDuration(remaining),
+            time_limit
+        )),
+        Ok(_) => None,
+        Err(_) if remaining < EXPIRY_WARNING => {
+            Some(format!("expires in {}", HumanDuration(remaining)))
+        }
+        Err(_) => None,
+    }
+}
+
+/// Warn when the jwt in the Authorization header has expired, or is likely to expire before the
+/// scan is done
+///
+/// with a `[login]`, a new token is requested before then, so only expired tokens are mentioned
+pub fn warn_about_expiry(config: &Configuration, session: &Session) {
+    let expires = match authorization(config, session).as_deref().and_then(expiry) {
+        Some(expires) => expires,
+        None => return,
+    };
+
+    let problem = match expiry_problem(expires, &config.time_limit) {
+        Some(problem) => problem,
+        None => return,
+    };
+
+    let msg = if config.login.is_none() {
+        format!(
+            "The jwt in the Authorization header {}; requests made after that will likely be turned away",
+            problem
+        )
+    } else if expires <= SystemTime::now() {
+        format!("The jwt in the Authorization header {}", problem)
+    } else {
+        return;
+    };
+
+    // unlike log messages, shown without -v; a scan that runs on with an expired token is mostly
+    // wasted
+    if matches!(
+        config.output_level,
+        OutputLevel::Default | OutputLevel::Quiet
+    ) {
+        ferox_print(
+            &format!("{} {}\n", style("WRN").red(), msg),
+            &PROGRESS_PRINTER,
+        );
+    }
+}
+
+/// Log in again (`[login]`) shortly before the session's jwt expires, for as long as the scan
+/// runs; returns right away when the session doesn't carry a jwt with an expiry
+pub async fn refresh_before_expiry(config: Arc<Configuration>, session: Arc<Session>) {
+    log::trace!("enter: refresh_before_expiry");
+
+    while let Some(expires) = authorization(&config, &session).as_deref().and_then(expiry) {
+        let generation = session.generation();
+
+        let wait = expires
+            .duration_since(SystemTime::now())
+            .unwrap_or_default()
+            .saturating_sub(REFRESH_MARGIN);
+
+        sleep(wait).await;
+
+        if session.generation() != generation {
+            // a request found the session expired and already logged in again
+            continue;
+        }
+
+        log::debug!("jwt expires within {:?}, logging in again", REFRESH_MARGIN);
+
+        if !login::relogin(&config, &session, generation).await {
+            sleep(RETRY_INTERVAL).await;
+        }
+    }
+
+    log::trace!("exit: refresh_before_expiry");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// unsigned jwt with the given claims
+    fn jwt(claims: &str) -> String {
+        format!(
+            "eyJhbGciOiJub25lIn0.{}.",
+            base64::encode_config(claims, base64::URL_SAFE_NO_PAD)
+        )
+    }
+
+    /// seconds since the epoch, `offset` seconds from now
+    fn from_now(offset: i64) -> i64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs() as i64;
+        now + offset
+    }
+
+    #[test]
+    /// exp is read from bearer tokens and bare jwts, anything else has no expiry
+    fn expiry_reads_exp_claim() {
+        let token = jwt(r#"{"sub":"admin","exp":1700000000}"#);
+        let expected = Some(UNIX_EPOCH + Duration::from_secs(1700000000));
+
+        assert_eq!(expiry(&format!("Bearer {}", token)), expected);
+        assert_eq!(expiry(&token), expected);
+
+        assert_eq!(expiry(&jwt(r#"{"sub":"admin"}"#)), None);
+        assert_eq!(expiry("Basic YWRtaW46YWRtaW4="), None);
+        assert_eq!(expiry("Bearer not.a.jwt"), None);
+
+        // an exp beyond what the clock can represent has no usable expiry, rather than a panic
+        assert_eq!(expiry(&jwt(r#"{"exp":1e300}"#)), None);
+    }
+
+    #[test]
+    /// expired tokens, and tokens that expire before --time-limit (or within the hour without
+    /// one), are reported
+    fn expiry_problem_compares_against_time_limit() {
+        let at = |offset| UNIX_EPOCH + Duration::from_secs(from_now(offset) as u64);
+
+        assert_eq!(
+            expiry_problem(at(-10), ""),
+            Some(String::from("has already expired"))
+        );
+        assert!(expiry_problem(at(600), "").is_some());
+        assert!(expiry_problem(at(7200), "").is_none());
+        assert!(expiry_problem(at(7200), "3h")
+            .unwrap()
+            .ends_with("before the --time-limit of 3h"));
+        assert!(expiry_problem(at(600), "5m").is_none());
+    }
+
+    #[test]
+    /// the Authorization header given with --headers is used when there's no session
+    fn authorization_falls_back_to_headers() {
+        let mut config = Configuration::default();
+        config
+            .headers
+            .insert(String::from("authorization"), String::from("Bearer abc"));
+
+        assert_eq!(
+            authorization(&config, &Session::default()),
+            Some(String::from("Bearer abc"))
+        );
+    }
+}
@@ -7,17 +7,25 @@ use tokio::{
 
 use crate::event_handlers::Command;
 
+pub mod api;
 pub mod banner;
+pub mod bench;
+pub mod buckets;
 pub mod config;
+pub mod distributed;
 mod client;
 pub mod event_handlers;
+pub mod event_stream;
+pub mod exit_codes;
 pub mod filters;
 pub mod heuristics;
 pub mod logger;
+pub mod numbers;
 mod parser;
 pub mod progress;
 pub mod scan_manager;
 pub mod scanner;
+pub mod server;
 pub mod statistics;
 mod traits;
 pub mod utils;
@@ -26,6 +34,43 @@ mod macros;
 mod url;
 mod response;
 mod message;
+mod finding;
+pub mod analyzers;
+mod fingerprint;
+mod graphql;
+mod sourcemap;
+mod params;
+mod artifacts;
+mod cors;
+mod websocket;
+mod auth_diff;
+mod csrf;
+mod methods;
+mod permutations;
+mod iis_shortnames;
+mod placeholders;
+pub mod scan_id;
+pub mod presets;
+pub mod product_wordlists;
+pub mod crawl_wordlist;
+pub mod wordlist_map;
+pub mod wordlists;
+pub mod wordlist_cache;
+pub mod trace;
+mod burp;
+mod defectdojo;
+mod evidence;
+mod scope;
+pub mod tor;
+pub mod login;
+pub mod jwt;
+pub mod lifecycle;
+mod zap;
+
+pub use self::api::{ScanResult, Scanner};
+pub use self::config::{Configuration, ConfigurationBuilder};
+pub use self::finding::FeroxFinding;
+pub use self::response::FeroxResponse;
 
 /// Alias for tokio::sync::mpsc::UnboundedSender<Command>
 pub(crate) type CommandSender = UnboundedSender<Command>;
@@ -0,0 +1,296 @@
+//! starting and shutting down scans; main and [Scanner](../api/struct.Scanner.html) both go
+//! through these, so a scan takes the same steps no matter how it was started
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use anyhow::{bail, Result};
+use serde_json::json;
+use tokio::sync::{mpsc::UnboundedSender, oneshot};
+
+use crate::{
+    analyzers,
+    api::ScanResult,
+    config::Configuration,
+    event_handlers::{
+        Command::{Exit, JoinTasks},
+        FiltersHandler, Handles, ScanHandler, StatsHandler, Tasks, TermOutHandler,
+    },
+    event_stream::{self, EventStream},
+    exit_codes::{ExitCode, ExitError},
+    filters, heuristics, jwt,
+    login::{self, Session},
+    scan_manager, tor, trace,
+    utils::fmt_err,
+};
+
+/// Number of scans that were started and haven't been shut down yet; the span exporter is shared
+/// by every scan in the process, so it's started with the first scan and finished with the last
+static RUNNING: AtomicUsize = AtomicUsize::new(0);
+
+/// Spawn the event handlers, along with the background tasks the configuration asks for
+/// (--time-limit, --checkpoint-interval, --event-stream statistics), and send user-supplied
+/// filters to the filters handler
+///
+/// results are sent to `results` instead of being printed, when given
+pub async fn start(
+    config: Arc<Configuration>,
+    results: Option<UnboundedSender<ScanResult>>,
+) -> Result<(Arc<Handles>, Tasks)> {
+    log::trace!("enter: start({:?})", config);
+
+    if RUNNING.fetch_add(1, Ordering::SeqCst) == 0 {
+        // spans are exported by a task on the runtime, so this can't happen any earlier
+        if let Err(e) = trace::initialize(&config.trace_output) {
+            RUNNING.fetch_sub(1, Ordering::SeqCst);
+            return Err(e);
+        }
+    }
+
+    // --event-stream: everything after this point can be emitted
+    let events = EventStream::open(&config);
+
+    // spawn all event handlers, expect back a JoinHandle and a *Handle to the specific event
+    let (stats_task, stats_handle) = StatsHandler::initialize(config.clone());
+    let (filters_task, filters_handle) = FiltersHandler::initialize();
+    let session = Arc::new(Session::default());
+    let (out_task, out_handle) = match results {
+        Some(tx_results) => TermOutHandler::initialize_with_results(
+            config.clone(),
+            stats_handle.tx.clone(),
+            session.clone(),
+            events.clone(),
+            tx_results,
+        ),
+        None => TermOutHandler::initialize(
+            config.clone(),
+            stats_handle.tx.clone(),
+            session.clone(),
+            events.clone(),
+        ),
+    };
+
+    // bundle up all the disparate handles and JoinHandles (tasks)
+    let handles = Arc::new(Handles::new(
+        stats_handle,
+        filters_handle,
+        out_handle,
+        session,
+        events,
+        config.clone(),
+    ));
+
+    let (scan_task, scan_handle) = ScanHandler::initialize(handles.clone());
+
+    handles.set_scan_handle(scan_handle); // must be done after Handles initialization
+
+    // each of these handles is one that will be joined on during shutdown
+    let tasks = Tasks::new(out_task, stats_task, filters_task, scan_task);
+
+    if let Err(e) = filters::initialize(handles.clone()).await {
+        shutdown(handles, tasks).await?;
+        return Err(e);
+    }
+
+    if handles.events.enabled() {
+        let stream_handles = handles.clone();
+        tokio::spawn(async move { event_stream::tick_statistics(stream_handles).await });
+    }
+
+    if !config.time_limit.is_empty() {
+        // --time-limit value not an empty string, need to kick off the thread that enforces
+        // the limit
+        let time_handles = handles.clone();
+        tokio::spawn(async move { scan_manager::start_max_time_thread(time_handles).await });
+    }
+
+    if config.save_state
+        && (!config.checkpoint_interval.is_empty() || config.checkpoint_requests > 0)
+    {
+        // --checkpoint-interval and/or --checkpoint-requests used, need to kick off the thread
+        // that writes the state file while scanning
+        let checkpoint_handles = handles.clone();
+        tokio::spawn(
+            async move { scan_manager::start_checkpoint_thread(checkpoint_handles).await },
+        );
+    }
+
+    log::trace!("exit: start");
+    Ok((handles, tasks))
+}
+
+/// --tor: make sure requests actually leave through Tor before anything reaches a target
+///
+/// the check goes to check.torproject.org rather than a target, so it's sent through the same
+/// proxy as the scan, but without any of the -H headers
+pub async fn check_tor(config: &Configuration) -> Result<()> {
+    if !config.tor {
+        return Ok(());
+    }
+
+    let exit = match config.third_party_client() {
+        Ok(client) => tor::check_exit(&client, tor::TOR_CHECK_URL).await,
+        Err(e) => Err(e),
+    };
+
+    match exit {
+        Ok(ip) => {
+            log::info!("Requests leave through Tor from {}", ip);
+            Ok(())
+        }
+        Err(e) => {
+            let msg = fmt_err(&e.to_string());
+            Err(ExitError::new(ExitCode::Unreachable, &msg).into())
+        }
+    }
+}
+
+/// Get the given targets ready to be scanned: check the Tor exit (--tor), log in ([login]), and
+/// discard non-responsive targets
+///
+/// the live targets are returned, and announced on the event stream
+pub async fn prepare(targets: &[String], handles: Arc<Handles>) -> Result<Vec<String>> {
+    log::trace!("enter: prepare({:?})", targets);
+
+    let config = handles.config.clone();
+
+    check_tor(&config).await?;
+
+    if config.login.is_some() {
+        // [login]: scans start out with a fresh session
+        if let Err(e) = login::login(&config, &handles.session).await {
+            bail!(fmt_err(&format!("Could not log in: {}", e)));
+        }
+
+        // a session that's a jwt is renewed shortly before it expires, rather than after a
+        // wall of 401s; the renewals stop when the scan shuts down
+        handles.spawn_background(jwt::refresh_before_expiry(
+            config.clone(),
+            handles.session.clone(),
+        ));
+    }
+
+    jwt::warn_about_expiry(&config, &handles.session);
+
+    let live_targets = match heuristics::HeuristicTests::new(handles.clone())
+        .connectivity(targets)
+        .await
+    {
+        Ok(live) => live,
+        Err(e) => {
+            let msg = fmt_err(&e.to_string());
+            return Err(ExitError::new(ExitCode::Unreachable, &msg).into());
+        }
+    };
+
+    if live_targets.is_empty() {
+        let msg = fmt_err("Could not find any live targets to scan");
+        return Err(ExitError::new(ExitCode::Unreachable, &msg).into());
+    }
+
+    handles
+        .events
+        .emit("start", json!({ "targets": live_targets }));
+
+    log::trace!("exit: prepare -> {:?}", live_targets);
+    Ok(live_targets)
+}
+
+/// Wait for all scans to finish, report what's only known once they have (muted clusters,
+/// summaries, changes), and shut down the event handlers
+///
+/// the scan's event stream is closed once everything's been emitted; the last scan in the
+/// process to shut down also exports any remaining spans
+pub async fn shutdown(handles: Arc<Handles>, tasks: Tasks) -> Result<()> {
+    log::trace!("enter: shutdown({:?}, {:?})", handles, tasks);
+
+    let result = stop_handlers(handles.clone(), tasks).await;
+
+    // i.e. the `[login]` token refresh, which would otherwise keep logging in to the target
+    handles.abort_background_tasks();
+
+    if handles.events.enabled() {
+        let stats = serde_json::to_value(&*handles.stats.data).unwrap_or_default();
+        handles.events.emit("complete", stats);
+    }
+
+    handles.events.finish().await;
+
+    if RUNNING.fetch_sub(1, Ordering::SeqCst) == 1 {
+        trace::shutdown(&handles.config.trace_output).await;
+    }
+
+    log::trace!("exit: shutdown");
+    result
+}
+
+/// Wait for all scans to finish, report on them, and stop the event handlers, see `shutdown`
+async fn stop_handlers(handles: Arc<Handles>, tasks: Tasks) -> Result<()> {
+    let (tx, rx) = oneshot::channel::<bool>();
+    handles.send_scan_command(JoinTasks(tx))?;
+    rx.await?;
+
+    log::info!("All scans complete!");
+
+    // muted response clusters are summarized once, after all scans have finished
+    handles.filters.data.print_cluster_summary();
+
+    // harvested emails/usernames, header issues, etc are summarized across all scans
+    analyzers::report_summaries(handles.clone())?;
+
+    // content hashes are compared against (and then saved to) the --track-changes file
+    scan_manager::report_changes(handles.clone())?;
+
+    // updates still sitting in a statistics batch are counted before the results stream ends,
+    // so that statistics read once it has are final; the output handler goes first, since it
+    // also sends updates (i.e. resources discovered) while working through its queue
+    handles.output.sync(false).await?;
+    handles.stats.sync().await?;
+
+    // terminal handler closes file handler if one is in use
+    handles.output.send(Exit)?;
+    tasks.terminal.await??;
+    log::trace!("terminal handler closed");
+
+    handles.filters.send(Exit)?;
+    tasks.filters.await??;
+    log::trace!("filters handler closed");
+
+    handles.stats.send(Exit)?;
+    tasks.stats.await??;
+    log::trace!("stats handler closed");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::{
+        sync::mpsc,
+        time::{timeout, Duration},
+    };
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// tasks running alongside a scan (i.e. the `[login]` token refresh) stop when it shuts down
+    async fn shutdown_aborts_background_tasks() {
+        let (tx_results, _rx_results) = mpsc::unbounded_channel();
+        let (handles, tasks) = start(Arc::new(Configuration::default()), Some(tx_results))
+            .await
+            .unwrap();
+
+        let (tx_alive, rx_alive) = oneshot::channel::<()>();
+
+        handles.spawn_background(async move {
+            // never sends; the sender is only dropped once the task is aborted
+            let _alive = tx_alive;
+            futures::future::pending::<()>().await;
+        });
+
+        shutdown(handles, tasks).await.unwrap();
+
+        let stopped = timeout(Duration::from_secs(5), rx_alive).await;
+        assert!(matches!(stopped, Ok(Err(_))));
+    }
+}
@@ -5,12 +5,17 @@ use std::sync::{Arc, RwLock};
 use std::time::Instant;
 
 use anyhow::{Context, Result};
+use console::user_attended;
 use env_logger::Builder;
+use log::Level;
+use serde_json::json;
 
 use crate::{
-    config::Configuration,
+    config::{Configuration, OutputLevel},
+    event_stream,
     message::FeroxMessage,
     progress::PROGRESS_PRINTER,
+    scan_id,
     traits::FeroxSerialize,
     utils::{fmt_err, write_to},
 };
@@ -18,6 +23,8 @@ use crate::{
 /// Create a customized instance of
 /// [env_logger::Logger](https://docs.rs/env_logger/latest/env_logger/struct.Logger.html)
 /// with timer offset/color and set the log level based on `verbosity`
+///
+/// `--log-format json` writes each entry as a single line of JSON instead
 pub fn initialize(config: Arc<Configuration>) -> Result<()> {
     // use occurrences of -v on commandline to or verbosity = N in feroxconfig.toml to set
     // log level for the application; respects already specified RUST_LOG environment variable
@@ -37,6 +44,7 @@ pub fn initialize(config: Arc<Configuration>) -> Result<()> {
 
     let start = Instant::now();
     let mut builder = Builder::from_default_env();
+    let json_logs = config.log_format == "json";
 
     let file = if !config.debug_log.is_empty() {
         let f = OpenOptions::new() // std fs
@@ -65,11 +73,36 @@ pub fn initialize(config: Arc<Configuration>) -> Result<()> {
                 kind: "log".to_string(),
             };
 
-            PROGRESS_PRINTER.println(&log_entry.as_str());
+            let line = if json_logs {
+                scan_id::stamp(log_entry.as_json().unwrap_or_default())
+            } else {
+                log_entry.as_str()
+            };
+
+            if json_logs || matches!(config.output_level, OutputLevel::Silent) || !user_attended() {
+                // stdout is reserved for results when --silent is used, json logs are meant for
+                // machines, and there are no bars to print around when stdout isn't a terminal
+                eprint!("{}", line);
+            } else {
+                PROGRESS_PRINTER.println(&line);
+            }
+
+            if record.level() <= Level::Warn {
+                // --event-stream: warnings and errors, whichever of them are logged, to every
+                // scan's stream
+                event_stream::broadcast(
+                    "error",
+                    json!({
+                        "level": log_entry.level,
+                        "message": log_entry.message,
+                        "module": log_entry.module,
+                    }),
+                );
+            }
 
             if let Some(buffered_file) = file.clone() {
                 if let Ok(mut unlocked) = buffered_file.write() {
-                    let _ = write_to(&log_entry, &mut unlocked, config.json);
+                    let _ = write_to(&log_entry, &mut *unlocked, config.json || json_logs);
                 }
             }
 
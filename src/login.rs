@@ -0,0 +1,566 @@
+//! log in with a scripted request before scanning, and log in again whenever responses show that
+//! the session expired (`[login]` in the config file)
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, CONTENT_TYPE, LOCATION, SET_COOKIE},
+    Method, StatusCode, Url,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+
+use crate::{
+    client,
+    config::Configuration,
+    response::FeroxResponse,
+    utils::{send_to_target, target_request},
+};
+
+/// minimum time between two logins after the session expired; keeps an endpoint that always
+/// answers with a 401 from turning into a login per request
+const RELOGIN_INTERVAL: Duration = Duration::from_secs(10);
+
+lazy_static! {
+    /// `{env:NAME}` placeholder in a login body
+    static ref ENV_PLACEHOLDER: Regex = Regex::new(r"\{env:([A-Za-z_][A-Za-z0-9_]*)\}").unwrap();
+}
+
+/// default method of the login request
+fn method() -> String {
+    String::from("POST")
+}
+
+/// default Content-Type of the login request's body
+fn content_type() -> String {
+    String::from("application/x-www-form-urlencoded")
+}
+
+/// default header a token is sent with
+fn token_header() -> String {
+    String::from("Authorization: Bearer {token}")
+}
+
+/// A request that logs in, along with how to tell that it worked and what to keep from it
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct Login {
+    /// url the login request is sent to
+    pub url: String,
+
+    /// http method of the login request
+    #[serde(default = "method")]
+    pub method: String,
+
+    /// body of the login request; `{env:NAME}` is replaced with the environment variable `NAME`,
+    /// which keeps passwords out of the config file
+    #[serde(default)]
+    pub body: String,
+
+    /// Content-Type of `body`
+    #[serde(default = "content_type")]
+    pub content_type: String,
+
+    /// regex that must match the login response's headers or body for the login to succeed;
+    /// without one, any response that isn't a 4xx/5xx succeeds
+    #[serde(default)]
+    pub success_regex: String,
+
+    /// names of the cookies kept from the login response's Set-Cookie headers; all of them when
+    /// empty
+    #[serde(default)]
+    pub cookies: Vec<String>,
+
+    /// regex whose first capture group, found in the login response's body, is a session token
+    #[serde(default)]
+    pub token_regex: String,
+
+    /// header the token is sent with; `{token}` is replaced with the token
+    #[serde(default = "token_header")]
+    pub token_header: String,
+
+    /// regex that, matched against a response's body, means the session expired; 401s and
+    /// redirects to `url` always do
+    #[serde(default)]
+    pub expired_regex: String,
+
+    /// other hosts the session is sent to; only requests to the login url's host carry it
+    /// otherwise, which keeps the session from leaking to other --url targets and third parties
+    #[serde(default)]
+    pub hosts: Vec<String>,
+}
+
+impl Login {
+    /// Problems with the values of this login, worded like `Configuration::validate`'s
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if let Err(e) = Url::parse(&self.url) {
+            problems.push(format!("Invalid login url {}: {}", self.url, e));
+        }
+
+        if Method::from_bytes(self.method.to_uppercase().as_bytes()).is_err() {
+            problems.push(format!("Invalid login method {}", self.method));
+        }
+
+        for (name, pattern) in [
+            ("success_regex", &self.success_regex),
+            ("token_regex", &self.token_regex),
+            ("expired_regex", &self.expired_regex),
+        ]
+        .iter()
+        {
+            if let Err(e) = Regex::new(pattern) {
+                problems.push(format!("Invalid login {} {:?}: {}", name, pattern, e));
+            }
+        }
+
+        if !self.token_regex.is_empty() && !self.token_header.contains(':') {
+            problems.push(format!(
+                "Invalid login token_header {}, expected NAME: VALUE",
+                self.token_header
+            ));
+        }
+
+        problems
+    }
+}
+
+/// Headers that carry the current session, sent with every request to the session's hosts once
+/// logged in
+///
+/// each scan has its own (see `Handles`), so scans with different `[login]`s don't send each
+/// other's cookies and tokens
+#[derive(Debug, Default)]
+pub struct Session {
+    /// Cookie and/or token headers from the last successful login
+    headers: RwLock<Vec<(HeaderName, HeaderValue)>>,
+
+    /// hosts the headers are sent to: the login url's, and the login's `hosts`
+    hosts: RwLock<Vec<String>>,
+
+    /// compiled `expired_regex` of the login that established the session
+    expired: RwLock<Option<Regex>>,
+
+    /// number of successful logins so far
+    generation: AtomicUsize,
+
+    /// when the session was last renewed; held while logging in again, so that requests that
+    /// find the session expired at the same time only log in once
+    last_relogin: Mutex<Option<Instant>>,
+}
+
+impl Session {
+    /// headers to send with the next request to the given url; empty when not logged in, or when
+    /// the url isn't on one of the session's hosts
+    pub fn headers_for(&self, url: &Url) -> Vec<(HeaderName, HeaderValue)> {
+        let host = url.host_str().unwrap_or_default();
+
+        let allowed = self.hosts.read().map_or(false, |hosts| {
+            hosts
+                .iter()
+                .any(|allowed| allowed.eq_ignore_ascii_case(host))
+        });
+
+        if !allowed {
+            return Vec::new();
+        }
+
+        self.headers
+            .read()
+            .map(|headers| headers.clone())
+            .unwrap_or_default()
+    }
+
+    /// number of successful logins so far; lets a request that found the session expired tell
+    /// whether it was renewed since the request was sent
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::SeqCst)
+    }
+
+    /// replace the session with the given headers, sent to the given hosts
+    fn set(
+        &self,
+        headers: Vec<(HeaderName, HeaderValue)>,
+        hosts: Vec<String>,
+        expired: Option<Regex>,
+    ) {
+        if let Ok(mut current) = self.headers.write() {
+            *current = headers;
+        }
+
+        if let Ok(mut current) = self.hosts.write() {
+            *current = hosts;
+        }
+
+        if let Ok(mut current) = self.expired.write() {
+            *current = expired;
+        }
+
+        self.generation.fetch_add(1, Ordering::SeqCst);
+    }
+
+    /// Whether the given response shows that the session expired: a 401, a redirect to the login
+    /// page, or a body that matches `expired_regex`
+    pub fn expired(&self, response: &FeroxResponse, login: &Login) -> bool {
+        if *response.status() == StatusCode::UNAUTHORIZED {
+            return true;
+        }
+
+        if response.status().is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| response.url().join(value).ok());
+
+            if let (Some(location), Ok(login_url)) = (location, Url::parse(&login.url)) {
+                if location.host() == login_url.host() && location.path() == login_url.path() {
+                    return true;
+                }
+            }
+        }
+
+        match self.expired.read() {
+            Ok(expired) => expired
+                .as_ref()
+                .map_or(false, |pattern| pattern.is_match(response.text())),
+            Err(_) => false,
+        }
+    }
+}
+
+/// `template` with each `{env:NAME}` replaced by the environment variable `NAME`
+fn fill_template(template: &str) -> Result<String> {
+    let mut missing = None;
+
+    let filled = ENV_PLACEHOLDER.replace_all(template, |captures: &regex::Captures| {
+        env::var(&captures[1]).unwrap_or_else(|_| {
+            missing = Some(captures[1].to_string());
+            String::new()
+        })
+    });
+
+    if let Some(name) = missing {
+        bail!(
+            "environment variable {} used by the login body isn't set",
+            name
+        );
+    }
+
+    Ok(filled.to_string())
+}
+
+/// `name=value` of each cookie set by the given headers, limited to `wanted` unless it's empty
+pub(crate) fn session_cookies(headers: &HeaderMap, wanted: &[String]) -> Vec<String> {
+    headers
+        .get_all(SET_COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| value.split(';').next())
+        .map(str::trim)
+        .filter(|cookie| {
+            let name = cookie.split('=').next().unwrap_or_default();
+            cookie.contains('=') && (wanted.is_empty() || wanted.iter().any(|w| w == name))
+        })
+        .map(String::from)
+        .collect()
+}
+
+/// Send the login request from the configuration's `[login]` and, when it succeeds, store the
+/// session it established in `session`, to be sent with every request that follows
+pub async fn login(config: &Configuration, session: &Session) -> Result<()> {
+    log::trace!("enter: login");
+
+    let login = config
+        .login
+        .as_ref()
+        .ok_or_else(|| anyhow!("no [login] was configured"))?;
+
+    let proxy = if config.proxy.is_empty() {
+        None
+    } else {
+        Some(config.proxy.as_str())
+    };
+
+    // redirects aren't followed, since the session cookie is usually set by the redirect
+    let client = client::initialize(
+        config.timeout,
+        &config.user_agent,
+        false,
+        config.insecure,
+        &config.headers,
+        proxy,
+        &config.request_context,
+    )?;
+
+    let method = Method::from_bytes(login.method.to_uppercase().as_bytes())?;
+    let url = Url::parse(&login.url)?;
+    let mut request = target_request(&config.request_context, &client, &method, &url);
+
+    if !login.body.is_empty() {
+        request = request
+            .header(CONTENT_TYPE, &login.content_type)
+            .body(fill_template(&login.body)?);
+    }
+
+    let response = send_to_target(&config.request_context, request, &url)
+        .await
+        .with_context(|| format!("Could not reach {}", login.url))?;
+
+    let status = response.status();
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_default();
+
+    if login.success_regex.is_empty() {
+        if status.is_client_error() || status.is_server_error() {
+            bail!("Login to {} failed with status {}", login.url, status);
+        }
+    } else {
+        let transcript = headers
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value.to_str().unwrap_or_default()))
+            .chain(std::iter::once(body.clone()))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        if !Regex::new(&login.success_regex)?.is_match(&transcript) {
+            bail!(
+                "Login to {} failed, its response didn't match {:?}",
+                login.url,
+                login.success_regex
+            );
+        }
+    }
+
+    let mut established = Vec::new();
+    let cookies = session_cookies(&headers, &login.cookies);
+
+    if !cookies.is_empty() {
+        // cookies given with --headers are kept alongside the session's
+        let mut cookie = config
+            .headers
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case("cookie"))
+            .map(|(_, value)| vec![value.trim().to_string()])
+            .unwrap_or_default();
+
+        cookie.extend(cookies);
+        established.push((
+            reqwest::header::COOKIE,
+            HeaderValue::from_str(&cookie.join("; "))?,
+        ));
+    }
+
+    if !login.token_regex.is_empty() {
+        let token = Regex::new(&login.token_regex)?
+            .captures(&body)
+            .and_then(|captures| captures.get(1))
+            .map(|token| token.as_str().to_string())
+            .ok_or_else(|| anyhow!("No token matching {:?} was found", login.token_regex))?;
+
+        let (name, value) = login
+            .token_header
+            .split_once(':')
+            .ok_or_else(|| anyhow!("Invalid login token_header {}", login.token_header))?;
+
+        established.push((
+            HeaderName::from_bytes(name.trim().as_bytes())?,
+            HeaderValue::from_str(&value.trim().replace("{token}", &token))?,
+        ));
+    }
+
+    if established.is_empty() {
+        bail!("Login to {} didn't set a session cookie", login.url);
+    }
+
+    let expired = if login.expired_regex.is_empty() {
+        None
+    } else {
+        Some(Regex::new(&login.expired_regex)?)
+    };
+
+    let mut hosts = vec![url.host_str().unwrap_or_default().to_string()];
+    hosts.extend(login.hosts.iter().map(|host| host.trim().to_string()));
+
+    session.set(established, hosts, expired);
+
+    log::info!("Logged in at {}", login.url);
+    log::trace!("exit: login");
+    Ok(())
+}
+
+/// Log in again after a response sent during the session numbered `generation` showed that it
+/// expired; returns whether the request should be sent again with a new session
+///
+/// when the session was already renewed by another request, nothing is sent; otherwise logins are
+/// at least `RELOGIN_INTERVAL` apart
+pub async fn relogin(config: &Configuration, session: &Session, generation: usize) -> bool {
+    let mut last_relogin = session.last_relogin.lock().await;
+
+    if session.generation() != generation {
+        return true;
+    }
+
+    if matches!(*last_relogin, Some(last) if last.elapsed() < RELOGIN_INTERVAL) {
+        return false;
+    }
+
+    *last_relogin = Some(Instant::now());
+
+    match login(config, session).await {
+        Ok(_) => {
+            log::info!("Session expired, logged in again");
+            true
+        }
+        Err(e) => {
+            log::warn!("Session expired, could not log in again: {}", e);
+            false
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::POST, MockServer};
+    use std::collections::HashMap;
+
+    /// login to the given url that keeps the `session` cookie
+    fn test_login(url: &str) -> Login {
+        Login {
+            url: url.to_string(),
+            method: method(),
+            body: String::from("user=admin&pass={env:FEROX_TEST_LOGIN_PASS}"),
+            content_type: content_type(),
+            success_regex: String::from("(?i)location: /dashboard"),
+            cookies: vec![String::from("session")],
+            token_regex: String::new(),
+            token_header: token_header(),
+            expired_regex: String::from("Please log in"),
+            hosts: vec![String::from("api.localhost")],
+        }
+    }
+
+    #[test]
+    /// placeholders are filled from the environment, and unset variables are errors
+    fn fill_template_reads_environment() {
+        env::set_var("FEROX_TEST_TEMPLATE", "hunter2");
+
+        assert_eq!(
+            fill_template("user=admin&pass={env:FEROX_TEST_TEMPLATE}").unwrap(),
+            "user=admin&pass=hunter2"
+        );
+        assert!(fill_template("pass={env:FEROX_TEST_NOT_SET}").is_err());
+    }
+
+    #[test]
+    /// only the wanted cookies are kept, without their attributes
+    fn session_cookies_keeps_wanted_cookies() {
+        let mut headers = HeaderMap::new();
+        headers.append(SET_COOKIE, "session=abc; Path=/; HttpOnly".parse().unwrap());
+        headers.append(SET_COOKIE, "theme=dark".parse().unwrap());
+
+        assert_eq!(
+            session_cookies(&headers, &[String::from("session")]),
+            vec!["session=abc"]
+        );
+        assert_eq!(
+            session_cookies(&headers, &[]),
+            vec!["session=abc", "theme=dark"]
+        );
+    }
+
+    #[test]
+    /// bad urls, methods, and patterns are reported
+    fn problems_reports_bad_values() {
+        let mut login = test_login("http://localhost/login");
+        assert!(login.problems().is_empty());
+
+        login.url = String::from("nope");
+        login.method = String::from("P OST");
+        login.success_regex = String::from("(");
+        login.token_regex = String::from("token=(.+)");
+        login.token_header = String::from("Bearer {token}");
+
+        assert_eq!(login.problems().len(), 4);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a successful login sends its session cookie with later requests to its hosts, and expiry
+    /// is detected from 401s, redirects to the login page, and expired_regex
+    async fn login_sets_session_and_detects_expiry() {
+        env::set_var("FEROX_TEST_LOGIN_PASS", "hunter2");
+
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(POST)
+                .path("/login")
+                .body("user=admin&pass=hunter2");
+            then.status(302)
+                .header("Location", "/dashboard")
+                .header("Set-Cookie", "session=abc123; HttpOnly");
+        });
+
+        let mut headers = HashMap::new();
+        headers.insert(String::from("Cookie"), String::from("lang=en"));
+
+        let config = Configuration {
+            login: Some(test_login(&srv.url("/login"))),
+            headers,
+            ..Default::default()
+        };
+
+        let session = Session::default();
+        login(&config, &session).await.unwrap();
+
+        assert_eq!(mock.hits(), 1);
+        assert_eq!(session.generation(), 1);
+        let established = vec![(
+            reqwest::header::COOKIE,
+            HeaderValue::from_static("lang=en; session=abc123"),
+        )];
+        let url = |url: &str| Url::parse(url).unwrap();
+
+        assert_eq!(session.headers_for(&url(&srv.url("/admin"))), established);
+        assert_eq!(
+            session.headers_for(&url("http://api.localhost/v1")),
+            established
+        );
+        assert!(session
+            .headers_for(&url("http://elsewhere.com/"))
+            .is_empty());
+
+        let login = config.login.as_ref().unwrap();
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&srv.url("/admin"));
+        response.set_text("hello admin");
+        assert!(!session.expired(&response, login));
+
+        response.set_text("Please log in to continue");
+        assert!(session.expired(&response, login));
+
+        response.set_text("");
+        response.set_status(StatusCode::UNAUTHORIZED);
+        assert!(session.expired(&response, login));
+
+        response.set_status(StatusCode::FOUND);
+        response.add_header("location", "/login?next=/admin");
+        assert!(session.expired(&response, login));
+
+        // another scan's session is left alone
+        assert!(Session::default()
+            .headers_for(&url(&srv.url("/admin")))
+            .is_empty());
+    }
+}
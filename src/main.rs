@@ -1,7 +1,7 @@
 use std::{
     env::args,
-    fs::{create_dir, remove_file, File},
-    io::{stderr, BufRead, BufReader},
+    fs::{create_dir, remove_file},
+    io::stderr,
     ops::Index,
     path::Path,
     process::Command,
@@ -10,25 +10,28 @@ use std::{
 
 use anyhow::{bail, Context, Result};
 use futures::StreamExt;
-use tokio::{
-    io,
-    sync::{oneshot, Semaphore},
-};
+use tokio::{io, sync::Semaphore};
 use tokio_util::codec::{FramedRead, LinesCodec};
 
 use feroxbuster::{
     banner::{Banner, UPDATE_URL},
-    config::{Configuration, OutputLevel},
+    bench, buckets,
+    config::{recipe, report_check, watch, Configuration, Invocation, OutputLevel},
+    crawl_wordlist, distributed,
     event_handlers::{
-        Command::{CreateBar, Exit, JoinTasks, LoadStats, ScanInitialUrls, UpdateWordlist},
-        FiltersHandler, Handles, ScanHandler, StatsHandler, Tasks, TermInputHandler,
-        TermOutHandler, SCAN_COMPLETE,
+        Command::{CreateBar, LoadStats, ScanInitialUrls, UpdateWordlist},
+        Handles, Tasks, TermInputHandler, FINDINGS_REPORTED, SCAN_COMPLETE,
     },
-    filters, heuristics, logger,
-    progress::{PROGRESS_BAR, PROGRESS_PRINTER},
+    exit_codes::ExitCode,
+    lifecycle, logger,
+    numbers::NumberFormat,
+    product_wordlists,
+    progress::{self, PROGRESS_BAR, PROGRESS_PRINTER},
+    scan_id,
     scan_manager::{self},
-    scanner,
-    utils::{fmt_err, slugify_filename},
+    scanner, server,
+    utils::{fmt_err, load_wordlist, merge_duplicate_targets, slugify_filename},
+    wordlists,
 };
 #[cfg(not(target_os = "windows"))]
 use feroxbuster::{utils::set_open_file_limit, DEFAULT_OPEN_FILE_LIMIT};
@@ -40,37 +43,6 @@ lazy_static! {
     static ref PARALLEL_LIMITER: Semaphore = Semaphore::new(0);
 }
 
-/// Create a HashSet of Strings from the given wordlist then stores it inside an Arc
-fn get_unique_words_from_wordlist(path: &str) -> Result<Arc<Vec<String>>> {
-    log::trace!("enter: get_unique_words_from_wordlist({})", path);
-
-    let file = File::open(&path).with_context(|| format!("Could not open {}", path))?;
-
-    let reader = BufReader::new(file);
-
-    let mut words = Vec::new();
-
-    for line in reader.lines() {
-        let result = match line {
-            Ok(read_line) => read_line,
-            Err(_) => continue,
-        };
-
-        if result.starts_with('#') || result.is_empty() {
-            continue;
-        }
-
-        words.push(result);
-    }
-
-    log::trace!(
-        "exit: get_unique_words_from_wordlist -> Arc<wordlist[{} words...]>",
-        words.len()
-    );
-
-    Ok(Arc::new(words))
-}
-
 /// Determine whether it's a single url scan or urls are coming from stdin, then scan as needed
 async fn scan(targets: Vec<String>, handles: Arc<Handles>) -> Result<()> {
     log::trace!("enter: scan({:?}, {:?})", targets, handles);
@@ -78,18 +50,31 @@ async fn scan(targets: Vec<String>, handles: Arc<Handles>) -> Result<()> {
     // so that will allow for cheap/safe sharing of a single wordlist across multi-target scans
     // as well as additional directories found as part of recursion
 
-    let words = get_unique_words_from_wordlist(&handles.config.wordlist)?;
+    let mut words = load_wordlist(&handles.config)?;
 
     if words.len() == 0 {
         bail!("Did not find any words in {}", handles.config.wordlist);
     }
 
+    if handles.config.fingerprint && !handles.config.no_product_wordlists {
+        // products identified by --fingerprint have their bundled words appended
+        words = product_wordlists::extend_wordlist(words, &targets, handles.clone()).await;
+    }
+
+    if handles.config.crawl_wordlist > 0 {
+        // --crawl-wordlist: words from the targets' own pages are appended
+        words = crawl_wordlist::extend_wordlist(words, &targets, handles.clone()).await;
+    }
+
     let scanned_urls = handles.ferox_scans()?;
 
     handles.send_scan_command(UpdateWordlist(words.clone()))?;
 
     scanner::initialize(words.len(), handles.clone()).await?;
 
+    // the whole wordlist, once per target (and once more per extension), before any recursion
+    scanner::warn_about_size(targets.len(), words.len(), handles.clone());
+
     // at this point, the stat thread's progress bar can be created; things that needed to happen
     // first:
     // - banner gets printed
@@ -150,7 +135,8 @@ async fn get_targets(handles: Arc<Handles>) -> Result<Vec<String>> {
                 targets.push(scan.url().to_owned());
             }
         };
-    } else {
+    } else if !handles.config.target_url.is_empty() {
+        // --buckets doesn't need a url
         targets.push(handles.config.target_url.clone());
     }
 
@@ -161,7 +147,7 @@ async fn get_targets(handles: Arc<Handles>) -> Result<Vec<String>> {
 
 /// async main called from real main, broken out in this way to allow for some synchronous code
 /// to be executed before bringing the tokio runtime online
-async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
+async fn wrapped_main(config: Arc<Configuration>) -> Result<ExitCode> {
     // join can only be called once, otherwise it causes the thread to panic
     tokio::task::spawn_blocking(move || {
         // ok, lazy_static! uses (unsurprisingly in retrospect) a lazy loading model where the
@@ -175,35 +161,9 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
         PROGRESS_BAR.join().unwrap();
     });
 
-    // spawn all event handlers, expect back a JoinHandle and a *Handle to the specific event
-    let (stats_task, stats_handle) = StatsHandler::initialize(config.clone());
-    let (filters_task, filters_handle) = FiltersHandler::initialize();
-    let (out_task, out_handle) =
-        TermOutHandler::initialize(config.clone(), stats_handle.tx.clone());
-
-    // bundle up all the disparate handles and JoinHandles (tasks)
-    let handles = Arc::new(Handles::new(
-        stats_handle,
-        filters_handle,
-        out_handle,
-        config.clone(),
-    ));
-
-    let (scan_task, scan_handle) = ScanHandler::initialize(handles.clone());
-
-    handles.set_scan_handle(scan_handle); // must be done after Handles initialization
-
-    filters::initialize(handles.clone()).await?; // send user-supplied filters to the handler
-
-    // create new Tasks object, each of these handles is one that will be joined on later
-    let tasks = Tasks::new(out_task, stats_task, filters_task, scan_task);
-
-    if !config.time_limit.is_empty() {
-        // --time-limit value not an empty string, need to kick off the thread that enforces
-        // the limit
-        let time_handles = handles.clone();
-        tokio::spawn(async move { scan_manager::start_max_time_thread(time_handles).await });
-    }
+    // spawn all event handlers and the background tasks the configuration asks for, expect back
+    // the Handles to each of them and their JoinHandles (tasks)
+    let (handles, tasks) = lifecycle::start(config.clone(), None).await?;
 
     // can't trace main until after logger is initialized and the above task is started
     log::trace!("enter: main");
@@ -221,6 +181,9 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
         // populate FeroxScans object with previously seen scans
         scanned_urls.add_serialized_scans(&from_here)?;
 
+        // along with the responses they already reported
+        handles.output.data.add_serialized_responses(&from_here)?;
+
         // populate Stats object with previously known statistics
         handles.stats.send(LoadStats(from_here))?;
     }
@@ -235,6 +198,9 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
         }
     };
 
+    // equivalent targets (http://a, http://a/, http://a:80/) are only scanned once
+    let targets = merge_duplicate_targets(targets, handles.clone());
+
     // --parallel branch
     if config.parallel > 0 {
         log::trace!("enter: parallel branch");
@@ -291,6 +257,8 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
             String::new()
         };
 
+        let mut children = Vec::with_capacity(targets.len());
+
         // unvalidated targets fresh from stdin, just spawn children and let them do all checks
         for target in targets {
             // add the current target to the provided command
@@ -328,7 +296,7 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
 
             log::debug!("parallel exec: {} {}", bin, args.join(" "));
 
-            tokio::task::spawn_blocking(move || {
+            children.push(tokio::task::spawn_blocking(move || {
                 let result = Command::new(bin)
                     .args(&args)
                     .spawn()
@@ -338,7 +306,18 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
 
                 drop(permit);
                 result
-            });
+            }));
+        }
+
+        // each child exits with its own code; a signal-killed child counts as interrupted
+        let mut child_codes = Vec::with_capacity(children.len());
+
+        for child in children {
+            let status = child.await?;
+            let code = status
+                .code()
+                .unwrap_or_else(|| ExitCode::Interrupted.code());
+            child_codes.push(ExitCode::from_code(code));
         }
 
         // the output handler creates an empty file to which it will try to write, because
@@ -357,8 +336,10 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
             }
         }
 
-        log::trace!("exit: parallel branch && wrapped main");
-        return Ok(());
+        let exit_code = ExitCode::combine(&child_codes);
+
+        log::trace!("exit: parallel branch && wrapped main -> {:?}", exit_code);
+        return Ok(exit_code);
     }
 
     if matches!(config.output_level, OutputLevel::Default) {
@@ -370,6 +351,11 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
         // only interested in the side-effect that sets banner.update_status
         let _ = banner.check_for_updates(UPDATE_URL, handles.clone()).await;
 
+        if config.fingerprint {
+            // identified technologies are displayed as part of the banner
+            let _ = banner.fingerprint_targets(&targets, handles.clone()).await;
+        }
+
         if banner.print_to(std_stderr, config.clone()).is_err() {
             clean_up(handles, tasks).await?;
             bail!(fmt_err("Could not print banner"));
@@ -390,24 +376,45 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
         }
     }
 
-    // discard non-responsive targets
-    let live_targets = {
-        let test = heuristics::HeuristicTests::new(handles.clone());
-        let result = test.connectivity(&targets).await;
-        if result.is_err() {
+    if !config.buckets.is_empty() {
+        // --buckets: cloud storage is checked instead of scanning targets, but still through Tor
+        if let Err(e) = lifecycle::check_tor(&config).await {
             clean_up(handles, tasks).await?;
-            bail!(fmt_err(&result.unwrap_err().to_string()));
+            return Err(e);
         }
-        result?
-    };
 
-    if live_targets.is_empty() {
+        let result = buckets::scan_buckets(handles.clone()).await;
+
         clean_up(handles, tasks).await?;
-        bail!(fmt_err("Could not find any live targets to scan"));
+
+        if let Err(e) = result {
+            bail!(fmt_err(&format!("Failed while looking for buckets: {}", e)));
+        }
+
+        let exit_code = ExitCode::from_findings(FINDINGS_REPORTED.load(Ordering::Relaxed));
+
+        log::trace!("exit: wrapped_main -> {:?}", exit_code);
+        return Ok(exit_code);
     }
 
-    // kick off a scan against any targets determined to be responsive
-    match scan(live_targets, handles.clone()).await {
+    // check the tor exit, log in, and discard non-responsive targets
+    let live_targets = match lifecycle::prepare(&targets, handles.clone()).await {
+        Ok(live) => live,
+        Err(e) => {
+            clean_up(handles, tasks).await?;
+            return Err(e);
+        }
+    };
+
+    // kick off a scan against any targets determined to be responsive; with --coordinate, the
+    // scan is handed out to workers instead of being run here
+    let result = if config.coordinate.is_empty() {
+        scan(live_targets, handles.clone()).await
+    } else {
+        distributed::coordinate(live_targets, handles.clone()).await
+    };
+
+    match result {
         Ok(_) => {}
         Err(e) => {
             clean_up(handles, tasks).await?;
@@ -415,10 +422,26 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
         }
     }
 
+    let config = handles.config.clone();
+    let responses = handles.output.data.clone();
+
     clean_up(handles, tasks).await?;
 
-    log::trace!("exit: wrapped_main");
-    Ok(())
+    // --expect-found|--expect-absent are checked against everything reported during the scan
+    let violations = scan_manager::check_expectations(&config, &responses);
+
+    for violation in &violations {
+        eprintln!("{}", fmt_err(violation));
+    }
+
+    let exit_code = if violations.is_empty() {
+        ExitCode::from_findings(FINDINGS_REPORTED.load(Ordering::Relaxed))
+    } else {
+        ExitCode::ExpectationsFailed
+    };
+
+    log::trace!("exit: wrapped_main -> {:?}", exit_code);
+    Ok(exit_code)
 }
 
 /// Single cleanup function that handles all the necessary drops/finishes etc required to gracefully
@@ -426,24 +449,8 @@ async fn wrapped_main(config: Arc<Configuration>) -> Result<()> {
 async fn clean_up(handles: Arc<Handles>, tasks: Tasks) -> Result<()> {
     log::trace!("enter: clean_up({:?}, {:?})", handles, tasks);
 
-    let (tx, rx) = oneshot::channel::<bool>();
-    handles.send_scan_command(JoinTasks(tx))?;
-    rx.await?;
-
-    log::info!("All scans complete!");
-
-    // terminal handler closes file handler if one is in use
-    handles.output.send(Exit)?;
-    tasks.terminal.await??;
-    log::trace!("terminal handler closed");
-
-    handles.filters.send(Exit)?;
-    tasks.filters.await??;
-    log::trace!("filters handler closed");
-
-    handles.stats.send(Exit)?;
-    tasks.stats.await??;
-    log::trace!("stats handler closed");
+    // same shutdown as scans started through the api, followed by what's only done on a terminal
+    lifecycle::shutdown(handles.clone(), tasks).await?;
 
     // mark all scans complete so the terminal input handler will exit cleanly
     SCAN_COMPLETE.store(true, Ordering::Relaxed);
@@ -452,37 +459,128 @@ async fn clean_up(handles: Arc<Handles>, tasks: Tasks) -> Result<()> {
     // the final trace messages above
     PROGRESS_PRINTER.finish();
 
+    if matches!(handles.config.output_level, OutputLevel::Default)
+        && handles.stats.data.requests() > 0
+    {
+        // requests, response times, status codes, and errors across all scans; only shown
+        // alongside the banner (no summary on --quiet|--silent)
+        let numbers = NumberFormat::from_config(&handles.config);
+        eprint!("{}", handles.stats.data.summary(&numbers));
+    }
+
     log::trace!("exit: clean_up");
     Ok(())
 }
 
-fn main() -> Result<()> {
-    let config = Arc::new(Configuration::new().with_context(|| "Could not create Configuration")?);
+/// Carry out whatever the command line asked for
+fn run() -> Result<ExitCode> {
+    let invocation =
+        Configuration::from_command_line().with_context(|| "Could not create Configuration")?;
+
+    match invocation {
+        Invocation::Scan(config) => run_scan(Arc::new(*config)),
+        Invocation::Recipe(config, recipe_args) => recipe::run(&config, &recipe_args),
+        Invocation::Watch(config, watch_args) => {
+            // only returns when interrupted
+            watch::run(&config, &watch_args)
+        }
+        Invocation::Serve(serve_args) => {
+            // runs until ctrl+c
+            server::run(&serve_args)?;
+            Ok(ExitCode::NoFindings)
+        }
+        Invocation::Worker(worker_args) => {
+            // scans shards handed out by a coordinator
+            distributed::run(&worker_args)?;
+            Ok(ExitCode::NoFindings)
+        }
+        Invocation::Bench(bench_args) => {
+            bench::run(&bench_args)?;
+            Ok(ExitCode::NoFindings)
+        }
+        Invocation::Wordlists(args) => {
+            // unwrap is fine, it's only a Wordlists invocation if the subcommand was used
+            let wordlist_args = args.subcommand_matches("wordlists").unwrap();
+            wordlists::run(&args, wordlist_args)?;
+            Ok(ExitCode::NoFindings)
+        }
+        Invocation::ConfigCheck(problems) => {
+            report_check(&problems)?;
+            Ok(ExitCode::NoFindings)
+        }
+        Invocation::Usage(e) if e.use_stderr() => {
+            // usage errors exit with ConfigError instead of clap's default of 1
+            eprintln!("{}", e.message);
+            Ok(ExitCode::ConfigError)
+        }
+        Invocation::Usage(e) => e.exit(), // --help and --version
+        Invocation::Nothing => Ok(ExitCode::NoFindings),
+    }
+}
+
+/// Everything a scan needs, from logging to the tokio runtime, before handing off to wrapped_main
+fn run_scan(config: Arc<Configuration>) -> Result<ExitCode> {
+    // the scan id is stamped on every json record written from here on, logs included
+    scan_id::initialize(&config);
 
     // setup logging based on the number of -v's used
-    if matches!(
-        config.output_level,
-        OutputLevel::Default | OutputLevel::Quiet
-    ) {
-        // don't log on --silent
+    if !matches!(config.output_level, OutputLevel::Silent) || config.verbosity > 0 {
+        // --silent only logs when asked to with -v, and then only to stderr
         logger::initialize(config.clone())?;
     }
 
+    if !config.save_config.is_empty() {
+        // written before the scan starts, so the configuration survives a scan that's cut short
+        config.save()?;
+    }
+
+    // must happen before anything is drawn, or the first few redraws use the default rate
+    progress::set_refresh_rate(config.refresh_rate);
+
     // this function uses rlimit, which is not supported on windows
     #[cfg(not(target_os = "windows"))]
     set_open_file_limit(DEFAULT_OPEN_FILE_LIMIT);
 
-    if let Ok(runtime) = tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()
-    {
+    let mut exit_code = ExitCode::NoFindings;
+
+    let mut builder = tokio::runtime::Builder::new_multi_thread();
+    builder.enable_all();
+
+    // the runtime's os threads are sized separately from --threads, which only bounds the
+    // number of requests in flight
+    if config.async_workers > 0 {
+        builder.worker_threads(config.async_workers);
+    }
+
+    if config.blocking_threads > 0 {
+        builder.max_blocking_threads(config.blocking_threads);
+    }
+
+    if let Ok(runtime) = builder.build() {
         let future = wrapped_main(config);
-        if let Err(e) = runtime.block_on(future) {
-            eprintln!("{}", e);
+        exit_code = match runtime.block_on(future) {
+            Ok(code) => code,
+            Err(e) => {
+                eprintln!("{}", e);
+                ExitCode::from_error(&e)
+            }
         };
     }
 
-    log::trace!("exit: main");
+    log::trace!("exit: main -> {:?}", exit_code);
 
-    Ok(())
+    Ok(exit_code)
+}
+
+fn main() {
+    let exit_code = match run() {
+        Ok(code) => code,
+        Err(e) => {
+            // same format used when main returns an Err
+            eprintln!("Error: {:?}", e);
+            ExitCode::from_error(&e)
+        }
+    };
+
+    std::process::exit(exit_code.code());
 }
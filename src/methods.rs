@@ -0,0 +1,178 @@
+//! enumerate the http methods allowed by each scanned directory via OPTIONS requests
+use std::sync::Arc;
+
+use anyhow::Result;
+use reqwest::{
+    header::{HeaderMap, ALLOW},
+    Method, Url,
+};
+use serde_json::json;
+
+use crate::{event_handlers::Handles, finding::FeroxFinding, utils::logged_request_with_method};
+
+/// methods that allow modifying/inspecting server content and are rarely meant to be exposed
+const RISKY_METHODS: [&str; 8] = [
+    "PUT",
+    "DELETE",
+    "PROPFIND",
+    "PROPPATCH",
+    "MKCOL",
+    "MOVE",
+    "COPY",
+    "TRACE",
+];
+
+/// Return the unique methods listed in the `Allow` and `Public` (IIS) headers, uppercased, in the
+/// order they were first seen
+fn allowed_methods(headers: &HeaderMap) -> Vec<String> {
+    let mut methods: Vec<String> = Vec::new();
+
+    let listed = headers
+        .get_all(ALLOW)
+        .iter()
+        .chain(headers.get_all("public").iter())
+        .filter_map(|value| value.to_str().ok());
+
+    for value in listed {
+        for method in value.split(',') {
+            let method = method.trim().to_ascii_uppercase();
+
+            if !method.is_empty() && !methods.contains(&method) {
+                methods.push(method);
+            }
+        }
+    }
+
+    methods
+}
+
+/// Send an OPTIONS request to the given directory and report the methods it allows, along with
+/// any WebDAV compliance classes it advertises
+///
+/// `Ok(None)` is returned when the response doesn't list any methods or DAV support
+pub async fn check_methods(directory: &str, handles: Arc<Handles>) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: check_methods({}, {:?})", directory, handles);
+
+    let mut url = Url::parse(directory)?;
+
+    if !url.path().ends_with('/') {
+        url.set_path(&format!("{}/", url.path()));
+    }
+
+    let response = logged_request_with_method(&url, &Method::OPTIONS, None, handles).await?;
+
+    let allowed = allowed_methods(response.headers());
+
+    let dav = response
+        .headers()
+        .get("dav")
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim().to_string())
+        .unwrap_or_default();
+
+    if allowed.is_empty() && dav.is_empty() {
+        log::trace!("exit: check_methods -> None");
+        return Ok(None);
+    }
+
+    let risky: Vec<String> = allowed
+        .iter()
+        .filter(|method| RISKY_METHODS.contains(&method.as_str()))
+        .cloned()
+        .collect();
+
+    let mut summary = format!("allowed [{}]", allowed.join(", "));
+
+    if !risky.is_empty() {
+        summary.push_str(&format!("; risky [{}]", risky.join(", ")));
+    }
+
+    if !dav.is_empty() {
+        summary.push_str(&format!("; DAV {}", dav));
+    }
+
+    let finding = FeroxFinding::new(
+        "methods",
+        url.as_str(),
+        &summary,
+        json!({
+            "severity": if risky.is_empty() { "info" } else { "high" },
+            "allowed": allowed,
+            "risky": risky,
+            "dav": dav,
+        }),
+    );
+
+    log::trace!("exit: check_methods -> {:?}", finding);
+    Ok(Some(finding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Configuration, scan_manager::FeroxScans};
+    use httpmock::{Method::OPTIONS, MockServer};
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    /// methods from Allow and Public are uppercased and deduplicated
+    fn allowed_methods_merges_allow_and_public() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ALLOW, HeaderValue::from_static("GET, head,OPTIONS"));
+        headers.insert("public", HeaderValue::from_static("OPTIONS, PROPFIND, GET"));
+
+        assert_eq!(
+            allowed_methods(&headers),
+            vec!["GET", "HEAD", "OPTIONS", "PROPFIND"]
+        );
+        assert!(allowed_methods(&HeaderMap::new()).is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// an OPTIONS request is sent to the directory and risky methods are highlighted
+    async fn check_methods_reports_risky_methods() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(OPTIONS).path("/uploads/");
+            then.status(200)
+                .header("allow", "OPTIONS, GET, HEAD, PUT, DELETE")
+                .header("dav", "1,2");
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let scans = Arc::new(FeroxScans::default());
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        let finding = check_methods(&srv.url("/uploads"), handles)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(mock.hits(), 1);
+        assert_eq!(finding.category(), "methods");
+        assert_eq!(finding.url(), srv.url("/uploads/"));
+        assert_eq!(finding.details()["risky"], json!(["PUT", "DELETE"]));
+        assert_eq!(finding.details()["dav"], "1,2");
+        assert_eq!(finding.details()["severity"], "high");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// directories that don't advertise their methods produce no finding
+    async fn check_methods_returns_none_without_allow_header() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(OPTIONS).path("/");
+            then.status(204);
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let scans = Arc::new(FeroxScans::default());
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        let finding = check_methods(&srv.url("/"), handles).await.unwrap();
+
+        assert!(finding.is_none());
+    }
+}
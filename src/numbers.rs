@@ -0,0 +1,250 @@
+//! formatting of the counts and sizes shown in the banner, progress bars, and summaries;
+//! controlled by --thousands-separator and --byte-units
+use std::env;
+
+use crate::config::Configuration;
+
+/// locale prefixes (language[_TERRITORY]) grouped by the thousands separator they use; the
+/// first match wins, so territories are listed before their language
+const LOCALE_SEPARATORS: [(&str, &[&str]); 4] = [
+    ("'", &["de_CH", "it_CH", "fr_CH", "rm_CH"]),
+    (
+        ".",
+        &[
+            "de", "es", "it", "nl", "pt", "da", "id", "tr", "el", "ro", "sl", "hr", "sr", "vi",
+        ],
+    ),
+    (
+        " ",
+        &[
+            "fr", "ru", "pl", "cs", "sk", "sv", "nb", "nn", "no", "fi", "uk", "hu", "bg", "et",
+            "lt", "lv",
+        ],
+    ),
+    (",", &["en", "ja", "ko", "zh", "he", "th", "hi"]),
+];
+
+/// Units used when displaying a number of bytes
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ByteUnits {
+    /// plain number of bytes, i.e. 5174
+    Bytes,
+
+    /// powers of 1000, i.e. 5.2 kB
+    Si,
+
+    /// powers of 1024, i.e. 5.1 KiB
+    Iec,
+}
+
+impl Default for ByteUnits {
+    /// plain bytes, same as before units were configurable
+    fn default() -> Self {
+        ByteUnits::Bytes
+    }
+}
+
+/// Formats counts and sizes according to the user's --thousands-separator and --byte-units
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct NumberFormat {
+    /// placed between each group of three digits; empty means no grouping
+    separator: String,
+
+    /// units used for sizes
+    units: ByteUnits,
+}
+
+impl NumberFormat {
+    /// create a new `NumberFormat`; `separator` may be `locale`, in which case it's derived from
+    /// the given locale (i.e. the value of LC_ALL, LC_NUMERIC, or LANG)
+    pub fn new(separator: &str, units: &str, locale: &str) -> Self {
+        let separator = if separator == "locale" {
+            locale_separator(locale).to_string()
+        } else {
+            separator.to_string()
+        };
+
+        let units = match units {
+            "si" => ByteUnits::Si,
+            "iec" => ByteUnits::Iec,
+            _ => ByteUnits::Bytes,
+        };
+
+        Self { separator, units }
+    }
+
+    /// create a new `NumberFormat` from the given configuration and the current environment
+    pub fn from_config(config: &Configuration) -> Self {
+        let locale = ["LC_ALL", "LC_NUMERIC", "LANG"]
+            .iter()
+            .filter_map(|name| env::var(name).ok())
+            .find(|value| !value.is_empty())
+            .unwrap_or_default();
+
+        Self::new(&config.thousands_separator, &config.byte_units, &locale)
+    }
+
+    /// format a count, i.e. 1234567 -> 1,234,567
+    pub fn count(&self, count: u64) -> String {
+        let digits = count.to_string();
+
+        if self.separator.is_empty() {
+            return digits;
+        }
+
+        let mut grouped = String::with_capacity(digits.len() + digits.len() / 3);
+
+        for (index, digit) in digits.chars().enumerate() {
+            if index > 0 && (digits.len() - index) % 3 == 0 {
+                grouped.push_str(&self.separator);
+            }
+            grouped.push(digit);
+        }
+
+        grouped
+    }
+
+    /// format a count roughly, i.e. 4213977 -> 4.2M; counts below a thousand are left as they are
+    pub fn approximate(&self, count: u64) -> String {
+        let units = ["", "K", "M", "B", "T"];
+
+        let mut size = count as f64;
+        let mut unit = 0;
+
+        while size >= 1000.0 && unit < units.len() - 1 {
+            size /= 1000.0;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            return count.to_string();
+        }
+
+        format!("{}{}", self.decimal(size), units[unit])
+    }
+
+    /// format a number with a single decimal; locales that group with a period or space use a
+    /// comma as their decimal mark
+    fn decimal(&self, value: f64) -> String {
+        let decimal = format!("{:.1}", value);
+
+        if self.separator == "." || self.separator == " " {
+            decimal.replace('.', ",")
+        } else {
+            decimal
+        }
+    }
+
+    /// format a size, i.e. 1536 -> 1.5 KiB
+    pub fn bytes(&self, bytes: u64) -> String {
+        let (base, units) = match self.units {
+            ByteUnits::Bytes => return self.count(bytes),
+            ByteUnits::Si => (1000.0, ["B", "kB", "MB", "GB", "TB"]),
+            ByteUnits::Iec => (1024.0, ["B", "KiB", "MiB", "GiB", "TiB"]),
+        };
+
+        let mut size = bytes as f64;
+        let mut unit = 0;
+
+        while size >= base && unit < units.len() - 1 {
+            size /= base;
+            unit += 1;
+        }
+
+        if unit == 0 {
+            return format!("{} {}", bytes, units[0]);
+        }
+
+        format!("{} {}", self.decimal(size), units[unit])
+    }
+}
+
+/// thousands separator used by the given locale (ex: de_DE.UTF-8); unknown locales, including
+/// C and POSIX, get a comma
+fn locale_separator(locale: &str) -> &'static str {
+    for (separator, prefixes) in LOCALE_SEPARATORS.iter() {
+        if prefixes.iter().any(|prefix| locale.starts_with(prefix)) {
+            return separator;
+        }
+    }
+
+    ","
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// counts are grouped in threes, and left alone without a separator
+    fn number_format_groups_counts() {
+        let plain = NumberFormat::default();
+        assert_eq!(plain.count(1234567), "1234567");
+
+        let commas = NumberFormat::new(",", "bytes", "");
+        assert_eq!(commas.count(0), "0");
+        assert_eq!(commas.count(999), "999");
+        assert_eq!(commas.count(1000), "1,000");
+        assert_eq!(commas.count(1234567), "1,234,567");
+
+        let underscores = NumberFormat::new("_", "bytes", "");
+        assert_eq!(underscores.count(123456), "123_456");
+    }
+
+    #[test]
+    /// sizes are shown in the requested units
+    fn number_format_formats_bytes() {
+        let plain = NumberFormat::new(",", "bytes", "");
+        assert_eq!(plain.bytes(5174), "5,174");
+
+        let si = NumberFormat::new("", "si", "");
+        assert_eq!(si.bytes(999), "999 B");
+        assert_eq!(si.bytes(5174), "5.2 kB");
+        assert_eq!(si.bytes(3_000_000), "3.0 MB");
+
+        let iec = NumberFormat::new("", "iec", "");
+        assert_eq!(iec.bytes(1536), "1.5 KiB");
+        assert_eq!(iec.bytes(1024 * 1024 * 1024 * 2), "2.0 GiB");
+
+        let german = NumberFormat::new(".", "iec", "");
+        assert_eq!(german.bytes(1536), "1,5 KiB");
+    }
+
+    #[test]
+    /// rough counts are shortened to one decimal and a suffix
+    fn number_format_approximates_counts() {
+        let plain = NumberFormat::default();
+        assert_eq!(plain.approximate(999), "999");
+        assert_eq!(plain.approximate(30_000), "30.0K");
+        assert_eq!(plain.approximate(4_213_977), "4.2M");
+        assert_eq!(plain.approximate(7_500_000_000), "7.5B");
+
+        let german = NumberFormat::new(".", "bytes", "");
+        assert_eq!(german.approximate(4_213_977), "4,2M");
+    }
+
+    #[test]
+    /// locale separators are derived from the locale's language and territory
+    fn number_format_uses_locale_separators() {
+        assert_eq!(
+            NumberFormat::new("locale", "bytes", "de_DE.UTF-8").count(1000),
+            "1.000"
+        );
+        assert_eq!(
+            NumberFormat::new("locale", "bytes", "de_CH.UTF-8").count(1000),
+            "1'000"
+        );
+        assert_eq!(
+            NumberFormat::new("locale", "bytes", "fr_FR").count(1000),
+            "1 000"
+        );
+        assert_eq!(
+            NumberFormat::new("locale", "bytes", "en_US.UTF-8").count(1000),
+            "1,000"
+        );
+        assert_eq!(
+            NumberFormat::new("locale", "bytes", "C").count(1000),
+            "1,000"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+//! discover the hidden query/body parameters accepted by an endpoint
+use std::sync::Arc;
+
+use anyhow::Result;
+use reqwest::{Method, StatusCode, Url};
+use serde_json::json;
+
+use crate::{
+    csrf,
+    event_handlers::Handles,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{send_to_target, target_request, with_user_headers},
+};
+
+/// value sent with every candidate parameter; used to detect reflection in the response body
+const CANARY: &str = "ferox7331";
+
+/// number of candidate parameters sent together in a single request
+const BATCH_SIZE: usize = 32;
+
+/// extensions of static content that won't make use of parameters
+const STATIC_EXTENSIONS: [&str; 14] = [
+    "js", "css", "png", "jpg", "jpeg", "gif", "svg", "ico", "woff", "woff2", "ttf", "pdf", "txt",
+    "map",
+];
+
+/// parameter names commonly accepted by web applications
+const CANDIDATE_PARAMS: [&str; 96] = [
+    "id",
+    "user",
+    "username",
+    "name",
+    "email",
+    "password",
+    "pass",
+    "token",
+    "key",
+    "api_key",
+    "apikey",
+    "access_token",
+    "auth",
+    "session",
+    "sid",
+    "uid",
+    "user_id",
+    "account",
+    "admin",
+    "debug",
+    "test",
+    "dev",
+    "mode",
+    "action",
+    "cmd",
+    "exec",
+    "command",
+    "do",
+    "func",
+    "function",
+    "method",
+    "type",
+    "format",
+    "output",
+    "callback",
+    "jsonp",
+    "lang",
+    "language",
+    "locale",
+    "page",
+    "limit",
+    "offset",
+    "start",
+    "count",
+    "size",
+    "sort",
+    "order",
+    "orderby",
+    "dir",
+    "q",
+    "query",
+    "search",
+    "s",
+    "keyword",
+    "filter",
+    "category",
+    "cat",
+    "tag",
+    "view",
+    "template",
+    "theme",
+    "file",
+    "filename",
+    "path",
+    "folder",
+    "url",
+    "uri",
+    "redirect",
+    "redirect_uri",
+    "return",
+    "returnurl",
+    "next",
+    "continue",
+    "dest",
+    "target",
+    "src",
+    "source",
+    "ref",
+    "referrer",
+    "data",
+    "json",
+    "xml",
+    "config",
+    "include",
+    "load",
+    "read",
+    "show",
+    "preview",
+    "download",
+    "report",
+    "date",
+    "from",
+    "to",
+    "year",
+    "version",
+    "v",
+];
+
+/// The observable behavior of an endpoint in response to a request
+#[derive(Debug, Clone, PartialEq)]
+struct Behavior {
+    /// response status code
+    status: StatusCode,
+
+    /// number of words in the body, with the canary removed
+    word_count: usize,
+
+    /// number of lines in the body
+    line_count: usize,
+
+    /// number of times the canary was reflected in the body
+    reflections: usize,
+}
+
+/// Determine whether or not the given response is worth mining for parameters
+///
+/// only successful responses for non-static content are mined
+pub fn is_mining_candidate(response: &FeroxResponse) -> bool {
+    if *response.status() != StatusCode::OK {
+        return false;
+    }
+
+    let path = response.url().path().to_ascii_lowercase();
+
+    match path
+        .rsplit('/')
+        .next()
+        .and_then(|name| name.rsplit_once('.'))
+    {
+        Some((_, extension)) => !STATIC_EXTENSIONS.contains(&extension),
+        None => true,
+    }
+}
+
+/// Send the given candidate parameters to `url` using `method`, either in the query string (GET)
+/// or as a form body (POST), and capture how the endpoint behaved, along with its response
+///
+/// form bodies carry the `[csrf]` token, when there is one; a rejected token is replaced and the
+/// request sent once more
+async fn observe(
+    url: &Url,
+    method: &Method,
+    params: &[&str],
+    handles: &Arc<Handles>,
+) -> Result<(Behavior, FeroxResponse)> {
+    let mut token = if *method == Method::GET {
+        None
+    } else {
+        csrf::current(handles).await?
+    };
+
+    let mut retried = false;
+
+    let context = &handles.config.request_context;
+
+    let response = loop {
+        let mut pairs: Vec<(&str, &str)> = params.iter().map(|p| (*p, CANARY)).collect();
+        let request = target_request(context, &handles.config.client, method, url);
+        let mut request = with_user_headers(context, &handles.session, request, url, None);
+
+        if let (Some(csrf), Some(token)) = (&handles.config.csrf, &token) {
+            pairs.push((csrf.name.as_str(), token.value.as_str()));
+            request = token.add_to(csrf, &handles.session, request, &handles.config.headers);
+        }
+
+        let request = if *method == Method::GET {
+            request.query(&pairs)
+        } else {
+            request.form(&pairs)
+        };
+
+        let response = send_to_target(context, request, url).await?;
+
+        match &token {
+            Some(stale) if !retried && csrf::rejected(response.status()) => {
+                match csrf::refresh(stale, handles).await {
+                    Some(fresh) => {
+                        token = Some(fresh);
+                        retried = true;
+                    }
+                    None => break response,
+                }
+            }
+            _ => break response,
+        }
+    };
+
+    let response = FeroxResponse::from(response, true, 0, handles.config.output_level).await;
+    let body = response.text();
+
+    let behavior = Behavior {
+        status: *response.status(),
+        word_count: body.replace(CANARY, "").split_whitespace().count(),
+        line_count: body.lines().count(),
+        reflections: body.matches(CANARY).count(),
+    };
+
+    Ok((behavior, response))
+}
+
+/// Find the candidate parameters that change the behavior of the endpoint at `url`
+///
+/// candidates are sent in batches; any batch that changes the endpoint's behavior is split in
+/// half and retried until the individual parameters responsible are identified
+///
+/// the response to the last parameter identified is returned with them, as the finding's evidence
+async fn mine(
+    url: &Url,
+    method: &Method,
+    handles: &Arc<Handles>,
+) -> Result<(Vec<String>, Option<FeroxResponse>)> {
+    // a parameter the application can't know about; establishes the baseline behavior
+    let (baseline, _) = observe(url, method, &["ferox_baseline"], handles).await?;
+
+    if observe(url, method, &["ferox_baseline"], handles).await?.0 != baseline {
+        // the endpoint's content is dynamic, differences can't be attributed to parameters
+        log::debug!("{} {} is unstable, skipping parameter mining", method, url);
+        return Ok((Vec::new(), None));
+    }
+
+    let mut found = Vec::new();
+    let mut evidence = None;
+    let mut batches: Vec<Vec<&str>> = CANDIDATE_PARAMS
+        .chunks(BATCH_SIZE)
+        .map(|chunk| chunk.to_vec())
+        .collect();
+
+    while let Some(batch) = batches.pop() {
+        let (behavior, response) = observe(url, method, &batch, handles).await?;
+
+        if behavior == baseline {
+            continue;
+        }
+
+        if batch.len() == 1 {
+            found.push(batch[0].to_string());
+            evidence = Some(response);
+            continue;
+        }
+
+        let (left, right) = batch.split_at(batch.len() / 2);
+        batches.push(left.to_vec());
+        batches.push(right.to_vec());
+    }
+
+    found.sort();
+    Ok((found, evidence))
+}
+
+/// Mine the endpoint of the given response for accepted query (GET) and body (POST) parameters
+/// and return a finding for each method that accepted at least one
+pub async fn probe(response: &FeroxResponse, handles: Arc<Handles>) -> Result<Vec<FeroxFinding>> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let mut url = response.url().clone();
+    url.set_query(None);
+
+    let mut findings = Vec::new();
+
+    if handles.denied_by_regex(&url) {
+        log::trace!("exit: probe -> {:?}", findings);
+        return Ok(findings);
+    }
+
+    for (method, location) in [(Method::GET, "query"), (Method::POST, "body")].iter() {
+        if !handles.first_probe("params", &format!("{} {}", method, url)) {
+            continue;
+        }
+
+        let (parameters, evidence) = mine(&url, method, &handles).await?;
+
+        if parameters.is_empty() {
+            continue;
+        }
+
+        let summary = format!("{} {} [{}]", method, location, parameters.join(", "));
+
+        let finding = FeroxFinding::new(
+            "param",
+            url.as_str(),
+            &summary,
+            json!({
+                "method": method.as_str(),
+                "location": location,
+                "parameters": parameters,
+            }),
+        );
+
+        findings.push(match evidence {
+            Some(response) => finding.with_exchange(&response),
+            None => finding,
+        });
+    }
+
+    log::trace!("exit: probe -> {:?}", findings);
+    Ok(findings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::GET, Method::POST, MockServer};
+
+    #[test]
+    /// static content and unsuccessful responses aren't mined
+    fn is_mining_candidate_skips_static_and_unsuccessful_responses() {
+        let mut response = FeroxResponse::default();
+
+        response.set_url("http://localhost/search.php");
+        assert!(is_mining_candidate(&response));
+
+        response.set_url("http://localhost/api/users");
+        assert!(is_mining_candidate(&response));
+
+        response.set_url("http://localhost/static/app.JS");
+        assert!(!is_mining_candidate(&response));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// parameters that change the response are isolated and reported per method
+    async fn probe_reports_parameters_that_change_behavior() {
+        let srv = MockServer::start();
+
+        let debug = srv.mock(|when, then| {
+            when.method(GET)
+                .path("/report")
+                .query_param("debug", CANARY);
+            then.status(200).body("stack trace follows\nline two");
+        });
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/report");
+            then.status(200).body("nothing to see here");
+        });
+
+        srv.mock(|when, then| {
+            when.method(POST).path("/report");
+            then.status(405);
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&srv.url("/report?existing=1"));
+
+        let findings = probe(&response, handles.clone()).await.unwrap();
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].category(), "param");
+        assert_eq!(findings[0].url(), srv.url("/report"));
+        assert_eq!(findings[0].details()["method"], "GET");
+        assert_eq!(findings[0].details()["parameters"], json!(["debug"]));
+
+        // one batch containing `debug`, then halved until only `debug` remains
+        assert_eq!(debug.hits(), 1 + 5);
+
+        assert!(probe(&response, handles).await.unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// form bodies carry the token read from the [csrf] page, which is only fetched once
+    async fn observe_sends_csrf_token_with_form_bodies() {
+        let srv = MockServer::start();
+
+        let page = srv.mock(|when, then| {
+            when.method(GET).path("/form");
+            then.status(200)
+                .header("Set-Cookie", "csrftoken=abc123; Path=/")
+                .body(r#"<form><input type="hidden" name="csrf_token" value="abc123"></form>"#);
+        });
+
+        let submit = srv.mock(|when, then| {
+            when.method(POST)
+                .path("/submit")
+                .body_contains("csrf_token=abc123");
+            then.status(200).body("thanks");
+        });
+
+        let config = Configuration {
+            csrf: Some(csrf::Csrf {
+                url: srv.url("/form"),
+                name: String::from("csrf_token"),
+                regex: String::new(),
+                header: String::new(),
+            }),
+            ..Default::default()
+        };
+        let handles = Arc::new(Handles::for_testing(None, Some(Arc::new(config))).0);
+        let url = Url::parse(&srv.url("/submit")).unwrap();
+
+        for _ in 0..2 {
+            let (behavior, _) = observe(&url, &Method::POST, &["debug"], &handles)
+                .await
+                .unwrap();
+            assert_eq!(behavior.status, StatusCode::OK);
+        }
+
+        assert_eq!(page.hits(), 1);
+        assert_eq!(submit.hits(), 2);
+    }
+}
@@ -1,6 +1,7 @@
-use clap::{App, Arg, ArgGroup};
+use clap::{App, AppSettings, Arg, ArgGroup, SubCommand};
 use lazy_static::lazy_static;
 use regex::Regex;
+use reqwest::header::{HeaderName, HeaderValue};
 use std::env;
 use std::process;
 
@@ -14,8 +15,35 @@ lazy_static! {
     /// - 1d
     pub static ref TIMESPEC_REGEX: Regex =
         Regex::new(r"^(?i)(?P<n>\d+)(?P<m>[smdh])$").expect("Could not compile regex");
+
+    /// Regex used to split values passed to options that accept units into a number and its unit
+    ///
+    /// Examples of expected values that will this regex will match:
+    /// - 4096
+    /// - 10kb
+    /// - 1.5k
+    static ref UNIT_REGEX: Regex =
+        Regex::new(r"^(?P<n>\d+(\.\d+)?)(?P<u>[a-zA-Z]*)$").expect("Could not compile regex");
 }
 
+/// units accepted by options that take a number of bytes; sizes are 1024-based
+const SIZE_UNITS: [(&str, u64); 11] = [
+    ("", 1),
+    ("b", 1),
+    ("k", 1 << 10),
+    ("kb", 1 << 10),
+    ("kib", 1 << 10),
+    ("m", 1 << 20),
+    ("mb", 1 << 20),
+    ("mib", 1 << 20),
+    ("g", 1 << 30),
+    ("gb", 1 << 30),
+    ("gib", 1 << 30),
+];
+
+/// units accepted by options that take a plain count
+const COUNT_UNITS: [(&str, u64); 3] = [("", 1), ("k", 1_000), ("m", 1_000_000)];
+
 /// Create and return an instance of [clap::App](https://docs.rs/clap/latest/clap/struct.App.html), i.e. the Command Line Interface's configuration
 pub fn initialize() -> App<'static, 'static> {
     let mut app = App::new("feroxbuster")
@@ -27,14 +55,20 @@ pub fn initialize() -> App<'static, 'static> {
                 .short("w")
                 .long("wordlist")
                 .value_name("FILE")
-                .help("Path to the wordlist")
+                .help("Path to the wordlist, or the name of one installed via `feroxbuster wordlists install`")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("wordlist_cache")
+                .long("wordlist-cache")
+                .takes_value(false)
+                .help("Compile the wordlist (deduplicated) into a binary cache on first use, and load it from there on later runs")
+        )
         .arg(
             Arg::with_name("url")
                 .short("u")
                 .long("url")
-                .required_unless_one(&["stdin", "resume_from"])
+                .required_unless_one(&["stdin", "resume_from", "wizard", "buckets"])
                 .value_name("URL")
                 .multiple(true)
                 .use_delimiter(true)
@@ -48,6 +82,20 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(true)
                 .help("Number of concurrent threads (default: 50)"),
         )
+        .arg(
+            Arg::with_name("async_workers")
+                .long("async-workers")
+                .value_name("WORKERS")
+                .takes_value(true)
+                .help("Number of os threads that run the async runtime, independent of --threads (default: number of cpu cores)"),
+        )
+        .arg(
+            Arg::with_name("blocking_threads")
+                .long("blocking-threads")
+                .value_name("THREADS")
+                .takes_value(true)
+                .help("Maximum number of os threads kept for blocking work like file and dns i/o (default: 512)"),
+        )
         .arg(
             Arg::with_name("depth")
                 .short("d")
@@ -62,7 +110,54 @@ pub fn initialize() -> App<'static, 'static> {
                 .long("timeout")
                 .value_name("SECONDS")
                 .takes_value(true)
-                .help("Number of seconds before a request times out (default: 7)"),
+                .validator(valid_timeout)
+                .help("Number of seconds before a request times out; s, m, and h units are accepted (ex: 30s, 1m) (default: 7)"),
+        )
+        .arg(
+            Arg::with_name("adaptive_timeout")
+                .long("adaptive-timeout")
+                .value_name("FACTOR")
+                .takes_value(true)
+                .help("Time out requests to each host after FACTOR times its response time during the initial checks, capped by --timeout (ex: --adaptive-timeout 10)"),
+        )
+        .arg(
+            Arg::with_name("dns_ttl")
+                .long("dns-ttl")
+                .value_name("SECONDS")
+                .takes_value(true)
+                .validator(valid_timeout)
+                .help("Cache dns answers for this long instead of for their TTL; s, m, and h units are accepted (ex: --dns-ttl 10m)"),
+        )
+        .arg(
+            Arg::with_name("streams_per_connection")
+                .long("streams-per-connection")
+                .value_name("STREAMS")
+                .takes_value(true)
+                .help("When http/2 is negotiated, spread requests over enough connections to keep about this many in flight on each (default: 0, i.e. one connection per host)"),
+        )
+        .arg(
+            Arg::with_name("max_response_size")
+                .long("max-response-size")
+                .value_name("SIZE")
+                .takes_value(true)
+                .validator(valid_size)
+                .help("Stop reading a response's body after this many bytes and mark it as truncated; b, kb, mb, and gb units are accepted (ex: --max-response-size 10mb)"),
+        )
+        .arg(
+            Arg::with_name("throttle_bytes")
+                .long("throttle-bytes")
+                .value_name("SIZE")
+                .takes_value(true)
+                .validator(valid_size)
+                .help("Cap how fast response bodies are downloaded, across all requests, at this many bytes per second; b, kb, mb, and gb units are accepted (ex: --throttle-bytes 500kb)"),
+        )
+        .arg(
+            Arg::with_name("memory_limit")
+                .long("memory-limit")
+                .value_name("SIZE")
+                .takes_value(true)
+                .validator(valid_size)
+                .help("Pause new requests while feroxbuster's resident memory is over this size (linux only); b, kb, mb, and gb units are accepted (ex: --memory-limit 512mb)"),
         )
         .arg(
             Arg::with_name("verbosity")
@@ -83,6 +178,37 @@ pub fn initialize() -> App<'static, 'static> {
                     "Proxy to use for requests (ex: http(s)://host:port, socks5(h)://host:port)",
                 ),
         )
+        .arg(
+            Arg::with_name("no_env_proxy")
+                .long("no-env-proxy")
+                .takes_value(false)
+                .help(
+                    "Ignore the proxy in HTTP_PROXY, HTTPS_PROXY, and ALL_PROXY (used by default when there's no --proxy)",
+                ),
+        )
+        .arg(
+            Arg::with_name("tor")
+                .long("tor")
+                .takes_value(false)
+                .conflicts_with("proxy")
+                .help("Send requests through the local Tor daemon (socks5h://127.0.0.1:9050), checking that they leave through Tor before scanning"),
+        )
+        .arg(
+            Arg::with_name("tor_control")
+                .long("tor-control")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .requires("tor")
+                .help("Tor's control port (ex: 127.0.0.1:9051); used to switch to new circuits when the exit node looks blocked by excessive 403s/429s"),
+        )
+        .arg(
+            Arg::with_name("tor_control_password")
+                .long("tor-control-password")
+                .value_name("PASSWORD")
+                .takes_value(true)
+                .requires("tor_control")
+                .help("Password for --tor-control (default: authenticate with Tor's cookie file)"),
+        )
         .arg(
             Arg::with_name("replay_proxy")
                 .short("P")
@@ -123,14 +249,22 @@ pub fn initialize() -> App<'static, 'static> {
                 .long("silent")
                 .takes_value(false)
                 .conflicts_with("quiet")
-                .help("Only print URLs + turn off logging (good for piping a list of urls to other commands)")
+                .help("Only print raw results (urls) for piping to other commands; logging is off unless -v is used, and goes to stderr")
         )
         .arg(
             Arg::with_name("quiet")
                 .short("q")
                 .long("quiet")
                 .takes_value(false)
-                .help("Hide progress bars and banner (good for tmux windows w/ notifications)")
+                .help("Hide progress bars and banner, but keep fully formatted results (good for tmux windows w/ notifications)")
+        )
+        .arg(
+            Arg::with_name("refresh_rate")
+                .long("refresh-rate")
+                .value_name("MILLIS")
+                .takes_value(true)
+                .validator(valid_refresh_rate)
+                .help("Milliseconds between progress bar redraws, from 1 to 1000; slower redraws save bandwidth and cpu over ssh or serial consoles (default: 67, about 15 per second)"),
         )
         .arg(
             Arg::with_name("auto_tune")
@@ -145,6 +279,21 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(false)
                 .help("Automatically stop scanning when an excessive amount of errors are encountered")
         )
+        .arg(
+            Arg::with_name("adaptive_threads")
+                .long("adaptive-threads")
+                .takes_value(false)
+                .help("Grow concurrency while the target stays healthy and back off on timeouts/429s; --threads becomes the maximum")
+        )
+        .arg(
+            Arg::with_name("seen_urls")
+                .long("seen-urls")
+                .value_name("MODE")
+                .takes_value(true)
+                .case_insensitive(true)
+                .possible_values(&["exact", "bloom"])
+                .help("How urls that were already seen are tracked; bloom bounds memory on huge scans, but may rarely skip a url (default: exact)")
+        )
         .arg(
             Arg::with_name("json")
                 .long("json")
@@ -152,6 +301,12 @@ pub fn initialize() -> App<'static, 'static> {
                 .requires("output_files")
                 .help("Emit JSON logs to --output and --debug-log instead of normal text")
         )
+        .arg(
+            Arg::with_name("error_records")
+                .long("error-records")
+                .takes_value(false)
+                .help("Write each failed request (dns, connect_timeout, tls, reset, too_many_redirects, ...) to --output and --event-stream as an error record")
+        )
         .arg(
             Arg::with_name("dont_filter")
                 .short("D")
@@ -175,6 +330,13 @@ pub fn initialize() -> App<'static, 'static> {
                 .conflicts_with("url")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("config")
+                .long("config")
+                .value_name("FILE")
+                .help("Read settings from the given ferox-config.toml instead of searching the default locations")
+                .takes_value(true),
+        )
         .arg(
             Arg::with_name("debug_log")
                 .long("debug-log")
@@ -182,6 +344,145 @@ pub fn initialize() -> App<'static, 'static> {
                 .help("Output file to write log entries (use w/ --json for JSON entries)")
                 .takes_value(true),
         )
+        .arg(
+            Arg::with_name("burp_output")
+                .long("burp-output")
+                .value_name("FILE")
+                .help("Output file to write results to as Burp Suite items, requests and responses included, for import into Burp's site map")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("defectdojo_output")
+                .long("defectdojo-output")
+                .value_name("FILE")
+                .help("Output file to write results and findings to as DefectDojo's generic findings import json")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("evidence_dir")
+                .long("evidence-dir")
+                .value_name("DIR")
+                .help("Directory to save the raw request and response behind each finding to (see --evidence-severity)")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("evidence_severity")
+                .long("evidence-severity")
+                .value_name("LEVEL")
+                .takes_value(true)
+                .case_insensitive(true)
+                .possible_values(&["info", "low", "medium", "high", "critical"])
+                .requires("evidence_dir")
+                .help("Least severe finding whose request and response are saved to --evidence-dir (default: medium)")
+        )
+        .arg(
+            Arg::with_name("output_format")
+                .long("output-format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .case_insensitive(true)
+                .possible_values(&["text", "urls-with-meta"])
+                .requires("output")
+                .help("Format of results written to --output; urls-with-meta writes `url [status] [content-type]` lines for nuclei/httpx (default: text)")
+        )
+        .arg(
+            Arg::with_name("bucket_by_tech")
+                .long("bucket-by-tech")
+                .takes_value(false)
+                .requires("output")
+                .help("Also write the urls found on each target to one file per technology identified on it, next to --output (ex: results.php.txt)")
+        )
+        .arg(
+            Arg::with_name("zap_url")
+                .long("zap-url")
+                .value_name("URL")
+                .takes_value(true)
+                .help("Send every result to the OWASP ZAP instance whose api listens at URL, adding it to ZAP's sites tree (ex: http://127.0.0.1:8080)")
+        )
+        .arg(
+            Arg::with_name("zap_api_key")
+                .long("zap-api-key")
+                .value_name("KEY")
+                .takes_value(true)
+                .requires("zap_url")
+                .help("API key configured in ZAP, used with --zap-url")
+        )
+        .arg(
+            Arg::with_name("zap_active_scan")
+                .long("zap-active-scan")
+                .takes_value(false)
+                .requires("zap_url")
+                .help("Have ZAP start an active scan against every result sent with --zap-url")
+        )
+        .arg(
+            Arg::with_name("event_stream")
+                .long("event-stream")
+                .value_name("DESTINATION")
+                .takes_value(true)
+                .validator(valid_event_stream)
+                .help("Write every event (results, findings, errors, scans starting/stopping, statistics) as NDJSON while scanning to unix:PATH, tcp:HOST:PORT, or a file/FIFO")
+        )
+        .arg(
+            Arg::with_name("scan_id")
+                .long("scan-id")
+                .value_name("ID")
+                .takes_value(true)
+                .help("Identifier of this run, stamped on every JSON record written (default: a random uuid)")
+        )
+        .arg(
+            Arg::with_name("scan_id_header")
+                .long("scan-id-header")
+                .value_name("HEADER")
+                .takes_value(true)
+                .help("Send the scan id in the given header with every request (ex: --scan-id-header X-Scan-Id)")
+        )
+        .arg(
+            Arg::with_name("log_format")
+                .long("log-format")
+                .value_name("FORMAT")
+                .takes_value(true)
+                .case_insensitive(true)
+                .possible_values(&["text", "json"])
+                .help("Format of log messages; json writes one object per line, which suits log collectors (default: text)")
+        )
+        .arg(
+            Arg::with_name("trace_output")
+                .long("trace-output")
+                .value_name("ENDPOINT")
+                .takes_value(true)
+                .help("Export spans showing where time goes to a Jaeger agent (jaeger://HOST:PORT) or an OTLP collector (http://HOST:PORT); needs a build with --features trace"),
+        )
+        .arg(
+            Arg::with_name("thousands_separator")
+                .long("thousands-separator")
+                .value_name("SEPARATOR")
+                .takes_value(true)
+                .validator(valid_thousands_separator)
+                .help("Group the digits of counts shown in the banner, progress bars, and summaries; a single character, or locale to use the environment's locale (ex: --thousands-separator ,)")
+        )
+        .arg(
+            Arg::with_name("byte_units")
+                .long("byte-units")
+                .value_name("UNITS")
+                .takes_value(true)
+                .case_insensitive(true)
+                .possible_values(&["bytes", "si", "iec"])
+                .help("Units of sizes shown in the banner and summaries; si uses kB/MB, iec uses KiB/MiB (default: bytes)")
+        )
+        .arg(
+            Arg::with_name("track_changes")
+                .long("track-changes")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Report urls whose content changed, appeared, or disappeared since the last run that used FILE, then update FILE")
+        )
+        .arg(
+            Arg::with_name("save_config")
+                .long("save-config")
+                .value_name("FILE")
+                .takes_value(true)
+                .help("Write the fully resolved configuration (defaults + config file + env + cli) to FILE as TOML")
+        )
         .arg(
             Arg::with_name("user_agent")
                 .short("a")
@@ -229,6 +530,128 @@ pub fn initialize() -> App<'static, 'static> {
                     "URL(s) to exclude from recursion/scans",
                 ),
         )
+        .arg(
+            Arg::with_name("deny_url_regex")
+                .long("deny-url-regex")
+                .value_name("REGEX")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Never request a url matching the given regular expression(s), no matter where it came from (ex: --deny-url-regex logout 'delete|remove')",
+                ),
+        )
+        .arg(
+            Arg::with_name("scope")
+                .long("scope")
+                .value_name("RULE")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .validator(valid_scope_rule)
+                .help(
+                    "Host(s), *.domain(s), and ip range(s) that extracted links and redirects may lead to, beyond the host they were found on; prefix with ! to exclude (ex: --scope '*.example.com' '!dev.example.com')",
+                ),
+        )
+        .arg(
+            Arg::with_name("expect_found")
+                .long("expect-found")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help(
+                    "Path(s) that must be discovered; exits with 5 if any aren't (ex: --expect-found /login)",
+                ),
+        )
+        .arg(
+            Arg::with_name("expect_absent")
+                .long("expect-absent")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help(
+                    "Path(s) that must not be discovered; exits with 5 if any are (ex: --expect-absent /.git/)",
+                ),
+        )
+        .arg(
+            Arg::with_name("stop_on_match")
+                .long("stop-on-match")
+                .value_name("PATH")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .help(
+                    "End the scan, with its usual exit code and output, as soon as a result is found at one of the given path(s), or a finding:CATEGORY is reported (ex: --stop-on-match /.env,finding:secret)",
+                ),
+        )
+        .arg(
+            Arg::with_name("secret_patterns")
+                .long("secret-pattern")
+                .value_name("NAME=REGEX")
+                .takes_value(true)
+                .multiple(true)
+                .help(
+                    "Additional rule(s) used to find secrets in response bodies; implies --find-secrets (ex: --secret-pattern 'slack=xox[bp]-[0-9a-zA-Z-]+')",
+                ),
+        )
+        .arg(
+            Arg::with_name("seed_from")
+                .long("seed-from")
+                .value_name("SOURCE")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .case_insensitive(true)
+                .possible_values(&["wayback"])
+                .help(
+                    "Seed the scan with historical urls from the given source(s) before brute forcing (ex: --seed-from wayback)",
+                ),
+        )
+        .arg(
+            Arg::with_name("presets")
+                .long("preset")
+                .value_name("PRESET")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .case_insensitive(true)
+                .possible_values(&["admin-panels", "api", "cloud-metadata", "backup-files"])
+                .help(
+                    "Scan the words of bundled, high-signal wordlist(s), along with --wordlist when given, or instead of it when not (ex: --preset admin-panels,api)",
+                ),
+        )
+        .arg(
+            Arg::with_name("crawl_wordlist")
+                .long("crawl-wordlist")
+                .value_name("DEPTH")
+                .takes_value(true)
+                .validator(valid_count)
+                .help(
+                    "Crawl each target first, following links up to DEPTH times, and add the words, path segments, and parameter names found to the wordlist (ex: --crawl-wordlist 2)",
+                ),
+        )
+        .arg(
+            Arg::with_name("buckets")
+                .long("buckets")
+                .value_name("COMPANY")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .conflicts_with_all(&["url", "stdin", "resume_from"])
+                .help(
+                    "Look for AWS/GCP/Azure storage buckets named after COMPANY and words from the wordlist, reporting the ones that exist and can be listed, instead of scanning a url",
+                ),
+        )
+        .arg(
+            Arg::with_name("seed_file")
+                .long("seed-file")
+                .value_name("FILE")
+                .takes_value(true)
+                .help(
+                    "Seed the scan with the urls in FILE, one per line (gau, katana, waybackurls output, etc...); urls on other hosts are ignored",
+                ),
+        )
         .arg(
             Arg::with_name("headers")
                 .short("H")
@@ -236,9 +659,29 @@ pub fn initialize() -> App<'static, 'static> {
                 .value_name("HEADER")
                 .takes_value(true)
                 .multiple(true)
-                .use_delimiter(true)
+                .validator(valid_header)
+                .help(
+                    "Specify HTTP headers; repeated headers are combined (ex: -H Header:val -H 'stuff: things')",
+                ),
+        )
+        .arg(
+            Arg::with_name("host_header")
+                .long("host-header")
+                .value_name("HOST")
+                .takes_value(true)
                 .help(
-                    "Specify HTTP headers (ex: -H Header:val 'stuff: things')",
+                    "Send HOST as the Host header and tls SNI while connecting to a target given as an ip address (ex: --host-header example.com)",
+                ),
+        )
+        .arg(
+            Arg::with_name("rotate_headers")
+                .long("rotate-header")
+                .value_name("HEADER")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .help(
+                    "Send a header whose value is the next line of a file on every request (ex: --rotate-header 'X-Forwarded-For:@ips.txt')",
                 ),
         )
         .arg(
@@ -275,6 +718,13 @@ pub fn initialize() -> App<'static, 'static> {
                 .help("Read url(s) from STDIN")
                 .conflicts_with("url")
         )
+        .arg(
+            Arg::with_name("wizard")
+                .long("wizard")
+                .takes_value(false)
+                .conflicts_with_all(&["url", "stdin", "resume_from"])
+                .help("Interactively build a scan's options (target, wordlist, auth, filters), show the equivalent command, and optionally start it")
+        )
         .arg(
             Arg::with_name("filter_size")
                 .short("S")
@@ -283,8 +733,9 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(true)
                 .multiple(true)
                 .use_delimiter(true)
+                .validator(valid_size)
                 .help(
-                    "Filter out messages of a particular size (ex: -S 5120 -S 4927,1970)",
+                    "Filter out messages of a particular size; b, kb, mb, and gb units are accepted (ex: -S 5120 -S 4927,10kb)",
                 ),
         )
         .arg(
@@ -346,6 +797,41 @@ pub fn initialize() -> App<'static, 'static> {
                     "Filter out pages that are similar to the given page (ex. --filter-similar-to http://site.xyz/soft404)",
                 ),
         )
+        .arg(
+            Arg::with_name("cluster_threshold")
+                .long("cluster-threshold")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(valid_count)
+                .help(
+                    "Automatically mute responses sharing a status, word count, and line count once seen more than COUNT times (default: 0, i.e. disabled)",
+                ),
+        )
+        .arg(
+            Arg::with_name("coordinate")
+                .long("coordinate")
+                .value_name("ADDRESS")
+                .takes_value(true)
+                .validator(valid_socket_address)
+                .help("Accept worker connections on ADDRESS and distribute the scan across them instead of scanning locally (ex. --coordinate 0.0.0.0:9000 --coordinate-secret 0123456789ABCDEF)")
+        )
+        .arg(
+            Arg::with_name("coordinate_secret")
+                .long("coordinate-secret")
+                .value_name("SECRET")
+                .takes_value(true)
+                .env("FEROX_COORDINATE_SECRET")
+                .hide_env_values(true)
+                .help("Secret workers must prove they know before they're sent the scan's settings; required unless --coordinate is a loopback address")
+        )
+        .arg(
+            Arg::with_name("shard_size")
+                .long("shard-size")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(valid_count)
+                .help("Number of words handed to a worker at a time when using --coordinate (default: 1000)")
+        )
         .arg(
             Arg::with_name("extract_links")
                 .short("e")
@@ -353,6 +839,157 @@ pub fn initialize() -> App<'static, 'static> {
                 .takes_value(false)
                 .help("Extract links from response body (html, javascript, etc...); make new requests based on findings (default: false)")
         )
+        .arg(
+            Arg::with_name("head_only")
+                .long("head-only")
+                .takes_value(false)
+                .conflicts_with_all(&["extract_links", "filter_regex", "filter_words", "filter_lines", "filter_similar"])
+                .help("Send HEAD requests and never download bodies; fastest way to check what exists, but word and line counts aren't available")
+        )
+        .arg(
+            Arg::with_name("response_time")
+                .long("response-time")
+                .takes_value(false)
+                .help("Record how long each request took to be answered; shown beside each result and written to JSON output as response_time_ms (default: false)")
+        )
+        .arg(
+            Arg::with_name("extract_forms")
+                .long("extract-forms")
+                .takes_value(false)
+                .help("Parse html forms found in responses and report their action, method, and input names (default: false)")
+        )
+        .arg(
+            Arg::with_name("extract_comments")
+                .long("extract-comments")
+                .takes_value(false)
+                .help("Report html/javascript comments mentioning todos, credentials, internal hostnames, or IPs (default: false)")
+        )
+        .arg(
+            Arg::with_name("fingerprint")
+                .long("fingerprint")
+                .takes_value(false)
+                .help("Identify the server, framework, and CMS used by each target, hash its favicon, and suggest relevant extensions (default: false)")
+        )
+        .arg(
+            Arg::with_name("discover_openapi")
+                .long("discover-openapi")
+                .takes_value(false)
+                .help("Look for OpenAPI/Swagger specs (/swagger.json, /v2/api-docs, etc) and request every documented path (default: false)")
+        )
+        .arg(
+            Arg::with_name("graphql_probe")
+                .long("graphql-probe")
+                .takes_value(false)
+                .help("Send an introspection query to likely GraphQL endpoints and report the result (default: false)")
+        )
+        .arg(
+            Arg::with_name("source_maps")
+                .long("source-maps")
+                .takes_value(false)
+                .help("Request the source map of each discovered javascript file and report the original source paths it lists (default: false)")
+        )
+        .arg(
+            Arg::with_name("find_secrets")
+                .long("find-secrets")
+                .takes_value(false)
+                .help("Search response bodies for secrets (AWS/Google keys, private keys, JWTs, etc) and report redacted matches (default: false)")
+        )
+        .arg(
+            Arg::with_name("harvest")
+                .long("harvest")
+                .takes_value(false)
+                .help("Collect email addresses and probable usernames from responses and report a deduplicated list when the scan ends (default: false)")
+        )
+        .arg(
+            Arg::with_name("mine_params")
+                .long("mine-params")
+                .takes_value(false)
+                .help("Send batches of common parameter names to discovered endpoints and report the query/body parameters they accept (default: false)")
+        )
+        .arg(
+            Arg::with_name("check_artifacts")
+                .long("check-artifacts")
+                .takes_value(false)
+                .help("Request well-known VCS/IDE artifacts (.git/HEAD, .svn/entries, .DS_Store, .env, etc) from each scanned directory and report the ones confirmed by their content (default: false)")
+        )
+        .arg(
+            Arg::with_name("check_headers")
+                .long("check-headers")
+                .takes_value(false)
+                .help("Summarize missing/misconfigured security headers (CSP, HSTS, X-Frame-Options, etc) per host when the scan ends (default: false)")
+        )
+        .arg(
+            Arg::with_name("cors_probe")
+                .long("cors-probe")
+                .takes_value(false)
+                .help("Send requests with untrusted Origin headers to each discovered endpoint and report permissive Access-Control-Allow-Origin/Credentials combinations (default: false)")
+        )
+        .arg(
+            Arg::with_name("ws_probe")
+                .long("ws-probe")
+                .takes_value(false)
+                .help("Attempt a WebSocket upgrade handshake with each discovered endpoint and report the ones that accept it, along with the subprotocol they pick (default: false)")
+        )
+        .arg(
+            Arg::with_name("auth_diff")
+                .long("auth-diff")
+                .takes_value(false)
+                .help("Request each discovered endpoint again without the credentials from --headers/--query (Authorization, Cookie, etc) and report the ones whose responses change (default: false)")
+        )
+        .arg(
+            Arg::with_name("check_methods")
+                .long("check-methods")
+                .takes_value(false)
+                .help("Send an OPTIONS request to each scanned directory, report its Allow/DAV headers, and highlight risky methods (PUT, DELETE, PROPFIND, etc) (default: false)")
+        )
+        .arg(
+            Arg::with_name("permute")
+                .long("permute")
+                .takes_value(false)
+                .help("Request variations of the name of each discovered resource, i.e. admin -> admin2, admin_old, admin-dev, and backup -> backup2024, backup_2025 (default: false)")
+        )
+        .arg(
+            Arg::with_name("permutation_rules")
+                .long("permutation-rules")
+                .value_name("RULE")
+                .takes_value(true)
+                .multiple(true)
+                .use_delimiter(true)
+                .requires("permute")
+                .help(
+                    "Rule(s) used by --permute instead of the built-in ones; {word} is the discovered name, {year} each of the last six years (ex: --permutation-rules '{word}2,{word}_{year}')",
+                ),
+        )
+        .arg(
+            Arg::with_name("iis_shortnames")
+                .long("iis-shortnames")
+                .takes_value(false)
+                .help("Enumerate the 8.3 short names (ADMINI~1.ASP) in each directory of IIS targets through tilde probing, and request the words of the wordlist that match them (default: false)")
+        )
+        .arg(
+            Arg::with_name("redact_secrets")
+                .long("redact-secrets")
+                .takes_value(false)
+                .help("Redact auth headers, tokens, and proxy passwords from the file written by --save-config (default: false)")
+        )
+        .arg(
+            Arg::with_name("no_product_wordlists")
+                .long("no-product-wordlists")
+                .takes_value(false)
+                .help("Don't append bundled product-specific words (WordPress, Tomcat, etc) when --fingerprint identifies a product (default: false)")
+        )
+        .arg(
+            Arg::with_name("parse_listings")
+                .long("parse-listings")
+                .takes_value(false)
+                .help("Add the contents of directory listings (index pages) to results/recursion (default: false)")
+        )
+        .arg(
+            Arg::with_name("skip_listed_dirs")
+                .long("skip-listed-dirs")
+                .takes_value(false)
+                .help("Don't use the wordlist against directories that have a listing; implies --parse-listings (default: false)")
+        )
         .arg(
             Arg::with_name("scan_limit")
                 .short("L")
@@ -375,7 +1012,32 @@ pub fn initialize() -> App<'static, 'static> {
                 .value_name("RATE_LIMIT")
                 .takes_value(true)
                 .conflicts_with("auto_tune")
-                .help("Limit number of requests per second (per directory) (default: 0, i.e. no limit)")
+                .validator(valid_count)
+                .help("Limit number of requests per second (per directory); k and m units are accepted (ex: 1k) (default: 0, i.e. no limit)")
+        )
+        .arg(
+            Arg::with_name("request_limit")
+                .long("request-limit")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(valid_count)
+                .help("Limit total number of requests sent to any single host; a host's scans stop once it's reached, k and m units are accepted (ex: 10k) (default: 0, i.e. no limit)")
+        )
+        .arg(
+            Arg::with_name("max_429s")
+                .long("max-429s")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(valid_count)
+                .help("Pause all requests to a host for --cooldown after it answers with COUNT 429 (Too Many Requests) responses in a row (default: 0, i.e. only pause when asked to by Retry-After)")
+        )
+        .arg(
+            Arg::with_name("cooldown")
+                .long("cooldown")
+                .value_name("TIME_SPEC")
+                .takes_value(true)
+                .validator(valid_time_spec)
+                .help("How long a host is paused after --max-429s 429s in a row; given alone, a host is paused after every 429 (ex: --cooldown 2m) (default: 30s)")
         )
         .arg(
             Arg::with_name("time_limit")
@@ -383,10 +1045,180 @@ pub fn initialize() -> App<'static, 'static> {
                 .value_name("TIME_SPEC")
                 .takes_value(true)
                 .validator(valid_time_spec)
-                .help("Limit total run time of all scans (ex: --time-limit 10m)")
+                .help("Limit total run time of all scans (ex: --time-limit 10m, --time-limit 1h30m)")
         )
+        .arg(
+            Arg::with_name("checkpoint_interval")
+                .long("checkpoint-interval")
+                .value_name("TIME_SPEC")
+                .takes_value(true)
+                .validator(valid_time_spec)
+                .help("Save the scan's state to disk this often, not only on Ctrl+C, so a crash loses at most one interval of work (ex: --checkpoint-interval 5m)")
+        )
+        .arg(
+            Arg::with_name("checkpoint_requests")
+                .long("checkpoint-requests")
+                .value_name("COUNT")
+                .takes_value(true)
+                .validator(valid_count)
+                .help("Save the scan's state to disk every COUNT requests, not only on Ctrl+C; k and m units are accepted (ex: 50k)")
+        )
+        .subcommand(
+            SubCommand::with_name("config")
+                .about("Work with feroxbuster's configuration")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("check")
+                        .about("Validate the config file(s), environment, and options given before `config`, report every problem found, and exit without scanning")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("wordlists")
+                .about("Download and manage curated wordlists, which can then be used by name (ex: -w seclists-common)")
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .subcommand(
+                    SubCommand::with_name("list")
+                        .about("List the wordlists that can be installed, and which of them already are")
+                )
+                .subcommand(
+                    SubCommand::with_name("install")
+                        .about("Download the given wordlist(s); honors --proxy, --insecure, and --timeout given before `wordlists`")
+                        .arg(
+                            Arg::with_name("name")
+                                .value_name("NAME")
+                                .required(true)
+                                .multiple(true)
+                                .help("Name of the wordlist(s) to install (ex: seclists-common)")
+                        )
+                )
+                .subcommand(
+                    SubCommand::with_name("remove")
+                        .about("Delete the given installed wordlist(s)")
+                        .arg(
+                            Arg::with_name("name")
+                                .value_name("NAME")
+                                .required(true)
+                                .multiple(true)
+                                .help("Name of the wordlist(s) to remove")
+                        )
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("recipe")
+                .about("Run every scan described in a recipe file, one after the other or several at once; options given before `recipe` apply to every scan")
+                .arg(
+                    Arg::with_name("file")
+                        .value_name("FILE")
+                        .required(true)
+                        .help("Recipe to run; .yml/.yaml files are read as YAML, anything else as TOML")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("watch")
+                .about("Scan each target again every interval, until interrupted, and report only what's new or changed since its last scan; options given before `watch` apply to every scan")
+                .arg(
+                    Arg::with_name("targets")
+                        .long("targets")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .required(true)
+                        .help("File with the targets to watch, one per line")
+                )
+                .arg(
+                    Arg::with_name("interval")
+                        .long("interval")
+                        .value_name("DURATION")
+                        .takes_value(true)
+                        .required(true)
+                        .validator(valid_time_spec)
+                        .help("Time between the start of two rounds of scans (ex: 24h, 12h30m)")
+                )
+                .arg(
+                    Arg::with_name("state_dir")
+                        .long("state-dir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .help("Directory in which each target's baselines are kept (default: feroxbuster/watch in the user's data directory)")
+                )
+                .arg(
+                    Arg::with_name("webhook")
+                        .long("webhook")
+                        .value_name("URL")
+                        .takes_value(true)
+                        .help("Url to which what's new or changed about a target is POSTed as json")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("serve")
+                .about("Run as a long-running service; scans are submitted, monitored, streamed, and cancelled over a REST api")
+                .arg(
+                    Arg::with_name("listen")
+                        .long("listen")
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .validator(valid_socket_address)
+                        .help("Address on which to serve the api (default: 127.0.0.1:8000)")
+                )
+                .arg(
+                    Arg::with_name("api_token")
+                        .long("api-token")
+                        .value_name("TOKEN")
+                        .takes_value(true)
+                        .env("FEROX_API_TOKEN")
+                        .hide_env_values(true)
+                        .help("Bearer token every api request must carry; required unless --listen is a loopback address")
+                )
+                .arg(
+                    Arg::with_name("wordlist_dir")
+                        .long("wordlist-dir")
+                        .value_name("DIR")
+                        .takes_value(true)
+                        .help("Directory whose files submitted scans may use as wordlists, in addition to installed wordlists")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("worker")
+                .about("Scan shards of a distributed scan handed out by a coordinator started with --coordinate")
+                .arg(
+                    Arg::with_name("connect")
+                        .long("connect")
+                        .value_name("ADDRESS")
+                        .takes_value(true)
+                        .required(true)
+                        .help("Address of the coordinator (ex. --connect 10.0.0.5:9000)")
+                )
+                .arg(
+                    Arg::with_name("secret")
+                        .long("secret")
+                        .value_name("SECRET")
+                        .takes_value(true)
+                        .env("FEROX_COORDINATE_SECRET")
+                        .hide_env_values(true)
+                        .help("Secret shared with the coordinator (its --coordinate-secret)")
+                )
+        )
+        .subcommand(
+            SubCommand::with_name("bench")
+                .about("Scan a built-in local server with fixed settings and report requests/s, response times, cpu, and memory, to compare performance between releases")
+                .arg(
+                    Arg::with_name("requests")
+                        .long("requests")
+                        .value_name("COUNT")
+                        .takes_value(true)
+                        .validator(valid_count)
+                        .help("Number of words to scan; k and m units are accepted (default: 50k)")
+                )
+                .arg(
+                    Arg::with_name("threads")
+                        .long("threads")
+                        .value_name("THREADS")
+                        .takes_value(true)
+                        .help("Number of concurrent requests (default: 50)")
+                )
+        )
+        .setting(AppSettings::SubcommandsNegateReqs)
         .group(ArgGroup::with_name("output_files")
-            .args(&["debug_log", "output"])
+            .args(&["debug_log", "output", "burp_output", "defectdojo_output"])
             .multiple(true)
         )
         .after_help(r#"NOTE:
@@ -399,6 +1231,12 @@ pub fn initialize() -> App<'static, 'static> {
     All of the methods above (multiple flags, space separated, comma separated, etc...) are valid
     and interchangeable.  The same goes for urls, headers, status codes, queries, and size filters.
 
+EXIT CODES:
+    0 = scan completed, nothing found      1 = scan completed, something found
+    2 = configuration error                3 = no target could be reached
+    4 = interrupted by ctrl+c or --time-limit
+    5 = an --expect-found or --expect-absent check failed
+
 EXAMPLES:
     Multiple headers:
         ./feroxbuster -u http://127.1 -H Accept:application/json "Authorization: Bearer {token}"
@@ -415,12 +1253,44 @@ EXAMPLES:
     Proxy traffic through a SOCKS proxy
         ./feroxbuster -u http://127.1 --proxy socks5://127.0.0.1:9050
 
+    Quick scan for admin panels and backups, no wordlist needed
+        ./feroxbuster -u http://127.1 --preset admin-panels,backup-files
+
+    Find the endpoints that respond differently without a session cookie
+        ./feroxbuster -u http://127.1 -H 'Cookie: session=abc123' --auth-diff
+
+    Send a different X-Forwarded-For with every request
+        ./feroxbuster -u http://127.1 --rotate-header 'X-Forwarded-For:@ips.txt'
+
+    Look for public cloud storage buckets instead of scanning a url
+        ./feroxbuster --buckets ellingson -w /wordlists/bucket-words.txt
+
+    Scan through Tor, switching exit nodes when the current one gets blocked
+        ./feroxbuster -u http://127.1 --tor --tor-control 127.0.0.1:9051
+
     Pass auth token via query parameter
         ./feroxbuster -u http://127.1 --query token=0123456789ABCDEF
 
     Find links in javascript/html and make additional requests based on results
         ./feroxbuster -u http://127.1 --extract-links
 
+    Validate the configuration (config files + env + cli) without scanning
+        ./feroxbuster -w /wordlists/common.txt --proxy http://127.0.0.1:8080 config check
+
+    Serve the REST api on all interfaces, requiring a bearer token
+        ./feroxbuster serve --listen 0.0.0.0:8000 --api-token 0123456789ABCDEF
+
+    Distribute a scan across workers running on other machines, sharing a secret with them
+        ./feroxbuster -u http://127.1 --coordinate 0.0.0.0:9000 --coordinate-secret 0123456789ABCDEF
+        ./feroxbuster worker --connect 10.0.0.5:9000 --secret 0123456789ABCDEF
+
+    Install a wordlist and scan with it
+        ./feroxbuster wordlists install seclists-common
+        ./feroxbuster -u http://127.1 -w seclists-common
+
+    Run several scans described in a recipe, sharing a single rate limit
+        ./feroxbuster --insecure recipe nightly.toml
+
     Ludicrous speed... go!
         ./feroxbuster -u http://127.1 -t 200
     "#);
@@ -439,18 +1309,222 @@ EXAMPLES:
     app
 }
 
+/// Convert a duration made up of one or more numbers followed by s, m, h, or d (10d, 30s, 1h30m,
+/// etc...) into seconds
+pub fn parse_duration(value: &str) -> Result<u64, String> {
+    let invalid = || {
+        format!(
+            "Expected a non-negative, whole number followed by s, m, h, or d (case insensitive), optionally repeated (ex: 1h30m); received {}",
+            value
+        )
+    };
+
+    let too_large = || format!("{} is too long of a duration", value);
+
+    let mut total: u64 = 0;
+    let mut start = 0;
+
+    for (index, character) in value.char_indices() {
+        if character.is_ascii_digit() {
+            continue;
+        }
+
+        // each segment is a number followed by its unit, i.e. 1h in 1h30m
+        let end = index + character.len_utf8();
+        let captures = TIMESPEC_REGEX
+            .captures(&value[start..end])
+            .ok_or_else(invalid)?;
+
+        let number = captures["n"].parse::<u64>().map_err(|_| too_large())?;
+
+        let seconds = match captures["m"].to_ascii_lowercase().as_str() {
+            "s" => 1,
+            "m" => 60,         // minutes
+            "h" => 60 * 60,    // hours
+            _ => 60 * 60 * 24, // days
+        };
+
+        total = number
+            .checked_mul(seconds)
+            .and_then(|segment| total.checked_add(segment))
+            .ok_or_else(too_large)?;
+
+        start = end;
+    }
+
+    if start == 0 || start != value.len() {
+        // empty, or a trailing number without a unit
+        return Err(invalid());
+    }
+
+    Ok(total)
+}
+
+/// Convert a number followed by one of the given (case insensitive) units into a plain number;
+/// fractions are allowed as long as the result is a whole number (1.5k)
+fn parse_with_units(value: &str, units: &[(&str, u64)]) -> Result<u64, String> {
+    let invalid = || {
+        let names: Vec<&str> = units
+            .iter()
+            .map(|(name, _)| *name)
+            .filter(|name| !name.is_empty())
+            .collect();
+
+        format!(
+            "Expected a non-negative number, optionally followed by one of {} (case insensitive); received {}",
+            names.join(", "),
+            value
+        )
+    };
+
+    let too_large = || format!("{} is too large", value);
+
+    let captures = UNIT_REGEX.captures(value).ok_or_else(invalid)?;
+    let unit = captures["u"].to_ascii_lowercase();
+
+    let multiplier = units
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .map(|(_, multiplier)| *multiplier)
+        .ok_or_else(invalid)?;
+
+    if let Ok(number) = captures["n"].parse::<u64>() {
+        return number.checked_mul(multiplier).ok_or_else(too_large);
+    }
+
+    let number = captures["n"].parse::<f64>().map_err(|_| invalid())? * multiplier as f64;
+
+    if number.fract() > 0.0 {
+        return Err(format!("{} isn't a whole number", value));
+    }
+
+    if number >= u64::MAX as f64 {
+        return Err(too_large());
+    }
+
+    Ok(number as u64)
+}
+
+/// Convert a number of bytes, optionally followed by b, kb, mb, or gb (10kb, 1.5mb, etc...) into
+/// bytes; sizes are 1024-based
+pub fn parse_size(value: &str) -> Result<u64, String> {
+    parse_with_units(value, &SIZE_UNITS)
+}
+
+/// Convert a count, optionally followed by k or m (1k, 2.5m, etc...) into a plain number
+pub fn parse_count(value: &str) -> Result<u64, String> {
+    parse_with_units(value, &COUNT_UNITS)
+}
+
+/// Split a `Name: value` header into its trimmed name and value; only the first colon separates
+/// the two, so values may contain colons of their own (`Authorization: Basic dXNlcjpwYXNz`)
+pub fn parse_header(header: &str) -> Result<(String, String), String> {
+    let (name, value) = header.split_once(':').ok_or_else(|| {
+        format!(
+            "Expected a header formatted as Name: value (ex: -H 'Api-Key: abc'); received {}",
+            header
+        )
+    })?;
+
+    let name = name.trim();
+    let value = value.trim();
+
+    if name.is_empty() {
+        return Err(format!("Header {} is missing a name", header));
+    }
+
+    if HeaderName::from_bytes(name.as_bytes()).is_err() {
+        return Err(format!(
+            "Invalid header name {:?}; names may only contain letters, digits, and !#$%&'*+-.^_`|~",
+            name
+        ));
+    }
+
+    if HeaderValue::from_str(value).is_err() {
+        return Err(format!(
+            "Invalid value for header {}; values can't contain control characters",
+            name
+        ));
+    }
+
+    Ok((name.to_string(), value.to_string()))
+}
+
+/// Validate that a string is a `Name: value` header
+fn valid_header(header: String) -> Result<(), String> {
+    parse_header(&header).map(|_| ())
+}
+
+/// Validate that a string is an --event-stream destination (unix:PATH, tcp:HOST:PORT, or a path)
+fn valid_event_stream(destination: String) -> Result<(), String> {
+    crate::event_stream::valid_destination(&destination)
+}
+
+/// Validate that a string is a --scope rule (example.com, *.example.com, 10.0.0.0/24, or any of
+/// them prefixed with !)
+fn valid_scope_rule(rule: String) -> Result<(), String> {
+    crate::scope::valid_rule(&rule)
+}
+
 /// Validate that a string is formatted as a number followed by s, m, h, or d (10d, 30s, etc...)
 fn valid_time_spec(time_spec: String) -> Result<(), String> {
-    match TIMESPEC_REGEX.is_match(&time_spec) {
-        true => Ok(()),
-        false => {
-            let msg = format!(
-                "Expected a non-negative, whole number followed by s, m, h, or d (case insensitive); received {}",
-                time_spec
-            );
-            Err(msg)
-        }
+    parse_duration(&time_spec).map(|_| ())
+}
+
+/// Validate that a string is a number of seconds, or a duration (30s, 1m, etc...)
+fn valid_timeout(timeout: String) -> Result<(), String> {
+    if timeout.parse::<u64>().is_ok() {
+        return Ok(());
     }
+
+    parse_duration(&timeout).map(|_| ())
+}
+
+/// Validate that a string is a number of milliseconds between progress bar redraws; redraws are
+/// scheduled a whole number of times per second, so anything over a second can't be honored
+fn valid_refresh_rate(millis: String) -> Result<(), String> {
+    match millis.parse::<u64>() {
+        Ok(1..=1000) => Ok(()),
+        _ => Err(format!(
+            "Expected a number of milliseconds from 1 to 1000; received {}",
+            millis
+        )),
+    }
+}
+
+/// Validate that a string is a size (5120, 10kb, etc...)
+fn valid_size(size: String) -> Result<(), String> {
+    parse_size(&size).map(|_| ())
+}
+
+/// Validate that a string is a count (100, 1k, etc...)
+fn valid_count(count: String) -> Result<(), String> {
+    parse_count(&count).map(|_| ())
+}
+
+/// Validate that a string is a single character, or `locale`
+fn valid_thousands_separator(separator: String) -> Result<(), String> {
+    if separator == "locale" || separator.chars().count() == 1 {
+        Ok(())
+    } else {
+        Err(format!(
+            "Expected a single character or locale; received {}",
+            separator
+        ))
+    }
+}
+
+/// Validate that a string is an ip address and port (127.0.0.1:8000, [::1]:8000, etc...)
+fn valid_socket_address(address: String) -> Result<(), String> {
+    address
+        .parse::<std::net::SocketAddr>()
+        .map(|_| ())
+        .map_err(|_| {
+            format!(
+                "Expected an ip address and port (ex: 127.0.0.1:8000); received {}",
+                address
+            )
+        })
 }
 
 #[cfg(test)]
@@ -496,4 +1570,105 @@ mod tests {
         let space_between_rejected = "1 4m";
         assert!(valid_time_spec(space_between_rejected.into()).is_err());
     }
+
+    #[test]
+    /// durations can be made up of several segments, and are converted to seconds
+    fn parse_duration_converts_to_seconds() {
+        assert_eq!(parse_duration("30s"), Ok(30));
+        assert_eq!(parse_duration("90m"), Ok(5400));
+        assert_eq!(parse_duration("1h30m"), Ok(5400));
+        assert_eq!(parse_duration("1D2h"), Ok(93600));
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("1h30").is_err());
+        assert!(parse_duration("1w").is_err());
+        assert!(parse_duration("18446744073709551616m")
+            .unwrap_err()
+            .contains("too long"));
+    }
+
+    #[test]
+    /// sizes accept 1024-based units, with or without a fraction
+    fn parse_size_converts_to_bytes() {
+        assert_eq!(parse_size("5120"), Ok(5120));
+        assert_eq!(parse_size("512b"), Ok(512));
+        assert_eq!(parse_size("10kb"), Ok(10240));
+        assert_eq!(parse_size("10KiB"), Ok(10240));
+        assert_eq!(parse_size("1.5M"), Ok(1572864));
+        assert_eq!(parse_size("1g"), Ok(1 << 30));
+        assert!(parse_size("10 kb").is_err());
+        assert!(parse_size("-1kb").is_err());
+        assert!(parse_size("10tb").unwrap_err().contains("b, k, kb, kib"));
+        assert!(parse_size("1.0001b").unwrap_err().contains("whole number"));
+    }
+
+    #[test]
+    /// counts accept k and m
+    fn parse_count_converts_to_number() {
+        assert_eq!(parse_count("250"), Ok(250));
+        assert_eq!(parse_count("1k"), Ok(1000));
+        assert_eq!(parse_count("2.5K"), Ok(2500));
+        assert_eq!(parse_count("1m"), Ok(1_000_000));
+        assert!(parse_count("1kb").is_err());
+        assert!(parse_count("18446744073709551615k").is_err());
+    }
+
+    #[test]
+    /// headers split on their first colon only, and bad names/values are rejected
+    fn parse_header_splits_on_first_colon() {
+        assert_eq!(
+            parse_header("Authorization: Basic dXNlcjpwYXNz"),
+            Ok((
+                String::from("Authorization"),
+                String::from("Basic dXNlcjpwYXNz")
+            ))
+        );
+        assert_eq!(
+            parse_header(" Referer :http://localhost:8080/ "),
+            Ok((
+                String::from("Referer"),
+                String::from("http://localhost:8080/")
+            ))
+        );
+        assert_eq!(
+            parse_header("X-Empty:"),
+            Ok((String::from("X-Empty"), String::new()))
+        );
+        assert!(parse_header("no colon").is_err());
+        assert!(parse_header(": value").is_err());
+        assert!(parse_header("Bad Name: value").is_err());
+        assert!(parse_header("X-Bad: line\nbreak").is_err());
+    }
+
+    #[test]
+    /// header values with commas in them are kept whole instead of being split into more headers
+    fn headers_keep_commas_in_values() {
+        let matches = initialize()
+            .get_matches_from_safe(vec![
+                "feroxbuster",
+                "--url",
+                "http://localhost",
+                "-H",
+                "Accept: text/html, application/json",
+                "-H",
+                "X-Other: 1",
+            ])
+            .unwrap();
+
+        let headers: Vec<&str> = matches.values_of("headers").unwrap().collect();
+        assert_eq!(
+            headers,
+            ["Accept: text/html, application/json", "X-Other: 1"]
+        );
+    }
+
+    #[test]
+    /// timeouts can be given as plain seconds or as a duration
+    fn valid_timeout_accepts_seconds_and_durations() {
+        assert!(valid_timeout("7".into()).is_ok());
+        assert!(valid_timeout("1m".into()).is_ok());
+        assert!(valid_timeout("soon".into()).is_err());
+        assert!(valid_socket_address("127.0.0.1:8000".into()).is_ok());
+        assert!(valid_socket_address("[::1]:8000".into()).is_ok());
+        assert!(valid_socket_address("localhost".into()).is_err());
+    }
 }
@@ -0,0 +1,253 @@
+//! request variations of the names of discovered resources, i.e. admin -> admin2, admin_old,
+//! admin-dev, and backup -> backup2021...backup2026 (--permute)
+use std::sync::Arc;
+
+use anyhow::Result;
+use reqwest::Url;
+use tokio::sync::oneshot;
+
+use crate::{
+    client,
+    event_handlers::{
+        Command::{self, AddToUsizeField},
+        Handles,
+    },
+    response::FeroxResponse,
+    scan_manager::ScanOrder,
+    statistics::StatField::TotalExpected,
+    utils::{civil_date, logged_request, should_deny_url, unix_timestamp},
+};
+
+/// rules used when none are configured; `{word}` is the discovered name, `{year}` each of the
+/// last `YEARS` years
+pub const DEFAULT_RULES: [&str; 14] = [
+    "{word}2",
+    "{word}_old",
+    "{word}-old",
+    "{word}_bak",
+    "{word}-bak",
+    "{word}_dev",
+    "{word}-dev",
+    "{word}-test",
+    "{word}_new",
+    "{word}-new",
+    "old_{word}",
+    "{word}{year}",
+    "{word}_{year}",
+    "{word}-{year}",
+];
+
+/// number of years, counting back from (and including) the current one, that `{year}` expands to
+const YEARS: i64 = 6;
+
+/// names shorter than this aren't permuted; `js` or `v1` make for a lot of noise
+const MIN_WORD_LENGTH: usize = 3;
+
+/// Whether the given rule can be expanded, i.e. it has a `{word}` in it
+pub fn valid_rule(rule: &str) -> bool {
+    rule.contains("{word}")
+}
+
+/// Expand each rule for the given word
+fn permute(word: &str, rules: &[String], current_year: i64) -> Vec<String> {
+    let mut names = Vec::new();
+
+    for rule in rules {
+        let rule = rule.replace("{word}", word);
+
+        if !rule.contains("{year}") {
+            names.push(rule);
+            continue;
+        }
+
+        for year in (current_year - YEARS + 1)..=current_year {
+            names.push(rule.replace("{year}", &year.to_string()));
+        }
+    }
+
+    names
+}
+
+/// Urls of the permutations of the last segment of `url`; directories keep their trailing slash,
+/// and files keep their extension (backup.zip -> backup2.zip)
+fn candidates(url: &Url, rules: &[String], current_year: i64) -> Vec<Url> {
+    let path = url.path().trim_end_matches('/');
+    let is_directory = url.path().ends_with('/');
+
+    let (parent, name) = match path.rfind('/') {
+        Some(slash) => path.split_at(slash + 1),
+        None => return Vec::new(),
+    };
+
+    // the extension starts at the first dot that doesn't start the name (.git, .env)
+    let (stem, extension) = match name.get(1..).and_then(|rest| rest.find('.')) {
+        Some(dot) => name.split_at(dot + 1),
+        None => (name, ""),
+    };
+
+    if stem.chars().count() < MIN_WORD_LENGTH {
+        return Vec::new();
+    }
+
+    let suffix = if is_directory { "/" } else { "" };
+
+    permute(stem, rules, current_year)
+        .into_iter()
+        .filter(|permutation| permutation != stem)
+        .map(|permutation| {
+            let mut candidate = url.clone();
+            candidate.set_query(None);
+            candidate.set_path(&format!("{}{}{}{}", parent, permutation, extension, suffix));
+            candidate
+        })
+        .collect()
+}
+
+/// Request the permutations of the name of the given (discovered) response, reporting the ones
+/// that make it through the filters and handing new directories to the recursion handler
+pub async fn probe(response: &FeroxResponse, handles: Arc<Handles>) -> Result<()> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let rules = if handles.config.permutation_rules.is_empty() {
+        DEFAULT_RULES.iter().map(|rule| rule.to_string()).collect()
+    } else {
+        handles.config.permutation_rules.clone()
+    };
+
+    let (current_year, _, _) = civil_date(unix_timestamp());
+
+    // the same name found twice in a scan (say, through link extraction and the wordlist) is
+    // only permuted once
+    let urls: Vec<Url> = candidates(response.url(), &rules, current_year)
+        .into_iter()
+        .filter(|url| handles.first_probe("permutations", url.as_str()))
+        .collect();
+
+    if urls.is_empty() {
+        log::trace!("exit: probe");
+        return Ok(());
+    }
+
+    handles
+        .stats
+        .send(AddToUsizeField(TotalExpected, urls.len()))?;
+
+    let scanned_urls = handles.ferox_scans()?;
+    let should_test_deny =
+        !handles.config.url_denylist.is_empty() || !handles.config.deny_url_regex.is_empty();
+
+    for url in urls {
+        if scanned_urls.get_scan_by_url(url.as_str()).is_some() {
+            // found by the wordlist already
+            continue;
+        }
+
+        if should_test_deny && should_deny_url(&url, handles.clone())? {
+            continue;
+        }
+
+        let permutation = logged_request(&url, handles.clone()).await?;
+
+        let mut permutation = FeroxResponse::from(
+            permutation,
+            !handles.config.head_only,
+            handles.config.max_response_size,
+            handles.config.output_level,
+        )
+        .await;
+        permutation.set_redirect_chain(client::take_redirect_chain(&url));
+
+        if handles
+            .filters
+            .data
+            .should_filter_response(&permutation, handles.stats.tx.clone())
+        {
+            continue;
+        }
+
+        if permutation.is_file() || handles.config.no_recursion {
+            scanned_urls.add_file_scan(url.as_str(), ScanOrder::Latest);
+        } else {
+            handles.send_scan_command(Command::TryRecursion(Box::new(permutation.clone())))?;
+            let (tx, rx) = oneshot::channel::<bool>();
+            handles.send_scan_command(Command::Sync(tx))?;
+            rx.await?;
+        }
+
+        if let Err(e) = permutation.send_report(handles.output.tx.clone()) {
+            log::warn!("Could not send FeroxResponse to output handler: {}", e);
+        }
+    }
+
+    log::trace!("exit: probe");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the built-in rules, as configured rules
+    fn default_rules() -> Vec<String> {
+        DEFAULT_RULES.iter().map(|rule| rule.to_string()).collect()
+    }
+
+    #[test]
+    /// {word} is replaced once per rule, {year} once per year
+    fn permute_expands_words_and_years() {
+        let rules = vec![String::from("{word}_old"), String::from("{word}{year}")];
+        let names = permute("backup", &rules, 2025);
+
+        assert_eq!(
+            names,
+            vec![
+                "backup_old",
+                "backup2020",
+                "backup2021",
+                "backup2022",
+                "backup2023",
+                "backup2024",
+                "backup2025",
+            ]
+        );
+    }
+
+    #[test]
+    /// directories keep their slash, files their extension, and short names aren't permuted
+    fn candidates_keep_slashes_and_extensions() {
+        let rules = vec![String::from("{word}-dev")];
+
+        let url = Url::parse("http://localhost/site/admin/").unwrap();
+        assert_eq!(
+            candidates(&url, &rules, 2025),
+            vec![Url::parse("http://localhost/site/admin-dev/").unwrap()]
+        );
+
+        let url = Url::parse("http://localhost/backup.tar.gz").unwrap();
+        assert_eq!(
+            candidates(&url, &rules, 2025),
+            vec![Url::parse("http://localhost/backup-dev.tar.gz").unwrap()]
+        );
+
+        let url = Url::parse("http://localhost/.git/").unwrap();
+        assert_eq!(
+            candidates(&url, &rules, 2025),
+            vec![Url::parse("http://localhost/.git-dev/").unwrap()]
+        );
+
+        let url = Url::parse("http://localhost/js/").unwrap();
+        assert!(candidates(&url, &rules, 2025).is_empty());
+
+        let url = Url::parse("http://localhost/admin").unwrap();
+        assert_eq!(candidates(&url, &default_rules(), 2025).len(), 11 + 3 * 6);
+    }
+
+    #[test]
+    /// rules without a {word} would request the same url for every discovery
+    fn valid_rule_needs_word() {
+        assert!(valid_rule("{word}2"));
+        assert!(valid_rule("old_{word}_{year}"));
+        assert!(!valid_rule("{year}"));
+        assert!(!valid_rule("admin2"));
+    }
+}
@@ -0,0 +1,226 @@
+//! expand placeholders in -H header values (cookies included) for every request, i.e.
+//! `-H "X-Trace: ferox-{{uuid}}"` or `-H "Cookie: cb={{rand_int}}"`
+//!
+//! - `{{word}}`: the wordlist word the request was made for, without any -x extension; requests
+//!   that weren't made for a word (heuristics, extracted links, probes) use the last segment of
+//!   the requested path instead
+//! - `{{rand_int}}`: a random number from 0 to 4294967295
+//! - `{{uuid}}`: a random (v4) uuid
+//! - `{{timestamp}}`: the current unix timestamp, in seconds
+//!
+//! only header values are expanded; scan requests don't have a body, and the url (-Q included)
+//! is sent as-is, so placeholders anywhere else are rejected by `Configuration::validate`
+use std::{collections::HashMap, sync::RwLock};
+
+use reqwest::{
+    header::{HeaderName, HeaderValue},
+    Url,
+};
+use uuid::Uuid;
+
+use crate::utils::unix_timestamp;
+
+/// names of the placeholders that can be used
+pub const PLACEHOLDERS: [&str; 4] = ["word", "rand_int", "uuid", "timestamp"];
+
+/// Names of the `{{placeholders}}` in `template`, in order, known or not
+fn placeholders_in(template: &str) -> Vec<&str> {
+    let mut names = Vec::new();
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+
+        match after.find("}}") {
+            Some(end) => {
+                names.push(&after[..end]);
+                rest = &after[end + 2..];
+            }
+            None => break,
+        }
+    }
+
+    names
+}
+
+/// Whether `template` has any `{{placeholders}}` in it
+pub fn is_template(template: &str) -> bool {
+    !placeholders_in(template).is_empty()
+}
+
+/// Placeholders in `template` that aren't one of `PLACEHOLDERS`
+pub fn unknown_placeholders(template: &str) -> Vec<String> {
+    placeholders_in(template)
+        .into_iter()
+        .filter(|name| !PLACEHOLDERS.contains(name))
+        .map(|name| format!("{{{{{}}}}}", name))
+        .collect()
+}
+
+/// Value of the placeholder with the given name for a request to `url`, made for `word`
+fn value_of(name: &str, url: &Url, word: Option<&str>) -> Option<String> {
+    match name {
+        "word" => Some(match word {
+            Some(word) => word.to_string(),
+            None => url
+                .path()
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or_default()
+                .to_string(),
+        }),
+        "rand_int" => {
+            let id = Uuid::new_v4();
+            let bytes = id.as_bytes();
+            Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]).to_string())
+        }
+        "uuid" => Some(Uuid::new_v4().to_string()),
+        "timestamp" => Some(unix_timestamp().to_string()),
+        _ => None,
+    }
+}
+
+/// Replace each known placeholder in `template` with its value for a request to `url`, made for
+/// `word`; unknown placeholders are left as they are
+pub fn expand(template: &str, url: &Url, word: Option<&str>) -> String {
+    let mut expanded = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        let after = &rest[start + 2..];
+
+        let end = match after.find("}}") {
+            Some(end) => end,
+            None => break,
+        };
+
+        expanded.push_str(&rest[..start]);
+
+        match value_of(&after[..end], url, word) {
+            Some(value) => expanded.push_str(&value),
+            None => expanded.push_str(&rest[start..start + end + 4]),
+        }
+
+        rest = &after[end + 2..];
+    }
+
+    expanded.push_str(rest);
+    expanded
+}
+
+/// -H headers whose value has placeholders in it
+///
+/// the client's default headers still hold the unexpanded values; the expanded ones replace
+/// them on each request
+#[derive(Debug, Default)]
+pub struct TemplatedHeaders {
+    /// header names, and their unexpanded values
+    headers: RwLock<Vec<(HeaderName, String)>>,
+}
+
+impl TemplatedHeaders {
+    /// keep the headers that have placeholders in their value, replacing any loaded before
+    pub fn load(&self, headers: &HashMap<String, String>) {
+        let templated = headers
+            .iter()
+            .filter(|(_, value)| is_template(value))
+            .filter_map(|(name, value)| {
+                HeaderName::from_bytes(name.as_bytes())
+                    .ok()
+                    .map(|name| (name, value.clone()))
+            })
+            .collect();
+
+        if let Ok(mut current) = self.headers.write() {
+            *current = templated;
+        }
+    }
+
+    /// the headers, expanded for a request to `url`, made for `word`; empty when no header has a
+    /// placeholder
+    pub fn values_for(&self, url: &Url, word: Option<&str>) -> Vec<(HeaderName, HeaderValue)> {
+        let headers = match self.headers.read() {
+            Ok(headers) => headers,
+            Err(_) => return Vec::new(),
+        };
+
+        headers
+            .iter()
+            .filter_map(|(name, template)| {
+                match HeaderValue::from_str(&expand(template, url, word)) {
+                    Ok(value) => Some((name.clone(), value)),
+                    Err(e) => {
+                        log::debug!("could not expand {} for {}: {}", name, url, e);
+                        None
+                    }
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// known placeholders are replaced, unknown and unterminated ones are left alone
+    fn expand_replaces_known_placeholders() {
+        let url = Url::parse("http://localhost/api/users/").unwrap();
+
+        assert_eq!(expand("word={{word}}", &url, None), "word=users");
+        assert_eq!(expand("{{nope}}-{{word}}", &url, None), "{{nope}}-users");
+        assert_eq!(expand("{{word", &url, None), "{{word");
+        assert_eq!(expand("plain", &url, None), "plain");
+
+        let expanded = expand("{{uuid}}", &url, None);
+        assert_eq!(expanded.len(), 36);
+        assert_ne!(expanded, expand("{{uuid}}", &url, None));
+
+        assert!(expand("{{rand_int}}", &url, None).parse::<u32>().is_ok());
+        assert!(expand("{{timestamp}}", &url, None).parse::<u64>().unwrap() > 0);
+    }
+
+    #[test]
+    /// typos in placeholder names are reported
+    fn unknown_placeholders_are_reported() {
+        assert!(unknown_placeholders("cb={{rand_int}}&t={{timestamp}}").is_empty());
+        assert_eq!(
+            unknown_placeholders("{{uuid}} {{random}}"),
+            vec!["{{random}}"]
+        );
+        assert!(!is_template("Bearer abc"));
+        assert!(is_template("{{random}}"));
+    }
+
+    #[test]
+    /// only headers with placeholders are kept, and they're expanded per request
+    fn templated_headers_expand_per_request() {
+        let mut headers = HashMap::new();
+        headers.insert("X-Word".to_string(), "{{word}}".to_string());
+        headers.insert("Accept".to_string(), "text/html".to_string());
+
+        let templated = TemplatedHeaders::default();
+        templated.load(&headers);
+
+        let url = Url::parse("http://localhost/admin").unwrap();
+        let values = templated.values_for(&url, None);
+
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].0, "x-word");
+        assert_eq!(values[0].1, "admin");
+    }
+
+    #[test]
+    /// {{word}} is the word the request was made for, not whatever -x added to the path
+    fn word_is_the_wordlist_word() {
+        let url = Url::parse("http://localhost/api/users.php").unwrap();
+
+        assert_eq!(expand("{{word}}", &url, Some("users")), "users");
+        assert_eq!(expand("{{word}}", &url, None), "users.php");
+
+        let url = Url::parse("http://localhost/api/v1/").unwrap();
+        assert_eq!(expand("{{word}}", &url, Some("api/v1")), "api/v1");
+    }
+}
@@ -0,0 +1,230 @@
+//! small, high-signal wordlists embedded in the binary (--preset), usable alongside or instead
+//! of --wordlist
+use std::collections::HashSet;
+
+/// version of the bundled presets, bumped whenever their words change so that results can be
+/// tied back to the exact lists that produced them
+pub const PRESETS_VERSION: u32 = 1;
+
+/// names accepted by --preset, in the order they're listed in --help
+pub const PRESET_NAMES: [&str; 4] = ["admin-panels", "api", "cloud-metadata", "backup-files"];
+
+/// login pages and administrative interfaces of common applications and appliances
+const ADMIN_PANELS: [&str; 40] = [
+    "admin",
+    "admin.php",
+    "admin/login",
+    "admin/login.php",
+    "administrator",
+    "administrator/index.php",
+    "adminer.php",
+    "admincp",
+    "adminpanel",
+    "admin-console",
+    "backend",
+    "controlpanel",
+    "cpanel",
+    "dashboard",
+    "jenkins",
+    "jmx-console",
+    "web-console",
+    "kibana",
+    "login",
+    "login.php",
+    "manage",
+    "management",
+    "manager/html",
+    "moderator",
+    "panel",
+    "phpmyadmin",
+    "pma",
+    "portal",
+    "siteadmin",
+    "solr/admin",
+    "sysadmin",
+    "user/login",
+    "webadmin",
+    "wp-admin",
+    "wp-login.php",
+    "_admin",
+    "actuator",
+    "console",
+    "grafana",
+    "zabbix",
+];
+
+/// api roots, specs, and explorers
+const API: [&str; 36] = [
+    "api",
+    "api/v1",
+    "api/v2",
+    "api/v3",
+    "api/internal",
+    "api/private",
+    "api/swagger.json",
+    "api/openapi.json",
+    "api-docs",
+    "apidocs",
+    "graphql",
+    "graphiql",
+    "graphql/console",
+    "playground",
+    "openapi.json",
+    "openapi.yaml",
+    "rest",
+    "rest/api",
+    "services",
+    "swagger",
+    "swagger.json",
+    "swagger.yaml",
+    "swagger-ui",
+    "swagger-ui.html",
+    "swagger/index.html",
+    "v1",
+    "v2",
+    "v3",
+    "v1/api-docs",
+    "v2/api-docs",
+    "v3/api-docs",
+    "ws",
+    "wsdl",
+    "json",
+    "rpc",
+    "jsonrpc",
+];
+
+/// instance metadata endpoints of cloud providers; mostly of interest when the target proxies
+/// requests to other hosts, i.e. through an ssrf
+const CLOUD_METADATA: [&str; 24] = [
+    "latest/meta-data/",
+    "latest/meta-data/hostname",
+    "latest/meta-data/iam/",
+    "latest/meta-data/iam/info",
+    "latest/meta-data/iam/security-credentials/",
+    "latest/meta-data/public-keys/",
+    "latest/user-data",
+    "latest/dynamic/instance-identity/document",
+    "latest/api/token",
+    "computeMetadata/v1/",
+    "computeMetadata/v1/project/project-id",
+    "computeMetadata/v1/instance/hostname",
+    "computeMetadata/v1/instance/service-accounts/",
+    "computeMetadata/v1/instance/service-accounts/default/token",
+    "computeMetadata/v1beta1/",
+    "metadata/instance",
+    "metadata/identity/oauth2/token",
+    "metadata/v1/",
+    "metadata/v1.json",
+    "openstack/latest/meta_data.json",
+    "openstack/latest/user_data",
+    "opc/v1/instance/",
+    "opc/v2/instance/",
+    "2009-04-04/meta-data/",
+];
+
+/// archives, dumps, and editor leftovers that commonly expose source or data
+const BACKUP_FILES: [&str; 40] = [
+    "backup",
+    "backups",
+    "backup.zip",
+    "backup.tar",
+    "backup.tar.gz",
+    "backup.tgz",
+    "backup.sql",
+    "backup.sql.gz",
+    "backup.rar",
+    "backup.7z",
+    "site.zip",
+    "site.tar.gz",
+    "www.zip",
+    "www.tar.gz",
+    "web.zip",
+    "html.zip",
+    "htdocs.zip",
+    "public_html.zip",
+    "dump.sql",
+    "database.sql",
+    "db.sql",
+    "db.sqlite",
+    "db.sqlite3",
+    "data.sql",
+    "mysql.sql",
+    "old",
+    "old.zip",
+    "index.php.bak",
+    "index.php~",
+    "index.php.old",
+    "index.php.swp",
+    ".index.php.swp",
+    "config.php.bak",
+    "config.php~",
+    "config.php.old",
+    "web.config.bak",
+    "web.config.old",
+    ".env.bak",
+    ".env.old",
+    "wp-config.php.bak",
+];
+
+/// Return the words of the preset with the given name, or `None` for an unknown name
+pub fn preset(name: &str) -> Option<&'static [&'static str]> {
+    match name {
+        "admin-panels" => Some(&ADMIN_PANELS),
+        "api" => Some(&API),
+        "cloud-metadata" => Some(&CLOUD_METADATA),
+        "backup-files" => Some(&BACKUP_FILES),
+        _ => None,
+    }
+}
+
+/// Words of every named preset, in order; words that show up in more than one preset (or in
+/// `existing`) are only included once
+///
+/// unknown names are skipped, they're reported when the configuration is validated
+pub fn preset_words(names: &[String], existing: &[String]) -> Vec<String> {
+    let mut seen: HashSet<&str> = existing.iter().map(String::as_str).collect();
+    let mut words = Vec::new();
+
+    for name in names {
+        for word in preset(name).unwrap_or_default() {
+            if seen.insert(*word) {
+                words.push(word.to_string());
+            }
+        }
+    }
+
+    words
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// every advertised name has words, and no preset repeats a word
+    fn every_preset_is_populated_and_unique() {
+        for name in PRESET_NAMES.iter() {
+            let words = preset(name).unwrap();
+            let unique: HashSet<_> = words.iter().collect();
+
+            assert!(!words.is_empty(), "{}", name);
+            assert_eq!(unique.len(), words.len(), "{}", name);
+        }
+
+        assert!(preset("nope").is_none());
+    }
+
+    #[test]
+    /// presets are combined in order, without repeating words already present
+    fn preset_words_combines_presets_without_duplicates() {
+        let existing = vec!["admin".to_string()];
+        let names = vec!["admin-panels".to_string(), "api".to_string()];
+
+        let words = preset_words(&names, &existing);
+
+        assert!(!words.contains(&"admin".to_string()));
+        assert_eq!(words[0], "admin.php");
+        assert!(words.contains(&"swagger.json".to_string()));
+        assert_eq!(words.len(), ADMIN_PANELS.len() - 1 + API.len());
+    }
+}
@@ -0,0 +1,254 @@
+//! bundled, product-specific wordlist segments that are appended to the user's wordlist when
+//! --fingerprint identifies the matching product
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::{event_handlers::Handles, fingerprint::fingerprint_target};
+
+/// paths specific to WordPress installations
+const WORDPRESS: [&str; 28] = [
+    "wp-admin",
+    "wp-admin/admin-ajax.php",
+    "wp-admin/install.php",
+    "wp-admin/setup-config.php",
+    "wp-admin/upgrade.php",
+    "wp-content",
+    "wp-content/backup-db",
+    "wp-content/debug.log",
+    "wp-content/plugins",
+    "wp-content/themes",
+    "wp-content/uploads",
+    "wp-includes",
+    "wp-json",
+    "wp-json/wp/v2/users",
+    "wp-config.php",
+    "wp-config.php.bak",
+    "wp-config.php.old",
+    "wp-config.php.save",
+    "wp-config.php~",
+    "wp-cron.php",
+    "wp-links-opml.php",
+    "wp-login.php",
+    "wp-mail.php",
+    "wp-signup.php",
+    "wp-trackback.php",
+    "xmlrpc.php",
+    "readme.html",
+    "license.txt",
+];
+
+/// paths specific to Apache Tomcat
+const TOMCAT: [&str; 22] = [
+    "manager",
+    "manager/html",
+    "manager/status",
+    "manager/text",
+    "manager/jmxproxy",
+    "host-manager",
+    "host-manager/html",
+    "host-manager/text",
+    "examples",
+    "examples/jsp",
+    "examples/servlets",
+    "examples/websocket",
+    "docs",
+    "WEB-INF",
+    "WEB-INF/web.xml",
+    "META-INF",
+    "META-INF/MANIFEST.MF",
+    "status",
+    "probe",
+    "axis2",
+    "axis2-admin",
+    "servlet",
+];
+
+/// paths specific to Microsoft SharePoint
+const SHAREPOINT: [&str; 24] = [
+    "_layouts",
+    "_layouts/15/authenticate.aspx",
+    "_layouts/15/people.aspx",
+    "_layouts/15/settings.aspx",
+    "_layouts/15/start.aspx",
+    "_layouts/15/userdisp.aspx",
+    "_layouts/15/viewlsts.aspx",
+    "_layouts/15/workflow.aspx",
+    "_vti_bin",
+    "_vti_bin/lists.asmx",
+    "_vti_bin/permissions.asmx",
+    "_vti_bin/search.asmx",
+    "_vti_bin/sites.asmx",
+    "_vti_bin/spsdisco.aspx",
+    "_vti_bin/usergroup.asmx",
+    "_vti_bin/webs.asmx",
+    "_vti_pvt",
+    "_vti_pvt/service.cnf",
+    "_api/web",
+    "_api/web/siteusers",
+    "_catalogs/masterpage",
+    "Lists",
+    "Pages",
+    "SitePages",
+];
+
+/// paths specific to Drupal installations
+const DRUPAL: [&str; 14] = [
+    "CHANGELOG.txt",
+    "core/CHANGELOG.txt",
+    "core/install.php",
+    "cron.php",
+    "install.php",
+    "update.php",
+    "node",
+    "admin/config",
+    "user/login",
+    "user/register",
+    "sites/default",
+    "sites/default/files",
+    "sites/default/settings.php",
+    "sites/default/settings.php.bak",
+];
+
+/// paths specific to Joomla installations
+const JOOMLA: [&str; 15] = [
+    "administrator",
+    "administrator/manifests/files/joomla.xml",
+    "administrator/logs",
+    "configuration.php",
+    "configuration.php.bak",
+    "configuration.php~",
+    "components",
+    "modules",
+    "plugins",
+    "templates",
+    "cache",
+    "logs",
+    "tmp",
+    "language/en-GB/en-GB.xml",
+    "htaccess.txt",
+];
+
+/// Return the bundled wordlist segment for the technology with the given name; empty when the
+/// technology doesn't have one
+pub fn product_wordlist(name: &str) -> &'static [&'static str] {
+    match name {
+        "WordPress" => &WORDPRESS,
+        "Tomcat" => &TOMCAT,
+        "SharePoint" => &SHAREPOINT,
+        "Drupal" => &DRUPAL,
+        "Joomla" => &JOOMLA,
+        _ => &[],
+    }
+}
+
+/// Fingerprint each target and append the bundled wordlist segment of every identified product to
+/// `words`; words already present in the wordlist aren't added a second time
+///
+/// targets that can't be fingerprinted are skipped
+pub async fn extend_wordlist(
+    words: Arc<Vec<String>>,
+    targets: &[String],
+    handles: Arc<Handles>,
+) -> Arc<Vec<String>> {
+    log::trace!(
+        "enter: extend_wordlist(wordlist[{} words...], {:?}, {:?})",
+        words.len(),
+        targets,
+        handles
+    );
+
+    let mut seen: HashSet<&str> = words.iter().map(String::as_str).collect();
+    let mut additions = Vec::new();
+
+    for target in targets {
+        let technologies = match fingerprint_target(target, handles.clone()).await {
+            Ok(technologies) => technologies,
+            Err(e) => {
+                log::warn!("Could not fingerprint {}: {}", target, e);
+                continue;
+            }
+        };
+
+        for tech in technologies {
+            let segment = product_wordlist(tech.name);
+
+            if !segment.is_empty() {
+                log::info!(
+                    "{} identified on {}, adding its wordlist",
+                    tech.name,
+                    target
+                );
+            }
+
+            for word in segment {
+                if seen.insert(*word) {
+                    additions.push(word.to_string());
+                }
+            }
+        }
+    }
+
+    if additions.is_empty() {
+        log::trace!(
+            "exit: extend_wordlist -> wordlist[{} words...]",
+            words.len()
+        );
+        return words;
+    }
+
+    let mut extended = words.to_vec();
+    extended.extend(additions);
+
+    log::trace!(
+        "exit: extend_wordlist -> wordlist[{} words...]",
+        extended.len()
+    );
+    Arc::new(extended)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::Configuration, scan_manager::FeroxScans};
+    use httpmock::{Method::GET, MockServer};
+
+    #[test]
+    /// only products with a bundled segment return words
+    fn product_wordlist_returns_segment_for_known_products() {
+        assert!(product_wordlist("WordPress").contains(&"wp-login.php"));
+        assert!(product_wordlist("Tomcat").contains(&"manager/html"));
+        assert!(product_wordlist("SharePoint").contains(&"_vti_bin"));
+        assert!(product_wordlist("nginx").is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// identified products have their words appended once, after the user's wordlist
+    async fn extend_wordlist_appends_words_for_identified_products() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET).path("/");
+            then.status(200)
+                .header("server", "Apache-Coyote/1.1")
+                .body("<html><title>Apache Tomcat/9.0.41</title></html>");
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let scans = Arc::new(FeroxScans::default());
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        let words = Arc::new(vec!["admin".to_string(), "manager".to_string()]);
+        let targets = vec![srv.url("/")];
+
+        let extended = extend_wordlist(words, &targets, handles.clone()).await;
+
+        assert_eq!(&extended[..2], &["admin", "manager"]);
+        assert_eq!(extended.len(), 2 + TOMCAT.len() - 1);
+        assert_eq!(extended.iter().filter(|w| *w == "manager").count(), 1);
+        assert!(extended.contains(&"manager/html".to_string()));
+
+        // fingerprints are cached per target, the target isn't requested a second time
+        extend_wordlist(extended, &targets, handles).await;
+        assert_eq!(mock.hits(), 1);
+    }
+}
@@ -1,14 +1,53 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use console::user_attended;
 use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
 use lazy_static::lazy_static;
 
+/// redraws per second of `PROGRESS_BAR` when --refresh-rate isn't used; same as indicatif's
+const DEFAULT_REFRESH_HZ: u64 = 15;
+
+/// redraws per second of `PROGRESS_BAR`, see `set_refresh_rate`
+static REFRESH_HZ: AtomicU64 = AtomicU64::new(DEFAULT_REFRESH_HZ);
+
 lazy_static! {
     /// Global progress bar that houses other progress bars
-    pub static ref PROGRESS_BAR: MultiProgress = MultiProgress::with_draw_target(ProgressDrawTarget::stdout());
+    ///
+    /// bars are only drawn when stdout is a terminal; redirected output (CI jobs, containers,
+    /// pipes) would otherwise be filled with redraws of every bar
+    pub static ref PROGRESS_BAR: MultiProgress = MultiProgress::with_draw_target(draw_target());
 
     /// Global progress bar that is only used for printing messages that don't jack up other bars
     pub static ref PROGRESS_PRINTER: ProgressBar = add_bar("", 0, BarType::Hidden);
 }
 
+/// Where `PROGRESS_BAR` is drawn; hidden when stdout isn't a terminal
+pub fn draw_target() -> ProgressDrawTarget {
+    if user_attended() {
+        ProgressDrawTarget::stdout_with_hz(REFRESH_HZ.load(Ordering::Relaxed))
+    } else {
+        ProgressDrawTarget::hidden()
+    }
+}
+
+/// Redraw `PROGRESS_BAR` about every `millis` milliseconds (--refresh-rate); 0 keeps the default
+///
+/// indicatif schedules redraws a whole number of times per second, so the interval is rounded to
+/// the nearest one of those, and can be at most a second
+pub fn set_refresh_rate(millis: u64) {
+    if millis == 0 {
+        return;
+    }
+
+    REFRESH_HZ.store(refresh_hz(millis), Ordering::Relaxed);
+    PROGRESS_BAR.set_draw_target(draw_target());
+}
+
+/// number of redraws per second closest to one every `millis` milliseconds
+fn refresh_hz(millis: u64) -> u64 {
+    ((1000 + millis / 2) / millis).max(1)
+}
+
 /// Types of ProgressBars that can be added to `PROGRESS_BAR`
 #[derive(Copy, Clone)]
 pub enum BarType {
@@ -21,11 +60,9 @@ pub enum BarType {
     /// similar to `Default`, except `-` is used in place of line/word/char count
     Message,
 
-    /// bar used to show overall scan metrics
+    /// bar used to show overall scan metrics; position and length are given through the prefix so
+    /// they can be formatted (i.e. --thousands-separator)
     Total,
-
-    /// simpler output bar that shows only the directory being scanned (no updating info)
-    Quiet,
 }
 
 /// Add an [indicatif::ProgressBar](https://docs.rs/indicatif/latest/indicatif/struct.ProgressBar.html)
@@ -42,9 +79,8 @@ pub fn add_bar(prefix: &str, length: u64, bar_type: BarType) -> ProgressBar {
             "-"
         )),
         BarType::Total => {
-            style.template("[{bar:.yellow/blue}] - {elapsed:<4} {pos:>7}/{len:7} {eta:7} {msg}")
+            style.template("[{bar:.yellow/blue}] - {elapsed:<4} {prefix} {eta:7} {msg}")
         }
-        BarType::Quiet => style.template("Scanning: {prefix}"),
     };
 
     let progress_bar = PROGRESS_BAR.add(ProgressBar::new(length));
@@ -60,6 +96,16 @@ pub fn add_bar(prefix: &str, length: u64, bar_type: BarType) -> ProgressBar {
 mod tests {
     use super::*;
 
+    #[test]
+    /// refresh intervals are turned into the nearest whole number of redraws per second
+    fn refresh_hz_rounds_to_nearest() {
+        assert_eq!(refresh_hz(67), 15);
+        assert_eq!(refresh_hz(100), 10);
+        assert_eq!(refresh_hz(300), 3);
+        assert_eq!(refresh_hz(1000), 1);
+        assert_eq!(refresh_hz(1), 1000);
+    }
+
     #[test]
     /// hit all code branches for add_bar
     fn add_bar_with_all_configurations() {
@@ -4,6 +4,7 @@ use std::{
     fmt,
     str::FromStr,
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
@@ -16,6 +17,8 @@ use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
 use crate::{
+    burp::SentRequest,
+    client::{RedirectHop, UnpinnedUrl, BANDWIDTH},
     config::OutputLevel,
     event_handlers::{Command, Handles},
     traits::FeroxSerialize,
@@ -33,8 +36,9 @@ pub struct FeroxResponse {
     /// The `StatusCode` of this `FeroxResponse`
     status: StatusCode,
 
-    /// The full response text
-    text: String,
+    /// The full response text; shared between clones so that handing a response to another
+    /// handler doesn't copy the body
+    text: Arc<String>,
 
     /// The content-length of this response, if known
     content_length: u64,
@@ -51,6 +55,25 @@ pub struct FeroxResponse {
     /// Wildcard response status
     wildcard: bool,
 
+    /// whether or not this response's url was extracted from a javascript file
+    extracted_from_js: bool,
+
+    /// whether reading the body stopped early because of --max-response-size
+    truncated: bool,
+
+    /// whether the body was skipped entirely (--head-only), leaving word and line counts unknown
+    headers_only: bool,
+
+    /// redirects that were followed on the way to this response's url (--redirects)
+    redirect_chain: Vec<RedirectHop>,
+
+    /// how long the request took to be answered (--response-time)
+    response_time: Option<Duration>,
+
+    /// the request that was sent for this response, as it looked on the wire; only recorded with
+    /// --burp-output or --evidence-dir
+    sent_request: Option<Arc<String>>,
+
     /// whether the user passed --quiet|--silent on the command line
     pub(crate) output_level: OutputLevel,
 }
@@ -62,12 +85,18 @@ impl Default for FeroxResponse {
         Self {
             url: Url::parse("http://localhost").unwrap(),
             status: Default::default(),
-            text: "".to_string(),
+            text: Default::default(),
             content_length: 0,
             line_count: 0,
             word_count: 0,
             headers: Default::default(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
         }
     }
@@ -99,6 +128,56 @@ impl FeroxResponse {
         self.wildcard
     }
 
+    /// Get the `extracted_from_js` of this `FeroxResponse`
+    pub fn extracted_from_js(&self) -> bool {
+        self.extracted_from_js
+    }
+
+    /// Set `FeroxResponse`'s `extracted_from_js` attribute
+    pub fn set_extracted_from_js(&mut self, extracted_from_js: bool) {
+        self.extracted_from_js = extracted_from_js;
+    }
+
+    /// Get the `truncated` of this `FeroxResponse`
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Get the `headers_only` of this `FeroxResponse`
+    pub fn headers_only(&self) -> bool {
+        self.headers_only
+    }
+
+    /// Get the redirects that were followed on the way to this `FeroxResponse`
+    pub fn redirect_chain(&self) -> &[RedirectHop] {
+        &self.redirect_chain
+    }
+
+    /// Set `FeroxResponse`'s `redirect_chain` attribute
+    pub fn set_redirect_chain(&mut self, redirect_chain: Vec<RedirectHop>) {
+        self.redirect_chain = redirect_chain;
+    }
+
+    /// Get how long the request took to be answered, if it was recorded (--response-time)
+    pub fn response_time(&self) -> Option<Duration> {
+        self.response_time
+    }
+
+    /// Set `FeroxResponse`'s `response_time` attribute
+    pub fn set_response_time(&mut self, response_time: Duration) {
+        self.response_time = Some(response_time);
+    }
+
+    /// Get the request that was sent for this response, if it was recorded
+    pub fn sent_request(&self) -> Option<&str> {
+        self.sent_request.as_deref().map(String::as_str)
+    }
+
+    /// Set `FeroxResponse`'s `sent_request` attribute
+    pub fn set_sent_request(&mut self, sent_request: &str) {
+        self.sent_request = Some(Arc::new(sent_request.to_string()));
+    }
+
     /// Get the final `Url` of this `FeroxResponse`.
     pub fn url(&self) -> &Url {
         &self.url
@@ -136,22 +215,33 @@ impl FeroxResponse {
         self.wildcard = is_wildcard;
     }
 
+    /// set `status` attribute
+    #[cfg(test)]
+    pub fn set_status(&mut self, status: StatusCode) {
+        self.status = status;
+    }
+
     /// set `text` attribute; update words/lines/content_length
     #[cfg(test)]
     pub fn set_text(&mut self, text: &str) {
-        self.text = String::from(text);
+        self.text = Arc::new(String::from(text));
         self.content_length = self.text.len() as u64;
-        self.line_count = self.text.lines().count();
-        self.word_count = self
-            .text
-            .lines()
-            .map(|s| s.split_whitespace().count())
-            .sum();
+
+        let (line_count, word_count) = count_lines_and_words(&self.text);
+        self.line_count = line_count;
+        self.word_count = word_count;
+    }
+
+    /// add a header to the `headers` attribute; existing values for the same name are kept
+    #[cfg(test)]
+    pub fn add_header(&mut self, name: &'static str, value: &str) {
+        self.headers
+            .append(name, HeaderValue::from_str(value).unwrap());
     }
 
     /// free the `text` data, reducing memory usage
     pub fn drop_text(&mut self) {
-        self.text = String::new();
+        self.text = Default::default();
     }
 
     /// Make a reasonable guess at whether the response is a file or not
@@ -175,6 +265,23 @@ impl FeroxResponse {
         self.url.query_pairs().count() > 0 || has_extension
     }
 
+    /// Make a reasonable guess at whether the response is javascript or not
+    ///
+    /// Examines the Content-Type header as well as the extension of the url's path
+    pub fn is_javascript(&self) -> bool {
+        if let Some(content_type) = self.headers.get("Content-Type") {
+            if let Ok(ct_str) = content_type.to_str() {
+                if ct_str.to_ascii_lowercase().contains("javascript") {
+                    return true;
+                }
+            }
+        }
+
+        let path = self.url.path().to_ascii_lowercase();
+
+        path.ends_with(".js") || path.ends_with(".mjs")
+    }
+
     /// Returns line count of the response text.
     pub fn line_count(&self) -> usize {
         self.line_count
@@ -185,42 +292,88 @@ impl FeroxResponse {
         self.word_count
     }
 
+    /// Create a line in the format nuclei and httpx read lists of urls from
+    /// (--output-format urls-with-meta)
+    ///
+    /// ex: https://localhost/admin [200] [text/html]
+    pub fn as_url_with_meta(&self) -> String {
+        let content_type = self
+            .headers
+            .get("content-type")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .unwrap_or_default()
+            .trim();
+
+        format!(
+            "{} [{}] [{}]\n",
+            self.url,
+            self.status.as_u16(),
+            content_type
+        )
+    }
+
     /// Create a new `FeroxResponse` from the given `Response`
-    pub async fn from(response: Response, read_body: bool, output_level: OutputLevel) -> Self {
-        let url = response.url().clone();
+    ///
+    /// at most `max_size` bytes of the body are read (0 for no limit, see --max-response-size)
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(url = %response.url())))]
+    pub async fn from(
+        response: Response,
+        read_body: bool,
+        max_size: u64,
+        output_level: OutputLevel,
+    ) -> Self {
+        // --host-header; responses are reported against the ip that was requested
+        let url = response
+            .extensions()
+            .get::<UnpinnedUrl>()
+            .map_or_else(|| response.url().clone(), |unpinned| unpinned.0.clone());
         let status = response.status();
         let headers = response.headers().clone();
         let content_length = response.content_length().unwrap_or(0);
+        let sent_request = response
+            .extensions()
+            .get::<SentRequest>()
+            .map(|sent| sent.0.clone());
 
-        let text = if read_body {
-            // .text() consumes the response, must be called last
+        let (text, truncated) = if read_body {
+            // reading the body consumes the response, must be called last
             // additionally, --extract-links is currently the only place we use the body of the
             // response, so we forego the processing if not performing extraction
-            match response.text().await {
+            match read_text(response, max_size).await {
                 // await the response's body
-                Ok(text) => text,
+                Ok(body) => body,
                 Err(e) => {
                     log::warn!("Could not parse body from response: {}", e);
-                    String::new()
+                    (String::new(), false)
                 }
             }
         } else {
-            String::new()
+            (String::new(), false)
         };
 
-        let line_count = text.lines().count();
-        let word_count = text.lines().map(|s| s.split_whitespace().count()).sum();
+        if truncated {
+            log::debug!("stopped reading {} after {} bytes", url, max_size);
+        }
+
+        let (line_count, word_count) = count_lines_and_words(&text);
 
         FeroxResponse {
             url,
             status,
             content_length,
-            text,
+            text: Arc::new(text),
             headers,
             line_count,
             word_count,
             output_level,
+            truncated,
+            headers_only: !read_body,
             wildcard: false,
+            extracted_from_js: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request,
         }
     }
 
@@ -318,12 +471,56 @@ impl FeroxResponse {
     }
 }
 
+/// count lines and words of the given text in a single pass, without allocating
+fn count_lines_and_words(text: &str) -> (usize, usize) {
+    text.lines().fold((0, 0), |(lines, words), line| {
+        (lines + 1, words + line.split_whitespace().count())
+    })
+}
+
+/// read the body of the given response, stopping once `max_size` bytes have arrived (0 for no
+/// limit); returns the body and whether it was cut short
+///
+/// dropping the response mid-body closes its connection, so whatever the server has left to send
+/// is never downloaded. each chunk is held back as needed to stay under --throttle-bytes
+async fn read_text(mut response: Response, max_size: u64) -> reqwest::Result<(String, bool)> {
+    if max_size == 0 && !BANDWIDTH.is_limited() {
+        return Ok((response.text().await?, false));
+    }
+
+    let max_size = match usize::try_from(max_size) {
+        Ok(0) | Err(_) => usize::MAX,
+        Ok(max_size) => max_size,
+    };
+    let mut body = Vec::new();
+
+    while let Some(chunk) = response.chunk().await? {
+        BANDWIDTH.consume(chunk.len()).await;
+
+        let room = max_size - body.len();
+
+        if chunk.len() > room {
+            body.extend_from_slice(&chunk[..room]);
+            // a multi-byte character may have been split at the cut, hence the lossy conversion
+            return Ok((String::from_utf8_lossy(&body).into_owned(), true));
+        }
+
+        body.extend_from_slice(&chunk);
+    }
+
+    Ok((String::from_utf8_lossy(&body).into_owned(), false))
+}
+
 /// Implement FeroxSerialize for FeroxResponse
 impl FeroxSerialize for FeroxResponse {
     /// Simple wrapper around create_report_string
     fn as_str(&self) -> String {
-        let lines = self.line_count().to_string();
-        let words = self.word_count().to_string();
+        let (lines, words) = if self.headers_only {
+            // no body, no counts; shown as - rather than a misleading 0
+            ("-".to_string(), "-".to_string())
+        } else {
+            (self.line_count().to_string(), self.word_count().to_string())
+        };
         let chars = self.content_length().to_string();
         let status = self.status().as_str();
         let wild_status = status_colorizer("WLD");
@@ -367,12 +564,40 @@ impl FeroxSerialize for FeroxResponse {
             message
         } else {
             // not a wildcard, just create a normal entry
+            let verbose = matches!(self.output_level, OutputLevel::Default | OutputLevel::Quiet);
+
+            let mut url = self.url().to_string();
+
+            if self.extracted_from_js && verbose {
+                url.push_str(" (extracted-from-JS)");
+            }
+
+            if self.truncated && verbose {
+                url.push_str(" (truncated)");
+            }
+
+            if !self.redirect_chain.is_empty() && verbose {
+                // show every hop that led to the final url, i.e. 301 http://a => 302 http://b
+                let chain = self
+                    .redirect_chain
+                    .iter()
+                    .map(|hop| format!("{} {}", hop.status, hop.url))
+                    .collect::<Vec<_>>()
+                    .join(" => ");
+
+                url.push_str(&format!(" (redirected: {})", chain));
+            }
+
+            if let Some(response_time) = self.response_time.filter(|_| verbose) {
+                url.push_str(&format!(" ({}ms)", response_time.as_millis()));
+            }
+
             utils::create_report_string(
                 self.status.as_str(),
                 &lines,
                 &words,
                 &chars,
-                self.url().as_str(),
+                &url,
                 self.output_level,
             )
         }
@@ -386,6 +611,8 @@ impl FeroxSerialize for FeroxResponse {
     ///    "type":"response",
     ///    "url":"https://localhost.com/images",
     ///    "path":"/images",
+    ///    "wildcard":false,
+    ///    "extracted_from_js":false,
     ///    "status":301,
     ///    "content_length":179,
     ///    "line_count":10,
@@ -423,7 +650,7 @@ impl Serialize for FeroxResponse {
         S: Serializer,
     {
         let mut headers = HashMap::new();
-        let mut state = serializer.serialize_struct("FeroxResponse", 7)?;
+        let mut state = serializer.serialize_struct("FeroxResponse", 8)?;
 
         // need to convert the HeaderMap to a HashMap in order to pass it to the serializer
         for (key, value) in &self.headers {
@@ -436,12 +663,33 @@ impl Serialize for FeroxResponse {
         state.serialize_field("url", self.url.as_str())?;
         state.serialize_field("path", self.url.path())?;
         state.serialize_field("wildcard", &self.wildcard)?;
+        state.serialize_field("extracted_from_js", &self.extracted_from_js)?;
         state.serialize_field("status", &self.status.as_u16())?;
         state.serialize_field("content_length", &self.content_length)?;
         state.serialize_field("line_count", &self.line_count)?;
         state.serialize_field("word_count", &self.word_count)?;
         state.serialize_field("headers", &headers)?;
 
+        if !self.redirect_chain.is_empty() {
+            // only present when redirects were actually followed
+            state.serialize_field("redirect_chain", &self.redirect_chain)?;
+        }
+
+        if self.truncated {
+            // only present when --max-response-size cut the body short
+            state.serialize_field("truncated", &self.truncated)?;
+        }
+
+        if self.headers_only {
+            // only present with --head-only, where line_count and word_count are meaningless
+            state.serialize_field("headers_only", &self.headers_only)?;
+        }
+
+        if let Some(response_time) = self.response_time {
+            // only present with --response-time
+            state.serialize_field("response_time_ms", &(response_time.as_millis() as u64))?;
+        }
+
         state.end()
     }
 }
@@ -456,10 +704,16 @@ impl<'de> Deserialize<'de> for FeroxResponse {
         let mut response = Self {
             url: Url::parse("http://localhost").unwrap(),
             status: StatusCode::OK,
-            text: String::new(),
+            text: Default::default(),
             content_length: 0,
             headers: HeaderMap::new(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
             line_count: 0,
             word_count: 0,
@@ -521,6 +775,31 @@ impl<'de> Deserialize<'de> for FeroxResponse {
                         response.wildcard = result;
                     }
                 }
+                "extracted_from_js" => {
+                    if let Some(result) = value.as_bool() {
+                        response.extracted_from_js = result;
+                    }
+                }
+                "headers_only" => {
+                    if let Some(result) = value.as_bool() {
+                        response.headers_only = result;
+                    }
+                }
+                "truncated" => {
+                    if let Some(result) = value.as_bool() {
+                        response.truncated = result;
+                    }
+                }
+                "redirect_chain" => {
+                    if let Ok(chain) = serde_json::from_value(value.clone()) {
+                        response.redirect_chain = chain;
+                    }
+                }
+                "response_time_ms" => {
+                    if let Some(millis) = value.as_u64() {
+                        response.response_time = Some(Duration::from_millis(millis));
+                    }
+                }
                 _ => {}
             }
         }
@@ -541,12 +820,18 @@ mod tests {
         let response = FeroxResponse {
             url,
             status: Default::default(),
-            text: "".to_string(),
+            text: Default::default(),
             content_length: 0,
             line_count: 0,
             word_count: 0,
             headers: Default::default(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
         };
         let result = response.reached_max_depth(0, 0, handles);
@@ -562,12 +847,18 @@ mod tests {
         let response = FeroxResponse {
             url,
             status: Default::default(),
-            text: "".to_string(),
+            text: Default::default(),
             content_length: 0,
             line_count: 0,
             word_count: 0,
             headers: Default::default(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
         };
 
@@ -583,12 +874,18 @@ mod tests {
         let response = FeroxResponse {
             url,
             status: Default::default(),
-            text: "".to_string(),
+            text: Default::default(),
             content_length: 0,
             line_count: 0,
             word_count: 0,
             headers: Default::default(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
         };
 
@@ -604,12 +901,18 @@ mod tests {
         let response = FeroxResponse {
             url,
             status: Default::default(),
-            text: "".to_string(),
+            text: Default::default(),
             content_length: 0,
             line_count: 0,
             word_count: 0,
             headers: Default::default(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
         };
 
@@ -625,16 +928,209 @@ mod tests {
         let response = FeroxResponse {
             url,
             status: Default::default(),
-            text: "".to_string(),
+            text: Default::default(),
             content_length: 0,
             line_count: 0,
             word_count: 0,
             headers: Default::default(),
             wildcard: false,
+            extracted_from_js: false,
+            truncated: false,
+            headers_only: false,
+            redirect_chain: Vec::new(),
+            response_time: None,
+            sent_request: None,
             output_level: Default::default(),
         };
 
         let result = response.reached_max_depth(0, 2, handles);
         assert!(result);
     }
+
+    #[test]
+    /// responses extracted from javascript should be tagged as such, except when --silent is used
+    fn as_str_tags_responses_extracted_from_js() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/api/users");
+
+        assert!(!response.as_str().contains("extracted-from-JS"));
+
+        response.set_extracted_from_js(true);
+        assert!(response.extracted_from_js());
+        assert!(response.as_str().contains("extracted-from-JS"));
+
+        response.output_level = OutputLevel::Silent;
+        assert_eq!(response.as_str(), "http://localhost/api/users\n");
+    }
+
+    #[test]
+    /// urls-with-meta lines hold the url, status, and content-type without its parameters
+    fn as_url_with_meta_includes_status_and_content_type() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/admin");
+
+        assert_eq!(
+            response.as_url_with_meta(),
+            "http://localhost/admin [200] []\n"
+        );
+
+        response.add_header("content-type", "text/html; charset=utf-8");
+        assert_eq!(
+            response.as_url_with_meta(),
+            "http://localhost/admin [200] [text/html]\n"
+        );
+    }
+
+    #[test]
+    /// followed redirects are shown in the report and serialized, except when --silent is used
+    fn redirect_chain_is_reported_and_serialized() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/new");
+
+        assert!(!response.as_json().unwrap().contains("redirect_chain"));
+
+        response.set_redirect_chain(vec![
+            RedirectHop {
+                status: 301,
+                url: "http://localhost/old".to_string(),
+            },
+            RedirectHop {
+                status: 302,
+                url: "http://localhost/older".to_string(),
+            },
+        ]);
+
+        assert!(response.as_str().contains(
+            "http://localhost/new (redirected: 301 http://localhost/old => 302 http://localhost/older)"
+        ));
+
+        let json = response.as_json().unwrap();
+        let deserialized: FeroxResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(deserialized.redirect_chain(), response.redirect_chain());
+
+        response.output_level = OutputLevel::Silent;
+        assert_eq!(response.as_str(), "http://localhost/new\n");
+    }
+
+    #[test]
+    /// recorded response times are shown in the report and serialized, except when --silent is
+    /// used
+    fn response_time_is_reported_and_serialized() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/slow");
+
+        assert!(!response.as_json().unwrap().contains("response_time_ms"));
+        assert!(!response.as_str().contains("ms)"));
+
+        response.set_response_time(Duration::from_millis(1530));
+
+        assert!(response.as_str().contains("http://localhost/slow (1530ms)"));
+
+        let json = response.as_json().unwrap();
+        assert!(json.contains(r#""response_time_ms":1530"#));
+
+        let deserialized: FeroxResponse = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            deserialized.response_time(),
+            Some(Duration::from_millis(1530))
+        );
+
+        response.output_level = OutputLevel::Silent;
+        assert_eq!(response.as_str(), "http://localhost/slow\n");
+    }
+
+    #[test]
+    /// is_javascript should key off of either the url's extension or the Content-Type header
+    fn is_javascript_checks_extension_and_content_type() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/static/app.js");
+        assert!(response.is_javascript());
+
+        response.set_url("http://localhost/static/app");
+        assert!(!response.is_javascript());
+
+        response.headers.insert(
+            "Content-Type",
+            HeaderValue::from_static("text/javascript; charset=utf-8"),
+        );
+        assert!(response.is_javascript());
+    }
+
+    #[test]
+    /// clones share the body instead of copying it, and counts are taken in a single pass
+    fn clones_share_text_and_counts_match() {
+        let mut response = FeroxResponse::default();
+        response.set_text("one two\n three  four five\n\nsix");
+
+        assert_eq!(response.line_count(), 4);
+        assert_eq!(response.word_count(), 6);
+
+        let cloned = response.clone();
+        assert!(Arc::ptr_eq(&response.text, &cloned.text));
+
+        response.drop_text();
+        assert_eq!(response.text(), "");
+        assert_eq!(cloned.text(), "one two\n three  four five\n\nsix");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// bodies larger than --max-response-size are cut short and marked as truncated, smaller
+    /// ones are read in full
+    async fn from_stops_reading_at_max_size() {
+        let srv = httpmock::MockServer::start();
+        let mock = srv.mock(|when, then| {
+            when.method(httpmock::Method::GET).path("/big");
+            then.status(200).body("a".repeat(10_000));
+        });
+
+        let url = srv.url("/big");
+
+        let response = reqwest::get(&url).await.unwrap();
+        let response = FeroxResponse::from(response, true, 1024, OutputLevel::Default).await;
+
+        assert!(response.truncated());
+        assert_eq!(response.text().len(), 1024);
+        assert!(response.as_str().contains("(truncated)"));
+        assert!(response.as_json().unwrap().contains(r#""truncated":true"#));
+
+        let response = reqwest::get(&url).await.unwrap();
+        let response = FeroxResponse::from(response, true, 20_000, OutputLevel::Default).await;
+
+        assert!(!response.truncated());
+        assert_eq!(response.text().len(), 10_000);
+        assert!(!response.as_json().unwrap().contains("truncated"));
+
+        assert_eq!(mock.hits(), 2);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// responses whose bodies were skipped (--head-only) report their counts as unknown
+    async fn from_without_body_marks_counts_unknown() {
+        let srv = httpmock::MockServer::start();
+        let mock = srv.mock(|when, then| {
+            when.method(httpmock::Method::HEAD).path("/exists");
+            then.status(200).header("content-length", "1234");
+        });
+
+        let response = reqwest::Client::new()
+            .head(&srv.url("/exists"))
+            .send()
+            .await
+            .unwrap();
+        let response = FeroxResponse::from(response, false, 0, OutputLevel::Default).await;
+
+        assert!(response.headers_only());
+        assert_eq!(response.content_length(), 1234);
+
+        let report = console::strip_ansi_codes(&response.as_str()).to_string();
+        assert!(report.contains("-l"));
+        assert!(report.contains("-w"));
+        assert!(report.contains("1234c"));
+        assert!(response
+            .as_json()
+            .unwrap()
+            .contains(r#""headers_only":true"#));
+
+        assert_eq!(mock.hits(), 1);
+    }
 }
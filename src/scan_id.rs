@@ -0,0 +1,122 @@
+//! identifier of a single run of feroxbuster (--scan-id, generated when not given), stamped on
+//! every json record written and optionally sent in a header with every request
+//! (--scan-id-header), so the run can be picked out of a server's logs
+use std::sync::RwLock;
+
+use lazy_static::lazy_static;
+use reqwest::header::{HeaderName, HeaderValue};
+use uuid::Uuid;
+
+use crate::config::Configuration;
+
+lazy_static! {
+    /// the current run's identifier, stamped on the records it writes
+    static ref SCAN_ID: RwLock<Option<String>> = RwLock::new(None);
+}
+
+/// A new, random identifier for a run
+pub fn generate() -> String {
+    Uuid::new_v4().to_string()
+}
+
+/// Make the configured scan id the current one, stamped on everything the process writes; a
+/// no-op when the scan id hasn't been assigned yet
+///
+/// only the scan started from the command line is the current one; scans started through the
+/// api, i.e. by `feroxbuster serve`, hand their results back instead of writing them
+pub fn initialize(config: &Configuration) {
+    if config.scan_id.is_empty() {
+        return;
+    }
+
+    if let Ok(mut current) = SCAN_ID.write() {
+        *current = Some(config.scan_id.clone());
+    }
+}
+
+/// The current run's identifier, if one's been assigned
+pub fn current() -> Option<String> {
+    SCAN_ID.read().ok().and_then(|current| current.clone())
+}
+
+/// The --scan-id-header to send with each of the configuration's requests, if any
+pub fn header(config: &Configuration) -> Option<(HeaderName, HeaderValue)> {
+    if config.scan_id.is_empty() || config.scan_id_header.is_empty() {
+        return None;
+    }
+
+    let name = HeaderName::from_bytes(config.scan_id_header.as_bytes());
+    let value = HeaderValue::from_str(&config.scan_id);
+
+    match (name, value) {
+        (Ok(name), Ok(value)) => Some((name, value)),
+        _ => {
+            log::warn!(
+                "Could not send scan id {} in header {}",
+                config.scan_id,
+                config.scan_id_header
+            );
+            None
+        }
+    }
+}
+
+/// Add a `scan_id` field to the end of a single json object (one line of NDJSON); anything else,
+/// or an object that already has a scan id (i.e. the configuration), is left as it is
+pub fn stamp(json: String) -> String {
+    let id = match current() {
+        Some(id) => id,
+        None => return json,
+    };
+
+    stamp_with(json, &id)
+}
+
+/// `stamp`, with the given identifier
+fn stamp_with(json: String, id: &str) -> String {
+    let trimmed = json.trim_end();
+
+    if !trimmed.starts_with('{') || !trimmed.ends_with('}') || json.contains("\"scan_id\":") {
+        return json;
+    }
+
+    let body = &trimmed[..trimmed.len() - 1];
+    let separator = if body.trim_end() == "{" { "" } else { "," };
+    let newline = if json.ends_with('\n') { "\n" } else { "" };
+
+    format!(
+        "{}{}\"scan_id\":{}}}{}",
+        body,
+        separator,
+        serde_json::to_string(id).unwrap_or_default(),
+        newline
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// the scan id is appended to json objects, keeping their trailing newline
+    fn stamp_with_appends_scan_id() {
+        assert_eq!(
+            stamp_with(String::from("{\"type\":\"response\"}\n"), "abc"),
+            "{\"type\":\"response\",\"scan_id\":\"abc\"}\n"
+        );
+        assert_eq!(
+            stamp_with(String::from("{}"), "abc"),
+            "{\"scan_id\":\"abc\"}"
+        );
+    }
+
+    #[test]
+    /// plain text, and objects that already have a scan id, aren't touched
+    fn stamp_with_skips_text_and_stamped_objects() {
+        let text = String::from("200 GET http://localhost/\n");
+        assert_eq!(stamp_with(text.clone(), "abc"), text);
+
+        let config = String::from("{\"type\":\"configuration\",\"scan_id\":\"xyz\"}\n");
+        assert_eq!(stamp_with(config.clone(), "abc"), config);
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, fs,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{Context, Result};
+use reqwest::Url;
+use serde_json::json;
+
+use crate::{
+    event_handlers::{Command, Handles},
+    finding::FeroxFinding,
+    fingerprint::murmur3_32,
+    response::FeroxResponse,
+    utils::fmt_err,
+};
+
+/// The ways in which a url's content can differ between two runs
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContentChange {
+    /// the url was found during both runs, but its content differs
+    Changed,
+
+    /// the url wasn't found during the previous run
+    Appeared,
+
+    /// the url was found during the previous run, but not this one
+    Disappeared,
+}
+
+/// Display implementation for ContentChange
+impl fmt::Display for ContentChange {
+    /// human readable name of the change, also used in findings
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ContentChange::Changed => "changed",
+            ContentChange::Appeared => "appeared",
+            ContentChange::Disappeared => "disappeared",
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+/// Hash a response body; the value is stable across runs/platforms so that it can be stored
+/// on disk and compared later
+pub(super) fn content_hash(body: &str) -> String {
+    format!("{:08x}", murmur3_32(body.as_bytes(), 0))
+}
+
+/// url -> content hash of every response reported during a scan, for comparison with
+/// --track-changes; each scan's `Handles` has its own
+#[derive(Debug, Default)]
+pub struct ContentHashes(Mutex<BTreeMap<String, String>>);
+
+impl ContentHashes {
+    /// Record the content hash of the given response
+    pub fn record(&self, response: &FeroxResponse) {
+        if let Ok(mut hashes) = self.0.lock() {
+            hashes.insert(response.url().to_string(), content_hash(response.text()));
+        }
+    }
+
+    /// every hash recorded so far
+    fn current(&self) -> BTreeMap<String, String> {
+        self.0
+            .lock()
+            .map(|hashes| hashes.clone())
+            .unwrap_or_default()
+    }
+}
+
+/// scheme://host:port of the given url, used to scope comparisons to the targets scanned
+fn origin(url: &str) -> Option<String> {
+    Url::parse(url)
+        .ok()
+        .map(|parsed| parsed.origin().ascii_serialization())
+}
+
+/// Compare the hashes from a previous run against the current ones
+///
+/// only urls that share an origin with one of the current urls can have disappeared, this allows
+/// a single file to be used to track multiple targets
+pub(super) fn diff_content(
+    previous: &BTreeMap<String, String>,
+    current: &BTreeMap<String, String>,
+) -> Vec<(String, ContentChange)> {
+    let origins: HashSet<_> = current.keys().filter_map(|url| origin(url)).collect();
+
+    let mut changes = Vec::new();
+
+    for (url, hash) in current {
+        match previous.get(url) {
+            Some(old_hash) if old_hash != hash => {
+                changes.push((url.to_string(), ContentChange::Changed))
+            }
+            Some(_) => {}
+            None => changes.push((url.to_string(), ContentChange::Appeared)),
+        }
+    }
+
+    for url in previous.keys() {
+        if current.contains_key(url) {
+            continue;
+        }
+
+        if matches!(origin(url), Some(o) if origins.contains(&o)) {
+            changes.push((url.to_string(), ContentChange::Disappeared));
+        }
+    }
+
+    changes
+}
+
+/// Compare the content seen during this run against what was stored in the --track-changes file
+/// by the previous run, report anything that changed, appeared, or disappeared, and then save
+/// the current content hashes to the same file for next time
+///
+/// when the file doesn't exist yet, the current run is simply saved as the baseline. nothing is
+/// compared or saved when the run didn't find anything (i.e. it was cancelled before scanning)
+pub fn report_changes(handles: Arc<Handles>) -> Result<()> {
+    log::trace!("enter: report_changes({:?})", handles);
+
+    let filename = &handles.config.track_changes;
+
+    let current = handles.content_hashes.current();
+
+    if filename.is_empty() || current.is_empty() {
+        log::trace!("exit: report_changes");
+        return Ok(());
+    }
+
+    let previous: Option<BTreeMap<String, String>> = match fs::read_to_string(filename) {
+        Ok(contents) => Some(serde_json::from_str(&contents).with_context(|| {
+            fmt_err(&format!("Could not parse {} as tracked content", filename))
+        })?),
+        Err(_) => {
+            log::info!("{} not found; saving this run as the baseline", filename);
+            None
+        }
+    };
+
+    let mut saved = current.clone();
+
+    if let Some(previous) = previous {
+        for (url, change) in diff_content(&previous, &current) {
+            let old_hash = previous.get(&url).cloned().unwrap_or_default();
+            let new_hash = current.get(&url).cloned().unwrap_or_default();
+
+            let finding = FeroxFinding::new(
+                "change",
+                &url,
+                &change.to_string(),
+                json!({
+                    "change": change.to_string(),
+                    "previous": old_hash,
+                    "current": new_hash,
+                }),
+            );
+
+            handles
+                .output
+                .send(Command::ReportFinding(Box::new(finding)))?;
+        }
+
+        // keep entries for targets that weren't part of this run
+        let origins: HashSet<_> = current.keys().filter_map(|url| origin(url)).collect();
+
+        for (url, hash) in previous {
+            if !matches!(origin(&url), Some(o) if origins.contains(&o)) {
+                saved.entry(url).or_insert(hash);
+            }
+        }
+    }
+
+    let serialized = serde_json::to_string_pretty(&saved)?;
+
+    fs::write(filename, serialized)
+        .with_context(|| fmt_err(&format!("Could not write tracked content to {}", filename)))?;
+
+    log::trace!("exit: report_changes");
+    Ok(())
+}
@@ -0,0 +1,95 @@
+use reqwest::Url;
+
+use crate::{config::Configuration, scan_manager::FeroxResponses};
+
+/// prefix of the --stop-on-match criteria that match findings by category, rather than urls by
+/// path
+pub const FINDING_PREFIX: &str = "finding:";
+
+/// Normalize a path given to --expect-found|--expect-absent; leading slash added, trailing
+/// slash removed, so that `.git/`, `/.git`, and `/.git/` are all the same expectation
+fn normalize(path: &str) -> String {
+    format!("/{}", path.trim_matches('/'))
+}
+
+/// Whether the given url is the expected path or is located beneath it
+pub(super) fn matches_path(expected: &str, url: &Url) -> bool {
+    let expected = normalize(expected);
+    let path = normalize(url.path());
+
+    if expected == "/" {
+        return true;
+    }
+
+    path == expected || path.starts_with(&format!("{}/", expected))
+}
+
+/// Compare the responses discovered during the scan against --expect-found and --expect-absent
+///
+/// returns one human readable message per violation; wildcard responses never satisfy (or
+/// violate) an expectation
+pub fn check_expectations(config: &Configuration, responses: &FeroxResponses) -> Vec<String> {
+    log::trace!(
+        "enter: check_expectations({:?}, {:?})",
+        config.expect_found,
+        config.expect_absent
+    );
+
+    let mut violations = Vec::new();
+
+    if config.expect_found.is_empty() && config.expect_absent.is_empty() {
+        log::trace!("exit: check_expectations -> {:?}", violations);
+        return violations;
+    }
+
+    let urls: Vec<Url> = match responses.responses.read() {
+        Ok(guard) => guard
+            .iter()
+            .filter(|response| !response.wildcard())
+            .map(|response| response.url().to_owned())
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+
+    for expected in &config.expect_found {
+        if !urls.iter().any(|url| matches_path(expected, url)) {
+            violations.push(format!(
+                "Expected {} to be found, but it wasn't",
+                normalize(expected)
+            ));
+        }
+    }
+
+    for expected in &config.expect_absent {
+        for url in urls.iter().filter(|url| matches_path(expected, url)) {
+            violations.push(format!(
+                "Expected {} to be absent, but found {}",
+                normalize(expected),
+                url
+            ));
+        }
+    }
+
+    log::trace!("exit: check_expectations -> {:?}", violations);
+    violations
+}
+
+/// The --stop-on-match criterion matched by a result at `url`; `category` is the category of a
+/// finding, `None` for a response
+///
+/// `finding:CATEGORY` criteria match findings of that category, anything else is a path matched
+/// the same way --expect-absent's are
+pub fn stop_criterion<'a>(
+    criteria: &'a [String],
+    url: &Url,
+    category: Option<&str>,
+) -> Option<&'a str> {
+    criteria.iter().map(String::as_str).find(|criterion| {
+        match criterion.strip_prefix(FINDING_PREFIX) {
+            Some(wanted) => {
+                category.map_or(false, |category| category.eq_ignore_ascii_case(wanted))
+            }
+            None => matches_path(criterion, url),
+        }
+    })
+}
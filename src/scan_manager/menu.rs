@@ -1,4 +1,4 @@
-use crate::progress::PROGRESS_BAR;
+use crate::progress::{draw_target, PROGRESS_BAR};
 use console::{measure_text_width, pad_str, style, Alignment, Term};
 use indicatif::ProgressDrawTarget;
 
@@ -88,7 +88,7 @@ impl Menu {
 
     /// set PROGRESS_BAR bar target to hidden
     pub(super) fn show_progress_bars(&self) {
-        PROGRESS_BAR.set_draw_target(ProgressDrawTarget::stdout());
+        PROGRESS_BAR.set_draw_target(draw_target());
     }
 
     /// Wrapper around console's Term::clear_screen and flush
@@ -1,3 +1,5 @@
+mod changes;
+mod expectations;
 mod scan_container;
 mod response_container;
 mod scan;
@@ -8,10 +10,14 @@ mod state;
 #[cfg(test)]
 mod tests;
 
+pub use changes::{report_changes, ContentHashes};
+pub use expectations::{check_expectations, stop_criterion, FINDING_PREFIX};
 pub(self) use menu::Menu;
 pub use order::ScanOrder;
 pub use response_container::FeroxResponses;
 pub use scan::{FeroxScan, ScanStatus, ScanType};
 pub use scan_container::{FeroxScans, PAUSE_SCAN};
 pub use state::FeroxState;
-pub use utils::{resume_scan, start_max_time_thread};
+pub use utils::{
+    resume_scan, save_state, start_checkpoint_thread, start_max_time_thread, state_filename,
+};
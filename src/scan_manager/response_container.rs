@@ -1,6 +1,11 @@
 use crate::response::FeroxResponse;
+use anyhow::Result;
 use serde::{ser::SerializeSeq, Serialize, Serializer};
-use std::sync::{Arc, RwLock};
+use std::{
+    fs::File,
+    io::BufReader,
+    sync::{Arc, RwLock},
+};
 
 /// Container around a locked vector of `FeroxResponse`s, adds wrappers for insertion and search
 #[derive(Debug, Default)]
@@ -41,6 +46,28 @@ impl FeroxResponses {
         }
     }
 
+    /// load serialized FeroxResponse(s) into this FeroxResponses
+    pub fn add_serialized_responses(&self, filename: &str) -> Result<()> {
+        log::trace!("enter: add_serialized_responses({})", filename);
+        let file = File::open(filename)?;
+
+        let reader = BufReader::new(file);
+        let state: serde_json::Value = serde_json::from_reader(reader)?;
+
+        if let Some(responses) = state.get("responses") {
+            if let Some(arr_responses) = responses.as_array() {
+                for response in arr_responses {
+                    if let Ok(deser_resp) = serde_json::from_value(response.clone()) {
+                        self.insert(deser_resp);
+                    }
+                }
+            }
+        }
+
+        log::trace!("exit: add_serialized_responses");
+        Ok(())
+    }
+
     /// Simple check for whether or not a FeroxResponse is contained within the inner container
     pub fn contains(&self, other: &FeroxResponse) -> bool {
         if let Ok(responses) = self.responses.read() {
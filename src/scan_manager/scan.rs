@@ -1,6 +1,7 @@
 use super::*;
 use crate::{
     config::OutputLevel,
+    event_stream::EventStream,
     progress::{add_bar, BarType},
     scanner::PolicyTrigger,
 };
@@ -8,7 +9,7 @@ use anyhow::Result;
 use console::style;
 use indicatif::ProgressBar;
 use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializer};
-use serde_json::Value;
+use serde_json::{json, Value};
 use std::{
     collections::HashMap,
     fmt,
@@ -64,6 +65,9 @@ pub struct FeroxScan {
 
     /// tracker for the time at which this scan was started
     pub(super) start_time: Instant,
+
+    /// the --event-stream that the scan starting and stopping is announced on
+    pub(super) events: Arc<EventStream>,
 }
 
 /// Default implementation for FeroxScan
@@ -86,6 +90,7 @@ impl Default for FeroxScan {
             status_429s: Default::default(),
             status_403s: Default::default(),
             start_time: Instant::now(),
+            events: Arc::default(),
         }
     }
 }
@@ -128,7 +133,28 @@ impl FeroxScan {
     /// small wrapper to set ScanStatus
     pub fn set_status(&self, status: ScanStatus) -> Result<()> {
         if let Ok(mut guard) = self.status.lock() {
-            let _ = std::mem::replace(&mut *guard, status);
+            let previous = std::mem::replace(&mut *guard, status);
+
+            let event = match (previous, status) {
+                (ScanStatus::Running, ScanStatus::Running) => None,
+                (_, ScanStatus::Running) => Some("scan_start"),
+                (ScanStatus::Running, ScanStatus::Complete)
+                | (ScanStatus::Running, ScanStatus::Cancelled) => Some("scan_stop"),
+                _ => None,
+            };
+
+            if let Some(event) = event {
+                // --event-stream
+                self.events.emit(
+                    event,
+                    json!({
+                        "id": self.id,
+                        "url": self.url,
+                        "scan_type": self.scan_type,
+                        "status": status,
+                    }),
+                );
+            }
         }
         Ok(())
     }
@@ -151,8 +177,7 @@ impl FeroxScan {
                 } else {
                     let bar_type = match self.output_level {
                         OutputLevel::Default => BarType::Default,
-                        OutputLevel::Quiet => BarType::Quiet,
-                        OutputLevel::Silent => BarType::Hidden,
+                        OutputLevel::Quiet | OutputLevel::Silent => BarType::Hidden,
                     };
 
                     let pb = add_bar(&self.url, self.num_requests, bar_type);
@@ -168,8 +193,7 @@ impl FeroxScan {
 
                 let bar_type = match self.output_level {
                     OutputLevel::Default => BarType::Default,
-                    OutputLevel::Quiet => BarType::Quiet,
-                    OutputLevel::Silent => BarType::Hidden,
+                    OutputLevel::Quiet | OutputLevel::Silent => BarType::Hidden,
                 };
 
                 let pb = add_bar(&self.url, self.num_requests, bar_type);
@@ -180,7 +204,8 @@ impl FeroxScan {
         }
     }
 
-    /// Given a URL and ProgressBar, create a new FeroxScan, wrap it in an Arc and return it
+    /// Given a URL, ProgressBar, and the --event-stream to announce it on, create a new FeroxScan,
+    /// wrap it in an Arc and return it
     pub fn new(
         url: &str,
         scan_type: ScanType,
@@ -188,6 +213,7 @@ impl FeroxScan {
         num_requests: u64,
         output_level: OutputLevel,
         pb: Option<ProgressBar>,
+        events: Arc<EventStream>,
     ) -> Arc<Self> {
         Arc::new(Self {
             url: url.to_string(),
@@ -196,6 +222,7 @@ impl FeroxScan {
             num_requests,
             output_level,
             progress_bar: Mutex::new(pb),
+            events,
             ..Default::default()
         })
     }
@@ -220,6 +247,14 @@ impl FeroxScan {
         false
     }
 
+    /// small wrapper to inspect ScanStatus and see if it's Running
+    pub fn is_running(&self) -> bool {
+        if let Ok(guard) = self.status.lock() {
+            return matches!(*guard, ScanStatus::Running);
+        }
+        false
+    }
+
     /// small wrapper to inspect ScanStatus and see if it's Complete
     pub fn is_complete(&self) -> bool {
         if let Ok(guard) = self.status.lock() {
@@ -460,6 +495,7 @@ mod tests {
             1000,
             OutputLevel::Default,
             None,
+            Arc::default(),
         );
 
         scan.add_error();
@@ -491,6 +527,7 @@ mod tests {
             status_429s: Default::default(),
             errors: Default::default(),
             start_time: Instant::now(),
+            events: Arc::default(),
         };
 
         let pb = scan.progress_bar();
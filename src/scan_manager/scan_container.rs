@@ -2,9 +2,9 @@ use super::scan::ScanType;
 use super::*;
 use crate::{
     config::OutputLevel,
+    event_stream::EventStream,
     progress::PROGRESS_PRINTER,
     progress::{add_bar, BarType},
-    scanner::RESPONSES,
     traits::FeroxSerialize,
     SLEEP_DURATION,
 };
@@ -46,6 +46,12 @@ pub struct FeroxScans {
 
     /// whether or not the user passed --silent|--quiet on the command line
     output_level: OutputLevel,
+
+    /// responses reported so far, printed again when the scan is resumed or unpaused
+    responses: Arc<FeroxResponses>,
+
+    /// the --event-stream that each `FeroxScan` announces starting and stopping on
+    events: Arc<EventStream>,
 }
 
 /// Serialize implementation for FeroxScans
@@ -74,10 +80,17 @@ impl Serialize for FeroxScans {
 
 /// Implementation of `FeroxScans`
 impl FeroxScans {
-    /// given an OutputLevel, create a new FeroxScans object
-    pub fn new(output_level: OutputLevel) -> Self {
+    /// given an OutputLevel, the responses reported so far, and the scan's --event-stream, create
+    /// a new FeroxScans object
+    pub fn new(
+        output_level: OutputLevel,
+        responses: Arc<FeroxResponses>,
+        events: Arc<EventStream>,
+    ) -> Self {
         Self {
             output_level,
+            responses,
+            events,
             ..Default::default()
         }
     }
@@ -126,6 +139,7 @@ impl FeroxScans {
                     // and resumes the scan but adds -q, FeroxScan will not have the proper value
                     // without the line below
                     deser_scan.output_level = self.output_level;
+                    deser_scan.events = self.events.clone();
 
                     log::debug!("added: {}", deser_scan);
                     self.insert(Arc::new(deser_scan));
@@ -321,7 +335,7 @@ impl FeroxScans {
 
     /// prints all known responses that the scanner has already seen
     pub fn print_known_responses(&self) {
-        if let Ok(mut responses) = RESPONSES.responses.write() {
+        if let Ok(mut responses) = self.responses.responses.write() {
             for response in responses.iter_mut() {
                 if self.output_level != response.output_level {
                     // set the output_level prior to printing the response to ensure that the
@@ -338,8 +352,8 @@ impl FeroxScans {
     pub fn print_completed_bars(&self, bar_length: usize) -> Result<()> {
         let bar_type = match self.output_level {
             OutputLevel::Default => BarType::Message,
-            OutputLevel::Quiet => BarType::Quiet,
-            OutputLevel::Silent => return Ok(()), // fast exit when --silent was used
+            // fast exit when --quiet|--silent was used, neither shows progress bars
+            OutputLevel::Quiet | OutputLevel::Silent => return Ok(()),
         };
 
         if let Ok(scans) = self.scans.read() {
@@ -427,8 +441,7 @@ impl FeroxScans {
             ScanType::Directory => {
                 let bar_type = match self.output_level {
                     OutputLevel::Default => BarType::Default,
-                    OutputLevel::Quiet => BarType::Quiet,
-                    OutputLevel::Silent => BarType::Hidden,
+                    OutputLevel::Quiet | OutputLevel::Silent => BarType::Hidden,
                 };
 
                 let progress_bar = add_bar(url, bar_length, bar_type);
@@ -447,6 +460,7 @@ impl FeroxScans {
             bar_length,
             self.output_level,
             bar,
+            self.events.clone(),
         );
 
         // If the set did not contain the scan, true is returned.
@@ -14,7 +14,7 @@ pub struct FeroxState {
     config: Arc<Configuration>,
 
     /// Known responses
-    responses: &'static FeroxResponses,
+    responses: Arc<FeroxResponses>,
 
     /// Gathered statistics
     statistics: Arc<Stats>,
@@ -26,7 +26,7 @@ impl FeroxState {
     pub fn new(
         scans: Arc<FeroxScans>,
         config: Arc<Configuration>,
-        responses: &'static FeroxResponses,
+        responses: Arc<FeroxResponses>,
         statistics: Arc<Stats>,
     ) -> Self {
         Self {
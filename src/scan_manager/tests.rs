@@ -3,7 +3,6 @@ use crate::{
     config::{Configuration, OutputLevel},
     event_handlers::Handles,
     response::FeroxResponse,
-    scanner::RESPONSES,
     statistics::Stats,
     traits::FeroxSerialize,
     SLEEP_DURATION, VERSION,
@@ -69,6 +68,7 @@ fn add_url_to_list_of_scanned_urls_with_known_url() {
         pb.length(),
         OutputLevel::Default,
         Some(pb),
+        Arc::default(),
     );
 
     assert!(urls.insert(scan));
@@ -91,6 +91,7 @@ fn stop_progress_bar_stops_bar() {
         pb.length(),
         OutputLevel::Default,
         Some(pb),
+        Arc::default(),
     );
 
     assert!(!scan
@@ -125,6 +126,7 @@ fn add_url_to_list_of_scanned_urls_with_known_url_without_slash() {
         0,
         OutputLevel::Default,
         None,
+        Arc::default(),
     );
 
     assert!(urls.insert(scan));
@@ -149,6 +151,7 @@ async fn call_display_scans() {
         pb.length(),
         OutputLevel::Default,
         Some(pb),
+        Arc::default(),
     );
     let scan_two = FeroxScan::new(
         url_two,
@@ -157,6 +160,7 @@ async fn call_display_scans() {
         pb_two.length(),
         OutputLevel::Default,
         Some(pb_two),
+        Arc::default(),
     );
 
     scan_two.finish().unwrap(); // one complete, one incomplete
@@ -184,6 +188,7 @@ fn partial_eq_compares_the_id_field() {
         0,
         OutputLevel::Default,
         None,
+        Arc::default(),
     );
     let scan_two = FeroxScan::new(
         url,
@@ -192,6 +197,7 @@ fn partial_eq_compares_the_id_field() {
         0,
         OutputLevel::Default,
         None,
+        Arc::default(),
     );
 
     assert!(!scan.eq(&scan_two));
@@ -269,6 +275,7 @@ fn ferox_scan_serialize() {
         0,
         OutputLevel::Default,
         None,
+        Arc::default(),
     );
     let fs_json = format!(
         r#"{{"id":"{}","url":"https://spiritanimal.com","scan_type":"Directory","status":"NotStarted","num_requests":0}}"#,
@@ -287,6 +294,7 @@ fn ferox_scans_serialize() {
         0,
         OutputLevel::Default,
         None,
+        Arc::default(),
     );
     let ferox_scans = FeroxScans::default();
     let ferox_scans_json = format!(
@@ -348,22 +356,24 @@ fn feroxstates_feroxserialize_implementation() {
         0,
         OutputLevel::Default,
         None,
+        Arc::default(),
     );
     let ferox_scans = FeroxScans::default();
     let saved_id = ferox_scan.id.clone();
     ferox_scans.insert(ferox_scan);
 
     let config = Configuration::new().unwrap();
-    let stats = Arc::new(Stats::new(config.extensions.len(), config.json));
+    let stats = Arc::new(Stats::new(config.json));
 
     let json_response = r#"{"type":"response","url":"https://nerdcore.com/css","path":"/css","wildcard":true,"status":301,"content_length":173,"line_count":10,"word_count":16,"headers":{"server":"nginx/1.16.1"}}"#;
     let response: FeroxResponse = serde_json::from_str(json_response).unwrap();
-    RESPONSES.insert(response);
+    let responses = Arc::new(FeroxResponses::default());
+    responses.insert(response);
 
     let ferox_state = FeroxState::new(
         Arc::new(ferox_scans),
         Arc::new(Configuration::new().unwrap()),
-        &RESPONSES,
+        responses,
         stats,
     );
 
@@ -379,7 +389,7 @@ fn feroxstates_feroxserialize_implementation() {
 
     let json_state = ferox_state.as_json().unwrap();
     let expected = format!(
-        r#"{{"scans":[{{"id":"{}","url":"https://spiritanimal.com","scan_type":"Directory","status":"NotStarted","num_requests":0}}],"config":{{"type":"configuration","wordlist":"/usr/share/seclists/Discovery/Web-Content/raft-medium-directories.txt","config":"","proxy":"","replay_proxy":"","target_url":"","status_codes":[200,204,301,302,307,308,401,403,405,500],"replay_codes":[200,204,301,302,307,308,401,403,405,500],"filter_status":[],"threads":50,"timeout":7,"verbosity":0,"silent":false,"quiet":false,"auto_bail":false,"auto_tune":false,"json":false,"output":"","debug_log":"","user_agent":"feroxbuster/{}","redirects":false,"insecure":false,"extensions":[],"headers":{{}},"queries":[],"no_recursion":false,"extract_links":false,"add_slash":false,"stdin":false,"depth":4,"scan_limit":0,"parallel":0,"rate_limit":0,"filter_size":[],"filter_line_count":[],"filter_word_count":[],"filter_regex":[],"dont_filter":false,"resumed":false,"resume_from":"","save_state":false,"time_limit":"","filter_similar":[],"url_denylist":[]}},"responses":[{{"type":"response","url":"https://nerdcore.com/css","path":"/css","wildcard":true,"status":301,"content_length":173,"line_count":10,"word_count":16,"headers":{{"server":"nginx/1.16.1"}}}}]"#,
+        r#"{{"scans":[{{"id":"{}","url":"https://spiritanimal.com","scan_type":"Directory","status":"NotStarted","num_requests":0}}],"config":{{"type":"configuration","wordlist":"/usr/share/seclists/Discovery/Web-Content/raft-medium-directories.txt","config":"","proxy":"","replay_proxy":"","no_env_proxy":false,"target_url":"","status_codes":[200,204,301,302,307,308,401,403,405,500],"replay_codes":[200,204,301,302,307,308,401,403,405,500],"filter_status":[],"threads":50,"timeout":7,"verbosity":0,"silent":false,"quiet":false,"auto_bail":false,"auto_tune":false,"json":false,"error_records":false,"output":"","debug_log":"","user_agent":"feroxbuster/{}","redirects":false,"insecure":false,"extensions":[],"headers":{{}},"host_header":"","queries":[],"no_recursion":false,"extract_links":false,"add_slash":false,"stdin":false,"depth":4,"scan_limit":0,"parallel":0,"rate_limit":0,"request_limit":0,"max_429s":0,"cooldown":"","filter_size":[],"filter_line_count":[],"filter_word_count":[],"filter_regex":[],"dont_filter":false,"resumed":false,"resume_from":"","save_state":false,"time_limit":"","filter_similar":[],"url_denylist":[],"deny_url_regex":[],"scope":[],"cluster_threshold":0,"extract_forms":false,"extract_comments":false,"seed_from":[],"fingerprint":false,"parse_listings":false,"skip_listed_dirs":false,"discover_openapi":false,"graphql_probe":false,"source_maps":false,"find_secrets":false,"secret_patterns":[],"harvest":false,"track_changes":"","save_config":"","mine_params":false,"check_artifacts":false,"check_headers":false,"cors_probe":false,"ws_probe":false,"auth_diff":false,"check_methods":false,"permute":false,"permutation_rules":[],"iis_shortnames":false,"redact_secrets":false,"no_product_wordlists":false,"coordinate":"","shard_size":1000,"log_format":"text","thousands_separator":"","byte_units":"bytes","expect_found":[],"expect_absent":[],"stop_on_match":[],"adaptive_threads":false,"seen_urls":"exact","wordlist_cache":false,"dns_ttl":0,"max_response_size":0,"head_only":false,"response_time":false,"streams_per_connection":0,"memory_limit":0,"adaptive_timeout":0,"throttle_bytes":0,"async_workers":0,"blocking_threads":0,"trace_output":"","refresh_rate":0,"checkpoint_interval":"","checkpoint_requests":0,"burp_output":"","zap_url":"","zap_api_key":"","zap_active_scan":false,"event_stream":"","scan_id":"","scan_id_header":"","defectdojo_output":"","evidence_dir":"","evidence_severity":"medium","output_format":"text","bucket_by_tech":false,"seed_file":"","tor":false,"tor_control":"","tor_control_password":"","buckets":[],"presets":[],"crawl_wordlist":0,"rotate_headers":[]}},"responses":[{{"type":"response","url":"https://nerdcore.com/css","path":"/css","wildcard":true,"extracted_from_js":false,"status":301,"content_length":173,"line_count":10,"word_count":16,"headers":{{"server":"nginx/1.16.1"}}}}]"#,
         saved_id, VERSION
     );
     println!("{}\n{}", expected, json_state);
@@ -425,6 +435,43 @@ async fn start_max_time_thread_returns_immediately_with_too_large_input() {
     assert!(now.elapsed() < delay); // assuming function call will take less than 1second
 }
 
+#[test]
+/// save_state should leave a complete, resumable state file behind each time it's called, and
+/// nothing else
+fn save_state_replaces_previous_state() {
+    let directory = tempfile::tempdir().unwrap();
+    let filename = directory.path().join("ferox-checkpoint.state");
+    let filename = filename.to_str().unwrap();
+
+    let scans = Arc::new(FeroxScans::default());
+    scans.add_directory_scan("http://localhost", ScanOrder::Initial);
+    let handles = Handles::for_testing(Some(scans.clone()), None).0;
+
+    save_state(&handles, filename).unwrap();
+
+    scans.add_directory_scan("http://localhost/admin", ScanOrder::Latest);
+    save_state(&handles, filename).unwrap();
+
+    let contents = std::fs::read_to_string(filename).unwrap();
+    let state: serde_json::Value = serde_json::from_str(&contents).unwrap();
+
+    assert_eq!(state["scans"].as_array().unwrap().len(), 2);
+    assert!(state.get("config").is_some());
+    assert_eq!(std::fs::read_dir(directory.path()).unwrap().count(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// call start_checkpoint_thread without a checkpoint interval or request count, expect an
+/// immediate return
+async fn start_checkpoint_thread_returns_immediately_without_checkpoints() {
+    let now = time::Instant::now();
+    let handles = Arc::new(Handles::for_testing(None, None).0);
+
+    start_checkpoint_thread(handles).await;
+
+    assert!(now.elapsed() < time::Duration::new(1, 0));
+}
+
 #[test]
 /// coverage for FeroxScan's Display implementation
 fn feroxscan_display() {
@@ -442,6 +489,7 @@ fn feroxscan_display() {
         task: tokio::sync::Mutex::new(None),
         progress_bar: std::sync::Mutex::new(None),
         errors: Default::default(),
+        events: Arc::default(),
     };
 
     let not_started = format!("{}", scan);
@@ -488,6 +536,7 @@ async fn ferox_scan_abort() {
         }))),
         progress_bar: std::sync::Mutex::new(None),
         errors: Default::default(),
+        events: Arc::default(),
     };
 
     scan.abort().await.unwrap();
@@ -592,3 +641,142 @@ fn get_base_scan_by_url_finds_correct_scan_with_trailing_slash() {
         scan.id
     );
 }
+
+#[test]
+/// content hashes are stable, and only urls sharing an origin with this run can disappear
+fn diff_content_reports_changed_appeared_and_disappeared() {
+    assert_eq!(changes::content_hash("hello"), "248bfa47");
+
+    let mut previous = std::collections::BTreeMap::new();
+    previous.insert("http://one.test/a".to_string(), "1".to_string());
+    previous.insert("http://one.test/b".to_string(), "2".to_string());
+    previous.insert("http://one.test/c".to_string(), "3".to_string());
+    previous.insert("http://two.test/a".to_string(), "4".to_string());
+
+    let mut current = std::collections::BTreeMap::new();
+    current.insert("http://one.test/a".to_string(), "1".to_string());
+    current.insert("http://one.test/b".to_string(), "changed".to_string());
+    current.insert("http://one.test/d".to_string(), "5".to_string());
+
+    let diff = changes::diff_content(&previous, &current);
+
+    assert_eq!(
+        diff,
+        vec![
+            (
+                "http://one.test/b".to_string(),
+                changes::ContentChange::Changed
+            ),
+            (
+                "http://one.test/d".to_string(),
+                changes::ContentChange::Appeared
+            ),
+            (
+                "http://one.test/c".to_string(),
+                changes::ContentChange::Disappeared
+            ),
+        ]
+    );
+    assert_eq!(
+        changes::ContentChange::Disappeared.to_string(),
+        "disappeared"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// each scan compares and saves only the content it saw itself
+async fn report_changes_only_saves_the_scans_own_content() {
+    let directory = tempfile::tempdir().unwrap();
+    let filename = directory.path().join("changes.json");
+
+    let mut config = Configuration::new().unwrap();
+    config.track_changes = filename.to_str().unwrap().to_string();
+    let config = Arc::new(config);
+
+    let mut response = FeroxResponse::default();
+    response.set_url("http://one.test/a");
+    response.set_text("first");
+
+    let (first, _rx) = Handles::for_testing(None, Some(config.clone()));
+    first.content_hashes.record(&response);
+    report_changes(Arc::new(first)).unwrap();
+
+    response.set_url("http://one.test/b");
+
+    let (second, _rx) = Handles::for_testing(None, Some(config));
+    second.content_hashes.record(&response);
+    report_changes(Arc::new(second)).unwrap();
+
+    let saved: std::collections::BTreeMap<String, String> =
+        serde_json::from_str(&std::fs::read_to_string(&filename).unwrap()).unwrap();
+
+    assert_eq!(saved.keys().collect::<Vec<_>>(), vec!["http://one.test/b"]);
+}
+
+#[test]
+/// paths match themselves and anything beneath them, regardless of leading/trailing slashes
+fn expectations_match_paths_and_their_children() {
+    let url = reqwest::Url::parse("http://localhost/.git/HEAD").unwrap();
+
+    assert!(expectations::matches_path("/.git/", &url));
+    assert!(expectations::matches_path(".git", &url));
+    assert!(expectations::matches_path("/.git/HEAD", &url));
+    assert!(!expectations::matches_path("/.gitignore", &url));
+    assert!(!expectations::matches_path("/.git/HEAD/more", &url));
+}
+
+#[test]
+/// missing --expect-found paths and discovered --expect-absent paths are both violations, and
+/// wildcard responses are ignored
+fn check_expectations_reports_violations() {
+    let config = Configuration {
+        expect_found: vec!["/login".to_string(), "/admin".to_string()],
+        expect_absent: vec!["/.git/".to_string(), "/backup".to_string()],
+        ..Default::default()
+    };
+
+    let responses = FeroxResponses::default();
+
+    for (url, wildcard) in &[
+        ("http://localhost/login", false),
+        ("http://localhost/.git/HEAD", false),
+        ("http://localhost/admin", true),
+        ("http://localhost/backup", true),
+    ] {
+        let mut response = FeroxResponse::default();
+        response.set_url(url);
+        response.set_wildcard(*wildcard);
+        responses.insert(response);
+    }
+
+    let violations = check_expectations(&config, &responses);
+
+    assert_eq!(
+        violations,
+        vec![
+            "Expected /admin to be found, but it wasn't".to_string(),
+            "Expected /.git to be absent, but found http://localhost/.git/HEAD".to_string(),
+        ]
+    );
+
+    let nothing_expected = Configuration::default();
+    assert!(check_expectations(&nothing_expected, &responses).is_empty());
+}
+
+#[test]
+/// paths match responses and findings beneath them, finding: criteria only findings of their
+/// category
+fn stop_criterion_matches_paths_and_categories() {
+    let criteria = vec!["/.env".to_string(), "finding:Secret".to_string()];
+    let env = reqwest::Url::parse("http://localhost/.env").unwrap();
+    let admin = reqwest::Url::parse("http://localhost/admin").unwrap();
+
+    assert_eq!(stop_criterion(&criteria, &env, None), Some("/.env"));
+    assert_eq!(stop_criterion(&criteria, &admin, None), None);
+    assert_eq!(
+        stop_criterion(&criteria, &admin, Some("secret")),
+        Some("finding:Secret")
+    );
+    assert_eq!(stop_criterion(&criteria, &admin, Some("cors")), None);
+    assert_eq!(stop_criterion(&[], &env, None), None);
+}
@@ -1,10 +1,21 @@
+use super::FeroxState;
 #[cfg(not(test))]
 use crate::event_handlers::TermInputHandler;
 use crate::{
-    config::Configuration, event_handlers::Handles, parser::TIMESPEC_REGEX, scanner::RESPONSES,
+    config::Configuration,
+    event_handlers::{Handles, SCAN_COMPLETE},
+    parser::parse_duration,
+    utils::{slugify_filename, write_to},
+    SLEEP_DURATION,
 };
 
-use std::{fs::File, io::BufReader, sync::Arc};
+use anyhow::{anyhow, Context, Result};
+use std::{
+    fs::{self, File},
+    io::{BufReader, BufWriter},
+    sync::{atomic::Ordering, Arc},
+    time::{Duration, Instant},
+};
 use tokio::time;
 
 /// Given a string representing some number of seconds, minutes, hours, or days, convert
@@ -14,22 +25,9 @@ use tokio::time;
 pub async fn start_max_time_thread(handles: Arc<Handles>) {
     log::trace!("enter: start_max_time_thread({:?})", handles);
 
-    // as this function has already made it through the parser, which calls is_match on
-    // the value passed to --time-limit using TIMESPEC_REGEX; we can safely assume that
-    // the capture groups are populated; can expect something like 10m, 30s, 1h, etc...
-    let captures = TIMESPEC_REGEX.captures(&handles.config.time_limit).unwrap();
-    let length_match = captures.get(1).unwrap();
-    let measurement_match = captures.get(2).unwrap();
-
-    if let Ok(length) = length_match.as_str().parse::<u64>() {
-        let length_in_secs = match measurement_match.as_str().to_ascii_lowercase().as_str() {
-            "s" => length,
-            "m" => length * 60,           // minutes
-            "h" => length * 60 * 60,      // hours
-            "d" => length * 60 * 60 * 24, // days
-            _ => length,
-        };
-
+    // the value passed to --time-limit has already made it through the parser, but a config
+    // file's time_limit hasn't; can expect something like 10m, 30s, 1h, 1h30m, etc...
+    if let Ok(length_in_secs) = parse_duration(&handles.config.time_limit) {
         log::debug!(
             "max time limit as string: {} and as seconds: {}",
             handles.config.time_limit,
@@ -52,41 +50,138 @@ pub async fn start_max_time_thread(handles: Arc<Handles>) {
     );
 }
 
-/// Primary logic used to load a Configuration from disk and populate the appropriate data
-/// structures
-pub fn resume_scan(filename: &str) -> Configuration {
-    log::trace!("enter: resume_scan({})", filename);
+/// Name of the state file written for the scan described by the given `Configuration`
+///
+/// ex: ferox-http_telsa_com-1606947491.state
+pub fn state_filename(config: &Configuration) -> String {
+    if !config.target_url.is_empty() {
+        // target url populated
+        slugify_filename(&config.target_url, "ferox", "state")
+    } else {
+        // stdin used
+        slugify_filename("stdin", "ferox", "state")
+    }
+}
 
-    let file = File::open(filename).unwrap_or_else(|e| {
-        log::error!("{}", e);
-        log::error!("Could not open state file, exiting");
-        std::process::exit(1);
-    });
+/// Write the current state of the scan to `filename`, replacing whatever was there before
+///
+/// the state is written to a `.partial` file that's only renamed over `filename` once it's
+/// complete and synced to disk, so that dying mid-write leaves the previous checkpoint intact
+pub fn save_state(handles: &Handles, filename: &str) -> Result<()> {
+    log::trace!("enter: save_state({:?}, {})", handles, filename);
+
+    let state = FeroxState::new(
+        handles.ferox_scans()?,
+        handles.config.clone(),
+        handles.output.data.clone(),
+        handles.stats.data.clone(),
+    );
 
-    let reader = BufReader::new(file);
-    let state: serde_json::Value = serde_json::from_reader(reader).unwrap();
-
-    let conf = state.get("config").unwrap_or_else(|| {
-        log::error!("Could not load configuration from state file, exiting");
-        std::process::exit(1);
-    });
-
-    let config = serde_json::from_value(conf.clone()).unwrap_or_else(|e| {
-        log::error!("{}", e);
-        log::error!("Could not deserialize configuration found in state file, exiting");
-        std::process::exit(1);
-    });
-
-    if let Some(responses) = state.get("responses") {
-        if let Some(arr_responses) = responses.as_array() {
-            for response in arr_responses {
-                if let Ok(deser_resp) = serde_json::from_value(response.clone()) {
-                    RESPONSES.insert(deser_resp);
-                }
+    let partial = format!("{}.partial", filename);
+
+    let file = File::create(&partial)
+        .with_context(|| format!("Could not create state file {}", partial))?;
+
+    let mut buffered_file = BufWriter::new(file);
+    write_to(&state, &mut buffered_file, true)?;
+    buffered_file.into_inner()?.sync_all()?;
+
+    fs::rename(&partial, filename)
+        .with_context(|| format!("Could not move {} to {}", partial, filename))?;
+
+    log::trace!("exit: save_state");
+    Ok(())
+}
+
+/// Periodically write a state file that can be used to resume the scan, every
+/// `--checkpoint-interval` and/or every `--checkpoint-requests` requests, until all scans are
+/// complete
+///
+/// every checkpoint replaces the previous one, so a crash or kill that never gives the ctrl+c
+/// handler a chance to run loses, at most, the work done since the last checkpoint
+pub async fn start_checkpoint_thread(handles: Arc<Handles>) {
+    log::trace!("enter: start_checkpoint_thread({:?})", handles);
+
+    let interval = if handles.config.checkpoint_interval.is_empty() {
+        None
+    } else {
+        match parse_duration(&handles.config.checkpoint_interval) {
+            Ok(seconds) if seconds > 0 => Some(Duration::from_secs(seconds)),
+            _ => {
+                log::warn!(
+                    "Could not parse the value provided ({}), won't checkpoint on a timer",
+                    handles.config.checkpoint_interval
+                );
+                None
             }
         }
+    };
+
+    let every = handles.config.checkpoint_requests as usize;
+
+    if interval.is_none() && every == 0 {
+        log::trace!("exit: start_checkpoint_thread (nothing to do)");
+        return;
+    }
+
+    let filename = state_filename(&handles.config);
+
+    let mut last_saved = Instant::now();
+    let mut requests_at_last_save = handles.stats.data.requests();
+
+    let mut ticker = time::interval(Duration::from_millis(SLEEP_DURATION));
+
+    while !SCAN_COMPLETE.load(Ordering::Relaxed) {
+        ticker.tick().await;
+
+        let requests = handles.stats.data.requests();
+
+        let timer_due = interval.map_or(false, |interval| last_saved.elapsed() >= interval);
+        let requests_due = every > 0 && requests.saturating_sub(requests_at_last_save) >= every;
+
+        if !timer_due && !requests_due {
+            continue;
+        }
+
+        // serializing every response seen so far can take a while on big scans
+        let cloned_handles = handles.clone();
+        let cloned_filename = filename.clone();
+        let result =
+            tokio::task::spawn_blocking(move || save_state(&cloned_handles, &cloned_filename))
+                .await;
+
+        match result {
+            Ok(Ok(_)) => log::info!("checkpoint written to {}", filename),
+            Ok(Err(e)) => log::warn!("Could not write checkpoint to {}: {}", filename, e),
+            Err(e) => log::warn!("Could not write checkpoint to {}: {}", filename, e),
+        }
+
+        last_saved = Instant::now();
+        requests_at_last_save = requests;
     }
 
+    log::trace!("exit: start_checkpoint_thread");
+}
+
+/// Primary logic used to load a Configuration from disk; the scans, responses, and statistics
+/// saved with it are loaded once the scan's handlers are running
+pub fn resume_scan(filename: &str) -> Result<Configuration> {
+    log::trace!("enter: resume_scan({})", filename);
+
+    let file =
+        File::open(filename).with_context(|| format!("Could not open state file {}", filename))?;
+
+    let reader = BufReader::new(file);
+    let state: serde_json::Value = serde_json::from_reader(reader)
+        .with_context(|| format!("Could not parse state file {}", filename))?;
+
+    let conf = state
+        .get("config")
+        .ok_or_else(|| anyhow!("Could not load configuration from state file {}", filename))?;
+
+    let config = serde_json::from_value(conf.clone())
+        .with_context(|| "Could not deserialize configuration found in state file")?;
+
     log::trace!("exit: resume_scan -> {:?}", config);
-    config
+    Ok(config)
 }
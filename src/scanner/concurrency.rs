@@ -0,0 +1,290 @@
+use std::{sync::Mutex, time::Duration};
+
+use anyhow::Result;
+use reqwest::{Response, StatusCode};
+use tokio::sync::{Semaphore, SemaphorePermit};
+
+/// how much slower than the fastest window seen a window's average response time may be while
+/// still allowing concurrency to grow
+const LATENCY_TOLERANCE: u32 = 2;
+
+/// What a finished request says about the target's health
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub(super) enum Signal {
+    /// a response arrived in the given amount of time
+    Healthy(Duration),
+
+    /// the request timed out, failed to connect, or was answered with a 429/503
+    Congested,
+}
+
+impl Signal {
+    /// inspect the result of a request and determine what it says about the target
+    pub(super) fn from_result(result: &Result<Response>, elapsed: Duration) -> Self {
+        match result {
+            Ok(response)
+                if !matches!(
+                    response.status(),
+                    StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE
+                ) =>
+            {
+                Signal::Healthy(elapsed)
+            }
+            _ => Signal::Congested,
+        }
+    }
+}
+
+/// bookkeeping for the current window; a window lasts until as many requests as the current
+/// limit have finished
+#[derive(Debug, Default)]
+struct Window {
+    /// current number of requests allowed in flight
+    limit: usize,
+
+    /// double the limit after each healthy window until the first sign of congestion
+    slow_start: bool,
+
+    /// number of requests finished during this window
+    finished: usize,
+
+    /// number of healthy requests finished during this window
+    healthy: usize,
+
+    /// sum of the response times of this window's healthy requests
+    latency: Duration,
+
+    /// whether the limit was already lowered during this window; requests sent before lowering
+    /// it are still in flight, and shouldn't lower it again
+    congested: bool,
+
+    /// lowest average response time of any window
+    baseline: Option<Duration>,
+
+    /// number of permits to drop, instead of returning them, after lowering the limit
+    owed: usize,
+}
+
+/// AIMD (additive increase, multiplicative decrease) controller for the number of requests a
+/// scan has in flight
+///
+/// concurrency starts low and doubles each window until the target shows signs of congestion,
+/// after which it grows by one each healthy window and is halved whenever timeouts, connection
+/// errors, or 429/503s appear
+#[derive(Debug)]
+pub(super) struct ConcurrencyController {
+    /// url of the scan being controlled, used for logging
+    target_url: String,
+
+    /// permits for requests in flight, one per request
+    permits: Semaphore,
+
+    /// concurrency is never raised beyond this number, i.e. --threads
+    max: usize,
+
+    /// bookkeeping for the current window
+    window: Mutex<Window>,
+}
+
+impl ConcurrencyController {
+    /// create a controller that starts at a tenth of `max` concurrent requests
+    pub(super) fn new(target_url: &str, max: usize) -> Self {
+        let max = max.max(1);
+        let limit = (max / 10).max(1);
+
+        Self {
+            max,
+            target_url: target_url.to_string(),
+            permits: Semaphore::new(limit),
+            window: Mutex::new(Window {
+                limit,
+                slow_start: true,
+                ..Default::default()
+            }),
+        }
+    }
+
+    /// current number of requests allowed in flight
+    pub(super) fn limit(&self) -> usize {
+        self.window.lock().map(|window| window.limit).unwrap_or(1)
+    }
+
+    /// wait for room to send a request
+    pub(super) async fn acquire(&self) -> Option<SemaphorePermit<'_>> {
+        self.permits.acquire().await.ok()
+    }
+
+    /// hand back the permit of a finished request, adjusting the limit based on how it went
+    pub(super) fn release(&self, permit: SemaphorePermit<'_>, signal: Signal) {
+        let mut window = match self.window.lock() {
+            Ok(window) => window,
+            Err(_) => return,
+        };
+
+        window.finished += 1;
+
+        match signal {
+            Signal::Healthy(elapsed) => {
+                window.healthy += 1;
+                window.latency += elapsed;
+            }
+            Signal::Congested if !window.congested => {
+                let lowered = (window.limit / 2).max(1);
+
+                log::info!(
+                    "lowering concurrency of {} from {} to {}",
+                    self.target_url,
+                    window.limit,
+                    lowered
+                );
+
+                window.owed += window.limit - lowered;
+                window.limit = lowered;
+                window.slow_start = false;
+                window.congested = true;
+            }
+            Signal::Congested => {}
+        }
+
+        if window.owed > 0 {
+            window.owed -= 1;
+            permit.forget();
+        } else {
+            drop(permit);
+        }
+
+        if window.finished >= window.limit {
+            self.finish_window(&mut window);
+        }
+    }
+
+    /// raise the limit if the window that just finished was healthy, then start a new window
+    fn finish_window(&self, window: &mut Window) {
+        if !window.congested && window.healthy > 0 && window.limit < self.max {
+            let average = window.latency / window.healthy as u32;
+            let baseline = window
+                .baseline
+                .map_or(average, |fastest| fastest.min(average));
+
+            window.baseline = Some(baseline);
+
+            if average <= baseline * LATENCY_TOLERANCE {
+                let raised = if window.slow_start {
+                    window.limit * 2
+                } else {
+                    window.limit + 1
+                }
+                .min(self.max);
+
+                log::debug!(
+                    "raising concurrency of {} from {} to {}",
+                    self.target_url,
+                    window.limit,
+                    raised
+                );
+
+                // permits still owed from lowering the limit are paid off before adding new ones
+                let added = raised - window.limit;
+                let repaid = added.min(window.owed);
+
+                window.owed -= repaid;
+                window.limit = raised;
+                self.permits.add_permits(added - repaid);
+            }
+        }
+
+        window.finished = 0;
+        window.healthy = 0;
+        window.latency = Duration::default();
+        window.congested = false;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// acquire `count` permits and release them all with the given signal
+    async fn run_requests(controller: &ConcurrencyController, count: usize, signal: Signal) {
+        let mut permits = Vec::new();
+
+        for _ in 0..count {
+            permits.push(controller.acquire().await.unwrap());
+        }
+
+        for permit in permits {
+            controller.release(permit, signal);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// concurrency doubles per healthy window until congestion, then grows by one
+    async fn controller_slow_starts_then_grows_additively() {
+        let controller = ConcurrencyController::new("http://localhost", 50);
+        let fast = Signal::Healthy(Duration::from_millis(10));
+
+        assert_eq!(controller.limit(), 5);
+
+        run_requests(&controller, 5, fast).await;
+        assert_eq!(controller.limit(), 10);
+
+        run_requests(&controller, 10, fast).await;
+        assert_eq!(controller.limit(), 20);
+
+        run_requests(&controller, 20, fast).await;
+        assert_eq!(controller.limit(), 40);
+
+        run_requests(&controller, 40, fast).await;
+        assert_eq!(controller.limit(), 50); // capped at --threads
+        assert_eq!(controller.permits.available_permits(), 50);
+
+        // a single congestion signal halves the limit, the rest of the window is ignored
+        let mut permits = Vec::new();
+        for _ in 0..50 {
+            permits.push(controller.acquire().await.unwrap());
+        }
+        controller.release(permits.pop().unwrap(), Signal::Congested);
+        controller.release(permits.pop().unwrap(), Signal::Congested);
+        assert_eq!(controller.limit(), 25);
+
+        // the remaining in-flight requests make up the rest of the congested window and one
+        // healthy window, which grows the limit by one now that slow start is over
+        for permit in permits {
+            controller.release(permit, fast);
+        }
+        assert_eq!(controller.limit(), 26);
+        assert_eq!(controller.permits.available_permits(), 26);
+
+        run_requests(&controller, 26, fast).await;
+        assert_eq!(controller.limit(), 27);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// concurrency stops growing once response times climb well above the fastest window
+    async fn controller_holds_when_latency_climbs() {
+        let controller = ConcurrencyController::new("http://localhost", 100);
+
+        run_requests(&controller, 10, Signal::Healthy(Duration::from_millis(20))).await;
+        assert_eq!(controller.limit(), 20);
+
+        run_requests(&controller, 20, Signal::Healthy(Duration::from_millis(200))).await;
+        assert_eq!(controller.limit(), 20);
+
+        run_requests(&controller, 20, Signal::Healthy(Duration::from_millis(30))).await;
+        assert_eq!(controller.limit(), 40);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// concurrency never drops below a single request
+    async fn controller_never_drops_below_one() {
+        let controller = ConcurrencyController::new("http://localhost", 5);
+        assert_eq!(controller.limit(), 1);
+
+        for _ in 0..5 {
+            run_requests(&controller, 1, Signal::Congested).await;
+            assert_eq!(controller.limit(), 1);
+        }
+
+        assert_eq!(controller.permits.available_permits(), 1);
+    }
+}
@@ -1,32 +1,44 @@
-use std::{ops::Deref, sync::atomic::Ordering, sync::Arc, time::Instant};
+use std::{sync::atomic::Ordering, sync::Arc, time::Instant};
 
 use anyhow::{bail, Result};
+use console::style;
 use futures::{stream, StreamExt};
-use lazy_static::lazy_static;
+use reqwest::Url;
 use tokio::sync::Semaphore;
 
 use crate::{
+    artifacts::check_artifacts,
+    config::OutputLevel,
     event_handlers::{
-        Command::{AddError, AddToF64Field, SubtractFromUsizeField},
+        Command::{
+            self, AddDirectoryRequests, AddError, AddToF64Field, AddToUsizeField,
+            SubtractFromUsizeField,
+        },
         Handles,
     },
-    extractor::{ExtractionTarget::RobotsTxt, ExtractorBuilder},
-    heuristics,
-    scan_manager::{FeroxResponses, ScanOrder, ScanStatus, PAUSE_SCAN},
+    extractor::{
+        ExtractionTarget::{DirectoryListing, OpenApi, RobotsTxt, SeedFile, Wayback},
+        ExtractorBuilder,
+    },
+    fingerprint::favicon_finding,
+    heuristics, iis_shortnames,
+    methods::check_methods,
+    progress::PROGRESS_PRINTER,
+    scan_manager::{ScanOrder, ScanStatus, PAUSE_SCAN},
     statistics::{
         StatError::Other,
         StatField::{DirScanTimes, TotalExpected},
     },
-    utils::fmt_err,
+    utils::{ferox_print, fmt_err},
+    wordlist_map,
 };
 
-use super::requester::Requester;
+use super::{
+    init::{requests_per_directory, requests_per_word},
+    memory,
+    requester::Requester,
+};
 
-lazy_static! {
-    /// Vector of FeroxResponse objects
-    pub static ref RESPONSES: FeroxResponses = FeroxResponses::default();
-    // todo consider removing this
-}
 /// handles the main muscle movement of scanning a url
 pub struct FeroxScanner {
     /// handles to handlers and config
@@ -68,6 +80,7 @@ impl FeroxScanner {
     /// Scan a given url using a given wordlist
     ///
     /// This is the primary entrypoint for the scanner
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(url = %self.target_url)))]
     pub async fn scan_url(&self) -> Result<()> {
         log::trace!("enter: scan_url");
         log::info!("Starting scan against: {}", self.target_url);
@@ -87,6 +100,136 @@ impl FeroxScanner {
             extractor.request_links(links).await?;
         }
 
+        if matches!(self.order, ScanOrder::Initial)
+            && self.handles.config.seed_from.iter().any(|s| s == "wayback")
+        {
+            // historical paths are only looked up once per target, prior to brute forcing
+            let extractor = ExtractorBuilder::default()
+                .url(&self.target_url)
+                .handles(self.handles.clone())
+                .target(Wayback)
+                .build()?;
+
+            match extractor.extract().await {
+                Ok(links) => extractor.request_links(links).await?,
+                Err(e) => log::warn!(
+                    "Could not seed {} from the wayback machine: {}",
+                    self.target_url,
+                    e
+                ),
+            }
+        }
+
+        if matches!(self.order, ScanOrder::Initial) && !self.handles.config.seed_file.is_empty() {
+            // urls found by other tools are requested once per target, prior to brute forcing
+            let extractor = ExtractorBuilder::default()
+                .url(&self.target_url)
+                .handles(self.handles.clone())
+                .target(SeedFile)
+                .build()?;
+
+            match extractor.extract().await {
+                Ok(links) => extractor.request_links(links).await?,
+                Err(e) => log::warn!("Could not seed {} from a file: {}", self.target_url, e),
+            }
+        }
+
+        if matches!(self.order, ScanOrder::Initial) && self.handles.config.discover_openapi {
+            // api specs live at well-known locations relative to the root of the target
+            let extractor = ExtractorBuilder::default()
+                .url(&self.target_url)
+                .handles(self.handles.clone())
+                .target(OpenApi)
+                .build()?;
+
+            match extractor.extract().await {
+                Ok(links) => extractor.request_links(links).await?,
+                Err(e) => log::warn!("Could not discover api specs on {}: {}", self.target_url, e),
+            }
+        }
+
+        if matches!(self.order, ScanOrder::Initial) && self.handles.config.fingerprint {
+            // the favicon hash allows correlation with internet-wide data, i.e. shodan
+            match favicon_finding(&self.target_url, self.handles.clone()).await {
+                Ok(Some(finding)) => {
+                    self.handles
+                        .output
+                        .send(Command::ReportFinding(Box::new(finding)))?;
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Could not hash favicon for {}: {}", self.target_url, e),
+            }
+        }
+
+        if self.handles.config.check_artifacts {
+            // artifacts are checked in every directory, whether or not they're in the wordlist
+            match check_artifacts(&self.target_url, self.handles.clone()).await {
+                Ok(findings) => {
+                    for finding in findings {
+                        self.handles
+                            .output
+                            .send(Command::ReportFinding(Box::new(finding)))?;
+                    }
+                }
+                Err(e) => log::warn!("Could not check {} for artifacts: {}", self.target_url, e),
+            }
+        }
+
+        if self.handles.config.check_methods {
+            match check_methods(&self.target_url, self.handles.clone()).await {
+                Ok(Some(finding)) => {
+                    self.handles
+                        .output
+                        .send(Command::ReportFinding(Box::new(finding)))?;
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!("Could not check methods on {}: {}", self.target_url, e),
+            }
+        }
+
+        if self.handles.config.iis_shortnames {
+            // short names narrow the wordlist down to the names that are actually there
+            match iis_shortnames::enumerate(
+                &self.target_url,
+                self.wordlist.clone(),
+                self.handles.clone(),
+            )
+            .await
+            {
+                Ok(Some(finding)) => {
+                    self.handles
+                        .output
+                        .send(Command::ReportFinding(Box::new(finding)))?;
+                }
+                Ok(None) => {}
+                Err(e) => log::warn!(
+                    "Could not enumerate short names in {}: {}",
+                    self.target_url,
+                    e
+                ),
+            }
+        }
+
+        let mut listed = false;
+
+        if self.handles.config.parse_listings {
+            // index pages give up a directory's contents without needing to brute force them
+            let extractor = ExtractorBuilder::default()
+                .url(&self.target_url)
+                .handles(self.handles.clone())
+                .target(DirectoryListing)
+                .build()?;
+
+            match extractor.extract().await {
+                Ok(links) if !links.is_empty() => {
+                    listed = true;
+                    extractor.request_links(links).await?;
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Could not check {} for a listing: {}", self.target_url, e),
+            }
+        }
+
         let scanned_urls = self.handles.ferox_scans()?;
 
         let ferox_scan = match scanned_urls.get_scan_by_url(&self.target_url) {
@@ -105,6 +248,45 @@ impl FeroxScanner {
 
         let progress_bar = ferox_scan.progress_bar();
 
+        if wordlist_map::mapping_for(&self.handles.config.wordlist_map, &self.target_url).is_some()
+        {
+            // the bar (and overall total) were sized for --wordlist, not the wordlist mapped to
+            // this directory's path
+            let expected = requests_per_directory(self.wordlist.len(), &self.handles.config) as u64;
+            let length = progress_bar.length();
+
+            if expected > length {
+                let difference = (expected - length) as usize;
+                self.handles
+                    .stats
+                    .send(AddToUsizeField(TotalExpected, difference))?;
+            } else if expected < length {
+                let difference = (length - expected) as usize;
+                self.handles
+                    .stats
+                    .send(SubtractFromUsizeField(TotalExpected, difference))?;
+            }
+
+            progress_bar.set_length(expected);
+        }
+
+        if listed && self.handles.config.skip_listed_dirs {
+            // the listing already enumerated everything in this directory; the wordlist's
+            // requests are never made, so they're removed from the overall total
+            let num_skipped = progress_bar
+                .length()
+                .saturating_sub(progress_bar.position()) as usize;
+
+            self.handles
+                .stats
+                .send(SubtractFromUsizeField(TotalExpected, num_skipped))?;
+
+            ferox_scan.finish()?;
+
+            log::trace!("exit: scan_url (directory listing)");
+            return Ok(());
+        }
+
         // When acquire is called and the semaphore has remaining permits, the function immediately
         // returns a permit. However, if no remaining permits are available, acquire (asynchronously)
         // waits until an outstanding permit is dropped, at which point, the freed permit is assigned
@@ -122,17 +304,35 @@ impl FeroxScanner {
         }
 
         let requester = Arc::new(Requester::from(self, ferox_scan.clone())?);
-        let increment_len = (self.handles.config.extensions.len() + 1) as u64;
+        let increment_len = requests_per_word(&self.handles.config) as u64;
+        let target = Url::parse(&self.target_url)?;
 
         // producer tasks (mp of mpsc); responsible for making requests
-        let producers = stream::iter(looping_words.deref().to_owned())
-            .map(|word| {
+        //
+        // words are taken from the shared wordlist one at a time, rather than copying the whole
+        // wordlist for every directory that gets scanned
+        let producers = stream::iter(0..looping_words.len())
+            .map(|index| {
+                let word = looping_words[index].clone();
                 let pb = progress_bar.clone(); // progress bar is an Arc around internal state
                 let scanned_urls_clone = scanned_urls.clone();
                 let requester_clone = requester.clone();
                 let handles_clone = self.handles.clone();
+                let target_clone = target.clone();
                 (
                     tokio::spawn(async move {
+                        if handles_clone.output.is_stopped() {
+                            // --stop-on-match: what the scan was looking for turned up already
+                            return;
+                        }
+
+                        let limits = &handles_clone.config.request_context.request_limits;
+
+                        if limits.reached(&target_clone) {
+                            // --request-limit: the host can't be sent any more requests
+                            return;
+                        }
+
                         if PAUSE_SCAN.load(Ordering::Acquire) {
                             // for every word in the wordlist, check to see if PAUSE_SCAN is set to true
                             // when true; enter a busy loop that only exits by setting PAUSE_SCAN back
@@ -147,6 +347,9 @@ impl FeroxScanner {
                                     });
                             }
                         }
+                        // --memory-limit: hold off on new requests while memory is over budget
+                        memory::wait_for_room().await;
+
                         requester_clone
                             .request(&word)
                             .await
@@ -172,11 +375,39 @@ impl FeroxScanner {
         producers.await;
         log::trace!("done awaiting scan producers");
 
+        let limits = &self.handles.config.request_context.request_limits;
+
+        if limits.newly_reached(&target) {
+            // reported once per host, by whichever scan of it notices first
+            let message = format!(
+                "{} was sent {} requests (--request-limit); its remaining requests were skipped",
+                target.host_str().unwrap_or_default(),
+                limits.limit()
+            );
+
+            log::warn!("{}", message);
+
+            if matches!(
+                self.handles.config.output_level,
+                OutputLevel::Default | OutputLevel::Quiet
+            ) {
+                ferox_print(
+                    &format!("{} {}\n", style("WRN").red(), message),
+                    &PROGRESS_PRINTER,
+                );
+            }
+        }
+
         self.handles.stats.send(AddToF64Field(
             DirScanTimes,
             scan_timer.elapsed().as_secs_f64(),
         ))?;
 
+        self.handles.stats.send(AddDirectoryRequests(
+            self.target_url.clone(),
+            ferox_scan.requests() as usize,
+        ))?;
+
         ferox_scan.finish()?;
 
         log::trace!("exit: scan_url");
@@ -1,22 +1,101 @@
 use crate::{
+    config::{Configuration, OutputLevel},
     event_handlers::{Command::AddToUsizeField, Handles},
+    numbers::NumberFormat,
+    progress::PROGRESS_PRINTER,
     statistics::StatField::ExpectedPerScan,
+    utils::ferox_print,
 };
 use anyhow::Result;
+use console::style;
 use std::{convert::TryInto, sync::Arc};
 
+use super::memory;
+
+/// projected number of requests at (or above) which the user is warned before scanning
+const LARGE_SCAN: usize = 1_000_000;
+
+/// Number of requests sent for each word in the wordlist; one for the word itself, and one more
+/// per extension
+///
+/// -f changes the word's url instead of adding another one, and each url is requested with a
+/// single method (GET, or HEAD with --head-only)
+pub fn requests_per_word(config: &Configuration) -> usize {
+    config.extensions.len() + 1
+}
+
+/// Number of requests needed to scan a single directory with the given number of words
+pub fn requests_per_directory(num_words: usize, config: &Configuration) -> usize {
+    num_words.saturating_mul(requests_per_word(config))
+}
+
+/// Warning about the number of requests scanning the given number of targets will take, when
+/// it's at least `LARGE_SCAN`; directories found while recursing are projected per directory,
+/// since there's no telling how many there'll be
+fn projection_warning(
+    num_targets: usize,
+    num_words: usize,
+    config: &Configuration,
+) -> Option<String> {
+    let per_directory = requests_per_directory(num_words, config);
+    let projected = per_directory.saturating_mul(num_targets.max(1));
+
+    if projected < LARGE_SCAN {
+        return None;
+    }
+
+    let numbers = NumberFormat::from_config(config);
+
+    let mut warning = format!(
+        "this configuration will send ~{} requests ({} words x {} per word x {} target(s))",
+        numbers.approximate(projected as u64),
+        numbers.count(num_words as u64),
+        requests_per_word(config),
+        numbers.count(num_targets.max(1) as u64)
+    );
+
+    if !config.no_recursion {
+        warning.push_str(&format!(
+            ", plus ~{} for every directory found while recursing",
+            numbers.approximate(per_directory as u64)
+        ));
+    }
+
+    Some(warning)
+}
+
+/// Warn the user when scanning the given number of targets will take a large number of requests;
+/// resumed scans are left alone, part of their work is already done
+pub fn warn_about_size(num_targets: usize, num_words: usize, handles: Arc<Handles>) {
+    if handles.config.resumed {
+        return;
+    }
+
+    let warning = match projection_warning(num_targets, num_words, &handles.config) {
+        Some(warning) => warning,
+        None => return,
+    };
+
+    log::warn!("{}", warning);
+
+    if matches!(
+        handles.config.output_level,
+        OutputLevel::Default | OutputLevel::Quiet
+    ) {
+        ferox_print(
+            &format!("{} {}\n", style("WRN").red(), warning),
+            &PROGRESS_PRINTER,
+        );
+    }
+}
+
 /// Perform steps necessary to run scans that only need to be performed once (warming up the
 /// engine, as it were)
 pub async fn initialize(num_words: usize, handles: Arc<Handles>) -> Result<()> {
     log::trace!("enter: initialize({}, {:?})", num_words, handles);
 
     // number of requests only needs to be calculated once, and then can be reused
-    let num_reqs_expected: u64 = if handles.config.extensions.is_empty() {
-        num_words.try_into()?
-    } else {
-        let total = num_words * (handles.config.extensions.len() + 1);
-        total.try_into()?
-    };
+    let num_reqs_expected: u64 = requests_per_directory(num_words, &handles.config).try_into()?;
 
     {
         // no real reason to keep the arc around beyond this call
@@ -24,6 +103,9 @@ pub async fn initialize(num_words: usize, handles: Arc<Handles>) -> Result<()> {
         scans.set_bar_length(num_reqs_expected);
     }
 
+    // --memory-limit applies to every scan from here on
+    memory::start_monitor(handles.config.memory_limit);
+
     // tell Stats object about the number of expected requests
     handles
         .stats
@@ -32,3 +114,42 @@ pub async fn initialize(num_words: usize, handles: Arc<Handles>) -> Result<()> {
     log::trace!("exit: initialize");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// each word is requested once, and once more per extension
+    fn requests_per_directory_counts_extensions() {
+        let mut config = Configuration::default();
+        assert_eq!(requests_per_directory(30_000, &config), 30_000);
+
+        config.extensions = vec![String::from("php"), String::from("txt")];
+        config.add_slash = true;
+        assert_eq!(requests_per_directory(30_000, &config), 90_000);
+    }
+
+    #[test]
+    /// small scans aren't warned about, large ones are, with recursion projected per directory
+    fn projection_warning_only_for_large_scans() {
+        let mut config = Configuration {
+            extensions: vec![
+                String::from("php"),
+                String::from("html"),
+                String::from("js"),
+            ],
+            ..Default::default()
+        };
+
+        assert!(projection_warning(1, 30_000, &config).is_none());
+
+        let warning = projection_warning(35, 30_000, &config).unwrap();
+        assert!(warning.starts_with("this configuration will send ~4.2M requests"));
+        assert!(warning.ends_with("plus ~120.0K for every directory found while recursing"));
+
+        config.no_recursion = true;
+        let warning = projection_warning(35, 30_000, &config).unwrap();
+        assert!(warning.ends_with("x 35 target(s))"));
+    }
+}
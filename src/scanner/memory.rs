@@ -0,0 +1,143 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use tokio::time::{sleep, Duration};
+
+/// how often resident memory is checked against --memory-limit
+const CHECK_INTERVAL: Duration = Duration::from_millis(250);
+
+/// once over the limit, requests stay paused until resident memory drops below this percentage
+/// of it; keeps the scan from flapping between paused and running right at the limit
+const RESUME_PERCENT: u64 = 90;
+
+/// --memory-limit in bytes; 0 when there's no limit
+static LIMIT: AtomicU64 = AtomicU64::new(0);
+
+/// whether resident memory is currently over the limit
+static OVER_LIMIT: AtomicBool = AtomicBool::new(false);
+
+/// set by the monitor each interval while over the limit, letting a single waiting request
+/// through; memory freed by the allocator isn't always handed back to the os, so waiting for
+/// resident memory to drop could otherwise pause the scan forever
+static PASS: AtomicBool = AtomicBool::new(false);
+
+/// whether the monitor task was already started
+static MONITORING: AtomicBool = AtomicBool::new(false);
+
+/// Resident memory of the process in bytes, where the platform reports it
+#[cfg(target_os = "linux")]
+fn resident_bytes() -> Option<u64> {
+    // second field of statm is the resident set size, in pages
+    let statm = std::fs::read_to_string("/proc/self/statm").ok()?;
+    let pages = statm.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+
+    // safety: sysconf only reads a system setting
+    let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+
+    if page_size <= 0 {
+        return None;
+    }
+
+    Some(pages * page_size as u64)
+}
+
+/// Resident memory of the process in bytes, where the platform reports it
+#[cfg(not(target_os = "linux"))]
+fn resident_bytes() -> Option<u64> {
+    None
+}
+
+/// whether the scan should be held back, given resident memory, the limit, and whether it was
+/// already being held back
+fn over_limit(resident: u64, limit: u64, currently_over: bool) -> bool {
+    if currently_over {
+        resident > limit / 100 * RESUME_PERCENT
+    } else {
+        resident > limit
+    }
+}
+
+/// Keep resident memory under `limit` bytes (--memory-limit) by pausing new requests while it's
+/// over; a limit of 0 does nothing
+///
+/// the monitor runs for the rest of the process, later calls only update the limit
+pub(super) fn start_monitor(limit: u64) {
+    LIMIT.store(limit, Ordering::Relaxed);
+
+    if limit == 0 || MONITORING.swap(true, Ordering::AcqRel) {
+        return;
+    }
+
+    if resident_bytes().is_none() {
+        log::warn!("--memory-limit isn't supported on this platform; ignoring it");
+        return;
+    }
+
+    tokio::spawn(async {
+        loop {
+            sleep(CHECK_INTERVAL).await;
+
+            let limit = LIMIT.load(Ordering::Relaxed);
+            let resident = resident_bytes().unwrap_or_default();
+            let was_over = OVER_LIMIT.load(Ordering::Acquire);
+            let over = limit > 0 && over_limit(resident, limit, was_over);
+
+            if over != was_over {
+                if over {
+                    log::warn!(
+                        "resident memory ({} bytes) is over --memory-limit ({} bytes); pausing new requests",
+                        resident,
+                        limit
+                    );
+                } else {
+                    log::warn!("resident memory is back under --memory-limit; resuming requests");
+                }
+
+                OVER_LIMIT.store(over, Ordering::Release);
+            }
+
+            if over {
+                PASS.store(true, Ordering::Release);
+            }
+        }
+    });
+}
+
+/// Wait until there's room to make another request under --memory-limit
+///
+/// returns right away when under the limit; when over, a single request is let through per
+/// check, so the scan slows to a crawl instead of stopping outright
+pub(super) async fn wait_for_room() {
+    while OVER_LIMIT.load(Ordering::Acquire) {
+        sleep(CHECK_INTERVAL).await;
+
+        if PASS.swap(false, Ordering::AcqRel) {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// scans are held back above the limit, and only released once well under it
+    fn over_limit_uses_hysteresis() {
+        let limit = 1000;
+
+        assert!(!over_limit(999, limit, false));
+        assert!(over_limit(1001, limit, false));
+
+        // already over: stays over until resident memory drops under 90% of the limit
+        assert!(over_limit(950, limit, true));
+        assert!(!over_limit(899, limit, true));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    /// resident memory is read from /proc on linux
+    fn resident_bytes_reports_usage() {
+        let resident = resident_bytes().unwrap();
+        assert!(resident > 0);
+    }
+}
@@ -1,12 +1,16 @@
+mod concurrency;
 mod ferox_scanner;
 mod utils;
 mod init;
 #[cfg(test)]
 mod tests;
 mod limit_heap;
+mod memory;
 mod policy_data;
 mod requester;
+mod seen;
 
-pub use self::ferox_scanner::{FeroxScanner, RESPONSES};
-pub use self::init::initialize;
+pub use self::ferox_scanner::FeroxScanner;
+pub use self::init::{initialize, warn_about_size};
+pub(crate) use self::utils::build_a_bucket;
 pub use self::utils::PolicyTrigger;
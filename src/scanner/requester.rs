@@ -1,32 +1,45 @@
 use std::{
     cmp::max,
     sync::{atomic::Ordering, Arc, Mutex},
+    time::Instant,
 };
 
 use anyhow::Result;
 use leaky_bucket::LeakyBucket;
+use reqwest::Method;
 use tokio::{
     sync::{oneshot, RwLock},
     time::{sleep, Duration},
 };
 
 use crate::{
-    atomic_load, atomic_store,
+    analyzers, atomic_load, atomic_store, auth_diff,
+    client::take_redirect_chain,
     config::RequesterPolicy,
+    cors,
     event_handlers::{
         Command::{self, AddError, SubtractFromUsizeField},
         Handles,
     },
     extractor::{ExtractionTarget::ResponseBody, ExtractorBuilder},
+    graphql, login, params, permutations,
     response::FeroxResponse,
     scan_manager::{FeroxScan, ScanStatus},
+    sourcemap,
     statistics::{StatError::Other, StatField::TotalExpected},
+    tor,
     url::FeroxUrl,
-    utils::logged_request,
-    HIGH_ERROR_RATIO,
+    utils::logged_request_with_method,
+    websocket, HIGH_ERROR_RATIO,
 };
 
-use super::{policy_data::PolicyData, FeroxScanner, PolicyTrigger};
+use super::{
+    concurrency::{ConcurrencyController, Signal},
+    policy_data::PolicyData,
+    seen::SeenUrls,
+    utils::build_a_bucket,
+    FeroxScanner, PolicyTrigger,
+};
 use crate::utils::should_deny_url;
 use std::collections::HashSet;
 
@@ -44,13 +57,16 @@ pub(super) struct Requester {
     /// data regarding policy and metadata about last enforced trigger etc...
     policy_data: PolicyData,
 
+    /// limits the number of requests in flight when --adaptive-threads is used
+    concurrency: Option<ConcurrencyController>,
+
     /// FeroxScan associated with the creation of this Requester
     ferox_scan: Arc<FeroxScan>,
 
     /// cache of previously seen links gotten via link extraction. since the requester is passed
     /// around as an arc, and seen_links needs to be mutable, putting it behind a lock for
     /// interior mutability, similar to the tuning_lock below
-    seen_links: RwLock<HashSet<String>>,
+    seen_links: RwLock<SeenUrls>,
 
     /// simple lock to control access to tuning to a single thread (per-scan)
     ///
@@ -67,7 +83,7 @@ impl Requester {
         let limit = scanner.handles.config.rate_limit;
 
         let rate_limiter = if limit > 0 {
-            Some(Self::build_a_bucket(limit)?)
+            Some(build_a_bucket(limit)?)
         } else {
             None
         };
@@ -77,10 +93,20 @@ impl Requester {
             scanner.handles.config.timeout,
         );
 
+        let concurrency = if scanner.handles.config.adaptive_threads {
+            Some(ConcurrencyController::new(
+                &scanner.target_url,
+                scanner.handles.config.threads,
+            ))
+        } else {
+            None
+        };
+
         Ok(Self {
             ferox_scan,
             policy_data,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            concurrency,
+            seen_links: RwLock::new(SeenUrls::new(&scanner.handles.config.seen_urls)),
             rate_limiter: RwLock::new(rate_limiter),
             handles: scanner.handles.clone(),
             target_url: scanner.target_url.to_owned(),
@@ -88,20 +114,6 @@ impl Requester {
         })
     }
 
-    /// build a LeakyBucket, given a rate limit (as requests per second)
-    fn build_a_bucket(limit: usize) -> Result<LeakyBucket> {
-        let refill = max((limit as f64 / 10.0).round() as usize, 1); // minimum of 1 per second
-        let tokens = max((limit as f64 / 2.0).round() as usize, 1);
-        let interval = if refill == 1 { 1000 } else { 100 }; // 1 second if refill is 1
-
-        Ok(LeakyBucket::builder()
-            .refill_interval(Duration::from_millis(interval)) // add tokens every 0.1s
-            .refill_amount(refill) // ex: 100 req/s -> 10 tokens per 0.1s
-            .tokens(tokens) // reduce initial burst, 2 is arbitrary, but felt good
-            .max(limit)
-            .build()?)
-    }
-
     /// sleep and set a flag that can be checked by other threads
     async fn cool_down(&self) {
         if atomic_load!(self.policy_data.cooling_down, Ordering::SeqCst) {
@@ -234,7 +246,7 @@ impl Requester {
             // mutex/rwlock primitives and awaits, this will minimize the cost of the extra calls
             return Ok(());
         } else {
-            Some(Self::build_a_bucket(new_limit.unwrap())?)
+            Some(build_a_bucket(new_limit.unwrap())?)
         };
 
         let _ = std::mem::replace(&mut *guard, new_bucket);
@@ -300,13 +312,15 @@ impl Requester {
     /// Wrapper for make_request
     ///
     /// Attempts recursion when appropriate and sends Responses to the output handler for processing
+    #[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(word = %word)))]
     pub async fn request(&self, word: &str) -> Result<()> {
         log::trace!("enter: request({})", word);
 
         let urls =
             FeroxUrl::from_string(&self.target_url, self.handles.clone()).formatted_urls(word)?;
 
-        let should_test_deny = !self.handles.config.url_denylist.is_empty();
+        let should_test_deny = !self.handles.config.url_denylist.is_empty()
+            || !self.handles.config.deny_url_regex.is_empty();
 
         for url in urls {
             // auto_tune is true, or rate_limit was set (mutually exclusive to user)
@@ -328,7 +342,29 @@ impl Requester {
                 continue;
             }
 
-            let response = logged_request(&url, self.handles.clone()).await?;
+            let permit = match &self.concurrency {
+                Some(controller) => controller.acquire().await,
+                None => None,
+            };
+
+            // --head-only: existence checks only, bodies are never sent
+            let method = if self.handles.config.head_only {
+                Method::HEAD
+            } else {
+                Method::GET
+            };
+
+            let timer = Instant::now();
+            let session = self.handles.session.generation();
+            let response =
+                logged_request_with_method(&url, &method, Some(word), self.handles.clone()).await;
+            let mut response_time = timer.elapsed();
+
+            if let (Some(controller), Some(permit)) = (&self.concurrency, permit) {
+                controller.release(permit, Signal::from_result(&response, response_time));
+            }
+
+            let response = response?;
 
             if (should_tune || self.handles.config.auto_bail)
                 && !atomic_load!(self.policy_data.cooling_down, Ordering::SeqCst)
@@ -351,9 +387,50 @@ impl Requester {
                 }
             }
 
+            if !self.handles.config.tor_control.is_empty()
+                && matches!(
+                    self.should_enforce_policy(),
+                    Some(PolicyTrigger::Status403) | Some(PolicyTrigger::Status429)
+                )
+            {
+                // --tor-control: walls of 403s/429s most likely mean the exit node got blocked
+                tor::renew_identity(&self.handles.config);
+            }
+
             // response came back without error, convert it to FeroxResponse
-            let ferox_response =
-                FeroxResponse::from(response, true, self.handles.config.output_level).await;
+            let mut ferox_response = FeroxResponse::from(
+                response,
+                !self.handles.config.head_only,
+                self.handles.config.max_response_size,
+                self.handles.config.output_level,
+            )
+            .await;
+
+            if let Some(login) = &self.handles.config.login {
+                if self.handles.session.expired(&ferox_response, login)
+                    && login::relogin(&self.handles.config, &self.handles.session, session).await
+                {
+                    // [login]: the session expired and was renewed, this url gets one more try
+                    let retried = Instant::now();
+                    let response =
+                        logged_request_with_method(&url, &method, Some(word), self.handles.clone())
+                            .await?;
+                    response_time = retried.elapsed();
+
+                    ferox_response = FeroxResponse::from(
+                        response,
+                        !self.handles.config.head_only,
+                        self.handles.config.max_response_size,
+                        self.handles.config.output_level,
+                    )
+                    .await;
+                }
+            }
+            ferox_response.set_redirect_chain(take_redirect_chain(&url));
+
+            if self.handles.config.response_time {
+                ferox_response.set_response_time(response_time);
+            }
 
             // do recursion if appropriate
             if !self.handles.config.no_recursion {
@@ -375,7 +452,10 @@ impl Requester {
                 continue;
             }
 
-            if self.handles.config.extract_links && !ferox_response.status().is_redirection() {
+            if self.handles.config.extract_links
+                && !self.handles.config.head_only
+                && !ferox_response.status().is_redirection()
+            {
                 let extractor = ExtractorBuilder::default()
                     .target(ResponseBody)
                     .response(&ferox_response)
@@ -389,7 +469,10 @@ impl Requester {
                     // gain and quickly drop the read lock on seen_links, using it while unlocked
                     // to determine if there are any new links to process
                     let read_links = self.seen_links.read().await;
-                    new_links = extracted.difference(&read_links).cloned().collect();
+                    new_links = extracted
+                        .into_iter()
+                        .filter(|link| !read_links.contains(link))
+                        .collect();
                 }
 
                 if !new_links.is_empty() {
@@ -397,13 +480,125 @@ impl Requester {
                     // some kind of less expensive gate (and not in a loop, obv)
                     let mut write_links = self.seen_links.write().await;
                     for new_link in &new_links {
-                        write_links.insert(new_link.to_owned());
+                        write_links.insert(new_link);
                     }
                 }
 
                 extractor.request_links(new_links).await?;
             }
 
+            if let Err(e) = analyzers::analyze(&ferox_response, self.handles.clone()) {
+                log::warn!("Could not analyze {}: {}", ferox_response.url(), e);
+            }
+
+            if self.handles.config.mine_params && params::is_mining_candidate(&ferox_response) {
+                match params::probe(&ferox_response, self.handles.clone()).await {
+                    Ok(findings) => {
+                        for finding in findings {
+                            self.handles
+                                .output
+                                .send(Command::ReportFinding(Box::new(finding)))?;
+                        }
+                    }
+                    Err(e) => log::warn!(
+                        "Could not mine parameters for {}: {}",
+                        ferox_response.url(),
+                        e
+                    ),
+                }
+            }
+
+            if !self.handles.config.track_changes.is_empty() {
+                self.handles.content_hashes.record(&ferox_response);
+            }
+
+            if self.handles.config.graphql_probe && graphql::is_graphql_candidate(&ferox_response) {
+                // sending an introspection query is an active check, only done when asked for
+                match graphql::probe(&ferox_response, self.handles.clone()).await {
+                    Ok(Some(finding)) => {
+                        self.handles
+                            .output
+                            .send(Command::ReportFinding(Box::new(finding)))?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Could not probe {}: {}", ferox_response.url(), e),
+                }
+            }
+
+            if self.handles.config.source_maps && ferox_response.is_javascript() {
+                match sourcemap::probe(&ferox_response, self.handles.clone()).await {
+                    Ok(Some(finding)) => {
+                        self.handles
+                            .output
+                            .send(Command::ReportFinding(Box::new(finding)))?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!(
+                        "Could not request source map for {}: {}",
+                        ferox_response.url(),
+                        e
+                    ),
+                }
+            }
+
+            if self.handles.config.cors_probe {
+                // requests with forged Origin headers are an active check, only done when asked for
+                match cors::probe(&ferox_response, self.handles.clone()).await {
+                    Ok(Some(finding)) => {
+                        self.handles
+                            .output
+                            .send(Command::ReportFinding(Box::new(finding)))?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!("Could not probe CORS on {}: {}", ferox_response.url(), e),
+                }
+            }
+
+            if self.handles.config.ws_probe {
+                // upgrade handshakes are an active check, only done when asked for
+                match websocket::probe(&ferox_response, self.handles.clone()).await {
+                    Ok(Some(finding)) => {
+                        self.handles
+                            .output
+                            .send(Command::ReportFinding(Box::new(finding)))?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!(
+                        "Could not probe {} for websockets: {}",
+                        ferox_response.url(),
+                        e
+                    ),
+                }
+            }
+
+            if self.handles.config.auth_diff {
+                // requesting everything twice is an active check, only done when asked for
+                match auth_diff::probe(&ferox_response, self.handles.clone()).await {
+                    Ok(Some(finding)) => {
+                        self.handles
+                            .output
+                            .send(Command::ReportFinding(Box::new(finding)))?;
+                    }
+                    Ok(None) => {}
+                    Err(e) => log::warn!(
+                        "Could not request {} without credentials: {}",
+                        ferox_response.url(),
+                        e
+                    ),
+                }
+            }
+
+            if self.handles.config.permute {
+                // found admin, so admin2, admin_old, etc are worth a look too
+                if let Err(e) = permutations::probe(&ferox_response, self.handles.clone()).await {
+                    log::warn!(
+                        "Could not request permutations of {}: {}",
+                        ferox_response.url(),
+                        e
+                    );
+                }
+            }
+
             // everything else should be reported
             if let Err(e) = ferox_response.send_report(self.handles.output.tx.clone()) {
                 log::warn!("Could not send FeroxResponse to output handler: {}", e);
@@ -425,7 +620,9 @@ mod tests {
         config::Configuration,
         config::OutputLevel,
         event_handlers::{FiltersHandler, ScanHandler, StatsHandler, Tasks, TermOutHandler},
+        event_stream::EventStream,
         filters,
+        login::Session,
         scan_manager::{ScanOrder, ScanType},
         statistics::StatError,
     };
@@ -439,13 +636,21 @@ mod tests {
 
         let (stats_task, stats_handle) = StatsHandler::initialize(configuration.clone());
         let (filters_task, filters_handle) = FiltersHandler::initialize();
-        let (out_task, out_handle) =
-            TermOutHandler::initialize(configuration.clone(), stats_handle.tx.clone());
+        let session = Arc::new(Session::default());
+        let events = Arc::new(EventStream::default());
+        let (out_task, out_handle) = TermOutHandler::initialize(
+            configuration.clone(),
+            stats_handle.tx.clone(),
+            session.clone(),
+            events.clone(),
+        );
 
         let handles = Arc::new(Handles::new(
             stats_handle,
             filters_handle,
             out_handle,
+            session,
+            events,
             configuration.clone(),
         ));
 
@@ -526,6 +731,7 @@ mod tests {
             1000,
             OutputLevel::Default,
             None,
+            Arc::default(),
         );
 
         scan.set_status(ScanStatus::Running).unwrap();
@@ -570,8 +776,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -598,8 +805,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: ferox_scan.clone(),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -623,8 +831,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: ferox_scan.clone(),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -663,8 +872,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: ferox_scan.clone(),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -718,8 +928,9 @@ mod tests {
         let req_clone = scan_two.clone();
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: req_clone,
             target_url: "http://one/one/stuff.php".to_string(),
             rate_limiter: RwLock::new(None),
@@ -752,8 +963,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://one/one/stuff.php".to_string(),
             rate_limiter: RwLock::new(None),
@@ -774,8 +986,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -797,8 +1010,9 @@ mod tests {
 
         let requester = Arc::new(Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -827,8 +1041,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -865,8 +1080,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(scan),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(Some(limiter)),
@@ -901,8 +1117,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(scan),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -929,8 +1146,9 @@ mod tests {
 
         let mut requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(None),
@@ -972,8 +1190,9 @@ mod tests {
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: Arc::new(FeroxScan::default()),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(Some(limiter)),
@@ -1009,14 +1228,16 @@ mod tests {
             1000,
             OutputLevel::Default,
             None,
+            Arc::default(),
         );
         scan.set_status(ScanStatus::Running).unwrap();
         scan.add_429();
 
         let requester = Requester {
             handles,
-            seen_links: RwLock::new(HashSet::<String>::new()),
+            seen_links: RwLock::new(SeenUrls::default()),
             tuning_lock: Mutex::new(0),
+            concurrency: None,
             ferox_scan: scan.clone(),
             target_url: "http://localhost".to_string(),
             rate_limiter: RwLock::new(Some(limiter)),
@@ -0,0 +1,213 @@
+use std::{
+    collections::{hash_map::DefaultHasher, HashSet},
+    hash::{Hash, Hasher},
+};
+
+/// number of urls the first bloom filter is sized for; each filter added afterwards holds twice
+/// as many as the one before it
+const INITIAL_CAPACITY: usize = 65_536;
+
+/// false positive rate of the first bloom filter; each filter added afterwards halves it, which
+/// keeps the overall rate under twice this value no matter how many filters are added
+const INITIAL_FALSE_POSITIVE_RATE: f64 = 0.001;
+
+/// Single, fixed-size bloom filter
+#[derive(Debug)]
+pub(super) struct BloomFilter {
+    /// bit array, 64 bits at a time
+    bits: Vec<u64>,
+
+    /// number of bits in `bits`
+    size: u64,
+
+    /// number of bits set per url
+    hashes: u32,
+
+    /// number of urls this filter was sized for
+    capacity: usize,
+
+    /// number of urls inserted so far
+    len: usize,
+}
+
+impl BloomFilter {
+    /// create a bloom filter with room for `capacity` urls at the given false positive rate
+    fn new(capacity: usize, false_positive_rate: f64) -> Self {
+        let ln2 = std::f64::consts::LN_2;
+        let size = (-(capacity as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil() as u64;
+        let size = size.max(64);
+        let hashes = ((size as f64 / capacity as f64) * ln2).round().max(1.0) as u32;
+
+        Self {
+            bits: vec![0; ((size + 63) / 64) as usize],
+            size,
+            hashes,
+            capacity,
+            len: 0,
+        }
+    }
+
+    /// positions of the bits that belong to the given hash pair (Kirsch-Mitzenmacher double
+    /// hashing)
+    fn positions(&self, (first, second): (u64, u64)) -> impl Iterator<Item = u64> {
+        let size = self.size;
+
+        (0..u64::from(self.hashes)).map(move |i| first.wrapping_add(i.wrapping_mul(second)) % size)
+    }
+
+    /// whether every bit for the given hash pair is set
+    fn contains(&self, hashes: (u64, u64)) -> bool {
+        self.positions(hashes)
+            .all(|bit| self.bits[(bit / 64) as usize] & (1 << (bit % 64)) != 0)
+    }
+
+    /// set every bit for the given hash pair
+    fn insert(&mut self, hashes: (u64, u64)) {
+        for bit in self.positions(hashes) {
+            self.bits[(bit / 64) as usize] |= 1 << (bit % 64);
+        }
+
+        self.len += 1;
+    }
+
+    /// whether the filter has taken in as many urls as it was sized for
+    fn is_full(&self) -> bool {
+        self.len >= self.capacity
+    }
+}
+
+/// Set of urls a scan has already seen, used to avoid requesting the same url twice
+///
+/// `exact` (the default) keeps every url around, which is precise but grows with the number of
+/// urls seen. `bloom` (--seen-urls bloom) trades precision for memory: it's a scalable bloom
+/// filter that stores a handful of bits per url, at the cost of occasionally (~0.2% of the time)
+/// reporting a url that was never seen as seen, i.e. skipping it
+#[derive(Debug)]
+pub(super) enum SeenUrls {
+    /// every seen url, as-is
+    Exact(HashSet<String>),
+
+    /// chain of bloom filters, each twice the size of the one before it
+    Bloom(Vec<BloomFilter>),
+}
+
+impl Default for SeenUrls {
+    /// exact tracking
+    fn default() -> Self {
+        SeenUrls::Exact(HashSet::new())
+    }
+}
+
+impl SeenUrls {
+    /// create an empty set based on the value of --seen-urls
+    pub(super) fn new(mode: &str) -> Self {
+        match mode {
+            "bloom" => SeenUrls::Bloom(vec![BloomFilter::new(
+                INITIAL_CAPACITY,
+                INITIAL_FALSE_POSITIVE_RATE,
+            )]),
+            _ => SeenUrls::default(),
+        }
+    }
+
+    /// two independent hashes of the given url
+    fn hash(url: &str) -> (u64, u64) {
+        let mut first = DefaultHasher::new();
+        url.hash(&mut first);
+
+        let mut second = DefaultHasher::new();
+        (url, 0x9e37_79b9_7f4a_7c15_u64).hash(&mut second);
+
+        // an even step could cycle through only part of the bit array
+        (first.finish(), second.finish() | 1)
+    }
+
+    /// whether the given url has (probably, when using bloom filters) been seen already
+    pub(super) fn contains(&self, url: &str) -> bool {
+        match self {
+            SeenUrls::Exact(urls) => urls.contains(url),
+            SeenUrls::Bloom(filters) => {
+                let hashes = Self::hash(url);
+                filters.iter().any(|filter| filter.contains(hashes))
+            }
+        }
+    }
+
+    /// add the given url to the set; returns false if it was (probably) already present
+    pub(super) fn insert(&mut self, url: &str) -> bool {
+        match self {
+            SeenUrls::Exact(urls) => {
+                if urls.contains(url) {
+                    return false;
+                }
+                urls.insert(url.to_owned())
+            }
+            SeenUrls::Bloom(filters) => {
+                let hashes = Self::hash(url);
+
+                if filters.iter().any(|filter| filter.contains(hashes)) {
+                    return false;
+                }
+
+                if filters.last().map_or(true, BloomFilter::is_full) {
+                    let count = filters.len() as i32;
+                    filters.push(BloomFilter::new(
+                        INITIAL_CAPACITY << count,
+                        INITIAL_FALSE_POSITIVE_RATE / 2_f64.powi(count),
+                    ));
+                }
+
+                if let Some(filter) = filters.last_mut() {
+                    filter.insert(hashes);
+                }
+
+                true
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// exact tracking never mistakes a new url for a seen one
+    fn seen_urls_exact_tracks_inserted_urls() {
+        let mut seen = SeenUrls::new("exact");
+
+        assert!(seen.insert("http://localhost/a"));
+        assert!(!seen.insert("http://localhost/a"));
+        assert!(seen.contains("http://localhost/a"));
+        assert!(!seen.contains("http://localhost/b"));
+    }
+
+    #[test]
+    /// bloom filters grow past their initial capacity, never forget a url, and rarely report a
+    /// url as seen when it wasn't
+    fn seen_urls_bloom_grows_with_few_false_positives() {
+        let mut seen = SeenUrls::new("bloom");
+        let total = INITIAL_CAPACITY * 2;
+
+        for i in 0..total {
+            seen.insert(&format!("http://localhost/{}", i));
+        }
+
+        if let SeenUrls::Bloom(filters) = &seen {
+            assert_eq!(filters.len(), 2);
+
+            // a few bits per url, well under the size of the urls themselves
+            let bytes: usize = filters.iter().map(|filter| filter.bits.len() * 8).sum();
+            assert!(bytes < 512 * 1024);
+        } else {
+            panic!("expected bloom filters");
+        }
+
+        assert!((0..total).all(|i| seen.contains(&format!("http://localhost/{}", i))));
+
+        let false_positives = (0..10_000)
+            .filter(|i| seen.contains(&format!("http://localhost/unseen/{}", i)))
+            .count();
+        assert!(false_positives < 50);
+    }
+}
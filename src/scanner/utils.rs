@@ -1,3 +1,9 @@
+use std::cmp::max;
+
+use anyhow::Result;
+use leaky_bucket::LeakyBucket;
+use tokio::time::Duration;
+
 #[derive(Copy, Clone, PartialEq, Debug)]
 /// represents different situations where different criteria can trigger auto-tune/bail behavior
 pub enum PolicyTrigger {
@@ -10,3 +16,17 @@ pub enum PolicyTrigger {
     /// excessive general errors
     Errors,
 }
+
+/// build a LeakyBucket, given a rate limit (as requests per second)
+pub(crate) fn build_a_bucket(limit: usize) -> Result<LeakyBucket> {
+    let refill = max((limit as f64 / 10.0).round() as usize, 1); // minimum of 1 per second
+    let tokens = max((limit as f64 / 2.0).round() as usize, 1);
+    let interval = if refill == 1 { 1000 } else { 100 }; // 1 second if refill is 1
+
+    Ok(LeakyBucket::builder()
+        .refill_interval(Duration::from_millis(interval)) // add tokens every 0.1s
+        .refill_amount(refill) // ex: 100 req/s -> 10 tokens per 0.1s
+        .tokens(tokens) // reduce initial burst, 2 is arbitrary, but felt good
+        .max(limit)
+        .build()?)
+}
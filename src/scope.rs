@@ -0,0 +1,304 @@
+//! hosts beyond the one a link was found on that may be scanned (--scope), i.e. subdomains
+//! mentioned in a target's pages; checked before an extracted link is queued and before a
+//! redirect is followed
+//!
+//! - `example.com`: that host
+//! - `*.example.com`: any subdomain of example.com
+//! - `10.0.0.0/24`: any ip address in the range; a lone address is a range of one
+//! - `!RULE`: never in scope, even when another rule includes it
+//!
+//! the host a link was found on (or a redirect started from) is always in scope
+use std::{net::IpAddr, sync::RwLock};
+
+use reqwest::Url;
+
+use crate::client::ip_host;
+
+/// A single --scope rule, without its `!`
+#[derive(Debug, Clone, PartialEq)]
+enum Rule {
+    /// a single host name
+    Host(String),
+
+    /// subdomains of the given domain, any number of levels deep
+    Subdomains(String),
+
+    /// ip addresses whose first `prefix` bits match the network's
+    Network(IpAddr, u8),
+}
+
+impl Rule {
+    /// Parse a --scope rule, without its `!`
+    fn parse(value: &str) -> Result<Self, String> {
+        let value = value.trim().trim_end_matches('.').to_ascii_lowercase();
+
+        if value.is_empty() {
+            return Err(String::from("Scope rule needs a host, domain, or address"));
+        }
+
+        if let Some(domain) = value.strip_prefix("*.") {
+            if !is_host_name(domain) {
+                return Err(format!(
+                    "Invalid scope rule *.{}, expected *.DOMAIN",
+                    domain
+                ));
+            }
+
+            return Ok(Rule::Subdomains(domain.to_string()));
+        }
+
+        let (address, prefix) = match value.split_once('/') {
+            Some((address, prefix)) => (address, Some(prefix)),
+            None => (value.as_str(), None),
+        };
+
+        if let Ok(ip) = address.parse::<IpAddr>() {
+            let max = if ip.is_ipv4() { 32 } else { 128 };
+
+            let prefix = match prefix {
+                Some(prefix) => match prefix.parse::<u8>() {
+                    Ok(prefix) if prefix <= max => prefix,
+                    _ => {
+                        return Err(format!(
+                            "Invalid scope rule {}, expected a prefix from 0 to {}",
+                            value, max
+                        ))
+                    }
+                },
+                None => max,
+            };
+
+            return Ok(Rule::Network(ip, prefix));
+        }
+
+        if prefix.is_some() || !is_host_name(&value) {
+            return Err(format!(
+                "Invalid scope rule {}, expected a host (example.com), domain (*.example.com), or \
+                 range (10.0.0.0/24)",
+                value
+            ));
+        }
+
+        Ok(Rule::Host(value))
+    }
+
+    /// Whether the given host (an ip address, when `ip` is given) matches the rule
+    fn matches(&self, host: &str, ip: Option<IpAddr>) -> bool {
+        match (self, ip) {
+            (Rule::Host(name), None) => name.eq_ignore_ascii_case(host),
+            (Rule::Subdomains(domain), None) => {
+                host.len() > domain.len() + 1
+                    && host.to_ascii_lowercase().ends_with(&format!(".{}", domain))
+            }
+            (Rule::Network(network, prefix), Some(ip)) => in_network(ip, *network, *prefix),
+            _ => false,
+        }
+    }
+}
+
+/// Whether `value` looks like a host name, i.e. example.com or intranet
+fn is_host_name(value: &str) -> bool {
+    !value.is_empty()
+        && value.split('.').all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        })
+}
+
+/// Whether the first `prefix` bits of `ip` and `network` are the same; addresses of different
+/// families never are
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    let (ip, network, bits) = match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) => (
+            u128::from(u32::from(ip)),
+            u128::from(u32::from(network)),
+            32,
+        ),
+        (IpAddr::V6(ip), IpAddr::V6(network)) => (u128::from(ip), u128::from(network), 128),
+        _ => return false,
+    };
+
+    if prefix == 0 {
+        return true;
+    }
+
+    let shift = bits - u32::from(prefix);
+    ip >> shift == network >> shift
+}
+
+/// Validate a --scope rule, `!` included
+pub fn valid_rule(value: &str) -> Result<(), String> {
+    Rule::parse(value.strip_prefix('!').unwrap_or(value)).map(|_| ())
+}
+
+/// Included and excluded --scope rules
+#[derive(Debug, Default)]
+struct Rules {
+    /// hosts that may be scanned
+    included: Vec<Rule>,
+
+    /// hosts that may never be scanned
+    excluded: Vec<Rule>,
+}
+
+/// The --scope rules in use
+#[derive(Debug, Default)]
+pub struct Scope {
+    /// rules, as loaded from the configuration
+    rules: RwLock<Rules>,
+}
+
+impl Scope {
+    /// use the given rules, replacing any loaded before; invalid rules are skipped (they're
+    /// reported when the configuration is validated)
+    pub fn load(&self, values: &[String]) {
+        let mut rules = Rules::default();
+
+        for value in values {
+            let (excluded, value) = match value.strip_prefix('!') {
+                Some(value) => (true, value),
+                None => (false, value.as_str()),
+            };
+
+            match Rule::parse(value) {
+                Ok(rule) if excluded => rules.excluded.push(rule),
+                Ok(rule) => rules.included.push(rule),
+                Err(e) => log::warn!("{}", e),
+            }
+        }
+
+        if let Ok(mut current) = self.rules.write() {
+            *current = rules;
+        }
+    }
+
+    /// whether any rules were given
+    pub fn is_set(&self) -> bool {
+        self.rules
+            .read()
+            .map(|rules| !rules.included.is_empty() || !rules.excluded.is_empty())
+            .unwrap_or(false)
+    }
+
+    /// whether `url` may be scanned, having been found on (or redirected to from) `origin`
+    ///
+    /// `origin`'s own host is always in scope; otherwise, exclusions win over inclusions, and
+    /// without a matching inclusion, the url is out of scope
+    pub fn allows(&self, url: &Url, origin: &Url) -> bool {
+        let host = match url.host_str() {
+            Some(host) => host,
+            None => return false,
+        };
+
+        if origin
+            .host_str()
+            .map_or(false, |origin| origin.eq_ignore_ascii_case(host))
+        {
+            return true;
+        }
+
+        let rules = match self.rules.read() {
+            Ok(rules) => rules,
+            Err(_) => return false,
+        };
+
+        let ip = ip_host(url);
+
+        if rules.excluded.iter().any(|rule| rule.matches(host, ip)) {
+            return false;
+        }
+
+        rules.included.iter().any(|rule| rule.matches(host, ip))
+    }
+
+    /// whether a redirect from `origin` to `url` is followed; without --scope, every redirect is
+    pub fn follows_redirect(&self, url: &Url, origin: &Url) -> bool {
+        !self.is_set() || self.allows(url, origin)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// scope loaded with the given rules
+    fn scope(rules: &[&str]) -> Scope {
+        let rules: Vec<String> = rules.iter().map(|rule| rule.to_string()).collect();
+
+        let scope = Scope::default();
+        scope.load(&rules);
+        scope
+    }
+
+    /// whether `url` is in `scope` when found on http://example.com/
+    fn allowed(scope: &Scope, url: &str) -> bool {
+        scope.allows(
+            &Url::parse(url).unwrap(),
+            &Url::parse("http://example.com/").unwrap(),
+        )
+    }
+
+    #[test]
+    /// hosts, domains, and ranges are told apart, and bad rules are reported
+    fn rules_parse_hosts_domains_and_ranges() {
+        assert_eq!(
+            Rule::parse("API.example.com."),
+            Ok(Rule::Host(String::from("api.example.com")))
+        );
+        assert_eq!(
+            Rule::parse("*.example.com"),
+            Ok(Rule::Subdomains(String::from("example.com")))
+        );
+        assert_eq!(
+            Rule::parse("10.0.0.0/8"),
+            Ok(Rule::Network("10.0.0.0".parse().unwrap(), 8))
+        );
+        assert_eq!(
+            Rule::parse("::1"),
+            Ok(Rule::Network("::1".parse().unwrap(), 128))
+        );
+
+        assert!(valid_rule("!staging.example.com").is_ok());
+        assert!(valid_rule("").is_err());
+        assert!(valid_rule("*.").is_err());
+        assert!(valid_rule("10.0.0.0/33").is_err());
+        assert!(valid_rule("example.com/24").is_err());
+        assert!(valid_rule("http://example.com").is_err());
+    }
+
+    #[test]
+    /// without rules, only the origin's host is in scope, and every redirect is followed
+    fn empty_scope_only_allows_origin() {
+        let scope = scope(&[]);
+
+        assert!(!scope.is_set());
+        assert!(allowed(&scope, "https://example.com:8443/admin"));
+        assert!(!allowed(&scope, "http://api.example.com/"));
+        assert!(scope.follows_redirect(
+            &Url::parse("http://elsewhere.test/").unwrap(),
+            &Url::parse("http://example.com/").unwrap(),
+        ));
+    }
+
+    #[test]
+    /// inclusions add hosts, and exclusions win over them
+    fn rules_include_and_exclude() {
+        let scope = scope(&["*.example.com", "!staging.example.com", "10.0.0.0/24"]);
+
+        assert!(allowed(&scope, "http://api.example.com/v1/"));
+        assert!(allowed(&scope, "http://a.b.example.com/"));
+        assert!(!allowed(&scope, "http://staging.example.com/"));
+        assert!(!allowed(&scope, "http://notexample.com/"));
+        assert!(allowed(&scope, "http://10.0.0.37/"));
+        assert!(!allowed(&scope, "http://10.0.1.37/"));
+        assert!(!allowed(&scope, "http://[::1]/"));
+
+        assert!(!scope.follows_redirect(
+            &Url::parse("http://elsewhere.test/").unwrap(),
+            &Url::parse("http://example.com/").unwrap(),
+        ));
+    }
+}
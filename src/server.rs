@@ -0,0 +1,906 @@
+//! run feroxbuster as a long-running scanning service, controlled over a small REST api
+//!
+//! | method | path                  | description                                             |
+//! |--------|-----------------------|---------------------------------------------------------|
+//! | POST   | `/scans`              | start a scan; the body is a JSON object of scan options |
+//! | GET    | `/scans`              | list all scans                                          |
+//! | GET    | `/scans/{id}`         | a scan's status, progress, and statistics               |
+//! | GET    | `/scans/{id}/results` | stream a scan's results as NDJSON until it's finished   |
+//! | GET    | `/scans/{id}/metrics` | a scan's statistics in the Prometheus text format       |
+//! | DELETE | `/scans/{id}`         | cancel a scan                                           |
+//!
+//! with `--api-token`, every request must carry the token as `Authorization: Bearer TOKEN`; it's
+//! required unless the api only listens on a loopback address. wordlists are read from the
+//! server's disk, so submitted scans may only use installed wordlists or files in `--wordlist-dir`
+use std::{
+    collections::HashMap,
+    convert::Infallible,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ArgMatches;
+use futures::{stream, StreamExt};
+use hyper::{
+    header::{AUTHORIZATION, CONTENT_TYPE, WWW_AUTHENTICATE},
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::Notify;
+use uuid::Uuid;
+
+use crate::{
+    api::{ScanResult, Scanner},
+    config::Configuration,
+    traits::FeroxSerialize,
+    wordlists,
+};
+
+/// address used when `--listen` isn't given
+pub const DEFAULT_LISTEN_ADDRESS: &str = "127.0.0.1:8000";
+
+/// number of finished (or cancelled) scans kept around for their results; the oldest are dropped
+/// as new scans are submitted
+const RETAINED_JOBS: usize = 100;
+
+/// Who may use the api, and what they may read from the server's disk
+#[derive(Debug, Default)]
+struct Access {
+    /// bearer token every request must carry (--api-token); any request is allowed without one
+    token: Option<String>,
+
+    /// directory whose files may be used as wordlists (--wordlist-dir)
+    wordlist_dir: Option<PathBuf>,
+}
+
+impl Access {
+    /// Whether the request carries the api token, if there is one
+    fn allows(&self, request: &Request<Body>) -> bool {
+        let token = match &self.token {
+            Some(token) => token,
+            None => return true,
+        };
+
+        let given = request
+            .headers()
+            .get(AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        // compared in constant time, so the token can't be guessed a byte at a time
+        match given {
+            Some(given) if given.len() == token.len() => {
+                given
+                    .bytes()
+                    .zip(token.bytes())
+                    .fold(0, |difference, (a, b)| difference | (a ^ b))
+                    == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// Path of the wordlist a submitted scan asked for, which must be the name of an installed
+    /// wordlist or a file inside of --wordlist-dir
+    fn wordlist(&self, requested: &str) -> Result<String> {
+        if let Some(path) = wordlists::installed(requested) {
+            return Ok(path.to_string_lossy().to_string());
+        }
+
+        if let Some(dir) = &self.wordlist_dir {
+            if let Some(path) = file_within(dir, requested) {
+                return Ok(path.to_string_lossy().to_string());
+            }
+        }
+
+        bail!(
+            "Wordlist {} is neither an installed wordlist nor a file in --wordlist-dir",
+            requested
+        )
+    }
+}
+
+/// The file `requested` refers to, relative to `dir`, as long as it's inside of `dir` once
+/// symlinks and `..` are resolved
+fn file_within(dir: &Path, requested: &str) -> Option<PathBuf> {
+    let dir = dir.canonicalize().ok()?;
+    let path = dir.join(requested).canonicalize().ok()?;
+
+    if path.starts_with(&dir) && path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Options accepted when submitting a scan; only `url` is required
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ScanRequest {
+    /// the target url
+    url: String,
+
+    /// path to the wordlist, or the name of an installed wordlist
+    wordlist: Option<String>,
+
+    /// number of concurrent threads
+    threads: Option<usize>,
+
+    /// number of seconds before a request times out
+    timeout: Option<u64>,
+
+    /// maximum recursion depth
+    depth: Option<usize>,
+
+    /// status codes to include
+    status_codes: Option<Vec<u16>>,
+
+    /// status codes to filter out
+    filter_status: Option<Vec<u16>>,
+
+    /// response sizes to filter out
+    filter_size: Option<Vec<u64>>,
+
+    /// file extension(s) to search for
+    extensions: Vec<String>,
+
+    /// headers sent with each request
+    headers: HashMap<String, String>,
+
+    /// proxy to use for requests
+    proxy: Option<String>,
+
+    /// user-agent sent with each request
+    user_agent: Option<String>,
+
+    /// disable TLS certificate validation
+    insecure: bool,
+
+    /// follow redirects
+    redirects: bool,
+
+    /// do not scan recursively
+    no_recursion: bool,
+
+    /// limit the number of requests per second per directory
+    rate_limit: Option<usize>,
+
+    /// stop scanning after the given amount of time, i.e. 10m
+    time_limit: Option<String>,
+}
+
+impl ScanRequest {
+    /// Validate the request's options and turn them into a Configuration
+    fn into_configuration(self, access: &Access) -> Result<Configuration> {
+        let extensions: Vec<&str> = self.extensions.iter().map(String::as_str).collect();
+
+        let mut builder = Configuration::builder()
+            .target_url(&self.url)
+            .extensions(&extensions)
+            .headers(self.headers)
+            .insecure(self.insecure)
+            .redirects(self.redirects)
+            .no_recursion(self.no_recursion);
+
+        if let Some(wordlist) = &self.wordlist {
+            builder = builder.wordlist(&access.wordlist(wordlist)?);
+        }
+
+        if let Some(threads) = self.threads {
+            builder = builder.threads(threads);
+        }
+
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+
+        if let Some(depth) = self.depth {
+            builder = builder.depth(depth);
+        }
+
+        if let Some(codes) = &self.status_codes {
+            builder = builder.status_codes(codes);
+        }
+
+        if let Some(codes) = &self.filter_status {
+            builder = builder.filter_status(codes);
+        }
+
+        if let Some(sizes) = &self.filter_size {
+            builder = builder.filter_size(sizes);
+        }
+
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy);
+        }
+
+        if let Some(user_agent) = &self.user_agent {
+            builder = builder.user_agent(user_agent);
+        }
+
+        if let Some(limit) = self.rate_limit {
+            builder = builder.rate_limit(limit);
+        }
+
+        if let Some(limit) = &self.time_limit {
+            builder = builder.time_limit(limit);
+        }
+
+        builder.build()
+    }
+}
+
+/// Where a submitted scan is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum JobStatus {
+    /// still scanning
+    Running,
+
+    /// every scan, including recursion, finished
+    Finished,
+
+    /// cancelled through the api
+    Cancelled,
+}
+
+/// A scan submitted through the api
+#[derive(Debug)]
+struct Job {
+    /// unique identifier of the scan
+    id: String,
+
+    /// the scan's target
+    url: String,
+
+    /// engine running the scan
+    scanner: Scanner,
+
+    /// where the scan is in its lifecycle
+    status: Mutex<JobStatus>,
+
+    /// every result found so far, as NDJSON lines
+    results: Mutex<Vec<String>>,
+
+    /// woken whenever a result is added or the status changes
+    updated: Notify,
+}
+
+impl Job {
+    /// Current status of the scan
+    fn status(&self) -> JobStatus {
+        self.status
+            .lock()
+            .map(|status| *status)
+            .unwrap_or(JobStatus::Finished)
+    }
+
+    /// Move the scan to the given status; a cancelled scan stays cancelled
+    fn set_status(&self, new_status: JobStatus) {
+        if let Ok(mut status) = self.status.lock() {
+            if *status != JobStatus::Cancelled {
+                *status = new_status;
+            }
+        }
+
+        self.updated.notify_waiters();
+    }
+
+    /// Record a result
+    fn push(&self, result: ScanResult) {
+        let line = match result {
+            ScanResult::Response(response) => response.as_json(),
+            ScanResult::Finding(finding) => finding.as_json(),
+        };
+
+        match line {
+            Ok(line) => {
+                if let Ok(mut results) = self.results.lock() {
+                    results.push(line);
+                }
+                self.updated.notify_waiters();
+            }
+            Err(e) => log::warn!("Could not serialize result of scan {}: {}", self.id, e),
+        }
+    }
+
+    /// Results found from index `start` onwards
+    fn results_from(&self, start: usize) -> Vec<String> {
+        self.results
+            .lock()
+            .map(|results| results.iter().skip(start).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Status, progress, and statistics of the scan as JSON
+    fn summary(&self) -> Value {
+        let stats = self
+            .scanner
+            .stats()
+            .and_then(|stats| serde_json::to_value(&*stats).ok())
+            .unwrap_or(Value::Null);
+
+        let requests = stats["requests"].as_u64().unwrap_or(0);
+        let expected = stats["total_expected"].as_u64().unwrap_or(0);
+
+        let progress = match self.status() {
+            JobStatus::Finished => 100.0,
+            _ if expected == 0 => 0.0,
+            _ => (requests as f64 / expected as f64 * 100.0).min(100.0),
+        };
+
+        json!({
+            "id": self.id,
+            "url": self.url,
+            "status": self.status(),
+            "progress": progress,
+            "results": self.results.lock().map(|results| results.len()).unwrap_or(0),
+            "statistics": stats,
+        })
+    }
+}
+
+/// All scans submitted to the server
+#[derive(Debug)]
+struct Jobs {
+    /// scans in the order they were submitted
+    jobs: Mutex<Vec<Arc<Job>>>,
+
+    /// most finished scans kept, see `RETAINED_JOBS`
+    retained: usize,
+}
+
+impl Default for Jobs {
+    fn default() -> Self {
+        Self {
+            jobs: Default::default(),
+            retained: RETAINED_JOBS,
+        }
+    }
+}
+
+impl Jobs {
+    /// Find a scan by its id
+    fn get(&self, id: &str) -> Option<Arc<Job>> {
+        self.jobs
+            .lock()
+            .ok()
+            .and_then(|jobs| jobs.iter().find(|job| job.id == id).cloned())
+    }
+
+    /// Start a scan using the given configuration, returning it once it's running
+    async fn submit(&self, config: Configuration) -> Result<Arc<Job>> {
+        let url = config.target_url.clone();
+        let scanner = Scanner::new(config);
+        let mut results = scanner.scan().await?;
+
+        let job = Arc::new(Job {
+            id: Uuid::new_v4().to_simple().to_string(),
+            url,
+            scanner,
+            status: Mutex::new(JobStatus::Running),
+            results: Default::default(),
+            updated: Notify::new(),
+        });
+
+        let running = job.clone();
+
+        tokio::spawn(async move {
+            while let Some(result) = results.next().await {
+                running.push(result);
+            }
+            running.set_status(JobStatus::Finished);
+        });
+
+        self.add(job.clone());
+
+        Ok(job)
+    }
+
+    /// Keep track of the given scan, dropping the oldest finished scans beyond `retained`
+    fn add(&self, job: Arc<Job>) {
+        if let Ok(mut jobs) = self.jobs.lock() {
+            jobs.push(job);
+
+            let finished = jobs
+                .iter()
+                .filter(|job| job.status() != JobStatus::Running)
+                .count();
+
+            let mut excess = finished.saturating_sub(self.retained);
+
+            jobs.retain(|job| {
+                if excess > 0 && job.status() != JobStatus::Running {
+                    excess -= 1;
+                    false
+                } else {
+                    true
+                }
+            });
+        }
+    }
+}
+
+/// A JSON response with the given status code
+fn json_response(status: StatusCode, body: Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap_or_default()
+}
+
+/// A JSON error response with the given status code
+fn error_response(status: StatusCode, message: &str) -> Response<Body> {
+    json_response(status, json!({ "error": message }))
+}
+
+/// A scan's statistics in the Prometheus text exposition format
+fn metrics_response(job: Arc<Job>) -> Response<Body> {
+    let metrics = job
+        .scanner
+        .stats()
+        .map(|stats| stats.as_prometheus())
+        .unwrap_or_default();
+
+    Response::builder()
+        .header(CONTENT_TYPE, "text/plain; version=0.0.4")
+        .body(Body::from(metrics))
+        .unwrap_or_default()
+}
+
+/// Stream a scan's results as NDJSON; the stream stays open until the scan is no longer running
+fn stream_results(job: Arc<Job>) -> Response<Body> {
+    let results = stream::unfold((job, 0), |(job, sent)| async move {
+        let batch = loop {
+            // created before checking for new results, so no update can be missed in between
+            let updated = job.updated.notified();
+
+            let batch = job.results_from(sent);
+
+            if !batch.is_empty() {
+                break batch;
+            }
+
+            if job.status() != JobStatus::Running {
+                return None;
+            }
+
+            updated.await;
+        };
+
+        let next = sent + batch.len();
+        Some((Ok::<_, Infallible>(batch.concat()), (job, next)))
+    });
+
+    Response::builder()
+        .header(CONTENT_TYPE, "application/x-ndjson")
+        .body(Body::wrap_stream(results))
+        .unwrap_or_default()
+}
+
+/// Handle a single api request
+async fn route(
+    jobs: Arc<Jobs>,
+    access: Arc<Access>,
+    request: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    log::debug!("{} {}", request.method(), request.uri().path());
+
+    if !access.allows(&request) {
+        let mut response = error_response(StatusCode::UNAUTHORIZED, "Missing or invalid token");
+        response
+            .headers_mut()
+            .insert(WWW_AUTHENTICATE, "Bearer".parse().unwrap());
+        return Ok(response);
+    }
+
+    let segments: Vec<String> = request
+        .uri()
+        .path()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(String::from)
+        .collect();
+
+    let segments: Vec<&str> = segments.iter().map(String::as_str).collect();
+
+    let response = match (request.method().clone(), segments.as_slice()) {
+        (Method::POST, ["scans"]) => {
+            let submitted = async {
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                let options: ScanRequest =
+                    serde_json::from_slice(&body).context("Invalid scan options")?;
+                options.into_configuration(&access)
+            };
+
+            match submitted.await {
+                Ok(config) => match jobs.submit(config).await {
+                    Ok(job) => json_response(StatusCode::CREATED, job.summary()),
+                    Err(e) => error_response(StatusCode::UNPROCESSABLE_ENTITY, &e.to_string()),
+                },
+                Err(e) => error_response(StatusCode::BAD_REQUEST, &format!("{:#}", e)),
+            }
+        }
+        (Method::GET, ["scans"]) => {
+            let summaries: Vec<Value> = jobs
+                .jobs
+                .lock()
+                .map(|jobs| jobs.iter().map(|job| job.summary()).collect())
+                .unwrap_or_default();
+
+            json_response(StatusCode::OK, Value::Array(summaries))
+        }
+        (method, ["scans", id])
+        | (method, ["scans", id, "results"])
+        | (method, ["scans", id, "metrics"]) => match jobs.get(id) {
+            None => error_response(StatusCode::NOT_FOUND, &format!("No scan with id {}", id)),
+            Some(job) => match (method, segments.get(2).copied()) {
+                (Method::GET, None) => json_response(StatusCode::OK, job.summary()),
+                (Method::GET, Some("results")) => stream_results(job),
+                (Method::GET, Some(_)) => metrics_response(job),
+                (Method::DELETE, None) => match job.scanner.cancel().await {
+                    Ok(_) => {
+                        job.set_status(JobStatus::Cancelled);
+                        json_response(StatusCode::OK, job.summary())
+                    }
+                    Err(e) => error_response(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string()),
+                },
+                _ => error_response(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+            },
+        },
+        _ => error_response(StatusCode::NOT_FOUND, "Not found"),
+    };
+
+    Ok(response)
+}
+
+/// Serve the api on `address` until ctrl+c is pressed
+async fn serve(address: SocketAddr, access: Access) -> Result<()> {
+    let jobs = Arc::new(Jobs::default());
+    let access = Arc::new(access);
+
+    let service = make_service_fn(move |_| {
+        let jobs = jobs.clone();
+        let access = access.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                route(jobs.clone(), access.clone(), request)
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&address)
+        .with_context(|| format!("Could not listen on {}", address))?
+        .serve(service);
+
+    println!("Listening on http://{}", server.local_addr());
+
+    server
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.unwrap_or_default();
+        })
+        .await?;
+
+    Ok(())
+}
+
+/// Run the `feroxbuster serve` subcommand
+pub fn run(subcommand: &ArgMatches) -> Result<()> {
+    let listen = subcommand
+        .value_of("listen")
+        .unwrap_or(DEFAULT_LISTEN_ADDRESS);
+
+    let address: SocketAddr = listen
+        .parse()
+        .map_err(|e| anyhow!("Invalid --listen address {}: {}", listen, e))?;
+
+    let access = Access {
+        token: subcommand
+            .value_of("api_token")
+            .filter(|token| !token.is_empty())
+            .map(String::from),
+        wordlist_dir: subcommand.value_of("wordlist_dir").map(PathBuf::from),
+    };
+
+    check_exposure(&address, &access)?;
+
+    let runtime = tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?;
+
+    runtime.block_on(serve(address, access))
+}
+
+/// Refuse to serve the api beyond the local machine without a token; anyone that can reach it
+/// could start scans
+fn check_exposure(address: &SocketAddr, access: &Access) -> Result<()> {
+    if !address.ip().is_loopback() && access.token.is_none() {
+        bail!(
+            "Refusing to listen on {} without --api-token (or FEROX_API_TOKEN); only loopback addresses may be used without one",
+            address
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use std::fs::write;
+    use tempfile::NamedTempFile;
+
+    /// send a request to the api and return the response's status and body
+    async fn call(jobs: Arc<Jobs>, method: Method, path: &str, body: &str) -> (StatusCode, String) {
+        call_with(jobs, Arc::new(Access::default()), method, path, body, None).await
+    }
+
+    /// same as `call`, with the given access rules and bearer token
+    async fn call_with(
+        jobs: Arc<Jobs>,
+        access: Arc<Access>,
+        method: Method,
+        path: &str,
+        body: &str,
+        token: Option<&str>,
+    ) -> (StatusCode, String) {
+        let mut request = Request::builder().method(method).uri(path);
+
+        if let Some(token) = token {
+            request = request.header(AUTHORIZATION, format!("Bearer {}", token));
+        }
+
+        let request = request.body(Body::from(body.to_string())).unwrap();
+
+        let response = route(jobs, access, request).await.unwrap();
+        let status = response.status();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+
+        (status, String::from_utf8_lossy(&body).to_string())
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// invalid options, unknown scans, and unknown paths are errors
+    async fn route_rejects_bad_requests() {
+        let jobs = Arc::new(Jobs::default());
+
+        let (status, body) = call(jobs.clone(), Method::POST, "/scans", r#"{"threads": 0}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("No target url was given"));
+
+        let (status, _) = call(jobs.clone(), Method::POST, "/scans", r#"{"nope": 1}"#).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+
+        let (status, _) = call(jobs.clone(), Method::GET, "/scans/1234", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = call(jobs.clone(), Method::GET, "/stuff", "").await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, body) = call(jobs, Method::GET, "/scans", "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "[]");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// a submitted scan runs in the background and its results can be streamed
+    async fn route_submits_and_streams_scan() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/LICENSE");
+            then.status(200).body("this is a test");
+        });
+
+        let wordlist = NamedTempFile::new().unwrap();
+        write(wordlist.path(), "LICENSE\n").unwrap();
+
+        let options = json!({
+            "url": srv.url("/"),
+            "wordlist": wordlist.path(),
+            "no_recursion": true,
+        });
+
+        let jobs = Arc::new(Jobs::default());
+
+        let access = Arc::new(Access {
+            wordlist_dir: wordlist.path().parent().map(PathBuf::from),
+            ..Default::default()
+        });
+
+        let (status, body) = call_with(
+            jobs.clone(),
+            access,
+            Method::POST,
+            "/scans",
+            &options.to_string(),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let submitted: Value = serde_json::from_str(&body).unwrap();
+        let id = submitted["id"].as_str().unwrap();
+
+        let path = format!("/scans/{}/results", id);
+        let (status, results) = call(jobs.clone(), Method::GET, &path, "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(results.contains(&srv.url("/LICENSE")));
+
+        let (_, body) = call(jobs.clone(), Method::GET, &format!("/scans/{}", id), "").await;
+        let summary: Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(summary["status"], "finished");
+        assert_eq!(summary["results"], 1);
+        assert!(summary["statistics"]["latency"]["p99_ms"].is_u64());
+
+        let path = format!("/scans/{}/metrics", id);
+        let (status, metrics) = call(jobs, Method::GET, &path, "").await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(metrics.contains("feroxbuster_responses_total{status=\"200\"} 1"));
+        assert!(metrics.contains("feroxbuster_response_time_milliseconds{quantile=\"0.99\"}"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// with a token, requests without it (or with the wrong one) are turned away
+    async fn route_requires_token() {
+        let jobs = Arc::new(Jobs::default());
+
+        let access = Arc::new(Access {
+            token: Some("s3cr3t".to_string()),
+            ..Default::default()
+        });
+
+        for token in [None, Some("nope"), Some("s3cr3")].iter() {
+            let (status, _) = call_with(
+                jobs.clone(),
+                access.clone(),
+                Method::GET,
+                "/scans",
+                "",
+                *token,
+            )
+            .await;
+            assert_eq!(status, StatusCode::UNAUTHORIZED);
+        }
+
+        let (status, body) =
+            call_with(jobs, access, Method::GET, "/scans", "", Some("s3cr3t")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body, "[]");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// wordlists outside of --wordlist-dir can't be read through the api
+    async fn route_refuses_wordlists_outside_wordlist_dir() {
+        let wordlist_dir = tempfile::tempdir().unwrap();
+        write(wordlist_dir.path().join("words.txt"), "LICENSE\n").unwrap();
+
+        let access = Access {
+            wordlist_dir: Some(wordlist_dir.path().to_path_buf()),
+            ..Default::default()
+        };
+
+        assert!(access.wordlist("words.txt").is_ok());
+        assert!(access.wordlist("../../../../etc/passwd").is_err());
+        assert!(access.wordlist("/etc/passwd").is_err());
+        assert!(Access::default().wordlist("words.txt").is_err());
+
+        let options = json!({ "url": "http://localhost", "wordlist": "/etc/passwd" });
+
+        let (status, body) = call_with(
+            Arc::new(Jobs::default()),
+            Arc::new(access),
+            Method::POST,
+            "/scans",
+            &options.to_string(),
+            None,
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body.contains("neither an installed wordlist nor a file in --wordlist-dir"));
+    }
+
+    #[test]
+    /// only loopback addresses can be listened on without a token
+    fn check_exposure_requires_token_beyond_loopback() {
+        let open = Access::default();
+        let protected = Access {
+            token: Some("s3cr3t".to_string()),
+            ..Default::default()
+        };
+
+        let loopback: SocketAddr = "127.0.0.1:8000".parse().unwrap();
+        let everywhere: SocketAddr = "0.0.0.0:8000".parse().unwrap();
+
+        assert!(check_exposure(&loopback, &open).is_ok());
+        assert!(check_exposure(&everywhere, &open).is_err());
+        assert!(check_exposure(&everywhere, &protected).is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    /// the same scan submitted twice reports its results both times
+    async fn route_reports_results_of_resubmitted_scan() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/LICENSE");
+            then.status(200).body("this is a test");
+        });
+
+        let wordlist = NamedTempFile::new().unwrap();
+        write(wordlist.path(), "LICENSE\n").unwrap();
+
+        let options = json!({
+            "url": srv.url("/"),
+            "wordlist": wordlist.path(),
+            "no_recursion": true,
+        });
+
+        let jobs = Arc::new(Jobs::default());
+
+        let access = Arc::new(Access {
+            wordlist_dir: wordlist.path().parent().map(PathBuf::from),
+            ..Default::default()
+        });
+
+        for _ in 0..2 {
+            let (status, body) = call_with(
+                jobs.clone(),
+                access.clone(),
+                Method::POST,
+                "/scans",
+                &options.to_string(),
+                None,
+            )
+            .await;
+            assert_eq!(status, StatusCode::CREATED);
+
+            let submitted: Value = serde_json::from_str(&body).unwrap();
+            let path = format!("/scans/{}/results", submitted["id"].as_str().unwrap());
+
+            let (_, results) = call(jobs.clone(), Method::GET, &path, "").await;
+            assert!(results.contains(&srv.url("/LICENSE")));
+        }
+    }
+
+    #[test]
+    /// the oldest finished scans are dropped once there are too many, running ones never are
+    fn jobs_evict_oldest_finished_scans() {
+        let job = |id: &str, status: JobStatus| {
+            Arc::new(Job {
+                id: id.to_string(),
+                url: String::from("http://localhost"),
+                scanner: Scanner::new(Configuration::default()),
+                status: Mutex::new(status),
+                results: Default::default(),
+                updated: Notify::new(),
+            })
+        };
+
+        let jobs = Jobs {
+            retained: 1,
+            ..Default::default()
+        };
+
+        jobs.add(job("running", JobStatus::Running));
+        jobs.add(job("finished", JobStatus::Finished));
+        jobs.add(job("cancelled", JobStatus::Cancelled));
+        jobs.add(job("also-running", JobStatus::Running));
+
+        let ids: Vec<String> = jobs
+            .jobs
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|job| job.id.clone())
+            .collect();
+
+        assert_eq!(ids, ["running", "cancelled", "also-running"]);
+    }
+}
@@ -0,0 +1,220 @@
+//! find source maps for javascript files and report the original source paths they contain
+use std::sync::Arc;
+
+use anyhow::Result;
+use lazy_static::lazy_static;
+use regex::Regex;
+use reqwest::{Method, StatusCode, Url};
+use serde_json::{json, Value};
+
+use crate::{
+    event_handlers::Handles,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{send_to_target, target_request, with_user_headers},
+};
+
+/// headers that may point to a javascript file's source map
+const SOURCEMAP_HEADERS: [&str; 2] = ["SourceMap", "X-SourceMap"];
+
+lazy_static! {
+    /// Regex used to find the `//# sourceMappingURL=...` comment at the end of a javascript file
+    static ref SOURCE_MAPPING_URL_REGEX: Regex =
+        Regex::new(r#"[#@]\s*sourceMappingURL=(?P<url>[^\s'"*]+)"#)
+            .expect("Could not compile regex");
+}
+
+/// Determine the url of the source map for the given javascript response
+///
+/// in order of preference: the `SourceMap`/`X-SourceMap` headers, a `sourceMappingURL` comment,
+/// and lastly the url of the javascript file with `.map` appended. inline (`data:`) maps are
+/// ignored
+pub(crate) fn source_map_url(response: &FeroxResponse) -> Option<Url> {
+    let header = SOURCEMAP_HEADERS.iter().find_map(|name| {
+        response
+            .headers()
+            .get(*name)
+            .and_then(|value| value.to_str().ok())
+            .map(String::from)
+    });
+
+    let comment = || {
+        SOURCE_MAPPING_URL_REGEX
+            .captures_iter(response.text())
+            .last()
+            .and_then(|c| c.name("url").map(|m| m.as_str().to_string()))
+    };
+
+    let map = match header.or_else(comment) {
+        Some(map) if map.starts_with("data:") => return None,
+        Some(map) => map,
+        None => format!("{}.map", response.url().path()),
+    };
+
+    // relative references are resolved against the javascript file's url
+    response.url().join(&map).ok()
+}
+
+/// Parse the `sources` out of the given source map body, returning `None` when the body isn't a
+/// source map
+pub(crate) fn parse_sources(body: &str) -> Option<Vec<String>> {
+    let json: Value = serde_json::from_str(body).ok()?;
+
+    if json["version"].is_null() || json["mappings"].is_null() {
+        return None;
+    }
+
+    let root = json["sourceRoot"].as_str().unwrap_or_default();
+
+    let sources = json["sources"]
+        .as_array()?
+        .iter()
+        .filter_map(|s| s.as_str())
+        .map(|s| format!("{}{}", root, s))
+        .collect();
+
+    Some(sources)
+}
+
+/// Request the source map for the given javascript response and report the original source
+/// file paths it lists
+///
+/// `Ok(None)` is returned when the map was already requested, doesn't exist, or isn't valid
+pub async fn probe(
+    response: &FeroxResponse,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let map_url = match source_map_url(response) {
+        Some(url) => url,
+        None => {
+            log::trace!("exit: probe -> None");
+            return Ok(None);
+        }
+    };
+
+    if handles.denied_by_regex(&map_url) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    if !handles.first_probe("sourcemap", map_url.as_str()) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    let context = &handles.config.request_context;
+    let request = target_request(context, &handles.config.client, &Method::GET, &map_url);
+    let request = with_user_headers(context, &handles.session, request, &map_url, None);
+    let map_response = send_to_target(context, request, &map_url).await?;
+
+    if map_response.status() != StatusCode::OK {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    let map_response =
+        FeroxResponse::from(map_response, true, 0, handles.config.output_level).await;
+
+    let finding = parse_sources(map_response.text()).map(|sources| {
+        let summary = format!("{} sources [{}]", sources.len(), sources.join(", "));
+
+        FeroxFinding::new(
+            "sourcemap",
+            map_url.as_str(),
+            &summary,
+            json!({
+                "script": response.url().as_str(),
+                "sources": sources,
+            }),
+        )
+        .with_exchange(&map_response)
+    });
+
+    log::trace!("exit: probe -> {:?}", finding);
+    Ok(finding)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use httpmock::{Method::GET, MockServer};
+
+    #[test]
+    /// headers win over comments, comments win over the default, inline maps are ignored
+    fn source_map_url_prefers_header_then_comment() {
+        let mut response = FeroxResponse::default();
+        response.set_url("http://localhost/static/app.js");
+
+        assert_eq!(
+            source_map_url(&response).unwrap().as_str(),
+            "http://localhost/static/app.js.map"
+        );
+
+        response.set_text("var a = 1;\n//# sourceMappingURL=maps/app.min.js.map");
+        assert_eq!(
+            source_map_url(&response).unwrap().as_str(),
+            "http://localhost/static/maps/app.min.js.map"
+        );
+
+        response.add_header("SourceMap", "/app.map");
+        assert_eq!(
+            source_map_url(&response).unwrap().as_str(),
+            "http://localhost/app.map"
+        );
+
+        let mut inline = FeroxResponse::default();
+        inline.set_url("http://localhost/inline.js");
+        inline.set_text("//# sourceMappingURL=data:application/json;base64,e30=");
+        assert!(source_map_url(&inline).is_none());
+    }
+
+    #[test]
+    /// sources are prefixed with the sourceRoot, non-maps are rejected
+    fn parse_sources_handles_source_root_and_other_json() {
+        let map = r#"{"version":3,"sourceRoot":"webpack:///","sources":["src/api/client.ts","src/App.vue"],"mappings":"AAAA"}"#;
+        assert_eq!(
+            parse_sources(map),
+            Some(vec![
+                "webpack:///src/api/client.ts".to_string(),
+                "webpack:///src/App.vue".to_string()
+            ])
+        );
+
+        assert!(parse_sources(r#"{"sources":["a.js"]}"#).is_none());
+        assert!(parse_sources("not a map").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// the map is requested once and its sources are reported as a finding
+    async fn probe_reports_sources_once() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET).path("/js/main.js.map");
+            then.status(200).body(
+                r#"{"version":3,"sources":["../internal/admin.js"],"names":[],"mappings":";AAAA"}"#,
+            );
+        });
+
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&srv.url("/js/main.js"));
+
+        let finding = probe(&response, handles.clone()).await.unwrap().unwrap();
+
+        assert_eq!(finding.category(), "sourcemap");
+        assert_eq!(
+            finding.details()["sources"],
+            json!(["../internal/admin.js"])
+        );
+        assert_eq!(finding.details()["script"], srv.url("/js/main.js"));
+
+        assert!(probe(&response, handles).await.unwrap().is_none());
+        assert_eq!(mock.hits(), 1);
+    }
+}
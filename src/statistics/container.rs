@@ -1,5 +1,5 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     convert::TryFrom,
     fs::File,
     io::BufReader,
@@ -7,6 +7,7 @@ use std::{
         atomic::{AtomicUsize, Ordering},
         Mutex,
     },
+    time::Duration,
 };
 
 use anyhow::{Context, Result};
@@ -15,11 +16,20 @@ use serde::{ser::SerializeStruct, Deserialize, Deserializer, Serialize, Serializ
 use serde_json::Value;
 
 use crate::{
+    client::{RATE_LIMITS, TRANSPORT},
+    numbers::NumberFormat,
     traits::FeroxSerialize,
     utils::{fmt_err, open_file, write_to},
 };
 
-use super::{error::StatError, field::StatField};
+use super::{
+    error::StatError,
+    field::StatField,
+    latency::{LatencyDistribution, LatencyHistogram, StatusLatency},
+};
+
+/// number of directories listed in the final summary, busiest first
+const SUMMARY_DIRECTORIES: usize = 5;
 
 /// Data collection of statistics related to a scan
 #[derive(Default, Debug)]
@@ -120,15 +130,39 @@ pub struct Stats {
     /// tracker for number of errors related to the request used
     request_errors: AtomicUsize,
 
+    /// tracker for number of errors that don't fall into any of the categories above
+    other_errors: AtomicUsize,
+
+    /// tracker for number of host names that couldn't be resolved
+    dns_errors: AtomicUsize,
+
+    /// tracker for number of timeouts while connecting (also counted in `timeouts`)
+    connect_timeouts: AtomicUsize,
+
+    /// tracker for number of failed tls handshakes
+    tls_errors: AtomicUsize,
+
+    /// tracker for number of connections reset by the other side
+    reset_errors: AtomicUsize,
+
+    /// tracker for the number of responses seen for each status code
+    status_codes: Mutex<BTreeMap<u16, usize>>,
+
+    /// tracker for the number of requests sent while scanning each directory
+    directory_requests: Mutex<BTreeMap<String, usize>>,
+
+    /// tracker for the response times of all requests that received a response
+    latency: LatencyHistogram,
+
+    /// tracker for the response times of all requests that received a response, by status class
+    latency_by_status: StatusLatency,
+
     /// tracker for each directory's total scan time in seconds as a float
     directory_scan_times: Mutex<Vec<f64>>,
 
     /// tracker for total runtime
     total_runtime: Mutex<Vec<f64>>,
 
-    /// tracker for the number of extensions the user specified
-    num_extensions: usize,
-
     /// tracker for whether to use json during serialization or not
     json: bool,
 }
@@ -154,7 +188,7 @@ impl Serialize for Stats {
     where
         S: Serializer,
     {
-        let mut state = serializer.serialize_struct("Stats", 32)?;
+        let mut state = serializer.serialize_struct("Stats", 43)?;
 
         state.serialize_field("type", &self.kind)?;
         state.serialize_field("timeouts", &atomic_load!(self.timeouts))?;
@@ -191,6 +225,17 @@ impl Serialize for Stats {
         state.serialize_field("request_errors", &atomic_load!(self.request_errors))?;
         state.serialize_field("directory_scan_times", &self.directory_scan_times)?;
         state.serialize_field("total_runtime", &self.total_runtime)?;
+        state.serialize_field("other_errors", &atomic_load!(self.other_errors))?;
+        state.serialize_field("dns_errors", &atomic_load!(self.dns_errors))?;
+        state.serialize_field("connect_timeouts", &atomic_load!(self.connect_timeouts))?;
+        state.serialize_field("tls_errors", &atomic_load!(self.tls_errors))?;
+        state.serialize_field("reset_errors", &atomic_load!(self.reset_errors))?;
+        state.serialize_field("status_codes", &self.status_codes)?;
+        state.serialize_field("directory_requests", &self.directory_requests)?;
+        state.serialize_field("latency", &self.latency)?;
+        state.serialize_field("latency_by_status", &self.latency_by_status)?;
+        state.serialize_field("transport", &TRANSPORT.snapshot())?;
+        state.serialize_field("rate_limits", &RATE_LIMITS.snapshot())?;
 
         state.end()
     }
@@ -203,7 +248,7 @@ impl<'a> Deserialize<'a> for Stats {
     where
         D: Deserializer<'a>,
     {
-        let stats = Self::new(0, false);
+        let stats = Self::new(false);
 
         let map: HashMap<String, Value> = HashMap::deserialize(deserializer)?;
 
@@ -412,6 +457,61 @@ impl<'a> Deserialize<'a> for Stats {
                         }
                     }
                 }
+                "other_errors" => {
+                    if let Some(num) = value.as_u64() {
+                        if let Ok(parsed) = usize::try_from(num) {
+                            atomic_increment!(stats.other_errors, parsed);
+                        }
+                    }
+                }
+                "dns_errors" => {
+                    if let Some(num) = value.as_u64() {
+                        if let Ok(parsed) = usize::try_from(num) {
+                            atomic_increment!(stats.dns_errors, parsed);
+                        }
+                    }
+                }
+                "connect_timeouts" => {
+                    if let Some(num) = value.as_u64() {
+                        if let Ok(parsed) = usize::try_from(num) {
+                            atomic_increment!(stats.connect_timeouts, parsed);
+                        }
+                    }
+                }
+                "tls_errors" => {
+                    if let Some(num) = value.as_u64() {
+                        if let Ok(parsed) = usize::try_from(num) {
+                            atomic_increment!(stats.tls_errors, parsed);
+                        }
+                    }
+                }
+                "reset_errors" => {
+                    if let Some(num) = value.as_u64() {
+                        if let Ok(parsed) = usize::try_from(num) {
+                            atomic_increment!(stats.reset_errors, parsed);
+                        }
+                    }
+                }
+                "status_codes" => {
+                    if let Some(codes) = value.as_object() {
+                        for (code, num) in codes {
+                            if let (Ok(code), Some(num)) = (code.parse::<u16>(), num.as_u64()) {
+                                stats.add_to_status_code(code, num as usize);
+                            }
+                        }
+                    }
+                }
+                "directory_requests" => {
+                    if let Some(directories) = value.as_object() {
+                        for (directory, num) in directories {
+                            if let Some(num) = num.as_u64() {
+                                stats.add_directory_requests(directory, num as usize);
+                            }
+                        }
+                    }
+                }
+                "latency" => stats.latency.merge_value(value),
+                "latency_by_status" => stats.latency_by_status.merge_value(value),
                 "directory_scan_times" => {
                     if let Some(arr) = value.as_array() {
                         for val in arr {
@@ -446,9 +546,8 @@ impl<'a> Deserialize<'a> for Stats {
 impl Stats {
     /// Small wrapper for default to set `kind` to "statistics" and `total_runtime` to have at least
     /// one value
-    pub fn new(num_extensions: usize, is_json: bool) -> Self {
+    pub fn new(is_json: bool) -> Self {
         Self {
-            num_extensions,
             json: is_json,
             kind: String::from("statistics"),
             total_runtime: Mutex::new(vec![0.0]),
@@ -466,6 +565,11 @@ impl Stats {
         atomic_load!(self.resources_discovered)
     }
 
+    /// public getter for requests
+    pub fn requests(&self) -> usize {
+        atomic_load!(self.requests)
+    }
+
     /// public getter for errors
     pub fn errors(&self) -> usize {
         atomic_load!(self.errors)
@@ -539,7 +643,23 @@ impl Stats {
             StatError::Request => {
                 atomic_increment!(self.request_errors);
             }
-            _ => {} // no need to hit Other as we always increment self.errors anyway
+            StatError::Other => {
+                atomic_increment!(self.other_errors);
+            }
+            StatError::Dns => {
+                atomic_increment!(self.dns_errors);
+            }
+            StatError::ConnectTimeout => {
+                // still a timeout, just one that happened before there was a connection
+                atomic_increment!(self.timeouts);
+                atomic_increment!(self.connect_timeouts);
+            }
+            StatError::Tls => {
+                atomic_increment!(self.tls_errors);
+            }
+            StatError::Reset => {
+                atomic_increment!(self.reset_errors);
+            }
         }
     }
 
@@ -548,9 +668,11 @@ impl Stats {
     /// Implies incrementing:
     ///     - requests
     ///     - appropriate status_* codes
+    ///     - status_codes
     ///     - errors (when code is [45]xx)
     pub fn add_status_code(&self, status: StatusCode) {
         self.add_request();
+        self.add_to_status_code(status.as_u16(), 1);
 
         if status.is_success() {
             atomic_increment!(self.successes);
@@ -597,6 +719,326 @@ impl Stats {
         }
     }
 
+    /// add `value` to the number of responses seen for the given status code
+    fn add_to_status_code(&self, code: u16, value: usize) {
+        if let Ok(mut codes) = self.status_codes.lock() {
+            *codes.entry(code).or_insert(0) += value;
+        }
+    }
+
+    /// record the time it took for a request to receive a response with the given status
+    pub fn add_latency(&self, status: StatusCode, elapsed: Duration) {
+        self.latency.record(elapsed);
+        self.latency_by_status.record(status, elapsed);
+    }
+
+    /// add `value` to the number of requests sent while scanning the given directory
+    pub fn add_directory_requests(&self, directory: &str, value: usize) {
+        if let Ok(mut directories) = self.directory_requests.lock() {
+            *directories.entry(directory.to_string()).or_insert(0) += value;
+        }
+    }
+
+    /// number of errors seen, by type
+    fn error_breakdown(&self) -> [(&'static str, usize); 10] {
+        [
+            ("timeout", atomic_load!(self.timeouts)),
+            ("connect timeout", atomic_load!(self.connect_timeouts)),
+            ("dns", atomic_load!(self.dns_errors)),
+            ("tls", atomic_load!(self.tls_errors)),
+            ("reset", atomic_load!(self.reset_errors)),
+            ("connection", atomic_load!(self.connection_errors)),
+            ("redirection", atomic_load!(self.redirection_errors)),
+            ("request", atomic_load!(self.request_errors)),
+            ("url format", atomic_load!(self.url_format_errors)),
+            ("other", atomic_load!(self.other_errors)),
+        ]
+    }
+
+    /// Human readable summary of the scan's requests, response times, status codes, errors, and
+    /// busiest directories; shown once all scans have finished
+    pub fn summary(&self, numbers: &NumberFormat) -> String {
+        let count = |value: usize| numbers.count(value as u64);
+        let millis = |value: u64| format!("{}ms", numbers.count(value));
+
+        let status_codes = self
+            .status_codes
+            .lock()
+            .map(|codes| {
+                codes
+                    .iter()
+                    .map(|(code, num)| format!("{}: {}", code, count(*num)))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+
+        let errors: Vec<String> = self
+            .error_breakdown()
+            .iter()
+            .filter(|(_, num)| *num > 0)
+            .map(|(kind, num)| format!("{}: {}", kind, count(*num)))
+            .collect();
+
+        let mut summary = format!(
+            "Requests: {} | Errors: {}\n",
+            count(atomic_load!(self.requests)),
+            count(self.errors())
+        );
+
+        let distribution = |label: &str, latency: LatencyDistribution| {
+            format!(
+                "{}: min {} | p50 {} | p95 {} | p99 {} | max {}\n",
+                label,
+                millis(latency.min),
+                millis(latency.p50),
+                millis(latency.p95),
+                millis(latency.p99),
+                millis(latency.max)
+            )
+        };
+
+        summary.push_str(&distribution("Latency", self.latency_by_status.overall()));
+
+        for (class, latency) in self.latency_by_status.by_class() {
+            summary.push_str(&distribution(&format!("  {}", class), latency));
+        }
+
+        let transport = TRANSPORT.snapshot();
+
+        if transport.new_connections > 0 {
+            summary.push_str(&format!(
+                "Connections: {} new, {} reused | DNS lookups: {} | TLS handshakes: {}\n",
+                count(transport.new_connections),
+                count(transport.reused_connections),
+                count(transport.dns_lookups),
+                count(transport.tls_handshakes)
+            ));
+        }
+
+        let rate_limits = RATE_LIMITS.snapshot();
+
+        for observed in &rate_limits {
+            let mut announced = Vec::new();
+
+            if let Some(limit) = observed.limit {
+                announced.push(format!("{} per window", numbers.count(limit)));
+            }
+
+            if let Some(remaining) = observed.lowest_remaining {
+                announced.push(format!("as few as {} left", numbers.count(remaining)));
+            }
+
+            if observed.too_many_requests > 0 {
+                announced.push(format!("{} 429s", count(observed.too_many_requests)));
+            }
+
+            if observed.cooldowns > 0 {
+                announced.push(format!("{} cool-downs", count(observed.cooldowns)));
+            }
+
+            if observed.pauses > 0 {
+                announced.push(format!(
+                    "paused {} times for {}s",
+                    count(observed.pauses),
+                    numbers.count((observed.paused_millis + 999) / 1000)
+                ));
+            }
+
+            summary.push_str(&format!(
+                "Rate limit ({}): {}\n",
+                observed.host,
+                announced.join(" | ")
+            ));
+        }
+
+        let throttled_millis: u64 = rate_limits
+            .iter()
+            .map(|observed| observed.paused_millis)
+            .sum();
+
+        if throttled_millis > 0 {
+            summary.push_str(&format!(
+                "Time throttled: {}s\n",
+                numbers.count((throttled_millis + 999) / 1000)
+            ));
+        }
+
+        if !status_codes.is_empty() {
+            summary.push_str(&format!("Status codes: {}\n", status_codes));
+        }
+
+        if !errors.is_empty() {
+            summary.push_str(&format!("Error types: {}\n", errors.join(", ")));
+        }
+
+        if let Ok(directories) = self.directory_requests.lock() {
+            let mut busiest: Vec<_> = directories.iter().collect();
+            busiest.sort_by(|a, b| b.1.cmp(a.1));
+
+            for (directory, num) in busiest.iter().take(SUMMARY_DIRECTORIES) {
+                summary.push_str(&format!("Requests to {}: {}\n", directory, count(**num)));
+            }
+        }
+
+        summary
+    }
+
+    /// Statistics in the Prometheus text exposition format
+    pub fn as_prometheus(&self) -> String {
+        let mut metrics = String::new();
+
+        let mut metric = |name: &str, kind: &str, help: &str, samples: Vec<(String, usize)>| {
+            metrics.push_str(&format!("# HELP feroxbuster_{} {}\n", name, help));
+            metrics.push_str(&format!("# TYPE feroxbuster_{} {}\n", name, kind));
+
+            for (labels, value) in samples {
+                metrics.push_str(&format!("feroxbuster_{}{} {}\n", name, labels, value));
+            }
+        };
+
+        metric(
+            "requests_total",
+            "counter",
+            "Requests sent",
+            vec![(String::new(), atomic_load!(self.requests))],
+        );
+
+        metric(
+            "responses_total",
+            "counter",
+            "Responses received, by status code",
+            self.status_codes
+                .lock()
+                .map(|codes| {
+                    codes
+                        .iter()
+                        .map(|(code, num)| (format!("{{status=\"{}\"}}", code), *num))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        );
+
+        metric(
+            "errors_total",
+            "counter",
+            "Errors encountered, by type",
+            self.error_breakdown()
+                .iter()
+                .map(|(kind, num)| (format!("{{type=\"{}\"}}", kind), *num))
+                .collect(),
+        );
+
+        metric(
+            "directory_requests_total",
+            "counter",
+            "Requests sent while scanning a directory",
+            self.directory_requests
+                .lock()
+                .map(|directories| {
+                    directories
+                        .iter()
+                        .map(|(directory, num)| {
+                            (
+                                format!("{{directory=\"{}\"}}", escape_label(directory)),
+                                *num,
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        );
+
+        let transport = TRANSPORT.snapshot();
+
+        metric(
+            "connections_total",
+            "counter",
+            "Requests sent to a named host, by whether a new connection was opened",
+            vec![
+                ("{state=\"new\"}".to_string(), transport.new_connections),
+                (
+                    "{state=\"reused\"}".to_string(),
+                    transport.reused_connections,
+                ),
+            ],
+        );
+
+        metric(
+            "dns_lookups_total",
+            "counter",
+            "DNS lookups performed",
+            vec![(String::new(), transport.dns_lookups)],
+        );
+
+        metric(
+            "tls_handshakes_total",
+            "counter",
+            "TLS handshakes performed",
+            vec![(String::new(), transport.tls_handshakes)],
+        );
+
+        metric(
+            "rate_limit_pauses_total",
+            "counter",
+            "Times requests to a host were paused because it asked for a break",
+            RATE_LIMITS
+                .snapshot()
+                .iter()
+                .map(|observed| {
+                    (
+                        format!("{{host=\"{}\"}}", escape_label(&observed.host)),
+                        observed.pauses,
+                    )
+                })
+                .collect(),
+        );
+
+        metric(
+            "rate_limit_paused_seconds_total",
+            "counter",
+            "Time requests to a host were paused, because it asked for a break or after repeated 429s",
+            RATE_LIMITS
+                .snapshot()
+                .iter()
+                .map(|observed| {
+                    (
+                        format!("{{host=\"{}\"}}", escape_label(&observed.host)),
+                        ((observed.paused_millis + 999) / 1000) as usize,
+                    )
+                })
+                .collect(),
+        );
+
+        metric(
+            "response_time_milliseconds",
+            "summary",
+            "Time taken for a request to receive a response",
+            vec![
+                (
+                    "{quantile=\"0.5\"}".to_string(),
+                    self.latency.percentile(0.5),
+                ),
+                (
+                    "{quantile=\"0.9\"}".to_string(),
+                    self.latency.percentile(0.9),
+                ),
+                (
+                    "{quantile=\"0.99\"}".to_string(),
+                    self.latency.percentile(0.99),
+                ),
+            ],
+        );
+
+        metrics.push_str(&format!(
+            "feroxbuster_response_time_milliseconds_sum {}\nferoxbuster_response_time_milliseconds_count {}\n",
+            self.latency.total(),
+            self.latency.count()
+        ));
+
+        metrics
+    }
+
     /// Update a `Stats` field of type f64
     pub fn update_f64_field(&self, field: StatField, value: f64) {
         if let StatField::DirScanTimes = field {
@@ -620,12 +1062,11 @@ impl Stats {
                 atomic_increment!(self.expected_per_scan, value);
             }
             StatField::TotalScans => {
-                let multiplier = self.num_extensions.max(1);
-
+                // expected_per_scan already counts each word once per extension
                 atomic_increment!(self.total_scans, value);
                 atomic_increment!(
                     self.total_expected,
-                    value * self.expected_per_scan.load(Ordering::Relaxed) * multiplier
+                    value * self.expected_per_scan.load(Ordering::Relaxed)
                 );
             }
             StatField::TotalExpected => {
@@ -662,64 +1103,101 @@ impl Stats {
 
         if let Some(state_stats) = state.get("statistics") {
             let d_stats = serde_json::from_value::<Stats>(state_stats.clone())?;
-            atomic_increment!(self.successes, atomic_load!(d_stats.successes));
-            atomic_increment!(self.timeouts, atomic_load!(d_stats.timeouts));
-            atomic_increment!(self.requests, atomic_load!(d_stats.requests));
-            atomic_increment!(self.errors, atomic_load!(d_stats.errors));
-            atomic_increment!(self.redirects, atomic_load!(d_stats.redirects));
-            atomic_increment!(self.client_errors, atomic_load!(d_stats.client_errors));
-            atomic_increment!(self.server_errors, atomic_load!(d_stats.server_errors));
-            atomic_increment!(self.links_extracted, atomic_load!(d_stats.links_extracted));
-            atomic_increment!(self.status_200s, atomic_load!(d_stats.status_200s));
-            atomic_increment!(self.status_301s, atomic_load!(d_stats.status_301s));
-            atomic_increment!(self.status_302s, atomic_load!(d_stats.status_302s));
-            atomic_increment!(self.status_401s, atomic_load!(d_stats.status_401s));
-            atomic_increment!(self.status_403s, atomic_load!(d_stats.status_403s));
-            atomic_increment!(self.status_429s, atomic_load!(d_stats.status_429s));
-            atomic_increment!(self.status_500s, atomic_load!(d_stats.status_500s));
-            atomic_increment!(self.status_503s, atomic_load!(d_stats.status_503s));
-            atomic_increment!(self.status_504s, atomic_load!(d_stats.status_504s));
-            atomic_increment!(self.status_508s, atomic_load!(d_stats.status_508s));
-            atomic_increment!(
-                self.wildcards_filtered,
-                atomic_load!(d_stats.wildcards_filtered)
-            );
-            atomic_increment!(
-                self.responses_filtered,
-                atomic_load!(d_stats.responses_filtered)
-            );
-            atomic_increment!(
-                self.resources_discovered,
-                atomic_load!(d_stats.resources_discovered)
-            );
-            atomic_increment!(
-                self.url_format_errors,
-                atomic_load!(d_stats.url_format_errors)
-            );
-            atomic_increment!(
-                self.connection_errors,
-                atomic_load!(d_stats.connection_errors)
-            );
-            atomic_increment!(
-                self.redirection_errors,
-                atomic_load!(d_stats.redirection_errors)
-            );
-            atomic_increment!(self.request_errors, atomic_load!(d_stats.request_errors));
-
-            if let Ok(scan_times) = d_stats.directory_scan_times.lock() {
-                for scan_time in scan_times.iter() {
-                    self.update_f64_field(StatField::DirScanTimes, *scan_time);
-                }
-            };
+            self.merge(&d_stats);
         }
         Ok(())
     }
+
+    /// Add the counters of another `Stats` object to this one; used when resuming a scan and
+    /// when collecting the statistics of distributed workers
+    ///
+    /// totals that describe the scan as a whole (expected requests, total scans, runtime, etc)
+    /// are left alone
+    pub fn merge(&self, other: &Stats) {
+        atomic_increment!(self.successes, atomic_load!(other.successes));
+        atomic_increment!(self.timeouts, atomic_load!(other.timeouts));
+        atomic_increment!(self.requests, atomic_load!(other.requests));
+        atomic_increment!(self.errors, atomic_load!(other.errors));
+        atomic_increment!(self.redirects, atomic_load!(other.redirects));
+        atomic_increment!(self.client_errors, atomic_load!(other.client_errors));
+        atomic_increment!(self.server_errors, atomic_load!(other.server_errors));
+        atomic_increment!(self.links_extracted, atomic_load!(other.links_extracted));
+        atomic_increment!(self.status_200s, atomic_load!(other.status_200s));
+        atomic_increment!(self.status_301s, atomic_load!(other.status_301s));
+        atomic_increment!(self.status_302s, atomic_load!(other.status_302s));
+        atomic_increment!(self.status_401s, atomic_load!(other.status_401s));
+        atomic_increment!(self.status_403s, atomic_load!(other.status_403s));
+        atomic_increment!(self.status_429s, atomic_load!(other.status_429s));
+        atomic_increment!(self.status_500s, atomic_load!(other.status_500s));
+        atomic_increment!(self.status_503s, atomic_load!(other.status_503s));
+        atomic_increment!(self.status_504s, atomic_load!(other.status_504s));
+        atomic_increment!(self.status_508s, atomic_load!(other.status_508s));
+        atomic_increment!(
+            self.wildcards_filtered,
+            atomic_load!(other.wildcards_filtered)
+        );
+        atomic_increment!(
+            self.responses_filtered,
+            atomic_load!(other.responses_filtered)
+        );
+        atomic_increment!(
+            self.resources_discovered,
+            atomic_load!(other.resources_discovered)
+        );
+        atomic_increment!(
+            self.url_format_errors,
+            atomic_load!(other.url_format_errors)
+        );
+        atomic_increment!(
+            self.connection_errors,
+            atomic_load!(other.connection_errors)
+        );
+        atomic_increment!(
+            self.redirection_errors,
+            atomic_load!(other.redirection_errors)
+        );
+        atomic_increment!(self.request_errors, atomic_load!(other.request_errors));
+        atomic_increment!(self.other_errors, atomic_load!(other.other_errors));
+        atomic_increment!(self.dns_errors, atomic_load!(other.dns_errors));
+        atomic_increment!(self.connect_timeouts, atomic_load!(other.connect_timeouts));
+        atomic_increment!(self.tls_errors, atomic_load!(other.tls_errors));
+        atomic_increment!(self.reset_errors, atomic_load!(other.reset_errors));
+
+        self.latency.merge(&other.latency);
+        self.latency_by_status.merge(&other.latency_by_status);
+
+        if let Ok(codes) = other.status_codes.lock() {
+            for (code, num) in codes.iter() {
+                self.add_to_status_code(*code, *num);
+            }
+        }
+
+        if let Ok(directories) = other.directory_requests.lock() {
+            for (directory, num) in directories.iter() {
+                self.add_directory_requests(directory, *num);
+            }
+        }
+
+        if let Ok(scan_times) = other.directory_scan_times.lock() {
+            for scan_time in scan_times.iter() {
+                self.update_f64_field(StatField::DirScanTimes, *scan_time);
+            }
+        };
+    }
+}
+
+/// escape a Prometheus label value
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{config::Configuration, Command};
-    use std::fs::write;
+    use std::{fs::write, time::Duration};
     use tempfile::NamedTempFile;
 
     use super::super::*;
@@ -792,7 +1270,7 @@ mod tests {
     ///     - errors
     fn stats_increments_timeouts() {
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
 
         stats.add_error(StatError::Timeout);
         stats.add_error(StatError::Timeout);
@@ -804,13 +1282,43 @@ mod tests {
         assert_eq!(stats.timeouts.load(Ordering::Relaxed), 4);
     }
 
+    #[test]
+    /// each typed error is counted on its own, connect timeouts as timeouts too, and the counts
+    /// survive a round trip through json
+    fn stats_counts_typed_errors() {
+        let stats = Stats::new(false);
+
+        stats.add_error(StatError::Dns);
+        stats.add_error(StatError::Dns);
+        stats.add_error(StatError::ConnectTimeout);
+        stats.add_error(StatError::Tls);
+        stats.add_error(StatError::Reset);
+
+        assert_eq!(atomic_load!(stats.errors), 5);
+        assert_eq!(atomic_load!(stats.dns_errors), 2);
+        assert_eq!(atomic_load!(stats.timeouts), 1);
+        assert_eq!(atomic_load!(stats.connect_timeouts), 1);
+
+        let loaded: Stats = serde_json::from_str(&stats.as_json().unwrap()).unwrap();
+        let merged = Stats::new(false);
+        merged.merge(&loaded);
+
+        assert_eq!(atomic_load!(merged.dns_errors), 2);
+        assert_eq!(atomic_load!(merged.connect_timeouts), 1);
+        assert_eq!(atomic_load!(merged.tls_errors), 1);
+        assert_eq!(atomic_load!(merged.reset_errors), 1);
+
+        let summary = stats.summary(&NumberFormat::new(",", "bytes", ""));
+        assert!(summary.contains("Error types: timeout: 1, connect timeout: 1, dns: 2, tls: 1"));
+    }
+
     #[test]
     /// when Stats::update_usize_field receives StatField::WildcardsFiltered, it should increment
     /// the following:
     ///     - responses_filtered
     fn stats_increments_wildcards() {
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
 
         assert_eq!(stats.responses_filtered.load(Ordering::Relaxed), 0);
         assert_eq!(stats.wildcards_filtered.load(Ordering::Relaxed), 0);
@@ -826,7 +1334,7 @@ mod tests {
     /// when Stats::update_usize_field receives StatField::ResponsesFiltered, it should increment
     fn stats_increments_responses_filtered() {
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
 
         assert_eq!(stats.responses_filtered.load(Ordering::Relaxed), 0);
 
@@ -842,7 +1350,7 @@ mod tests {
     fn stats_merge_from_alters_correct_fields() {
         let contents = r#"{"statistics":{"type":"statistics","timeouts":1,"requests":9207,"expected_per_scan":707,"total_expected":9191,"errors":3,"successes":720,"redirects":13,"client_errors":8474,"server_errors":2,"total_scans":13,"initial_targets":1,"links_extracted":51,"status_403s":3,"status_200s":720,"status_301s":12,"status_302s":1,"status_401s":4,"status_429s":2,"status_500s":5,"status_503s":9,"status_504s":6,"status_508s":7,"wildcards_filtered":707,"responses_filtered":707,"resources_discovered":27,"directory_scan_times":[2.211973078,1.989015505,1.898675839,3.9714468910000003,4.938152838,5.256073528,6.021986595,6.065740734,6.42633762,7.095142125,7.336982137,5.319785619,4.843649778],"total_runtime":[11.556575456000001],"url_format_errors":17,"redirection_errors":12,"connection_errors":21,"request_errors":4}}"#;
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
 
         let tfile = NamedTempFile::new().unwrap();
         write(&tfile, contents).unwrap();
@@ -889,11 +1397,128 @@ mod tests {
         assert!((stats.total_runtime.lock().unwrap()[0] - 0.0).abs() < f64::EPSILON);
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// status codes, response times, and per-directory requests are tracked from the handler's
+    /// commands
+    async fn statistics_handler_tracks_codes_latency_and_directories() -> Result<()> {
+        let (task, handle) = setup_stats_test();
+
+        handle
+            .tx
+            .send(Command::AddStatus(reqwest::StatusCode::NOT_FOUND))?;
+        handle
+            .tx
+            .send(Command::AddStatus(reqwest::StatusCode::NOT_FOUND))?;
+        handle
+            .tx
+            .send(Command::AddStatus(reqwest::StatusCode::IM_A_TEAPOT))?;
+        handle.tx.send(Command::AddLatency(
+            reqwest::StatusCode::NOT_FOUND,
+            Duration::from_millis(30),
+        ))?;
+        handle.tx.send(Command::AddError(StatError::Other))?;
+        handle.tx.send(Command::AddDirectoryRequests(
+            "http://localhost/".to_string(),
+            12,
+        ))?;
+        handle.tx.send(Command::AddDirectoryRequests(
+            "http://localhost/".to_string(),
+            3,
+        ))?;
+
+        teardown_stats_test(handle.tx.clone(), task).await;
+
+        let stats = handle.data;
+        assert_eq!(stats.status_codes.lock().unwrap()[&404], 2);
+        assert_eq!(stats.status_codes.lock().unwrap()[&418], 1);
+        assert_eq!(stats.latency.count(), 1);
+        assert_eq!(stats.latency.max(), 30);
+        assert_eq!(stats.latency_by_status.by_class()[0].0, "4xx");
+        assert_eq!(atomic_load!(stats.other_errors), 1);
+        assert_eq!(
+            stats.directory_requests.lock().unwrap()["http://localhost/"],
+            15
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    /// the new per-status, per-directory, and latency fields survive a round trip through json
+    fn stats_round_trips_breakdowns() {
+        let stats = Stats::new(false);
+
+        stats.add_status_code(StatusCode::OK);
+        stats.add_status_code(StatusCode::NOT_FOUND);
+        stats.add_error(StatError::Other);
+        stats.add_latency(StatusCode::OK, Duration::from_millis(7));
+        stats.add_latency(StatusCode::NOT_FOUND, Duration::from_millis(300));
+        stats.add_directory_requests("http://localhost/api/", 42);
+
+        let json = stats.as_json().unwrap();
+        assert!(json.contains(r#""status_codes":{"200":1,"404":1}"#));
+        assert!(json.contains(r#""directory_requests":{"http://localhost/api/":42}"#));
+        assert!(json.contains(r#""p99_ms":"#));
+        assert!(json.contains(r#""latency_by_status":{"2xx":{"#));
+        assert!(json.contains(r#""transport":{"dns_lookups":"#));
+        assert!(json.contains(r#""rate_limits":["#));
+
+        let loaded: Stats = serde_json::from_str(&json).unwrap();
+        let merged = Stats::new(false);
+        merged.merge(&loaded);
+        merged.merge(&loaded);
+
+        assert_eq!(merged.status_codes.lock().unwrap()[&200], 2);
+        assert_eq!(atomic_load!(merged.other_errors), 2);
+        assert_eq!(merged.latency.count(), 4);
+        assert_eq!(merged.latency.max(), 300);
+        assert_eq!(merged.latency_by_status.overall().count, 4);
+        assert_eq!(merged.latency_by_status.overall().max, 300);
+        assert_eq!(
+            merged.directory_requests.lock().unwrap()["http://localhost/api/"],
+            84
+        );
+    }
+
+    #[test]
+    /// the summary and prometheus output include percentiles, status codes, error types, and
+    /// directories
+    fn stats_summary_and_prometheus_include_breakdowns() {
+        let stats = Stats::new(false);
+
+        for _ in 0..1500 {
+            stats.add_status_code(StatusCode::NOT_FOUND);
+            stats.add_latency(StatusCode::NOT_FOUND, Duration::from_millis(15));
+        }
+        stats.add_error(StatError::Timeout);
+        stats.add_directory_requests("http://localhost/\"quoted\"/", 1500);
+
+        let summary = stats.summary(&NumberFormat::new(",", "bytes", ""));
+        assert!(summary.contains("Requests: 1,501 | Errors: 1"));
+        assert!(summary.contains("Latency: min 15ms | p50 15ms | p95 15ms | p99 15ms"));
+        assert!(summary.contains("  4xx: min 15ms"));
+        assert!(summary.contains("Status codes: 404: 1,500"));
+        assert!(summary.contains("Error types: timeout: 1"));
+        assert!(summary.contains("Requests to http://localhost/\"quoted\"/: 1,500"));
+
+        let metrics = stats.as_prometheus();
+        assert!(metrics.contains("feroxbuster_requests_total 1501\n"));
+        assert!(metrics.contains("feroxbuster_responses_total{status=\"404\"} 1500\n"));
+        assert!(metrics.contains("feroxbuster_errors_total{type=\"timeout\"} 1\n"));
+        assert!(metrics.contains("feroxbuster_errors_total{type=\"other\"} 0\n"));
+        assert!(metrics.contains(
+            "feroxbuster_directory_requests_total{directory=\"http://localhost/\\\"quoted\\\"/\"} 1500\n"
+        ));
+        assert!(metrics.contains("feroxbuster_response_time_milliseconds_count 1500\n"));
+        assert!(metrics.contains("# TYPE feroxbuster_dns_lookups_total counter\n"));
+        assert!(metrics.contains("feroxbuster_connections_total{state=\"reused\"}"));
+    }
+
     #[test]
     /// ensure update runtime overwrites the default 0th entry
     fn update_runtime_works() {
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
 
         assert!((stats.total_runtime.lock().unwrap()[0] - 0.0).abs() < f64::EPSILON);
         stats.update_runtime(20.2);
@@ -904,7 +1529,7 @@ mod tests {
     /// ensure status_403s returns the correct value
     fn status_403s_returns_correct_value() {
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
         stats.status_403s.store(12, Ordering::Relaxed);
         assert_eq!(stats.status_403s(), 12);
     }
@@ -913,7 +1538,7 @@ mod tests {
     /// ensure status_403s returns the correct value
     fn status_429s_returns_correct_value() {
         let config = Configuration::new().unwrap();
-        let stats = Stats::new(config.extensions.len(), config.json);
+        let stats = Stats::new(config.json);
         stats.status_429s.store(141, Ordering::Relaxed);
         assert_eq!(stats.status_429s(), 141);
     }
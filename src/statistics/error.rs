@@ -1,4 +1,13 @@
-#[derive(Debug, Copy, Clone)]
+use std::{error::Error, io};
+
+use anyhow::Context;
+use console::style;
+use reqwest::{Method, Url};
+use serde::{Deserialize, Serialize};
+
+use crate::{traits::FeroxSerialize, utils::fmt_err};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 /// Enum variants used to inform the `StatCommand` protocol what `Stats` fields should be updated
 pub enum StatError {
     /// Represents a timeout error
@@ -7,7 +16,7 @@ pub enum StatError {
     /// Represents a URL formatting error
     UrlFormat,
 
-    /// Represents an error encountered during redirection
+    /// Represents an error encountered during redirection, i.e. too many redirects
     Redirection,
 
     /// Represents an error encountered during connection
@@ -18,4 +27,229 @@ pub enum StatError {
 
     /// Represents any other error not explicitly defined above
     Other,
+
+    /// Represents a host name that couldn't be resolved
+    Dns,
+
+    /// Represents a timeout while connecting, as opposed to while waiting on a response
+    ConnectTimeout,
+
+    /// Represents a failed tls handshake, i.e. a bad certificate
+    Tls,
+
+    /// Represents a connection reset (or aborted) by the other side
+    Reset,
+}
+
+impl StatError {
+    /// Category of the given failed request
+    ///
+    /// the error's own kind is used where reqwest tells it apart (timeouts, redirects), and the
+    /// errors behind it otherwise, since dns, tls, and reset errors all come back as a failure to
+    /// connect or send the request
+    pub fn from_request_error(error: &reqwest::Error) -> Self {
+        if error.is_redirect() {
+            return StatError::Redirection;
+        }
+
+        if error.is_timeout() {
+            if error.is_connect() {
+                return StatError::ConnectTimeout;
+            }
+
+            return StatError::Timeout;
+        }
+
+        if let Some(category) = Self::from_cause(error) {
+            return category;
+        }
+
+        if error.is_connect() {
+            StatError::Connection
+        } else if error.is_request() {
+            StatError::Request
+        } else {
+            StatError::Other
+        }
+    }
+
+    /// Category named by the given error, or any error behind it, if it's a dns, tls, or reset
+    /// error
+    fn from_cause(error: &(dyn Error + 'static)) -> Option<Self> {
+        let mut current = Some(error);
+
+        while let Some(error) = current {
+            if let Some(io_error) = error.downcast_ref::<io::Error>() {
+                if matches!(
+                    io_error.kind(),
+                    io::ErrorKind::ConnectionReset
+                        | io::ErrorKind::ConnectionAborted
+                        | io::ErrorKind::BrokenPipe
+                ) {
+                    return Some(StatError::Reset);
+                }
+            }
+
+            let message = error.to_string().to_ascii_lowercase();
+
+            if message.contains("dns error") || message.contains("failed to lookup address") {
+                return Some(StatError::Dns);
+            }
+
+            if message.contains("ssl") || message.contains("tls") || message.contains("certificate")
+            {
+                return Some(StatError::Tls);
+            }
+
+            current = error.source();
+        }
+
+        None
+    }
+
+    /// Name of the category, as used in error records
+    pub fn name(&self) -> &'static str {
+        match self {
+            StatError::Timeout => "timeout",
+            StatError::UrlFormat => "url_format",
+            StatError::Redirection => "too_many_redirects",
+            StatError::Connection => "connection",
+            StatError::Request => "request",
+            StatError::Other => "other",
+            StatError::Dns => "dns",
+            StatError::ConnectTimeout => "connect_timeout",
+            StatError::Tls => "tls",
+            StatError::Reset => "reset",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+/// A single failed request, written to --output (and --event-stream) with --error-records; can
+/// be represented as a human readable string or JSON
+pub struct ErrorRecord {
+    #[serde(rename = "type")]
+    /// Name of this type of struct, used for serialization, i.e. `{"type":"error"}`
+    kind: String,
+
+    /// The category of the failure, i.e. `dns`, see `StatError::name`
+    category: String,
+
+    /// The http method of the failed request
+    method: String,
+
+    /// The url of the failed request
+    url: String,
+
+    /// The error, as reported by the client
+    message: String,
+}
+
+impl ErrorRecord {
+    /// Create a new ErrorRecord for the given failed request
+    pub fn new(category: StatError, method: &Method, url: &Url, message: &str) -> Self {
+        Self {
+            kind: "error".to_string(),
+            category: category.name().to_string(),
+            method: method.to_string(),
+            url: url.to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    /// getter for category
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    /// getter for url
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+/// Implementation of FeroxSerialize for ErrorRecord
+impl FeroxSerialize for ErrorRecord {
+    /// Create a string representation of the failed request
+    ///
+    /// ex: ERR       dns GET https://nope.localhost/ => error sending request for url (...)
+    fn as_str(&self) -> String {
+        format!(
+            "{} {:>9} {} {} => {}\n",
+            style("ERR").red(),
+            self.category,
+            self.method,
+            self.url,
+            self.message
+        )
+    }
+
+    /// Create an NDJSON representation of the failed request
+    ///
+    /// (expanded for clarity)
+    /// ex:
+    /// {
+    ///   "type": "error",
+    ///   "category": "dns",
+    ///   "method": "GET",
+    ///   "url": "https://nope.localhost/",
+    ///   "message": "error sending request for url (https://nope.localhost/): ..."
+    /// }\n
+    fn as_json(&self) -> anyhow::Result<String> {
+        let mut json = serde_json::to_string(&self).with_context(|| {
+            fmt_err(&format!(
+                "Could not convert {} error for {} to JSON",
+                self.category, self.url
+            ))
+        })?;
+        json.push('\n');
+        Ok(json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// resets are recognized by their kind, dns and tls failures by what they say
+    fn from_cause_recognizes_dns_tls_and_resets() {
+        let reset = io::Error::new(io::ErrorKind::ConnectionReset, "connection reset by peer");
+        assert_eq!(StatError::from_cause(&reset), Some(StatError::Reset));
+
+        let dns = io::Error::new(
+            io::ErrorKind::Other,
+            "dns error: failed to lookup address information",
+        );
+        assert_eq!(StatError::from_cause(&dns), Some(StatError::Dns));
+
+        let tls = io::Error::new(
+            io::ErrorKind::Other,
+            "error:1416F086:SSL routines:tls_process_server_certificate:certificate verify failed",
+        );
+        assert_eq!(StatError::from_cause(&tls), Some(StatError::Tls));
+
+        let refused = io::Error::new(io::ErrorKind::ConnectionRefused, "connection refused");
+        assert_eq!(StatError::from_cause(&refused), None);
+    }
+
+    #[test]
+    /// error records carry their category, and are a single line in either format
+    fn error_record_serializes_as_text_and_json() {
+        let record = ErrorRecord::new(
+            StatError::ConnectTimeout,
+            &Method::GET,
+            &Url::parse("http://localhost/admin").unwrap(),
+            "operation timed out",
+        );
+
+        assert!(record
+            .as_str()
+            .ends_with("connect_timeout GET http://localhost/admin => operation timed out\n"));
+
+        let json: serde_json::Value = serde_json::from_str(&record.as_json().unwrap()).unwrap();
+        assert_eq!(json["type"], "error");
+        assert_eq!(json["category"], "connect_timeout");
+        assert_eq!(json["url"], "http://localhost/admin");
+    }
 }
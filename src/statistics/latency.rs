@@ -0,0 +1,523 @@
+use std::{
+    collections::BTreeMap,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use hdrhistogram::{
+    serialization::{Deserializer, Serializer as _, V2Serializer},
+    Histogram,
+};
+use reqwest::StatusCode;
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use serde_json::Value;
+
+/// upper bounds (inclusive, in milliseconds) of each bucket; anything slower than the last
+/// bound lands in a final overflow bucket
+const BUCKET_BOUNDS: [usize; 18] = [
+    1, 2, 5, 10, 20, 35, 50, 75, 100, 150, 250, 400, 600, 1000, 2000, 5000, 10000, 30000,
+];
+
+/// Bucketed histogram of response times; cheap enough to update for every request while still
+/// giving reasonable percentiles
+#[derive(Debug)]
+pub struct LatencyHistogram {
+    /// number of responses seen per bucket, see `BUCKET_BOUNDS`
+    buckets: Vec<AtomicUsize>,
+
+    /// total number of responses recorded
+    count: AtomicUsize,
+
+    /// sum of all recorded response times, in milliseconds
+    total_ms: AtomicUsize,
+
+    /// slowest recorded response time, in milliseconds
+    max_ms: AtomicUsize,
+}
+
+impl Default for LatencyHistogram {
+    /// empty histogram with one bucket per bound, plus the overflow bucket
+    fn default() -> Self {
+        Self {
+            buckets: (0..=BUCKET_BOUNDS.len())
+                .map(|_| AtomicUsize::new(0))
+                .collect(),
+            count: AtomicUsize::new(0),
+            total_ms: AtomicUsize::new(0),
+            max_ms: AtomicUsize::new(0),
+        }
+    }
+}
+
+impl LatencyHistogram {
+    /// record a single response time
+    pub fn record(&self, elapsed: Duration) {
+        let millis = elapsed.as_millis() as usize;
+
+        let index = BUCKET_BOUNDS
+            .iter()
+            .position(|bound| millis <= *bound)
+            .unwrap_or(BUCKET_BOUNDS.len());
+
+        atomic_increment!(self.buckets[index]);
+        atomic_increment!(self.count);
+        atomic_increment!(self.total_ms, millis);
+        self.max_ms.fetch_max(millis, Ordering::Relaxed);
+    }
+
+    /// total number of recorded response times
+    pub fn count(&self) -> usize {
+        atomic_load!(self.count)
+    }
+
+    /// sum of all recorded response times, in milliseconds
+    pub fn total(&self) -> usize {
+        atomic_load!(self.total_ms)
+    }
+
+    /// average response time, in milliseconds
+    pub fn mean(&self) -> usize {
+        self.total().checked_div(self.count()).unwrap_or(0)
+    }
+
+    /// slowest response time, in milliseconds
+    pub fn max(&self) -> usize {
+        atomic_load!(self.max_ms)
+    }
+
+    /// estimate the given percentile (0.0 - 1.0), in milliseconds
+    ///
+    /// the bucket that contains the percentile is found, and the value is linearly interpolated
+    /// between the bucket's bounds
+    pub fn percentile(&self, percentile: f64) -> usize {
+        let count = self.count();
+
+        if count == 0 {
+            return 0;
+        }
+
+        let rank = ((percentile * count as f64).ceil() as usize)
+            .max(1)
+            .min(count);
+        let mut seen = 0;
+
+        for (index, bucket) in self.buckets.iter().enumerate() {
+            let in_bucket = atomic_load!(bucket);
+
+            if seen + in_bucket < rank {
+                seen += in_bucket;
+                continue;
+            }
+
+            let lower = if index == 0 {
+                0
+            } else {
+                BUCKET_BOUNDS[index - 1]
+            };
+            let upper = BUCKET_BOUNDS
+                .get(index)
+                .copied()
+                .unwrap_or_else(|| self.max());
+            let offset = (upper.saturating_sub(lower) * (rank - seen)) / in_bucket;
+
+            return (lower + offset).min(self.max());
+        }
+
+        self.max()
+    }
+
+    /// add the recorded response times of another histogram to this one
+    pub fn merge(&self, other: &LatencyHistogram) {
+        for (bucket, other_bucket) in self.buckets.iter().zip(other.buckets.iter()) {
+            atomic_increment!(bucket, atomic_load!(other_bucket));
+        }
+
+        atomic_increment!(self.count, other.count());
+        atomic_increment!(self.total_ms, other.total());
+        self.max_ms.fetch_max(other.max(), Ordering::Relaxed);
+    }
+
+    /// add the recorded response times found in a serialized histogram (i.e. from a state file)
+    pub fn merge_value(&self, value: &Value) {
+        if let Some(buckets) = value["buckets"].as_array() {
+            for (bucket, num) in self.buckets.iter().zip(buckets.iter()) {
+                atomic_increment!(bucket, num.as_u64().unwrap_or(0) as usize);
+            }
+        }
+
+        let field = |name: &str| value[name].as_u64().unwrap_or(0) as usize;
+
+        atomic_increment!(self.count, field("count"));
+        atomic_increment!(self.total_ms, field("total_ms"));
+        self.max_ms.fetch_max(field("max_ms"), Ordering::Relaxed);
+    }
+}
+
+/// Serialize implementation for LatencyHistogram
+impl Serialize for LatencyHistogram {
+    /// percentiles are included for consumers of the stats file; the buckets themselves are
+    /// what's read back in when resuming
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let buckets: Vec<usize> = self
+            .buckets
+            .iter()
+            .map(|bucket| atomic_load!(bucket))
+            .collect();
+
+        let mut state = serializer.serialize_struct("LatencyHistogram", 8)?;
+
+        state.serialize_field("count", &self.count())?;
+        state.serialize_field("total_ms", &self.total())?;
+        state.serialize_field("max_ms", &self.max())?;
+        state.serialize_field("mean_ms", &self.mean())?;
+        state.serialize_field("p50_ms", &self.percentile(0.5))?;
+        state.serialize_field("p90_ms", &self.percentile(0.9))?;
+        state.serialize_field("p99_ms", &self.percentile(0.99))?;
+        state.serialize_field("buckets", &buckets)?;
+
+        state.end()
+    }
+}
+
+/// significant figures kept by each of `StatusLatency`'s histograms; every recorded value is
+/// accurate to within 0.1%
+const SIGNIFICANT_FIGURES: u8 = 3;
+
+/// status classes tracked by `StatusLatency`, 1xx through 5xx
+const STATUS_CLASSES: usize = 5;
+
+/// Response time distribution, in milliseconds, as shown in the final summary
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct LatencyDistribution {
+    /// number of response times the distribution covers
+    pub count: u64,
+
+    /// fastest response time
+    pub min: u64,
+
+    /// median response time
+    pub p50: u64,
+
+    /// 95th percentile response time
+    pub p95: u64,
+
+    /// 99th percentile response time
+    pub p99: u64,
+
+    /// slowest response time
+    pub max: u64,
+}
+
+impl LatencyDistribution {
+    /// distribution of the response times (in microseconds) recorded in `histogram`
+    fn from_histogram(histogram: &Histogram<u64>) -> Self {
+        let millis = |micros: u64| (micros + 500) / 1000;
+
+        if histogram.is_empty() {
+            return Self::default();
+        }
+
+        Self {
+            count: histogram.len(),
+            min: millis(histogram.min()),
+            p50: millis(histogram.value_at_quantile(0.5)),
+            p95: millis(histogram.value_at_quantile(0.95)),
+            p99: millis(histogram.value_at_quantile(0.99)),
+            max: millis(histogram.max()),
+        }
+    }
+}
+
+/// HDR histograms of response times, one per status class (2xx, 3xx, ...)
+///
+/// unlike `LatencyHistogram`, values are kept at full precision, so the tail percentiles are
+/// exact rather than interpolated; updates take a lock, which is fine for the statistics handler
+/// that owns them
+#[derive(Debug)]
+pub struct StatusLatency {
+    /// response times, in microseconds, index 0 being 1xx responses
+    classes: Vec<Mutex<Histogram<u64>>>,
+}
+
+/// empty histogram that grows as needed to hold whatever it's given
+fn empty_histogram() -> Histogram<u64> {
+    Histogram::new(SIGNIFICANT_FIGURES).expect("Could not create latency histogram")
+}
+
+/// index into `StatusLatency::classes` for the given status code
+fn class_index(status: u16) -> Option<usize> {
+    match (status / 100) as usize {
+        class @ 1..=STATUS_CLASSES => Some(class - 1),
+        _ => None,
+    }
+}
+
+impl Default for StatusLatency {
+    /// one empty histogram per status class
+    fn default() -> Self {
+        Self {
+            classes: (0..STATUS_CLASSES)
+                .map(|_| Mutex::new(empty_histogram()))
+                .collect(),
+        }
+    }
+}
+
+impl StatusLatency {
+    /// record a single response time under the class of the response's status code
+    pub fn record(&self, status: StatusCode, elapsed: Duration) {
+        if let Some(index) = class_index(status.as_u16()) {
+            if let Ok(mut histogram) = self.classes[index].lock() {
+                histogram.saturating_record(elapsed.as_micros() as u64);
+            }
+        }
+    }
+
+    /// copy of each class's histogram, along with the class's name (i.e. `2xx`); empty classes
+    /// are skipped
+    fn snapshot(&self) -> Vec<(String, Histogram<u64>)> {
+        self.classes
+            .iter()
+            .enumerate()
+            .filter_map(|(index, histogram)| {
+                let histogram = histogram.lock().ok()?;
+
+                if histogram.is_empty() {
+                    return None;
+                }
+
+                Some((format!("{}xx", index + 1), histogram.clone()))
+            })
+            .collect()
+    }
+
+    /// response time distribution of each status class that saw a response, i.e. `2xx`
+    pub fn by_class(&self) -> Vec<(String, LatencyDistribution)> {
+        self.snapshot()
+            .iter()
+            .map(|(class, histogram)| {
+                (
+                    class.clone(),
+                    LatencyDistribution::from_histogram(histogram),
+                )
+            })
+            .collect()
+    }
+
+    /// response time distribution across all status classes
+    pub fn overall(&self) -> LatencyDistribution {
+        let mut overall = empty_histogram();
+
+        for (_, histogram) in self.snapshot() {
+            overall.add(&histogram).unwrap_or_default();
+        }
+
+        LatencyDistribution::from_histogram(&overall)
+    }
+
+    /// add the recorded response times of another `StatusLatency` to this one
+    pub fn merge(&self, other: &StatusLatency) {
+        // other's histograms are copied before any of ours are locked, so merging with ourselves
+        // can't deadlock
+        for (class, histogram) in other.snapshot() {
+            self.add(&class, &histogram);
+        }
+    }
+
+    /// add the recorded response times found in a serialized `StatusLatency` (i.e. from a state
+    /// file)
+    pub fn merge_value(&self, value: &Value) {
+        let classes = match value.as_object() {
+            Some(classes) => classes,
+            None => return,
+        };
+
+        for (class, distribution) in classes {
+            let bytes = distribution["histogram"]
+                .as_str()
+                .and_then(|encoded| base64::decode(encoded).ok());
+
+            if let Some(bytes) = bytes {
+                match Deserializer::new().deserialize::<u64, _>(&mut bytes.as_slice()) {
+                    Ok(histogram) => self.add(class, &histogram),
+                    Err(e) => log::warn!("Could not read {} latency histogram: {:?}", class, e),
+                }
+            }
+        }
+    }
+
+    /// add `histogram` to the histogram of the named class (i.e. `2xx`)
+    fn add(&self, class: &str, histogram: &Histogram<u64>) {
+        let index = class
+            .strip_suffix("xx")
+            .and_then(|class| class.parse::<u16>().ok())
+            .and_then(|class| class_index(class * 100));
+
+        if let Some(index) = index {
+            if let Ok(mut ours) = self.classes[index].lock() {
+                ours.add(histogram).unwrap_or_default();
+            }
+        }
+    }
+}
+
+/// Serialize implementation for StatusLatency
+impl Serialize for StatusLatency {
+    /// each class that saw a response is written as its distribution, for consumers of the stats
+    /// file, and the encoded histogram itself, which is what's read back in when resuming
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut classes = BTreeMap::new();
+
+        for (class, histogram) in self.snapshot() {
+            let distribution = LatencyDistribution::from_histogram(&histogram);
+            let mut encoded = Vec::new();
+
+            if let Err(e) = V2Serializer::new().serialize(&histogram, &mut encoded) {
+                log::warn!("Could not serialize {} latency histogram: {:?}", class, e);
+                continue;
+            }
+
+            classes.insert(
+                class,
+                serde_json::json!({
+                    "count": distribution.count,
+                    "min_ms": distribution.min,
+                    "p50_ms": distribution.p50,
+                    "p95_ms": distribution.p95,
+                    "p99_ms": distribution.p99,
+                    "max_ms": distribution.max,
+                    "histogram": base64::encode(&encoded),
+                }),
+            );
+        }
+
+        classes.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// an empty histogram reports zeroes
+    fn latency_histogram_empty_is_zero() {
+        let histogram = LatencyHistogram::default();
+        assert_eq!(histogram.count(), 0);
+        assert_eq!(histogram.mean(), 0);
+        assert_eq!(histogram.percentile(0.99), 0);
+    }
+
+    #[test]
+    /// percentiles land within the bucket holding the ranked response time
+    fn latency_histogram_estimates_percentiles() {
+        let histogram = LatencyHistogram::default();
+
+        for _ in 0..90 {
+            histogram.record(Duration::from_millis(8));
+        }
+        for _ in 0..9 {
+            histogram.record(Duration::from_millis(120));
+        }
+        histogram.record(Duration::from_millis(45_000));
+
+        assert_eq!(histogram.count(), 100);
+        assert_eq!(histogram.max(), 45_000);
+
+        let p50 = histogram.percentile(0.5);
+        assert!(p50 > 5 && p50 <= 10);
+
+        let p90 = histogram.percentile(0.9);
+        assert!(p90 > 5 && p90 <= 10);
+
+        let p99 = histogram.percentile(0.99);
+        assert!(p99 > 100 && p99 <= 150);
+
+        assert_eq!(histogram.percentile(1.0), 45_000);
+    }
+
+    #[test]
+    /// merging, both directly and from json, adds the other histogram's counts
+    fn latency_histogram_merges() {
+        let first = LatencyHistogram::default();
+        let second = LatencyHistogram::default();
+
+        first.record(Duration::from_millis(3));
+        second.record(Duration::from_millis(700));
+
+        first.merge(&second);
+        assert_eq!(first.count(), 2);
+        assert_eq!(first.max(), 700);
+
+        let third = LatencyHistogram::default();
+        third.merge_value(&serde_json::to_value(&first).unwrap());
+        assert_eq!(third.count(), 2);
+        assert_eq!(third.mean(), 351);
+        assert_eq!(third.percentile(0.5), first.percentile(0.5));
+    }
+
+    #[test]
+    /// response times are split by status class, and percentiles come from the exact values
+    fn status_latency_tracks_each_class() {
+        let latency = StatusLatency::default();
+
+        for millis in 1..=100 {
+            latency.record(StatusCode::NOT_FOUND, Duration::from_millis(millis));
+        }
+        latency.record(StatusCode::OK, Duration::from_millis(250));
+
+        let by_class = latency.by_class();
+        assert_eq!(by_class.len(), 2);
+        assert_eq!(by_class[0].0, "2xx");
+        assert_eq!(by_class[0].1.count, 1);
+        assert_eq!(by_class[0].1.min, 250);
+        assert_eq!(by_class[1].0, "4xx");
+
+        let not_found = by_class[1].1;
+        assert_eq!(not_found.count, 100);
+        assert_eq!(not_found.min, 1);
+        assert_eq!(not_found.p50, 50);
+        assert_eq!(not_found.p95, 95);
+        assert_eq!(not_found.p99, 99);
+        assert_eq!(not_found.max, 100);
+
+        let overall = latency.overall();
+        assert_eq!(overall.count, 101);
+        assert_eq!(overall.max, 250);
+    }
+
+    #[test]
+    /// merging, both directly and from json, adds the other's response times class by class
+    fn status_latency_merges() {
+        let first = StatusLatency::default();
+        let second = StatusLatency::default();
+
+        first.record(StatusCode::OK, Duration::from_millis(3));
+        second.record(StatusCode::OK, Duration::from_millis(9));
+        second.record(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Duration::from_millis(700),
+        );
+
+        first.merge(&second);
+        assert_eq!(first.overall().count, 3);
+        assert_eq!(first.by_class()[0].1.max, 9);
+
+        let json = serde_json::to_value(&first).unwrap();
+        assert_eq!(json["2xx"]["p50_ms"], 3);
+        assert_eq!(json["5xx"]["count"], 1);
+
+        let third = StatusLatency::default();
+        third.merge_value(&json);
+        assert_eq!(third.by_class(), first.by_class());
+    }
+}
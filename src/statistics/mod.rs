@@ -2,11 +2,12 @@ mod error;
 mod macros;
 mod container;
 mod field;
+mod latency;
 #[cfg(test)]
 mod tests;
 
 pub use self::container::Stats;
-pub use self::error::StatError;
+pub use self::error::{ErrorRecord, StatError};
 pub use self::field::StatField;
 
 #[cfg(test)]
@@ -7,7 +7,7 @@ use crate::{
 };
 use anyhow::Result;
 use reqwest::StatusCode;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tempfile::NamedTempFile;
 
 /// simple helper to reduce code reuse
@@ -37,11 +37,58 @@ async fn statistics_handler_exits() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// updates buffered by batched senders are all accounted for by the time a later Sync is answered,
+/// whether or not their batch filled up
+async fn statistics_handler_merges_batches() -> Result<()> {
+    let (task, handle) = setup_stats_test();
+
+    for _ in 0..1000 {
+        let sender = handle.batched();
+        sender.send(Command::AddStatus(StatusCode::NOT_FOUND))?;
+        sender.send(Command::AddLatency(
+            StatusCode::NOT_FOUND,
+            Duration::from_millis(5),
+        ))?;
+    }
+
+    handle
+        .batched()
+        .send(Command::AddError(StatError::Connection))?;
+    handle.sync().await?;
+
+    assert_eq!(handle.data.requests(), 1001);
+    assert_eq!(handle.data.errors(), 1);
+    let json = serde_json::to_value(&*handle.data)?;
+    assert_eq!(json["status_codes"]["404"], 1000);
+    assert_eq!(json["latency_by_status"]["4xx"]["count"], 1000);
+
+    teardown_stats_test(handle.tx.clone(), task).await;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+/// a batch that never fills up is still picked up by the handler shortly after
+async fn statistics_handler_flushes_partial_batches() -> Result<()> {
+    let (task, handle) = setup_stats_test();
+
+    let sender = handle.batched();
+    sender.send(Command::AddStatus(StatusCode::OK))?;
+    sender.send(Command::AddStatus(StatusCode::OK))?;
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+
+    assert_eq!(handle.data.requests(), 2);
+
+    teardown_stats_test(handle.tx.clone(), task).await;
+    Ok(())
+}
+
 #[test]
 /// Stats::save should write contents of Stats to disk
 fn save_writes_stats_object_to_disk() {
     let config = Configuration::new().unwrap();
-    let stats = Stats::new(config.extensions.len(), config.json);
+    let stats = Stats::new(config.json);
 
     stats.add_request();
     stats.add_request();
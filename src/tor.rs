@@ -0,0 +1,342 @@
+//! route scans through a local Tor daemon (--tor), and ask it for a new identity over its control
+//! port when the current exit node looks blocked (--tor-control)
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use anyhow::{bail, Context, Result};
+use lazy_static::lazy_static;
+use reqwest::Client;
+use serde_json::Value;
+use tokio::{
+    io::{AsyncBufReadExt, AsyncWriteExt, BufReader},
+    net::TcpStream,
+};
+
+use crate::config::Configuration;
+
+/// SOCKS proxy of a local Tor daemon; socks5h, so that hostnames are resolved by Tor as well
+pub const TOR_PROXY: &str = "socks5h://127.0.0.1:9050";
+
+/// api run by the Tor project that reports whether a request came through Tor, and from where
+pub const TOR_CHECK_URL: &str = "https://check.torproject.org/api/ip";
+
+/// minimum time between two requests for a new identity; Tor ignores NEWNYM signals sent less than
+/// 10 seconds apart, and new circuits take a moment to be put to use
+const RENEWAL_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    /// when a new identity was last asked for
+    static ref LAST_RENEWAL: Mutex<Option<Instant>> = Mutex::new(None);
+}
+
+/// Make sure that requests made with the given client leave through Tor, returning the ip address
+/// of the exit node
+pub async fn check_exit(client: &Client, check_url: &str) -> Result<String> {
+    log::trace!("enter: check_exit({})", check_url);
+
+    let response =
+        client.get(check_url).send().await.with_context(|| {
+            format!("Could not reach {} through Tor, is it running?", check_url)
+        })?;
+
+    let text = response.text().await?;
+
+    let body: Value = serde_json::from_str(&text)
+        .with_context(|| format!("Unexpected response from {}", check_url))?;
+
+    let ip = body["IP"].as_str().unwrap_or("unknown").to_string();
+
+    if body["IsTor"] != Value::Bool(true) {
+        bail!("Requests aren't leaving through Tor (seen from {})", ip);
+    }
+
+    log::trace!("exit: check_exit -> {}", ip);
+    Ok(ip)
+}
+
+/// Ask for a new identity in the background, unless --tor-control wasn't used or a new identity
+/// was already asked for within the last `RENEWAL_INTERVAL`
+pub fn renew_identity(config: &Configuration) {
+    if config.tor_control.is_empty() {
+        return;
+    }
+
+    if let Ok(mut last) = LAST_RENEWAL.lock() {
+        if matches!(*last, Some(at) if at.elapsed() < RENEWAL_INTERVAL) {
+            return;
+        }
+
+        *last = Some(Instant::now());
+    }
+
+    let address = config.tor_control.clone();
+    let password = config.tor_control_password.clone();
+
+    tokio::spawn(async move {
+        match new_identity(&address, &password).await {
+            Ok(_) => log::warn!("The Tor exit node looks blocked, switched to new circuits"),
+            Err(e) => log::warn!("Could not get a new identity from Tor: {}", e),
+        }
+    });
+}
+
+/// Signal NEWNYM over the control port at the given address; requests made on new connections
+/// afterwards go through new circuits, and most likely a different exit node
+pub async fn new_identity(address: &str, password: &str) -> Result<()> {
+    log::trace!("enter: new_identity({})", address);
+
+    let mut control = ControlConnection::connect(address).await?;
+
+    control.authenticate(password).await?;
+    control.command("SIGNAL NEWNYM").await?;
+    control.command("QUIT").await.unwrap_or_default();
+
+    log::trace!("exit: new_identity");
+    Ok(())
+}
+
+/// Connection to Tor's control port, speaking just enough of the control protocol to
+/// authenticate and send signals
+///
+/// ref: https://gitweb.torproject.org/torspec.git/tree/control-spec.txt
+struct ControlConnection {
+    /// the connection, buffered so that replies can be read line by line
+    stream: BufReader<TcpStream>,
+}
+
+/// implementation of ControlConnection
+impl ControlConnection {
+    /// connect to the control port at the given address, i.e. 127.0.0.1:9051
+    async fn connect(address: &str) -> Result<Self> {
+        let stream = TcpStream::connect(address)
+            .await
+            .with_context(|| format!("Could not connect to Tor's control port at {}", address))?;
+
+        Ok(Self {
+            stream: BufReader::new(stream),
+        })
+    }
+
+    /// send the given command and return the lines of Tor's reply; anything other than a 250
+    /// (success) reply is an error
+    async fn command(&mut self, command: &str) -> Result<Vec<String>> {
+        self.stream
+            .get_mut()
+            .write_all(format!("{}\r\n", command).as_bytes())
+            .await?;
+
+        let mut lines = Vec::new();
+
+        loop {
+            let mut line = String::new();
+
+            if self.stream.read_line(&mut line).await? == 0 {
+                bail!("Tor closed the control connection");
+            }
+
+            let line = line.trim_end().to_string();
+
+            // every line but the last has a - or + after the status code, i.e. 250-... 250 OK
+            let last = line.as_bytes().get(3).map_or(true, |c| *c == b' ');
+
+            lines.push(line);
+
+            if last {
+                break;
+            }
+        }
+
+        let reply = lines.last().map(String::as_str).unwrap_or_default();
+
+        if !reply.starts_with("250") {
+            let verb = command.split_whitespace().next().unwrap_or_default();
+            bail!("Tor refused {}: {}", verb, reply);
+        }
+
+        Ok(lines)
+    }
+
+    /// authenticate with the given password or, without one, with whatever Tor accepts out of
+    /// no authentication and its authentication cookie
+    async fn authenticate(&mut self, password: &str) -> Result<()> {
+        if !password.is_empty() {
+            let escaped = password.replace('\\', "\\\\").replace('"', "\\\"");
+            self.command(&format!("AUTHENTICATE \"{}\"", escaped))
+                .await?;
+            return Ok(());
+        }
+
+        let info = self.command("PROTOCOLINFO 1").await?;
+
+        let methods = info
+            .iter()
+            .find_map(|line| line.strip_prefix("250-AUTH METHODS="))
+            .unwrap_or_default();
+
+        if methods.split(|c| c == ',' || c == ' ').any(|m| m == "NULL") {
+            self.command("AUTHENTICATE").await?;
+            return Ok(());
+        }
+
+        let cookie_file = match cookie_file(methods) {
+            Some(file) if methods.contains("COOKIE") => file,
+            _ => bail!("Tor's control port needs a password, use --tor-control-password"),
+        };
+
+        let cookie = tokio::fs::read(&cookie_file).await.with_context(|| {
+            format!("Could not read Tor's authentication cookie {}", cookie_file)
+        })?;
+
+        let hex: String = cookie.iter().map(|byte| format!("{:02x}", byte)).collect();
+
+        self.command(&format!("AUTHENTICATE {}", hex)).await?;
+        Ok(())
+    }
+}
+
+/// path to the authentication cookie, pulled out of a PROTOCOLINFO reply's AUTH line
+///
+/// ex: METHODS=COOKIE,SAFECOOKIE COOKIEFILE="/run/tor/control.authcookie" -> /run/tor/control.authcookie
+fn cookie_file(auth: &str) -> Option<String> {
+    let start = auth.find("COOKIEFILE=\"")? + "COOKIEFILE=\"".len();
+    let end = auth[start..].find('"')?;
+
+    Some(auth[start..start + end].replace("\\\\", "\\"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use tokio::net::TcpListener;
+
+    /// fake control port that answers each expected command, in order, with the given reply and
+    /// returns every command it received
+    async fn fake_control_port(
+        replies: Vec<&'static str>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+
+        let task = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut stream = BufReader::new(stream);
+            let mut received = Vec::new();
+
+            for reply in replies {
+                let mut line = String::new();
+                stream.read_line(&mut line).await.unwrap();
+                received.push(line.trim_end().to_string());
+                stream.get_mut().write_all(reply.as_bytes()).await.unwrap();
+            }
+
+            received
+        });
+
+        (address, task)
+    }
+
+    #[test]
+    /// the cookie's path is pulled out of PROTOCOLINFO's AUTH line
+    fn cookie_file_is_parsed_from_auth_line() {
+        assert_eq!(
+            cookie_file("COOKIE,SAFECOOKIE COOKIEFILE=\"/run/tor/control.authcookie\""),
+            Some(String::from("/run/tor/control.authcookie"))
+        );
+        assert_eq!(cookie_file("HASHEDPASSWORD"), None);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a password is sent quoted, followed by the NEWNYM signal
+    async fn new_identity_authenticates_with_password_and_signals_newnym() {
+        let (address, task) = fake_control_port(vec![
+            "250 OK\r\n",
+            "250 OK\r\n",
+            "250 closing connection\r\n",
+        ])
+        .await;
+
+        new_identity(&address, "hunter\"2").await.unwrap();
+
+        assert_eq!(
+            task.await.unwrap(),
+            vec!["AUTHENTICATE \"hunter\\\"2\"", "SIGNAL NEWNYM", "QUIT"]
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// without a password, the authentication cookie advertised by PROTOCOLINFO is sent hex encoded
+    async fn new_identity_authenticates_with_cookie() {
+        let directory = tempfile::tempdir().unwrap();
+        let cookie = directory.path().join("control.authcookie");
+        std::fs::write(&cookie, [0xde, 0xad, 0xbe, 0xef]).unwrap();
+
+        let protocol_info: &'static str = Box::leak(
+            format!(
+                "250-PROTOCOLINFO 1\r\n250-AUTH METHODS=COOKIE,SAFECOOKIE COOKIEFILE=\"{}\"\r\n250-VERSION Tor=\"0.4.7.10\"\r\n250 OK\r\n",
+                cookie.display()
+            )
+            .into_boxed_str(),
+        );
+
+        let (address, task) = fake_control_port(vec![
+            protocol_info,
+            "250 OK\r\n",
+            "250 OK\r\n",
+            "250 closing connection\r\n",
+        ])
+        .await;
+
+        new_identity(&address, "").await.unwrap();
+
+        let received = task.await.unwrap();
+        assert_eq!(received[0], "PROTOCOLINFO 1");
+        assert_eq!(received[1], "AUTHENTICATE deadbeef");
+        assert_eq!(received[2], "SIGNAL NEWNYM");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// errors from the control port are passed along
+    async fn new_identity_reports_refused_authentication() {
+        let (address, _task) = fake_control_port(vec!["515 Authentication failed\r\n"]).await;
+
+        let error = new_identity(&address, "wrong").await.unwrap_err();
+
+        assert!(error.to_string().contains("Authentication failed"));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// requests that don't leave through Tor fail the check
+    async fn check_exit_requires_tor() {
+        let srv = MockServer::start();
+
+        let mock = srv.mock(|when, then| {
+            when.method(GET).path("/api/ip");
+            then.status(200)
+                .body(r#"{"IsTor":false,"IP":"203.0.113.7"}"#);
+        });
+
+        let error = check_exit(&Client::new(), &srv.url("/api/ip"))
+            .await
+            .unwrap_err();
+
+        assert_eq!(mock.hits(), 1);
+        assert!(error.to_string().contains("203.0.113.7"));
+
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/api/ip");
+            then.status(200)
+                .body(r#"{"IsTor":true,"IP":"198.51.100.4"}"#);
+        });
+
+        let ip = check_exit(&Client::new(), &srv.url("/api/ip"))
+            .await
+            .unwrap();
+
+        assert_eq!(ip, "198.51.100.4");
+    }
+}
@@ -0,0 +1,147 @@
+//! optional span instrumentation (`--trace-output`)
+//!
+//! when built with the `trace` feature, the request pipeline, filters, and event handlers open
+//! `tracing` spans as they work, and `--trace-output` exports those spans to a Jaeger agent or an
+//! OpenTelemetry (OTLP) collector. without the feature, the instrumentation compiles away to
+//! nothing and `--trace-output` is refused
+use anyhow::Result;
+use reqwest::Url;
+
+/// name spans are reported under
+#[cfg(feature = "trace")]
+const SERVICE_NAME: &str = "feroxbuster";
+
+/// whether spans were ever exported by this process; the subscriber can only be set once, so
+/// scans started after the exporter was shut down aren't traced
+#[cfg(feature = "trace")]
+static INSTALLED: std::sync::atomic::AtomicBool = std::sync::atomic::AtomicBool::new(false);
+
+/// Where spans are exported to
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TraceEndpoint {
+    /// Jaeger agent listening for udp at the given `host:port` (`jaeger://HOST:PORT`)
+    Jaeger(String),
+
+    /// OTLP collector listening for grpc at the given url (`http://HOST:PORT`)
+    Otlp(String),
+}
+
+/// Parse a `--trace-output` value into the endpoint it names
+pub fn parse_endpoint(output: &str) -> Result<TraceEndpoint, String> {
+    let url = Url::parse(output).map_err(|e| format!("Invalid trace output {}: {}", output, e))?;
+
+    match url.scheme() {
+        "jaeger" => match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => Ok(TraceEndpoint::Jaeger(format!("{}:{}", host, port))),
+            _ => Err(format!(
+                "Invalid trace output {}, expected jaeger://HOST:PORT",
+                output
+            )),
+        },
+        "http" | "https" => Ok(TraceEndpoint::Otlp(output.to_string())),
+        scheme => Err(format!(
+            "Invalid trace output {}, expected jaeger://HOST:PORT or http://HOST:PORT, not {}://",
+            output, scheme
+        )),
+    }
+}
+
+/// Start exporting spans to `output` (--trace-output); does nothing when it's empty
+///
+/// must be called from within the runtime, which runs the exporter
+#[cfg(feature = "trace")]
+pub fn initialize(output: &str) -> Result<()> {
+    use opentelemetry::{
+        sdk::{trace, Resource},
+        KeyValue,
+    };
+    use tracing_subscriber::{layer::SubscriberExt, Registry};
+
+    if output.is_empty() {
+        return Ok(());
+    }
+
+    if INSTALLED.load(std::sync::atomic::Ordering::SeqCst) {
+        log::warn!(
+            "spans were already exported by this process, not exporting to {}",
+            output
+        );
+        return Ok(());
+    }
+
+    let tracer =
+        match parse_endpoint(output).map_err(anyhow::Error::msg)? {
+            TraceEndpoint::Jaeger(agent) => opentelemetry_jaeger::new_pipeline()
+                .with_service_name(SERVICE_NAME)
+                .with_agent_endpoint(agent)
+                .install_batch(opentelemetry::runtime::Tokio)?,
+            TraceEndpoint::Otlp(collector) => opentelemetry_otlp::new_pipeline()
+                .tracing()
+                .with_exporter(
+                    opentelemetry_otlp::new_exporter()
+                        .tonic()
+                        .with_endpoint(collector),
+                )
+                .with_trace_config(trace::config().with_resource(Resource::new(vec![
+                    KeyValue::new("service.name", SERVICE_NAME),
+                ])))
+                .install_batch(opentelemetry::runtime::Tokio)?,
+        };
+
+    let subscriber = Registry::default().with(tracing_opentelemetry::layer().with_tracer(tracer));
+    tracing::subscriber::set_global_default(subscriber)?;
+    INSTALLED.store(true, std::sync::atomic::Ordering::SeqCst);
+
+    log::info!("exporting spans to {}", output);
+    Ok(())
+}
+
+/// Start exporting spans to `output` (--trace-output); does nothing when it's empty
+///
+/// this build doesn't have the `trace` feature, so there are no spans to export
+#[cfg(not(feature = "trace"))]
+pub fn initialize(output: &str) -> Result<()> {
+    if output.is_empty() {
+        return Ok(());
+    }
+
+    anyhow::bail!("--trace-output needs a feroxbuster built with `--features trace`")
+}
+
+/// Send any spans that haven't been exported yet; called once the last scan is shut down
+#[cfg(feature = "trace")]
+pub async fn shutdown(output: &str) {
+    if output.is_empty() {
+        return;
+    }
+
+    // flushing blocks until the exporter is done, which mustn't happen on a runtime thread
+    tokio::task::spawn_blocking(opentelemetry::global::shutdown_tracer_provider)
+        .await
+        .unwrap_or_default();
+}
+
+/// Send any spans that haven't been exported yet; called once the last scan is shut down
+#[cfg(not(feature = "trace"))]
+pub async fn shutdown(_output: &str) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    /// jaeger agents and otlp collectors are told apart by scheme; anything else is refused
+    fn parse_endpoint_recognizes_exporters() {
+        assert_eq!(
+            parse_endpoint("jaeger://127.0.0.1:6831"),
+            Ok(TraceEndpoint::Jaeger("127.0.0.1:6831".to_string()))
+        );
+        assert_eq!(
+            parse_endpoint("http://localhost:4317"),
+            Ok(TraceEndpoint::Otlp("http://localhost:4317".to_string()))
+        );
+        assert!(parse_endpoint("jaeger://127.0.0.1").is_err());
+        assert!(parse_endpoint("udp://127.0.0.1:6831").is_err());
+        assert!(parse_endpoint("127.0.0.1:6831").is_err());
+    }
+}
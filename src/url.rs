@@ -188,6 +188,21 @@ impl FeroxUrl {
         normalized
     }
 
+    /// Canonical form of the target, used to tell whether two targets are the same
+    ///
+    /// the normalized url, with its scheme and host lowercased and a default port (80 for http,
+    /// 443 for https) dropped; http://a, http://a/, and HTTP://A:80/ are all http://a/
+    ///
+    /// targets that can't be parsed are left as normalized
+    pub fn canonical(&self) -> String {
+        let normalized = self.normalize();
+
+        match Url::parse(&normalized) {
+            Ok(url) => url.to_string(),
+            Err(_) => normalized,
+        }
+    }
+
     /// Helper function that determines the current depth of a given url
     ///
     /// Essentially looks at the Url path and determines how many directories are present in the
@@ -305,6 +320,29 @@ mod tests {
         }
     }
 
+    #[test]
+    /// trailing slashes, case, and default ports don't make a target different
+    fn canonical_collapses_equivalent_targets() {
+        let handles = Arc::new(Handles::for_testing(None, None).0);
+
+        for target in &["http://a", "http://a/", "http://a:80/", "HTTP://A:80"] {
+            let url = FeroxUrl::from_string(target, handles.clone());
+            assert_eq!(url.canonical(), "http://a/");
+        }
+
+        let url = FeroxUrl::from_string("https://a:443/api", handles.clone());
+        assert_eq!(url.canonical(), "https://a/api/");
+
+        let url = FeroxUrl::from_string("http://a:8080", handles.clone());
+        assert_eq!(url.canonical(), "http://a:8080/");
+
+        let url = FeroxUrl::from_string("https://a:80", handles.clone());
+        assert_eq!(url.canonical(), "https://a:80/");
+
+        let url = FeroxUrl::from_string("not a url", handles);
+        assert_eq!(url.canonical(), "not a url/");
+    }
+
     #[test]
     /// base url returns 1
     fn depth_base_url_returns_1() {
@@ -1,30 +1,74 @@
 use anyhow::{bail, Context, Result};
 use console::{strip_ansi_codes, style, user_attended};
 use indicatif::ProgressBar;
-use reqwest::{Client, Response, StatusCode, Url};
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode, Url};
 #[cfg(not(target_os = "windows"))]
 use rlimit::{getrlimit, setrlimit, Resource};
 use std::{
+    collections::HashMap,
     fs,
-    io::{self, BufWriter, Write},
+    io::{BufRead, BufReader, BufWriter, Write},
     sync::Arc,
     time::Duration,
-    time::{SystemTime, UNIX_EPOCH},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
-use tokio::sync::mpsc::UnboundedSender;
 
 use crate::{
-    config::OutputLevel,
+    burp,
+    client::{RequestContext, HOST_TIMEOUTS, RATE_LIMITS, ROTATING_HEADERS, TRANSPORT},
+    config::{Configuration, OutputLevel},
     event_handlers::{
-        Command::{self, AddError, AddStatus},
-        Handles,
+        Command::{AddError, AddLatency, AddStatus, ReportError},
+        Handles, StatsSender,
     },
+    login::Session,
+    presets,
     progress::PROGRESS_PRINTER,
-    send_command,
-    statistics::StatError::{Connection, Other, Redirection, Request, Timeout},
+    scan_id, send_command,
+    statistics::{ErrorRecord, StatError},
     traits::FeroxSerialize,
+    url::FeroxUrl,
+    wordlist_cache,
 };
 
+/// Merge targets that are the same once canonicalized (see `FeroxUrl::canonical`), so that
+/// http://a/, http://a, and http://a:80/ are scanned once; the first of them given is kept, and
+/// every target merged into it is reported
+pub fn merge_duplicate_targets(targets: Vec<String>, handles: Arc<Handles>) -> Vec<String> {
+    log::trace!("enter: merge_duplicate_targets({:?})", targets);
+
+    let print = matches!(
+        handles.config.output_level,
+        OutputLevel::Default | OutputLevel::Quiet
+    );
+
+    // canonical form -> index of the target kept for it
+    let mut seen = HashMap::new();
+    let mut unique: Vec<String> = vec![];
+
+    for target in targets {
+        let canonical = FeroxUrl::from_string(&target, handles.clone()).canonical();
+
+        if let Some(&index) = seen.get(&canonical) {
+            let msg = format!("Merged duplicate target {} into {}", target, unique[index]);
+
+            log::warn!("{}", msg);
+
+            if print {
+                ferox_print(&msg, &PROGRESS_PRINTER);
+            }
+
+            continue;
+        }
+
+        seen.insert(canonical, unique.len());
+        unique.push(target);
+    }
+
+    log::trace!("exit: merge_duplicate_targets -> {:?}", unique);
+    unique
+}
+
 /// Given the path to a file, open the file in append mode (create it if it doesn't exist) and
 /// return a reference to the buffered file
 pub fn open_file(filename: &str) -> Result<BufWriter<fs::File>> {
@@ -90,11 +134,35 @@ pub fn ferox_print(msg: &str, bar: &ProgressBar) {
 /// wrapper for make_request used to pass error/response codes to FeroxScans for per-scan stats
 /// tracking of information related to auto-tune/bail
 pub async fn logged_request(url: &Url, handles: Arc<Handles>) -> Result<Response> {
-    let client = &handles.config.client;
-    let level = handles.config.output_level;
-    let tx_stats = handles.stats.tx.clone();
+    logged_request_with_method(url, &Method::GET, None, handles).await
+}
+
+/// same as logged_request, but uses the given http `Method` instead of GET; `word` is the
+/// wordlist word the request was made for, if any
+pub async fn logged_request_with_method(
+    url: &Url,
+    method: &Method,
+    word: Option<&str>,
+    handles: Arc<Handles>,
+) -> Result<Response> {
+    if handles.denied_by_regex(url) {
+        // last line of defense, callers are expected to have checked should_deny_url already
+        bail!("prevented request to {} due to --deny-url-regex", url);
+    }
 
-    let response = make_request(client, url, level, tx_stats).await;
+    let client = handles.config.scan_client();
+    let tx_stats = handles.stats.batched();
+
+    let response = make_request(
+        client,
+        method,
+        url,
+        word,
+        &handles.config,
+        &handles.session,
+        tx_stats,
+    )
+    .await;
 
     let scans = handles.ferox_scans()?;
 
@@ -111,32 +179,194 @@ pub async fn logged_request(url: &Url, handles: Arc<Handles>) -> Result<Response
         Err(e) => {
             log::warn!("err: {:?}", e);
             scans.increment_error(url.as_str());
+
+            if handles.config.error_records {
+                // --error-records; the failure is written out alongside the results
+                if let Some(error) = e.downcast_ref::<reqwest::Error>() {
+                    let category = StatError::from_request_error(error);
+                    let record = ErrorRecord::new(category, method, url, &error.to_string());
+                    handles
+                        .output
+                        .send(ReportError(Box::new(record)))
+                        .unwrap_or_default();
+                }
+            }
+
             bail!(e)
         }
     }
 }
 
-/// Initiate request to the given `Url` using `Client`
+/// Wait until a request may be sent to the given `Url`; every request to a target goes through
+/// here, whether it's part of the scan or one of the probes that follow up on its responses
+///
+/// - --request-limit: errors once the host has been sent every request it may be
+/// - a host that answered with Retry-After, or ran out of X-RateLimit-Remaining, is left alone
+///   until it's ready again
+/// - `feroxbuster recipe`: waits for the recipe's rate_limit, shared by all of its scans
+pub async fn request_gate(context: &RequestContext, url: &Url) -> Result<()> {
+    if !context.request_limits.acquire(url) {
+        bail!(
+            "--request-limit of {} reached for {}",
+            context.request_limits.limit(),
+            url.host_str().unwrap_or_default()
+        );
+    }
+
+    RATE_LIMITS.wait(url).await;
+
+    context.shared_rate_limit.acquire().await;
+
+    TRANSPORT.record_request(url);
+
+    Ok(())
+}
+
+/// Start a request to the given `Url` on a target, with everything that applies to every request
+/// sent to it: the --host-header pin, the --adaptive-timeout, and the --scan-id-header
+pub fn target_request(
+    context: &RequestContext,
+    client: &Client,
+    method: &Method,
+    url: &Url,
+) -> RequestBuilder {
+    // --host-header; the request goes to the ip under the given name
+    let target = context
+        .host_pins
+        .pinned(url)
+        .unwrap_or_else(|| url.to_owned());
+
+    let mut request = client.request(method.clone(), target);
+
+    if let Some(timeout) = HOST_TIMEOUTS.get(url) {
+        // --adaptive-timeout learned how long this host usually takes
+        request = request.timeout(timeout);
+    }
+
+    if let Some((name, value)) = &context.scan_id_header {
+        // --scan-id-header; lets the run be picked out of the server's logs
+        request = request.header(name.clone(), value.clone());
+    }
+
+    request
+}
+
+/// Add the headers that change from one request to the next: templated and rotating -H headers,
+/// and the [login] session; see `placeholders` for what `word` is used for
+pub fn with_user_headers(
+    context: &RequestContext,
+    session: &Session,
+    mut request: RequestBuilder,
+    url: &Url,
+    word: Option<&str>,
+) -> RequestBuilder {
+    for (name, value) in context.templated_headers.values_for(url, word) {
+        // -H headers with {{placeholders}}, expanded for this request
+        request = request.header(name, value);
+    }
+
+    for (name, value) in ROTATING_HEADERS.next_values() {
+        // --rotate-header; replaces a -H header of the same name for this request
+        request = request.header(name, value);
+    }
+
+    for (name, value) in session.headers_for(url) {
+        // [login]; the session's cookie/token replace -H headers of the same name
+        request = request.header(name, value);
+    }
+
+    request
+}
+
+/// Send a request started with `target_request` once `request_gate` allows it, for the requests
+/// that aren't part of the scan itself (probes, logins); these aren't counted in the scan's
+/// statistics
+pub async fn send_to_target(
+    context: &RequestContext,
+    request: RequestBuilder,
+    url: &Url,
+) -> Result<Response> {
+    send_gated(context, request, url, true).await
+}
+
+/// `send_to_target` for requests made with the client that leaves credentials out (--auth-diff)
+pub async fn send_anonymously(
+    context: &RequestContext,
+    request: RequestBuilder,
+    url: &Url,
+) -> Result<Response> {
+    send_gated(context, request, url, false).await
+}
+
+/// Send the given request once `request_gate` allows it, with the request attached to the
+/// response when requests are recorded (see `burp::record`)
+async fn send_gated(
+    context: &RequestContext,
+    request: RequestBuilder,
+    url: &Url,
+    credentials: bool,
+) -> Result<Response> {
+    request_gate(context, url).await?;
+
+    let sent = burp::record(context.recorder.as_ref(), &request, credentials);
+    let mut response = request.send().await?;
+
+    if let Some(sent) = sent {
+        response.extensions_mut().insert(sent);
+    }
+
+    // --host-header; the response is reported against the ip that was requested
+    context.host_pins.unpin(&mut response);
+
+    RATE_LIMITS.learn(url, response.status(), response.headers());
+
+    Ok(response)
+}
+
+/// Initiate a request to the given `Url` using `Client` and the given http `Method`
+///
+/// `word` is the wordlist word the request was made for, if any, which is what `{{word}}`
+/// expands to in -H headers
+#[cfg_attr(feature = "trace", tracing::instrument(skip_all, fields(method = %method, url = %url)))]
 pub async fn make_request(
     client: &Client,
+    method: &Method,
     url: &Url,
-    output_level: OutputLevel,
-    tx_stats: UnboundedSender<Command>,
+    word: Option<&str>,
+    config: &Configuration,
+    session: &Session,
+    tx_stats: impl Into<StatsSender>,
 ) -> Result<Response> {
+    let tx_stats = tx_stats.into();
+    let context = &config.request_context;
+    let output_level = config.output_level;
+
     log::trace!(
-        "enter: make_request(Configuration::Client, {}, {:?}, {:?})",
+        "enter: make_request(Configuration::Client, {}, {}, {:?}, {:?})",
+        method,
         url,
         output_level,
         tx_stats
     );
 
-    match client.get(url.to_owned()).send().await {
+    if let Err(e) = request_gate(context, url).await {
+        log::trace!("exit: make_request -> {}", e);
+        return Err(e);
+    }
+
+    let timer = Instant::now();
+
+    let request = target_request(context, client, method, url);
+    let request = with_user_headers(context, session, request, url, word);
+    let sent = burp::record(context.recorder.as_ref(), &request, true);
+
+    match request.send().await {
         Err(e) => {
             log::trace!("exit: make_request -> {}", e);
 
-            if e.is_timeout() {
-                send_command!(tx_stats, AddError(Timeout));
-            } else if e.is_redirect() {
+            let category = StatError::from_request_error(&e);
+
+            if e.is_redirect() {
                 if let Some(last_redirect) = e.url() {
                     // get where we were headed (last_redirect) and where we came from (url)
                     let fancy_message = format!("{} !=> {}", url, last_redirect);
@@ -155,24 +385,30 @@ pub async fn make_request(
                         create_report_string("UNK", "-1", "-1", "-1", &fancy_message, output_level)
                     };
 
-                    send_command!(tx_stats, AddError(Redirection));
-
                     ferox_print(&report, &PROGRESS_PRINTER)
                 };
-            } else if e.is_connect() {
-                send_command!(tx_stats, AddError(Connection));
-            } else if e.is_request() {
-                send_command!(tx_stats, AddError(Request));
-            } else {
-                send_command!(tx_stats, AddError(Other));
             }
 
-            log::warn!("Error while making request: {}", e);
-            bail!("{}", e)
+            send_command!(tx_stats, AddError(category));
+
+            log::warn!("Error while making request ({}): {}", category.name(), e);
+
+            // the reqwest error is kept, so callers can tell what kind of failure it was
+            bail!(e)
         }
-        Ok(resp) => {
+        Ok(mut resp) => {
+            if let Some(sent) = sent {
+                // --burp-output, --evidence-dir; the request travels with its response
+                resp.extensions_mut().insert(sent);
+            }
+
+            // --host-header; the response is reported against the ip that was requested
+            context.host_pins.unpin(&mut resp);
+
             log::trace!("exit: make_request -> {:?}", resp);
+            send_command!(tx_stats, AddLatency(resp.status(), timer.elapsed()));
             send_command!(tx_stats, AddStatus(resp.status()));
+            RATE_LIMITS.learn(url, resp.status(), resp.headers());
             Ok(resp)
         }
     }
@@ -253,15 +489,12 @@ pub fn set_open_file_limit(limit: u64) -> bool {
     false
 }
 
-/// Given a string and a reference to a locked buffered file, write the contents and flush
-/// the buffer to disk.
-pub fn write_to<T>(
-    value: &T,
-    file: &mut io::BufWriter<fs::File>,
-    convert_to_json: bool,
-) -> Result<()>
+/// Given a string and a reference to a locked buffered file (or any other writer, such as the
+/// in-memory buffer used by the file output handler), write the contents and flush the buffer
+pub fn write_to<T, W>(value: &T, file: &mut W, convert_to_json: bool) -> Result<()>
 where
     T: FeroxSerialize,
+    W: Write,
 {
     // note to future self: adding logging of anything other than error to this function
     // is a bad idea. we call this function while processing records generated by the logger.
@@ -269,7 +502,7 @@ where
     // the second log entry being injected into the first.
 
     let contents = if convert_to_json {
-        value.as_json()?
+        scan_id::stamp(value.as_json()?)
     } else {
         value.as_str()
     };
@@ -288,15 +521,23 @@ where
     Ok(())
 }
 
-/// determines whether or not a given url should be denied based on the user-supplied --dont-scan
-/// flag
+/// determines whether or not a given url should be denied based on the user-supplied
+/// --dont-scan and --deny-url-regex flags
 pub fn should_deny_url(url: &Url, handles: Arc<Handles>) -> Result<bool> {
     log::trace!(
-        "enter: should_deny_url({}, {:?}, {:?})",
+        "enter: should_deny_url({}, {:?}, {:?}, {:?})",
         url.as_str(),
         handles.config.url_denylist,
+        handles.config.deny_url_regex,
         handles.ferox_scans()?
     );
+
+    if handles.denied_by_regex(url) {
+        // unlike --dont-scan, a scan url given by the user takes no precedence over a pattern
+        log::trace!("exit: should_deny_url -> true");
+        return Ok(true);
+    }
+
     // normalization for comparison is to remove the trailing / if one exists, this is done for
     // the given url and any url to which it's compared
     let normed_url = Url::parse(url.to_string().trim_end_matches('/'))?;
@@ -412,12 +653,121 @@ pub fn slugify_filename(url: &str, prefix: &str, suffix: &str) -> String {
     filename
 }
 
+/// seconds since the unix epoch
+pub fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs())
+        .unwrap_or_default()
+}
+
+/// the (year, month, day) in UTC of the given unix timestamp
+pub fn civil_date(timestamp: u64) -> (i64, i64, i64) {
+    // days since the epoch to a civil date; http://howardhinnant.github.io/date_algorithms.html
+    let shifted = (timestamp / 86400) as i64 + 719_468;
+    let era = shifted.div_euclid(146_097);
+    let day_of_era = shifted.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Load the words of the configured wordlist, going through the compiled copy when
+/// --wordlist-cache is used
+///
+/// words of any --preset are appended; when presets are used without a --wordlist, they're the
+/// only words scanned
+pub fn load_wordlist(config: &Configuration) -> Result<Arc<Vec<String>>> {
+    if config.uses_presets_only() {
+        return Ok(Arc::new(presets::preset_words(&config.presets, &[])));
+    }
+
+    let words = if config.wordlist_cache {
+        wordlist_cache::load(&config.wordlist)?
+    } else {
+        get_unique_words_from_wordlist(&config.wordlist)?
+    };
+
+    if config.presets.is_empty() {
+        return Ok(words);
+    }
+
+    let mut combined = words.to_vec();
+    combined.extend(presets::preset_words(&config.presets, &words));
+
+    Ok(Arc::new(combined))
+}
+
+/// Create a HashSet of Strings from the given wordlist then stores it inside an Arc
+pub fn get_unique_words_from_wordlist(path: &str) -> Result<Arc<Vec<String>>> {
+    log::trace!("enter: get_unique_words_from_wordlist({})", path);
+
+    let file = fs::File::open(&path).with_context(|| format!("Could not open {}", path))?;
+
+    let reader = BufReader::new(file);
+
+    let mut words = Vec::new();
+
+    for line in reader.lines() {
+        let result = match line {
+            Ok(read_line) => read_line,
+            Err(_) => continue,
+        };
+
+        if result.starts_with('#') || result.is_empty() {
+            continue;
+        }
+
+        words.push(result);
+    }
+
+    log::trace!(
+        "exit: get_unique_words_from_wordlist -> Arc<wordlist[{} words...]>",
+        words.len()
+    );
+
+    Ok(Arc::new(words))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::Configuration;
     use crate::scan_manager::{FeroxScans, ScanOrder};
 
+    #[test]
+    /// equivalent targets are scanned once, as the first of them given, and order is kept
+    fn merge_duplicate_targets_keeps_first_of_each() {
+        let handles = Arc::new(Handles::for_testing(None, None).0);
+
+        let targets = vec![
+            String::from("http://a"),
+            String::from("https://b/api"),
+            String::from("http://a:80/"),
+            String::from("http://a:8080"),
+            String::from("HTTPS://B:443/api/"),
+            String::from("http://a/"),
+        ];
+
+        assert_eq!(
+            merge_duplicate_targets(targets, handles),
+            vec![
+                String::from("http://a"),
+                String::from("https://b/api"),
+                String::from("http://a:8080"),
+            ]
+        );
+    }
+
     #[test]
     /// set_open_file_limit with a low requested limit succeeds
     fn utils_set_open_file_limit_with_low_requested_limit() {
@@ -669,4 +1019,58 @@ mod tests {
 
         assert!(!should_deny_url(&tested_url, handles).unwrap());
     }
+
+    #[test]
+    /// urls matching --deny-url-regex are denied, even when they're a child of a scanned url
+    fn should_deny_url_blocks_regex_matches() {
+        let scan_url = "https://testdomain.com/";
+
+        let scans = Arc::new(FeroxScans::default());
+        scans.add_directory_scan(scan_url, ScanOrder::Initial);
+
+        let mut config = Configuration::new().unwrap();
+        config.deny_url_regex = vec![String::from("logout"), String::from("/admin/shut")];
+        let config = Arc::new(config);
+
+        let handles = Arc::new(Handles::for_testing(Some(scans), Some(config)).0);
+
+        for denied in &[
+            "https://testdomain.com/logout",
+            "https://testdomain.com/user/logout.php",
+            "https://testdomain.com/admin/shutdown",
+        ] {
+            let tested_url = Url::parse(denied).unwrap();
+            assert!(should_deny_url(&tested_url, handles.clone()).unwrap());
+        }
+
+        let tested_url = Url::parse("https://testdomain.com/admin/users").unwrap();
+        assert!(!should_deny_url(&tested_url, handles).unwrap());
+    }
+
+    #[test]
+    /// presets replace the default wordlist, and are appended to one that was given
+    fn load_wordlist_uses_presets() {
+        let config = Configuration {
+            presets: vec![String::from("api")],
+            ..Default::default()
+        };
+
+        let words = load_wordlist(&config).unwrap();
+        assert_eq!(words[0], "api");
+        assert!(words.contains(&String::from("swagger.json")));
+
+        let tmp = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(tmp.path(), "stuff\napi\n").unwrap();
+
+        let config = Configuration {
+            wordlist: tmp.path().to_string_lossy().to_string(),
+            presets: vec![String::from("api")],
+            ..Default::default()
+        };
+
+        let words = load_wordlist(&config).unwrap();
+        assert_eq!(&words[..2], &["stuff", "api"]);
+        assert_eq!(words.iter().filter(|w| *w == "api").count(), 1);
+        assert!(words.contains(&String::from("swagger.json")));
+    }
 }
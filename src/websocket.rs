@@ -0,0 +1,234 @@
+//! attempt websocket upgrade handshakes against discovered endpoints and report the ones that
+//! accept them (--ws-probe)
+use std::sync::Arc;
+
+use anyhow::Result;
+use openssl::sha::sha1;
+use reqwest::{
+    header::{CONNECTION, UPGRADE},
+    Method, StatusCode, Url,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    event_handlers::Handles,
+    finding::FeroxFinding,
+    response::FeroxResponse,
+    utils::{send_to_target, target_request, with_user_headers},
+};
+
+/// guid appended to the client's key when computing Sec-WebSocket-Accept (rfc 6455, section 4.2.2)
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// subprotocols offered during the handshake; the server picks the one it speaks, if any, which
+/// says a lot about what's behind the endpoint
+const SUBPROTOCOLS: [&str; 9] = [
+    "graphql-transport-ws",
+    "graphql-ws",
+    "wamp.2.json",
+    "mqtt",
+    "v12.stomp",
+    "v11.stomp",
+    "v10.stomp",
+    "xmpp",
+    "soap",
+];
+
+/// Value of Sec-WebSocket-Accept that a server must send back for the given Sec-WebSocket-Key
+fn accept_key(key: &str) -> String {
+    base64::encode(sha1(format!("{}{}", key, HANDSHAKE_GUID).as_bytes()))
+}
+
+/// ws:// or wss:// version of the given http(s) url, as it'd be used by a browser
+fn ws_url(url: &Url) -> String {
+    let mut ws = url.clone();
+
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    ws.set_scheme(scheme).unwrap_or_default();
+
+    ws.to_string()
+}
+
+/// Send a websocket upgrade request to the url of the given response and report it when the
+/// server switches protocols with a valid Sec-WebSocket-Accept
+///
+/// `Ok(None)` is returned when the endpoint was already probed or didn't accept the upgrade
+pub async fn probe(
+    response: &FeroxResponse,
+    handles: Arc<Handles>,
+) -> Result<Option<FeroxFinding>> {
+    log::trace!("enter: probe({}, {:?})", response, handles);
+
+    let url = response.url().clone();
+
+    if handles.denied_by_regex(&url) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    if !handles.first_probe("websocket", url.as_str()) {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    let key = base64::encode(Uuid::new_v4().as_bytes());
+
+    let context = &handles.config.request_context;
+    let request = target_request(context, &handles.config.client, &Method::GET, &url);
+    let request = with_user_headers(context, &handles.session, request, &url, None)
+        .header(CONNECTION, "Upgrade")
+        .header(UPGRADE, "websocket")
+        .header("Sec-WebSocket-Version", "13")
+        .header("Sec-WebSocket-Key", &key)
+        .header("Sec-WebSocket-Protocol", SUBPROTOCOLS.join(", "));
+    let upgrade = send_to_target(context, request, &url).await?;
+    let upgrade = FeroxResponse::from(upgrade, false, 0, handles.config.output_level).await;
+
+    let header = |name: &str| {
+        upgrade
+            .headers()
+            .get(name)
+            .and_then(|value| value.to_str().ok())
+            .map(str::trim)
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    if *upgrade.status() != StatusCode::SWITCHING_PROTOCOLS
+        || header("sec-websocket-accept") != accept_key(&key)
+    {
+        log::trace!("exit: probe -> None");
+        return Ok(None);
+    }
+
+    let subprotocol = header("sec-websocket-protocol");
+    let extensions = header("sec-websocket-extensions");
+
+    let summary = if subprotocol.is_empty() {
+        String::from("[info] websocket endpoint accepts upgrades")
+    } else {
+        format!(
+            "[info] websocket endpoint accepts upgrades (subprotocol: {})",
+            subprotocol
+        )
+    };
+
+    let finding = FeroxFinding::new(
+        "websocket",
+        url.as_str(),
+        &summary,
+        json!({
+            "severity": "info",
+            "ws_url": ws_url(&url),
+            "subprotocol": subprotocol,
+            "extensions": extensions,
+        }),
+    )
+    .with_exchange(&upgrade);
+
+    log::trace!("exit: probe -> {:?}", finding);
+    Ok(Some(finding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Configuration;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    /// fake server that answers a single upgrade request; with `subprotocol`, the handshake is
+    /// accepted using that subprotocol, without it the request is answered with a plain 200
+    async fn fake_ws_server(subprotocol: Option<&'static str>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let address = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buffer = [0; 1024];
+
+            while !request.ends_with(b"\r\n\r\n") {
+                let read = stream.read(&mut buffer).await.unwrap();
+                request.extend_from_slice(&buffer[..read]);
+            }
+
+            let request = String::from_utf8_lossy(&request);
+
+            let key = request
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .find(|(name, _)| name.eq_ignore_ascii_case("sec-websocket-key"))
+                .map(|(_, value)| value.trim())
+                .unwrap();
+
+            let reply = match subprotocol {
+                Some(subprotocol) => format!(
+                    "HTTP/1.1 101 Switching Protocols\r\nUpgrade: websocket\r\nConnection: Upgrade\r\nSec-WebSocket-Accept: {}\r\nSec-WebSocket-Protocol: {}\r\n\r\n",
+                    accept_key(key),
+                    subprotocol
+                ),
+                None => String::from("HTTP/1.1 200 OK\r\nContent-Length: 0\r\n\r\n"),
+            };
+
+            stream.write_all(reply.as_bytes()).await.unwrap();
+        });
+
+        format!("http://{}/socket", address)
+    }
+
+    #[test]
+    /// the accept key matches the example from rfc 6455
+    fn accept_key_matches_rfc_example() {
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    /// http(s) urls are reported as the ws(s) urls a browser would use
+    fn ws_url_swaps_scheme() {
+        let url = Url::parse("https://example.com/live?room=1").unwrap();
+        assert_eq!(ws_url(&url), "wss://example.com/live?room=1");
+
+        let url = Url::parse("http://example.com/socket").unwrap();
+        assert_eq!(ws_url(&url), "ws://example.com/socket");
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// endpoints that switch protocols are reported with the subprotocol they picked, once
+    async fn probe_reports_accepted_upgrade_once() {
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&fake_ws_server(Some("graphql-ws")).await);
+
+        let finding = probe(&response, handles.clone()).await.unwrap().unwrap();
+
+        assert_eq!(finding.category(), "websocket");
+        assert_eq!(finding.details()["subprotocol"], "graphql-ws");
+        assert!(finding.details()["ws_url"]
+            .as_str()
+            .unwrap()
+            .starts_with("ws://127.0.0.1:"));
+
+        assert!(probe(&response, handles).await.unwrap().is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// endpoints that answer the upgrade request like any other request aren't reported
+    async fn probe_ignores_plain_responses() {
+        let config = Arc::new(Configuration::new().unwrap());
+        let handles = Arc::new(Handles::for_testing(None, Some(config)).0);
+
+        let mut response = FeroxResponse::default();
+        response.set_url(&fake_ws_server(None).await);
+
+        assert!(probe(&response, handles).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,255 @@
+//! compile wordlists into a binary cache on first use (--wordlist-cache), so that later runs can
+//! memory-map the already deduplicated words instead of parsing the text file again
+use std::{
+    collections::HashSet,
+    convert::TryInto,
+    fs::{self, create_dir_all, rename, File},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::UNIX_EPOCH,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use memmap2::Mmap;
+
+use crate::utils::get_unique_words_from_wordlist;
+
+/// first bytes of every cache file; the last byte is the version of the format
+const MAGIC: &[u8; 8] = b"FXWLC\0\0\x01";
+
+/// size of the header: magic, source length, source modification time (secs, nanos), word count
+const HEADER_LEN: usize = 8 + 8 + 8 + 4 + 8;
+
+/// Directory in which compiled wordlists are stored
+///
+/// resolves to one of the following
+///   - linux: $XDG_CACHE_HOME/feroxbuster/wordlists or $HOME/.cache/feroxbuster/wordlists
+///   - macOS: $HOME/Library/Caches/feroxbuster/wordlists
+///   - windows: {FOLDERID_LocalAppData}/feroxbuster/wordlists
+pub fn cache_dir() -> Result<PathBuf> {
+    let cache_dir =
+        dirs::cache_dir().ok_or_else(|| anyhow!("Couldn't determine cache directory"))?;
+    Ok(cache_dir.join("feroxbuster").join("wordlists"))
+}
+
+/// Location of the compiled copy of the given wordlist inside of `dir`
+///
+/// the wordlist's full path is folded into the file name, so that wordlists with the same name
+/// in different directories don't share a cache file
+fn cache_path(dir: &Path, wordlist: &Path) -> PathBuf {
+    let slug: String = wordlist
+        .to_string_lossy()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+
+    dir.join(format!("{}.cache", slug.trim_start_matches('_')))
+}
+
+/// size and modification time of the given wordlist, stored in the cache's header so that a
+/// changed wordlist is compiled again
+fn fingerprint(wordlist: &Path) -> Result<(u64, u64, u32)> {
+    let metadata =
+        fs::metadata(wordlist).with_context(|| format!("Could not open {}", wordlist.display()))?;
+
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+
+    Ok((metadata.len(), modified.as_secs(), modified.subsec_nanos()))
+}
+
+/// Load the words of the given wordlist from its compiled copy in the default `cache_dir`,
+/// compiling it first if there isn't an up-to-date copy
+pub fn load(wordlist: &str) -> Result<Arc<Vec<String>>> {
+    load_from(&cache_dir()?, wordlist)
+}
+
+/// Load the words of the given wordlist from its compiled copy in `dir`, compiling it first if
+/// there isn't an up-to-date copy
+///
+/// failing to write the cache isn't fatal; the words are still returned
+pub fn load_from(dir: &Path, wordlist: &str) -> Result<Arc<Vec<String>>> {
+    log::trace!("enter: load_from({:?}, {})", dir, wordlist);
+
+    let source =
+        fs::canonicalize(wordlist).with_context(|| format!("Could not open {}", wordlist))?;
+    let fingerprint = fingerprint(&source)?;
+    let cached = cache_path(dir, &source);
+
+    match read_cache(&cached, fingerprint) {
+        Ok(words) => {
+            log::info!("Loaded {} words from {}", words.len(), cached.display());
+            log::trace!("exit: load_from -> Arc<wordlist[{} words...]>", words.len());
+            return Ok(Arc::new(words));
+        }
+        Err(e) => log::debug!("Compiling {}: {}", wordlist, e),
+    }
+
+    let words = compile(wordlist)?;
+
+    if let Err(e) = write_cache(dir, &cached, fingerprint, &words) {
+        log::warn!("Could not cache {} in {}: {}", wordlist, dir.display(), e);
+    }
+
+    log::trace!("exit: load_from -> Arc<wordlist[{} words...]>", words.len());
+    Ok(Arc::new(words))
+}
+
+/// read the wordlist the same way it's read without the cache, then drop repeated words
+fn compile(wordlist: &str) -> Result<Vec<String>> {
+    let words = get_unique_words_from_wordlist(wordlist)?;
+    let mut seen = HashSet::with_capacity(words.len());
+
+    Ok(words
+        .iter()
+        .filter(|word| seen.insert(word.as_str()))
+        .cloned()
+        .collect())
+}
+
+/// write the compiled words to `cached`
+///
+/// the cache is written next to its final location first and then moved into place, so a
+/// process that has the previous copy mapped never sees it change underneath it
+fn write_cache(
+    dir: &Path,
+    cached: &Path,
+    (length, secs, nanos): (u64, u64, u32),
+    words: &[String],
+) -> Result<()> {
+    create_dir_all(dir)?;
+
+    let body_len: usize = words.iter().map(|word| 4 + word.len()).sum();
+    let mut contents = Vec::with_capacity(HEADER_LEN + body_len);
+
+    contents.extend_from_slice(MAGIC);
+    contents.extend_from_slice(&length.to_le_bytes());
+    contents.extend_from_slice(&secs.to_le_bytes());
+    contents.extend_from_slice(&nanos.to_le_bytes());
+    contents.extend_from_slice(&(words.len() as u64).to_le_bytes());
+
+    for word in words {
+        contents.extend_from_slice(&(word.len() as u32).to_le_bytes());
+        contents.extend_from_slice(word.as_bytes());
+    }
+
+    let partial = cached.with_extension("partial");
+    fs::write(&partial, &contents)?;
+    rename(&partial, cached)?;
+
+    Ok(())
+}
+
+/// memory-map the cache file and read its words, as long as it was compiled from the wordlist's
+/// current contents
+fn read_cache(cached: &Path, fingerprint: (u64, u64, u32)) -> Result<Vec<String>> {
+    let file = File::open(cached)?;
+
+    // safety: cache files are only ever replaced by renaming a new file over them (see
+    // write_cache), never modified in place, so the mapped contents can't change while mapped
+    let map = unsafe { Mmap::map(&file)? };
+
+    parse(&map, fingerprint)
+}
+
+/// read a `u64` from the start of `bytes`
+fn read_u64(bytes: &[u8]) -> Result<u64> {
+    let bytes = bytes
+        .get(..8)
+        .ok_or_else(|| anyhow!("cache is truncated"))?;
+    Ok(u64::from_le_bytes(bytes.try_into()?))
+}
+
+/// read a `u32` from the start of `bytes`
+fn read_u32(bytes: &[u8]) -> Result<u32> {
+    let bytes = bytes
+        .get(..4)
+        .ok_or_else(|| anyhow!("cache is truncated"))?;
+    Ok(u32::from_le_bytes(bytes.try_into()?))
+}
+
+/// parse the contents of a cache file
+fn parse(bytes: &[u8], (length, secs, nanos): (u64, u64, u32)) -> Result<Vec<String>> {
+    if bytes.len() < HEADER_LEN || &bytes[..8] != MAGIC {
+        bail!("not a wordlist cache, or written by another version");
+    }
+
+    if read_u64(&bytes[8..])? != length
+        || read_u64(&bytes[16..])? != secs
+        || read_u32(&bytes[24..])? != nanos
+    {
+        bail!("wordlist changed since it was cached");
+    }
+
+    let count = read_u64(&bytes[28..])? as usize;
+    let mut words = Vec::with_capacity(count.min(bytes.len() / 4));
+    let mut position = HEADER_LEN;
+
+    for _ in 0..count {
+        let len = read_u32(&bytes[position..])? as usize;
+        position += 4;
+
+        let word = bytes
+            .get(position..position + len)
+            .ok_or_else(|| anyhow!("cache is truncated"))?;
+        position += len;
+
+        words.push(String::from_utf8(word.to_vec())?);
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::{NamedTempFile, TempDir};
+
+    #[test]
+    /// the first load compiles the wordlist, later loads read the compiled copy, and changing the
+    /// wordlist compiles it again
+    fn load_compiles_then_reuses_cache() {
+        let dir = TempDir::new().unwrap();
+        let mut wordlist = NamedTempFile::new().unwrap();
+        writeln!(wordlist, "# comment\nadmin\nlogin\n\nadmin\nédition").unwrap();
+        let path = wordlist.path().to_str().unwrap().to_string();
+
+        let words = load_from(dir.path(), &path).unwrap();
+        assert_eq!(*words, vec!["admin", "login", "édition"]);
+
+        let cached = cache_path(dir.path(), &fs::canonicalize(&path).unwrap());
+        assert!(cached.exists());
+
+        let fingerprint = fingerprint(wordlist.path()).unwrap();
+        assert_eq!(read_cache(&cached, fingerprint).unwrap(), *words);
+
+        let words = load_from(dir.path(), &path).unwrap();
+        assert_eq!(*words, vec!["admin", "login", "édition"]);
+
+        writeln!(wordlist, "backup").unwrap();
+        wordlist.flush().unwrap();
+
+        let words = load_from(dir.path(), &path).unwrap();
+        assert_eq!(*words, vec!["admin", "login", "édition", "backup"]);
+    }
+
+    #[test]
+    /// caches that don't match the wordlist, or aren't caches at all, are rejected
+    fn parse_rejects_stale_or_corrupt_caches() {
+        let dir = TempDir::new().unwrap();
+        let cached = dir.path().join("words.cache");
+        let words = vec!["one".to_string(), "two".to_string()];
+
+        write_cache(dir.path(), &cached, (10, 20, 30), &words).unwrap();
+        let contents = fs::read(&cached).unwrap();
+
+        assert_eq!(parse(&contents, (10, 20, 30)).unwrap(), words);
+        assert!(parse(&contents, (11, 20, 30)).is_err());
+        assert!(parse(&contents[..contents.len() - 1], (10, 20, 30)).is_err());
+        assert!(parse(b"one\ntwo\n", (10, 20, 30)).is_err());
+    }
+}
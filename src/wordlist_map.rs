@@ -0,0 +1,179 @@
+//! scan the directories whose path matches a pattern with a wordlist of their own, i.e. an api
+//! wordlist for everything under /api/ (`[[wordlist_map]]` tables in the config file)
+//!
+//! ```toml
+//! wordlist = "/wordlists/common.txt"   # everything else
+//!
+//! [[wordlist_map]]
+//! path = "/api/**"
+//! wordlist = "/wordlists/api-endpoints.txt"
+//! ```
+use std::path::PathBuf;
+
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::wordlists;
+
+/// A path pattern, and the wordlist used for the directories that match it
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+pub struct WordlistMapping {
+    /// pattern matched against the path of each directory; `*` matches within a single segment,
+    /// and `**` any number of segments (`/api/**` matches /api/ and everything below it)
+    pub path: String,
+
+    /// wordlist used for the matching directories; a file, or the name of an installed wordlist
+    pub wordlist: String,
+}
+
+impl WordlistMapping {
+    /// Problems with the values of this mapping, worded like `Configuration::validate`'s
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        if !self.path.starts_with('/') {
+            problems.push(format!(
+                "wordlist_map path {} needs to start with a /",
+                self.path
+            ));
+        }
+
+        if let Err(e) = path_pattern(&self.path) {
+            problems.push(format!("Invalid wordlist_map path {}: {}", self.path, e));
+        }
+
+        if !PathBuf::from(self.resolved_wordlist()).is_file() {
+            problems.push(format!(
+                "Wordlist {} (wordlist_map {}) not found",
+                self.wordlist, self.path
+            ));
+        }
+
+        problems
+    }
+
+    /// The mapping's wordlist on disk; installed wordlists are resolved by name
+    pub fn resolved_wordlist(&self) -> String {
+        match wordlists::resolve(&self.wordlist) {
+            Some(path) => path.to_string_lossy().to_string(),
+            None => self.wordlist.clone(),
+        }
+    }
+
+    /// Whether the given directory path (without its trailing slash) matches the mapping's path
+    fn matches(&self, path: &str) -> bool {
+        path_pattern(&self.path)
+            .map(|pattern| pattern.is_match(path))
+            .unwrap_or(false)
+    }
+}
+
+/// Regex equivalent to the given path pattern; trailing slashes are ignored on both sides
+fn path_pattern(path: &str) -> Result<Regex, regex::Error> {
+    let mut rest = path.trim_end_matches('/');
+    let mut pattern = String::from("^");
+
+    while let Some(c) = rest.chars().next() {
+        if let Some(after) = rest.strip_prefix("/**") {
+            // the directory itself, or anything below it
+            pattern.push_str("(/.*)?");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix("**") {
+            pattern.push_str(".*");
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('*') {
+            pattern.push_str("[^/]*");
+            rest = after;
+        } else {
+            pattern.push_str(&regex::escape(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+
+    pattern.push('$');
+    Regex::new(&pattern)
+}
+
+/// The first mapping whose path matches the directory at `url`; `None` when the directory is
+/// scanned with --wordlist
+pub fn mapping_for<'a>(mappings: &'a [WordlistMapping], url: &str) -> Option<&'a WordlistMapping> {
+    if mappings.is_empty() {
+        return None;
+    }
+
+    let parsed = Url::parse(url).ok()?;
+    let path = parsed.path().trim_end_matches('/');
+
+    mappings.iter().find(|mapping| mapping.matches(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// mapping of the given path to a wordlist named after it
+    fn mapping(path: &str) -> WordlistMapping {
+        WordlistMapping {
+            path: path.to_string(),
+            wordlist: format!("{}.txt", path.trim_matches(|c| c == '/' || c == '*')),
+        }
+    }
+
+    #[test]
+    /// ** spans segments (and matches the directory itself), * stays within one
+    fn path_pattern_matches_globs() {
+        let api = path_pattern("/api/**").unwrap();
+        assert!(api.is_match("/api"));
+        assert!(api.is_match("/api/v1/users"));
+        assert!(!api.is_match("/apis"));
+        assert!(!api.is_match(""));
+
+        let admin = path_pattern("/*/admin/").unwrap();
+        assert!(admin.is_match("/site/admin"));
+        assert!(!admin.is_match("/site/one/admin"));
+
+        let everything = path_pattern("/**").unwrap();
+        assert!(everything.is_match(""));
+        assert!(everything.is_match("/anything/at/all"));
+
+        let literal = path_pattern("/v1.0").unwrap();
+        assert!(literal.is_match("/v1.0"));
+        assert!(!literal.is_match("/v1x0"));
+    }
+
+    #[test]
+    /// the first matching mapping wins; directories nothing matches use --wordlist
+    fn mapping_for_picks_first_match() {
+        let mappings = vec![mapping("/api/internal/**"), mapping("/api/**")];
+
+        let found = |url| mapping_for(&mappings, url).map(|m| m.wordlist.as_str());
+
+        assert_eq!(
+            found("http://localhost/api/internal/"),
+            Some("api/internal.txt")
+        );
+        assert_eq!(found("http://localhost/api/v2/"), Some("api.txt"));
+        assert_eq!(found("http://localhost/api"), Some("api.txt"));
+        assert_eq!(found("http://localhost/"), None);
+        assert_eq!(found("http://localhost/static/"), None);
+    }
+
+    #[test]
+    /// relative paths and missing wordlists are reported
+    fn problems_reports_bad_values() {
+        let wordlist = tempfile::NamedTempFile::new().unwrap();
+
+        let good = WordlistMapping {
+            path: String::from("/api/**"),
+            wordlist: wordlist.path().to_string_lossy().to_string(),
+        };
+        assert!(good.problems().is_empty());
+
+        let bad = WordlistMapping {
+            path: String::from("api/**"),
+            wordlist: String::from("/does/not/exist.txt"),
+        };
+        assert_eq!(bad.problems().len(), 2);
+    }
+}
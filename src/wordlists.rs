@@ -0,0 +1,332 @@
+//! download curated wordlists into a managed data directory, so they can be used by short name,
+//! i.e. `feroxbuster wordlists install seclists-common` followed by `-w seclists-common`
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, remove_file, rename, write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::ArgMatches;
+use reqwest::{Client, Url};
+
+use crate::{client, DEFAULT_WORDLIST, VERSION};
+
+/// A wordlist that can be installed by name
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CatalogEntry {
+    /// short name used to install/reference the wordlist
+    pub name: &'static str,
+
+    /// where the wordlist is downloaded from
+    pub url: &'static str,
+
+    /// what's in the wordlist
+    pub description: &'static str,
+}
+
+/// wordlists that can be installed with `feroxbuster wordlists install NAME`
+pub const CATALOG: [CatalogEntry; 7] = [
+    CatalogEntry {
+        name: "seclists-common",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/common.txt",
+        description: "SecLists' common.txt; small, general purpose (~4.7k words)",
+    },
+    CatalogEntry {
+        name: "seclists-quickhits",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/quickhits.txt",
+        description: "SecLists' quickhits.txt; commonly exposed sensitive files (~2.5k words)",
+    },
+    CatalogEntry {
+        name: "seclists-raft-medium-directories",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/raft-medium-directories.txt",
+        description: "SecLists' raft-medium-directories.txt; feroxbuster's default (~30k words)",
+    },
+    CatalogEntry {
+        name: "seclists-raft-medium-files",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/raft-medium-files.txt",
+        description: "SecLists' raft-medium-files.txt; files with extensions (~17k words)",
+    },
+    CatalogEntry {
+        name: "seclists-big",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/big.txt",
+        description: "SecLists' big.txt; larger, general purpose (~20k words)",
+    },
+    CatalogEntry {
+        name: "seclists-api-endpoints",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/api/api-endpoints.txt",
+        description: "SecLists' api-endpoints.txt; common REST api paths",
+    },
+    CatalogEntry {
+        name: "seclists-directory-list-2.3-medium",
+        url: "https://raw.githubusercontent.com/danielmiessler/SecLists/master/Discovery/Web-Content/directory-list-2.3-medium.txt",
+        description: "SecLists' directory-list-2.3-medium.txt; DirBuster's classic (~220k words)",
+    },
+];
+
+/// name of the managed wordlist that's used in place of `DEFAULT_WORDLIST` when the default
+/// isn't on disk
+const DEFAULT_MANAGED_WORDLIST: &str = "seclists-raft-medium-directories";
+
+/// Directory in which managed wordlists are stored
+///
+/// resolves to one of the following
+///   - linux: $XDG_DATA_HOME/feroxbuster/wordlists or $HOME/.local/share/feroxbuster/wordlists
+///   - macOS: $HOME/Library/Application Support/feroxbuster/wordlists
+///   - windows: {FOLDERID_RoamingAppData}/feroxbuster/wordlists
+pub fn data_dir() -> Result<PathBuf> {
+    let data_dir = dirs::data_dir().ok_or_else(|| anyhow!("Couldn't determine data directory"))?;
+    Ok(data_dir.join("feroxbuster").join("wordlists"))
+}
+
+/// Return the catalog entry with the given name
+pub fn catalog_entry(name: &str) -> Option<&'static CatalogEntry> {
+    CATALOG.iter().find(|entry| entry.name == name)
+}
+
+/// Location of the wordlist with the given name inside of `dir`
+fn installed_path(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("{}.txt", name))
+}
+
+/// Return the managed wordlist with the given name inside of `dir`, if it's installed
+fn resolve_in(dir: &Path, name: &str) -> Option<PathBuf> {
+    if name.is_empty() || name.contains(|c: char| c == '/' || c == '\\') {
+        // paths are never short names
+        return None;
+    }
+
+    let path = installed_path(dir, name);
+
+    if path.is_file() {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Return the installed wordlist with the given name; unlike `resolve`, files on disk are never
+/// considered
+pub fn installed(name: &str) -> Option<PathBuf> {
+    resolve_in(&data_dir().ok()?, name)
+}
+
+/// Given the value of `-w|--wordlist`, return the installed wordlist it refers to
+///
+/// `None` is returned when `wordlist` is a file on disk or doesn't name an installed wordlist.
+/// When the default wordlist isn't on disk, the managed copy of it is used, if installed.
+pub fn resolve(wordlist: &str) -> Option<PathBuf> {
+    if Path::new(wordlist).is_file() {
+        return None;
+    }
+
+    let name = if wordlist == DEFAULT_WORDLIST {
+        DEFAULT_MANAGED_WORDLIST
+    } else {
+        wordlist
+    };
+
+    resolve_in(&data_dir().ok()?, name)
+}
+
+/// Download the contents of `url` to `destination`, returning the number of bytes written
+///
+/// the download is written next to `destination` first, so a failed download never leaves a
+/// partial wordlist behind
+async fn download(client: &Client, url: Url, destination: &Path) -> Result<usize> {
+    log::trace!("enter: download({}, {})", url, destination.display());
+
+    let response = client.get(url.clone()).send().await?;
+
+    if !response.status().is_success() {
+        bail!("Could not download {}: {}", url, response.status());
+    }
+
+    let body = response.bytes().await?;
+
+    if let Some(parent) = destination.parent() {
+        create_dir_all(parent).with_context(|| format!("Could not create {}", parent.display()))?;
+    }
+
+    let partial = destination.with_extension("part");
+
+    write(&partial, &body).with_context(|| format!("Could not write {}", partial.display()))?;
+    rename(&partial, destination)
+        .with_context(|| format!("Could not write {}", destination.display()))?;
+
+    log::trace!("exit: download -> {}", body.len());
+    Ok(body.len())
+}
+
+/// Download the catalog's wordlist with the given name into `dir`
+pub async fn install(client: &Client, dir: &Path, name: &str) -> Result<PathBuf> {
+    let entry = catalog_entry(name).ok_or_else(|| {
+        anyhow!(
+            "Unknown wordlist {}; see `feroxbuster wordlists list` for the available names",
+            name
+        )
+    })?;
+
+    let destination = installed_path(dir, entry.name);
+
+    download(client, Url::parse(entry.url)?, &destination).await?;
+
+    Ok(destination)
+}
+
+/// Remove the installed wordlist with the given name from `dir`
+pub fn remove(dir: &Path, name: &str) -> Result<()> {
+    let path = resolve_in(dir, name).ok_or_else(|| anyhow!("{} isn't installed", name))?;
+
+    remove_file(&path).with_context(|| format!("Could not remove {}", path.display()))
+}
+
+/// Run the `feroxbuster wordlists` subcommand given by `subcommand`; `args` are the options given
+/// before it (--proxy, --insecure, and --timeout are used for downloads)
+pub fn run(args: &ArgMatches, subcommand: &ArgMatches) -> Result<()> {
+    let dir = data_dir()?;
+
+    match subcommand.subcommand() {
+        ("install", Some(install_args)) => {
+            let names: Vec<&str> = install_args
+                .values_of("name")
+                .into_iter()
+                .flatten()
+                .collect();
+
+            let timeout = args
+                .value_of("timeout")
+                .and_then(|timeout| timeout.parse().ok())
+                .unwrap_or(30);
+
+            let client = client::initialize(
+                timeout,
+                &format!("feroxbuster/{}", VERSION),
+                true,
+                args.is_present("insecure"),
+                &HashMap::new(),
+                args.value_of("proxy"),
+                &Arc::default(),
+            )?;
+
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?;
+
+            for name in names {
+                let path = runtime.block_on(install(&client, &dir, name))?;
+                println!("Installed {} to {}", name, path.display());
+            }
+        }
+        ("remove", Some(remove_args)) => {
+            for name in remove_args.values_of("name").into_iter().flatten() {
+                remove(&dir, name)?;
+                println!("Removed {}", name);
+            }
+        }
+        _ => {
+            // list
+            println!("Wordlists are installed to {}\n", dir.display());
+
+            for entry in CATALOG.iter() {
+                let installed = if resolve_in(&dir, entry.name).is_some() {
+                    "[installed]"
+                } else {
+                    ""
+                };
+
+                println!("  {:<36} {} {}", entry.name, entry.description, installed);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+    use std::fs::read_to_string;
+    use tempfile::TempDir;
+
+    #[test]
+    /// every catalog entry has a unique name and a valid url
+    fn catalog_entries_are_unique_and_valid() {
+        for entry in CATALOG.iter() {
+            assert!(Url::parse(entry.url).is_ok());
+            assert_eq!(CATALOG.iter().filter(|e| e.name == entry.name).count(), 1);
+        }
+
+        assert!(catalog_entry(DEFAULT_MANAGED_WORDLIST).is_some());
+        assert!(catalog_entry("rockyou").is_none());
+    }
+
+    #[test]
+    /// only installed short names are resolved; paths never are
+    fn resolve_in_finds_installed_wordlists() {
+        let dir = TempDir::new().unwrap();
+        write(installed_path(dir.path(), "seclists-common"), "admin\n").unwrap();
+
+        assert_eq!(
+            resolve_in(dir.path(), "seclists-common"),
+            Some(dir.path().join("seclists-common.txt"))
+        );
+        assert!(resolve_in(dir.path(), "seclists-big").is_none());
+        assert!(resolve_in(dir.path(), "../seclists-common").is_none());
+        assert!(resolve_in(dir.path(), "").is_none());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a download is written to its destination, creating the directory as needed
+    async fn download_writes_wordlist() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/common.txt");
+            then.status(200).body("admin\nlogin\n");
+        });
+
+        let dir = TempDir::new().unwrap();
+        let destination = installed_path(&dir.path().join("wordlists"), "seclists-common");
+        let url = Url::parse(&srv.url("/common.txt")).unwrap();
+
+        let written = download(&Client::new(), url, &destination).await.unwrap();
+
+        assert_eq!(written, 12);
+        assert_eq!(read_to_string(&destination).unwrap(), "admin\nlogin\n");
+        assert!(!destination.with_extension("part").exists());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// a failed download doesn't leave anything behind
+    async fn download_errors_on_bad_status() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/missing.txt");
+            then.status(404);
+        });
+
+        let dir = TempDir::new().unwrap();
+        let destination = installed_path(dir.path(), "missing");
+        let url = Url::parse(&srv.url("/missing.txt")).unwrap();
+
+        assert!(download(&Client::new(), url, &destination).await.is_err());
+        assert!(!destination.exists());
+    }
+
+    #[test]
+    /// removing a wordlist deletes its file; removing one that isn't installed is an error
+    fn remove_deletes_installed_wordlist() {
+        let dir = TempDir::new().unwrap();
+        let path = installed_path(dir.path(), "seclists-common");
+        write(&path, "admin\n").unwrap();
+
+        remove(dir.path(), "seclists-common").unwrap();
+
+        assert!(!path.exists());
+        assert!(remove(dir.path(), "seclists-common").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! push discovered urls into a running OWASP ZAP instance through its api (--zap-url)
+use std::{collections::HashSet, time::Duration};
+
+use anyhow::{anyhow, bail, Context, Result};
+use reqwest::{Client, Url};
+use serde_json::Value;
+use tokio::{
+    sync::mpsc::{self, UnboundedSender},
+    task::JoinHandle,
+};
+
+use crate::config::Configuration;
+
+/// header ZAP reads the api key from
+const API_KEY_HEADER: &str = "X-ZAP-API-Key";
+
+/// Thin client for the parts of ZAP's JSON api used to keep it in sync with a scan
+#[derive(Debug)]
+pub struct ZapClient {
+    /// client used to talk to ZAP (never proxied, since it'd usually be proxied through ZAP)
+    client: Client,
+
+    /// base url of the ZAP api, i.e. http://127.0.0.1:8080
+    base: Url,
+
+    /// api key configured in ZAP, if any
+    api_key: String,
+
+    /// whether an active scan is started for every url that's pushed
+    active_scan: bool,
+}
+
+/// implementation of ZapClient
+impl ZapClient {
+    /// create a client for the ZAP instance described by the given `Configuration`
+    pub fn new(config: &Configuration) -> Result<Self> {
+        let base = Url::parse(&config.zap_url)
+            .with_context(|| format!("Invalid ZAP url {}", config.zap_url))?;
+
+        let client = Client::builder()
+            .timeout(Duration::from_secs(config.timeout))
+            .danger_accept_invalid_certs(config.insecure)
+            .no_proxy()
+            .build()?;
+
+        Ok(Self {
+            client,
+            base,
+            api_key: config.zap_api_key.clone(),
+            active_scan: config.zap_active_scan,
+        })
+    }
+
+    /// call the given api endpoint (`component/type/name/`) with the given parameters
+    async fn call(&self, endpoint: &str, params: &[(&str, &str)]) -> Result<Value> {
+        let url = self.base.join(&format!("JSON/{}", endpoint))?;
+
+        let mut request = self.client.get(url).query(params);
+
+        if !self.api_key.is_empty() {
+            request = request.header(API_KEY_HEADER, &self.api_key);
+        }
+
+        let response = request.send().await?;
+        let status = response.status();
+        let text = response.text().await?;
+        let body: Value = serde_json::from_str(&text).unwrap_or_default();
+
+        if !status.is_success() {
+            // errors look like {"code": "bad_api_key", "message": "..."}
+            let message = body["message"].as_str().unwrap_or_else(|| status.as_str());
+            bail!("ZAP responded to {} with {}", endpoint, message);
+        }
+
+        Ok(body)
+    }
+
+    /// version of the ZAP instance, used to make sure it's reachable before anything is pushed
+    pub async fn version(&self) -> Result<String> {
+        let body = self.call("core/view/version/", &[]).await?;
+
+        body["version"]
+            .as_str()
+            .map(String::from)
+            .ok_or_else(|| anyhow!("Unexpected response from ZAP: {}", body))
+    }
+
+    /// have ZAP request the given url, which adds it to the sites tree, and start an active scan
+    /// against it when --zap-active-scan is used
+    pub async fn push(&self, url: &str) -> Result<()> {
+        self.call(
+            "core/action/accessUrl/",
+            &[("url", url), ("followRedirects", "false")],
+        )
+        .await?;
+
+        if self.active_scan {
+            self.call("ascan/action/scan/", &[("url", url), ("recurse", "false")])
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Start a task that pushes every url sent over the returned transmitter to ZAP, one at a time,
+/// until the transmitter is dropped; each url is only pushed once
+pub fn start(config: &Configuration) -> Result<(UnboundedSender<String>, JoinHandle<()>)> {
+    let zap = ZapClient::new(config)?;
+    let (tx, mut rx) = mpsc::unbounded_channel::<String>();
+
+    let task = tokio::spawn(async move {
+        let reachable = match zap.version().await {
+            Ok(version) => {
+                log::info!("Sending results to ZAP {} at {}", version, zap.base);
+                true
+            }
+            Err(e) => {
+                log::warn!(
+                    "Could not reach ZAP at {}, results won't be sent: {}",
+                    zap.base,
+                    e
+                );
+                false
+            }
+        };
+
+        let mut pushed = HashSet::new();
+
+        while let Some(url) = rx.recv().await {
+            if !reachable || !pushed.insert(url.clone()) {
+                continue;
+            }
+
+            if let Err(e) = zap.push(&url).await {
+                log::warn!("Could not send {} to ZAP: {}", url, e);
+            }
+        }
+    });
+
+    Ok((tx, task))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use httpmock::{Method::GET, MockServer};
+
+    /// ZapClient pointed at the given mock server
+    fn zap_for(srv: &MockServer, active_scan: bool) -> ZapClient {
+        let config = Configuration {
+            zap_url: srv.url("/"),
+            zap_api_key: String::from("changeme"),
+            zap_active_scan: active_scan,
+            ..Default::default()
+        };
+
+        ZapClient::new(&config).unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// pushed urls are accessed through ZAP with the api key, and actively scanned when asked
+    async fn zap_push_accesses_and_scans_url() {
+        let srv = MockServer::start();
+
+        let access = srv.mock(|when, then| {
+            when.method(GET)
+                .path("/JSON/core/action/accessUrl/")
+                .query_param("url", "http://target/admin")
+                .query_param("followRedirects", "false")
+                .header(API_KEY_HEADER, "changeme");
+            then.status(200).body(r#"{"Result":"OK"}"#);
+        });
+
+        let scan = srv.mock(|when, then| {
+            when.method(GET)
+                .path("/JSON/ascan/action/scan/")
+                .query_param("url", "http://target/admin")
+                .header(API_KEY_HEADER, "changeme");
+            then.status(200).body(r#"{"scan":"0"}"#);
+        });
+
+        zap_for(&srv, false)
+            .push("http://target/admin")
+            .await
+            .unwrap();
+
+        assert_eq!(access.hits(), 1);
+        assert_eq!(scan.hits(), 0);
+
+        zap_for(&srv, true)
+            .push("http://target/admin")
+            .await
+            .unwrap();
+
+        assert_eq!(access.hits(), 2);
+        assert_eq!(scan.hits(), 1);
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+    /// errors reported by ZAP are passed along
+    async fn zap_reports_api_errors() {
+        let srv = MockServer::start();
+
+        srv.mock(|when, then| {
+            when.method(GET).path("/JSON/core/view/version/");
+            then.status(400)
+                .body(r#"{"code":"bad_api_key","message":"Bad API Key"}"#);
+        });
+
+        let error = zap_for(&srv, false).version().await.unwrap_err();
+
+        assert!(error.to_string().contains("Bad API Key"));
+    }
+}
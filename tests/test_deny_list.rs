@@ -63,9 +63,10 @@ fn deny_list_works_during_extraction() {
         .arg("--extract-links")
         .arg("--dont-scan")
         .arg(srv.url("/homepage/"))
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains("/homepage/assets/img/icons/handshake.svg").not()),
@@ -119,9 +120,10 @@ fn deny_list_works_during_recursion() {
         .arg("1")
         .arg("--dont-scan")
         .arg(srv.url("/js/dev"))
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::is_match("301.*js")
             .unwrap()
             .and(predicate::str::is_match("301.*js/prod").unwrap())
@@ -190,9 +192,10 @@ fn deny_list_works_during_recursion_with_inverted_parents() {
         .arg("-vvvv")
         .arg("--dont-scan")
         .arg(srv.url("/"))
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::is_match("301.*js")
             .unwrap()
             .and(predicate::str::is_match("301.*js/prod").unwrap())
@@ -210,3 +213,49 @@ fn deny_list_works_during_recursion_with_inverted_parents() {
 
     teardown_tmp_directory(tmp_dir);
 }
+
+#[test]
+/// test that --deny-url-regex prevents requests of matching urls from the wordlist and extraction
+fn deny_url_regex_works_during_scan_and_extraction() {
+    let srv = MockServer::start();
+    let words = ["LICENSE".to_string(), "logout".to_string()];
+    let (tmp_dir, file) = setup_tmp_directory(&words, "wordlist").unwrap();
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/LICENSE");
+        then.status(200).body(&srv.url("'/account/logout.php'"));
+    });
+
+    let logout_mock = srv.mock(|when, then| {
+        when.method(GET).path("/logout");
+        then.status(200);
+    });
+
+    let extracted_mock = srv.mock(|when, then| {
+        when.method(GET).path("/account/logout.php");
+        then.status(200);
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--extract-links")
+        .arg("--deny-url-regex")
+        .arg("logout")
+        .output()
+        .unwrap();
+
+    cmd.assert().code(1).stdout(
+        predicate::str::contains("/LICENSE")
+            .and(predicate::str::contains("200"))
+            .and(predicate::str::contains("logout").not()),
+    );
+
+    assert_eq!(mock.hits(), 1);
+    assert_eq!(logout_mock.hits(), 0);
+    assert_eq!(extracted_mock.hits(), 0);
+    teardown_tmp_directory(tmp_dir);
+}
@@ -32,9 +32,10 @@ fn extractor_finds_absolute_url() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--wordlist")
         .arg(file.as_os_str())
         .arg("--extract-links")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains(
@@ -68,9 +69,10 @@ fn extractor_finds_absolute_url_to_different_domain() -> Result<(), Box<dyn std:
         .arg("--wordlist")
         .arg(file.as_os_str())
         .arg("--extract-links")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains(
@@ -84,6 +86,54 @@ fn extractor_finds_absolute_url_to_different_domain() -> Result<(), Box<dyn std:
     Ok(())
 }
 
+#[test]
+/// send a request to a page that contains an absolute link to another host that's in --scope,
+/// scanner should follow
+fn extractor_follows_absolute_url_to_host_in_scope() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/LICENSE");
+        then.status(200).body(format!(
+            "\"http://localhost:{}/homepage/assets/img/icons/handshake.svg\"",
+            srv.port()
+        ));
+    });
+
+    let mock_two = srv.mock(|when, then| {
+        when.method(GET)
+            .path("/homepage/assets/img/icons/handshake.svg");
+        then.status(200);
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--extract-links")
+        .arg("--scope")
+        .arg("localhost")
+        .output()
+        .unwrap();
+
+    let in_scope = format!(
+        "http://localhost:{}/homepage/assets/img/icons/handshake.svg",
+        srv.port()
+    );
+
+    cmd.assert()
+        .code(1)
+        .stdout(predicate::str::contains("/LICENSE").and(predicate::str::contains(in_scope)));
+
+    assert_eq!(mock.hits(), 1);
+    assert_eq!(mock_two.hits(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
 #[test]
 /// send a request to a page that contains a relative link, should follow
 fn extractor_finds_relative_url() -> Result<(), Box<dyn std::error::Error>> {
@@ -109,9 +159,10 @@ fn extractor_finds_relative_url() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--wordlist")
         .arg(file.as_os_str())
         .arg("--extract-links")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains(
@@ -158,9 +209,10 @@ fn extractor_finds_same_relative_url_twice() {
         .arg("--wordlist")
         .arg(file.as_os_str())
         .arg("--extract-links")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             // .count(1) asserts that we only see the endpoint reported once, even though there
@@ -203,9 +255,10 @@ fn extractor_finds_filtered_content() -> Result<(), Box<dyn std::error::Error>>
         .arg("--extract-links")
         .arg("--filter-size")
         .arg("18")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains(
@@ -274,9 +327,10 @@ fn extractor_finds_robots_txt_links_and_displays_files_or_scans_directories() {
         .arg(file.as_os_str())
         .arg("--extract-links")
         .arg("-vvvv")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE") // 2 directories contain LICENSE
             .count(2)
             .and(predicate::str::contains("18c"))
@@ -349,9 +403,10 @@ fn extractor_finds_robots_txt_links_and_displays_files_non_recursive() {
         .arg(file.as_os_str())
         .arg("--extract-links")
         .arg("--no-recursion")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("18c"))
             .and(predicate::str::contains("/misc/stupidfile.php"))
@@ -402,9 +457,10 @@ fn extractor_recurses_into_403_directories() -> Result<(), Box<dyn std::error::E
         .arg("--extract-links")
         .arg("--depth") // need to go past default 4 directories
         .arg("0")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .count(2)
             .and(predicate::str::contains("1w")) // link in /LICENSE
@@ -33,9 +33,10 @@ fn filters_status_code_should_filter_response() {
         .arg("-vvvv")
         .arg("--filter-status")
         .arg("302")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .not()
             .and(predicate::str::contains("302"))
@@ -79,9 +80,10 @@ fn filters_lines_should_filter_response() {
         .arg(file.as_os_str())
         .arg("--filter-lines")
         .arg("2")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("302"))
             .and(predicate::str::contains("14"))
@@ -125,9 +127,10 @@ fn filters_words_should_filter_response() {
         .arg(file.as_os_str())
         .arg("--filter-words")
         .arg("13")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("302"))
             .and(predicate::str::contains("14"))
@@ -171,9 +174,10 @@ fn filters_size_should_filter_response() {
         .arg(file.as_os_str())
         .arg("--filter-size")
         .arg("56")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("302"))
             .and(predicate::str::contains("14"))
@@ -230,9 +234,10 @@ fn filters_similar_should_filter_response() {
         .arg(file.as_os_str())
         .arg("--filter-similar-to")
         .arg(srv.url("/canary"))
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICfdafdsafdsafadsENSE")
             .and(predicate::str::contains("302"))
             .and(predicate::str::contains("14c"))
@@ -19,7 +19,7 @@ fn test_single_target_cannot_connect() -> Result<(), Box<dyn std::error::Error>>
         .arg("--wordlist")
         .arg(file.as_os_str())
         .assert()
-        .success()
+        .code(3)
         .stdout(
             predicate::str::contains("Could not connect to http://fjdksafjkdsajfkdsajkfdsajkfsdjkdsfdsafdsafdsajkr3l2ajfdskafdsjk, skipping...", )
         );
@@ -45,7 +45,7 @@ fn test_two_targets_cannot_connect() -> Result<(), Box<dyn std::error::Error>> {
         .pipe_stdin(file)
         .unwrap()
         .assert()
-        .success()
+        .code(3)
         .stdout(
             predicate::str::contains("Could not connect to http://fjdksafjkdsajfkdsajkfdsajkfsdjkdsfdsafdsafdsajkr3l2ajfdskafdsjk, skipping...", )
         );
@@ -78,7 +78,7 @@ fn test_one_good_and_one_bad_target_scan_succeeds() -> Result<(), Box<dyn std::e
         .pipe_stdin(file)
         .unwrap()
         .assert()
-        .success()
+        .code(1)
         .stdout(
             predicate::str::contains("/LICENSE")
                 .and(predicate::str::contains("200"))
@@ -90,6 +90,57 @@ fn test_one_good_and_one_bad_target_scan_succeeds() -> Result<(), Box<dyn std::e
     Ok(())
 }
 
+#[test]
+/// test pipes two live targets and a dead one to the scanner, expected result is that the dead
+/// target is skipped with a summary, and both live targets are scanned
+fn test_dead_targets_are_summarized() -> Result<(), Box<dyn std::error::Error>> {
+    let fast = MockServer::start();
+    let slow = MockServer::start();
+
+    let not_real =
+        String::from("http://fjdksafjkdsajfkdsajkfdsajkfsdjkdsfdsafdsafdsajkr3l2ajfdskafdsjk");
+    let targets = vec![slow.url("/"), not_real, fast.url("/")];
+    let (targets_dir, targets_file) = setup_tmp_directory(&targets, "targets")?;
+    let (words_dir, words_file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    slow.mock(|when, then| {
+        when.method(GET).path("/");
+        then.status(200)
+            .delay(std::time::Duration::from_millis(500));
+    });
+
+    let fast_mock = fast.mock(|when, then| {
+        when.method(GET).path("/LICENSE");
+        then.status(200).body("this is a test");
+    });
+
+    let slow_mock = slow.mock(|when, then| {
+        when.method(GET).path("/LICENSE");
+        then.status(200).body("this is a test");
+    });
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--stdin")
+        .arg("--wordlist")
+        .arg(words_file.as_os_str())
+        .pipe_stdin(targets_file)
+        .unwrap()
+        .assert()
+        .code(1)
+        .stdout(
+            predicate::str::contains("Could not connect to http://fjdksafjkdsajfkdsajkfdsajkfsdjkdsfdsafdsafdsajkr3l2ajfdskafdsjk, skipping...")
+                .and(predicate::str::contains("Skipped 1 of 3 targets: 1 didn't resolve, 0 didn't respond")),
+        );
+
+    assert_eq!(fast_mock.hits(), 1);
+    assert_eq!(slow_mock.hits(), 1);
+
+    teardown_tmp_directory(targets_dir);
+    teardown_tmp_directory(words_dir);
+    Ok(())
+}
+
 #[test]
 /// test passes one target with SSL issues via -u to the scanner, expected result is that the
 /// scanner dies and prints an SSL specific error message
@@ -103,7 +154,7 @@ fn test_single_target_cannot_connect_due_to_ssl_errors() -> Result<(), Box<dyn s
         .arg("--wordlist")
         .arg(file.as_os_str())
         .assert()
-        .success()
+        .code(3)
         .stdout(
             predicate::str::contains("Could not connect to https://expired.badssl.com due to SSL errors (run with -k to ignore), skipping...", )
         );
@@ -140,7 +191,7 @@ fn test_two_good_targets_scan_succeeds() -> Result<(), Box<dyn std::error::Error
         .pipe_stdin(file)
         .unwrap()
         .assert()
-        .success()
+        .code(1)
         .stdout(
             predicate::str::contains("/LICENSE")
                 .and(predicate::str::contains("200"))
@@ -24,7 +24,7 @@ fn main_use_root_owned_file_as_wordlist() {
         .arg("/etc/shadow")
         .arg("-vvvv")
         .assert()
-        .success()
+        .code(2)
         .stderr(predicate::str::contains(
             "Failed while scanning: Could not open /etc/shadow",
         ));
@@ -52,7 +52,7 @@ fn main_use_empty_wordlist() -> Result<(), Box<dyn std::error::Error>> {
         .arg(file.as_os_str())
         .arg("-vvvv")
         .assert()
-        .success()
+        .code(2)
         .stderr(predicate::str::contains(
             "Failed while scanning: Did not find any words in",
         ));
@@ -79,7 +79,7 @@ fn main_use_empty_stdin_targets() -> Result<(), Box<dyn std::error::Error>> {
         .pipe_stdin(file)
         .unwrap()
         .assert()
-        .success()
+        .code(3)
         .stderr(
             predicate::str::contains("Could not connect to any target provided")
                 .and(predicate::str::contains("Target Url"))
@@ -223,3 +223,29 @@ fn main_parallel_creates_output_directory() -> Result<(), Box<dyn std::error::Er
 
     Ok(())
 }
+
+#[cfg(not(feature = "trace"))]
+#[test]
+/// --trace-output is refused by builds without the trace feature, instead of silently exporting
+/// nothing
+fn main_trace_output_needs_trace_feature() -> Result<(), Box<dyn std::error::Error>> {
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg("http://localhost")
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--trace-output")
+        .arg("jaeger://127.0.0.1:6831")
+        .assert()
+        .code(2)
+        .stderr(predicate::str::contains(
+            "--trace-output needs a feroxbuster built with `--features trace`",
+        ));
+
+    teardown_tmp_directory(tmp_dir);
+
+    Ok(())
+}
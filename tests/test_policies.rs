@@ -71,7 +71,7 @@ fn auto_bail_cancels_scan_with_timeouts() {
         .arg("-vvvv")
         .arg("--json")
         .assert()
-        .success();
+        .code(1);
 
     let debug_log = read_to_string(logfile).unwrap();
 
@@ -149,7 +149,7 @@ fn auto_bail_cancels_scan_with_403s() {
         .arg("-vvvv")
         .arg("--json")
         .assert()
-        .success();
+        .code(1);
 
     println!("log filesize: {}", logfile.metadata().unwrap().len());
     let debug_log = read_to_string(logfile).unwrap();
@@ -231,7 +231,7 @@ fn auto_bail_cancels_scan_with_429s() {
         .arg("-vvvv")
         .arg("--json")
         .assert()
-        .success();
+        .code(1);
 
     println!("log filesize: {}", logfile.metadata().unwrap().len());
     let debug_log = read_to_string(logfile).unwrap();
@@ -312,7 +312,7 @@ fn auto_tune_slows_scan_with_429s() {
         .arg("--threads")
         .arg("4")
         .assert()
-        .failure();
+        .code(4);
 
     teardown_tmp_directory(tmp_dir);
 
@@ -365,7 +365,7 @@ fn auto_tune_slows_scan_with_403s() {
         .arg("--threads")
         .arg("4")
         .assert()
-        .failure();
+        .code(4);
 
     teardown_tmp_directory(tmp_dir);
 
@@ -421,7 +421,7 @@ fn auto_tune_slows_scan_with_general_errors() {
         .arg("--timeout")
         .arg("2")
         .assert()
-        .failure();
+        .code(4);
 
     teardown_tmp_directory(tmp_dir);
 
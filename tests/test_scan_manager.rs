@@ -69,7 +69,7 @@ fn resume_scan_works() {
         .arg("--resume-from")
         .arg(state_file.as_os_str())
         .assert()
-        .success()
+        .code(1)
         .stdout(
             predicate::str::contains("/js/stuff")
                 .and(predicate::str::contains("22c"))
@@ -121,7 +121,7 @@ fn time_limit_enforced_when_specified() {
         .pipe_stdin(targets)
         .unwrap()
         .assert()
-        .failure();
+        .code(4);
 
     // expected run time is somewhere in the 30 seconds ballpark (real    0m37.376s)
     // so if the cmd returns in a significantly shorter amount of time, the test will have
@@ -24,9 +24,10 @@ fn scanner_single_request_scan() -> Result<(), Box<dyn std::error::Error>> {
         .arg("--wordlist")
         .arg(file.as_os_str())
         .arg("-vvvv")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains("14")),
@@ -79,9 +80,10 @@ fn scanner_recursive_request_scan() -> Result<(), Box<dyn std::error::Error>> {
         .arg("-vvvv")
         .arg("-t")
         .arg("1")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::is_match("301.*js")
             .unwrap()
             .and(predicate::str::is_match("301.*js/prod").unwrap())
@@ -143,9 +145,10 @@ fn scanner_recursive_request_scan_using_only_success_responses(
         .arg("-t")
         .arg("1")
         .arg("--redirects")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::is_match("200.*js")
             .unwrap()
             .and(predicate::str::is_match("200.*js/prod").unwrap())
@@ -185,7 +188,8 @@ fn scanner_single_request_scan_with_file_output() -> Result<(), Box<dyn std::err
         .arg("-vvvv")
         .arg("-o")
         .arg(outfile.as_os_str())
-        .unwrap();
+        .assert()
+        .code(1);
 
     let contents = std::fs::read_to_string(outfile)?;
 
@@ -222,7 +226,8 @@ fn scanner_single_request_scan_with_file_output_and_tack_q(
         .arg("-q")
         .arg("-o")
         .arg(outfile.as_os_str())
-        .unwrap();
+        .assert()
+        .code(1);
 
     let contents = std::fs::read_to_string(outfile)?;
 
@@ -258,7 +263,8 @@ fn scanner_single_request_scan_with_invalid_file_output() -> Result<(), Box<dyn
         .arg("-q")
         .arg("-o")
         .arg(outfile.as_os_str())
-        .unwrap();
+        .assert()
+        .code(2);
 
     let contents = std::fs::read_to_string(outfile);
     assert!(contents.is_err());
@@ -287,9 +293,10 @@ fn scanner_single_request_quiet_scan() -> Result<(), Box<dyn std::error::Error>>
         .arg(file.as_os_str())
         .arg("-x")
         .arg("js,html")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains(srv.url("/LICENSE"))
             .and(predicate::str::contains("200"))
             .not()
@@ -302,6 +309,42 @@ fn scanner_single_request_quiet_scan() -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[test]
+/// send a single valid request using --silent and -vv, expect only the url on stdout and the
+/// log messages on stderr
+fn scanner_single_request_silent_scan_logs_to_stderr() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET).path("/LICENSE");
+        then.status(200).body("this is a test");
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--silent")
+        .arg("-vv")
+        .output()
+        .unwrap();
+
+    cmd.assert()
+        .code(1)
+        .stdout(
+            predicate::str::contains(srv.url("/LICENSE"))
+                .and(predicate::str::contains("INF").not()),
+        )
+        .stderr(predicate::str::contains("INF"));
+
+    assert_eq!(mock.hits(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
 #[test]
 /// send single valid request, get back a 301 without a Location header
 /// expect response_is_directory to return false when called
@@ -325,9 +368,10 @@ fn scanner_single_request_returns_301_without_location_header(
         .arg("5")
         .arg("--user-agent")
         .arg("some-user-agent-string")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains(srv.url("/LICENSE"))
             .and(predicate::str::contains("301"))
             .and(predicate::str::contains("14")),
@@ -366,10 +410,11 @@ fn scanner_single_request_replayed_to_proxy() -> Result<(), Box<dyn std::error::
         .arg(format!("http://{}", proxy.address().to_string()))
         .arg("--replay-codes")
         .arg("200")
+        .output()
         .unwrap();
 
     cmd.assert()
-        .success()
+        .code(1)
         .stdout(
             predicate::str::contains("/LICENSE")
                 .and(predicate::str::contains("200"))
@@ -409,9 +454,10 @@ fn scanner_single_request_scan_with_filtered_result() -> Result<(), Box<dyn std:
         .arg("-n")
         .arg("-S")
         .arg("14")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains("20"))
@@ -449,7 +495,8 @@ fn scanner_single_request_scan_with_debug_logging() {
         .arg("-vvvv")
         .arg("--debug-log")
         .arg(outfile.as_os_str())
-        .unwrap();
+        .assert()
+        .code(1);
 
     let contents = std::fs::read_to_string(outfile).unwrap();
     println!("{}", contents);
@@ -487,7 +534,8 @@ fn scanner_single_request_scan_with_debug_logging_as_json() {
         .arg("--debug-log")
         .arg(outfile.as_os_str())
         .arg("--json")
-        .unwrap();
+        .assert()
+        .code(1);
 
     let contents = std::fs::read_to_string(outfile).unwrap();
     println!("{}", contents);
@@ -534,9 +582,10 @@ fn scanner_single_request_scan_with_regex_filtered_result() {
         .arg(file.as_os_str())
         .arg("--filter-regex")
         .arg("'That rug.*together$'")
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .and(predicate::str::contains("200"))
             .and(predicate::str::contains("20"))
@@ -580,9 +629,10 @@ fn scanner_recursion_works_with_403_directories() {
         .arg(srv.url("/"))
         .arg("--wordlist")
         .arg(file.as_os_str())
+        .output()
         .unwrap();
 
-    cmd.assert().success().stdout(
+    cmd.assert().code(1).stdout(
         predicate::str::contains("/LICENSE")
             .count(2)
             .and(predicate::str::contains("200").count(2))
@@ -638,3 +688,152 @@ fn rate_limit_enforced_when_specified() {
 
     teardown_tmp_directory(tmp_dir);
 }
+
+#[test]
+/// --rotate-header sends a value from its file with each request
+fn scanner_sends_rotating_header() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+    let (ips_dir, ips) = setup_tmp_directory(&["10.0.0.1".to_string()], "ips")?;
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET)
+            .path("/LICENSE")
+            .header("X-Forwarded-For", "10.0.0.1");
+        then.status(200).body("this is a test");
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--rotate-header")
+        .arg(format!("X-Forwarded-For:@{}", ips.display()))
+        .output()
+        .unwrap();
+
+    cmd.assert()
+        .stdout(predicate::str::contains("/LICENSE").and(predicate::str::contains("200")));
+
+    assert_eq!(mock.hits(), 1);
+    teardown_tmp_directory(tmp_dir);
+    teardown_tmp_directory(ips_dir);
+    Ok(())
+}
+
+#[test]
+/// {{word}} in a -H header is the wordlist word, not the path with its -x extension
+fn scanner_expands_word_placeholder() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET)
+            .path("/LICENSE.txt")
+            .header("X-Word", "LICENSE");
+        then.status(200).body("this is a test");
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--extensions")
+        .arg("txt")
+        .arg("-H")
+        .arg("X-Word: {{word}}")
+        .output()
+        .unwrap();
+
+    cmd.assert()
+        .stdout(predicate::str::contains("/LICENSE.txt").and(predicate::str::contains("200")));
+
+    assert_eq!(mock.hits(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// --host-header sends the given name to the ip target, and results are still reported against
+/// the ip
+fn scanner_sends_host_header_to_ip_target() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+    let (tmp_dir, file) = setup_tmp_directory(&["LICENSE".to_string()], "wordlist")?;
+
+    let mock = srv.mock(|when, then| {
+        when.method(GET)
+            .path("/LICENSE")
+            .header("Host", &format!("example.test:{}", srv.port()));
+        then.status(200).body("this is a test");
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--host-header")
+        .arg("example.test")
+        .output()
+        .unwrap();
+
+    cmd.assert().stdout(
+        predicate::str::contains(srv.url("/LICENSE"))
+            .and(predicate::str::contains("200"))
+            .and(predicate::str::contains("example.test").not()),
+    );
+
+    assert_eq!(mock.hits(), 1);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}
+
+#[test]
+/// --stop-on-match ends the scan once a matching url is found, without requesting the rest of
+/// the wordlist
+fn scanner_stops_on_match() -> Result<(), Box<dyn std::error::Error>> {
+    let srv = MockServer::start();
+
+    let mut words = vec![".env".to_string()];
+    words.extend((0..50).map(|i| format!("word{}", i)));
+
+    let (tmp_dir, file) = setup_tmp_directory(&words, "wordlist")?;
+
+    let env = srv.mock(|when, then| {
+        when.method(GET).path("/.env");
+        then.status(200).body("SECRET_KEY=hunter2");
+    });
+
+    let rest = srv.mock(|when, then| {
+        when.method(GET)
+            .path_matches(regex::Regex::new("^/word[0-9]+$").unwrap());
+        then.status(404);
+    });
+
+    let cmd = Command::cargo_bin("feroxbuster")
+        .unwrap()
+        .arg("--url")
+        .arg(srv.url("/"))
+        .arg("--wordlist")
+        .arg(file.as_os_str())
+        .arg("--threads")
+        .arg("1")
+        .arg("--stop-on-match")
+        .arg("/.env")
+        .output()
+        .unwrap();
+
+    cmd.assert()
+        .code(1)
+        .stdout(predicate::str::contains("/.env").and(predicate::str::contains("200")));
+
+    assert_eq!(env.hits(), 1);
+    assert!(rest.hits() < 50);
+    teardown_tmp_directory(tmp_dir);
+    Ok(())
+}